@@ -22,7 +22,7 @@ use zann_core::{StorageKind, VaultEncryptionType, VaultKind};
 use crate::services::sync_helpers::{
     apply_pull_change, apply_push_applied, apply_shared_pull_change, build_remote_storage,
     build_shared_push_changes, ensure_local_vaults, fetch_vault_details, handle_sync_conflict,
-    key_fingerprint,
+    key_fingerprint, pending_expires_at,
 };
 // preflight is handled in auth/session flow; sync logic only.
 
@@ -317,6 +317,7 @@ pub async fn remote_sync(
                         name: change.name.clone(),
                         type_id: change.type_id.clone(),
                         base_seq: change.base_seq,
+                        expires_at: pending_expires_at(vault_key.as_ref(), vault_id, change),
                     })
                     .collect();
                 (
@@ -229,6 +229,7 @@ pub(crate) fn build_shared_push_changes(
                 name: change.name.clone(),
                 type_id: change.type_id.clone(),
                 base_seq: change.base_seq,
+                expires_at: None,
             });
             continue;
         }
@@ -238,6 +239,7 @@ pub(crate) fn build_shared_push_changes(
             .as_ref()
             .ok_or_else(|| "missing payload".to_string())?;
         let payload = decrypt_payload(master_key, vault_id, change.item_id, payload_enc)?;
+        let expires_at = payload_expires_at(&payload);
         let payload_json = serde_json::to_value(payload).map_err(|err| err.to_string())?;
         changes.push(SyncSharedPushChange {
             item_id: change.item_id.to_string(),
@@ -247,11 +249,36 @@ pub(crate) fn build_shared_push_changes(
             name: change.name.clone(),
             type_id: change.type_id.clone(),
             base_seq: change.base_seq,
+            expires_at,
         });
     }
     Ok(changes)
 }
 
+/// The expiry date kept in `extra.expires_at` by the item form, as the server
+/// expects it in push changes. `None` when the payload does not mention it.
+pub(crate) fn payload_expires_at(payload: &zann_core::EncryptedPayload) -> Option<Option<String>> {
+    let value = payload.extra.as_ref()?.get("expires_at")?.trim();
+    if value.is_empty() {
+        return Some(None);
+    }
+    let value = match chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => format!("{date}T00:00:00Z"),
+        Err(_) => value.to_string(),
+    };
+    Some(Some(value))
+}
+
+pub(crate) fn pending_expires_at(
+    vault_key: Option<&SecretKey>,
+    vault_id: Uuid,
+    change: &LocalPendingChange,
+) -> Option<Option<String>> {
+    let payload_enc = change.payload_enc.as_ref()?;
+    let payload = decrypt_payload(vault_key?, vault_id, change.item_id, payload_enc).ok()?;
+    payload_expires_at(&payload)
+}
+
 pub(crate) async fn apply_push_applied(
     item_repo: &LocalItemRepo<'_>,
     storage_id: Uuid,
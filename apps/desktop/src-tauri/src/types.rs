@@ -300,6 +300,9 @@ pub struct SyncPushChange {
     pub type_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub base_seq: Option<i64>,
+    /// `Some(None)` clears the item's expiry date; `None` leaves it alone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Option<String>>,
 }
 
 #[derive(Serialize)]
@@ -322,6 +325,8 @@ pub struct SyncSharedPushChange {
     pub type_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub base_seq: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Option<String>>,
 }
 
 #[derive(Deserialize)]
@@ -165,6 +165,7 @@ vi.mock("../composables/useCreateModal", () => ({
     openCreateModal: vi.fn(),
     closeCreateModal: vi.fn(),
     createItemFolder: vi.fn(),
+    createItemExpiresAt: vi.fn(),
   }),
 }));
 
@@ -125,6 +125,7 @@ export function useAppBindings(options: AppBindingsOptions) {
     createItemType: createState.createItemType,
     createItemTitle: createState.createItemTitle,
     createItemFolder: createState.createItemFolder,
+    createItemExpiresAt: createState.createItemExpiresAt,
     kvFilter: createState.kvFilter,
     advancedOpen: createState.advancedOpen,
     createVaultName: createState.createVaultName,
@@ -294,6 +295,7 @@ export function useAppBindings(options: AppBindingsOptions) {
     createItemType: createState.createItemType,
     createItemTitle: createState.createItemTitle,
     createItemFolder: createState.createItemFolder,
+    createItemExpiresAt: createState.createItemExpiresAt,
     kvFilter: createState.kvFilter,
     advancedOpen: createState.advancedOpen,
     createVaultName: createState.createVaultName,
@@ -390,6 +392,7 @@ export function useAppBindings(options: AppBindingsOptions) {
     "createItemType",
     "createItemTitle",
     "createItemFolder",
+    "createItemExpiresAt",
     "kvFilter",
     "advancedOpen",
     "createVaultName",
@@ -417,6 +420,7 @@ export function useAppBindings(options: AppBindingsOptions) {
     "createItemType",
     "createItemTitle",
     "createItemFolder",
+    "createItemExpiresAt",
     "kvFilter",
     "advancedOpen",
     "createVaultName",
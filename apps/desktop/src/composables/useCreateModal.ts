@@ -50,6 +50,9 @@ export const useCreateModal = (options: UseCreateModalOptions) => {
   const showAllTypes = ref(false);
   const createItemTitle = ref("");
   const createItemFolder = ref("");
+  const createItemExpiresAt = ref("");
+  // Whether the edited item already had an expiry date, so clearing it is sent.
+  const createItemHadExpiry = ref(false);
   const createItemVaultId = ref<string | null>(null);
   const createEditingItemId = ref<string | null>(null);
   const createItemError = ref("");
@@ -379,6 +382,8 @@ export const useCreateModal = (options: UseCreateModalOptions) => {
     createItemFields.value = [];
     createItemTitle.value = "";
     createItemFolder.value = "";
+    createItemExpiresAt.value = "";
+    createItemHadExpiry.value = false;
     createItemVaultId.value = options.selectedVaultId.value ?? null;
     createEditingItemId.value = null;
     createItemError.value = "";
@@ -433,6 +438,8 @@ export const useCreateModal = (options: UseCreateModalOptions) => {
       }
       createItemTitle.value = "";
       createItemFolder.value = "";
+      createItemExpiresAt.value = "";
+      createItemHadExpiry.value = false;
       createItemVaultId.value = options.selectedVaultId.value ?? options.vaults.value[0]?.id ?? null;
       advancedOpen.value = false;
       kvFilter.value = "";
@@ -461,6 +468,9 @@ export const useCreateModal = (options: UseCreateModalOptions) => {
     kvFilter.value = "";
     await loadTypeOptions();
     const payloadSource = payloadOverride ?? options.selectedItem.value.payload;
+    const expiresAt = payloadSource?.extra?.expires_at ?? "";
+    createItemExpiresAt.value = expiresAt.slice(0, 10);
+    createItemHadExpiry.value = expiresAt !== "";
     createItemFields.value = flattenPayload(
       payloadSource,
       options.selectedItem.value.type_id,
@@ -532,10 +542,12 @@ export const useCreateModal = (options: UseCreateModalOptions) => {
         const key = field.key.trim();
         fields[key] = buildFieldValue(field);
       });
+    const expiresAt = createItemExpiresAt.value.trim();
     return {
       v: 1,
       typeId,
       fields,
+      ...(expiresAt || createItemHadExpiry.value ? { extra: { expires_at: expiresAt } } : {}),
     };
   };
 
@@ -909,6 +921,7 @@ export const useCreateModal = (options: UseCreateModalOptions) => {
     createItemFields,
     createItemTitle,
     createItemFolder,
+    createItemExpiresAt,
     createItemVaultId,
     createEditingItemId,
     createItemError,
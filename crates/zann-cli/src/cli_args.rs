@@ -1,7 +1,14 @@
 use clap::{ArgAction, Parser, Subcommand};
 
+pub use crate::modules::agent::args::*;
+pub use crate::modules::import::args::*;
+pub use crate::modules::manifest::args::*;
+pub use crate::modules::pki::args::*;
+pub use crate::modules::service_accounts::args::*;
 pub use crate::modules::shared::args::*;
+pub use crate::modules::ssh::args::*;
 pub use crate::modules::system::args::*;
+pub use crate::modules::vaults::args::*;
 
 #[derive(Parser)]
 #[command(name = "zann")]
@@ -41,8 +48,13 @@ pub struct Cli {
 pub enum Command {
     #[command(about = "Server metadata and diagnostics")]
     Server(ServerArgs),
-    #[command(about = "Run a command with secrets injected as env vars")]
+    #[command(
+        about = "Run a command with secrets injected as env vars",
+        allow_missing_positional = true
+    )]
     Run(RunArgs),
+    #[command(about = "Serve secrets locally and keep rendered files up to date")]
+    Agent(AgentArgs),
     #[command(about = "Print the current identity for the token")]
     Whoami,
     #[command(about = "List secrets (shared vaults)")]
@@ -63,6 +75,20 @@ pub enum Command {
     Set(SetArgs),
     #[command(about = "Delete a secret item from a shared vault")]
     Delete(DeleteArgs),
+    #[command(about = "Import keys from a dotenv, JSON, YAML or Kubernetes Secret file")]
+    Import(ImportArgs),
+    #[command(about = "Show the changes needed to match a secrets manifest")]
+    Plan(ManifestArgs),
+    #[command(about = "Create, update and prune items to match a secrets manifest")]
+    Apply(ManifestArgs),
+    #[command(about = "Manage service account tokens for CI/CD")]
+    ServiceAccount(ServiceAccountArgs),
+    #[command(about = "Issue TLS certificates from a vault certificate authority")]
+    Pki(PkiArgs),
+    #[command(about = "Sign SSH keys with a vault certificate authority")]
+    Ssh(SshArgs),
+    #[command(about = "Manage shared vaults and their members")]
+    Vault(VaultArgs),
     #[command(about = "Print version information")]
     Version,
 }
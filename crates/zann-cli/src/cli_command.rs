@@ -2,12 +2,18 @@ use crate::cli_args::*;
 use crate::modules::system::CommandContext;
 use reqwest::Method;
 
+use crate::modules::import::handle_import;
+use crate::modules::manifest::{handle_apply, handle_plan};
+use crate::modules::pki::handle_pki_command;
+use crate::modules::service_accounts::handle_service_account_command;
 use crate::modules::shared::{
     handle_create, handle_delete, handle_get, handle_list, handle_materialize, handle_render,
     handle_set, handle_update,
 };
+use crate::modules::ssh::handle_ssh_command;
 use crate::modules::system::http::print_json_response;
 use crate::modules::system::http::send_request;
+use crate::modules::vaults::handle_vault_command;
 
 pub(crate) async fn handle_command(
     command: Command,
@@ -22,12 +28,19 @@ pub(crate) async fn handle_command(
         Command::Update(args) => handle_update(args, ctx).await?,
         Command::Set(args) => handle_set(args, ctx).await?,
         Command::Delete(args) => handle_delete(args, ctx).await?,
+        Command::Import(args) => handle_import(args, ctx).await?,
+        Command::Plan(args) => handle_plan(args, ctx).await?,
+        Command::Apply(args) => handle_apply(args, ctx).await?,
+        Command::ServiceAccount(args) => handle_service_account_command(args, ctx).await?,
+        Command::Pki(args) => handle_pki_command(args, ctx).await?,
+        Command::Ssh(args) => handle_ssh_command(args, ctx).await?,
+        Command::Vault(args) => handle_vault_command(args, ctx).await?,
         Command::Whoami => {
             let url = format!("{}/v1/users/me", ctx.addr.trim_end_matches('/'));
             let response = send_request(ctx, Method::GET, url, None).await?;
             print_json_response(response).await?;
         }
-        Command::Server(_) | Command::Run(_) | Command::Version => {}
+        Command::Server(_) | Command::Run(_) | Command::Agent(_) | Command::Version => {}
         Command::Config(_) => {
             unreachable!()
         }
@@ -7,6 +7,7 @@ mod modules;
 
 use crate::cli_args::*;
 use crate::cli_command::handle_command;
+use crate::modules::agent::handle_agent_command;
 use crate::modules::auth::{
     ensure_access_token, exchange_service_account_token, verify_server_fingerprint,
 };
@@ -79,6 +80,19 @@ async fn main() -> anyhow::Result<()> {
             .await?;
             save_config(&config)?;
         }
+        Command::Agent(args) => {
+            handle_agent_command(
+                args,
+                addr_arg,
+                token_arg,
+                token_name_arg,
+                context_arg,
+                cli.insecure,
+                &client,
+                &mut config,
+            )
+            .await?;
+        }
         command => {
             let context_name = context_arg.or_else(|| config.current_context.clone());
             let context = context_name
@@ -0,0 +1,303 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tokio::time::MissedTickBehavior;
+
+use crate::cli_args::AgentArgs;
+use crate::modules::agent::cache::SecretCache;
+use crate::modules::agent::config::{load_agent_config, AgentConfig};
+use crate::modules::agent::session::Session;
+#[cfg(unix)]
+use crate::modules::agent::socket::AgentSocket;
+use crate::modules::agent::state::{fetch_vault_id, Agent};
+use crate::modules::auth::{load_service_token, verify_server_fingerprint};
+use crate::modules::shared::{
+    materialize_shared, normalize_prefix, prefix_match, render_template, resolve_vault_for_context,
+    template_dir, write_atomic,
+};
+use crate::modules::system::http::fetch_system_info;
+use crate::modules::system::{ensure_secure_addr, CliConfig, CommandContext};
+use crate::{DEFAULT_ADDR, SERVICE_ACCOUNT_PREFIX};
+
+const MATERIALIZE_LIMIT: i64 = 200;
+
+/// Where the agent runs: the resolved server, context and client settings.
+struct AgentEnv<'a> {
+    client: &'a reqwest::Client,
+    addr: String,
+    allow_insecure: bool,
+    context_name: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn handle_agent_command(
+    args: AgentArgs,
+    addr_arg: Option<String>,
+    token_arg: Option<String>,
+    token_name_arg: Option<String>,
+    context_arg: Option<String>,
+    allow_insecure: bool,
+    client: &reqwest::Client,
+    config: &mut CliConfig,
+) -> anyhow::Result<()> {
+    let agent_config = load_agent_config(&args.config)?;
+    #[cfg(not(unix))]
+    if agent_config.socket.is_some() && !args.once {
+        anyhow::bail!("the agent socket is only supported on Unix");
+    }
+
+    let context_name = context_arg.or_else(|| config.current_context.clone());
+    let context = context_name
+        .as_deref()
+        .and_then(|name| config.contexts.get(name))
+        .cloned();
+    let addr = addr_arg
+        .or_else(|| context.as_ref().map(|ctx| ctx.addr.clone()))
+        .unwrap_or_else(|| DEFAULT_ADDR.to_string());
+    ensure_secure_addr(&addr, allow_insecure)?;
+    let token_name =
+        token_name_arg.or_else(|| context.as_ref().and_then(|ctx| ctx.current_token.clone()));
+    let mut service_account_token =
+        token_arg.filter(|value| value.starts_with(SERVICE_ACCOUNT_PREFIX));
+    if service_account_token.is_none() {
+        if let (Some(context_name), Some(token_name)) =
+            (context_name.as_deref(), token_name.as_deref())
+        {
+            service_account_token = load_service_token(context_name, token_name)?;
+        }
+    }
+    let service_account_token = service_account_token
+        .ok_or_else(|| anyhow::anyhow!("service account token required for zann agent"))?;
+
+    let info = fetch_system_info(client, &addr).await?;
+    verify_server_fingerprint(
+        config,
+        context_name.as_deref(),
+        &addr,
+        &info.server_fingerprint,
+    )?;
+    let session = Session::new(client.clone(), addr.clone(), service_account_token);
+    let access_token = session.access_token().await?;
+    let vault = resolve_vault_for_context(
+        agent_config.vault.clone(),
+        context_name.as_deref(),
+        config,
+        client,
+        &addr,
+        &access_token,
+    )
+    .await?;
+    let vault_id = fetch_vault_id(client, &addr, &access_token, &vault).await?;
+    let agent = Arc::new(Agent {
+        client: client.clone(),
+        addr: addr.clone(),
+        session,
+        vault,
+        vault_id,
+        cache: Mutex::new(SecretCache::new(agent_config.cache_ttl)),
+    });
+    let env = AgentEnv {
+        client,
+        addr,
+        allow_insecure,
+        context_name,
+    };
+
+    // Start at the head of the change feed; the first render covers
+    // everything before it.
+    let (_, cursor) = agent.pull_changes(None).await?;
+    let written = refresh_outputs(&agent, &agent_config, None, &env, config).await?;
+    if written > 0 {
+        run_reload(agent_config.reload.as_deref()).await?;
+    }
+    if args.once {
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    let socket = agent_config
+        .socket
+        .as_deref()
+        .map(|path| AgentSocket::bind(path, &agent_config.allowed_uids))
+        .transpose()?;
+    let serve = async {
+        #[cfg(unix)]
+        if let Some(socket) = &socket {
+            socket.serve(agent.clone()).await;
+        }
+        std::future::pending::<()>().await
+    };
+
+    tokio::select! {
+        _ = serve => {}
+        _ = sync_loop(&agent, &agent_config, cursor, &env, config) => {}
+        result = shutdown_signal() => result?,
+    }
+    Ok(())
+}
+
+/// Polls the change feed and refreshes the outputs affected by each change.
+/// After a failed refresh every output is refreshed on the next poll.
+async fn sync_loop(
+    agent: &Agent,
+    agent_config: &AgentConfig,
+    mut cursor: String,
+    env: &AgentEnv<'_>,
+    config: &mut CliConfig,
+) {
+    let mut interval = tokio::time::interval(agent_config.poll_interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval.tick().await;
+    let mut refresh_all = false;
+    loop {
+        interval.tick().await;
+        let changed = match agent.pull_changes(Some(cursor.clone())).await {
+            Ok((changed, next_cursor)) => {
+                cursor = next_cursor;
+                changed
+            }
+            Err(err) => {
+                tracing::warn!("agent sync failed: {err}");
+                continue;
+            }
+        };
+        if changed.is_empty() && !refresh_all {
+            continue;
+        }
+        tracing::info!(count = changed.len(), "secrets changed");
+        let scope = (!refresh_all).then_some(changed.as_slice());
+        match refresh_outputs(agent, agent_config, scope, env, config).await {
+            Ok(0) => refresh_all = false,
+            Ok(_) => {
+                refresh_all = false;
+                if let Err(err) = run_reload(agent_config.reload.as_deref()).await {
+                    tracing::warn!("{err}");
+                }
+            }
+            Err(err) => {
+                tracing::warn!("agent refresh failed: {err}");
+                refresh_all = true;
+            }
+        }
+    }
+}
+
+/// Re-renders every template and re-materializes the targets whose prefix
+/// covers a changed path (all of them when `changed` is `None`). Returns how
+/// many files were written; unchanged files are left alone.
+async fn refresh_outputs(
+    agent: &Agent,
+    agent_config: &AgentConfig,
+    changed: Option<&[String]>,
+    env: &AgentEnv<'_>,
+    config: &mut CliConfig,
+) -> anyhow::Result<usize> {
+    let access_token = agent.session.access_token().await?;
+    let mut written = 0;
+    if !agent_config.templates.is_empty() {
+        let mut ctx = CommandContext {
+            client: env.client,
+            addr: &env.addr,
+            allow_insecure: env.allow_insecure,
+            access_token: access_token.clone(),
+            context_name: env.context_name.clone(),
+            token_name: None,
+            config,
+        };
+        for target in &agent_config.templates {
+            let template = std::fs::read_to_string(&target.template).map_err(|err| {
+                anyhow::anyhow!("failed to read {}: {err}", target.template.display())
+            })?;
+            let rendered = render_template(
+                &template,
+                template_dir(&target.template),
+                Some(&agent.vault),
+                target.strict,
+                &mut ctx,
+            )
+            .await
+            .map_err(|err| anyhow::anyhow!("{}: {err}", target.template.display()))?;
+            if write_if_changed(&target.out, rendered)? {
+                tracing::info!(path = %target.out.display(), "rendered template");
+                written += 1;
+            }
+        }
+    }
+    for target in &agent_config.materialize {
+        let prefix = normalize_prefix(target.prefix.as_deref());
+        let affected = changed.is_none_or(|paths| {
+            paths
+                .iter()
+                .any(|path| prefix_match(prefix.as_deref(), path))
+        });
+        if !affected {
+            continue;
+        }
+        let count = materialize_shared(
+            &agent.client,
+            &agent.addr,
+            &access_token,
+            &agent.vault,
+            prefix.as_deref(),
+            &target.out,
+            target.field.as_deref(),
+            true,
+            true,
+            MATERIALIZE_LIMIT,
+        )
+        .await?;
+        if count > 0 {
+            tracing::info!(path = %target.out.display(), count, "materialized secrets");
+        }
+        written += count;
+    }
+    Ok(written)
+}
+
+fn write_if_changed(path: &Path, contents: String) -> anyhow::Result<bool> {
+    match std::fs::read(path) {
+        Ok(existing) if existing == contents.as_bytes() => return Ok(false),
+        Ok(_) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err.into()),
+    }
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    write_atomic(path, contents)?;
+    Ok(true)
+}
+
+async fn run_reload(command: Option<&str>) -> anyhow::Result<()> {
+    let Some(command) = command else {
+        return Ok(());
+    };
+    tracing::info!(command, "running reload command");
+    let status = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .status()
+        .await?;
+    if !status.success() {
+        anyhow::bail!("reload command failed: {status}");
+    }
+    Ok(())
+}
+
+async fn shutdown_signal() -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
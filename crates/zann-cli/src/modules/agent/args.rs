@@ -0,0 +1,13 @@
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct AgentArgs {
+    #[arg(short = 'c', long = "config", help = "Agent configuration (YAML)")]
+    pub config: PathBuf,
+    #[arg(
+        long,
+        help = "Render templates and materialize targets once, then exit"
+    )]
+    pub once: bool,
+}
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use zann_core::EncryptedPayload;
+
+/// Item payloads by path. Entries expire after `ttl` and are evicted as soon
+/// as a sync pull reports a change to their path.
+pub(crate) struct SecretCache {
+    ttl: Duration,
+    entries: HashMap<String, (Instant, EncryptedPayload)>,
+}
+
+impl SecretCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn get(&mut self, path: &str) -> Option<EncryptedPayload> {
+        let key = cache_key(path);
+        match self.entries.get(&key) {
+            Some((stored_at, payload)) if stored_at.elapsed() < self.ttl => Some(payload.clone()),
+            Some(_) => {
+                self.entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, path: &str, payload: EncryptedPayload) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        self.entries
+            .insert(cache_key(path), (Instant::now(), payload));
+    }
+
+    pub(crate) fn evict(&mut self, path: &str) {
+        self.entries.remove(&cache_key(path));
+    }
+}
+
+fn cache_key(path: &str) -> String {
+    path.trim().trim_matches('/').to_string()
+}
@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AgentFile {
+    #[serde(default)]
+    vault: Option<String>,
+    #[serde(default)]
+    socket: Option<PathBuf>,
+    #[serde(default)]
+    cache_ttl: Option<serde_yaml::Value>,
+    #[serde(default)]
+    poll_interval: Option<serde_yaml::Value>,
+    #[serde(default)]
+    allowed_uids: Vec<u32>,
+    #[serde(default)]
+    templates: Vec<TemplateTarget>,
+    #[serde(default)]
+    materialize: Vec<MaterializeTarget>,
+    #[serde(default)]
+    reload: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TemplateTarget {
+    pub template: PathBuf,
+    pub out: PathBuf,
+    #[serde(default)]
+    pub strict: bool,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct MaterializeTarget {
+    #[serde(default)]
+    pub prefix: Option<String>,
+    pub out: PathBuf,
+    #[serde(default)]
+    pub field: Option<String>,
+}
+
+pub(crate) struct AgentConfig {
+    pub vault: Option<String>,
+    /// Unix socket that serves secrets to local processes; none when unset.
+    pub socket: Option<PathBuf>,
+    pub cache_ttl: Duration,
+    pub poll_interval: Duration,
+    /// Users besides the agent's own that may read from the socket.
+    pub allowed_uids: Vec<u32>,
+    pub templates: Vec<TemplateTarget>,
+    pub materialize: Vec<MaterializeTarget>,
+    /// Shell command run after a template or materialized file changed.
+    pub reload: Option<String>,
+}
+
+pub(crate) fn load_agent_config(path: &Path) -> anyhow::Result<AgentConfig> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("failed to read {}: {err}", path.display()))?;
+    parse_agent_config(&contents)
+        .map_err(|err| anyhow::anyhow!("invalid agent config {}: {err}", path.display()))
+}
+
+fn parse_agent_config(contents: &str) -> anyhow::Result<AgentConfig> {
+    let file: AgentFile = serde_yaml::from_str(contents)?;
+    let cache_ttl = match file.cache_ttl {
+        Some(value) => parse_duration_value("cache_ttl", &value)?,
+        None => DEFAULT_CACHE_TTL,
+    };
+    let poll_interval = match file.poll_interval {
+        Some(value) => parse_duration_value("poll_interval", &value)?,
+        None => DEFAULT_POLL_INTERVAL,
+    };
+    if poll_interval.is_zero() {
+        anyhow::bail!("poll_interval must be greater than zero");
+    }
+    if file.socket.is_none() && file.templates.is_empty() && file.materialize.is_empty() {
+        anyhow::bail!("nothing to do: set socket, templates or materialize");
+    }
+    Ok(AgentConfig {
+        vault: file.vault,
+        socket: file.socket,
+        cache_ttl,
+        poll_interval,
+        allowed_uids: file.allowed_uids,
+        templates: file.templates,
+        materialize: file.materialize,
+        reload: file.reload.filter(|command| !command.trim().is_empty()),
+    })
+}
+
+/// Accepts whole seconds or a number with an `s`, `m` or `h` suffix.
+fn parse_duration_value(name: &str, value: &serde_yaml::Value) -> anyhow::Result<Duration> {
+    let seconds = match value {
+        serde_yaml::Value::Number(number) => number.as_u64(),
+        serde_yaml::Value::String(text) => parse_duration(text),
+        _ => None,
+    };
+    seconds
+        .map(Duration::from_secs)
+        .ok_or_else(|| anyhow::anyhow!("{name}: expected a duration such as 30s, 5m or 1h"))
+}
+
+fn parse_duration(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (digits, unit) = match value.char_indices().find(|(_, ch)| !ch.is_ascii_digit()) {
+        Some((index, _)) => value.split_at(index),
+        None => (value, "s"),
+    };
+    let amount: u64 = digits.parse().ok()?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return None,
+    };
+    amount.checked_mul(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_agent_config;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_durations_and_targets() {
+        let config = parse_agent_config(
+            "vault: infra\nsocket: /run/zann.sock\ncache_ttl: 90\npoll_interval: 2m\n\
+             templates:\n  - template: app.tpl\n    out: app.env\n",
+        )
+        .expect("valid config");
+        assert_eq!(config.cache_ttl, Duration::from_secs(90));
+        assert_eq!(config.poll_interval, Duration::from_secs(120));
+        assert_eq!(config.templates.len(), 1);
+        assert!(config.reload.is_none());
+    }
+
+    #[test]
+    fn rejects_bad_durations_and_empty_configs() {
+        assert!(parse_agent_config("socket: a.sock\npoll_interval: 5d\n").is_err());
+        assert!(parse_agent_config("socket: a.sock\npoll_interval: 0\n").is_err());
+        assert!(parse_agent_config("vault: infra\n").is_err());
+    }
+}
@@ -0,0 +1,10 @@
+mod actions;
+pub(crate) mod args;
+mod cache;
+mod config;
+mod session;
+#[cfg(unix)]
+mod socket;
+mod state;
+
+pub(crate) use actions::handle_agent_command;
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use tokio::sync::Mutex;
+use zann_client::tokens::ensure_access_token_for_context;
+use zann_client::{CliConfig, CliContext, TokenEntry};
+
+const AGENT_CONTEXT: &str = "agent";
+const AGENT_TOKEN: &str = "agent";
+
+/// Keeps a service account session alive for the lifetime of the agent. The
+/// access token lives in memory only and is exchanged again shortly before it
+/// expires, so a long-running agent never hands out a stale token.
+pub(crate) struct Session {
+    client: reqwest::Client,
+    addr: String,
+    config: Mutex<CliConfig>,
+}
+
+impl Session {
+    pub(crate) fn new(client: reqwest::Client, addr: String, service_token: String) -> Self {
+        let entry = TokenEntry {
+            access_token: String::new(),
+            refresh_token: None,
+            access_expires_at: None,
+            service_account_token: Some(service_token),
+        };
+        let context = CliContext {
+            addr: addr.clone(),
+            tokens: HashMap::from([(AGENT_TOKEN.to_string(), entry)]),
+            current_token: Some(AGENT_TOKEN.to_string()),
+            ..CliContext::default()
+        };
+        let config = CliConfig {
+            current_context: Some(AGENT_CONTEXT.to_string()),
+            contexts: HashMap::from([(AGENT_CONTEXT.to_string(), context)]),
+            ..CliConfig::default()
+        };
+        Self {
+            client,
+            addr,
+            config: Mutex::new(config),
+        }
+    }
+
+    pub(crate) async fn access_token(&self) -> anyhow::Result<String> {
+        let mut config = self.config.lock().await;
+        ensure_access_token_for_context(&self.client, &self.addr, AGENT_CONTEXT, &mut config, None)
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to refresh access token: {err}"))
+    }
+}
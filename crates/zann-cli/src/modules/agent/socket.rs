@@ -0,0 +1,151 @@
+use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+use crate::modules::agent::state::Agent;
+use crate::modules::shared::flatten_payload;
+
+/// One request per line: `{"path": "apps/billing/db", "field": "password"}`.
+/// Without `field`, every field of the item is returned.
+#[derive(Deserialize)]
+struct SocketRequest {
+    path: String,
+    #[serde(default)]
+    field: Option<String>,
+}
+
+pub(crate) struct AgentSocket {
+    listener: UnixListener,
+    path: PathBuf,
+    allowed_uids: Vec<u32>,
+}
+
+impl AgentSocket {
+    /// Binds `path`, replacing a socket left behind by a previous run. The
+    /// agent's own user may always connect, plus `allowed_uids`.
+    pub(crate) fn bind(path: &Path, allowed_uids: &[u32]) -> anyhow::Result<Self> {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+            Ok(_) => anyhow::bail!("{} exists and is not a socket", path.display()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let listener = UnixListener::bind(path)
+            .map_err(|err| anyhow::anyhow!("failed to bind {}: {err}", path.display()))?;
+        // Other users can only connect when they are listed; every
+        // connection is still checked against the peer credentials.
+        let mode = if allowed_uids.is_empty() {
+            0o600
+        } else {
+            0o666
+        };
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+        let mut uids = allowed_uids.to_vec();
+        uids.push(std::fs::metadata(path)?.uid());
+        Ok(Self {
+            listener,
+            path: path.to_path_buf(),
+            allowed_uids: uids,
+        })
+    }
+
+    pub(crate) async fn serve(&self, agent: Arc<Agent>) {
+        tracing::info!(path = %self.path.display(), "agent socket listening");
+        loop {
+            let stream = match self.listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    tracing::warn!("agent socket accept failed: {err}");
+                    continue;
+                }
+            };
+            let uid = match stream.peer_cred() {
+                Ok(cred) => cred.uid(),
+                Err(err) => {
+                    tracing::warn!("agent socket peer credentials unavailable: {err}");
+                    continue;
+                }
+            };
+            let allowed = self.allowed_uids.contains(&uid);
+            let agent = agent.clone();
+            tokio::spawn(async move {
+                if let Err(err) = handle_connection(stream, uid, allowed, agent).await {
+                    tracing::debug!(uid, "agent socket connection closed: {err}");
+                }
+            });
+        }
+    }
+}
+
+impl Drop for AgentSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+async fn handle_connection(
+    stream: UnixStream,
+    uid: u32,
+    allowed: bool,
+    agent: Arc<Agent>,
+) -> anyhow::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    if !allowed {
+        tracing::warn!(uid, "agent socket rejected peer");
+        write_line(&mut writer, &error_response("permission denied")).await?;
+        return Ok(());
+    }
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<SocketRequest>(&line) {
+            Ok(request) => {
+                tracing::debug!(uid, path = %request.path, "agent socket request");
+                respond(&agent, request).await
+            }
+            Err(err) => error_response(&format!("invalid request: {err}")),
+        };
+        write_line(&mut writer, &response).await?;
+    }
+    Ok(())
+}
+
+async fn respond(agent: &Agent, request: SocketRequest) -> JsonValue {
+    let payload = match agent.lookup(&request.path).await {
+        Ok(payload) => payload,
+        Err(err) => return error_response(&err.to_string()),
+    };
+    match request.field {
+        Some(field) => match crate::find_field(&payload, &field) {
+            Some(value) => json!({ "ok": true, "value": value.value }),
+            None => error_response(&format!("field '{}' not found in {}", field, request.path)),
+        },
+        None => json!({ "ok": true, "fields": flatten_payload(&payload) }),
+    }
+}
+
+fn error_response(message: &str) -> JsonValue {
+    json!({ "ok": false, "error": message })
+}
+
+async fn write_line(
+    writer: &mut tokio::net::unix::OwnedWriteHalf,
+    value: &JsonValue,
+) -> anyhow::Result<()> {
+    let mut line = serde_json::to_vec(value)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
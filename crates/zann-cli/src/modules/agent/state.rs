@@ -0,0 +1,139 @@
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::Mutex;
+use zann_core::EncryptedPayload;
+
+use crate::modules::agent::cache::SecretCache;
+use crate::modules::agent::session::Session;
+use crate::modules::shared::{fetch_shared_items, payload_or_error, secret_not_found_error};
+
+const PULL_LIMIT: i64 = 250;
+const LOOKUP_LIMIT: i64 = 200;
+
+#[derive(Deserialize)]
+struct VaultResponse {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct PullResponse {
+    changes: Vec<PullChange>,
+    next_cursor: String,
+    has_more: bool,
+}
+
+#[derive(Deserialize)]
+struct PullChange {
+    path: String,
+}
+
+/// State shared by the sync loop and socket connections.
+pub(crate) struct Agent {
+    pub client: reqwest::Client,
+    pub addr: String,
+    pub session: Session,
+    /// Vault as configured (slug or ID), used for item lookups.
+    pub vault: String,
+    /// Vault ID, which the sync API requires.
+    pub vault_id: String,
+    pub cache: Mutex<SecretCache>,
+}
+
+impl Agent {
+    /// Payload of the item at `path`; served from the cache while it is fresh.
+    pub(crate) async fn lookup(&self, path: &str) -> anyhow::Result<EncryptedPayload> {
+        if let Some(payload) = self.cache.lock().await.get(path) {
+            return Ok(payload);
+        }
+        let access_token = self.session.access_token().await?;
+        // Listing by prefix already returns full items.
+        let items = fetch_shared_items(
+            &self.client,
+            &self.addr,
+            &access_token,
+            &self.vault,
+            Some(path),
+            Some(LOOKUP_LIMIT),
+            None,
+        )
+        .await?;
+        let wanted = path.trim().trim_matches('/');
+        let item = items
+            .items
+            .iter()
+            .find(|item| item.path.trim().trim_matches('/') == wanted)
+            .ok_or_else(|| secret_not_found_error(path))?;
+        let payload = payload_or_error(item)?;
+        self.cache.lock().await.insert(path, payload.clone());
+        Ok(payload)
+    }
+
+    /// Pulls every change after `cursor` and returns the changed paths with
+    /// the new cursor. Changed paths are evicted from the cache.
+    pub(crate) async fn pull_changes(
+        &self,
+        cursor: Option<String>,
+    ) -> anyhow::Result<(Vec<String>, String)> {
+        let url = format!("{}/v1/sync/shared/pull", self.addr.trim_end_matches('/'));
+        let mut cursor = cursor;
+        let mut paths = Vec::new();
+        loop {
+            let access_token = self.session.access_token().await?;
+            let body = json!({
+                "vault_id": self.vault_id,
+                "cursor": cursor,
+                "limit": PULL_LIMIT,
+            });
+            let response = self
+                .client
+                .post(&url)
+                .bearer_auth(access_token)
+                .json(&body)
+                .send()
+                .await?;
+            let page: PullResponse = parse_response(response).await?;
+            paths.extend(page.changes.into_iter().map(|change| change.path));
+            let has_more = page.has_more;
+            cursor = Some(page.next_cursor);
+            if !has_more {
+                break;
+            }
+        }
+        let mut cache = self.cache.lock().await;
+        for path in &paths {
+            cache.evict(path);
+        }
+        Ok((paths, cursor.unwrap_or_default()))
+    }
+}
+
+/// Looks up the ID of `vault`, which may be a slug.
+pub(crate) async fn fetch_vault_id(
+    client: &reqwest::Client,
+    addr: &str,
+    access_token: &str,
+    vault: &str,
+) -> anyhow::Result<String> {
+    if uuid::Uuid::parse_str(vault).is_ok() {
+        return Ok(vault.to_string());
+    }
+    let url = format!(
+        "{}/v1/vaults/{}",
+        addr.trim_end_matches('/'),
+        urlencoding::encode(vault)
+    );
+    let response = client.get(url).bearer_auth(access_token).send().await?;
+    let vault: VaultResponse = parse_response(response).await?;
+    Ok(vault.id)
+}
+
+async fn parse_response<T: for<'de> Deserialize<'de>>(
+    response: reqwest::Response,
+) -> anyhow::Result<T> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Request failed: {status} {body}");
+    }
+    Ok(response.json().await?)
+}
@@ -0,0 +1,351 @@
+use std::collections::{BTreeMap, HashMap};
+
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use zann_core::{EncryptedPayload, FieldKind, FieldValue};
+
+use crate::cli_args::*;
+use crate::modules::import::parse::parse_entries;
+use crate::modules::shared::{resolve_vault_arg, ItemsResponse, SharedItemResponse};
+use crate::modules::system::http::{append_params, build_params, opt_param, send_request};
+use crate::modules::system::CommandContext;
+
+#[derive(Deserialize)]
+struct BatchResult {
+    path: String,
+    status: String,
+    #[serde(default)]
+    secret: Option<BatchSecret>,
+    #[serde(default)]
+    error: Option<BatchError>,
+}
+
+#[derive(Deserialize)]
+struct BatchSecret {
+    value: String,
+}
+
+#[derive(Deserialize)]
+struct BatchError {
+    error: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Create,
+    Overwrite,
+    Skip,
+    Unchanged,
+    Conflict,
+}
+
+fn classify(current: Option<&str>, value: &str, mode: ConflictMode) -> Action {
+    match current {
+        None => Action::Create,
+        Some(current) if current == value => Action::Unchanged,
+        Some(_) => match mode {
+            ConflictMode::Skip => Action::Skip,
+            ConflictMode::Overwrite => Action::Overwrite,
+            ConflictMode::Fail => Action::Conflict,
+        },
+    }
+}
+
+fn writes(action: Action) -> bool {
+    matches!(action, Action::Create | Action::Overwrite)
+}
+
+/// Prints one line per key that is not unchanged; values are never shown.
+fn print_actions<'a>(actions: impl Iterator<Item = (&'a str, Action)>, indent: &str) {
+    for (label, action) in actions {
+        match action {
+            Action::Create => println!("{indent}+ {label}"),
+            Action::Overwrite => println!("{indent}~ {label}"),
+            Action::Skip => println!("{indent}= {label} (exists, skipped)"),
+            Action::Conflict => println!("{indent}! {label} (exists with a different value)"),
+            Action::Unchanged => {}
+        }
+    }
+}
+
+/// Prints the totals and fails on conflicts. Returns whether anything should be written.
+fn finish_report(actions: &[Action], dry_run: bool) -> anyhow::Result<bool> {
+    let count = |wanted: Action| actions.iter().filter(|action| **action == wanted).count();
+    let conflicts = count(Action::Conflict);
+    if conflicts > 0 {
+        anyhow::bail!(
+            "{conflicts} key(s) already exist with a different value; use --on-conflict skip or overwrite"
+        );
+    }
+    println!(
+        "{}{} new, {} overwritten, {} skipped, {} unchanged.",
+        if dry_run { "Dry run: " } else { "Import: " },
+        count(Action::Create),
+        count(Action::Overwrite),
+        count(Action::Skip),
+        count(Action::Unchanged)
+    );
+    Ok(!dry_run && actions.iter().any(|action| writes(*action)))
+}
+
+pub(crate) async fn handle_import(
+    args: ImportArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<()> {
+    let contents = std::fs::read_to_string(&args.file)
+        .map_err(|err| anyhow::anyhow!("failed to read {}: {err}", args.file.display()))?;
+    let entries = parse_entries(args.from, &contents)
+        .map_err(|err| anyhow::anyhow!("invalid {}: {err}", args.file.display()))?;
+    if entries.is_empty() {
+        anyhow::bail!("{} has no keys to import", args.file.display());
+    }
+    let path = args.path.trim().trim_matches('/').to_string();
+    if path.is_empty() {
+        anyhow::bail!("--path is required");
+    }
+    let vault = resolve_vault_arg(args.vault.clone(), ctx).await?;
+    if args.item_per_key {
+        import_secrets(ctx, &vault, &path, &entries, &args).await
+    } else {
+        import_item(ctx, &vault, &path, &entries, &args).await
+    }
+}
+
+/// One secret per key under `prefix`: one batch read, then one batch write.
+async fn import_secrets(
+    ctx: &mut CommandContext<'_>,
+    vault: &str,
+    prefix: &str,
+    entries: &BTreeMap<String, String>,
+    args: &ImportArgs,
+) -> anyhow::Result<()> {
+    let paths: Vec<String> = entries
+        .keys()
+        .map(|key| format!("{prefix}/{key}"))
+        .collect();
+    let response = send_request(
+        ctx,
+        Method::POST,
+        secrets_url(ctx, vault, "batch/get"),
+        Some(json!({ "paths": paths })),
+    )
+    .await?;
+    let results: Vec<BatchResult> = parse_response(response).await?;
+    let mut existing = HashMap::new();
+    for result in results {
+        match (result.status.as_str(), result.secret, result.error) {
+            ("ok", Some(secret), _) => {
+                existing.insert(result.path, secret.value);
+            }
+            (_, _, Some(error)) if error.error == "not_found" => {}
+            (_, _, error) => anyhow::bail!(
+                "{}: {}",
+                result.path,
+                error.map(|error| error.error).unwrap_or(result.status)
+            ),
+        }
+    }
+
+    let planned: Vec<(String, &String, Action)> = entries
+        .values()
+        .zip(paths)
+        .map(|(value, path)| {
+            let action = classify(
+                existing.get(&path).map(String::as_str),
+                value,
+                args.on_conflict,
+            );
+            (path, value, action)
+        })
+        .collect();
+    print_actions(
+        planned
+            .iter()
+            .map(|(path, _, action)| (path.as_str(), *action)),
+        "",
+    );
+    let actions: Vec<Action> = planned.iter().map(|(_, _, action)| *action).collect();
+    if !finish_report(&actions, args.dry_run)? {
+        return Ok(());
+    }
+
+    let secrets: Vec<JsonValue> = planned
+        .iter()
+        .filter(|(_, _, action)| writes(*action))
+        .map(|(path, value, _)| json!({ "path": path, "value": value }))
+        .collect();
+    let response = send_request(
+        ctx,
+        Method::POST,
+        secrets_url(ctx, vault, "batch/set"),
+        Some(json!({ "secrets": secrets })),
+    )
+    .await?;
+    let results: Vec<BatchResult> = parse_response(response).await?;
+    let failures: Vec<String> = results
+        .into_iter()
+        .filter(|result| result.status == "error")
+        .map(|result| {
+            format!(
+                "{}: {}",
+                result.path,
+                result
+                    .error
+                    .map(|error| error.error)
+                    .unwrap_or(result.status)
+            )
+        })
+        .collect();
+    if !failures.is_empty() {
+        anyhow::bail!("import failed for {}", failures.join("; "));
+    }
+    Ok(())
+}
+
+/// All keys as fields of the item at `path`; fields not in the file are kept.
+async fn import_item(
+    ctx: &mut CommandContext<'_>,
+    vault: &str,
+    path: &str,
+    entries: &BTreeMap<String, String>,
+    args: &ImportArgs,
+) -> anyhow::Result<()> {
+    let existing = find_item(ctx, vault, path).await?;
+    let current = match &existing {
+        Some(item) => {
+            let payload = item
+                .payload
+                .clone()
+                .and_then(|payload| serde_json::from_value::<EncryptedPayload>(payload).ok())
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "{path} does not hold fields; use --item-per-key or another --path"
+                    )
+                })?;
+            Some(payload)
+        }
+        None => None,
+    };
+
+    let planned: Vec<(&String, &String, Action)> = entries
+        .iter()
+        .map(|(key, value)| {
+            let field = current
+                .as_ref()
+                .and_then(|payload| payload.fields.get(key))
+                .map(|field| field.value.as_str());
+            (key, value, classify(field, value, args.on_conflict))
+        })
+        .collect();
+    match &current {
+        Some(_) => println!("~ {path}"),
+        None => println!("+ {path} ({})", args.type_id),
+    }
+    let labels: Vec<(String, Action)> = planned
+        .iter()
+        .map(|(key, _, action)| (format!("field {key}"), *action))
+        .collect();
+    print_actions(
+        labels
+            .iter()
+            .map(|(label, action)| (label.as_str(), *action)),
+        "    ",
+    );
+    let actions: Vec<Action> = planned.iter().map(|(_, _, action)| *action).collect();
+    if !finish_report(&actions, args.dry_run)? {
+        return Ok(());
+    }
+
+    let mut payload = current.unwrap_or_else(|| EncryptedPayload::new(&args.type_id));
+    for (key, value, action) in planned {
+        if !writes(action) {
+            continue;
+        }
+        let field = payload
+            .fields
+            .entry(key.clone())
+            .or_insert_with(|| FieldValue {
+                kind: FieldKind::Text,
+                value: String::new(),
+                meta: None,
+            });
+        field.value = value.clone();
+    }
+    let (method, url, body) = match existing {
+        Some(item) => (
+            Method::PUT,
+            format!("{}/v1/shared/items/{}", base(ctx), item.id),
+            json!({ "payload": payload }),
+        ),
+        None => (
+            Method::POST,
+            format!("{}/v1/shared/items", base(ctx)),
+            json!({
+                "vault_id": vault,
+                "path": path,
+                "type_id": args.type_id,
+                "payload": payload,
+            }),
+        ),
+    };
+    let response = send_request(ctx, method, url, Some(body)).await?;
+    let _: SharedItemResponse = parse_response(response).await?;
+    Ok(())
+}
+
+async fn find_item(
+    ctx: &mut CommandContext<'_>,
+    vault: &str,
+    path: &str,
+) -> anyhow::Result<Option<SharedItemResponse>> {
+    let mut url = format!(
+        "{}/v1/vaults/{}/items",
+        base(ctx),
+        urlencoding::encode(vault)
+    );
+    append_params(
+        &mut url,
+        build_params([opt_param("prefix", Some(path.to_string()))]),
+    );
+    let response = send_request(ctx, Method::GET, url, None).await?;
+    let items: ItemsResponse = parse_response(response).await?;
+    let Some(summary) = items
+        .items
+        .into_iter()
+        .find(|item| item.path.trim_matches('/') == path)
+    else {
+        return Ok(None);
+    };
+    let url = format!(
+        "{}/v1/vaults/{}/items/{}",
+        base(ctx),
+        urlencoding::encode(vault),
+        summary.id
+    );
+    let response = send_request(ctx, Method::GET, url, None).await?;
+    Ok(Some(parse_response(response).await?))
+}
+
+fn secrets_url(ctx: &CommandContext<'_>, vault: &str, endpoint: &str) -> String {
+    format!(
+        "{}/v1/vaults/{}/secrets/{endpoint}",
+        base(ctx),
+        urlencoding::encode(vault)
+    )
+}
+
+fn base(ctx: &CommandContext<'_>) -> String {
+    ctx.addr.trim_end_matches('/').to_string()
+}
+
+async fn parse_response<T: for<'de> Deserialize<'de>>(
+    response: reqwest::Response,
+) -> anyhow::Result<T> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Request failed: {status} {body}");
+    }
+    Ok(response.json().await?)
+}
@@ -0,0 +1,45 @@
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ImportArgs {
+    #[arg(long = "from", value_enum, help = "Input format")]
+    pub from: ImportFormat,
+    #[arg(help = "File to import")]
+    pub file: PathBuf,
+    #[arg(long, help = "Vault name or ID")]
+    pub vault: Option<String>,
+    #[arg(long, help = "Item path, or the prefix with --item-per-key")]
+    pub path: String,
+    #[arg(
+        long,
+        help = "Create one secret per key under --path instead of one item with a field per key"
+    )]
+    pub item_per_key: bool,
+    #[arg(long, default_value = "kv", help = "Item type (one item mode)")]
+    pub type_id: String,
+    #[arg(
+        long,
+        value_enum,
+        default_value = "fail",
+        help = "What to do with keys that already exist with a different value"
+    )]
+    pub on_conflict: ConflictMode,
+    #[arg(long, help = "Report what would change without writing anything")]
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ImportFormat {
+    Dotenv,
+    Json,
+    Yaml,
+    K8sSecret,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConflictMode {
+    Skip,
+    Overwrite,
+    Fail,
+}
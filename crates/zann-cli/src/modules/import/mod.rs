@@ -0,0 +1,5 @@
+mod actions;
+pub(crate) mod args;
+mod parse;
+
+pub(crate) use actions::handle_import;
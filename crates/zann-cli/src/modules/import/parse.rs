@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use base64::Engine;
+use serde::Deserialize;
+
+use crate::cli_args::ImportFormat;
+use crate::modules::shared::is_valid_env_key;
+
+/// Reads `contents` into key/value pairs. Later keys win, as with `source .env`.
+pub(crate) fn parse_entries(
+    format: ImportFormat,
+    contents: &str,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    match format {
+        ImportFormat::Dotenv => parse_dotenv(contents),
+        ImportFormat::Json => parse_json(contents),
+        ImportFormat::Yaml => parse_yaml(contents),
+        ImportFormat::K8sSecret => parse_k8s_secret(contents),
+    }
+}
+
+fn parse_dotenv(contents: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    let mut entries = BTreeMap::new();
+    let mut lines = contents.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line
+            .strip_prefix("export ")
+            .map(str::trim_start)
+            .unwrap_or(line);
+        let Some((key, raw)) = line.split_once('=') else {
+            anyhow::bail!("line {}: expected KEY=VALUE", index + 1);
+        };
+        let key = key.trim();
+        if !is_valid_env_key(key) {
+            anyhow::bail!("line {}: invalid key {key}", index + 1);
+        }
+        let raw = raw.trim_start();
+        let value = match raw.chars().next() {
+            Some(quote @ ('"' | '\'')) => read_quoted(
+                &raw[1..],
+                quote,
+                index + 1,
+                &mut lines.by_ref().map(|(_, l)| l),
+            )?,
+            _ => strip_inline_comment(raw).trim_end().to_string(),
+        };
+        entries.insert(key.to_string(), value);
+    }
+    Ok(entries)
+}
+
+/// Reads a quoted value that may continue on the following lines. Double
+/// quotes understand `\n`, `\t`, `\"` and `\\`; single quotes are literal.
+fn read_quoted<'a>(
+    first: &str,
+    quote: char,
+    line_number: usize,
+    rest: &mut impl Iterator<Item = &'a str>,
+) -> anyhow::Result<String> {
+    let mut value = String::new();
+    let mut line = first.to_string();
+    loop {
+        let mut chars = line.chars();
+        while let Some(ch) = chars.next() {
+            if ch == quote {
+                let tail = chars.as_str().trim();
+                if !tail.is_empty() && !tail.starts_with('#') {
+                    anyhow::bail!("line {line_number}: unexpected text after closing quote");
+                }
+                return Ok(value);
+            }
+            if ch == '\\' && quote == '"' {
+                match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some(other @ ('"' | '\\')) => value.push(other),
+                    Some(other) => {
+                        value.push('\\');
+                        value.push(other);
+                    }
+                    None => value.push('\\'),
+                }
+                continue;
+            }
+            value.push(ch);
+        }
+        let Some(next) = rest.next() else {
+            anyhow::bail!("line {line_number}: unterminated quoted value");
+        };
+        value.push('\n');
+        line = next.to_string();
+    }
+}
+
+/// `KEY=value # note` -> `value`; a `#` inside the value is kept.
+fn strip_inline_comment(raw: &str) -> &str {
+    match raw.find(" #").or_else(|| raw.find("\t#")) {
+        Some(index) => &raw[..index],
+        None => raw,
+    }
+}
+
+fn parse_json(contents: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    let value: serde_json::Value = serde_json::from_str(contents)?;
+    let serde_json::Value::Object(object) = value else {
+        anyhow::bail!("expected a JSON object of keys and values");
+    };
+    let mut entries = BTreeMap::new();
+    for (key, value) in object {
+        let value = match value {
+            serde_json::Value::String(value) => value,
+            serde_json::Value::Number(value) => value.to_string(),
+            serde_json::Value::Bool(value) => value.to_string(),
+            _ => anyhow::bail!("{key}: value must be a string, number or boolean"),
+        };
+        entries.insert(checked_key(key)?, value);
+    }
+    Ok(entries)
+}
+
+fn parse_yaml(contents: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    let value: serde_yaml::Value = serde_yaml::from_str(contents)?;
+    let serde_yaml::Value::Mapping(mapping) = value else {
+        anyhow::bail!("expected a YAML mapping of keys and values");
+    };
+    let mut entries = BTreeMap::new();
+    for (key, value) in mapping {
+        let serde_yaml::Value::String(key) = key else {
+            anyhow::bail!("keys must be strings");
+        };
+        let value = match value {
+            serde_yaml::Value::String(value) => value,
+            serde_yaml::Value::Number(value) => value.to_string(),
+            serde_yaml::Value::Bool(value) => value.to_string(),
+            _ => anyhow::bail!("{key}: value must be a string, number or boolean"),
+        };
+        entries.insert(checked_key(key)?, value);
+    }
+    Ok(entries)
+}
+
+#[derive(Deserialize)]
+struct K8sSecret {
+    #[serde(default)]
+    kind: String,
+    #[serde(default)]
+    data: BTreeMap<String, String>,
+    #[serde(default, rename = "stringData")]
+    string_data: BTreeMap<String, String>,
+}
+
+/// `data` is base64 encoded; `stringData` is plain and wins, as in the API server.
+fn parse_k8s_secret(contents: &str) -> anyhow::Result<BTreeMap<String, String>> {
+    let secret: K8sSecret = serde_yaml::from_str(contents)?;
+    if secret.kind != "Secret" {
+        anyhow::bail!("expected kind: Secret, found {:?}", secret.kind);
+    }
+    let mut entries = BTreeMap::new();
+    for (key, encoded) in secret.data {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .map_err(|err| anyhow::anyhow!("{key}: invalid base64: {err}"))?;
+        let value = String::from_utf8(bytes)
+            .map_err(|_| anyhow::anyhow!("{key}: binary values are not supported"))?;
+        entries.insert(checked_key(key)?, value);
+    }
+    for (key, value) in secret.string_data {
+        entries.insert(checked_key(key)?, value);
+    }
+    Ok(entries)
+}
+
+fn checked_key(key: String) -> anyhow::Result<String> {
+    let trimmed = key.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        anyhow::bail!("empty key");
+    }
+    Ok(trimmed.to_string())
+}
@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::cli_args::*;
+use crate::modules::manifest::plan::{
+    build_plan, new_payload, normalize_path, render_plan, Plan, Step,
+};
+use crate::modules::manifest::types::{
+    load_manifest, BatchEnsureResult, DesiredItem, ItemDetail, ItemSummary, ItemsResponse,
+};
+use crate::modules::shared::resolve_vault_arg;
+use crate::modules::system::http::{append_params, build_params, opt_param, send_request};
+use crate::modules::system::CommandContext;
+
+pub(crate) async fn handle_plan(
+    args: ManifestArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<()> {
+    let (_, plan) = prepare(args, ctx).await?;
+    print!("{}", render_plan(&plan));
+    let conflicts = plan.conflicts();
+    if conflicts > 0 {
+        anyhow::bail!("plan has {conflicts} conflict(s)");
+    }
+    Ok(())
+}
+
+pub(crate) async fn handle_apply(
+    args: ManifestArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<()> {
+    let (vault, plan) = prepare(args, ctx).await?;
+    print!("{}", render_plan(&plan));
+    let conflicts = plan.conflicts();
+    if conflicts > 0 {
+        anyhow::bail!("plan has {conflicts} conflict(s); nothing was changed");
+    }
+    if plan.is_empty() {
+        return Ok(());
+    }
+    apply_plan(ctx, &vault, plan).await
+}
+
+async fn prepare(
+    args: ManifestArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<(String, Plan)> {
+    let manifest = load_manifest(&args.file)?;
+    if args.prune && manifest.prefix.is_none() {
+        anyhow::bail!("--prune needs a prefix in the manifest");
+    }
+    let vault = resolve_vault_arg(args.vault.or(manifest.vault.clone()), ctx).await?;
+    let current = list_items(ctx, &vault, manifest.prefix.as_deref()).await?;
+    let by_path: HashMap<&str, &ItemSummary> = current
+        .iter()
+        .map(|item| (normalize_path(&item.path), item))
+        .collect();
+    let mut details = HashMap::new();
+    for item in &manifest.items {
+        if let Some(summary) = by_path.get(item.path.as_str()) {
+            let detail = fetch_item(ctx, &vault, &summary.id).await?;
+            details.insert(item.path.clone(), detail);
+        }
+    }
+    let prune_prefix = if args.prune {
+        manifest.prefix.as_deref()
+    } else {
+        None
+    };
+    let plan = build_plan(&manifest.items, &current, &details, prune_prefix);
+    Ok((vault, plan))
+}
+
+async fn apply_plan(ctx: &mut CommandContext<'_>, vault: &str, plan: Plan) -> anyhow::Result<()> {
+    let (mut created, mut updated, mut deleted) = (0, 0, 0);
+    let mut generated = Vec::new();
+    for step in plan.steps {
+        match step {
+            Step::Create(item) if item.generate.is_some() => generated.push(item),
+            Step::Create(item) => {
+                create_item(ctx, vault, &item).await?;
+                println!("Created {}", item.path);
+                created += 1;
+            }
+            Step::Update {
+                id,
+                desired,
+                payload,
+                ..
+            } => {
+                let body = json!({
+                    "type_id": desired.type_id,
+                    "tags": desired.tags,
+                    "payload": payload,
+                });
+                let url = format!("{}/v1/shared/items/{}", base(ctx), id);
+                let response = send_request(ctx, Method::PUT, url, Some(body)).await?;
+                check_response(response, &desired.path).await?;
+                println!("Updated {}", desired.path);
+                updated += 1;
+            }
+            Step::Delete { id, path } => {
+                let url = format!("{}/v1/shared/items/{}", base(ctx), id);
+                let response = send_request(ctx, Method::DELETE, url, None).await?;
+                check_response(response, &path).await?;
+                println!("Deleted {path}");
+                deleted += 1;
+            }
+            Step::Conflict { .. } => {}
+        }
+    }
+    if !generated.is_empty() {
+        created += ensure_generated(ctx, vault, &generated).await?;
+    }
+    println!("Apply complete: {created} created, {updated} updated, {deleted} deleted.");
+    Ok(())
+}
+
+async fn create_item(
+    ctx: &mut CommandContext<'_>,
+    vault: &str,
+    item: &DesiredItem,
+) -> anyhow::Result<()> {
+    let mut body = json!({
+        "vault_id": vault,
+        "path": item.path,
+        "type_id": item.type_id,
+        "payload": new_payload(item),
+    });
+    if !item.tags.is_empty() {
+        body["tags"] = json!(item.tags);
+    }
+    let url = format!("{}/v1/shared/items", base(ctx));
+    let response = send_request(ctx, Method::POST, url, Some(body)).await?;
+    check_response(response, &item.path).await
+}
+
+/// Creates generated secrets in one batch; the server picks the values. Tags
+/// are set afterwards because the secrets API does not take them.
+async fn ensure_generated(
+    ctx: &mut CommandContext<'_>,
+    vault: &str,
+    items: &[DesiredItem],
+) -> anyhow::Result<usize> {
+    let secrets: Vec<_> = items
+        .iter()
+        .map(|item| {
+            let mut secret = json!({ "path": item.path, "policy": item.generate });
+            if !item.fields.is_empty() {
+                secret["meta"] = json!(item.fields);
+            }
+            secret
+        })
+        .collect();
+    let url = format!(
+        "{}/v1/vaults/{}/secrets/batch/ensure",
+        base(ctx),
+        urlencoding::encode(vault)
+    );
+    let response =
+        send_request(ctx, Method::POST, url, Some(json!({ "secrets": secrets }))).await?;
+    let results: Vec<BatchEnsureResult> = parse_response(response).await?;
+    let mut created = 0;
+    let mut failures = Vec::new();
+    for result in &results {
+        match result.status.as_str() {
+            "created" => {
+                println!("Created {}", result.path);
+                created += 1;
+            }
+            "existing" => println!("Exists {}", result.path),
+            _ => failures.push(format!(
+                "{}: {}",
+                result.path,
+                result
+                    .error
+                    .as_ref()
+                    .map(|error| error.to_string())
+                    .unwrap_or_else(|| result.status.clone())
+            )),
+        }
+    }
+    if !failures.is_empty() {
+        anyhow::bail!(
+            "failed to create generated secrets: {}",
+            failures.join("; ")
+        );
+    }
+
+    let tagged: Vec<&DesiredItem> = items.iter().filter(|item| !item.tags.is_empty()).collect();
+    if !tagged.is_empty() {
+        let current = list_items(ctx, vault, None).await?;
+        for item in tagged {
+            let Some(summary) = current
+                .iter()
+                .find(|summary| normalize_path(&summary.path) == item.path)
+            else {
+                anyhow::bail!("{} was not found after it was created", item.path);
+            };
+            let detail = fetch_item(ctx, vault, &summary.id).await?;
+            let body = json!({ "tags": item.tags, "payload": detail.payload });
+            let url = format!("{}/v1/shared/items/{}", base(ctx), summary.id);
+            let response = send_request(ctx, Method::PUT, url, Some(body)).await?;
+            check_response(response, &item.path).await?;
+        }
+    }
+    Ok(created)
+}
+
+async fn list_items(
+    ctx: &mut CommandContext<'_>,
+    vault: &str,
+    prefix: Option<&str>,
+) -> anyhow::Result<Vec<ItemSummary>> {
+    let mut url = format!(
+        "{}/v1/vaults/{}/items",
+        base(ctx),
+        urlencoding::encode(vault)
+    );
+    append_params(
+        &mut url,
+        build_params([opt_param("prefix", prefix.map(str::to_string))]),
+    );
+    let response = send_request(ctx, Method::GET, url, None).await?;
+    let items: ItemsResponse = parse_response(response).await?;
+    Ok(items
+        .items
+        .into_iter()
+        .filter(|item| item.deleted_at.is_none())
+        .collect())
+}
+
+async fn fetch_item(
+    ctx: &mut CommandContext<'_>,
+    vault: &str,
+    item_id: &str,
+) -> anyhow::Result<ItemDetail> {
+    let url = format!(
+        "{}/v1/vaults/{}/items/{}",
+        base(ctx),
+        urlencoding::encode(vault),
+        item_id
+    );
+    let response = send_request(ctx, Method::GET, url, None).await?;
+    parse_response(response).await
+}
+
+fn base(ctx: &CommandContext<'_>) -> String {
+    ctx.addr.trim_end_matches('/').to_string()
+}
+
+async fn check_response(response: reqwest::Response, path: &str) -> anyhow::Result<()> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("{path}: request failed: {status} {body}");
+    }
+    Ok(())
+}
+
+async fn parse_response<T: for<'de> Deserialize<'de>>(
+    response: reqwest::Response,
+) -> anyhow::Result<T> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Request failed: {status} {body}");
+    }
+    Ok(response.json().await?)
+}
@@ -0,0 +1,15 @@
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ManifestArgs {
+    #[arg(short = 'f', long = "file", help = "Secrets manifest (YAML)")]
+    pub file: PathBuf,
+    #[arg(long, help = "Vault name or ID (overrides the manifest)")]
+    pub vault: Option<String>,
+    #[arg(
+        long,
+        help = "Delete items under the manifest prefix that the manifest does not declare"
+    )]
+    pub prune: bool,
+}
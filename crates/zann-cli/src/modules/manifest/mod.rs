@@ -0,0 +1,6 @@
+mod actions;
+pub(crate) mod args;
+mod plan;
+mod types;
+
+pub(crate) use actions::{handle_apply, handle_plan};
@@ -0,0 +1,370 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde_json::{json, Value as JsonValue};
+use zann_core::{EncryptedPayload, FieldKind, FieldValue};
+
+use crate::modules::manifest::types::{DesiredItem, ItemDetail, ItemSummary};
+use crate::modules::shared::prefix_match;
+
+/// Metadata the server adds to generated secrets (SSH public keys); kept on update.
+const SERVER_META_KEYS: &[&str] = &["public_key"];
+
+/// What an item holds on the server: regular fields, or a value generated by a
+/// secret policy with static metadata next to it.
+enum Content {
+    Fields(EncryptedPayload),
+    Generated {
+        value: String,
+        policy: String,
+        meta: BTreeMap<String, String>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Diff {
+    Type { from: String, to: String },
+    AddField(String),
+    ChangeField(String),
+    RemoveField(String),
+    AddTag(String),
+    RemoveTag(String),
+}
+
+#[derive(Debug)]
+pub(crate) enum Step {
+    Create(DesiredItem),
+    Update {
+        id: String,
+        desired: DesiredItem,
+        diffs: Vec<Diff>,
+        payload: JsonValue,
+    },
+    Delete {
+        id: String,
+        path: String,
+    },
+    Conflict {
+        path: String,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Plan {
+    pub steps: Vec<Step>,
+    pub unchanged: usize,
+}
+
+impl Plan {
+    pub(crate) fn conflicts(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| matches!(step, Step::Conflict { .. }))
+            .count()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+pub(crate) fn normalize_path(path: &str) -> &str {
+    path.trim().trim_matches('/')
+}
+
+/// Compares the manifest with the server. `details` holds the full item for
+/// every declared path that exists; with `prune_prefix`, undeclared items
+/// under it are deleted.
+pub(crate) fn build_plan(
+    desired: &[DesiredItem],
+    current: &[ItemSummary],
+    details: &HashMap<String, ItemDetail>,
+    prune_prefix: Option<&str>,
+) -> Plan {
+    let existing: HashMap<&str, &ItemSummary> = current
+        .iter()
+        .filter(|item| item.deleted_at.is_none())
+        .map(|item| (normalize_path(&item.path), item))
+        .collect();
+    let mut plan = Plan::default();
+    for item in desired {
+        if !existing.contains_key(item.path.as_str()) {
+            plan.steps.push(Step::Create(item.clone()));
+            continue;
+        }
+        let Some(detail) = details.get(&item.path) else {
+            plan.steps.push(Step::Conflict {
+                path: item.path.clone(),
+                reason: "item could not be read".to_string(),
+            });
+            continue;
+        };
+        match diff_item(item, detail) {
+            Ok(None) => plan.unchanged += 1,
+            Ok(Some((diffs, payload))) => plan.steps.push(Step::Update {
+                id: detail.id.clone(),
+                desired: item.clone(),
+                diffs,
+                payload,
+            }),
+            Err(reason) => plan.steps.push(Step::Conflict {
+                path: item.path.clone(),
+                reason,
+            }),
+        }
+    }
+    if let Some(prefix) = prune_prefix {
+        let declared: BTreeSet<&str> = desired.iter().map(|item| item.path.as_str()).collect();
+        let mut unmanaged: Vec<(&str, &ItemSummary)> = existing
+            .iter()
+            .filter(|(path, _)| prefix_match(Some(prefix), path) && !declared.contains(*path))
+            .map(|(path, item)| (*path, *item))
+            .collect();
+        unmanaged.sort_by_key(|(path, _)| *path);
+        for (path, item) in unmanaged {
+            plan.steps.push(Step::Delete {
+                id: item.id.clone(),
+                path: path.to_string(),
+            });
+        }
+    }
+    plan
+}
+
+/// The payload for a new item with static fields.
+pub(crate) fn new_payload(item: &DesiredItem) -> EncryptedPayload {
+    let mut payload = EncryptedPayload::new(&item.type_id);
+    for (key, value) in &item.fields {
+        payload.fields.insert(
+            key.clone(),
+            FieldValue {
+                kind: FieldKind::Text,
+                value: value.clone(),
+                meta: None,
+            },
+        );
+    }
+    payload
+}
+
+fn read_content(payload: Option<&JsonValue>) -> Option<Content> {
+    let payload = payload?;
+    let object = payload.as_object()?;
+    if !object.contains_key("fields") {
+        if let (Some(value), Some(policy)) = (
+            object.get("value").and_then(JsonValue::as_str),
+            object.get("policy").and_then(JsonValue::as_str),
+        ) {
+            let meta = object
+                .get("meta")
+                .and_then(JsonValue::as_object)
+                .map(|meta| {
+                    meta.iter()
+                        .filter_map(|(key, value)| {
+                            value.as_str().map(|value| (key.clone(), value.to_string()))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            return Some(Content::Generated {
+                value: value.to_string(),
+                policy: policy.to_string(),
+                meta,
+            });
+        }
+    }
+    serde_json::from_value::<EncryptedPayload>(payload.clone())
+        .ok()
+        .map(Content::Fields)
+}
+
+fn diff_fields<'a>(
+    desired: &BTreeMap<String, String>,
+    current: impl Iterator<Item = (&'a String, &'a String)>,
+    diffs: &mut Vec<Diff>,
+) {
+    let current: BTreeMap<&String, &String> = current.collect();
+    for (key, value) in desired {
+        match current.get(key) {
+            None => diffs.push(Diff::AddField(key.clone())),
+            Some(existing) if *existing != value => diffs.push(Diff::ChangeField(key.clone())),
+            Some(_) => {}
+        }
+    }
+    for key in current.keys() {
+        if !desired.contains_key(*key) {
+            diffs.push(Diff::RemoveField((*key).clone()));
+        }
+    }
+}
+
+type ItemUpdate = (Vec<Diff>, JsonValue);
+
+fn diff_item(item: &DesiredItem, detail: &ItemDetail) -> Result<Option<ItemUpdate>, String> {
+    let content = read_content(detail.payload.as_ref())
+        .ok_or_else(|| "payload is not readable".to_string())?;
+    let mut diffs = Vec::new();
+    let payload = match (item.generate.as_deref(), content) {
+        (Some(_), Content::Fields(_)) => {
+            return Err(format!(
+                "exists as a {} item with fields; generated secrets need their own path",
+                detail.type_id
+            ));
+        }
+        (None, Content::Generated { policy, .. }) => {
+            return Err(format!(
+                "holds a secret generated with policy {policy}; set generate: {policy} to manage it"
+            ));
+        }
+        (Some(wanted), Content::Generated { policy, .. }) if wanted != policy => {
+            return Err(format!(
+                "generated with policy {policy}, the manifest asks for {wanted}"
+            ));
+        }
+        (
+            Some(_),
+            Content::Generated {
+                value,
+                policy,
+                meta,
+            },
+        ) => {
+            diff_fields(
+                &item.fields,
+                meta.iter()
+                    .filter(|(key, _)| !SERVER_META_KEYS.contains(&key.as_str())),
+                &mut diffs,
+            );
+            let mut next: BTreeMap<String, String> = meta
+                .into_iter()
+                .filter(|(key, _)| SERVER_META_KEYS.contains(&key.as_str()))
+                .collect();
+            next.extend(item.fields.clone());
+            let mut payload = json!({ "value": value, "policy": policy });
+            if !next.is_empty() {
+                payload["meta"] = json!(next);
+            }
+            payload
+        }
+        (None, Content::Fields(existing)) => {
+            if detail.type_id != item.type_id {
+                diffs.push(Diff::Type {
+                    from: detail.type_id.clone(),
+                    to: item.type_id.clone(),
+                });
+            }
+            diff_fields(
+                &item.fields,
+                existing
+                    .fields
+                    .iter()
+                    .map(|(key, field)| (key, &field.value)),
+                &mut diffs,
+            );
+            let mut fields = HashMap::new();
+            for (key, value) in &item.fields {
+                let field = match existing.fields.get(key) {
+                    Some(current) => FieldValue {
+                        kind: current.kind,
+                        value: value.clone(),
+                        meta: current.meta.clone(),
+                    },
+                    None => FieldValue {
+                        kind: FieldKind::Text,
+                        value: value.clone(),
+                        meta: None,
+                    },
+                };
+                fields.insert(key.clone(), field);
+            }
+            let payload = EncryptedPayload {
+                v: existing.v,
+                type_id: item.type_id.clone(),
+                fields,
+                extra: existing.extra.clone(),
+            };
+            serde_json::to_value(payload).map_err(|err| err.to_string())?
+        }
+    };
+    let current_tags: BTreeSet<String> = detail
+        .tags
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    for tag in item.tags.difference(&current_tags) {
+        diffs.push(Diff::AddTag(tag.clone()));
+    }
+    for tag in current_tags.difference(&item.tags) {
+        diffs.push(Diff::RemoveTag(tag.clone()));
+    }
+    if diffs.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some((diffs, payload)))
+    }
+}
+
+/// Human-readable plan. Lists field names only; values are never printed.
+pub(crate) fn render_plan(plan: &Plan) -> String {
+    let mut out = String::new();
+    let (mut create, mut update, mut delete) = (0, 0, 0);
+    for step in &plan.steps {
+        match step {
+            Step::Create(item) => {
+                create += 1;
+                match item.generate.as_deref() {
+                    Some(policy) => out.push_str(&format!(
+                        "+ {} ({}, generated by {policy})\n",
+                        item.path, item.type_id
+                    )),
+                    None => out.push_str(&format!("+ {} ({})\n", item.path, item.type_id)),
+                }
+                for key in item.fields.keys() {
+                    out.push_str(&format!("    + field {key}\n"));
+                }
+                for tag in &item.tags {
+                    out.push_str(&format!("    + tag {tag}\n"));
+                }
+            }
+            Step::Update { desired, diffs, .. } => {
+                update += 1;
+                out.push_str(&format!("~ {}\n", desired.path));
+                for diff in diffs {
+                    let line = match diff {
+                        Diff::Type { from, to } => format!("~ type {from} -> {to}"),
+                        Diff::AddField(key) => format!("+ field {key}"),
+                        Diff::ChangeField(key) => format!("~ field {key}"),
+                        Diff::RemoveField(key) => format!("- field {key}"),
+                        Diff::AddTag(tag) => format!("+ tag {tag}"),
+                        Diff::RemoveTag(tag) => format!("- tag {tag}"),
+                    };
+                    out.push_str(&format!("    {line}\n"));
+                }
+            }
+            Step::Delete { path, .. } => {
+                delete += 1;
+                out.push_str(&format!("- {path}\n"));
+            }
+            Step::Conflict { path, reason } => {
+                out.push_str(&format!("! {path}: {reason}\n"));
+            }
+        }
+    }
+    if plan.is_empty() {
+        out.push_str(&format!(
+            "No changes. {} item(s) up to date.\n",
+            plan.unchanged
+        ));
+        return out;
+    }
+    out.push_str(&format!(
+        "\nPlan: {create} to create, {update} to update, {delete} to delete"
+    ));
+    let conflicts = plan.conflicts();
+    if conflicts > 0 {
+        out.push_str(&format!(", {conflicts} conflict(s)"));
+    }
+    out.push_str(".\n");
+    out
+}
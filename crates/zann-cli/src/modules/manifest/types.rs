@@ -0,0 +1,162 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+const DEFAULT_TYPE_ID: &str = "secret";
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    #[serde(default)]
+    vault: Option<String>,
+    #[serde(default)]
+    prefix: Option<String>,
+    #[serde(default)]
+    items: Vec<ManifestFileItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFileItem {
+    path: String,
+    #[serde(default)]
+    type_id: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    fields: BTreeMap<String, serde_yaml::Value>,
+    #[serde(default)]
+    generate: Option<String>,
+}
+
+/// A validated manifest with item paths joined to the prefix.
+#[derive(Debug)]
+pub(crate) struct Manifest {
+    pub vault: Option<String>,
+    pub prefix: Option<String>,
+    pub items: Vec<DesiredItem>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DesiredItem {
+    pub path: String,
+    pub type_id: String,
+    pub tags: BTreeSet<String>,
+    pub fields: BTreeMap<String, String>,
+    /// Secret policy that generates the item's value on the server.
+    pub generate: Option<String>,
+}
+
+pub(crate) fn load_manifest(path: &Path) -> anyhow::Result<Manifest> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("failed to read {}: {err}", path.display()))?;
+    parse_manifest(&contents)
+        .map_err(|err| anyhow::anyhow!("invalid manifest {}: {err}", path.display()))
+}
+
+pub(crate) fn parse_manifest(contents: &str) -> anyhow::Result<Manifest> {
+    let file: ManifestFile = serde_yaml::from_str(contents)?;
+    let prefix = file
+        .prefix
+        .as_deref()
+        .map(|prefix| prefix.trim().trim_matches('/').to_string())
+        .filter(|prefix| !prefix.is_empty());
+    let mut seen = BTreeSet::new();
+    let mut items = Vec::with_capacity(file.items.len());
+    for item in file.items {
+        let relative = item.path.trim().trim_matches('/');
+        if relative.is_empty() {
+            anyhow::bail!("item path is empty");
+        }
+        let path = match prefix.as_deref() {
+            Some(prefix) => format!("{prefix}/{relative}"),
+            None => relative.to_string(),
+        };
+        if !seen.insert(path.clone()) {
+            anyhow::bail!("{path} is declared more than once");
+        }
+        let type_id = item
+            .type_id
+            .map(|type_id| type_id.trim().to_string())
+            .filter(|type_id| !type_id.is_empty())
+            .unwrap_or_else(|| DEFAULT_TYPE_ID.to_string());
+        let generate = item
+            .generate
+            .map(|policy| policy.trim().to_string())
+            .filter(|policy| !policy.is_empty());
+        if generate.is_some() && type_id != DEFAULT_TYPE_ID {
+            anyhow::bail!("{path}: generated items must have type_id {DEFAULT_TYPE_ID}");
+        }
+        let mut fields = BTreeMap::new();
+        for (key, value) in item.fields {
+            let key = key.trim().to_string();
+            if key.is_empty() {
+                anyhow::bail!("{path}: field name is empty");
+            }
+            fields.insert(key.clone(), scalar_to_string(&path, &key, value)?);
+        }
+        if generate.is_none() && fields.is_empty() {
+            anyhow::bail!("{path}: declare fields or a generate policy");
+        }
+        let tags = item
+            .tags
+            .into_iter()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        items.push(DesiredItem {
+            path,
+            type_id,
+            tags,
+            fields,
+            generate,
+        });
+    }
+    Ok(Manifest {
+        vault: file.vault.filter(|vault| !vault.trim().is_empty()),
+        prefix,
+        items,
+    })
+}
+
+fn scalar_to_string(path: &str, key: &str, value: serde_yaml::Value) -> anyhow::Result<String> {
+    match value {
+        serde_yaml::Value::String(value) => Ok(value),
+        serde_yaml::Value::Number(value) => Ok(value.to_string()),
+        serde_yaml::Value::Bool(value) => Ok(value.to_string()),
+        _ => anyhow::bail!("{path}: field {key} must be a string, number or boolean"),
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ItemsResponse {
+    pub items: Vec<ItemSummary>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct ItemSummary {
+    pub id: String,
+    pub path: String,
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+pub(crate) struct ItemDetail {
+    pub id: String,
+    pub type_id: String,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub payload: Option<JsonValue>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct BatchEnsureResult {
+    pub path: String,
+    pub status: String,
+    #[serde(default)]
+    pub error: Option<JsonValue>,
+}
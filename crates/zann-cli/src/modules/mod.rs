@@ -1,3 +1,10 @@
+pub(crate) mod agent;
 pub(crate) mod auth;
+pub(crate) mod import;
+pub(crate) mod manifest;
+pub(crate) mod pki;
+pub(crate) mod service_accounts;
 pub(crate) mod shared;
+pub(crate) mod ssh;
 pub(crate) mod system;
+pub(crate) mod vaults;
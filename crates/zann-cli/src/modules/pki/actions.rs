@@ -0,0 +1,105 @@
+use std::fs;
+
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::cli_args::*;
+use crate::modules::shared::{resolve_vault_arg, write_atomic, write_atomic_private};
+use crate::modules::system::http::send_request;
+use crate::modules::system::CommandContext;
+
+#[derive(Deserialize)]
+struct IssueResponse {
+    serial: String,
+    certificate: String,
+    private_key: String,
+    ca_chain: Vec<String>,
+    not_after: String,
+}
+
+#[derive(Deserialize)]
+struct ChainResponse {
+    ca_chain: Vec<String>,
+}
+
+pub(crate) async fn handle_pki_command(
+    args: PkiArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<()> {
+    match args.command {
+        PkiCommand::Issue(args) => handle_issue(args, ctx).await,
+        PkiCommand::Ca(args) => {
+            let vault = resolve_vault_arg(args.vault, ctx).await?;
+            let url = format!("{}/ca", pki_base(ctx, &vault));
+            let response = send_request(ctx, Method::GET, url, None).await?;
+            let chain: ChainResponse = parse_response(response).await?;
+            print!("{}", pem_bundle(&chain.ca_chain));
+            Ok(())
+        }
+    }
+}
+
+async fn handle_issue(args: PkiIssueArgs, ctx: &mut CommandContext<'_>) -> anyhow::Result<()> {
+    let vault = resolve_vault_arg(args.vault, ctx).await?;
+    let mut payload = json!({
+        "role": args.role,
+        "common_name": args.common_name,
+    });
+    if !args.alt_names.is_empty() {
+        payload["alt_names"] = json!(args.alt_names);
+    }
+    if !args.ip_sans.is_empty() {
+        payload["ip_sans"] = json!(args.ip_sans);
+    }
+    if let Some(ttl) = args.ttl {
+        payload["ttl"] = json!(ttl);
+    }
+    let url = format!("{}/issue", pki_base(ctx, &vault));
+    let response = send_request(ctx, Method::POST, url, Some(payload)).await?;
+    let issued: IssueResponse = parse_response(response).await?;
+
+    fs::create_dir_all(&args.out)
+        .map_err(|err| anyhow::anyhow!("failed to create {}: {err}", args.out.display()))?;
+    // The key goes first so a reader never sees a new certificate next to a
+    // stale key.
+    write_atomic_private(&args.out.join("key.pem"), pem_bundle(&[issued.private_key]))?;
+    write_atomic(
+        &args.out.join("cert.pem"),
+        pem_bundle(&[issued.certificate]),
+    )?;
+    write_atomic(&args.out.join("ca.pem"), pem_bundle(&issued.ca_chain))?;
+    println!(
+        "Wrote cert.pem, key.pem and ca.pem to {} (serial {}, valid until {})",
+        args.out.display(),
+        issued.serial,
+        issued.not_after
+    );
+    Ok(())
+}
+
+fn pki_base(ctx: &CommandContext<'_>, vault: &str) -> String {
+    format!(
+        "{}/v1/vaults/{}/pki",
+        ctx.addr.trim_end_matches('/'),
+        urlencoding::encode(vault)
+    )
+}
+
+fn pem_bundle(blocks: &[String]) -> String {
+    blocks
+        .iter()
+        .map(|block| format!("{}\n", block.trim()))
+        .collect()
+}
+
+async fn parse_response<T: for<'de> Deserialize<'de>>(
+    response: reqwest::Response,
+) -> anyhow::Result<T> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Request failed: {status} {body}");
+    }
+    Ok(response.json().await?)
+}
@@ -0,0 +1,44 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct PkiArgs {
+    #[command(subcommand)]
+    pub command: PkiCommand,
+}
+
+#[derive(Subcommand)]
+pub enum PkiCommand {
+    #[command(about = "Issue a TLS certificate and write cert.pem, key.pem and ca.pem")]
+    Issue(PkiIssueArgs),
+    #[command(about = "Print the vault CA chain in PEM format")]
+    Ca(PkiCaArgs),
+}
+
+#[derive(Args)]
+pub struct PkiIssueArgs {
+    #[arg(long, help = "Vault name or ID")]
+    pub vault: Option<String>,
+    #[arg(long, help = "Issuing role configured on the vault")]
+    pub role: String,
+    #[arg(long = "cn", help = "Common name (also added as a DNS SAN)")]
+    pub common_name: String,
+    #[arg(
+        long = "alt-name",
+        value_delimiter = ',',
+        help = "Additional DNS name (repeatable)"
+    )]
+    pub alt_names: Vec<String>,
+    #[arg(long = "ip", value_delimiter = ',', help = "IP SAN (repeatable)")]
+    pub ip_sans: Vec<String>,
+    #[arg(long, help = "Certificate lifetime (e.g. 24h, 30d)")]
+    pub ttl: Option<String>,
+    #[arg(long, default_value = ".", help = "Output directory")]
+    pub out: PathBuf,
+}
+
+#[derive(Args)]
+pub struct PkiCaArgs {
+    #[arg(long, help = "Vault name or ID")]
+    pub vault: Option<String>,
+}
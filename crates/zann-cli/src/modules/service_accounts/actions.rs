@@ -0,0 +1,52 @@
+use reqwest::Method;
+use serde_json::json;
+
+use crate::cli_args::*;
+use crate::modules::shared::resolve_vault_arg;
+use crate::modules::system::http::{
+    append_params, build_params, opt_param, print_json_response, send_request,
+};
+use crate::modules::system::CommandContext;
+
+pub(crate) async fn handle_service_account_command(
+    args: ServiceAccountArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<()> {
+    let base = format!("{}/v1/service-accounts", ctx.addr.trim_end_matches('/'));
+    let response = match args.command {
+        ServiceAccountCommand::Create(args) => {
+            let vault = resolve_vault_arg(args.vault, ctx).await?;
+            let mut payload = json!({
+                "name": args.name,
+                "vault": vault,
+                "prefixes": args.prefixes,
+                "ops": args.ops,
+            });
+            if let Some(ttl) = args.ttl {
+                payload["ttl"] = json!(ttl);
+            }
+            if !args.allowed_ips.is_empty() {
+                payload["allowed_ips"] = json!(args.allowed_ips);
+            }
+            send_request(ctx, Method::POST, base, Some(payload)).await?
+        }
+        ServiceAccountCommand::List(args) => {
+            let mut url = base;
+            append_params(&mut url, build_params([opt_param("vault", args.vault)]));
+            send_request(ctx, Method::GET, url, None).await?
+        }
+        ServiceAccountCommand::Get(args) => {
+            let url = format!("{base}/{}", urlencoding::encode(&args.id));
+            send_request(ctx, Method::GET, url, None).await?
+        }
+        ServiceAccountCommand::Rotate(args) => {
+            let url = format!("{base}/{}/rotate", urlencoding::encode(&args.id));
+            send_request(ctx, Method::POST, url, None).await?
+        }
+        ServiceAccountCommand::Revoke(args) => {
+            let url = format!("{base}/{}", urlencoding::encode(&args.id));
+            send_request(ctx, Method::DELETE, url, None).await?
+        }
+    };
+    print_json_response(response).await
+}
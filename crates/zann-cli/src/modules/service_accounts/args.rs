@@ -0,0 +1,64 @@
+use clap::{Args, Subcommand};
+
+#[derive(Args)]
+pub struct ServiceAccountArgs {
+    #[command(subcommand)]
+    pub command: ServiceAccountCommand,
+}
+
+#[derive(Subcommand)]
+pub enum ServiceAccountCommand {
+    #[command(about = "Create a service account and print its token")]
+    Create(ServiceAccountCreateArgs),
+    #[command(about = "List service accounts you own or that are scoped to a vault")]
+    List(ServiceAccountListArgs),
+    #[command(about = "Show a service account")]
+    Get(ServiceAccountIdArgs),
+    #[command(about = "Issue a new token secret, keeping the service account ID")]
+    Rotate(ServiceAccountIdArgs),
+    #[command(about = "Revoke a service account")]
+    Revoke(ServiceAccountIdArgs),
+}
+
+#[derive(Args)]
+pub struct ServiceAccountCreateArgs {
+    #[arg(help = "Service account name")]
+    pub name: String,
+    #[arg(long, help = "Vault name or ID")]
+    pub vault: Option<String>,
+    #[arg(
+        long = "prefix",
+        default_value = "/",
+        help = "Path prefix the token is limited to (repeatable; '/' for the whole vault)"
+    )]
+    pub prefixes: Vec<String>,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "read",
+        help = "Comma-separated ops (read, write, read_history, read_previous, transit_encrypt, transit_decrypt, ssh_sign, pki_issue)"
+    )]
+    pub ops: Vec<String>,
+    #[arg(long, help = "Token lifetime (e.g. 12h, 30d)")]
+    pub ttl: Option<String>,
+    #[arg(
+        long = "allowed-ip",
+        help = "Client IP or CIDR range allowed to use the token (repeatable)"
+    )]
+    pub allowed_ips: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct ServiceAccountListArgs {
+    #[arg(
+        long,
+        help = "List every service account scoped to this vault (name or ID)"
+    )]
+    pub vault: Option<String>,
+}
+
+#[derive(Args)]
+pub struct ServiceAccountIdArgs {
+    #[arg(help = "Service account ID")]
+    pub id: String,
+}
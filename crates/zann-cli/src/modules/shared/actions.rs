@@ -117,6 +117,7 @@ pub(crate) async fn handle_create(
         &args.path,
         &args.type_id,
         payload,
+        args.expires.as_deref(),
     )
     .await?;
 
@@ -141,9 +142,13 @@ pub(crate) async fn handle_update(
     .map_err(|_| secret_not_found_error(&path))?;
 
     let has_payload_change = args.stdin || !args.field.is_empty();
-    if !has_payload_change && args.new_path.is_none() && args.type_id.is_none() {
+    if !has_payload_change
+        && args.new_path.is_none()
+        && args.type_id.is_none()
+        && args.expires.is_none()
+    {
         anyhow::bail!(
-            "Nothing to update: pass --field, --stdin, --new-path or --type-id"
+            "Nothing to update: pass --field, --stdin, --new-path, --type-id or --expires"
         );
     }
     let payload = if has_payload_change {
@@ -164,6 +169,7 @@ pub(crate) async fn handle_update(
         &item_id.to_string(),
         payload,
         args.new_path.as_deref(),
+        args.expires.as_deref(),
     )
     .await?;
 
@@ -182,6 +188,7 @@ pub(crate) async fn handle_set(args: SetArgs, ctx: &mut CommandContext<'_>) -> a
             &vault_id,
             &path,
             &args.value,
+            args.expires.as_deref(),
         )
         .await?;
         println!("Updated: {} field '{}'", path, args.key);
@@ -232,6 +239,7 @@ pub(crate) async fn handle_set(args: SetArgs, ctx: &mut CommandContext<'_>) -> a
                 &item_id.to_string(),
                 Some(serde_json::to_value(payload)?),
                 None,
+                args.expires.as_deref(),
             )
             .await?;
             println!(
@@ -255,6 +263,7 @@ pub(crate) async fn handle_set(args: SetArgs, ctx: &mut CommandContext<'_>) -> a
                 &path,
                 "secret",
                 serde_json::to_value(payload)?,
+                args.expires.as_deref(),
             )
             .await?;
             println!(
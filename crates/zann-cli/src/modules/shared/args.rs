@@ -41,6 +41,11 @@ pub struct RenderArgs {
     pub template: PathBuf,
     #[arg(long, help = "Output file (defaults to stdout)")]
     pub out: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "Fail on any missing item or field, even behind a default or an if"
+    )]
+    pub strict: bool,
 }
 
 #[derive(Args)]
@@ -71,6 +76,12 @@ pub struct CreateArgs {
     pub field: Vec<String>,
     #[arg(long, help = "Read payload as JSON from stdin")]
     pub stdin: bool,
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Expiry date (YYYY-MM-DD or RFC 3339); \"never\" clears it"
+    )]
+    pub expires: Option<String>,
 }
 
 #[derive(Args)]
@@ -93,6 +104,12 @@ pub struct UpdateArgs {
     pub field: Vec<String>,
     #[arg(long, help = "Read payload as JSON from stdin")]
     pub stdin: bool,
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Expiry date (YYYY-MM-DD or RFC 3339); \"never\" clears it"
+    )]
+    pub expires: Option<String>,
 }
 
 #[derive(Args)]
@@ -105,6 +122,12 @@ pub struct SetArgs {
     pub value: String,
     #[arg(long, help = "Vault name or ID")]
     pub vault: Option<String>,
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Expiry date (YYYY-MM-DD or RFC 3339); \"never\" clears it"
+    )]
+    pub expires: Option<String>,
 }
 
 #[derive(Args)]
@@ -1,11 +1,15 @@
 use uuid::Uuid;
+use zann_core::EncryptedPayload;
 
 use crate::modules::shared::{
     cursor_allows, encode_cursor, normalize_prefix, parse_cursor, parse_item_timestamp,
-    prefix_match, ItemSummaryResponse, ItemsResponse, SharedItemResponse, SharedItemsResponse,
+    payload_or_error, prefix_match, ItemSummaryResponse, ItemsResponse, SharedItemResponse,
+    SharedItemsResponse,
 };
 use crate::modules::system::http::{append_params, build_params, opt_param};
 
+const PAYLOAD_FETCH_LIMIT: i64 = 200;
+
 pub(crate) async fn fetch_shared_items(
     client: &reqwest::Client,
     addr: &str,
@@ -82,6 +86,66 @@ pub(crate) async fn fetch_shared_item(
     Ok(response.json::<SharedItemResponse>().await?)
 }
 
+/// Payload of the item at `path`, or `None` when there is no such item.
+pub(crate) async fn fetch_shared_payload(
+    client: &reqwest::Client,
+    addr: &str,
+    access_token: &str,
+    vault_id: &str,
+    path: &str,
+) -> anyhow::Result<Option<EncryptedPayload>> {
+    let response = fetch_shared_items(
+        client,
+        addr,
+        access_token,
+        vault_id,
+        Some(path),
+        Some(PAYLOAD_FETCH_LIMIT),
+        None,
+    )
+    .await?;
+    let wanted = path.trim().trim_matches('/');
+    response
+        .items
+        .iter()
+        .find(|item| item.path.trim().trim_matches('/') == wanted)
+        .map(payload_or_error)
+        .transpose()
+}
+
+/// Path and payload of every item under `prefix`, sorted by path.
+pub(crate) async fn fetch_shared_payloads(
+    client: &reqwest::Client,
+    addr: &str,
+    access_token: &str,
+    vault_id: &str,
+    prefix: &str,
+) -> anyhow::Result<Vec<(String, EncryptedPayload)>> {
+    let mut entries = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let response = fetch_shared_items(
+            client,
+            addr,
+            access_token,
+            vault_id,
+            Some(prefix),
+            Some(PAYLOAD_FETCH_LIMIT),
+            cursor.as_deref(),
+        )
+        .await?;
+        for item in &response.items {
+            entries.push((item.path.clone(), payload_or_error(item)?));
+        }
+        cursor = response.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    entries.sort_by(|left, right| left.0.trim_matches('/').cmp(right.0.trim_matches('/')));
+    Ok(entries)
+}
+
 async fn fetch_item_summaries(
     client: &reqwest::Client,
     addr: &str,
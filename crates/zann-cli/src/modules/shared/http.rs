@@ -17,6 +17,7 @@ pub(crate) async fn fetch_vaults(
     Ok(response.json::<VaultListResponse>().await?)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn create_shared_item(
     client: &reqwest::Client,
     addr: &str,
@@ -25,14 +26,18 @@ pub(crate) async fn create_shared_item(
     path: &str,
     type_id: &str,
     payload: JsonValue,
+    expires_at: Option<&str>,
 ) -> anyhow::Result<SharedItemResponse> {
     let url = format!("{}/v1/shared/items", addr.trim_end_matches('/'));
-    let body = serde_json::json!({
+    let mut body = serde_json::json!({
         "vault_id": vault_id,
         "path": path,
         "type_id": type_id,
         "payload": payload,
     });
+    if let Some(expires_at) = expires_at {
+        body["expires_at"] = expires_value(expires_at);
+    }
     let response = client
         .post(url)
         .bearer_auth(access_token)
@@ -54,6 +59,7 @@ pub(crate) async fn update_shared_item(
     item_id: &str,
     payload: Option<JsonValue>,
     new_path: Option<&str>,
+    expires_at: Option<&str>,
 ) -> anyhow::Result<SharedItemResponse> {
     let url = format!("{}/v1/shared/items/{}", addr.trim_end_matches('/'), item_id);
     let mut body = serde_json::Map::new();
@@ -63,6 +69,9 @@ pub(crate) async fn update_shared_item(
     if let Some(path) = new_path {
         body.insert("path".to_string(), JsonValue::String(path.to_string()));
     }
+    if let Some(expires_at) = expires_at {
+        body.insert("expires_at".to_string(), expires_value(expires_at));
+    }
     let body = JsonValue::Object(body);
     let response = client
         .put(url)
@@ -85,6 +94,7 @@ pub(crate) async fn set_secret_value(
     vault_id: &str,
     path: &str,
     value: &str,
+    expires_at: Option<&str>,
 ) -> anyhow::Result<SecretResponse> {
     let url = format!(
         "{}/v1/vaults/{}/secrets/{}",
@@ -92,9 +102,12 @@ pub(crate) async fn set_secret_value(
         vault_id,
         path.trim_matches('/')
     );
-    let body = serde_json::json!({
+    let mut body = serde_json::json!({
         "value": value,
     });
+    if let Some(expires_at) = expires_at {
+        body["expires_at"] = expires_value(expires_at);
+    }
     let response = client
         .put(url)
         .bearer_auth(access_token)
@@ -124,3 +137,12 @@ pub(crate) async fn delete_shared_item(
     }
     Ok(())
 }
+
+/// `--expires never` clears the date; anything else is sent as given.
+fn expires_value(expires_at: &str) -> JsonValue {
+    if expires_at.trim().eq_ignore_ascii_case("never") {
+        JsonValue::Null
+    } else {
+        JsonValue::String(expires_at.trim().to_string())
+    }
+}
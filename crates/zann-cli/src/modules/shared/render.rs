@@ -1,14 +1,19 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use zann_core::EncryptedPayload;
 
 use crate::cli_args::RenderArgs;
 use crate::modules::shared::render_fs::{read_template_source, write_render_output};
+use crate::modules::shared::template::{
+    collect_references, parse_template_nodes, render_nodes, TemplateData,
+};
+#[cfg(test)]
 use crate::modules::shared::TemplateToken;
 use crate::modules::shared::{
-    fetch_shared_item, payload_or_error, resolve_path_for_context, resolve_shared_item_id,
+    fetch_shared_payload, fetch_shared_payloads, parse_selector_if_present,
+    resolve_vault_for_context,
 };
-use crate::modules::shared::{parse_template, parse_template_placeholder, secret_not_found_error};
 use crate::modules::system::CommandContext;
 
 pub(crate) async fn render_shared_template(
@@ -16,24 +21,104 @@ pub(crate) async fn render_shared_template(
     ctx: &mut CommandContext<'_>,
 ) -> anyhow::Result<()> {
     let template = read_template_source(args.template.as_path())?;
-    let tokens = parse_template(&template)?;
-    let mut cache: HashMap<(String, String), EncryptedPayload> = HashMap::new();
-    let mut out = String::new();
+    let out = render_template(
+        &template,
+        template_dir(args.template.as_path()),
+        args.vault.as_deref(),
+        args.strict,
+        ctx,
+    )
+    .await?;
+    write_render_output(args.out.as_deref(), &out)?;
+    Ok(())
+}
 
-    for token in tokens {
-        match token {
-            TemplateToken::Text(text) => out.push_str(&text),
-            TemplateToken::Placeholder(expr) => {
-                let value =
-                    resolve_template_placeholder(&expr, args.vault.as_deref(), ctx, &mut cache)
+/// Directory that includes of the template at `path` are relative to.
+pub(crate) fn template_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if path != Path::new("-") && !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Renders `template`, fetching each referenced item and range once.
+pub(crate) async fn render_template(
+    template: &str,
+    base_dir: &Path,
+    vault: Option<&str>,
+    strict: bool,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<String> {
+    let nodes = parse_template_nodes(template, base_dir)?;
+    let (mut items, mut ranges) = (Vec::new(), Vec::new());
+    collect_references(&nodes, &mut items, &mut ranges);
+
+    let mut default_vault: Option<String> = None;
+    let mut cache: HashMap<(String, String), Option<EncryptedPayload>> = HashMap::new();
+    let mut data = TemplateData::default();
+
+    for prefix in ranges {
+        let (vault_id, path) = resolve_selector(&prefix, vault, &mut default_vault, ctx).await?;
+        let entries =
+            fetch_shared_payloads(ctx.client, ctx.addr, &ctx.access_token, &vault_id, &path)
+                .await?;
+        for (item_path, payload) in &entries {
+            cache.insert(
+                (vault_id.clone(), item_key(item_path)),
+                Some(payload.clone()),
+            );
+        }
+        data.ranges.insert(prefix, entries);
+    }
+    for selector in items {
+        let (vault_id, path) = resolve_selector(&selector, vault, &mut default_vault, ctx).await?;
+        let key = (vault_id, item_key(&path));
+        let payload = match cache.get(&key) {
+            Some(payload) => payload.clone(),
+            None => {
+                let payload =
+                    fetch_shared_payload(ctx.client, ctx.addr, &ctx.access_token, &key.0, &path)
                         .await?;
-                out.push_str(&value);
+                cache.insert(key, payload.clone());
+                payload
             }
-        }
+        };
+        data.items.insert(selector, payload);
     }
+    render_nodes(&nodes, &data, strict)
+}
 
-    write_render_output(args.out.as_deref(), &out)?;
-    Ok(())
+/// Splits `vault:path` selectors; plain paths use `vault`, or the context
+/// vault, which is looked up at most once per render.
+async fn resolve_selector(
+    selector: &str,
+    vault: Option<&str>,
+    default_vault: &mut Option<String>,
+    ctx: &CommandContext<'_>,
+) -> anyhow::Result<(String, String)> {
+    if let Some(selector) = parse_selector_if_present(selector)? {
+        return Ok((selector.vault, selector.path));
+    }
+    let vault_id = match default_vault {
+        Some(vault_id) => vault_id.clone(),
+        None => {
+            let vault_id = resolve_vault_for_context(
+                vault.map(|value| value.to_string()),
+                ctx.context_name.as_deref(),
+                ctx.config,
+                ctx.client,
+                ctx.addr,
+                &ctx.access_token,
+            )
+            .await?;
+            default_vault.insert(vault_id).clone()
+        }
+    };
+    Ok((vault_id, selector.to_string()))
+}
+
+fn item_key(path: &str) -> String {
+    path.trim().trim_matches('/').to_string()
 }
 
 #[cfg(test)]
@@ -54,56 +139,10 @@ where
     Ok(out)
 }
 
-async fn resolve_template_placeholder(
-    expr: &str,
-    vault: Option<&str>,
-    ctx: &mut CommandContext<'_>,
-    cache: &mut HashMap<(String, String), EncryptedPayload>,
-) -> anyhow::Result<String> {
-    let (selector, field) = parse_template_placeholder(expr)?;
-
-    let (vault_id, path) = resolve_path_for_context(
-        &selector,
-        vault.map(|value| value.to_string()),
-        ctx.context_name.as_deref(),
-        ctx.config,
-        ctx.client,
-        ctx.addr,
-        &ctx.access_token,
-    )
-    .await?;
-
-    let payload = if let Some(payload) = cache.get(&(vault_id.clone(), path.clone())) {
-        payload
-    } else {
-        let item_id = resolve_shared_item_id(
-            ctx.client,
-            ctx.addr,
-            &ctx.access_token,
-            &vault_id,
-            None,
-            Some(&path),
-        )
-        .await
-        .map_err(|_| secret_not_found_error(&path))?;
-        let item =
-            fetch_shared_item(ctx.client, ctx.addr, &ctx.access_token, &vault_id, item_id).await?;
-        let payload = payload_or_error(&item)?;
-        cache.insert((vault_id.clone(), path.clone()), payload);
-        cache
-            .get(&(vault_id.clone(), path.clone()))
-            .ok_or_else(|| anyhow::anyhow!("failed to cache payload for {}", path))?
-    };
-
-    let value = crate::find_field(payload, &field)
-        .map(|item| item.value.clone())
-        .ok_or_else(|| anyhow::anyhow!("field '{}' not found in {}", field, path))?;
-    Ok(value)
-}
-
 #[cfg(test)]
 mod tests {
-    use super::{parse_template, render_template_tokens_sync};
+    use super::render_template_tokens_sync;
+    use crate::modules::shared::parse_template;
     use crate::modules::shared::TemplateToken;
     use std::collections::HashMap;
 
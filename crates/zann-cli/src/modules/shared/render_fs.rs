@@ -4,6 +4,7 @@ use std::path::{Component, Path, PathBuf};
 
 use crate::modules::shared::{fetch_shared_items, payload_or_error};
 
+/// Writes one file per item under `out`. Returns how many files were written.
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn materialize_shared(
     client: &reqwest::Client,
@@ -16,9 +17,10 @@ pub(crate) async fn materialize_shared(
     skip_unchanged: bool,
     atomic: bool,
     limit: i64,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<usize> {
     fs::create_dir_all(out)?;
     let mut cursor: Option<String> = None;
+    let mut written = 0;
     loop {
         let response = fetch_shared_items(
             client,
@@ -53,13 +55,14 @@ pub(crate) async fn materialize_shared(
             } else {
                 fs::write(&target, contents)?;
             }
+            written += 1;
         }
         cursor = response.next_cursor;
         if cursor.is_none() {
             break;
         }
     }
-    Ok(())
+    Ok(written)
 }
 
 pub(crate) fn read_template_source(path: &Path) -> anyhow::Result<String> {
@@ -102,7 +105,18 @@ fn is_same_contents(path: &Path, contents: &str) -> anyhow::Result<bool> {
     Ok(existing == contents.as_bytes())
 }
 
-fn write_atomic(path: &Path, contents: String) -> anyhow::Result<()> {
+pub(crate) fn write_atomic(path: &Path, contents: String) -> anyhow::Result<()> {
+    write_atomic_with(path, contents, false)
+}
+
+/// Like [`write_atomic`], but the file is only readable by its owner. The
+/// mode is set when the temporary file is created, so key material is never
+/// briefly readable by others.
+pub(crate) fn write_atomic_private(path: &Path, contents: String) -> anyhow::Result<()> {
+    write_atomic_with(path, contents, true)
+}
+
+fn write_atomic_with(path: &Path, contents: String, private: bool) -> anyhow::Result<()> {
     let parent = path
         .parent()
         .ok_or_else(|| anyhow::anyhow!("invalid output path"))?;
@@ -112,7 +126,7 @@ fn write_atomic(path: &Path, contents: String) -> anyhow::Result<()> {
         .ok_or_else(|| anyhow::anyhow!("invalid output path"))?;
     let tmp_name = format!("{}.tmp.{}", file_name, rand::random::<u64>());
     let tmp_path = parent.join(tmp_name);
-    fs::write(&tmp_path, contents)?;
+    write_new(&tmp_path, contents.as_bytes(), private)?;
     if path.exists() {
         fs::remove_file(path)?;
     }
@@ -120,6 +134,23 @@ fn write_atomic(path: &Path, contents: String) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
+fn write_new(path: &Path, contents: &[u8], private: bool) -> io::Result<()> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    if private {
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_new(path: &Path, contents: &[u8], _private: bool) -> io::Result<()> {
+    fs::write(path, contents)
+}
+
 pub(crate) fn normalize_output_path(path: &str) -> anyhow::Result<PathBuf> {
     let trimmed = path.trim();
     let trimmed = trimmed.trim_matches('/');
@@ -0,0 +1,700 @@
+//! Template language for `zann render` and the agent.
+//!
+//! Templates are parsed into a tree before anything is fetched, so every item
+//! and range can be looked up once and the tree rendered without I/O.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use zann_core::EncryptedPayload;
+
+use crate::modules::shared::{
+    normalize_prefix, parse_template, parse_template_placeholder, secret_not_found_error,
+    TemplateToken,
+};
+
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+pub(crate) enum Node {
+    Text(String),
+    Value(ValueExpr),
+    If {
+        cond: ValueExpr,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Range {
+        prefix: String,
+        body: Vec<Node>,
+    },
+}
+
+pub(crate) struct ValueExpr {
+    reference: Reference,
+    filters: Vec<Filter>,
+}
+
+enum Reference {
+    /// `path#field` or `vault:path#field`; resolved before rendering.
+    Item { selector: String, field: String },
+    /// `.#field` inside a range.
+    CurrentField(String),
+    /// `.path` inside a range: the full item path.
+    CurrentPath,
+    /// `.name` inside a range: the item path below the range prefix.
+    CurrentName,
+}
+
+enum Filter {
+    Default(String),
+    Base64,
+    B64Decode,
+    Json,
+    YamlQuote,
+    UrlEncode,
+    Trim,
+}
+
+/// Items and ranges a template refers to, keyed as written in the template.
+#[derive(Default)]
+pub(crate) struct TemplateData {
+    /// Payload per item selector; `None` when the item does not exist.
+    pub items: HashMap<String, Option<EncryptedPayload>>,
+    /// Items per range prefix, sorted by path.
+    pub ranges: HashMap<String, Vec<(String, EncryptedPayload)>>,
+}
+
+/// Parses `template`, resolving includes relative to `base_dir`.
+pub(crate) fn parse_template_nodes(template: &str, base_dir: &Path) -> anyhow::Result<Vec<Node>> {
+    let mut stack = Vec::new();
+    let tokens = expand_includes(template, base_dir, &mut stack)?;
+    let mut parser = Parser {
+        tokens: tokens.into_iter(),
+        range_depth: 0,
+    };
+    let (nodes, end) = parser.parse_block()?;
+    match end {
+        BlockEnd::Eof => Ok(nodes),
+        BlockEnd::Else => anyhow::bail!("template has 'else' without 'if'"),
+        BlockEnd::End => anyhow::bail!("template has 'end' without 'if' or 'range'"),
+    }
+}
+
+/// Selectors and range prefixes used by `nodes`, in template order.
+pub(crate) fn collect_references(
+    nodes: &[Node],
+    items: &mut Vec<String>,
+    ranges: &mut Vec<String>,
+) {
+    fn push_unique(list: &mut Vec<String>, value: &str) {
+        if !list.iter().any(|existing| existing == value) {
+            list.push(value.to_string());
+        }
+    }
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Value(expr) => {
+                if let Reference::Item { selector, .. } = &expr.reference {
+                    push_unique(items, selector);
+                }
+            }
+            Node::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                if let Reference::Item { selector, .. } = &cond.reference {
+                    push_unique(items, selector);
+                }
+                collect_references(then, items, ranges);
+                collect_references(otherwise, items, ranges);
+            }
+            Node::Range { prefix, body } => {
+                push_unique(ranges, prefix);
+                collect_references(body, items, ranges);
+            }
+        }
+    }
+}
+
+/// Renders `nodes` from prefetched `data`. A missing field is an error unless
+/// a `default` covers it or an `if` tests it; with `strict`, it always is.
+pub(crate) fn render_nodes(
+    nodes: &[Node],
+    data: &TemplateData,
+    strict: bool,
+) -> anyhow::Result<String> {
+    let mut renderer = Renderer {
+        data,
+        strict,
+        scope: Vec::new(),
+    };
+    let mut out = String::new();
+    renderer.render(nodes, &mut out)?;
+    Ok(out)
+}
+
+/// Reads `template` and every template it includes into one token stream.
+fn expand_includes(
+    template: &str,
+    base_dir: &Path,
+    stack: &mut Vec<PathBuf>,
+) -> anyhow::Result<Vec<TemplateToken>> {
+    let mut tokens = Vec::new();
+    for token in trim_standalone_tags(parse_template(template)?) {
+        let TemplateToken::Placeholder(expr) = &token else {
+            tokens.push(token);
+            continue;
+        };
+        let Some(target) = keyword_arg(expr, "include") else {
+            tokens.push(token);
+            continue;
+        };
+        let target = parse_string_arg(target)?;
+        let path = base_dir.join(&target);
+        let canonical = path
+            .canonicalize()
+            .map_err(|err| anyhow::anyhow!("failed to read include {}: {err}", path.display()))?;
+        if stack.contains(&canonical) {
+            anyhow::bail!("template include cycle at {}", path.display());
+        }
+        if stack.len() >= MAX_INCLUDE_DEPTH {
+            anyhow::bail!("template includes nested too deeply at {}", path.display());
+        }
+        let source = std::fs::read_to_string(&canonical)
+            .map_err(|err| anyhow::anyhow!("failed to read include {}: {err}", path.display()))?;
+        let include_dir = canonical.parent().unwrap_or(base_dir).to_path_buf();
+        stack.push(canonical);
+        tokens.extend(expand_includes(&source, &include_dir, stack)?);
+        stack.pop();
+    }
+    Ok(tokens)
+}
+
+/// Drops the line of a block tag (`if`, `else`, `end`, `range`, `include`)
+/// when the tag is the only thing on it, so blocks leave no blank lines.
+fn trim_standalone_tags(tokens: Vec<TemplateToken>) -> Vec<TemplateToken> {
+    let is_tag = |token: &TemplateToken| matches!(token, TemplateToken::Placeholder(expr) if is_block_tag(expr));
+    // Bytes to drop from the start and end of each text token.
+    let mut cut_start = vec![0usize; tokens.len()];
+    let mut cut_end = vec![0usize; tokens.len()];
+    for index in 0..tokens.len() {
+        if !is_tag(&tokens[index]) {
+            continue;
+        }
+        let before = match index.checked_sub(1).map(|prev| &tokens[prev]) {
+            None => Some(None),
+            Some(TemplateToken::Text(text)) => match text.rfind('\n') {
+                Some(pos) if text[pos + 1..].trim().is_empty() => Some(Some(text.len() - pos - 1)),
+                None if index == 1 && text.trim().is_empty() => Some(Some(text.len())),
+                _ => None,
+            },
+            Some(TemplateToken::Placeholder(_)) => None,
+        };
+        let after = match tokens.get(index + 1) {
+            None => Some(None),
+            Some(TemplateToken::Text(text)) => match text.find('\n') {
+                Some(pos) if text[..pos].trim().is_empty() => Some(Some(pos + 1)),
+                None if index + 2 == tokens.len() && text.trim().is_empty() => {
+                    Some(Some(text.len()))
+                }
+                _ => None,
+            },
+            Some(TemplateToken::Placeholder(_)) => None,
+        };
+        if let (Some(before), Some(after)) = (before, after) {
+            if let Some(len) = before {
+                cut_end[index - 1] = len;
+            }
+            if let Some(len) = after {
+                cut_start[index + 1] = len;
+            }
+        }
+    }
+    tokens
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, token)| match token {
+            TemplateToken::Text(text) => {
+                let end = text.len() - cut_end[index];
+                let start = cut_start[index].min(end);
+                let text = &text[start..end];
+                (!text.is_empty()).then(|| TemplateToken::Text(text.to_string()))
+            }
+            placeholder => Some(placeholder),
+        })
+        .collect()
+}
+
+fn is_block_tag(expr: &str) -> bool {
+    expr == "else"
+        || expr == "end"
+        || keyword_arg(expr, "if").is_some()
+        || keyword_arg(expr, "range").is_some()
+        || keyword_arg(expr, "include").is_some()
+}
+
+/// The argument of `keyword arg`, if `expr` starts with `keyword`.
+fn keyword_arg<'a>(expr: &'a str, keyword: &str) -> Option<&'a str> {
+    let rest = expr.strip_prefix(keyword)?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(rest.trim())
+}
+
+enum BlockEnd {
+    Eof,
+    Else,
+    End,
+}
+
+struct Parser {
+    tokens: std::vec::IntoIter<TemplateToken>,
+    range_depth: usize,
+}
+
+impl Parser {
+    fn parse_block(&mut self) -> anyhow::Result<(Vec<Node>, BlockEnd)> {
+        let mut nodes = Vec::new();
+        while let Some(token) = self.tokens.next() {
+            let expr = match token {
+                TemplateToken::Text(text) => {
+                    nodes.push(Node::Text(text));
+                    continue;
+                }
+                TemplateToken::Placeholder(expr) => expr,
+            };
+            if expr == "else" {
+                return Ok((nodes, BlockEnd::Else));
+            }
+            if expr == "end" {
+                return Ok((nodes, BlockEnd::End));
+            }
+            if let Some(cond) = keyword_arg(&expr, "if") {
+                let (negate, cond) = match keyword_arg(cond, "not") {
+                    Some(cond) => (true, cond),
+                    None => (false, cond),
+                };
+                let cond = self.parse_value(cond)?;
+                let (then, end) = self.parse_block()?;
+                let otherwise = match end {
+                    BlockEnd::End => Vec::new(),
+                    BlockEnd::Else => match self.parse_block()? {
+                        (otherwise, BlockEnd::End) => otherwise,
+                        (_, BlockEnd::Else) => anyhow::bail!("template 'if' has two 'else'"),
+                        (_, BlockEnd::Eof) => anyhow::bail!("template 'if' missing 'end'"),
+                    },
+                    BlockEnd::Eof => anyhow::bail!("template 'if' missing 'end'"),
+                };
+                nodes.push(Node::If {
+                    cond,
+                    negate,
+                    then,
+                    otherwise,
+                });
+                continue;
+            }
+            if let Some(prefix) = keyword_arg(&expr, "range") {
+                let prefix = prefix.strip_suffix('*').unwrap_or(prefix).trim();
+                if prefix.is_empty() {
+                    anyhow::bail!("template 'range' missing prefix");
+                }
+                self.range_depth += 1;
+                let (body, end) = self.parse_block()?;
+                self.range_depth -= 1;
+                match end {
+                    BlockEnd::End => {}
+                    BlockEnd::Else => anyhow::bail!("template 'range' does not take 'else'"),
+                    BlockEnd::Eof => anyhow::bail!("template 'range' missing 'end'"),
+                }
+                nodes.push(Node::Range {
+                    prefix: prefix.to_string(),
+                    body,
+                });
+                continue;
+            }
+            nodes.push(Node::Value(self.parse_value(&expr)?));
+        }
+        Ok((nodes, BlockEnd::Eof))
+    }
+
+    fn parse_value(&self, expr: &str) -> anyhow::Result<ValueExpr> {
+        let mut parts = split_pipes(expr)?.into_iter();
+        let reference = parts
+            .next()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("template placeholder missing selector: {expr}"))?;
+        let reference = if let Some(current) = reference.strip_prefix('.') {
+            if self.range_depth == 0 {
+                anyhow::bail!("'{reference}' is only valid inside 'range'");
+            }
+            match current.trim() {
+                "" => Reference::CurrentField("password".to_string()),
+                "path" => Reference::CurrentPath,
+                "name" => Reference::CurrentName,
+                field => match field.strip_prefix('#').map(str::trim) {
+                    Some(field) if !field.is_empty() => Reference::CurrentField(field.to_string()),
+                    _ => anyhow::bail!("unknown template reference: {reference}"),
+                },
+            }
+        } else {
+            let (selector, field) = parse_template_placeholder(reference)?;
+            Reference::Item { selector, field }
+        };
+        let filters = parts.map(parse_filter).collect::<anyhow::Result<_>>()?;
+        Ok(ValueExpr { reference, filters })
+    }
+}
+
+/// Splits `expr` on `|` outside of double-quoted strings.
+fn split_pipes(expr: &str) -> anyhow::Result<Vec<&str>> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, ch) in expr.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '|' if !in_string => {
+                parts.push(&expr[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if in_string {
+        anyhow::bail!("template string missing closing '\"': {expr}");
+    }
+    parts.push(&expr[start..]);
+    Ok(parts)
+}
+
+fn parse_filter(part: &str) -> anyhow::Result<Filter> {
+    let part = part.trim();
+    let (name, arg) = match part.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, Some(arg.trim())),
+        None => (part, None),
+    };
+    let filter = match name {
+        "default" => {
+            let arg =
+                arg.ok_or_else(|| anyhow::anyhow!("template filter 'default' needs a value"))?;
+            return Ok(Filter::Default(parse_string_arg(arg)?));
+        }
+        "base64" => Filter::Base64,
+        "b64decode" => Filter::B64Decode,
+        "json" => Filter::Json,
+        "yaml_quote" => Filter::YamlQuote,
+        "urlencode" => Filter::UrlEncode,
+        "trim" => Filter::Trim,
+        "" => anyhow::bail!("template filter is empty"),
+        other => anyhow::bail!("unknown template filter: {other}"),
+    };
+    if arg.is_some() {
+        anyhow::bail!("template filter '{name}' takes no value");
+    }
+    Ok(filter)
+}
+
+/// A double-quoted string with `\"`, `\\`, `\n` and `\t` escapes, or a bare word.
+fn parse_string_arg(arg: &str) -> anyhow::Result<String> {
+    let arg = arg.trim();
+    let Some(inner) = arg.strip_prefix('"') else {
+        if arg.is_empty() || arg.contains(char::is_whitespace) || arg.contains('"') {
+            anyhow::bail!("template value must be a word or a quoted string: {arg}");
+        }
+        return Ok(arg.to_string());
+    };
+    let inner = inner
+        .strip_suffix('"')
+        .ok_or_else(|| anyhow::anyhow!("template string missing closing '\"': {arg}"))?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(ch @ ('"' | '\\')) => out.push(ch),
+            _ => anyhow::bail!("invalid escape in template string: {arg}"),
+        }
+    }
+    Ok(out)
+}
+
+struct Renderer<'a> {
+    data: &'a TemplateData,
+    strict: bool,
+    /// Items of the enclosing ranges: (range prefix, item path, payload).
+    scope: Vec<(Option<String>, &'a str, &'a EncryptedPayload)>,
+}
+
+impl<'a> Renderer<'a> {
+    fn render(&mut self, nodes: &'a [Node], out: &mut String) -> anyhow::Result<()> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Value(expr) => {
+                    let value = self.evaluate(expr)?;
+                    out.push_str(&value?);
+                }
+                Node::If {
+                    cond,
+                    negate,
+                    then,
+                    otherwise,
+                } => {
+                    let value = self.evaluate(cond)?;
+                    let truthy = value.is_ok_and(|value| !value.is_empty());
+                    if truthy != *negate {
+                        self.render(then, out)?;
+                    } else {
+                        self.render(otherwise, out)?;
+                    }
+                }
+                Node::Range { prefix, body } => {
+                    let items = self
+                        .data
+                        .ranges
+                        .get(prefix)
+                        .ok_or_else(|| anyhow::anyhow!("range {prefix} was not fetched"))?;
+                    let normalized = normalize_prefix(Some(prefix));
+                    for (path, payload) in items {
+                        self.scope.push((normalized.clone(), path, payload));
+                        let result = self.render(body, out);
+                        self.scope.pop();
+                        result?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The value of `expr`, or the reason it is missing. Missing values are
+    /// only returned as a hard error in strict mode.
+    fn evaluate(&self, expr: &ValueExpr) -> anyhow::Result<anyhow::Result<String>> {
+        let mut value = self.lookup(&expr.reference)?;
+        if self.strict {
+            value = Ok(value?);
+        }
+        for filter in &expr.filters {
+            value = match (filter, value) {
+                (Filter::Default(fallback), Err(_)) => Ok(fallback.clone()),
+                (Filter::Default(fallback), Ok(value)) if value.is_empty() => Ok(fallback.clone()),
+                (_, Err(err)) => Err(err),
+                (filter, Ok(value)) => Ok(apply_filter(filter, value)?),
+            };
+        }
+        Ok(value)
+    }
+
+    /// Same shape as `evaluate`: the outer error means the template data is
+    /// incomplete, the inner one that the item or field does not exist.
+    fn lookup(&self, reference: &Reference) -> anyhow::Result<anyhow::Result<String>> {
+        Ok(match reference {
+            Reference::Item { selector, field } => {
+                let payload = self
+                    .data
+                    .items
+                    .get(selector)
+                    .ok_or_else(|| anyhow::anyhow!("item {selector} was not fetched"))?;
+                match payload {
+                    Some(payload) => field_value(payload, field, selector),
+                    None => Err(secret_not_found_error(selector)),
+                }
+            }
+            Reference::CurrentField(field) => {
+                let (_, path, payload) = self.current()?;
+                field_value(payload, field, path)
+            }
+            Reference::CurrentPath => Ok(self.current()?.1.trim_matches('/').to_string()),
+            Reference::CurrentName => {
+                let (prefix, path, _) = self.current()?;
+                let path = path.trim().trim_matches('/');
+                let name = match prefix {
+                    Some(prefix) => path
+                        .strip_prefix(prefix.as_str())
+                        .map(|rest| rest.trim_start_matches('/'))
+                        .filter(|rest| !rest.is_empty())
+                        .unwrap_or(path),
+                    None => path,
+                };
+                Ok(name.to_string())
+            }
+        })
+    }
+
+    fn current(&self) -> anyhow::Result<&(Option<String>, &'a str, &'a EncryptedPayload)> {
+        self.scope
+            .last()
+            .ok_or_else(|| anyhow::anyhow!("'.' is only valid inside 'range'"))
+    }
+}
+
+fn field_value(payload: &EncryptedPayload, field: &str, path: &str) -> anyhow::Result<String> {
+    crate::find_field(payload, field)
+        .map(|item| item.value.clone())
+        .ok_or_else(|| anyhow::anyhow!("field '{}' not found in {}", field, path))
+}
+
+fn apply_filter(filter: &Filter, value: String) -> anyhow::Result<String> {
+    Ok(match filter {
+        Filter::Default(_) => value,
+        Filter::Base64 => base64::engine::general_purpose::STANDARD.encode(value),
+        Filter::B64Decode => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(value.trim())
+                .map_err(|err| anyhow::anyhow!("b64decode: {err}"))?;
+            String::from_utf8(bytes)
+                .map_err(|_| anyhow::anyhow!("b64decode: value is not UTF-8"))?
+        }
+        Filter::Json => serde_json::to_string(&value)?,
+        Filter::YamlQuote => yaml_quote(&value),
+        Filter::UrlEncode => urlencoding::encode(&value).into_owned(),
+        Filter::Trim => value.trim().to_string(),
+    })
+}
+
+/// Single-quoted YAML scalar; values with control characters fall back to a
+/// double-quoted one, which YAML reads the same way as a JSON string.
+fn yaml_quote(value: &str) -> String {
+    if value.chars().any(char::is_control) {
+        return serde_json::to_string(value).unwrap_or_default();
+    }
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{collect_references, parse_template_nodes, render_nodes, TemplateData};
+    use std::path::Path;
+    use zann_core::{EncryptedPayload, FieldKind, FieldValue};
+
+    fn payload(fields: &[(&str, &str)]) -> EncryptedPayload {
+        let mut payload = EncryptedPayload::new("secret");
+        for (key, value) in fields {
+            payload.fields.insert(
+                key.to_string(),
+                FieldValue {
+                    kind: FieldKind::Text,
+                    value: value.to_string(),
+                    meta: None,
+                },
+            );
+        }
+        payload
+    }
+
+    fn data() -> TemplateData {
+        let mut data = TemplateData::default();
+        data.items.insert(
+            "db".to_string(),
+            Some(payload(&[
+                ("password", "s3cr3t"),
+                ("user", "app"),
+                ("empty", ""),
+            ])),
+        );
+        data.items.insert("gone".to_string(), None);
+        data.ranges.insert(
+            "users/".to_string(),
+            vec![
+                ("users/alice".to_string(), payload(&[("password", "a'1")])),
+                ("users/bob".to_string(), payload(&[("password", "b2")])),
+            ],
+        );
+        data
+    }
+
+    fn render(template: &str, strict: bool) -> anyhow::Result<String> {
+        let nodes = parse_template_nodes(template, Path::new("."))?;
+        render_nodes(&nodes, &data(), strict)
+    }
+
+    #[test]
+    fn renders_filters_and_defaults() {
+        let out = render(
+            "{{ db }} {{ db#user | base64 }} {{ db#user | json }} {{ db#missing | default \"x y\" }} {{ db#empty | default none }} {{ gone#password | default \"\" | trim }}",
+            false,
+        )
+        .expect("render");
+        assert_eq!(out, "s3cr3t YXBw \"app\" x y none ");
+        let out = render(
+            "{{ db#user | base64 | b64decode }} {{ db#user | default \"a b\" | urlencode }} {{ gone | default \"a b\" | urlencode }}",
+            false,
+        )
+        .expect("render");
+        assert_eq!(out, "app app a%20b");
+    }
+
+    #[test]
+    fn renders_conditionals_and_ranges() {
+        let template = "userlist admins\n{{ range users/* }}\n  user {{ .name }} password {{ .#password | yaml_quote }}\n{{ end }}\n{{ if db#user }}db={{ db#user }}\n{{ else }}no db\n{{ end }}\n{{ if not gone#password }}gone\n{{ end }}";
+        let out = render(template, false).expect("render");
+        assert_eq!(
+            out,
+            "userlist admins\n  user alice password 'a''1'\n  user bob password 'b2'\ndb=app\ngone\n"
+        );
+    }
+
+    #[test]
+    fn strict_mode_rejects_missing_fields() {
+        assert!(render("{{ db#missing }}", false).is_err());
+        assert_eq!(
+            render("{{ if db#missing }}yes{{ end }}", false).expect("render"),
+            ""
+        );
+        assert!(render("{{ if db#missing }}yes{{ end }}", true).is_err());
+        assert!(render("{{ db#missing | default x }}", true).is_err());
+        assert_eq!(
+            render("{{ db#empty | default x }}", true).expect("render"),
+            "x"
+        );
+    }
+
+    #[test]
+    fn rejects_unbalanced_blocks() {
+        assert!(parse_template_nodes("{{ if db }}x", Path::new(".")).is_err());
+        assert!(parse_template_nodes("x{{ end }}", Path::new(".")).is_err());
+        assert!(parse_template_nodes("{{ .name }}", Path::new(".")).is_err());
+        assert!(parse_template_nodes("{{ db | shout }}", Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn includes_resolve_relative_to_the_template() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir(dir.path().join("partials")).expect("dir");
+        std::fs::write(
+            dir.path().join("partials/db.tpl"),
+            "{{ include \"user.tpl\" }}\npass={{ db }}\n",
+        )
+        .expect("partial");
+        std::fs::write(dir.path().join("partials/user.tpl"), "user={{ db#user }}\n")
+            .expect("partial");
+        let nodes = parse_template_nodes(
+            "[db]\n{{ include \"partials/db.tpl\" }}\n[end]\n",
+            dir.path(),
+        )
+        .expect("parse");
+        let (mut items, mut ranges) = (Vec::new(), Vec::new());
+        collect_references(&nodes, &mut items, &mut ranges);
+        assert_eq!(items, vec!["db".to_string()]);
+        let out = render_nodes(&nodes, &data(), true).expect("render");
+        assert_eq!(out, "[db]\nuser=app\npass=s3cr3t\n[end]\n");
+
+        std::fs::write(dir.path().join("loop.tpl"), "{{ include loop.tpl }}").expect("loop");
+        assert!(parse_template_nodes("{{ include loop.tpl }}", dir.path()).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::cli_args::*;
+use crate::modules::shared::resolve_vault_arg;
+use crate::modules::system::http::send_request;
+use crate::modules::system::CommandContext;
+
+#[derive(Deserialize)]
+struct SignResponse {
+    certificate: String,
+    serial: String,
+    principals: Vec<String>,
+    valid_before: String,
+}
+
+#[derive(Deserialize)]
+struct CaResponse {
+    public_key: String,
+}
+
+pub(crate) async fn handle_ssh_command(
+    args: SshArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<()> {
+    match args.command {
+        SshCommand::Sign(args) => handle_sign(args, ctx).await,
+        SshCommand::CaKey(args) => {
+            let vault = resolve_vault_arg(args.vault, ctx).await?;
+            let url = format!("{}/ca", ssh_base(ctx, &vault));
+            let response = send_request(ctx, Method::GET, url, None).await?;
+            let ca: CaResponse = parse_response(response).await?;
+            println!("{}", ca.public_key);
+            Ok(())
+        }
+    }
+}
+
+async fn handle_sign(args: SshSignArgs, ctx: &mut CommandContext<'_>) -> anyhow::Result<()> {
+    let public_key = std::fs::read_to_string(&args.public_key)
+        .map_err(|err| anyhow::anyhow!("failed to read {}: {err}", args.public_key.display()))?;
+    let vault = resolve_vault_arg(args.vault, ctx).await?;
+    let mut payload = json!({
+        "public_key": public_key.trim(),
+        "role": args.role,
+    });
+    if !args.principals.is_empty() {
+        payload["principals"] = json!(args.principals);
+    }
+    if !args.extensions.is_empty() {
+        payload["extensions"] = json!(args.extensions);
+    }
+    if let Some(ttl) = args.ttl {
+        payload["ttl"] = json!(ttl);
+    }
+    let url = format!("{}/sign", ssh_base(ctx, &vault));
+    let response = send_request(ctx, Method::POST, url, Some(payload)).await?;
+    let signed: SignResponse = parse_response(response).await?;
+
+    let output = args
+        .output
+        .unwrap_or_else(|| certificate_path(&args.public_key));
+    std::fs::write(&output, format!("{}\n", signed.certificate.trim()))
+        .map_err(|err| anyhow::anyhow!("failed to write {}: {err}", output.display()))?;
+    println!(
+        "Wrote {} (serial {}, principals {}, valid until {})",
+        output.display(),
+        signed.serial,
+        signed.principals.join(","),
+        signed.valid_before
+    );
+    Ok(())
+}
+
+fn ssh_base(ctx: &CommandContext<'_>, vault: &str) -> String {
+    format!(
+        "{}/v1/vaults/{}/ssh",
+        ctx.addr.trim_end_matches('/'),
+        urlencoding::encode(vault)
+    )
+}
+
+/// `id_ed25519.pub` -> `id_ed25519-cert.pub`, the name `ssh` looks for.
+fn certificate_path(public_key: &Path) -> PathBuf {
+    let name = public_key
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = name.strip_suffix(".pub").unwrap_or(&name);
+    public_key.with_file_name(format!("{stem}-cert.pub"))
+}
+
+async fn parse_response<T: for<'de> Deserialize<'de>>(
+    response: reqwest::Response,
+) -> anyhow::Result<T> {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Request failed: {status} {body}");
+    }
+    Ok(response.json().await?)
+}
@@ -0,0 +1,51 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct SshArgs {
+    #[command(subcommand)]
+    pub command: SshCommand,
+}
+
+#[derive(Subcommand)]
+pub enum SshCommand {
+    #[command(about = "Sign a public key with the vault SSH CA and write the certificate")]
+    Sign(SshSignArgs),
+    #[command(about = "Print the vault SSH CA public key (for TrustedUserCAKeys)")]
+    CaKey(SshCaKeyArgs),
+}
+
+#[derive(Args)]
+pub struct SshSignArgs {
+    #[arg(help = "Public key file to sign (e.g. ~/.ssh/id_ed25519.pub)")]
+    pub public_key: PathBuf,
+    #[arg(long, help = "Vault name or ID")]
+    pub vault: Option<String>,
+    #[arg(long, help = "Signing role configured on the vault")]
+    pub role: String,
+    #[arg(
+        long = "principal",
+        help = "Principal to request (repeatable; defaults to the role's default principals)"
+    )]
+    pub principals: Vec<String>,
+    #[arg(
+        long = "extension",
+        value_delimiter = ',',
+        help = "Extension to request (repeatable; defaults to the role's default extensions)"
+    )]
+    pub extensions: Vec<String>,
+    #[arg(long, help = "Certificate lifetime (e.g. 1h, 8h)")]
+    pub ttl: Option<String>,
+    #[arg(
+        short,
+        long,
+        help = "Certificate output path (default: <key>-cert.pub next to the public key)"
+    )]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct SshCaKeyArgs {
+    #[arg(long, help = "Vault name or ID")]
+    pub vault: Option<String>,
+}
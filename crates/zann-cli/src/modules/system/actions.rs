@@ -1,12 +1,23 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::process::ExitStatus;
+
+use zann_core::EncryptedPayload;
+
 use crate::cli_args::*;
 use crate::modules::auth::{
     exchange_service_account_token, load_service_token, verify_server_fingerprint,
 };
 use crate::modules::shared::{
-    fetch_shared_item, flatten_payload, is_valid_env_key, payload_or_error,
-    resolve_path_for_context, resolve_shared_item_id,
+    fetch_shared_payload, fetch_shared_payloads, flatten_payload, is_valid_env_key,
+    resolve_path_for_context, secret_not_found_error,
+};
+use crate::modules::system::http::{
+    fetch_system_info, issue_dynamic_credential, revoke_dynamic_lease,
+};
+use crate::modules::system::mask::run_masked;
+use crate::modules::system::run_env::{
+    parse_env_file, parse_secret_mapping, prefix_env_name, SecretMapping,
 };
-use crate::modules::system::http::fetch_system_info;
 use crate::modules::system::CliConfig;
 use crate::modules::system::{ensure_secure_addr, resolve_addr};
 use crate::{DEFAULT_ADDR, SERVICE_ACCOUNT_PREFIX};
@@ -43,6 +54,30 @@ pub(crate) async fn handle_run_command(
     if args.command.is_empty() {
         anyhow::bail!("command is required after --");
     }
+    // The path accepts hyphen values so clap keeps it as the path when an
+    // option follows it; a path that looks like an option is a typo.
+    if let Some(path) = args.path.as_deref().filter(|path| path.starts_with('-')) {
+        anyhow::bail!("unexpected argument '{path}'");
+    }
+    if args.path.is_none()
+        && args.secrets.is_empty()
+        && args.prefixes.is_empty()
+        && args.env_file.is_none()
+    {
+        anyhow::bail!("nothing to inject: pass a path, --secret, --prefix or --env-file");
+    }
+    // Mappings are checked before anything is sent to the server.
+    let mut mappings = Vec::new();
+    if let Some(env_file) = args.env_file.as_deref() {
+        let contents = std::fs::read_to_string(env_file)
+            .map_err(|err| anyhow::anyhow!("failed to read {}: {err}", env_file.display()))?;
+        let parsed = parse_env_file(&contents)
+            .map_err(|err| anyhow::anyhow!("{}: {err}", env_file.display()))?;
+        mappings.extend(parsed);
+    }
+    for value in &args.secrets {
+        mappings.push(parse_secret_mapping(value)?);
+    }
 
     let context_name = context_arg.or_else(|| config.current_context.clone());
     let context = context_name
@@ -78,9 +113,36 @@ pub(crate) async fn handle_run_command(
     let auth = exchange_service_account_token(client, &addr, &service_account_token).await?;
     let access_token = auth.access_token.clone();
 
-    let (vault_id, path) = resolve_path_for_context(
-        &args.path,
-        args.vault,
+    if args.dynamic {
+        let path = args
+            .path
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("--dynamic requires a path"))?;
+        let (vault_id, path) = resolve_path_for_context(
+            path,
+            args.vault.clone(),
+            context_name.as_deref(),
+            config,
+            client,
+            &addr,
+            &access_token,
+        )
+        .await?;
+        return run_with_dynamic_credential(
+            client,
+            &addr,
+            &access_token,
+            &vault_id,
+            &path,
+            args.ttl.as_deref(),
+            &args.command,
+            args.mask_output,
+        )
+        .await;
+    }
+    let env_values = collect_run_env(
+        &args,
+        mappings,
         context_name.as_deref(),
         config,
         client,
@@ -88,34 +150,182 @@ pub(crate) async fn handle_run_command(
         &access_token,
     )
     .await?;
-    let item_id =
-        resolve_shared_item_id(client, &addr, &access_token, &vault_id, None, Some(&path))
-            .await
-            .map_err(|_| anyhow::anyhow!("secret not found: {}", path))?;
-    let item = fetch_shared_item(client, &addr, &access_token, &vault_id, item_id).await?;
-    let mut env_values = std::collections::HashMap::new();
-    let payload = payload_or_error(&item)?;
-    for (key, value) in flatten_payload(&payload) {
-        if !is_valid_env_key(&key) {
-            eprintln!(
-                "Warning: Key \"{}\" is not a valid shell identifier. Skipped.",
-                key
+
+    let status = run_command(&args.command, &env_values, args.mask_output)?;
+    if let Some(code) = status.code() {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// Builds the environment for `zann run`. Fields of `path` and `--prefix`
+/// items come first; `--env-file` and `--secret` mappings override them.
+#[allow(clippy::too_many_arguments)]
+async fn collect_run_env(
+    args: &RunArgs,
+    mappings: Vec<SecretMapping>,
+    context_name: Option<&str>,
+    config: &CliConfig,
+    client: &reqwest::Client,
+    addr: &str,
+    access_token: &str,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let mut env_values = BTreeMap::new();
+    let mut payloads: HashMap<(String, String), EncryptedPayload> = HashMap::new();
+
+    if let Some(path) = args.path.as_deref() {
+        let (vault_id, path) = resolve_path_for_context(
+            path,
+            args.vault.clone(),
+            context_name,
+            config,
+            client,
+            addr,
+            access_token,
+        )
+        .await?;
+        let payload = fetch_shared_payload(client, addr, access_token, &vault_id, &path)
+            .await?
+            .ok_or_else(|| secret_not_found_error(&path))?;
+        for (key, value) in flatten_payload(&payload) {
+            if !is_valid_env_key(&key) {
+                eprintln!(
+                    "Warning: Key \"{}\" is not a valid shell identifier. Skipped.",
+                    key
+                );
+                continue;
+            }
+            env_values.insert(key, value);
+        }
+        payloads.insert((vault_id, path.trim_matches('/').to_string()), payload);
+    }
+
+    let mut exported_by: HashMap<String, String> = HashMap::new();
+    for prefix in &args.prefixes {
+        let (vault_id, prefix) = resolve_path_for_context(
+            prefix,
+            args.vault.clone(),
+            context_name,
+            config,
+            client,
+            addr,
+            access_token,
+        )
+        .await?;
+        let entries = fetch_shared_payloads(client, addr, access_token, &vault_id, &prefix).await?;
+        if entries.is_empty() {
+            eprintln!("Warning: no secrets found under {prefix}");
+        }
+        for (path, payload) in entries {
+            for (field, value) in flatten_payload(&payload) {
+                let Some(name) = prefix_env_name(&prefix, &path, &field) else {
+                    eprintln!(
+                        "Warning: Field \"{}\" of {} has no valid variable name. Skipped.",
+                        field, path
+                    );
+                    continue;
+                };
+                if let Some(other) = exported_by.insert(name.clone(), path.clone()) {
+                    anyhow::bail!("{other} and {path} both export {name}");
+                }
+                env_values.insert(name, value);
+            }
+            payloads.insert(
+                (vault_id.clone(), path.trim_matches('/').to_string()),
+                payload,
             );
-            continue;
         }
-        env_values.insert(key, value);
     }
 
-    let mut cmd = std::process::Command::new(&args.command[0]);
-    if args.command.len() > 1 {
-        cmd.args(&args.command[1..]);
+    let mut mapped = HashSet::new();
+    for mapping in mappings {
+        if !mapped.insert(mapping.env.clone()) {
+            anyhow::bail!("{} is mapped more than once", mapping.env);
+        }
+        let (vault_id, path) = resolve_path_for_context(
+            &mapping.selector,
+            args.vault.clone(),
+            context_name,
+            config,
+            client,
+            addr,
+            access_token,
+        )
+        .await?;
+        let key = (vault_id, path.trim_matches('/').to_string());
+        if !payloads.contains_key(&key) {
+            let payload = fetch_shared_payload(client, addr, access_token, &key.0, &path)
+                .await?
+                .ok_or_else(|| secret_not_found_error(&path))?;
+            payloads.insert(key.clone(), payload);
+        }
+        let value = payloads
+            .get(&key)
+            .and_then(|payload| crate::find_field(payload, &mapping.field))
+            .ok_or_else(|| anyhow::anyhow!("field '{}' not found in {}", mapping.field, path))?;
+        env_values.insert(mapping.env, value.value.clone());
+    }
+    Ok(env_values)
+}
+
+/// Runs `command` with `env` added to its environment. With `mask_output`,
+/// the values of `env` are redacted from its output.
+fn run_command(
+    command: &[String],
+    env: &BTreeMap<String, String>,
+    mask_output: bool,
+) -> anyhow::Result<ExitStatus> {
+    let mut cmd = std::process::Command::new(&command[0]);
+    if command.len() > 1 {
+        cmd.args(&command[1..]);
+    }
+    cmd.envs(env);
+    if mask_output {
+        let secrets: Vec<String> = env.values().cloned().collect();
+        return run_masked(&mut cmd, &secrets);
+    }
+    Ok(cmd.status()?)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_with_dynamic_credential(
+    client: &reqwest::Client,
+    addr: &str,
+    access_token: &str,
+    vault_id: &str,
+    path: &str,
+    ttl: Option<&str>,
+    command: &[String],
+    mask_output: bool,
+) -> anyhow::Result<()> {
+    let credential =
+        issue_dynamic_credential(client, addr, access_token, vault_id, path, ttl).await?;
+
+    let mut env = BTreeMap::new();
+    if let Some(host) = credential.host.as_deref() {
+        env.insert("PGHOST".to_string(), host.to_string());
+    }
+    if let Some(port) = credential.port {
+        env.insert("PGPORT".to_string(), port.to_string());
     }
-    for (key, value) in env_values {
-        cmd.env(key, value);
+    if let Some(database) = credential.database.as_deref() {
+        env.insert("PGDATABASE".to_string(), database.to_string());
     }
+    env.insert("PGUSER".to_string(), credential.username.clone());
+    env.insert("PGPASSWORD".to_string(), credential.password.clone());
+    env.insert("ZANN_LEASE_ID".to_string(), credential.lease_id.clone());
 
-    let status = cmd.status()?;
-    if let Some(code) = status.code() {
+    let status = run_command(command, &env, mask_output);
+    // The lease expires on its own; revoking here just shortens its lifetime.
+    if let Err(err) =
+        revoke_dynamic_lease(client, addr, access_token, vault_id, &credential.lease_id).await
+    {
+        eprintln!(
+            "Warning: failed to revoke lease {}: {err}",
+            credential.lease_id
+        );
+    }
+    if let Some(code) = status?.code() {
         std::process::exit(code);
     }
     Ok(())
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::{Args, Subcommand};
 
 #[derive(Args)]
@@ -20,11 +22,50 @@ pub struct ServerInfoArgs {
 
 #[derive(Args)]
 pub struct RunArgs {
-    #[arg(help = "Secret path to resolve (e.g. app/db/password)")]
-    pub path: String,
+    #[arg(
+        allow_hyphen_values = true,
+        help = "Secret path whose fields are exported by name (e.g. app/db/creds)"
+    )]
+    pub path: Option<String>,
     #[arg(long, help = "Vault name or ID")]
     pub vault: Option<String>,
-    #[arg(trailing_var_arg = true)]
+    #[arg(
+        long = "secret",
+        value_name = "PATH[:FIELD]=ENV_NAME",
+        help = "Export one field as ENV_NAME (repeatable; field defaults to password)"
+    )]
+    pub secrets: Vec<String>,
+    #[arg(
+        long = "prefix",
+        value_name = "PREFIX",
+        help = "Export every field of every item under PREFIX (repeatable)"
+    )]
+    pub prefixes: Vec<String>,
+    #[arg(long, help = "File of ENV_NAME=path[:field] lines to export")]
+    pub env_file: Option<PathBuf>,
+    #[arg(
+        long,
+        help = "Redact injected values from the command's stdout and stderr"
+    )]
+    pub mask_output: bool,
+    #[arg(
+        long,
+        requires = "path",
+        conflicts_with_all = ["secrets", "prefixes", "env_file"],
+        help = "Request a short-lived database credential from a dynamic secret"
+    )]
+    pub dynamic: bool,
+    #[arg(
+        long,
+        requires = "dynamic",
+        help = "Lease TTL for --dynamic (e.g. 15m, 1h)"
+    )]
+    pub ttl: Option<String>,
+    #[arg(
+        trailing_var_arg = true,
+        allow_hyphen_values = true,
+        help = "Command to run; put it after -- when no path is given"
+    )]
     pub command: Vec<String>,
 }
 
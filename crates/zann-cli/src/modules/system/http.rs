@@ -123,6 +123,56 @@ pub(crate) async fn fetch_system_info(
     fetch_json(client, &url).await
 }
 
+pub(crate) async fn issue_dynamic_credential(
+    client: &reqwest::Client,
+    addr: &str,
+    access_token: &str,
+    vault_id: &str,
+    path: &str,
+    ttl: Option<&str>,
+) -> anyhow::Result<crate::modules::system::DynamicCredentialResponse> {
+    let url = format!(
+        "{}/v1/vaults/{}/dynamic/{}",
+        addr.trim_end_matches('/'),
+        urlencoding::encode(vault_id),
+        urlencoding::encode(path.trim_start_matches('/'))
+    );
+    let response = client
+        .post(url)
+        .bearer_auth(access_token)
+        .json(&serde_json::json!({ "ttl": ttl }))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Dynamic credential request failed: {status} {body}");
+    }
+    Ok(response.json().await?)
+}
+
+pub(crate) async fn revoke_dynamic_lease(
+    client: &reqwest::Client,
+    addr: &str,
+    access_token: &str,
+    vault_id: &str,
+    lease_id: &str,
+) -> anyhow::Result<()> {
+    let url = format!(
+        "{}/v1/vaults/{}/dynamic-leases/{}",
+        addr.trim_end_matches('/'),
+        vault_id,
+        lease_id
+    );
+    let response = client.delete(url).bearer_auth(access_token).send().await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Lease revoke failed: {status} {body}");
+    }
+    Ok(())
+}
+
 pub(crate) fn parse_rfc3339(value: &str) -> Option<DateTime<Utc>> {
     DateTime::parse_from_rfc3339(value)
         .ok()
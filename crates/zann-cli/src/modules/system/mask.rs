@@ -0,0 +1,115 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Command, ExitStatus, Stdio};
+
+const REDACTED: &[u8] = b"*****";
+
+/// Runs `cmd` with its stdout and stderr passed through line by line, with
+/// every occurrence of `secrets` replaced by `*****`.
+pub(crate) fn run_masked(cmd: &mut Command, secrets: &[String]) -> anyhow::Result<ExitStatus> {
+    let masks = build_masks(secrets);
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("child stdout unavailable"))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("child stderr unavailable"))?;
+    let status = std::thread::scope(|scope| {
+        let out = scope.spawn(|| copy_redacted(stdout, std::io::stdout(), &masks));
+        let err = scope.spawn(|| copy_redacted(stderr, std::io::stderr(), &masks));
+        let status = child.wait();
+        for handle in [out, err] {
+            match handle.join() {
+                Ok(Ok(())) => {}
+                Ok(Err(err)) => eprintln!("Warning: failed to forward command output: {err}"),
+                Err(_) => eprintln!("Warning: failed to forward command output"),
+            }
+        }
+        status
+    })?;
+    Ok(status)
+}
+
+/// Values to redact, longest first so a secret that contains another one is
+/// replaced whole. Multi-line values are also matched line by line, since
+/// output is redacted one line at a time.
+fn build_masks(secrets: &[String]) -> Vec<Vec<u8>> {
+    let mut masks: Vec<Vec<u8>> = Vec::new();
+    for secret in secrets {
+        let lines = secret
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        for value in std::iter::once(secret.as_str()).chain(lines) {
+            if !value.is_empty() && !masks.iter().any(|mask| mask == value.as_bytes()) {
+                masks.push(value.as_bytes().to_vec());
+            }
+        }
+    }
+    masks.sort_by_key(|mask| std::cmp::Reverse(mask.len()));
+    masks
+}
+
+fn copy_redacted(
+    reader: impl Read,
+    mut writer: impl Write,
+    masks: &[Vec<u8>],
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(());
+        }
+        writer.write_all(&redact(&line, masks))?;
+        writer.flush()?;
+    }
+}
+
+fn redact(line: &[u8], masks: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = line.to_vec();
+    for mask in masks {
+        if out.len() < mask.len() {
+            continue;
+        }
+        let mut redacted = Vec::with_capacity(out.len());
+        let mut index = 0;
+        while index < out.len() {
+            if out[index..].starts_with(mask) {
+                redacted.extend_from_slice(REDACTED);
+                index += mask.len();
+            } else {
+                redacted.push(out[index]);
+                index += 1;
+            }
+        }
+        out = redacted;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_masks, redact};
+
+    #[test]
+    fn redacts_every_secret_longest_first() {
+        let masks = build_masks(&[
+            "pass".to_string(),
+            "password123".to_string(),
+            "line one\nline two".to_string(),
+            String::new(),
+        ]);
+        assert_eq!(
+            redact(b"db=password123 alt=pass key=line two\n", &masks),
+            b"db=***** alt=***** key=*****\n".to_vec()
+        );
+        assert_eq!(
+            redact(b"nothing here\n", &masks),
+            b"nothing here\n".to_vec()
+        );
+    }
+}
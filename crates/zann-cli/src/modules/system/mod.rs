@@ -2,6 +2,8 @@ mod actions;
 pub(crate) mod args;
 pub(crate) mod config;
 pub(crate) mod http;
+mod mask;
+mod run_env;
 pub(crate) mod types;
 
 pub(crate) use actions::{handle_run_command, handle_server_command};
@@ -9,4 +11,4 @@ pub(crate) use config::{
     ensure_secure_addr, handle_config_command, load_config, load_known_hosts, normalize_server_key,
     resolve_addr, save_config, save_known_hosts,
 };
-pub(crate) use types::{CliConfig, CommandContext, SystemInfoResponse};
+pub(crate) use types::{CliConfig, CommandContext, DynamicCredentialResponse, SystemInfoResponse};
@@ -0,0 +1,160 @@
+use crate::modules::shared::is_valid_env_key;
+
+/// One secret field exported as one environment variable.
+#[derive(Debug, PartialEq)]
+pub(crate) struct SecretMapping {
+    /// Item path, optionally `vault:path`.
+    pub selector: String,
+    pub field: String,
+    pub env: String,
+}
+
+/// Parses `--secret path[:field]=ENV_NAME`. The field defaults to `password`.
+pub(crate) fn parse_secret_mapping(value: &str) -> anyhow::Result<SecretMapping> {
+    let (source, env) = value
+        .rsplit_once('=')
+        .ok_or_else(|| anyhow::anyhow!("--secret must be path[:field]=ENV_NAME: {value}"))?;
+    build_mapping(source, env)
+}
+
+/// Parses an `--env-file`: one `ENV_NAME=path[:field]` per line, with blank
+/// lines and `#` comments ignored.
+pub(crate) fn parse_env_file(contents: &str) -> anyhow::Result<Vec<SecretMapping>> {
+    let mut mappings = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let mapping = line
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("expected ENV_NAME=path[:field]"))
+            .and_then(|(env, source)| build_mapping(source, env))
+            .map_err(|err| anyhow::anyhow!("line {}: {err}", index + 1))?;
+        mappings.push(mapping);
+    }
+    Ok(mappings)
+}
+
+fn build_mapping(source: &str, env: &str) -> anyhow::Result<SecretMapping> {
+    let env = env.trim();
+    if !is_valid_env_key(env) {
+        anyhow::bail!("\"{env}\" is not a valid environment variable name");
+    }
+    let source = source.trim();
+    // `vault:path:field` keeps the vault selector intact.
+    let (selector, field) = match source.rsplit_once(':') {
+        Some((selector, field)) if !field.contains('/') => (selector.trim(), field.trim()),
+        _ => (source, "password"),
+    };
+    if selector.is_empty() || field.is_empty() {
+        anyhow::bail!("secret must be path[:field]: {source}");
+    }
+    Ok(SecretMapping {
+        selector: selector.to_string(),
+        field: field.to_string(),
+        env: env.to_string(),
+    })
+}
+
+/// Variable name for `field` of the item at `path` under `prefix`: the path
+/// below the prefix and the field, upper-cased and joined with `_`, so
+/// `apps/billing/db` with field `password` becomes `DB_PASSWORD`. Returns
+/// `None` when the result is not a valid name.
+pub(crate) fn prefix_env_name(prefix: &str, path: &str, field: &str) -> Option<String> {
+    let prefix = prefix.trim().trim_matches('/');
+    let path = path.trim().trim_matches('/');
+    let relative = path
+        .strip_prefix(prefix)
+        .map(|rest| rest.trim_start_matches('/'))
+        .unwrap_or(path);
+    let mut name = String::new();
+    for part in relative.split('/').chain([field]) {
+        if part.is_empty() {
+            continue;
+        }
+        if !name.is_empty() {
+            name.push('_');
+        }
+        name.extend(part.chars().map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        }));
+    }
+    is_valid_env_key(&name).then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_env_file, parse_secret_mapping, prefix_env_name, SecretMapping};
+
+    fn mapping(selector: &str, field: &str, env: &str) -> SecretMapping {
+        SecretMapping {
+            selector: selector.to_string(),
+            field: field.to_string(),
+            env: env.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_secret_mappings() {
+        assert_eq!(
+            parse_secret_mapping("apps/db=DB_PASSWORD").expect("mapping"),
+            mapping("apps/db", "password", "DB_PASSWORD")
+        );
+        assert_eq!(
+            parse_secret_mapping("apps/db:username=DB_USER").expect("mapping"),
+            mapping("apps/db", "username", "DB_USER")
+        );
+        assert_eq!(
+            parse_secret_mapping("infra:apps/db:username=DB_USER").expect("mapping"),
+            mapping("infra:apps/db", "username", "DB_USER")
+        );
+        assert_eq!(
+            parse_secret_mapping("infra:apps/db=DB_PASSWORD").expect("mapping"),
+            mapping("infra:apps/db", "password", "DB_PASSWORD")
+        );
+        assert!(parse_secret_mapping("apps/db").is_err());
+        assert!(parse_secret_mapping("apps/db=1DB").is_err());
+        assert!(parse_secret_mapping("apps/db:=DB").is_err());
+    }
+
+    #[test]
+    fn parses_env_files() {
+        let mappings = parse_env_file(
+            "# billing\nDB_PASSWORD=apps/billing/db\n\nexport STRIPE_KEY = apps/billing/stripe:api_key\n",
+        )
+        .expect("env file");
+        assert_eq!(
+            mappings,
+            vec![
+                mapping("apps/billing/db", "password", "DB_PASSWORD"),
+                mapping("apps/billing/stripe", "api_key", "STRIPE_KEY"),
+            ]
+        );
+        let err = parse_env_file("DB=apps/db\nnot a mapping\n").expect_err("invalid");
+        assert!(err.to_string().starts_with("line 2:"));
+    }
+
+    #[test]
+    fn names_prefix_exports() {
+        assert_eq!(
+            prefix_env_name("apps/billing", "apps/billing/db", "password").as_deref(),
+            Some("DB_PASSWORD")
+        );
+        assert_eq!(
+            prefix_env_name("/apps/billing/", "apps/billing/stripe-live/keys", "api.key")
+                .as_deref(),
+            Some("STRIPE_LIVE_KEYS_API_KEY")
+        );
+        assert_eq!(
+            prefix_env_name("apps/billing", "apps/billing", "token").as_deref(),
+            Some("TOKEN")
+        );
+        assert_eq!(prefix_env_name("apps", "apps/2fa", "code"), None);
+    }
+}
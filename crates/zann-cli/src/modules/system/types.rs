@@ -74,3 +74,16 @@ pub struct CommandContext<'a> {
     pub token_name: Option<String>,
     pub config: &'a mut CliConfig,
 }
+
+#[derive(Deserialize)]
+pub struct DynamicCredentialResponse {
+    pub lease_id: String,
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub database: Option<String>,
+}
@@ -0,0 +1,146 @@
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::cli_args::*;
+use crate::modules::shared::resolve_vault_arg;
+use crate::modules::system::http::{print_json_response, send_request};
+use crate::modules::system::CommandContext;
+
+#[derive(Deserialize)]
+struct MembersResponse {
+    members: Vec<MemberEntry>,
+}
+
+#[derive(Deserialize)]
+struct MemberEntry {
+    user_id: String,
+    email: String,
+    #[serde(default)]
+    source: Option<String>,
+}
+
+pub(crate) async fn handle_vault_command(
+    args: VaultArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<()> {
+    match args.command {
+        VaultCommand::Members(args) => handle_members_command(args, ctx).await,
+        VaultCommand::Groups(args) => handle_groups_command(args, ctx).await,
+    }
+}
+
+async fn handle_members_command(
+    args: VaultMembersArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<()> {
+    let vault = resolve_vault_arg(args.vault, ctx).await?;
+    let base = format!(
+        "{}/v1/vaults/{}/members",
+        ctx.addr.trim_end_matches('/'),
+        urlencoding::encode(&vault)
+    );
+    match args.command {
+        VaultMembersCommand::List => {
+            let response = send_request(ctx, Method::GET, base, None).await?;
+            print_json_response(response).await
+        }
+        VaultMembersCommand::Add(args) => {
+            let mut payload = json!({ "role": args.role });
+            if Uuid::parse_str(&args.user).is_ok() {
+                payload["user_id"] = json!(args.user);
+            } else {
+                payload["email"] = json!(args.user);
+            }
+            let response = send_request(ctx, Method::POST, base, Some(payload)).await?;
+            print_json_response(response).await
+        }
+        VaultMembersCommand::SetRole(args) => {
+            let user_id = resolve_member_id(ctx, &base, &args.user).await?;
+            let url = format!("{base}/{user_id}");
+            let payload = json!({ "role": args.role });
+            let response = send_request(ctx, Method::PUT, url, Some(payload)).await?;
+            print_json_response(response).await
+        }
+        VaultMembersCommand::Remove(args) => {
+            let user_id = resolve_member_id(ctx, &base, &args.user).await?;
+            let url = format!("{base}/{user_id}");
+            let response = send_request(ctx, Method::DELETE, url, None).await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                anyhow::bail!("Request failed: {status} {body}");
+            }
+            println!("Removed {} from {vault}", args.user);
+            Ok(())
+        }
+    }
+}
+
+async fn handle_groups_command(
+    args: VaultGroupsArgs,
+    ctx: &mut CommandContext<'_>,
+) -> anyhow::Result<()> {
+    let vault = resolve_vault_arg(args.vault, ctx).await?;
+    let base = format!(
+        "{}/v1/vaults/{}/groups",
+        ctx.addr.trim_end_matches('/'),
+        urlencoding::encode(&vault)
+    );
+    match args.command {
+        VaultGroupsCommand::List => {
+            let response = send_request(ctx, Method::GET, base, None).await?;
+            print_json_response(response).await
+        }
+        VaultGroupsCommand::Add(args) => {
+            let payload = json!({ "group": args.group, "role": args.role });
+            let response = send_request(ctx, Method::POST, base, Some(payload)).await?;
+            print_json_response(response).await
+        }
+        VaultGroupsCommand::SetRole(args) => {
+            let url = format!("{base}/{}", urlencoding::encode(&args.group));
+            let payload = json!({ "role": args.role });
+            let response = send_request(ctx, Method::PUT, url, Some(payload)).await?;
+            print_json_response(response).await
+        }
+        VaultGroupsCommand::Remove(args) => {
+            let url = format!("{base}/{}", urlencoding::encode(&args.group));
+            let response = send_request(ctx, Method::DELETE, url, None).await?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                anyhow::bail!("Request failed: {status} {body}");
+            }
+            println!("Revoked group {} from {vault}", args.group);
+            Ok(())
+        }
+    }
+}
+
+/// Members are addressed by user ID; an email is looked up among the vault's
+/// direct members, since inherited access is managed through the group.
+async fn resolve_member_id(
+    ctx: &mut CommandContext<'_>,
+    base: &str,
+    user: &str,
+) -> anyhow::Result<String> {
+    if Uuid::parse_str(user).is_ok() {
+        return Ok(user.to_string());
+    }
+    let response = send_request(ctx, Method::GET, base.to_string(), None).await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        anyhow::bail!("Request failed: {status} {body}");
+    }
+    let members: MembersResponse = response.json().await?;
+    members
+        .members
+        .into_iter()
+        .find(|member| {
+            member.source.as_deref() != Some("group") && member.email.eq_ignore_ascii_case(user)
+        })
+        .map(|member| member.user_id)
+        .ok_or_else(|| anyhow::anyhow!("{user} is not a member of this vault"))
+}
@@ -0,0 +1,107 @@
+use clap::{Args, Subcommand};
+
+#[derive(Args)]
+pub struct VaultArgs {
+    #[command(subcommand)]
+    pub command: VaultCommand,
+}
+
+#[derive(Subcommand)]
+pub enum VaultCommand {
+    #[command(about = "Manage who can access a shared vault")]
+    Members(VaultMembersArgs),
+    #[command(about = "Grant vault access to groups")]
+    Groups(VaultGroupsArgs),
+}
+
+#[derive(Args)]
+pub struct VaultMembersArgs {
+    #[arg(long, global = true, help = "Vault name or ID")]
+    pub vault: Option<String>,
+    #[command(subcommand)]
+    pub command: VaultMembersCommand,
+}
+
+#[derive(Subcommand)]
+pub enum VaultMembersCommand {
+    #[command(about = "List vault members and their roles")]
+    List,
+    #[command(about = "Add a user to the vault")]
+    Add(VaultMemberAddArgs),
+    #[command(about = "Change a member's role")]
+    SetRole(VaultMemberSetRoleArgs),
+    #[command(about = "Remove a member from the vault")]
+    Remove(VaultMemberUserArgs),
+}
+
+#[derive(Args)]
+pub struct VaultMemberAddArgs {
+    #[arg(help = "User email or ID")]
+    pub user: String,
+    #[arg(
+        long,
+        default_value = "member",
+        help = "Role (admin, operator, member, readonly)"
+    )]
+    pub role: String,
+}
+
+#[derive(Args)]
+pub struct VaultMemberSetRoleArgs {
+    #[arg(help = "Member email or user ID")]
+    pub user: String,
+    #[arg(help = "New role (admin, operator, member, readonly)")]
+    pub role: String,
+}
+
+#[derive(Args)]
+pub struct VaultMemberUserArgs {
+    #[arg(help = "Member email or user ID")]
+    pub user: String,
+}
+
+#[derive(Args)]
+pub struct VaultGroupsArgs {
+    #[arg(long, global = true, help = "Vault name or ID")]
+    pub vault: Option<String>,
+    #[command(subcommand)]
+    pub command: VaultGroupsCommand,
+}
+
+#[derive(Subcommand)]
+pub enum VaultGroupsCommand {
+    #[command(about = "List groups granted access to the vault")]
+    List,
+    #[command(about = "Grant a group access to the vault")]
+    Add(VaultGroupAddArgs),
+    #[command(about = "Change a group's role")]
+    SetRole(VaultGroupSetRoleArgs),
+    #[command(about = "Revoke a group's access to the vault")]
+    Remove(VaultGroupArgs),
+}
+
+#[derive(Args)]
+pub struct VaultGroupAddArgs {
+    #[arg(help = "Group slug")]
+    pub group: String,
+    #[arg(
+        long,
+        default_value = "member",
+        help = "Role (admin, operator, member, readonly)"
+    )]
+    pub role: String,
+}
+
+#[derive(Args)]
+pub struct VaultGroupSetRoleArgs {
+    #[arg(help = "Group slug")]
+    pub group: String,
+    #[arg(help = "New role (admin, operator, member, readonly)")]
+    pub role: String,
+}
+
+#[derive(Args)]
+pub struct VaultGroupArgs {
+    #[arg(help = "Group slug")]
+    pub group: String,
+}
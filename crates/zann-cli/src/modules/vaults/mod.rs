@@ -0,0 +1,4 @@
+mod actions;
+pub(crate) mod args;
+
+pub(crate) use actions::handle_vault_command;
@@ -30,14 +30,35 @@ pub async fn ensure_access_token_for_context(
         .access_expires_at
         .as_deref()
         .and_then(parse_rfc3339);
-    let needs_refresh = expires_at
-        .map(|expires_at| Utc::now() + ChronoDuration::seconds(REFRESH_SKEW_SECONDS) >= expires_at)
-        .unwrap_or(false);
+    let needs_refresh = entry.access_token.is_empty()
+        || expires_at
+            .map(|expires_at| {
+                Utc::now() + ChronoDuration::seconds(REFRESH_SKEW_SECONDS) >= expires_at
+            })
+            .unwrap_or(false);
 
     if !needs_refresh {
         return Ok(entry.access_token);
     }
 
+    // Service account sessions have no refresh token; the long-lived
+    // `zann_sa_` token is exchanged again instead.
+    if entry.refresh_token.is_none() {
+        if let Some(service_token) = entry.service_account_token.as_deref() {
+            let (access_token, expires_in) =
+                exchange_service_account_token(client, addr, service_token).await?;
+            let new_expires =
+                (Utc::now() + ChronoDuration::seconds(expires_in as i64)).to_rfc3339();
+            if let Some(ctx) = config.contexts.get_mut(context_name) {
+                if let Some(entry) = ctx.tokens.get_mut(token_name) {
+                    entry.access_token = access_token.clone();
+                    entry.access_expires_at = Some(new_expires);
+                }
+            }
+            return Ok(access_token);
+        }
+    }
+
     let refresh = entry
         .refresh_token
         .clone()
@@ -81,3 +102,30 @@ pub async fn ensure_access_token_for_context(
     }
     Ok(auth.access_token)
 }
+
+async fn exchange_service_account_token(
+    client: &reqwest::Client,
+    addr: &str,
+    service_token: &str,
+) -> Result<(String, u64), String> {
+    let url = format!("{}/v1/auth/service-account", addr.trim_end_matches('/'));
+    let payload = serde_json::json!({ "token": service_token });
+    let response = client
+        .post(url)
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("service account login failed: {status} {body}"));
+    }
+    #[derive(serde::Deserialize)]
+    struct AuthResponse {
+        access_token: String,
+        expires_in: u64,
+    }
+    let auth: AuthResponse = response.json().await.map_err(|err| err.to_string())?;
+    Ok((auth.access_token, auth.expires_in))
+}
@@ -153,6 +153,17 @@ impl VaultMemberRole {
     pub const fn as_i32(self) -> i32 {
         self as i32
     }
+
+    /// Lowercase name accepted by [`std::str::FromStr`].
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Admin => "admin",
+            Self::Operator => "operator",
+            Self::Member => "member",
+            Self::Readonly => "readonly",
+        }
+    }
 }
 
 impl std::str::FromStr for VaultMemberRole {
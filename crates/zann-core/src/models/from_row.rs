@@ -136,6 +136,239 @@ impl_from_row!(ServiceAccount, row => {
     }
 );
 
+impl_from_row!(Invite, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            email: row.try_get("email")?,
+            token_hash: row.try_get("token_hash")?,
+            group_ids: row.try_get("group_ids")?,
+            vault_roles: row.try_get("vault_roles")?,
+            created_by_user_id: row.try_get("created_by_user_id")?,
+            expires_at: row.try_get("expires_at")?,
+            created_at: row.try_get("created_at")?,
+            accepted_at: row.try_get("accepted_at")?,
+            accepted_user_id: row.try_get("accepted_user_id")?,
+            revoked_at: row.try_get("revoked_at")?,
+        })
+    }
+);
+
+impl_from_row!(ItemRotationSchedule, row => {
+        Ok(Self {
+            item_id: row.try_get("item_id")?,
+            vault_id: row.try_get("vault_id")?,
+            schedule: row.try_get("schedule")?,
+            max_age_seconds: row.try_get("max_age_seconds")?,
+            password_policy: row.try_get("password_policy")?,
+            last_rotated_at: row.try_get("last_rotated_at")?,
+            last_started_at: row.try_get("last_started_at")?,
+            last_error: row.try_get("last_error")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+);
+
+impl_from_row!(ItemRotationHook, row => {
+        Ok(Self {
+            item_id: row.try_get("item_id")?,
+            vault_id: row.try_get("vault_id")?,
+            kind: row.try_get("kind")?,
+            config_enc: row.try_get("config_enc")?,
+            verify: row.try_get("verify")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+);
+
+impl_from_row!(DynamicSecret, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            vault_id: row.try_get("vault_id")?,
+            path: row.try_get("path")?,
+            kind: row.try_get("kind")?,
+            config_enc: row.try_get("config_enc")?,
+            default_ttl_seconds: row.try_get("default_ttl_seconds")?,
+            max_ttl_seconds: row.try_get("max_ttl_seconds")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+);
+
+impl_from_row!(DynamicLease, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            secret_id: row.try_get("secret_id")?,
+            vault_id: row.try_get("vault_id")?,
+            username: row.try_get("username")?,
+            issued_to_user_id: row.try_get("issued_to_user_id")?,
+            issued_to_service_account_id: row.try_get("issued_to_service_account_id")?,
+            issued_at: row.try_get("issued_at")?,
+            expires_at: row.try_get("expires_at")?,
+            revoked_at: row.try_get("revoked_at")?,
+            last_error: row.try_get("last_error")?,
+        })
+    }
+);
+
+impl_from_row!(SecretLease, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            item_id: row.try_get("item_id")?,
+            vault_id: row.try_get("vault_id")?,
+            item_version: row.try_get("item_version")?,
+            holder_user_id: row.try_get("holder_user_id")?,
+            holder_service_account_id: row.try_get("holder_service_account_id")?,
+            holder_device_id: row.try_get("holder_device_id")?,
+            holder_email: row.try_get("holder_email")?,
+            holder_name: row.try_get("holder_name")?,
+            issued_at: row.try_get("issued_at")?,
+            renewed_at: row.try_get("renewed_at")?,
+            expires_at: row.try_get("expires_at")?,
+            revoked_at: row.try_get("revoked_at")?,
+        })
+    }
+);
+
+impl_from_row!(TransitKey, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            vault_id: row.try_get("vault_id")?,
+            name: row.try_get("name")?,
+            latest_version: row.try_get("latest_version")?,
+            min_decryption_version: row.try_get("min_decryption_version")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+);
+
+impl_from_row!(TransitKeyVersion, row => {
+        Ok(Self {
+            key_id: row.try_get("key_id")?,
+            version: row.try_get("version")?,
+            key_enc: row.try_get("key_enc")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+);
+
+impl_from_row!(SshCa, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            vault_id: row.try_get("vault_id")?,
+            public_key: row.try_get("public_key")?,
+            private_key_enc: row.try_get("private_key_enc")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+);
+
+impl_from_row!(SshSigningRole, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            vault_id: row.try_get("vault_id")?,
+            name: row.try_get("name")?,
+            cert_type: row.try_get("cert_type")?,
+            allowed_principals: row.try_get("allowed_principals")?,
+            default_principals: row.try_get("default_principals")?,
+            allowed_extensions: row.try_get("allowed_extensions")?,
+            default_extensions: row.try_get("default_extensions")?,
+            default_ttl_seconds: row.try_get("default_ttl_seconds")?,
+            max_ttl_seconds: row.try_get("max_ttl_seconds")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+);
+
+impl_from_row!(PkiCa, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            vault_id: row.try_get("vault_id")?,
+            kind: row.try_get("kind")?,
+            common_name: row.try_get("common_name")?,
+            certificate_pem: row.try_get("certificate_pem")?,
+            private_key_enc: row.try_get("private_key_enc")?,
+            serial: row.try_get("serial")?,
+            not_after: row.try_get("not_after")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+);
+
+impl_from_row!(PkiRole, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            vault_id: row.try_get("vault_id")?,
+            name: row.try_get("name")?,
+            allowed_domains: row.try_get("allowed_domains")?,
+            allow_subdomains: row.try_get("allow_subdomains")?,
+            allow_ip_sans: row.try_get("allow_ip_sans")?,
+            key_type: row.try_get("key_type")?,
+            server_auth: row.try_get("server_auth")?,
+            client_auth: row.try_get("client_auth")?,
+            default_ttl_seconds: row.try_get("default_ttl_seconds")?,
+            max_ttl_seconds: row.try_get("max_ttl_seconds")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+);
+
+impl_from_row!(PkiCertificate, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            vault_id: row.try_get("vault_id")?,
+            issuer_id: row.try_get("issuer_id")?,
+            serial: row.try_get("serial")?,
+            role_name: row.try_get("role_name")?,
+            common_name: row.try_get("common_name")?,
+            sans: row.try_get("sans")?,
+            certificate_pem: row.try_get("certificate_pem")?,
+            not_before: row.try_get("not_before")?,
+            not_after: row.try_get("not_after")?,
+            revoked_at: row.try_get("revoked_at")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+);
+
+impl_from_row!(VaultWebhook, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            vault_id: row.try_get("vault_id")?,
+            url: row.try_get("url")?,
+            path_prefix: row.try_get("path_prefix")?,
+            secret_enc: row.try_get("secret_enc")?,
+            enabled: row.try_get("enabled")?,
+            last_seq: row.try_get("last_seq")?,
+            created_by_user_id: row.try_get("created_by_user_id")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+);
+
+impl_from_row!(WebhookDelivery, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            webhook_id: row.try_get("webhook_id")?,
+            seq: row.try_get("seq")?,
+            payload: row.try_get("payload")?,
+            status: row.try_get("status")?,
+            attempts: row.try_get("attempts")?,
+            next_attempt_at: row.try_get("next_attempt_at")?,
+            response_status: row.try_get("response_status")?,
+            last_error: row.try_get("last_error")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+);
+
 impl_from_row!(ServiceAccountSession, row => {
         Ok(Self {
             id: row.try_get("id")?,
@@ -194,6 +427,17 @@ impl_from_row!(VaultMember, row => {
     }
 );
 
+impl_from_row!(VaultGroupGrant, row => {
+        let role: i16 = row.try_get("role")?;
+        Ok(Self {
+            vault_id: row.try_get("vault_id")?,
+            group_id: row.try_get("group_id")?,
+            role: parse_enum(role)?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+);
+
 impl_from_row!(Item, row => {
         let sync_status: i16 = row.try_get("sync_status")?;
         Ok(Self {
@@ -213,6 +457,7 @@ impl_from_row!(Item, row => {
             deleted_at: row.try_get("deleted_at")?,
             deleted_by_user_id: row.try_get("deleted_by_user_id")?,
             deleted_by_device_id: row.try_get("deleted_by_device_id")?,
+            expires_at: row.try_get("expires_at")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
         })
@@ -280,3 +525,39 @@ impl_from_row!(Change, row => {
         })
     }
 );
+
+impl_from_row!(AuditEvent, row => {
+        Ok(Self {
+            seq: row.try_get("seq")?,
+            id: row.try_get("id")?,
+            actor_user_id: row.try_get("actor_user_id")?,
+            actor_email: row.try_get("actor_email")?,
+            actor_device_id: row.try_get("actor_device_id")?,
+            actor_service_account_id: row.try_get("actor_service_account_id")?,
+            auth_source: row.try_get("auth_source")?,
+            category: row.try_get("category")?,
+            action: row.try_get("action")?,
+            result: row.try_get("result")?,
+            vault_id: row.try_get("vault_id")?,
+            item_path: row.try_get("item_path")?,
+            target: row.try_get("target")?,
+            detail: row.try_get("detail")?,
+            client_ip: row.try_get("client_ip")?,
+            prev_hash: row.try_get("prev_hash")?,
+            hash: row.try_get("hash")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+);
+
+impl_from_row!(AuditCheckpoint, row => {
+        Ok(Self {
+            id: row.try_get("id")?,
+            seq: row.try_get("seq")?,
+            hash: row.try_get("hash")?,
+            public_key: row.try_get("public_key")?,
+            signature: row.try_get("signature")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+);
@@ -97,6 +97,29 @@ pub struct ServiceAccount {
     pub revoked_at: Option<DateTime<Utc>>,
 }
 
+/// A pending invitation to register with internal auth. Only the hash of the
+/// token is stored; groups and vault roles are applied when it is accepted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invite {
+    pub id: Uuid,
+    pub email: String,
+    pub token_hash: String,
+    pub group_ids: Json<Vec<Uuid>>,
+    pub vault_roles: Json<Vec<InviteVaultRole>>,
+    pub created_by_user_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub accepted_user_id: Option<Uuid>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteVaultRole {
+    pub vault_id: Uuid,
+    pub role: VaultMemberRole,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceAccountSession {
     pub id: Uuid,
@@ -143,6 +166,15 @@ pub struct VaultMember {
     pub created_at: DateTime<Utc>,
 }
 
+/// A vault role granted to every member of a group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultGroupGrant {
+    pub vault_id: Uuid,
+    pub group_id: Uuid,
+    pub role: VaultMemberRole,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub id: Uuid,
@@ -161,6 +193,247 @@ pub struct Item {
     pub deleted_at: Option<DateTime<Utc>>,
     pub deleted_by_user_id: Option<Uuid>,
     pub deleted_by_device_id: Option<Uuid>,
+    /// When the secret stops being valid upstream, e.g. a vendor API key's
+    /// end date. Informational; the item stays readable after it passes.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Automatic rotation schedule for a shared item. The item is due once
+/// `last_rotated_at + max_age_seconds` has passed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemRotationSchedule {
+    pub item_id: Uuid,
+    pub vault_id: Uuid,
+    /// Schedule as entered, e.g. `90d` or `@monthly`.
+    pub schedule: String,
+    pub max_age_seconds: i64,
+    /// Name of the password policy used for generated candidates.
+    pub password_policy: Option<String>,
+    pub last_rotated_at: DateTime<Utc>,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ItemRotationSchedule {
+    #[must_use]
+    pub fn due_at(&self) -> DateTime<Utc> {
+        self.last_rotated_at + chrono::Duration::seconds(self.max_age_seconds)
+    }
+}
+
+/// Executor that applies a rotated credential to the system it belongs to.
+/// `config_enc` holds the kind-specific settings (connection strings, signing
+/// secrets) encrypted with the vault key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemRotationHook {
+    pub item_id: Uuid,
+    pub vault_id: Uuid,
+    /// `postgres` or `webhook`.
+    pub kind: String,
+    pub config_enc: Vec<u8>,
+    /// Check the new credential against the target after commit.
+    pub verify: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Configuration for issuing short-lived credentials at a vault path.
+/// `config_enc` holds the connection settings and SQL templates encrypted
+/// with the vault key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicSecret {
+    pub id: Uuid,
+    pub vault_id: Uuid,
+    pub path: String,
+    /// Only `postgres` for now.
+    pub kind: String,
+    pub config_enc: Vec<u8>,
+    pub default_ttl_seconds: i64,
+    pub max_ttl_seconds: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A credential issued from a [`DynamicSecret`]. The password is never
+/// stored; only the generated username is kept so it can be revoked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicLease {
+    pub id: Uuid,
+    pub secret_id: Uuid,
+    pub vault_id: Uuid,
+    pub username: String,
+    pub issued_to_user_id: Option<Uuid>,
+    pub issued_to_service_account_id: Option<Uuid>,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// Last revocation failure; the reaper keeps retrying until it succeeds.
+    pub last_error: Option<String>,
+}
+
+/// A time-bounded read of a secret item. Active while `revoked_at` is unset
+/// and `expires_at` is in the future.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretLease {
+    pub id: Uuid,
+    pub item_id: Uuid,
+    pub vault_id: Uuid,
+    /// Item version that was handed out with the lease.
+    pub item_version: i64,
+    pub holder_user_id: Uuid,
+    pub holder_service_account_id: Option<Uuid>,
+    pub holder_device_id: Option<Uuid>,
+    pub holder_email: String,
+    pub holder_name: Option<String>,
+    pub issued_at: DateTime<Utc>,
+    pub renewed_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// A named transit encryption key. Data encrypted with it records the key
+/// version; versions below `min_decryption_version` are retired.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitKey {
+    pub id: Uuid,
+    pub vault_id: Uuid,
+    pub name: String,
+    pub latest_version: i32,
+    pub min_decryption_version: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Key material for one version of a [`TransitKey`], encrypted with the vault key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransitKeyVersion {
+    pub key_id: Uuid,
+    pub version: i32,
+    pub key_enc: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// SSH certificate authority of a shared vault. The private key is encrypted
+/// with the vault key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshCa {
+    pub id: Uuid,
+    pub vault_id: Uuid,
+    /// OpenSSH-encoded public key, as used in `TrustedUserCAKeys`.
+    pub public_key: String,
+    pub private_key_enc: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Limits applied when the vault CA signs a key under this role.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SshSigningRole {
+    pub id: Uuid,
+    pub vault_id: Uuid,
+    pub name: String,
+    /// `user` or `host`.
+    pub cert_type: String,
+    pub allowed_principals: Json<Vec<String>>,
+    pub default_principals: Json<Vec<String>>,
+    pub allowed_extensions: Json<Vec<String>>,
+    pub default_extensions: Json<Vec<String>>,
+    pub default_ttl_seconds: i64,
+    pub max_ttl_seconds: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// X.509 certificate authority of a shared vault, either the root or the
+/// intermediate that issues leaf certificates. The private key is a PKCS#8 PEM
+/// encrypted with the vault key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PkiCa {
+    pub id: Uuid,
+    pub vault_id: Uuid,
+    /// `root` or `intermediate`.
+    pub kind: String,
+    pub common_name: String,
+    pub certificate_pem: String,
+    pub private_key_enc: Vec<u8>,
+    /// Hex-encoded certificate serial.
+    pub serial: String,
+    pub not_after: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Template bounding the leaf certificates a vault CA issues.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PkiRole {
+    pub id: Uuid,
+    pub vault_id: Uuid,
+    pub name: String,
+    pub allowed_domains: Json<Vec<String>>,
+    pub allow_subdomains: bool,
+    pub allow_ip_sans: bool,
+    /// `ec-p256`, `ec-p384` or `ed25519`.
+    pub key_type: String,
+    pub server_auth: bool,
+    pub client_auth: bool,
+    pub default_ttl_seconds: i64,
+    pub max_ttl_seconds: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Leaf certificate issued by a vault CA. Only the certificate is kept; the
+/// private key is returned to the caller once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PkiCertificate {
+    pub id: Uuid,
+    pub vault_id: Uuid,
+    pub issuer_id: Uuid,
+    /// Hex-encoded certificate serial.
+    pub serial: String,
+    pub role_name: String,
+    pub common_name: String,
+    pub sans: Json<Vec<String>>,
+    pub certificate_pem: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Subscription that POSTs signed change notices for a vault to `url`. Only
+/// changes under `path_prefix` are sent; `last_seq` is the last `changes.seq`
+/// already queued. `secret_enc` is the signing secret encrypted with the vault key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultWebhook {
+    pub id: Uuid,
+    pub vault_id: Uuid,
+    pub url: String,
+    pub path_prefix: Option<String>,
+    pub secret_enc: Vec<u8>,
+    pub enabled: bool,
+    pub last_seq: i64,
+    pub created_by_user_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One change notice for a [`VaultWebhook`]. `payload` is the exact body sent,
+/// so a redelivery repeats it; it never contains item values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub seq: i64,
+    pub payload: Json<serde_json::Value>,
+    /// `pending`, `delivered` or `failed`.
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -222,3 +495,35 @@ pub struct Change {
     pub device_id: Uuid,
     pub created_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub seq: i64,
+    pub id: Uuid,
+    pub actor_user_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub actor_device_id: Option<Uuid>,
+    pub actor_service_account_id: Option<Uuid>,
+    pub auth_source: Option<String>,
+    pub category: String,
+    pub action: String,
+    pub result: String,
+    pub vault_id: Option<Uuid>,
+    pub item_path: Option<String>,
+    pub target: Option<String>,
+    pub detail: Option<String>,
+    pub client_ip: Option<String>,
+    pub prev_hash: Option<String>,
+    pub hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditCheckpoint {
+    pub id: Uuid,
+    pub seq: i64,
+    pub hash: String,
+    pub public_key: String,
+    pub signature: String,
+    pub created_at: DateTime<Utc>,
+}
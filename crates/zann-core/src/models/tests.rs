@@ -17,6 +17,19 @@ fn enum_roundtrips() {
         VaultMemberRole::Operator
     );
     assert_eq!(VaultMemberRole::Operator.as_i32(), 2);
+    for role in [
+        VaultMemberRole::Admin,
+        VaultMemberRole::Operator,
+        VaultMemberRole::Member,
+        VaultMemberRole::Readonly,
+    ] {
+        assert_eq!(
+            role.as_str()
+                .parse::<VaultMemberRole>()
+                .expect("valid vault member role"),
+            role
+        );
+    }
 
     assert_eq!(
         VaultEncryptionType::try_from(1).expect("valid vault encryption type"),
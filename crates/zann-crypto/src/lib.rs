@@ -8,6 +8,7 @@
 pub mod crypto;
 pub mod passwords;
 pub mod secrets;
+pub mod shamir;
 pub mod tokens;
 pub mod vault_crypto;
 
@@ -0,0 +1,208 @@
+//! Shamir secret sharing over GF(2^8), as used to split the server master key.
+//!
+//! Each share is `x || y_0 || ... || y_{n-1}`: a non-zero evaluation point
+//! followed by one polynomial evaluation per secret byte.
+
+use rand::rngs::OsRng;
+use rand::RngCore;
+use zeroize::Zeroize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShamirError {
+    /// Threshold must be at least 2 and no larger than the share count.
+    InvalidThreshold,
+    EmptySecret,
+    NotEnoughShares,
+    /// A share is empty, has a zero index or differs in length from the others.
+    InvalidShare,
+    DuplicateShare,
+}
+
+impl std::fmt::Display for ShamirError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidThreshold => write!(f, "invalid share threshold"),
+            Self::EmptySecret => write!(f, "secret is empty"),
+            Self::NotEnoughShares => write!(f, "not enough shares"),
+            Self::InvalidShare => write!(f, "invalid share"),
+            Self::DuplicateShare => write!(f, "duplicate share"),
+        }
+    }
+}
+
+impl std::error::Error for ShamirError {}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1, without
+/// data-dependent branches.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    product
+}
+
+/// Multiplicative inverse as a^254; zero maps to zero.
+fn gf_inv(a: u8) -> u8 {
+    let mut result = 1u8;
+    let mut base = a;
+    let mut exp = 254u8;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Splits `secret` into `shares` shares, any `threshold` of which recover it.
+pub fn split_secret(secret: &[u8], threshold: u8, shares: u8) -> Result<Vec<Vec<u8>>, ShamirError> {
+    if threshold < 2 || threshold > shares {
+        return Err(ShamirError::InvalidThreshold);
+    }
+    if secret.is_empty() {
+        return Err(ShamirError::EmptySecret);
+    }
+
+    let mut output: Vec<Vec<u8>> = (1..=shares)
+        .map(|x| {
+            let mut share = Vec::with_capacity(secret.len() + 1);
+            share.push(x);
+            share
+        })
+        .collect();
+    let mut coefficients = vec![0u8; usize::from(threshold)];
+    for &byte in secret {
+        coefficients[0] = byte;
+        OsRng.fill_bytes(&mut coefficients[1..]);
+        for share in &mut output {
+            let x = share[0];
+            // Horner evaluation from the highest coefficient down.
+            let y = coefficients
+                .iter()
+                .rev()
+                .fold(0u8, |acc, &coefficient| gf_mul(acc, x) ^ coefficient);
+            share.push(y);
+        }
+    }
+    coefficients.zeroize();
+    Ok(output)
+}
+
+/// Recovers the secret from shares produced by [`split_secret`]. Passing fewer
+/// shares than the threshold yields an unrelated value, so callers must verify
+/// the result.
+pub fn combine_shares(shares: &[Vec<u8>]) -> Result<Vec<u8>, ShamirError> {
+    if shares.len() < 2 {
+        return Err(ShamirError::NotEnoughShares);
+    }
+    let len = shares[0].len();
+    if len < 2 {
+        return Err(ShamirError::InvalidShare);
+    }
+    for (i, share) in shares.iter().enumerate() {
+        if share.len() != len || share[0] == 0 {
+            return Err(ShamirError::InvalidShare);
+        }
+        if shares[..i].iter().any(|other| other[0] == share[0]) {
+            return Err(ShamirError::DuplicateShare);
+        }
+    }
+
+    // Lagrange basis at x = 0: l_i = prod_{j != i} x_j / (x_j - x_i).
+    let basis: Vec<u8> = shares
+        .iter()
+        .enumerate()
+        .map(|(i, share)| {
+            let xi = share[0];
+            let (numerator, denominator) = shares
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold((1u8, 1u8), |(num, den), (_, other)| {
+                    (gf_mul(num, other[0]), gf_mul(den, other[0] ^ xi))
+                });
+            gf_mul(numerator, gf_inv(denominator))
+        })
+        .collect();
+
+    let secret = (1..len)
+        .map(|position| {
+            shares
+                .iter()
+                .zip(&basis)
+                .fold(0u8, |acc, (share, &l)| acc ^ gf_mul(share[position], l))
+        })
+        .collect();
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gf_inverse_roundtrip() {
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1, "a={a}");
+        }
+    }
+
+    #[test]
+    fn any_threshold_subset_recovers_secret() {
+        let secret = b"0123456789abcdef0123456789abcdef".to_vec();
+        let shares = split_secret(&secret, 3, 5).expect("split");
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|share| share.len() == secret.len() + 1));
+
+        for a in 0..5 {
+            for b in (a + 1)..5 {
+                for c in (b + 1)..5 {
+                    let subset = vec![shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                    assert_eq!(combine_shares(&subset).expect("combine"), secret);
+                }
+            }
+        }
+        assert_eq!(combine_shares(&shares).expect("combine all"), secret);
+    }
+
+    #[test]
+    fn below_threshold_does_not_recover_secret() {
+        let secret = [7u8; 32];
+        let shares = split_secret(&secret, 3, 5).expect("split");
+        let recovered = combine_shares(&shares[..2]).expect("combine");
+        assert_ne!(recovered, secret.to_vec());
+    }
+
+    #[test]
+    fn rejects_bad_parameters_and_shares() {
+        assert_eq!(
+            split_secret(b"secret", 1, 3),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(
+            split_secret(b"secret", 4, 3),
+            Err(ShamirError::InvalidThreshold)
+        );
+        assert_eq!(split_secret(b"", 2, 3), Err(ShamirError::EmptySecret));
+
+        let shares = split_secret(b"secret", 2, 3).expect("split");
+        assert_eq!(
+            combine_shares(&shares[..1]),
+            Err(ShamirError::NotEnoughShares)
+        );
+        assert_eq!(
+            combine_shares(&[shares[0].clone(), shares[0].clone()]),
+            Err(ShamirError::DuplicateShare)
+        );
+        assert_eq!(
+            combine_shares(&[shares[0].clone(), shares[1][..3].to_vec()]),
+            Err(ShamirError::InvalidShare)
+        );
+    }
+}
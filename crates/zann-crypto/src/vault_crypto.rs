@@ -63,6 +63,64 @@ pub fn rotation_candidate_aad(vault_id: Uuid, item_id: Uuid) -> Vec<u8> {
     aad
 }
 
+#[must_use]
+pub fn rotation_hook_aad(vault_id: Uuid, item_id: Uuid) -> Vec<u8> {
+    let mut aad = b"zann:rotation_hook:v1".to_vec();
+    aad.extend_from_slice(vault_id.as_bytes());
+    aad.extend_from_slice(item_id.as_bytes());
+    aad
+}
+
+pub fn dynamic_secret_aad(vault_id: Uuid, secret_id: Uuid) -> Vec<u8> {
+    let mut aad = b"zann:dynamic_secret:v1".to_vec();
+    aad.extend_from_slice(vault_id.as_bytes());
+    aad.extend_from_slice(secret_id.as_bytes());
+    aad
+}
+
+#[must_use]
+pub fn transit_key_aad(vault_id: Uuid, key_id: Uuid, version: i32) -> Vec<u8> {
+    let mut aad = b"zann:transit_key:v1".to_vec();
+    aad.extend_from_slice(vault_id.as_bytes());
+    aad.extend_from_slice(key_id.as_bytes());
+    aad.extend_from_slice(&version.to_be_bytes());
+    aad
+}
+
+/// AAD for data encrypted with a transit key. `context` is caller-supplied and
+/// must be presented again to decrypt.
+#[must_use]
+pub fn transit_data_aad(key_id: Uuid, context: &[u8]) -> Vec<u8> {
+    let mut aad = b"zann:transit:v1".to_vec();
+    aad.extend_from_slice(key_id.as_bytes());
+    aad.extend_from_slice(context);
+    aad
+}
+
+#[must_use]
+pub fn ssh_ca_key_aad(vault_id: Uuid, ca_id: Uuid) -> Vec<u8> {
+    let mut aad = b"zann:ssh_ca_key:v1".to_vec();
+    aad.extend_from_slice(vault_id.as_bytes());
+    aad.extend_from_slice(ca_id.as_bytes());
+    aad
+}
+
+#[must_use]
+pub fn pki_ca_key_aad(vault_id: Uuid, ca_id: Uuid) -> Vec<u8> {
+    let mut aad = b"zann:pki_ca_key:v1".to_vec();
+    aad.extend_from_slice(vault_id.as_bytes());
+    aad.extend_from_slice(ca_id.as_bytes());
+    aad
+}
+
+#[must_use]
+pub fn webhook_secret_aad(vault_id: Uuid, webhook_id: Uuid) -> Vec<u8> {
+    let mut aad = b"zann:webhook_secret:v1".to_vec();
+    aad.extend_from_slice(vault_id.as_bytes());
+    aad.extend_from_slice(webhook_id.as_bytes());
+    aad
+}
+
 #[instrument(level = "debug", skip(master_key, vault_key), fields(vault_id = %vault_id))]
 pub fn encrypt_vault_key(
     master_key: &SecretKey,
@@ -161,6 +219,212 @@ pub fn decrypt_rotation_candidate(
     decrypt_blob(vault_key, &blob, &aad).map_err(|_| VaultCryptoError::DecryptFailed)
 }
 
+#[instrument(
+    level = "debug",
+    skip(vault_key, config),
+    fields(vault_id = %vault_id, item_id = %item_id)
+)]
+pub fn encrypt_rotation_hook(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    item_id: Uuid,
+    config: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let aad = rotation_hook_aad(vault_id, item_id);
+    let blob =
+        encrypt_blob(vault_key, config, &aad).map_err(|_| VaultCryptoError::EncryptFailed)?;
+    Ok(blob.to_bytes())
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, config_enc),
+    fields(vault_id = %vault_id, item_id = %item_id)
+)]
+pub fn decrypt_rotation_hook(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    item_id: Uuid,
+    config_enc: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let blob = EncryptedBlob::from_bytes(config_enc).map_err(|_| VaultCryptoError::InvalidBlob)?;
+    let aad = rotation_hook_aad(vault_id, item_id);
+    decrypt_blob(vault_key, &blob, &aad).map_err(|_| VaultCryptoError::DecryptFailed)
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, config),
+    fields(vault_id = %vault_id, secret_id = %secret_id)
+)]
+pub fn encrypt_dynamic_secret(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    secret_id: Uuid,
+    config: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let aad = dynamic_secret_aad(vault_id, secret_id);
+    let blob =
+        encrypt_blob(vault_key, config, &aad).map_err(|_| VaultCryptoError::EncryptFailed)?;
+    Ok(blob.to_bytes())
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, config_enc),
+    fields(vault_id = %vault_id, secret_id = %secret_id)
+)]
+pub fn decrypt_dynamic_secret(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    secret_id: Uuid,
+    config_enc: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let blob = EncryptedBlob::from_bytes(config_enc).map_err(|_| VaultCryptoError::InvalidBlob)?;
+    let aad = dynamic_secret_aad(vault_id, secret_id);
+    decrypt_blob(vault_key, &blob, &aad).map_err(|_| VaultCryptoError::DecryptFailed)
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, transit_key),
+    fields(vault_id = %vault_id, key_id = %key_id, version)
+)]
+pub fn encrypt_transit_key(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    key_id: Uuid,
+    version: i32,
+    transit_key: &SecretKey,
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let aad = transit_key_aad(vault_id, key_id, version);
+    let blob = encrypt_blob(vault_key, transit_key.as_bytes(), &aad)
+        .map_err(|_| VaultCryptoError::EncryptFailed)?;
+    Ok(blob.to_bytes())
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, key_enc),
+    fields(vault_id = %vault_id, key_id = %key_id, version)
+)]
+pub fn decrypt_transit_key(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    key_id: Uuid,
+    version: i32,
+    key_enc: &[u8],
+) -> Result<SecretKey, VaultCryptoError> {
+    let blob = EncryptedBlob::from_bytes(key_enc).map_err(|_| VaultCryptoError::InvalidBlob)?;
+    let aad = transit_key_aad(vault_id, key_id, version);
+    let key_bytes =
+        decrypt_blob(vault_key, &blob, &aad).map_err(|_| VaultCryptoError::DecryptFailed)?;
+    let key: [u8; 32] = key_bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| VaultCryptoError::InvalidKeyLength)?;
+    Ok(SecretKey::from_bytes(key))
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, private_key),
+    fields(vault_id = %vault_id, ca_id = %ca_id)
+)]
+pub fn encrypt_ssh_ca_key(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    ca_id: Uuid,
+    private_key: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let aad = ssh_ca_key_aad(vault_id, ca_id);
+    let blob =
+        encrypt_blob(vault_key, private_key, &aad).map_err(|_| VaultCryptoError::EncryptFailed)?;
+    Ok(blob.to_bytes())
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, key_enc),
+    fields(vault_id = %vault_id, ca_id = %ca_id)
+)]
+pub fn decrypt_ssh_ca_key(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    ca_id: Uuid,
+    key_enc: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let blob = EncryptedBlob::from_bytes(key_enc).map_err(|_| VaultCryptoError::InvalidBlob)?;
+    let aad = ssh_ca_key_aad(vault_id, ca_id);
+    decrypt_blob(vault_key, &blob, &aad).map_err(|_| VaultCryptoError::DecryptFailed)
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, private_key),
+    fields(vault_id = %vault_id, ca_id = %ca_id)
+)]
+pub fn encrypt_pki_ca_key(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    ca_id: Uuid,
+    private_key: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let aad = pki_ca_key_aad(vault_id, ca_id);
+    let blob =
+        encrypt_blob(vault_key, private_key, &aad).map_err(|_| VaultCryptoError::EncryptFailed)?;
+    Ok(blob.to_bytes())
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, key_enc),
+    fields(vault_id = %vault_id, ca_id = %ca_id)
+)]
+pub fn decrypt_pki_ca_key(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    ca_id: Uuid,
+    key_enc: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let blob = EncryptedBlob::from_bytes(key_enc).map_err(|_| VaultCryptoError::InvalidBlob)?;
+    let aad = pki_ca_key_aad(vault_id, ca_id);
+    decrypt_blob(vault_key, &blob, &aad).map_err(|_| VaultCryptoError::DecryptFailed)
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, secret),
+    fields(vault_id = %vault_id, webhook_id = %webhook_id)
+)]
+pub fn encrypt_webhook_secret(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    webhook_id: Uuid,
+    secret: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let aad = webhook_secret_aad(vault_id, webhook_id);
+    let blob =
+        encrypt_blob(vault_key, secret, &aad).map_err(|_| VaultCryptoError::EncryptFailed)?;
+    Ok(blob.to_bytes())
+}
+
+#[instrument(
+    level = "debug",
+    skip(vault_key, secret_enc),
+    fields(vault_id = %vault_id, webhook_id = %webhook_id)
+)]
+pub fn decrypt_webhook_secret(
+    vault_key: &SecretKey,
+    vault_id: Uuid,
+    webhook_id: Uuid,
+    secret_enc: &[u8],
+) -> Result<Vec<u8>, VaultCryptoError> {
+    let blob = EncryptedBlob::from_bytes(secret_enc).map_err(|_| VaultCryptoError::InvalidBlob)?;
+    let aad = webhook_secret_aad(vault_id, webhook_id);
+    decrypt_blob(vault_key, &blob, &aad).map_err(|_| VaultCryptoError::DecryptFailed)
+}
+
 #[instrument(
     level = "debug",
     skip(vault_key, payload),
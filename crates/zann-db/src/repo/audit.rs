@@ -0,0 +1,361 @@
+use super::prelude::*;
+use sqlx_postgres::PgConnection;
+use tracing::{instrument, Span};
+use zann_core::{AuditCheckpoint, AuditEvent};
+
+/// Position of the newest chained audit record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditChainHead {
+    pub seq: i64,
+    pub hash: String,
+}
+
+/// Filters applied by [`AuditEventRepo::list`]. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventFilter {
+    pub actor_user_id: Option<Uuid>,
+    pub actor_email: Option<String>,
+    pub vault_id: Option<Uuid>,
+    pub item_path: Option<String>,
+    pub action: Option<String>,
+    pub result: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    /// Only return events with `seq` strictly below this value (newest-first paging).
+    pub before_seq: Option<i64>,
+}
+
+pub struct AuditEventRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> AuditEventRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Locks the chain head row until the surrounding transaction ends, creating it with
+    /// `genesis` on first use. Appends must hold this lock so records link in `seq` order.
+    #[instrument(
+        level = "debug",
+        skip(conn, genesis),
+        fields(
+            db.system = "postgresql",
+            db.operation = "SELECT",
+            db.query = "audit_chain_head.lock"
+        )
+    )]
+    pub async fn lock_chain_head(
+        conn: &mut PgConnection,
+        genesis: &str,
+    ) -> Result<AuditChainHead, sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO audit_chain_head (id, seq, hash)
+            VALUES (1, 0, $1)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+            genesis
+        )
+        .execute(&mut *conn)
+        .await?;
+        let row = query!(
+            r#"
+            SELECT seq, hash
+            FROM audit_chain_head
+            WHERE id = 1
+            FOR UPDATE
+            "#
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        Ok(AuditChainHead {
+            seq: row.try_get("seq")?,
+            hash: row.try_get("hash")?,
+        })
+    }
+
+    #[instrument(
+        level = "debug",
+        skip(conn, head),
+        fields(
+            seq = head.seq,
+            db.system = "postgresql",
+            db.operation = "UPDATE",
+            db.query = "audit_chain_head.advance"
+        )
+    )]
+    pub async fn advance_chain_head(
+        conn: &mut PgConnection,
+        head: &AuditChainHead,
+    ) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE audit_chain_head
+            SET seq = $1, hash = $2
+            WHERE id = 1
+            "#,
+            head.seq,
+            head.hash.as_str()
+        )
+        .execute(conn)
+        .await
+        .map(|_| ())
+    }
+
+    #[instrument(
+        level = "debug",
+        skip(self),
+        fields(
+            db.system = "postgresql",
+            db.operation = "SELECT",
+            db.query = "audit_chain_head.get"
+        )
+    )]
+    pub async fn chain_head(&self) -> Result<Option<AuditChainHead>, sqlx_core::Error> {
+        let row = query!(
+            r#"
+            SELECT seq, hash
+            FROM audit_chain_head
+            WHERE id = 1
+            "#
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        row.map(|row| {
+            Ok(AuditChainHead {
+                seq: row.try_get("seq")?,
+                hash: row.try_get("hash")?,
+            })
+        })
+        .transpose()
+    }
+
+    /// Inserts the event on an open connection so it commits or rolls back with the
+    /// caller's transaction.
+    #[instrument(
+        level = "debug",
+        skip(conn, event),
+        fields(
+            category = %event.category,
+            action = %event.action,
+            db.system = "postgresql",
+            db.operation = "INSERT",
+            db.query = "audit_events.create_in"
+        )
+    )]
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        event: &AuditEvent,
+    ) -> Result<i64, sqlx_core::Error> {
+        let row = query!(
+            r#"
+            INSERT INTO audit_events (
+                id, actor_user_id, actor_email, actor_device_id, actor_service_account_id,
+                auth_source, category, action, result, vault_id, item_path, target, detail,
+                client_ip, prev_hash, hash, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+            RETURNING seq
+            "#,
+            event.id,
+            event.actor_user_id,
+            event.actor_email.as_deref(),
+            event.actor_device_id,
+            event.actor_service_account_id,
+            event.auth_source.as_deref(),
+            event.category.as_str(),
+            event.action.as_str(),
+            event.result.as_str(),
+            event.vault_id,
+            event.item_path.as_deref(),
+            event.target.as_deref(),
+            event.detail.as_deref(),
+            event.client_ip.as_deref(),
+            event.prev_hash.as_deref(),
+            event.hash.as_deref(),
+            event.created_at
+        )
+        .fetch_one(conn)
+        .await?;
+        row.try_get("seq")
+    }
+
+    #[instrument(
+        level = "debug",
+        skip(self, filter),
+        fields(limit, db.system = "postgresql", db.operation = "SELECT", db.query = "audit_events.list")
+    )]
+    pub async fn list(
+        &self,
+        filter: &AuditEventFilter,
+        limit: i64,
+    ) -> Result<Vec<AuditEvent>, sqlx_core::Error> {
+        query_as!(
+            AuditEvent,
+            r#"
+            SELECT
+                seq, id, actor_user_id, actor_email, actor_device_id, actor_service_account_id,
+                auth_source, category, action, result, vault_id, item_path, target, detail,
+                client_ip, prev_hash, hash, created_at
+            FROM audit_events
+            WHERE ($1::uuid IS NULL OR actor_user_id = $1)
+              AND ($2::text IS NULL OR actor_email = $2)
+              AND ($3::uuid IS NULL OR vault_id = $3)
+              AND ($4::text IS NULL OR item_path = $4)
+              AND ($5::text IS NULL OR action = $5)
+              AND ($6::text IS NULL OR result = $6)
+              AND ($7::timestamptz IS NULL OR created_at >= $7)
+              AND ($8::timestamptz IS NULL OR created_at < $8)
+              AND ($9::bigint IS NULL OR seq < $9)
+            ORDER BY seq DESC
+            LIMIT $10
+            "#,
+            filter.actor_user_id,
+            filter.actor_email.as_deref(),
+            filter.vault_id,
+            filter.item_path.as_deref(),
+            filter.action.as_deref(),
+            filter.result.as_deref(),
+            filter.since,
+            filter.until,
+            filter.before_seq,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await
+        .inspect(|events| {
+            Span::current().record("db.rows", events.len() as i64);
+        })
+    }
+
+    /// Returns events with `seq` above `after_seq` in chain order.
+    #[instrument(
+        level = "debug",
+        skip(self),
+        fields(db.system = "postgresql", db.operation = "SELECT", db.query = "audit_events.list_after")
+    )]
+    pub async fn list_after(
+        &self,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<AuditEvent>, sqlx_core::Error> {
+        query_as!(
+            AuditEvent,
+            r#"
+            SELECT
+                seq, id, actor_user_id, actor_email, actor_device_id, actor_service_account_id,
+                auth_source, category, action, result, vault_id, item_path, target, detail,
+                client_ip, prev_hash, hash, created_at
+            FROM audit_events
+            WHERE seq > $1
+            ORDER BY seq ASC
+            LIMIT $2
+            "#,
+            after_seq,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    #[instrument(
+        level = "debug",
+        skip(self),
+        fields(db.system = "postgresql", db.operation = "SELECT", db.query = "audit_events.get_by_seq")
+    )]
+    pub async fn get_by_seq(&self, seq: i64) -> Result<Option<AuditEvent>, sqlx_core::Error> {
+        query_as!(
+            AuditEvent,
+            r#"
+            SELECT
+                seq, id, actor_user_id, actor_email, actor_device_id, actor_service_account_id,
+                auth_source, category, action, result, vault_id, item_path, target, detail,
+                client_ip, prev_hash, hash, created_at
+            FROM audit_events
+            WHERE seq = $1
+            "#,
+            seq
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+}
+
+pub struct AuditCheckpointRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> AuditCheckpointRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    #[instrument(
+        level = "debug",
+        skip(self, checkpoint),
+        fields(
+            seq = checkpoint.seq,
+            db.system = "postgresql",
+            db.operation = "INSERT",
+            db.query = "audit_checkpoints.create"
+        )
+    )]
+    pub async fn create(&self, checkpoint: &AuditCheckpoint) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO audit_checkpoints (id, seq, hash, public_key, signature, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            checkpoint.id,
+            checkpoint.seq,
+            checkpoint.hash.as_str(),
+            checkpoint.public_key.as_str(),
+            checkpoint.signature.as_str(),
+            checkpoint.created_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    #[instrument(
+        level = "debug",
+        skip(self),
+        fields(db.system = "postgresql", db.operation = "SELECT", db.query = "audit_checkpoints.latest")
+    )]
+    pub async fn latest(&self) -> Result<Option<AuditCheckpoint>, sqlx_core::Error> {
+        query_as!(
+            AuditCheckpoint,
+            r#"
+            SELECT id, seq, hash, public_key, signature, created_at
+            FROM audit_checkpoints
+            ORDER BY seq DESC, created_at DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    #[instrument(
+        level = "debug",
+        skip(self),
+        fields(db.system = "postgresql", db.operation = "SELECT", db.query = "audit_checkpoints.list")
+    )]
+    pub async fn list(&self) -> Result<Vec<AuditCheckpoint>, sqlx_core::Error> {
+        query_as!(
+            AuditCheckpoint,
+            r#"
+            SELECT id, seq, hash, public_key, signature, created_at
+            FROM audit_checkpoints
+            ORDER BY seq ASC, created_at ASC
+            "#
+        )
+        .fetch_all(self.pool)
+        .await
+        .inspect(|checkpoints| {
+            Span::current().record("db.rows", checkpoints.len() as i64);
+        })
+    }
+}
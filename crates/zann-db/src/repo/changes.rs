@@ -1,4 +1,5 @@
 use super::prelude::*;
+use sqlx_postgres::PgConnection;
 use tracing::{instrument, Span};
 
 pub struct ChangeRepo<'a> {
@@ -10,9 +11,14 @@ impl<'a> ChangeRepo<'a> {
         Self { pool }
     }
 
+    pub async fn create(&self, change: &Change) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, change).await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self, change),
+        skip(conn, change),
         fields(
             vault_id = %change.vault_id,
             item_id = %change.item_id,
@@ -21,7 +27,10 @@ impl<'a> ChangeRepo<'a> {
             db.query = "changes.create"
         )
     )]
-    pub async fn create(&self, change: &Change) -> Result<(), sqlx_core::Error> {
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        change: &Change,
+    ) -> Result<(), sqlx_core::Error> {
         query!(
             r#"
             INSERT INTO changes (vault_id, item_id, op, version, device_id, created_at)
@@ -34,7 +43,7 @@ impl<'a> ChangeRepo<'a> {
             change.device_id,
             change.created_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             Span::current().record("db.rows", result.rows_affected() as i64);
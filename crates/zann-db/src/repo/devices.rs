@@ -1,4 +1,5 @@
 use super::prelude::*;
+use sqlx_postgres::PgConnection;
 
 pub struct DeviceRepo<'a> {
     pool: &'a PgPool,
@@ -102,6 +103,15 @@ impl<'a> DeviceRepo<'a> {
         &self,
         device_id: Uuid,
         revoked_at: DateTime<Utc>,
+    ) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::revoke_in(&mut conn, device_id, revoked_at).await
+    }
+
+    pub async fn revoke_in(
+        conn: &mut PgConnection,
+        device_id: Uuid,
+        revoked_at: DateTime<Utc>,
     ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
@@ -112,7 +122,7 @@ impl<'a> DeviceRepo<'a> {
             device_id,
             revoked_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| result.rows_affected())
     }
@@ -228,6 +238,53 @@ impl<'a> ServiceAccountRepo<'a> {
             .await
     }
 
+    /// Accounts holding at least one scope on `vault_id`, either vault-wide or prefixed.
+    pub async fn list_by_vault(
+        &self,
+        vault_id: Uuid,
+        limit: i64,
+        offset: i64,
+        sort: &str,
+    ) -> Result<Vec<ServiceAccount>, sqlx_core::Error> {
+        let order_by = if sort.eq_ignore_ascii_case("asc") {
+            "ASC"
+        } else {
+            "DESC"
+        };
+        let query = format!(
+            r#"
+            SELECT
+                id as "id",
+                owner_user_id as "owner_user_id",
+                name,
+                description,
+                token_hash,
+                token_prefix,
+                scopes as "scopes",
+                allowed_ips as "allowed_ips",
+                expires_at as "expires_at",
+                last_used_at as "last_used_at",
+                last_used_ip as "last_used_ip",
+                last_used_user_agent as "last_used_user_agent",
+                use_count as "use_count",
+                created_at as "created_at",
+                revoked_at as "revoked_at"
+            FROM service_accounts
+            WHERE EXISTS (
+                SELECT 1
+                FROM jsonb_array_elements_text(scopes) AS scope
+                WHERE scope LIKE $1 || ':%' OR scope LIKE $1 || '/prefix:%'
+            )
+            ORDER BY created_at {}
+            LIMIT $2 OFFSET $3
+            "#,
+            order_by
+        );
+        query_as!(ServiceAccount, &query, vault_id.to_string(), limit, offset)
+            .fetch_all(self.pool)
+            .await
+    }
+
     pub async fn get_active_by_owner_and_name(
         &self,
         owner_user_id: Uuid,
@@ -0,0 +1,217 @@
+use super::prelude::*;
+
+const SECRET_COLUMNS: &str = r#"
+    id as "id",
+    vault_id as "vault_id",
+    path,
+    kind,
+    config_enc,
+    default_ttl_seconds as "default_ttl_seconds",
+    max_ttl_seconds as "max_ttl_seconds",
+    created_at as "created_at",
+    updated_at as "updated_at"
+"#;
+
+const LEASE_COLUMNS: &str = r#"
+    id as "id",
+    secret_id as "secret_id",
+    vault_id as "vault_id",
+    username,
+    issued_to_user_id as "issued_to_user_id",
+    issued_to_service_account_id as "issued_to_service_account_id",
+    issued_at as "issued_at",
+    expires_at as "expires_at",
+    revoked_at as "revoked_at",
+    last_error
+"#;
+
+pub struct DynamicSecretRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> DynamicSecretRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates or replaces the secret at `(vault_id, path)`. An existing row keeps
+    /// its id (and with it, its leases).
+    pub async fn upsert(&self, secret: &DynamicSecret) -> Result<DynamicSecret, sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO dynamic_secrets (
+                id, vault_id, path, kind, config_enc, default_ttl_seconds, max_ttl_seconds,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (vault_id, path) DO UPDATE
+            SET kind = EXCLUDED.kind,
+                config_enc = EXCLUDED.config_enc,
+                default_ttl_seconds = EXCLUDED.default_ttl_seconds,
+                max_ttl_seconds = EXCLUDED.max_ttl_seconds,
+                updated_at = EXCLUDED.updated_at
+            "#,
+            secret.id,
+            secret.vault_id,
+            secret.path.as_str(),
+            secret.kind.as_str(),
+            secret.config_enc.as_slice(),
+            secret.default_ttl_seconds,
+            secret.max_ttl_seconds,
+            secret.created_at,
+            secret.updated_at
+        )
+        .execute(self.pool)
+        .await?;
+        self.get_by_path(secret.vault_id, &secret.path)
+            .await?
+            .ok_or(sqlx_core::Error::RowNotFound)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Option<DynamicSecret>, sqlx_core::Error> {
+        let query = format!("SELECT {SECRET_COLUMNS} FROM dynamic_secrets WHERE id = $1");
+        query_as!(DynamicSecret, &query, id)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    pub async fn get_by_path(
+        &self,
+        vault_id: Uuid,
+        path: &str,
+    ) -> Result<Option<DynamicSecret>, sqlx_core::Error> {
+        let query = format!(
+            "SELECT {SECRET_COLUMNS} FROM dynamic_secrets WHERE vault_id = $1 AND path = $2"
+        );
+        query_as!(DynamicSecret, &query, vault_id, path)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM dynamic_secrets
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
+
+pub struct DynamicLeaseRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> DynamicLeaseRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, lease: &DynamicLease) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO dynamic_leases (
+                id, secret_id, vault_id, username, issued_to_user_id,
+                issued_to_service_account_id, issued_at, expires_at, revoked_at, last_error
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            lease.id,
+            lease.secret_id,
+            lease.vault_id,
+            lease.username.as_str(),
+            lease.issued_to_user_id,
+            lease.issued_to_service_account_id,
+            lease.issued_at,
+            lease.expires_at,
+            lease.revoked_at,
+            lease.last_error.as_deref()
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Option<DynamicLease>, sqlx_core::Error> {
+        let query = format!("SELECT {LEASE_COLUMNS} FROM dynamic_leases WHERE id = $1");
+        query_as!(DynamicLease, &query, id)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    /// Unrevoked leases past their expiry, oldest first.
+    pub async fn list_expired(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<DynamicLease>, sqlx_core::Error> {
+        let query = format!(
+            r#"
+            SELECT {LEASE_COLUMNS}
+            FROM dynamic_leases
+            WHERE revoked_at IS NULL
+              AND expires_at <= $1
+            ORDER BY expires_at ASC
+            LIMIT $2
+            "#
+        );
+        query_as!(DynamicLease, &query, now, limit)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    pub async fn count_active_by_secret(&self, secret_id: Uuid) -> Result<i64, sqlx_core::Error> {
+        let row = query!(
+            r#"
+            SELECT COUNT(*) as "count"
+            FROM dynamic_leases
+            WHERE secret_id = $1
+              AND revoked_at IS NULL
+            "#,
+            secret_id
+        )
+        .fetch_one(self.pool)
+        .await?;
+        row.try_get("count")
+    }
+
+    /// Marks a lease revoked. Returns `false` if it was already revoked.
+    pub async fn mark_revoked(
+        &self,
+        id: Uuid,
+        revoked_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE dynamic_leases
+            SET revoked_at = $2, last_error = NULL
+            WHERE id = $1
+              AND revoked_at IS NULL
+            "#,
+            id,
+            revoked_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    pub async fn mark_error(&self, id: Uuid, error: &str) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE dynamic_leases
+            SET last_error = $2
+            WHERE id = $1
+            "#,
+            id,
+            error
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+}
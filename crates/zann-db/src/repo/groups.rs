@@ -1,4 +1,5 @@
 use super::prelude::*;
+use sqlx_postgres::PgConnection;
 
 pub struct GroupRepo<'a> {
     pool: &'a PgPool,
@@ -10,6 +11,11 @@ impl<'a> GroupRepo<'a> {
     }
 
     pub async fn create(&self, group: &Group) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, group).await
+    }
+
+    pub async fn create_in(conn: &mut PgConnection, group: &Group) -> Result<(), sqlx_core::Error> {
         query!(
             r#"
             INSERT INTO groups (id, slug, name, created_at)
@@ -20,7 +26,7 @@ impl<'a> GroupRepo<'a> {
             group.name.as_str(),
             group.created_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|_| ())
     }
@@ -95,6 +101,16 @@ impl<'a> GroupRepo<'a> {
         group_id: Uuid,
         slug: &str,
         name: &str,
+    ) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_in(&mut conn, group_id, slug, name).await
+    }
+
+    pub async fn update_in(
+        conn: &mut PgConnection,
+        group_id: Uuid,
+        slug: &str,
+        name: &str,
     ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
@@ -106,12 +122,20 @@ impl<'a> GroupRepo<'a> {
             slug,
             name
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| result.rows_affected())
     }
 
     pub async fn delete_by_id(&self, id: Uuid) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::delete_by_id_in(&mut conn, id).await
+    }
+
+    pub async fn delete_by_id_in(
+        conn: &mut PgConnection,
+        id: Uuid,
+    ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
             DELETE FROM groups
@@ -119,7 +143,7 @@ impl<'a> GroupRepo<'a> {
             "#,
             id
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| result.rows_affected())
     }
@@ -135,6 +159,14 @@ impl<'a> GroupMemberRepo<'a> {
     }
 
     pub async fn create(&self, member: &GroupMember) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, member).await
+    }
+
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        member: &GroupMember,
+    ) -> Result<(), sqlx_core::Error> {
         query!(
             r#"
             INSERT INTO group_members (group_id, user_id, created_at)
@@ -144,7 +176,7 @@ impl<'a> GroupMemberRepo<'a> {
             member.user_id,
             member.created_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|_| ())
     }
@@ -209,6 +241,15 @@ impl<'a> GroupMemberRepo<'a> {
     }
 
     pub async fn delete(&self, group_id: Uuid, user_id: Uuid) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::delete_in(&mut conn, group_id, user_id).await
+    }
+
+    pub async fn delete_in(
+        conn: &mut PgConnection,
+        group_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
             DELETE FROM group_members
@@ -217,10 +258,30 @@ impl<'a> GroupMemberRepo<'a> {
             group_id,
             user_id
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| result.rows_affected())
     }
+
+    /// Those of `group_ids` that have at least one active, undeleted member.
+    pub async fn with_active_members_in(
+        conn: &mut PgConnection,
+        group_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, sqlx_core::Error> {
+        let rows = query!(
+            r#"
+            SELECT DISTINCT gm.group_id as "group_id"
+            FROM group_members gm
+            INNER JOIN users u ON u.id = gm.user_id
+            WHERE gm.group_id = ANY($1) AND u.status = $2 AND u.deleted_at IS NULL
+            "#,
+            group_ids,
+            UserStatus::Active.as_i32()
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        rows.iter().map(|row| row.try_get("group_id")).collect()
+    }
 }
 
 pub struct OidcGroupMappingRepo<'a> {
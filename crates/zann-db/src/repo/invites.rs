@@ -0,0 +1,170 @@
+use super::prelude::*;
+use sqlx_postgres::PgConnection;
+
+pub struct InviteRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> InviteRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, invite: &Invite) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO invites (
+                id, email, token_hash, group_ids, vault_roles, created_by_user_id, expires_at,
+                created_at, accepted_at, accepted_user_id, revoked_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#,
+            invite.id,
+            invite.email.as_str(),
+            invite.token_hash.as_str(),
+            &invite.group_ids,
+            &invite.vault_roles,
+            invite.created_by_user_id,
+            invite.expires_at,
+            invite.created_at,
+            invite.accepted_at,
+            invite.accepted_user_id,
+            invite.revoked_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Option<Invite>, sqlx_core::Error> {
+        query_as!(
+            Invite,
+            r#"
+            SELECT
+                id as "id",
+                email,
+                token_hash,
+                group_ids as "group_ids",
+                vault_roles as "vault_roles",
+                created_by_user_id as "created_by_user_id",
+                expires_at as "expires_at",
+                created_at as "created_at",
+                accepted_at as "accepted_at",
+                accepted_user_id as "accepted_user_id",
+                revoked_at as "revoked_at"
+            FROM invites
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    pub async fn get_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<Invite>, sqlx_core::Error> {
+        query_as!(
+            Invite,
+            r#"
+            SELECT
+                id as "id",
+                email,
+                token_hash,
+                group_ids as "group_ids",
+                vault_roles as "vault_roles",
+                created_by_user_id as "created_by_user_id",
+                expires_at as "expires_at",
+                created_at as "created_at",
+                accepted_at as "accepted_at",
+                accepted_user_id as "accepted_user_id",
+                revoked_at as "revoked_at"
+            FROM invites
+            WHERE token_hash = $1
+            "#,
+            token_hash
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    pub async fn list(
+        &self,
+        limit: i64,
+        offset: i64,
+        sort: &str,
+    ) -> Result<Vec<Invite>, sqlx_core::Error> {
+        let order_by = if sort.eq_ignore_ascii_case("asc") {
+            "ASC"
+        } else {
+            "DESC"
+        };
+        let query = format!(
+            r#"
+            SELECT
+                id as "id",
+                email,
+                token_hash,
+                group_ids as "group_ids",
+                vault_roles as "vault_roles",
+                created_by_user_id as "created_by_user_id",
+                expires_at as "expires_at",
+                created_at as "created_at",
+                accepted_at as "accepted_at",
+                accepted_user_id as "accepted_user_id",
+                revoked_at as "revoked_at"
+            FROM invites
+            ORDER BY created_at {}
+            LIMIT $1 OFFSET $2
+            "#,
+            order_by
+        );
+        query_as!(Invite, &query, limit, offset)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// Revokes an invite that has not been accepted yet.
+    pub async fn revoke(&self, id: Uuid, now: DateTime<Utc>) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE invites
+            SET revoked_at = $2
+            WHERE id = $1 AND accepted_at IS NULL AND revoked_at IS NULL
+            "#,
+            id,
+            now
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+
+    /// Marks an invite as used by `user_id`. Matches nothing when the invite
+    /// was accepted, revoked or expired in the meantime, so two registrations
+    /// racing on the same token cannot both succeed.
+    pub async fn accept_in(
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE invites
+            SET accepted_at = $3, accepted_user_id = $2
+            WHERE id = $1
+              AND accepted_at IS NULL
+              AND revoked_at IS NULL
+              AND expires_at > $3
+            "#,
+            id,
+            user_id,
+            now
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
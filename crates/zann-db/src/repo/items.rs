@@ -1,4 +1,5 @@
 use super::prelude::*;
+use sqlx_postgres::PgConnection;
 use tracing::{instrument, Span};
 
 pub struct ItemRepo<'a> {
@@ -10,9 +11,14 @@ impl<'a> ItemRepo<'a> {
         Self { pool }
     }
 
+    pub async fn create(&self, item: &Item) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, item).await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self, item),
+        skip(conn, item),
         fields(
             item_id = %item.id,
             vault_id = %item.vault_id,
@@ -21,17 +27,17 @@ impl<'a> ItemRepo<'a> {
             db.query = "items.create"
         )
     )]
-    pub async fn create(&self, item: &Item) -> Result<(), sqlx_core::Error> {
+    pub async fn create_in(conn: &mut PgConnection, item: &Item) -> Result<(), sqlx_core::Error> {
         query!(
             r#"
             INSERT INTO items (
                 id, vault_id, path, name, type_id, tags, favorite, payload_enc, checksum,
                 version, row_version, device_id, sync_status, deleted_at, deleted_by_user_id,
-                deleted_by_device_id, created_at, updated_at
+                deleted_by_device_id, expires_at, created_at, updated_at
             )
             VALUES (
                 $1, $2, $3, $4, $5, $6, $7, $8, $9,
-                $10, $11, $12, $13, $14, $15, $16, $17, $18
+                $10, $11, $12, $13, $14, $15, $16, $17, $18, $19
             )
             "#,
             item.id,
@@ -50,10 +56,11 @@ impl<'a> ItemRepo<'a> {
             item.deleted_at,
             item.deleted_by_user_id,
             item.deleted_by_device_id,
+            item.expires_at,
             item.created_at,
             item.updated_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             Span::current().record("db.rows", result.rows_affected() as i64);
@@ -86,6 +93,7 @@ impl<'a> ItemRepo<'a> {
                 deleted_at as "deleted_at",
                 deleted_by_user_id as "deleted_by_user_id",
                 deleted_by_device_id as "deleted_by_device_id",
+                expires_at as "expires_at",
                 created_at as "created_at",
                 updated_at as "updated_at"
             FROM items
@@ -127,6 +135,7 @@ impl<'a> ItemRepo<'a> {
                 deleted_at as "deleted_at",
                 deleted_by_user_id as "deleted_by_user_id",
                 deleted_by_device_id as "deleted_by_device_id",
+                expires_at as "expires_at",
                 created_at as "created_at",
                 updated_at as "updated_at"
             FROM items
@@ -173,6 +182,7 @@ impl<'a> ItemRepo<'a> {
                 deleted_at as "deleted_at",
                 deleted_by_user_id as "deleted_by_user_id",
                 deleted_by_device_id as "deleted_by_device_id",
+                expires_at as "expires_at",
                 created_at as "created_at",
                 updated_at as "updated_at"
             FROM items
@@ -188,9 +198,14 @@ impl<'a> ItemRepo<'a> {
         Ok(items)
     }
 
+    pub async fn update(&self, item: &Item) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_in(&mut conn, item).await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self, item),
+        skip(conn, item),
         fields(
             item_id = %item.id,
             vault_id = %item.vault_id,
@@ -199,7 +214,7 @@ impl<'a> ItemRepo<'a> {
             db.query = "items.update"
         )
     )]
-    pub async fn update(&self, item: &Item) -> Result<u64, sqlx_core::Error> {
+    pub async fn update_in(conn: &mut PgConnection, item: &Item) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
             UPDATE items
@@ -217,6 +232,11 @@ impl<'a> ItemRepo<'a> {
                 deleted_at = $12,
                 deleted_by_user_id = $13,
                 deleted_by_device_id = $14,
+                expiry_notified_at = CASE
+                    WHEN expires_at IS DISTINCT FROM $17 THEN NULL
+                    ELSE expiry_notified_at
+                END,
+                expires_at = $17,
                 updated_at = $15
             WHERE id = $1 AND row_version = $16
             "#,
@@ -235,9 +255,10 @@ impl<'a> ItemRepo<'a> {
             item.deleted_by_user_id,
             item.deleted_by_device_id,
             item.updated_at,
-            item.row_version
+            item.row_version,
+            item.expires_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             let rows = result.rows_affected();
@@ -245,6 +266,76 @@ impl<'a> ItemRepo<'a> {
             rows
         })
     }
+
+    /// Live items expiring at or before `before` that have not been announced
+    /// for their current expiry date, soonest first.
+    pub async fn list_expiry_unnotified(
+        &self,
+        before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Item>, sqlx_core::Error> {
+        let items = query_as!(
+            Item,
+            r#"
+            SELECT
+                id as "id",
+                vault_id as "vault_id",
+                path,
+                name,
+                type_id,
+                tags as "tags",
+                favorite as "favorite",
+                payload_enc,
+                checksum,
+                version as "version",
+                row_version as "row_version",
+                device_id as "device_id",
+                sync_status as "sync_status",
+                deleted_at as "deleted_at",
+                deleted_by_user_id as "deleted_by_user_id",
+                deleted_by_device_id as "deleted_by_device_id",
+                expires_at as "expires_at",
+                created_at as "created_at",
+                updated_at as "updated_at"
+            FROM items
+            WHERE sync_status = 1
+              AND expires_at IS NOT NULL
+              AND expires_at <= $1
+              AND expiry_notified_at IS NULL
+            ORDER BY expires_at ASC
+            LIMIT $2
+            "#,
+            before,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await?;
+        Span::current().record("db.rows", items.len() as i64);
+        Ok(items)
+    }
+
+    /// Records that the expiry notice went out. Matches on `expires_at` so a
+    /// date changed in the meantime is announced again.
+    pub async fn mark_expiry_notified(
+        &self,
+        id: Uuid,
+        expires_at: DateTime<Utc>,
+        notified_at: DateTime<Utc>,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE items
+            SET expiry_notified_at = $3
+            WHERE id = $1 AND expires_at = $2
+            "#,
+            id,
+            expires_at,
+            notified_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
 }
 
 pub struct ItemUsageRepo<'a> {
@@ -296,9 +387,15 @@ impl<'a> ItemHistoryRepo<'a> {
         Self { pool }
     }
 
+    pub async fn create(&self, history: &ItemHistory) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, history).await
+    }
+
+    /// Keeps the existing snapshot when `history.version` was already recorded.
     #[instrument(
         level = "debug",
-        skip(self, history),
+        skip(conn, history),
         fields(
             history_id = %history.id,
             item_id = %history.item_id,
@@ -307,7 +404,10 @@ impl<'a> ItemHistoryRepo<'a> {
             db.query = "item_history.create"
         )
     )]
-    pub async fn create(&self, history: &ItemHistory) -> Result<(), sqlx_core::Error> {
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        history: &ItemHistory,
+    ) -> Result<(), sqlx_core::Error> {
         query!(
             r#"
             INSERT INTO item_history (
@@ -316,6 +416,7 @@ impl<'a> ItemHistoryRepo<'a> {
                 changed_by_device_name, created_at
             )
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (item_id, version) DO NOTHING
             "#,
             history.id,
             history.item_id,
@@ -331,7 +432,7 @@ impl<'a> ItemHistoryRepo<'a> {
             history.changed_by_device_name.as_deref(),
             history.created_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             Span::current().record("db.rows", result.rows_affected() as i64);
@@ -485,12 +586,21 @@ impl<'a> ItemHistoryRepo<'a> {
         .await
     }
 
+    pub async fn prune_by_item(&self, item_id: Uuid, keep: i64) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::prune_by_item_in(&mut conn, item_id, keep).await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self),
+        skip(conn),
         fields(item_id = %item_id, keep, db.system = "postgresql", db.operation = "DELETE", db.query = "item_history.prune_by_item")
     )]
-    pub async fn prune_by_item(&self, item_id: Uuid, keep: i64) -> Result<u64, sqlx_core::Error> {
+    pub async fn prune_by_item_in(
+        conn: &mut PgConnection,
+        item_id: Uuid,
+        keep: i64,
+    ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
             DELETE FROM item_history
@@ -505,7 +615,7 @@ impl<'a> ItemHistoryRepo<'a> {
             item_id,
             keep
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             let rows = result.rows_affected();
@@ -524,9 +634,14 @@ impl<'a> AttachmentRepo<'a> {
         Self { pool }
     }
 
+    pub async fn create(&self, attachment: &Attachment) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, attachment).await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self, attachment),
+        skip(conn, attachment),
         fields(
             attachment_id = %attachment.id,
             item_id = %attachment.item_id,
@@ -535,7 +650,10 @@ impl<'a> AttachmentRepo<'a> {
             db.query = "attachments.create"
         )
     )]
-    pub async fn create(&self, attachment: &Attachment) -> Result<(), sqlx_core::Error> {
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        attachment: &Attachment,
+    ) -> Result<(), sqlx_core::Error> {
         query!(
             r#"
             INSERT INTO attachments (
@@ -556,7 +674,7 @@ impl<'a> AttachmentRepo<'a> {
             attachment.created_at,
             attachment.deleted_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             Span::current().record("db.rows", result.rows_affected() as i64);
@@ -626,13 +744,22 @@ impl<'a> AttachmentRepo<'a> {
         })
     }
 
+    pub async fn mark_deleted_by_item(
+        &self,
+        item_id: Uuid,
+        deleted_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::mark_deleted_by_item_in(&mut conn, item_id, deleted_at).await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self),
+        skip(conn),
         fields(item_id = %item_id, db.system = "postgresql", db.operation = "UPDATE", db.query = "attachments.mark_deleted_by_item")
     )]
-    pub async fn mark_deleted_by_item(
-        &self,
+    pub async fn mark_deleted_by_item_in(
+        conn: &mut PgConnection,
         item_id: Uuid,
         deleted_at: chrono::DateTime<chrono::Utc>,
     ) -> Result<u64, sqlx_core::Error> {
@@ -645,7 +772,7 @@ impl<'a> AttachmentRepo<'a> {
             item_id,
             deleted_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             let rows = result.rows_affected();
@@ -654,12 +781,20 @@ impl<'a> AttachmentRepo<'a> {
         })
     }
 
+    pub async fn clear_deleted_by_item(&self, item_id: Uuid) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::clear_deleted_by_item_in(&mut conn, item_id).await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self),
+        skip(conn),
         fields(item_id = %item_id, db.system = "postgresql", db.operation = "UPDATE", db.query = "attachments.clear_deleted_by_item")
     )]
-    pub async fn clear_deleted_by_item(&self, item_id: Uuid) -> Result<u64, sqlx_core::Error> {
+    pub async fn clear_deleted_by_item_in(
+        conn: &mut PgConnection,
+        item_id: Uuid,
+    ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
             UPDATE attachments
@@ -668,7 +803,7 @@ impl<'a> AttachmentRepo<'a> {
             "#,
             item_id
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             let rows = result.rows_affected();
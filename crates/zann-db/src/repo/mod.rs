@@ -22,24 +22,50 @@ pub(crate) mod prelude {
     pub(crate) use sqlx_core::row::Row;
     pub(crate) use uuid::Uuid;
     pub(crate) use zann_core::{
-        Attachment, Change, Device, Group, GroupMember, Item, ItemHistory, ItemUsage,
-        OidcGroupMapping, OidcIdentity, ServiceAccount, ServiceAccountSession, Session, User,
-        UserStatus, Vault, VaultMember,
+        Attachment, Change, Device, DynamicLease, DynamicSecret, Group, GroupMember, Invite, Item,
+        ItemHistory, ItemRotationHook, ItemRotationSchedule, ItemUsage, OidcGroupMapping,
+        OidcIdentity, PkiCa, PkiCertificate, PkiRole, SecretLease, ServiceAccount,
+        ServiceAccountSession, Session, SshCa, SshSigningRole, TransitKey, TransitKeyVersion, User,
+        UserStatus, Vault, VaultGroupGrant, VaultMember, VaultWebhook, WebhookDelivery,
     };
 }
 
+mod audit;
 mod changes;
 mod devices;
+mod dynamic;
 mod groups;
+mod invites;
 mod items;
+mod pki;
+mod rotation_hooks;
+mod rotation_schedules;
+mod seal;
+mod secret_leases;
 mod sessions;
+mod ssh;
+mod transit;
 mod users;
 mod vaults;
+mod webhooks;
 
+pub use audit::{AuditChainHead, AuditCheckpointRepo, AuditEventFilter, AuditEventRepo};
 pub use changes::ChangeRepo;
 pub use devices::{DeviceRepo, ServiceAccountRepo, ServiceAccountSessionRepo};
+pub use dynamic::{DynamicLeaseRepo, DynamicSecretRepo};
 pub use groups::{GroupMemberRepo, GroupRepo, OidcGroupMappingRepo};
+pub use invites::InviteRepo;
 pub use items::{AttachmentRepo, ItemHistoryRepo, ItemRepo, ItemUsageRepo};
+pub use pki::{PkiCaRepo, PkiCertificateRepo, PkiRoleRepo};
+pub use rotation_hooks::ItemRotationHookRepo;
+pub use rotation_schedules::ItemRotationScheduleRepo;
+pub use seal::{SealConfig, SealConfigRepo};
+pub use secret_leases::SecretLeaseRepo;
 pub use sessions::SessionRepo;
+pub use ssh::{SshCaRepo, SshSigningRoleRepo};
+pub use transit::TransitKeyRepo;
 pub use users::{OidcIdentityRepo, UserRepo};
-pub use vaults::{VaultMemberRepo, VaultRepo};
+pub use vaults::{
+    VaultGroupGrantListing, VaultGroupGrantRepo, VaultMemberListing, VaultMemberRepo, VaultRepo,
+};
+pub use webhooks::{ChangeNotice, VaultWebhookRepo, WebhookDeliveryRepo};
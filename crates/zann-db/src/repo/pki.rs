@@ -0,0 +1,301 @@
+use super::prelude::*;
+
+const CA_COLUMNS: &str = r#"
+    id as "id",
+    vault_id as "vault_id",
+    kind,
+    common_name,
+    certificate_pem,
+    private_key_enc,
+    serial,
+    not_after as "not_after",
+    created_at as "created_at"
+"#;
+
+const ROLE_COLUMNS: &str = r#"
+    id as "id",
+    vault_id as "vault_id",
+    name,
+    allowed_domains as "allowed_domains",
+    allow_subdomains,
+    allow_ip_sans,
+    key_type,
+    server_auth,
+    client_auth,
+    default_ttl_seconds as "default_ttl_seconds",
+    max_ttl_seconds as "max_ttl_seconds",
+    created_at as "created_at",
+    updated_at as "updated_at"
+"#;
+
+const CERT_COLUMNS: &str = r#"
+    id as "id",
+    vault_id as "vault_id",
+    issuer_id as "issuer_id",
+    serial,
+    role_name,
+    common_name,
+    sans as "sans",
+    certificate_pem,
+    not_before as "not_before",
+    not_after as "not_after",
+    revoked_at as "revoked_at",
+    created_at as "created_at"
+"#;
+
+pub struct PkiCaRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> PkiCaRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Stores a CA. Returns `false` if the vault already has one of that kind.
+    pub async fn create(&self, ca: &PkiCa) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO pki_cas (
+                id, vault_id, kind, common_name, certificate_pem, private_key_enc,
+                serial, not_after, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (vault_id, kind) DO NOTHING
+            "#,
+            ca.id,
+            ca.vault_id,
+            ca.kind.as_str(),
+            ca.common_name.as_str(),
+            ca.certificate_pem.as_str(),
+            ca.private_key_enc.as_slice(),
+            ca.serial.as_str(),
+            ca.not_after,
+            ca.created_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    pub async fn get_by_kind(
+        &self,
+        vault_id: Uuid,
+        kind: &str,
+    ) -> Result<Option<PkiCa>, sqlx_core::Error> {
+        let query = format!("SELECT {CA_COLUMNS} FROM pki_cas WHERE vault_id = $1 AND kind = $2");
+        query_as!(PkiCa, &query, vault_id, kind)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    /// Removes every CA of the vault together with the certificates they issued.
+    pub async fn delete_by_vault(&self, vault_id: Uuid) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM pki_cas
+            WHERE vault_id = $1
+            "#,
+            vault_id
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
+
+pub struct PkiRoleRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> PkiRoleRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates or replaces the role `(vault_id, name)`. An existing row keeps its id.
+    pub async fn upsert(&self, role: &PkiRole) -> Result<PkiRole, sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO pki_roles (
+                id, vault_id, name, allowed_domains, allow_subdomains, allow_ip_sans,
+                key_type, server_auth, client_auth, default_ttl_seconds, max_ttl_seconds,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            ON CONFLICT (vault_id, name) DO UPDATE
+            SET allowed_domains = EXCLUDED.allowed_domains,
+                allow_subdomains = EXCLUDED.allow_subdomains,
+                allow_ip_sans = EXCLUDED.allow_ip_sans,
+                key_type = EXCLUDED.key_type,
+                server_auth = EXCLUDED.server_auth,
+                client_auth = EXCLUDED.client_auth,
+                default_ttl_seconds = EXCLUDED.default_ttl_seconds,
+                max_ttl_seconds = EXCLUDED.max_ttl_seconds,
+                updated_at = EXCLUDED.updated_at
+            "#,
+            role.id,
+            role.vault_id,
+            role.name.as_str(),
+            &role.allowed_domains,
+            role.allow_subdomains,
+            role.allow_ip_sans,
+            role.key_type.as_str(),
+            role.server_auth,
+            role.client_auth,
+            role.default_ttl_seconds,
+            role.max_ttl_seconds,
+            role.created_at,
+            role.updated_at
+        )
+        .execute(self.pool)
+        .await?;
+        self.get_by_name(role.vault_id, &role.name)
+            .await?
+            .ok_or(sqlx_core::Error::RowNotFound)
+    }
+
+    pub async fn get_by_name(
+        &self,
+        vault_id: Uuid,
+        name: &str,
+    ) -> Result<Option<PkiRole>, sqlx_core::Error> {
+        let query =
+            format!("SELECT {ROLE_COLUMNS} FROM pki_roles WHERE vault_id = $1 AND name = $2");
+        query_as!(PkiRole, &query, vault_id, name)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    pub async fn list_by_vault(&self, vault_id: Uuid) -> Result<Vec<PkiRole>, sqlx_core::Error> {
+        let query =
+            format!("SELECT {ROLE_COLUMNS} FROM pki_roles WHERE vault_id = $1 ORDER BY name");
+        query_as!(PkiRole, &query, vault_id)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    pub async fn delete(&self, vault_id: Uuid, name: &str) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM pki_roles
+            WHERE vault_id = $1 AND name = $2
+            "#,
+            vault_id,
+            name
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
+
+pub struct PkiCertificateRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> PkiCertificateRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, cert: &PkiCertificate) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO pki_certificates (
+                id, vault_id, issuer_id, serial, role_name, common_name, sans,
+                certificate_pem, not_before, not_after, revoked_at, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            "#,
+            cert.id,
+            cert.vault_id,
+            cert.issuer_id,
+            cert.serial.as_str(),
+            cert.role_name.as_str(),
+            cert.common_name.as_str(),
+            &cert.sans,
+            cert.certificate_pem.as_str(),
+            cert.not_before,
+            cert.not_after,
+            cert.revoked_at,
+            cert.created_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn get_by_serial(
+        &self,
+        vault_id: Uuid,
+        serial: &str,
+    ) -> Result<Option<PkiCertificate>, sqlx_core::Error> {
+        let query = format!(
+            "SELECT {CERT_COLUMNS} FROM pki_certificates WHERE vault_id = $1 AND serial = $2"
+        );
+        query_as!(PkiCertificate, &query, vault_id, serial)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    pub async fn list_by_vault(
+        &self,
+        vault_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<PkiCertificate>, sqlx_core::Error> {
+        let query = format!(
+            r#"
+            SELECT {CERT_COLUMNS}
+            FROM pki_certificates
+            WHERE vault_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2 OFFSET $3
+            "#
+        );
+        query_as!(PkiCertificate, &query, vault_id, limit, offset)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    pub async fn list_revoked_by_issuer(
+        &self,
+        issuer_id: Uuid,
+    ) -> Result<Vec<PkiCertificate>, sqlx_core::Error> {
+        let query = format!(
+            r#"
+            SELECT {CERT_COLUMNS}
+            FROM pki_certificates
+            WHERE issuer_id = $1 AND revoked_at IS NOT NULL
+            ORDER BY revoked_at
+            "#
+        );
+        query_as!(PkiCertificate, &query, issuer_id)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// Marks the certificate revoked. Returns `false` if it was already revoked
+    /// or does not exist.
+    pub async fn revoke(
+        &self,
+        vault_id: Uuid,
+        serial: &str,
+        revoked_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE pki_certificates
+            SET revoked_at = $3
+            WHERE vault_id = $1 AND serial = $2 AND revoked_at IS NULL
+            "#,
+            vault_id,
+            serial,
+            revoked_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,74 @@
+use super::prelude::*;
+
+pub struct ItemRotationHookRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ItemRotationHookRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn upsert(&self, hook: &ItemRotationHook) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO item_rotation_hooks (
+                item_id, vault_id, kind, config_enc, verify, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (item_id) DO UPDATE
+            SET kind = EXCLUDED.kind,
+                config_enc = EXCLUDED.config_enc,
+                verify = EXCLUDED.verify,
+                updated_at = EXCLUDED.updated_at
+            "#,
+            hook.item_id,
+            hook.vault_id,
+            hook.kind.as_str(),
+            hook.config_enc.as_slice(),
+            hook.verify,
+            hook.created_at,
+            hook.updated_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn get_by_item(
+        &self,
+        item_id: Uuid,
+    ) -> Result<Option<ItemRotationHook>, sqlx_core::Error> {
+        query_as!(
+            ItemRotationHook,
+            r#"
+            SELECT
+                item_id as "item_id",
+                vault_id as "vault_id",
+                kind,
+                config_enc,
+                verify,
+                created_at as "created_at",
+                updated_at as "updated_at"
+            FROM item_rotation_hooks
+            WHERE item_id = $1
+            "#,
+            item_id
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    pub async fn delete_by_item(&self, item_id: Uuid) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM item_rotation_hooks
+            WHERE item_id = $1
+            "#,
+            item_id
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
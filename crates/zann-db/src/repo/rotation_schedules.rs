@@ -0,0 +1,222 @@
+use super::prelude::*;
+use sqlx_postgres::PgConnection;
+
+const SCHEDULE_COLUMNS: &str = r#"
+    s.item_id as "item_id",
+    s.vault_id as "vault_id",
+    s.schedule,
+    s.max_age_seconds as "max_age_seconds",
+    s.password_policy,
+    s.last_rotated_at as "last_rotated_at",
+    s.last_started_at as "last_started_at",
+    s.last_error,
+    s.created_at as "created_at",
+    s.updated_at as "updated_at"
+"#;
+
+pub struct ItemRotationScheduleRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> ItemRotationScheduleRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates or replaces the schedule for an item. `last_rotated_at` is only
+    /// taken from `schedule` on insert so that editing a schedule does not
+    /// reset the age of the current secret.
+    pub async fn upsert(
+        &self,
+        schedule: &ItemRotationSchedule,
+    ) -> Result<ItemRotationSchedule, sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO item_rotation_schedules (
+                item_id, vault_id, schedule, max_age_seconds, password_policy, last_rotated_at,
+                last_started_at, last_error, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (item_id) DO UPDATE
+            SET schedule = EXCLUDED.schedule,
+                max_age_seconds = EXCLUDED.max_age_seconds,
+                password_policy = EXCLUDED.password_policy,
+                last_error = NULL,
+                updated_at = EXCLUDED.updated_at
+            "#,
+            schedule.item_id,
+            schedule.vault_id,
+            schedule.schedule.as_str(),
+            schedule.max_age_seconds,
+            schedule.password_policy.as_deref(),
+            schedule.last_rotated_at,
+            schedule.last_started_at,
+            schedule.last_error.as_deref(),
+            schedule.created_at,
+            schedule.updated_at
+        )
+        .execute(self.pool)
+        .await?;
+        self.get_by_item(schedule.item_id)
+            .await?
+            .ok_or(sqlx_core::Error::RowNotFound)
+    }
+
+    pub async fn get_by_item(
+        &self,
+        item_id: Uuid,
+    ) -> Result<Option<ItemRotationSchedule>, sqlx_core::Error> {
+        let query = format!(
+            "SELECT {SCHEDULE_COLUMNS} FROM item_rotation_schedules s WHERE s.item_id = $1"
+        );
+        query_as!(ItemRotationSchedule, &query, item_id)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    pub async fn delete_by_item(&self, item_id: Uuid) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM item_rotation_schedules
+            WHERE item_id = $1
+            "#,
+            item_id
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+
+    pub async fn list_by_vault(
+        &self,
+        vault_id: Uuid,
+    ) -> Result<Vec<ItemRotationSchedule>, sqlx_core::Error> {
+        let query = format!(
+            r#"
+            SELECT {SCHEDULE_COLUMNS}
+            FROM item_rotation_schedules s
+            WHERE s.vault_id = $1
+            ORDER BY s.last_rotated_at ASC
+            "#
+        );
+        query_as!(ItemRotationSchedule, &query, vault_id)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// Schedules past their due time whose item is live and not already
+    /// rotating, oldest first. Schedules whose last attempt failed after
+    /// `retry_errors_before` are left out so they cannot hold the batch.
+    pub async fn list_due(
+        &self,
+        now: DateTime<Utc>,
+        retry_errors_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<ItemRotationSchedule>, sqlx_core::Error> {
+        let query = format!(
+            r#"
+            SELECT {SCHEDULE_COLUMNS}
+            FROM item_rotation_schedules s
+            JOIN items i ON i.id = s.item_id
+            WHERE i.deleted_at IS NULL
+              AND i.rotation_state IS NULL
+              AND s.last_rotated_at + make_interval(secs => s.max_age_seconds) <= $1
+              AND (
+                s.last_error IS NULL
+                OR s.last_started_at IS NULL
+                OR s.last_started_at <= $2
+              )
+            ORDER BY s.last_rotated_at ASC
+            LIMIT $3
+            "#
+        );
+        query_as!(
+            ItemRotationSchedule,
+            &query,
+            now,
+            retry_errors_before,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await
+    }
+
+    /// Number of schedules past their due time on live items.
+    pub async fn count_overdue(&self, now: DateTime<Utc>) -> Result<i64, sqlx_core::Error> {
+        let row = query!(
+            r#"
+            SELECT COUNT(*) as "count"
+            FROM item_rotation_schedules s
+            JOIN items i ON i.id = s.item_id
+            WHERE i.deleted_at IS NULL
+              AND s.last_rotated_at + make_interval(secs => s.max_age_seconds) <= $1
+            "#,
+            now
+        )
+        .fetch_one(self.pool)
+        .await?;
+        row.try_get("count")
+    }
+
+    pub async fn mark_started(
+        &self,
+        item_id: Uuid,
+        started_at: DateTime<Utc>,
+    ) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE item_rotation_schedules
+            SET last_started_at = $2, last_error = NULL
+            WHERE item_id = $1
+            "#,
+            item_id,
+            started_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    /// Records a failed attempt; `last_started_at` becomes the attempt time
+    /// so the scheduler can back off.
+    pub async fn mark_error(
+        &self,
+        item_id: Uuid,
+        error: &str,
+        attempted_at: DateTime<Utc>,
+    ) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE item_rotation_schedules
+            SET last_error = $2, last_started_at = $3
+            WHERE item_id = $1
+            "#,
+            item_id,
+            error,
+            attempted_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    /// Restarts the schedule after a committed rotation.
+    pub async fn record_rotated_in(
+        conn: &mut PgConnection,
+        item_id: Uuid,
+        rotated_at: DateTime<Utc>,
+    ) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE item_rotation_schedules
+            SET last_rotated_at = $2, last_error = NULL
+            WHERE item_id = $1
+            "#,
+            item_id,
+            rotated_at
+        )
+        .execute(conn)
+        .await
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,64 @@
+use super::prelude::*;
+use sqlx_postgres::PgConnection;
+
+/// Shamir split parameters recorded by `zann-server init` for manual unseal.
+/// `key_check` lets the server recognise the reconstructed master key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealConfig {
+    pub threshold: i16,
+    pub shares: i16,
+    pub key_check: String,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct SealConfigRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> SealConfigRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get(&self) -> Result<Option<SealConfig>, sqlx_core::Error> {
+        let row = query!(
+            r#"
+            SELECT threshold, shares, key_check, created_at
+            FROM seal_config
+            WHERE id = 1
+            "#
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        row.map(|row| {
+            Ok(SealConfig {
+                threshold: row.try_get("threshold")?,
+                shares: row.try_get("shares")?,
+                key_check: row.try_get("key_check")?,
+                created_at: row.try_get("created_at")?,
+            })
+        })
+        .transpose()
+    }
+
+    /// Records the split once. Returns 0 when the server was already initialised.
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        config: &SealConfig,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO seal_config (id, threshold, shares, key_check, created_at)
+            VALUES (1, $1, $2, $3, $4)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+            config.threshold,
+            config.shares,
+            config.key_check.as_str(),
+            config.created_at
+        )
+        .execute(conn)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
@@ -0,0 +1,198 @@
+use super::prelude::*;
+use sqlx_postgres::PgConnection;
+
+const LEASE_COLUMNS: &str = r#"
+    id as "id",
+    item_id as "item_id",
+    vault_id as "vault_id",
+    item_version as "item_version",
+    holder_user_id as "holder_user_id",
+    holder_service_account_id as "holder_service_account_id",
+    holder_device_id as "holder_device_id",
+    holder_email,
+    holder_name,
+    issued_at as "issued_at",
+    renewed_at as "renewed_at",
+    expires_at as "expires_at",
+    revoked_at as "revoked_at"
+"#;
+
+pub struct SecretLeaseRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> SecretLeaseRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, lease: &SecretLease) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, lease).await
+    }
+
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        lease: &SecretLease,
+    ) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO secret_leases (
+                id, item_id, vault_id, item_version, holder_user_id,
+                holder_service_account_id, holder_device_id, holder_email, holder_name,
+                issued_at, renewed_at, expires_at, revoked_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            "#,
+            lease.id,
+            lease.item_id,
+            lease.vault_id,
+            lease.item_version,
+            lease.holder_user_id,
+            lease.holder_service_account_id,
+            lease.holder_device_id,
+            lease.holder_email.as_str(),
+            lease.holder_name.as_deref(),
+            lease.issued_at,
+            lease.renewed_at,
+            lease.expires_at,
+            lease.revoked_at
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn get_by_id(&self, id: Uuid) -> Result<Option<SecretLease>, sqlx_core::Error> {
+        let query = format!("SELECT {LEASE_COLUMNS} FROM secret_leases WHERE id = $1");
+        query_as!(SecretLease, &query, id)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    /// Leases on `item_id` that are neither revoked nor expired at `now`,
+    /// soonest expiry first.
+    pub async fn list_active_by_item(
+        &self,
+        item_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SecretLease>, sqlx_core::Error> {
+        let query = format!(
+            r#"
+            SELECT {LEASE_COLUMNS}
+            FROM secret_leases
+            WHERE item_id = $1
+              AND revoked_at IS NULL
+              AND expires_at > $2
+            ORDER BY expires_at ASC
+            "#
+        );
+        query_as!(SecretLease, &query, item_id, now)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    pub async fn count_active_by_item(
+        &self,
+        item_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<i64, sqlx_core::Error> {
+        let row = query!(
+            r#"
+            SELECT COUNT(*) as "count"
+            FROM secret_leases
+            WHERE item_id = $1
+              AND revoked_at IS NULL
+              AND expires_at > $2
+            "#,
+            item_id,
+            now
+        )
+        .fetch_one(self.pool)
+        .await?;
+        row.try_get("count")
+    }
+
+    /// Moves the expiry of an active lease. Returns `false` if the lease was
+    /// revoked or had already expired at `now`.
+    pub async fn renew(
+        &self,
+        id: Uuid,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::renew_in(&mut conn, id, expires_at, now).await
+    }
+
+    pub async fn renew_in(
+        conn: &mut PgConnection,
+        id: Uuid,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE secret_leases
+            SET expires_at = $2, renewed_at = $3
+            WHERE id = $1
+              AND revoked_at IS NULL
+              AND expires_at > $3
+            "#,
+            id,
+            expires_at,
+            now
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    /// Marks a lease revoked. Returns `false` if it was already revoked.
+    pub async fn revoke(
+        &self,
+        id: Uuid,
+        revoked_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::revoke_in(&mut conn, id, revoked_at).await
+    }
+
+    pub async fn revoke_in(
+        conn: &mut PgConnection,
+        id: Uuid,
+        revoked_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE secret_leases
+            SET revoked_at = $2
+            WHERE id = $1
+              AND revoked_at IS NULL
+            "#,
+            id,
+            revoked_at
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    /// Deletes leases that expired or were revoked before `cutoff`.
+    pub async fn delete_inactive_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM secret_leases
+            WHERE expires_at < $1
+               OR revoked_at < $1
+            "#,
+            cutoff
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
@@ -0,0 +1,162 @@
+use super::prelude::*;
+
+const CA_COLUMNS: &str = r#"
+    id as "id",
+    vault_id as "vault_id",
+    public_key,
+    private_key_enc,
+    created_at as "created_at"
+"#;
+
+const ROLE_COLUMNS: &str = r#"
+    id as "id",
+    vault_id as "vault_id",
+    name,
+    cert_type,
+    allowed_principals as "allowed_principals",
+    default_principals as "default_principals",
+    allowed_extensions as "allowed_extensions",
+    default_extensions as "default_extensions",
+    default_ttl_seconds as "default_ttl_seconds",
+    max_ttl_seconds as "max_ttl_seconds",
+    created_at as "created_at",
+    updated_at as "updated_at"
+"#;
+
+pub struct SshCaRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> SshCaRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Stores the vault CA. Returns `false` if the vault already has one.
+    pub async fn create(&self, ca: &SshCa) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO ssh_cas (id, vault_id, public_key, private_key_enc, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (vault_id) DO NOTHING
+            "#,
+            ca.id,
+            ca.vault_id,
+            ca.public_key.as_str(),
+            ca.private_key_enc.as_slice(),
+            ca.created_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    pub async fn get_by_vault(&self, vault_id: Uuid) -> Result<Option<SshCa>, sqlx_core::Error> {
+        let query = format!("SELECT {CA_COLUMNS} FROM ssh_cas WHERE vault_id = $1");
+        query_as!(SshCa, &query, vault_id)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    pub async fn delete_by_vault(&self, vault_id: Uuid) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM ssh_cas
+            WHERE vault_id = $1
+            "#,
+            vault_id
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
+
+pub struct SshSigningRoleRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> SshSigningRoleRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates or replaces the role `(vault_id, name)`. An existing row keeps its id.
+    pub async fn upsert(&self, role: &SshSigningRole) -> Result<SshSigningRole, sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO ssh_signing_roles (
+                id, vault_id, name, cert_type, allowed_principals, default_principals,
+                allowed_extensions, default_extensions, default_ttl_seconds, max_ttl_seconds,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (vault_id, name) DO UPDATE
+            SET cert_type = EXCLUDED.cert_type,
+                allowed_principals = EXCLUDED.allowed_principals,
+                default_principals = EXCLUDED.default_principals,
+                allowed_extensions = EXCLUDED.allowed_extensions,
+                default_extensions = EXCLUDED.default_extensions,
+                default_ttl_seconds = EXCLUDED.default_ttl_seconds,
+                max_ttl_seconds = EXCLUDED.max_ttl_seconds,
+                updated_at = EXCLUDED.updated_at
+            "#,
+            role.id,
+            role.vault_id,
+            role.name.as_str(),
+            role.cert_type.as_str(),
+            &role.allowed_principals,
+            &role.default_principals,
+            &role.allowed_extensions,
+            &role.default_extensions,
+            role.default_ttl_seconds,
+            role.max_ttl_seconds,
+            role.created_at,
+            role.updated_at
+        )
+        .execute(self.pool)
+        .await?;
+        self.get_by_name(role.vault_id, &role.name)
+            .await?
+            .ok_or(sqlx_core::Error::RowNotFound)
+    }
+
+    pub async fn get_by_name(
+        &self,
+        vault_id: Uuid,
+        name: &str,
+    ) -> Result<Option<SshSigningRole>, sqlx_core::Error> {
+        let query = format!(
+            "SELECT {ROLE_COLUMNS} FROM ssh_signing_roles WHERE vault_id = $1 AND name = $2"
+        );
+        query_as!(SshSigningRole, &query, vault_id, name)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    pub async fn list_by_vault(
+        &self,
+        vault_id: Uuid,
+    ) -> Result<Vec<SshSigningRole>, sqlx_core::Error> {
+        let query = format!(
+            "SELECT {ROLE_COLUMNS} FROM ssh_signing_roles WHERE vault_id = $1 ORDER BY name"
+        );
+        query_as!(SshSigningRole, &query, vault_id)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    pub async fn delete(&self, vault_id: Uuid, name: &str) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM ssh_signing_roles
+            WHERE vault_id = $1 AND name = $2
+            "#,
+            vault_id,
+            name
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
@@ -0,0 +1,161 @@
+use super::prelude::*;
+use sqlx_postgres::PgConnection;
+
+const KEY_COLUMNS: &str = r#"
+    id as "id",
+    vault_id as "vault_id",
+    name,
+    latest_version as "latest_version",
+    min_decryption_version as "min_decryption_version",
+    created_at as "created_at",
+    updated_at as "updated_at"
+"#;
+
+const VERSION_COLUMNS: &str = r#"
+    key_id as "key_id",
+    version as "version",
+    key_enc,
+    created_at as "created_at"
+"#;
+
+pub struct TransitKeyRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> TransitKeyRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates a key together with its first version. Returns `false` if a key
+    /// with the same name already exists in the vault.
+    pub async fn create(
+        &self,
+        key: &TransitKey,
+        version: &TransitKeyVersion,
+    ) -> Result<bool, sqlx_core::Error> {
+        let mut tx = self.pool.begin().await?;
+        let inserted = query!(
+            r#"
+            INSERT INTO transit_keys (
+                id, vault_id, name, latest_version, min_decryption_version,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (vault_id, name) DO NOTHING
+            "#,
+            key.id,
+            key.vault_id,
+            key.name.as_str(),
+            key.latest_version,
+            key.min_decryption_version,
+            key.created_at,
+            key.updated_at
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if inserted == 0 {
+            return Ok(false);
+        }
+        insert_version(&mut tx, version).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn get_by_name(
+        &self,
+        vault_id: Uuid,
+        name: &str,
+    ) -> Result<Option<TransitKey>, sqlx_core::Error> {
+        let query =
+            format!("SELECT {KEY_COLUMNS} FROM transit_keys WHERE vault_id = $1 AND name = $2");
+        query_as!(TransitKey, &query, vault_id, name)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    pub async fn get_version(
+        &self,
+        key_id: Uuid,
+        version: i32,
+    ) -> Result<Option<TransitKeyVersion>, sqlx_core::Error> {
+        let query = format!(
+            "SELECT {VERSION_COLUMNS} FROM transit_key_versions WHERE key_id = $1 AND version = $2"
+        );
+        query_as!(TransitKeyVersion, &query, key_id, version)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    /// Adds `version` and makes it the latest. Returns `false` if another
+    /// rotation moved the key past `version.version - 1` first.
+    pub async fn add_version(
+        &self,
+        version: &TransitKeyVersion,
+        updated_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx_core::Error> {
+        let mut tx = self.pool.begin().await?;
+        let updated = query!(
+            r#"
+            UPDATE transit_keys
+            SET latest_version = $2, updated_at = $3
+            WHERE id = $1
+              AND latest_version = $2 - 1
+            "#,
+            version.key_id,
+            version.version,
+            updated_at
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if updated == 0 {
+            return Ok(false);
+        }
+        insert_version(&mut tx, version).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn set_min_decryption_version(
+        &self,
+        key_id: Uuid,
+        min_decryption_version: i32,
+        updated_at: DateTime<Utc>,
+    ) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE transit_keys
+            SET min_decryption_version = $2, updated_at = $3
+            WHERE id = $1
+              AND latest_version >= $2
+            "#,
+            key_id,
+            min_decryption_version,
+            updated_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+}
+
+async fn insert_version(
+    conn: &mut PgConnection,
+    version: &TransitKeyVersion,
+) -> Result<(), sqlx_core::Error> {
+    query!(
+        r#"
+        INSERT INTO transit_key_versions (key_id, version, key_enc, created_at)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        version.key_id,
+        version.version,
+        version.key_enc.as_slice(),
+        version.created_at
+    )
+    .execute(conn)
+    .await
+    .map(|_| ())
+}
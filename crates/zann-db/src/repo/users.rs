@@ -1,4 +1,5 @@
 use super::prelude::*;
+use sqlx_postgres::PgConnection;
 
 pub struct UserRepo<'a> {
     pool: &'a PgPool,
@@ -10,6 +11,11 @@ impl<'a> UserRepo<'a> {
     }
 
     pub async fn create(&self, user: &User) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, user).await
+    }
+
+    pub async fn create_in(conn: &mut PgConnection, user: &User) -> Result<(), sqlx_core::Error> {
         query!(
             r#"
             INSERT INTO users (
@@ -55,7 +61,7 @@ impl<'a> UserRepo<'a> {
             user.updated_at,
             user.last_login_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|_| ())
     }
@@ -172,6 +178,49 @@ impl<'a> UserRepo<'a> {
         .map(|result| result.rows_affected())
     }
 
+    /// The SSH principal an admin assigned to the user, if any.
+    pub async fn get_ssh_username(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<String>, sqlx_core::Error> {
+        let row = query!(
+            r#"
+            SELECT ssh_username as "ssh_username"
+            FROM users
+            WHERE id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(self.pool)
+        .await?;
+        match row {
+            Some(row) => row.try_get("ssh_username"),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn update_ssh_username_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        ssh_username: Option<&str>,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE users
+            SET ssh_username = $2,
+                row_version = row_version + 1,
+                updated_at = $3
+            WHERE id = $1
+            "#,
+            user_id,
+            ssh_username,
+            Utc::now()
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|result| result.rows_affected())
+    }
+
     pub async fn list(
         &self,
         limit: i64,
@@ -230,6 +279,16 @@ impl<'a> UserRepo<'a> {
         user_id: Uuid,
         row_version: i64,
         status: UserStatus,
+    ) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_status_in(&mut conn, user_id, row_version, status).await
+    }
+
+    pub async fn update_status_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        row_version: i64,
+        status: UserStatus,
     ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
@@ -244,7 +303,7 @@ impl<'a> UserRepo<'a> {
             status.as_i32(),
             Utc::now()
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| result.rows_affected())
     }
@@ -256,6 +315,26 @@ impl<'a> UserRepo<'a> {
         deleted_at: DateTime<Utc>,
         deleted_by_user_id: Uuid,
         deleted_by_device_id: Option<Uuid>,
+    ) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::delete_by_id_in(
+            &mut conn,
+            user_id,
+            row_version,
+            deleted_at,
+            deleted_by_user_id,
+            deleted_by_device_id,
+        )
+        .await
+    }
+
+    pub async fn delete_by_id_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        row_version: i64,
+        deleted_at: DateTime<Utc>,
+        deleted_by_user_id: Uuid,
+        deleted_by_device_id: Option<Uuid>,
     ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
@@ -274,7 +353,7 @@ impl<'a> UserRepo<'a> {
             deleted_by_device_id,
             deleted_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| result.rows_affected())
     }
@@ -284,6 +363,16 @@ impl<'a> UserRepo<'a> {
         user_id: Uuid,
         row_version: i64,
         password_hash: Option<&str>,
+    ) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_password_hash_in(&mut conn, user_id, row_version, password_hash).await
+    }
+
+    pub async fn update_password_hash_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        row_version: i64,
+        password_hash: Option<&str>,
     ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
@@ -298,7 +387,7 @@ impl<'a> UserRepo<'a> {
             password_hash,
             Utc::now()
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| result.rows_affected())
     }
@@ -308,6 +397,16 @@ impl<'a> UserRepo<'a> {
         user_id: Uuid,
         row_version: i64,
         recovery_key_hash: &str,
+    ) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_recovery_key_hash_in(&mut conn, user_id, row_version, recovery_key_hash).await
+    }
+
+    pub async fn update_recovery_key_hash_in(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        row_version: i64,
+        recovery_key_hash: &str,
     ) -> Result<u64, sqlx_core::Error> {
         query!(
             r#"
@@ -322,7 +421,7 @@ impl<'a> UserRepo<'a> {
             recovery_key_hash,
             Utc::now()
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| result.rows_affected())
     }
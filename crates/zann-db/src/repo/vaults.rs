@@ -1,5 +1,8 @@
 use super::prelude::*;
+use sqlx_core::from_row::FromRow;
+use sqlx_postgres::PgConnection;
 use tracing::{instrument, Span};
+use zann_core::VaultMemberRole;
 
 pub struct VaultRepo<'a> {
     pool: &'a PgPool,
@@ -10,9 +13,14 @@ impl<'a> VaultRepo<'a> {
         Self { pool }
     }
 
+    pub async fn create(&self, vault: &Vault) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, vault).await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self, vault),
+        skip(conn, vault),
         fields(
             vault_id = %vault.id,
             db.system = "postgresql",
@@ -20,7 +28,7 @@ impl<'a> VaultRepo<'a> {
             db.query = "vaults.create"
         )
     )]
-    pub async fn create(&self, vault: &Vault) -> Result<(), sqlx_core::Error> {
+    pub async fn create_in(conn: &mut PgConnection, vault: &Vault) -> Result<(), sqlx_core::Error> {
         let tags = vault
             .tags
             .clone()
@@ -47,7 +55,7 @@ impl<'a> VaultRepo<'a> {
             vault.row_version,
             vault.created_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             Span::current().record("db.rows", result.rows_affected() as i64);
@@ -152,8 +160,18 @@ impl<'a> VaultRepo<'a> {
                 v.row_version as "row_version",
                 v.created_at as "created_at"
             FROM vaults v
-            INNER JOIN vault_members vm ON vm.vault_id = v.id
-            WHERE vm.user_id = $1 AND v.deleted_at IS NULL
+            WHERE v.deleted_at IS NULL
+              AND (
+                EXISTS (
+                    SELECT 1 FROM vault_members vm
+                    WHERE vm.vault_id = v.id AND vm.user_id = $1
+                )
+                OR EXISTS (
+                    SELECT 1 FROM vault_group_grants vg
+                    INNER JOIN group_members gm ON gm.group_id = vg.group_id
+                    WHERE vg.vault_id = v.id AND gm.user_id = $1
+                )
+              )
             ORDER BY v.created_at {}
             LIMIT $2 OFFSET $3
             "#,
@@ -234,13 +252,33 @@ impl<'a> VaultRepo<'a> {
         Ok(vaults)
     }
 
+    pub async fn delete_by_id(
+        &self,
+        id: Uuid,
+        row_version: i64,
+        deleted_at: DateTime<Utc>,
+        deleted_by_user_id: Uuid,
+        deleted_by_device_id: Option<Uuid>,
+    ) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::delete_by_id_in(
+            &mut conn,
+            id,
+            row_version,
+            deleted_at,
+            deleted_by_user_id,
+            deleted_by_device_id,
+        )
+        .await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self),
+        skip(conn),
         fields(vault_id = %id, db.system = "postgresql", db.operation = "UPDATE", db.query = "vaults.delete_by_id")
     )]
-    pub async fn delete_by_id(
-        &self,
+    pub async fn delete_by_id_in(
+        conn: &mut PgConnection,
         id: Uuid,
         row_version: i64,
         deleted_at: DateTime<Utc>,
@@ -262,7 +300,7 @@ impl<'a> VaultRepo<'a> {
             deleted_by_user_id,
             deleted_by_device_id
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| {
             let rows = result.rows_affected();
@@ -271,9 +309,18 @@ impl<'a> VaultRepo<'a> {
         })
     }
 
+    pub async fn update_key_by_id(
+        &self,
+        id: Uuid,
+        vault_key_enc: &[u8],
+    ) -> Result<u64, sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::update_key_by_id_in(&mut conn, id, vault_key_enc).await
+    }
+
     #[instrument(
         level = "debug",
-        skip(self, vault_key_enc),
+        skip(conn, vault_key_enc),
         fields(
             vault_id = %id,
             vault_key_len = vault_key_enc.len(),
@@ -282,8 +329,8 @@ impl<'a> VaultRepo<'a> {
             db.query = "vaults.update_key_by_id"
         )
     )]
-    pub async fn update_key_by_id(
-        &self,
+    pub async fn update_key_by_id_in(
+        conn: &mut PgConnection,
         id: Uuid,
         vault_key_enc: &[u8],
     ) -> Result<u64, sqlx_core::Error> {
@@ -297,12 +344,27 @@ impl<'a> VaultRepo<'a> {
             id,
             vault_key_enc
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|result| result.rows_affected())
     }
 }
 
+/// A vault member joined with their email. `group_slug` names the granting
+/// group when access is inherited through a group grant.
+pub struct VaultMemberListing {
+    pub member: VaultMember,
+    pub email: String,
+    pub group_slug: Option<String>,
+}
+
+/// A group grant joined with the granted group's slug and name.
+pub struct VaultGroupGrantListing {
+    pub grant: VaultGroupGrant,
+    pub slug: String,
+    pub name: String,
+}
+
 pub struct VaultMemberRepo<'a> {
     pool: &'a PgPool,
 }
@@ -313,6 +375,14 @@ impl<'a> VaultMemberRepo<'a> {
     }
 
     pub async fn create(&self, member: &VaultMember) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, member).await
+    }
+
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        member: &VaultMember,
+    ) -> Result<(), sqlx_core::Error> {
         query!(
             r#"
             INSERT INTO vault_members (vault_id, user_id, role, created_at)
@@ -323,7 +393,7 @@ impl<'a> VaultMemberRepo<'a> {
             member.role.as_i32(),
             member.created_at
         )
-        .execute(self.pool)
+        .execute(&mut *conn)
         .await
         .map(|_| ())
     }
@@ -372,6 +442,53 @@ impl<'a> VaultMemberRepo<'a> {
         .await
     }
 
+    /// Direct members and members inherited through group grants, once per
+    /// group they inherit from, with their emails.
+    pub async fn list_with_emails(
+        &self,
+        vault_id: Uuid,
+    ) -> Result<Vec<VaultMemberListing>, sqlx_core::Error> {
+        let rows = query!(
+            r#"
+            SELECT
+                vm.vault_id as "vault_id",
+                vm.user_id as "user_id",
+                vm.role as "role",
+                vm.created_at as "created_at",
+                COALESCE(u.email, '') as "email",
+                NULL::TEXT as "group_slug"
+            FROM vault_members vm
+            LEFT JOIN users u ON u.id = vm.user_id
+            WHERE vm.vault_id = $1
+            UNION ALL
+            SELECT
+                vg.vault_id,
+                gm.user_id,
+                vg.role,
+                vg.created_at,
+                COALESCE(u.email, ''),
+                g.slug
+            FROM vault_group_grants vg
+            INNER JOIN groups g ON g.id = vg.group_id
+            INNER JOIN group_members gm ON gm.group_id = vg.group_id
+            LEFT JOIN users u ON u.id = gm.user_id
+            WHERE vg.vault_id = $1
+            "#,
+            vault_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(VaultMemberListing {
+                    member: VaultMember::from_row(row)?,
+                    email: row.try_get("email")?,
+                    group_slug: row.try_get("group_slug")?,
+                })
+            })
+            .collect()
+    }
+
     pub async fn list_by_user(&self, user_id: Uuid) -> Result<Vec<VaultMember>, sqlx_core::Error> {
         query_as!(
             VaultMember,
@@ -389,4 +506,255 @@ impl<'a> VaultMemberRepo<'a> {
         .fetch_all(self.pool)
         .await
     }
+
+    /// Strongest role the user holds on the vault, either directly or through
+    /// any group granted access to it.
+    pub async fn effective_role(
+        &self,
+        vault_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<VaultMemberRole>, sqlx_core::Error> {
+        let row = query!(
+            r#"
+            SELECT MIN(role) as "role"
+            FROM (
+                SELECT role FROM vault_members
+                WHERE vault_id = $1 AND user_id = $2
+                UNION ALL
+                SELECT vg.role FROM vault_group_grants vg
+                INNER JOIN group_members gm ON gm.group_id = vg.group_id
+                WHERE vg.vault_id = $1 AND gm.user_id = $2
+            ) roles
+            "#,
+            vault_id,
+            user_id
+        )
+        .fetch_one(self.pool)
+        .await?;
+        let role: Option<i16> = row.try_get("role")?;
+        role.map(|role| {
+            VaultMemberRole::try_from(i32::from(role))
+                .map_err(|err| sqlx_core::Error::Decode(Box::new(err)))
+        })
+        .transpose()
+    }
+
+    /// Locks every membership row of a vault for the rest of the transaction,
+    /// so concurrent role changes cannot race past the last-admin check.
+    pub async fn lock_by_vault(
+        conn: &mut PgConnection,
+        vault_id: Uuid,
+    ) -> Result<Vec<VaultMember>, sqlx_core::Error> {
+        query_as!(
+            VaultMember,
+            r#"
+            SELECT
+                vault_id as "vault_id",
+                user_id as "user_id",
+                role as "role",
+                created_at as "created_at"
+            FROM vault_members
+            WHERE vault_id = $1
+            ORDER BY user_id
+            FOR UPDATE
+            "#,
+            vault_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+    }
+
+    pub async fn update_role_in(
+        conn: &mut PgConnection,
+        vault_id: Uuid,
+        user_id: Uuid,
+        role: VaultMemberRole,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE vault_members
+            SET role = $3
+            WHERE vault_id = $1 AND user_id = $2
+            "#,
+            vault_id,
+            user_id,
+            role.as_i32()
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|result| result.rows_affected())
+    }
+
+    pub async fn delete_in(
+        conn: &mut PgConnection,
+        vault_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM vault_members
+            WHERE vault_id = $1 AND user_id = $2
+            "#,
+            vault_id,
+            user_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
+
+pub struct VaultGroupGrantRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> VaultGroupGrantRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, grant: &VaultGroupGrant) -> Result<(), sqlx_core::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::create_in(&mut conn, grant).await
+    }
+
+    pub async fn create_in(
+        conn: &mut PgConnection,
+        grant: &VaultGroupGrant,
+    ) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO vault_group_grants (vault_id, group_id, role, created_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+            grant.vault_id,
+            grant.group_id,
+            grant.role.as_i32(),
+            grant.created_at
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn get(
+        &self,
+        vault_id: Uuid,
+        group_id: Uuid,
+    ) -> Result<Option<VaultGroupGrant>, sqlx_core::Error> {
+        query_as!(
+            VaultGroupGrant,
+            r#"
+            SELECT
+                vault_id as "vault_id",
+                group_id as "group_id",
+                role as "role",
+                created_at as "created_at"
+            FROM vault_group_grants
+            WHERE vault_id = $1 AND group_id = $2
+            "#,
+            vault_id,
+            group_id
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// Group grants of a vault with their groups, ordered by slug.
+    pub async fn list_by_vault(
+        &self,
+        vault_id: Uuid,
+    ) -> Result<Vec<VaultGroupGrantListing>, sqlx_core::Error> {
+        let rows = query!(
+            r#"
+            SELECT
+                vg.vault_id as "vault_id",
+                vg.group_id as "group_id",
+                vg.role as "role",
+                vg.created_at as "created_at",
+                g.slug as "slug",
+                g.name as "name"
+            FROM vault_group_grants vg
+            INNER JOIN groups g ON g.id = vg.group_id
+            WHERE vg.vault_id = $1
+            ORDER BY g.slug
+            "#,
+            vault_id
+        )
+        .fetch_all(self.pool)
+        .await?;
+        rows.iter()
+            .map(|row| {
+                Ok(VaultGroupGrantListing {
+                    grant: VaultGroupGrant::from_row(row)?,
+                    slug: row.try_get("slug")?,
+                    name: row.try_get("name")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Locks every group grant of a vault; taken together with
+    /// [`VaultMemberRepo::lock_by_vault`] for the last-admin check.
+    pub async fn lock_by_vault(
+        conn: &mut PgConnection,
+        vault_id: Uuid,
+    ) -> Result<Vec<VaultGroupGrant>, sqlx_core::Error> {
+        query_as!(
+            VaultGroupGrant,
+            r#"
+            SELECT
+                vault_id as "vault_id",
+                group_id as "group_id",
+                role as "role",
+                created_at as "created_at"
+            FROM vault_group_grants
+            WHERE vault_id = $1
+            ORDER BY group_id
+            FOR UPDATE
+            "#,
+            vault_id
+        )
+        .fetch_all(&mut *conn)
+        .await
+    }
+
+    pub async fn update_role_in(
+        conn: &mut PgConnection,
+        vault_id: Uuid,
+        group_id: Uuid,
+        role: VaultMemberRole,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE vault_group_grants
+            SET role = $3
+            WHERE vault_id = $1 AND group_id = $2
+            "#,
+            vault_id,
+            group_id,
+            role.as_i32()
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|result| result.rows_affected())
+    }
+
+    pub async fn delete_in(
+        conn: &mut PgConnection,
+        vault_id: Uuid,
+        group_id: Uuid,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM vault_group_grants
+            WHERE vault_id = $1 AND group_id = $2
+            "#,
+            vault_id,
+            group_id
+        )
+        .execute(&mut *conn)
+        .await
+        .map(|result| result.rows_affected())
+    }
 }
@@ -0,0 +1,359 @@
+use super::prelude::*;
+
+const WEBHOOK_COLUMNS: &str = r#"
+    id as "id",
+    vault_id as "vault_id",
+    url,
+    path_prefix,
+    secret_enc,
+    enabled,
+    last_seq as "last_seq",
+    created_by_user_id as "created_by_user_id",
+    created_at as "created_at",
+    updated_at as "updated_at"
+"#;
+
+const DELIVERY_COLUMNS: &str = r#"
+    id as "id",
+    webhook_id as "webhook_id",
+    seq as "seq",
+    payload as "payload",
+    status,
+    attempts as "attempts",
+    next_attempt_at as "next_attempt_at",
+    response_status as "response_status",
+    last_error,
+    created_at as "created_at",
+    updated_at as "updated_at"
+"#;
+
+/// A row of the `changes` stream with the item path and the acting device,
+/// which is what a webhook notice carries.
+#[derive(Debug, Clone)]
+pub struct ChangeNotice {
+    pub seq: i64,
+    pub vault_id: Uuid,
+    pub item_id: Uuid,
+    pub path: String,
+    pub type_id: String,
+    pub op: i32,
+    pub version: i64,
+    pub device_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub user_email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct VaultWebhookRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> VaultWebhookRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, webhook: &VaultWebhook) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO vault_webhooks (
+                id, vault_id, url, path_prefix, secret_enc, enabled, last_seq,
+                created_by_user_id, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+            webhook.id,
+            webhook.vault_id,
+            webhook.url.as_str(),
+            webhook.path_prefix.as_deref(),
+            webhook.secret_enc.as_slice(),
+            webhook.enabled,
+            webhook.last_seq,
+            webhook.created_by_user_id,
+            webhook.created_at,
+            webhook.updated_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn get_by_id(
+        &self,
+        vault_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<VaultWebhook>, sqlx_core::Error> {
+        let query =
+            format!("SELECT {WEBHOOK_COLUMNS} FROM vault_webhooks WHERE vault_id = $1 AND id = $2");
+        query_as!(VaultWebhook, &query, vault_id, id)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    pub async fn list_by_vault(
+        &self,
+        vault_id: Uuid,
+    ) -> Result<Vec<VaultWebhook>, sqlx_core::Error> {
+        let query = format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM vault_webhooks WHERE vault_id = $1 ORDER BY created_at, id"
+        );
+        query_as!(VaultWebhook, &query, vault_id)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    pub async fn list_enabled(&self) -> Result<Vec<VaultWebhook>, sqlx_core::Error> {
+        let query = format!(
+            "SELECT {WEBHOOK_COLUMNS} FROM vault_webhooks WHERE enabled ORDER BY created_at, id"
+        );
+        query_as!(VaultWebhook, &query).fetch_all(self.pool).await
+    }
+
+    /// Saves the editable settings; `last_seq` is only moved by [`Self::advance`].
+    pub async fn update(&self, webhook: &VaultWebhook) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE vault_webhooks
+            SET url = $3, path_prefix = $4, secret_enc = $5, enabled = $6, updated_at = $7
+            WHERE vault_id = $1 AND id = $2
+            "#,
+            webhook.vault_id,
+            webhook.id,
+            webhook.url.as_str(),
+            webhook.path_prefix.as_deref(),
+            webhook.secret_enc.as_slice(),
+            webhook.enabled,
+            webhook.updated_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    pub async fn delete(&self, vault_id: Uuid, id: Uuid) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM vault_webhooks
+            WHERE vault_id = $1 AND id = $2
+            "#,
+            vault_id,
+            id
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+
+    /// Moves the queue position from `from_seq` to `to_seq`. Returns `false` if
+    /// another worker moved it first.
+    pub async fn advance(
+        &self,
+        id: Uuid,
+        from_seq: i64,
+        to_seq: i64,
+    ) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE vault_webhooks
+            SET last_seq = $3
+            WHERE id = $1 AND last_seq = $2
+            "#,
+            id,
+            from_seq,
+            to_seq
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    /// Changes in a vault after `since_seq`, oldest first.
+    pub async fn list_changes_since(
+        &self,
+        vault_id: Uuid,
+        since_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<ChangeNotice>, sqlx_core::Error> {
+        let rows = query!(
+            r#"
+            SELECT
+                c.seq,
+                c.vault_id,
+                c.item_id,
+                i.path,
+                i.type_id,
+                c.op,
+                c.version,
+                c.device_id,
+                d.user_id,
+                u.email,
+                c.created_at
+            FROM changes c
+            JOIN items i ON i.id = c.item_id
+            LEFT JOIN devices d ON d.id = c.device_id
+            LEFT JOIN users u ON u.id = d.user_id
+            WHERE c.vault_id = $1 AND c.seq > $2
+            ORDER BY c.seq ASC
+            LIMIT $3
+            "#,
+            vault_id,
+            since_seq,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await?;
+        rows.into_iter()
+            .map(|row| {
+                let op: i16 = row.try_get("op")?;
+                Ok(ChangeNotice {
+                    seq: row.try_get("seq")?,
+                    vault_id: row.try_get("vault_id")?,
+                    item_id: row.try_get("item_id")?,
+                    path: row.try_get("path")?,
+                    type_id: row.try_get("type_id")?,
+                    op: i32::from(op),
+                    version: row.try_get("version")?,
+                    device_id: row.try_get("device_id")?,
+                    user_id: row.try_get("user_id")?,
+                    user_email: row.try_get("email")?,
+                    created_at: row.try_get("created_at")?,
+                })
+            })
+            .collect()
+    }
+}
+
+pub struct WebhookDeliveryRepo<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> WebhookDeliveryRepo<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queues a delivery. Returns `false` if the change was already queued.
+    pub async fn create(&self, delivery: &WebhookDelivery) -> Result<bool, sqlx_core::Error> {
+        query!(
+            r#"
+            INSERT INTO webhook_deliveries (
+                id, webhook_id, seq, payload, status, attempts, next_attempt_at,
+                response_status, last_error, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (webhook_id, seq) DO NOTHING
+            "#,
+            delivery.id,
+            delivery.webhook_id,
+            delivery.seq,
+            &delivery.payload,
+            delivery.status.as_str(),
+            delivery.attempts,
+            delivery.next_attempt_at,
+            delivery.response_status,
+            delivery.last_error.as_deref(),
+            delivery.created_at,
+            delivery.updated_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected() > 0)
+    }
+
+    pub async fn get_by_id(
+        &self,
+        webhook_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<WebhookDelivery>, sqlx_core::Error> {
+        let query = format!(
+            "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries WHERE webhook_id = $1 AND id = $2"
+        );
+        query_as!(WebhookDelivery, &query, webhook_id, id)
+            .fetch_optional(self.pool)
+            .await
+    }
+
+    /// Newest first, optionally limited to one status.
+    pub async fn list_by_webhook(
+        &self,
+        webhook_id: Uuid,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<WebhookDelivery>, sqlx_core::Error> {
+        let query = format!(
+            r#"
+            SELECT {DELIVERY_COLUMNS}
+            FROM webhook_deliveries
+            WHERE webhook_id = $1
+              AND ($2::text IS NULL OR status = $2)
+            ORDER BY seq DESC, created_at DESC
+            LIMIT $3 OFFSET $4
+            "#
+        );
+        query_as!(WebhookDelivery, &query, webhook_id, status, limit, offset)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// Pending deliveries of enabled webhooks whose next attempt is due, oldest first.
+    pub async fn list_due(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, sqlx_core::Error> {
+        let query = format!(
+            r#"
+            SELECT {DELIVERY_COLUMNS}
+            FROM webhook_deliveries
+            WHERE status = 'pending'
+              AND next_attempt_at <= $1
+              AND webhook_id IN (SELECT id FROM vault_webhooks WHERE enabled)
+            ORDER BY next_attempt_at, seq
+            LIMIT $2
+            "#
+        );
+        query_as!(WebhookDelivery, &query, now, limit)
+            .fetch_all(self.pool)
+            .await
+    }
+
+    /// Stores the outcome of an attempt, or resets a delivery for redelivery.
+    pub async fn update_state(&self, delivery: &WebhookDelivery) -> Result<(), sqlx_core::Error> {
+        query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = $3, next_attempt_at = $4, response_status = $5,
+                last_error = $6, updated_at = $7
+            WHERE id = $1
+            "#,
+            delivery.id,
+            delivery.status.as_str(),
+            delivery.attempts,
+            delivery.next_attempt_at,
+            delivery.response_status,
+            delivery.last_error.as_deref(),
+            delivery.updated_at
+        )
+        .execute(self.pool)
+        .await
+        .map(|_| ())
+    }
+
+    /// Drops finished deliveries last touched before `cutoff`.
+    pub async fn delete_finished_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<u64, sqlx_core::Error> {
+        query!(
+            r#"
+            DELETE FROM webhook_deliveries
+            WHERE status <> 'pending' AND updated_at < $1
+            "#,
+            cutoff
+        )
+        .execute(self.pool)
+        .await
+        .map(|result| result.rows_affected())
+    }
+}
@@ -8,12 +8,14 @@ use std::env;
 use std::str::FromStr;
 use uuid::Uuid;
 use zann_core::{
-    CachePolicy, Device, Group, GroupMember, Item, ServiceAccount, ServiceAccountSession, User,
-    UserStatus, Vault, VaultKind,
+    CachePolicy, Device, Group, GroupMember, Invite, InviteVaultRole, Item, ItemRotationSchedule,
+    ServiceAccount, ServiceAccountSession, User, UserStatus, Vault, VaultGroupGrant, VaultKind,
+    VaultMember, VaultMemberRole,
 };
 use zann_db::repo::{
-    ChangeRepo, DeviceRepo, GroupMemberRepo, GroupRepo, ItemRepo, ServiceAccountRepo,
-    ServiceAccountSessionRepo, UserRepo, VaultMemberRepo, VaultRepo,
+    ChangeRepo, DeviceRepo, GroupMemberRepo, GroupRepo, InviteRepo, ItemRepo,
+    ItemRotationScheduleRepo, SealConfig, SealConfigRepo, ServiceAccountRepo,
+    ServiceAccountSessionRepo, UserRepo, VaultGroupGrantRepo, VaultMemberRepo, VaultRepo,
 };
 use zann_db::{migrate, PgPool};
 
@@ -311,6 +313,26 @@ async fn service_account_repo_crud() {
         .expect("list_by_owner");
     assert_eq!(list.len(), 1);
 
+    let vault_id = Uuid::now_v7();
+    let scoped = ServiceAccount {
+        id: Uuid::now_v7(),
+        name: "deploy".to_string(),
+        token_prefix: "zann_sa_def".to_string(),
+        scopes: SqlxJson(vec![format!("{vault_id}/prefix:apps::billing:read")]),
+        allowed_ips: None,
+        ..account.clone()
+    };
+    sa_repo
+        .create(&scoped)
+        .await
+        .expect("create scoped service account");
+    let by_vault = sa_repo
+        .list_by_vault(vault_id, 10, 0, "desc")
+        .await
+        .expect("list_by_vault");
+    assert_eq!(by_vault.len(), 1);
+    assert_eq!(by_vault[0].id, scoped.id);
+
     let updated = sa_repo
         .update_usage(account.id, now, Some("10.0.0.1"), Some("tests"), 1)
         .await
@@ -453,6 +475,35 @@ async fn vault_and_item_repos_workflow() {
         .expect("list_by_user");
     assert_eq!(list.len(), 1);
 
+    let mut tx = pool.begin().await.expect("begin");
+    let locked = VaultMemberRepo::lock_by_vault(&mut tx, vault.id)
+        .await
+        .expect("lock members");
+    assert_eq!(locked.len(), 1);
+    let updated = VaultMemberRepo::update_role_in(
+        &mut tx,
+        vault.id,
+        user.id,
+        zann_core::VaultMemberRole::Operator,
+    )
+    .await
+    .expect("update role");
+    assert_eq!(updated, 1);
+    tx.commit().await.expect("commit");
+    let member = vault_member_repo
+        .get(vault.id, user.id)
+        .await
+        .expect("get member")
+        .expect("member");
+    assert_eq!(member.role, zann_core::VaultMemberRole::Operator);
+
+    let mut tx = pool.begin().await.expect("begin");
+    let deleted = VaultMemberRepo::delete_in(&mut tx, vault.id, Uuid::now_v7())
+        .await
+        .expect("delete missing member");
+    assert_eq!(deleted, 0);
+    tx.commit().await.expect("commit");
+
     let item = Item {
         id: Uuid::now_v7(),
         vault_id: vault.id,
@@ -470,6 +521,7 @@ async fn vault_and_item_repos_workflow() {
         deleted_at: None,
         deleted_by_user_id: None,
         deleted_by_device_id: None,
+        expires_at: None,
         created_at: now,
         updated_at: now,
     };
@@ -489,6 +541,44 @@ async fn vault_and_item_repos_workflow() {
     let updated = item_repo.update(&updated_item).await.expect("update item");
     assert_eq!(updated, 1);
 
+    let expires_at = now + chrono::Duration::days(10);
+    updated_item.row_version = 2;
+    updated_item.expires_at = Some(expires_at);
+    let updated = item_repo.update(&updated_item).await.expect("set expiry");
+    assert_eq!(updated, 1);
+    let due = |items: Vec<Item>| items.iter().any(|due| due.id == item.id);
+    let cutoff = now + chrono::Duration::days(30);
+    assert!(due(item_repo
+        .list_expiry_unnotified(cutoff, 1000)
+        .await
+        .expect("list expiring")));
+    assert!(!due(item_repo
+        .list_expiry_unnotified(now + chrono::Duration::days(5), 1000)
+        .await
+        .expect("list expiring")));
+    let stored = item_repo
+        .get_by_id(item.id)
+        .await
+        .expect("get item")
+        .expect("item");
+    let marked = item_repo
+        .mark_expiry_notified(item.id, stored.expires_at.expect("expires_at"), Utc::now())
+        .await
+        .expect("mark notified");
+    assert_eq!(marked, 1);
+    assert!(!due(item_repo
+        .list_expiry_unnotified(cutoff, 1000)
+        .await
+        .expect("list expiring")));
+
+    updated_item.row_version = 3;
+    updated_item.expires_at = Some(expires_at + chrono::Duration::days(1));
+    item_repo.update(&updated_item).await.expect("move expiry");
+    assert!(due(item_repo
+        .list_expiry_unnotified(cutoff, 1000)
+        .await
+        .expect("list expiring")));
+
     let change = zann_core::Change {
         seq: 0,
         vault_id: vault.id,
@@ -506,3 +596,413 @@ async fn vault_and_item_repos_workflow() {
         .expect("last_seq");
     assert!(last_seq >= 1);
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn vault_group_grants_resolve_strongest_role() {
+    let pool = setup_db().await;
+    let user_repo = UserRepo::new(&pool);
+    let group_repo = GroupRepo::new(&pool);
+    let group_member_repo = GroupMemberRepo::new(&pool);
+    let vault_repo = VaultRepo::new(&pool);
+    let vault_member_repo = VaultMemberRepo::new(&pool);
+    let grant_repo = VaultGroupGrantRepo::new(&pool);
+
+    let now = Utc::now();
+    let lead = test_user(now, "lead@example.com", None);
+    let engineer = test_user(now, "engineer@example.com", None);
+    user_repo.create(&lead).await.expect("create lead");
+    user_repo.create(&engineer).await.expect("create engineer");
+
+    let group = Group {
+        id: Uuid::now_v7(),
+        slug: "sre".to_string(),
+        name: "SRE".to_string(),
+        created_at: now,
+    };
+    group_repo.create(&group).await.expect("create group");
+    for user_id in [lead.id, engineer.id] {
+        group_member_repo
+            .create(&GroupMember {
+                group_id: group.id,
+                user_id,
+                created_at: now,
+            })
+            .await
+            .expect("add group member");
+    }
+
+    let vault = Vault {
+        id: Uuid::now_v7(),
+        slug: "infra".to_string(),
+        name: "Infra".to_string(),
+        kind: VaultKind::Shared,
+        encryption_type: zann_core::VaultEncryptionType::Server,
+        vault_key_enc: vec![1, 2, 3],
+        cache_policy: CachePolicy::Full,
+        tags: None,
+        deleted_at: None,
+        deleted_by_user_id: None,
+        deleted_by_device_id: None,
+        row_version: 1,
+        created_at: now,
+    };
+    vault_repo.create(&vault).await.expect("create vault");
+    vault_member_repo
+        .create(&VaultMember {
+            vault_id: vault.id,
+            user_id: lead.id,
+            role: VaultMemberRole::Admin,
+            created_at: now,
+        })
+        .await
+        .expect("create member");
+
+    assert_eq!(
+        vault_member_repo
+            .effective_role(vault.id, engineer.id)
+            .await
+            .expect("effective role"),
+        None
+    );
+    assert!(vault_repo
+        .list_by_user(engineer.id, 10, 0, "asc")
+        .await
+        .expect("list_by_user")
+        .is_empty());
+
+    grant_repo
+        .create(&VaultGroupGrant {
+            vault_id: vault.id,
+            group_id: group.id,
+            role: VaultMemberRole::Member,
+            created_at: now,
+        })
+        .await
+        .expect("create grant");
+    let grants = grant_repo
+        .list_by_vault(vault.id)
+        .await
+        .expect("list grants");
+    assert_eq!(grants.len(), 1);
+    assert_eq!(grants[0].grant.group_id, group.id);
+    assert_eq!(grants[0].slug, group.slug);
+
+    // Direct admin outranks the group grant; the engineer only inherits it.
+    assert_eq!(
+        vault_member_repo
+            .effective_role(vault.id, lead.id)
+            .await
+            .expect("effective role"),
+        Some(VaultMemberRole::Admin)
+    );
+    assert_eq!(
+        vault_member_repo
+            .effective_role(vault.id, engineer.id)
+            .await
+            .expect("effective role"),
+        Some(VaultMemberRole::Member)
+    );
+    let listed = vault_repo
+        .list_by_user(lead.id, 10, 0, "asc")
+        .await
+        .expect("list_by_user");
+    assert_eq!(
+        listed.len(),
+        1,
+        "direct and group access list the vault once"
+    );
+    assert_eq!(
+        vault_repo
+            .list_by_user(engineer.id, 10, 0, "asc")
+            .await
+            .expect("list_by_user")
+            .len(),
+        1
+    );
+
+    let mut tx = pool.begin().await.expect("begin");
+    let locked = VaultGroupGrantRepo::lock_by_vault(&mut tx, vault.id)
+        .await
+        .expect("lock grants");
+    assert_eq!(locked.len(), 1);
+    let updated =
+        VaultGroupGrantRepo::update_role_in(&mut tx, vault.id, group.id, VaultMemberRole::Operator)
+            .await
+            .expect("update grant");
+    assert_eq!(updated, 1);
+    tx.commit().await.expect("commit");
+    let grant = grant_repo
+        .get(vault.id, group.id)
+        .await
+        .expect("get grant")
+        .expect("grant");
+    assert_eq!(grant.role, VaultMemberRole::Operator);
+
+    let mut tx = pool.begin().await.expect("begin");
+    let deleted = VaultGroupGrantRepo::delete_in(&mut tx, vault.id, group.id)
+        .await
+        .expect("delete grant");
+    assert_eq!(deleted, 1);
+    tx.commit().await.expect("commit");
+    assert_eq!(
+        vault_member_repo
+            .effective_role(vault.id, engineer.id)
+            .await
+            .expect("effective role"),
+        None
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn invite_repo_accepts_once() {
+    let pool = setup_db().await;
+    let user_repo = UserRepo::new(&pool);
+    let repo = InviteRepo::new(&pool);
+
+    let now = Utc::now();
+    let admin = test_user(now, "admin@example.com", None);
+    let invitee = test_user(now, "invitee@example.com", None);
+    user_repo.create(&admin).await.expect("create admin");
+    user_repo.create(&invitee).await.expect("create invitee");
+
+    let vault_id = Uuid::now_v7();
+    let invite = Invite {
+        id: Uuid::now_v7(),
+        email: invitee.email.clone(),
+        token_hash: "hash-a".to_string(),
+        group_ids: SqlxJson(vec![Uuid::now_v7()]),
+        vault_roles: SqlxJson(vec![InviteVaultRole {
+            vault_id,
+            role: VaultMemberRole::Operator,
+        }]),
+        created_by_user_id: Some(admin.id),
+        expires_at: now + chrono::Duration::days(1),
+        created_at: now,
+        accepted_at: None,
+        accepted_user_id: None,
+        revoked_at: None,
+    };
+    repo.create(&invite).await.expect("create invite");
+    let expired = Invite {
+        id: Uuid::now_v7(),
+        token_hash: "hash-b".to_string(),
+        expires_at: now - chrono::Duration::minutes(1),
+        group_ids: SqlxJson(Vec::new()),
+        vault_roles: SqlxJson(Vec::new()),
+        ..invite.clone()
+    };
+    repo.create(&expired).await.expect("create expired invite");
+
+    let fetched = repo
+        .get_by_token_hash("hash-a")
+        .await
+        .expect("get_by_token_hash")
+        .expect("invite exists");
+    assert_eq!(fetched.id, invite.id);
+    assert_eq!(fetched.vault_roles.0[0].vault_id, vault_id);
+    assert_eq!(fetched.vault_roles.0[0].role, VaultMemberRole::Operator);
+    assert_eq!(repo.list(10, 0, "asc").await.expect("list").len(), 2);
+
+    let mut tx = pool.begin().await.expect("begin");
+    let accepted = InviteRepo::accept_in(&mut tx, expired.id, invitee.id, now)
+        .await
+        .expect("accept expired");
+    assert_eq!(accepted, 0);
+    let accepted = InviteRepo::accept_in(&mut tx, invite.id, invitee.id, now)
+        .await
+        .expect("accept");
+    assert_eq!(accepted, 1);
+    let accepted = InviteRepo::accept_in(&mut tx, invite.id, invitee.id, now)
+        .await
+        .expect("accept again");
+    assert_eq!(accepted, 0);
+    tx.commit().await.expect("commit");
+
+    assert_eq!(repo.revoke(invite.id, now).await.expect("revoke"), 0);
+    assert_eq!(repo.revoke(expired.id, now).await.expect("revoke"), 1);
+    let accepted = repo
+        .get_by_id(invite.id)
+        .await
+        .expect("get_by_id")
+        .expect("invite exists");
+    assert_eq!(accepted.accepted_user_id, Some(invitee.id));
+    assert!(accepted.revoked_at.is_none());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn seal_config_is_recorded_once() {
+    let pool = setup_db().await;
+    let repo = SealConfigRepo::new(&pool);
+    assert!(repo.get().await.expect("get").is_none());
+
+    let config = SealConfig {
+        threshold: 3,
+        shares: 5,
+        key_check: "check-a".to_string(),
+        created_at: Utc::now(),
+    };
+    let mut conn = pool.acquire().await.expect("acquire");
+    let inserted = SealConfigRepo::create_in(&mut conn, &config)
+        .await
+        .expect("create");
+    assert_eq!(inserted, 1);
+    let replaced = SealConfig {
+        key_check: "check-b".to_string(),
+        ..config.clone()
+    };
+    let inserted = SealConfigRepo::create_in(&mut conn, &replaced)
+        .await
+        .expect("create again");
+    assert_eq!(inserted, 0);
+    drop(conn);
+
+    let stored = repo.get().await.expect("get").expect("config");
+    assert_eq!(stored.key_check, "check-a");
+    assert_eq!(stored.threshold, 3);
+    assert_eq!(stored.shares, 5);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn rotation_schedules_become_due_and_reset() {
+    let pool = setup_db().await;
+    let now = Utc::now();
+    let user = test_user(now, "rotation@example.com", None);
+    UserRepo::new(&pool)
+        .create(&user)
+        .await
+        .expect("create user");
+    let device = Device {
+        id: Uuid::now_v7(),
+        user_id: user.id,
+        name: "device".to_string(),
+        fingerprint: "sha256:rotation".to_string(),
+        os: None,
+        os_version: None,
+        app_version: None,
+        last_seen_at: None,
+        last_ip: None,
+        revoked_at: None,
+        created_at: now,
+    };
+    DeviceRepo::new(&pool)
+        .create(&device)
+        .await
+        .expect("create device");
+    let vault = Vault {
+        id: Uuid::now_v7(),
+        slug: "infra".to_string(),
+        name: "Infra".to_string(),
+        kind: VaultKind::Shared,
+        encryption_type: zann_core::VaultEncryptionType::Server,
+        vault_key_enc: vec![1, 2, 3],
+        cache_policy: CachePolicy::Full,
+        tags: None,
+        deleted_at: None,
+        deleted_by_user_id: None,
+        deleted_by_device_id: None,
+        row_version: 1,
+        created_at: now,
+    };
+    VaultRepo::new(&pool)
+        .create(&vault)
+        .await
+        .expect("create vault");
+    let item = Item {
+        id: Uuid::now_v7(),
+        vault_id: vault.id,
+        path: "db/root".to_string(),
+        name: "root".to_string(),
+        type_id: "login".to_string(),
+        tags: None,
+        favorite: false,
+        payload_enc: vec![9, 9, 9],
+        checksum: "checksum".to_string(),
+        version: 1,
+        row_version: 1,
+        device_id: device.id,
+        sync_status: zann_core::SyncStatus::Active,
+        deleted_at: None,
+        deleted_by_user_id: None,
+        deleted_by_device_id: None,
+        expires_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+    ItemRepo::new(&pool)
+        .create(&item)
+        .await
+        .expect("create item");
+
+    let repo = ItemRotationScheduleRepo::new(&pool);
+    let schedule = ItemRotationSchedule {
+        item_id: item.id,
+        vault_id: vault.id,
+        schedule: "1d".to_string(),
+        max_age_seconds: 86_400,
+        password_policy: None,
+        last_rotated_at: now - chrono::Duration::days(2),
+        last_started_at: None,
+        last_error: None,
+        created_at: now,
+        updated_at: now,
+    };
+    let stored = repo.upsert(&schedule).await.expect("upsert");
+    assert_eq!(stored.max_age_seconds, 86_400);
+    assert!(stored.due_at() < now);
+
+    let due = repo.list_due(now, now, 10).await.expect("list due");
+    assert_eq!(due.len(), 1);
+    assert_eq!(repo.count_overdue(now).await.expect("count"), 1);
+
+    let edited = ItemRotationSchedule {
+        schedule: "7d".to_string(),
+        max_age_seconds: 7 * 86_400,
+        last_rotated_at: now,
+        ..schedule.clone()
+    };
+    let stored = repo.upsert(&edited).await.expect("upsert again");
+    assert_eq!(stored.schedule, "7d");
+    assert_eq!(
+        stored.last_rotated_at.timestamp(),
+        schedule.last_rotated_at.timestamp()
+    );
+    assert_eq!(repo.count_overdue(now).await.expect("count"), 0);
+
+    repo.upsert(&schedule).await.expect("restore schedule");
+    repo.mark_error(item.id, "decrypt_failed", now)
+        .await
+        .expect("mark error");
+    let retry_errors_before = now - chrono::Duration::hours(1);
+    assert!(repo
+        .list_due(now, retry_errors_before, 10)
+        .await
+        .expect("list due")
+        .is_empty());
+    assert_eq!(
+        repo.list_due(now, now, 10).await.expect("list due").len(),
+        1
+    );
+    let mut conn = pool.acquire().await.expect("acquire");
+    ItemRotationScheduleRepo::record_rotated_in(&mut conn, item.id, now)
+        .await
+        .expect("record rotated");
+    drop(conn);
+    let stored = repo
+        .get_by_item(item.id)
+        .await
+        .expect("get")
+        .expect("schedule");
+    assert!(stored.last_error.is_none());
+    assert!(repo
+        .list_due(now, now, 10)
+        .await
+        .expect("list due")
+        .is_empty());
+
+    assert_eq!(repo.delete_by_item(item.id).await.expect("delete"), 1);
+    assert!(repo.get_by_item(item.id).await.expect("get").is_none());
+}
@@ -0,0 +1,23 @@
+CREATE TABLE audit_events (
+    seq BIGSERIAL PRIMARY KEY,
+    id UUID NOT NULL UNIQUE,
+    actor_user_id UUID,
+    actor_email TEXT,
+    actor_device_id UUID,
+    actor_service_account_id UUID,
+    auth_source TEXT,
+    category TEXT NOT NULL,
+    action TEXT NOT NULL,
+    result TEXT NOT NULL,
+    vault_id UUID,
+    item_path TEXT,
+    target TEXT,
+    detail TEXT,
+    client_ip TEXT,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_audit_events_created_at ON audit_events(created_at);
+CREATE INDEX idx_audit_events_actor ON audit_events(actor_user_id, seq);
+CREATE INDEX idx_audit_events_vault ON audit_events(vault_id, seq) WHERE vault_id IS NOT NULL;
+CREATE INDEX idx_audit_events_action ON audit_events(action, seq);
@@ -0,0 +1,11 @@
+CREATE TABLE vault_group_grants (
+    vault_id UUID NOT NULL,
+    group_id UUID NOT NULL,
+    role SMALLINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (vault_id, group_id),
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE,
+    FOREIGN KEY (group_id) REFERENCES groups(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_vault_group_grants_group_id ON vault_group_grants(group_id);
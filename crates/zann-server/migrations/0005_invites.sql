@@ -0,0 +1,17 @@
+CREATE TABLE invites (
+    id UUID PRIMARY KEY NOT NULL,
+    email TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    group_ids JSONB NOT NULL DEFAULT '[]'::jsonb,
+    vault_roles JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_by_user_id UUID,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    accepted_at TIMESTAMPTZ,
+    accepted_user_id UUID,
+    revoked_at TIMESTAMPTZ,
+    FOREIGN KEY (created_by_user_id) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY (accepted_user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_invites_created_at ON invites(created_at);
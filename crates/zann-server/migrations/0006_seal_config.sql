@@ -0,0 +1,7 @@
+CREATE TABLE seal_config (
+    id SMALLINT PRIMARY KEY CHECK (id = 1),
+    threshold SMALLINT NOT NULL,
+    shares SMALLINT NOT NULL,
+    key_check TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,16 @@
+CREATE TABLE item_rotation_schedules (
+    item_id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL,
+    schedule TEXT NOT NULL,
+    max_age_seconds BIGINT NOT NULL,
+    password_policy TEXT,
+    last_rotated_at TIMESTAMPTZ NOT NULL,
+    last_started_at TIMESTAMPTZ,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_item_rotation_schedules_vault ON item_rotation_schedules(vault_id);
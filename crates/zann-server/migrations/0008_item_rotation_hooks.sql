@@ -0,0 +1,11 @@
+CREATE TABLE item_rotation_hooks (
+    item_id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL,
+    kind TEXT NOT NULL,
+    config_enc BYTEA NOT NULL,
+    verify BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE
+);
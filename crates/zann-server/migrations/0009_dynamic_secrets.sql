@@ -0,0 +1,32 @@
+CREATE TABLE dynamic_secrets (
+    id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL,
+    path TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    config_enc BYTEA NOT NULL,
+    default_ttl_seconds BIGINT NOT NULL,
+    max_ttl_seconds BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE,
+    UNIQUE (vault_id, path)
+);
+
+CREATE TABLE dynamic_leases (
+    id UUID PRIMARY KEY NOT NULL,
+    secret_id UUID NOT NULL,
+    vault_id UUID NOT NULL,
+    username TEXT NOT NULL,
+    issued_to_user_id UUID,
+    issued_to_service_account_id UUID,
+    issued_at TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    last_error TEXT,
+    FOREIGN KEY (secret_id) REFERENCES dynamic_secrets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_dynamic_leases_active_expiry
+    ON dynamic_leases(expires_at)
+    WHERE revoked_at IS NULL;
+CREATE INDEX idx_dynamic_leases_secret ON dynamic_leases(secret_id);
@@ -0,0 +1,22 @@
+CREATE TABLE secret_leases (
+    id UUID PRIMARY KEY NOT NULL,
+    item_id UUID NOT NULL,
+    vault_id UUID NOT NULL,
+    item_version BIGINT NOT NULL,
+    holder_user_id UUID NOT NULL,
+    holder_service_account_id UUID,
+    holder_device_id UUID,
+    holder_email TEXT NOT NULL,
+    holder_name TEXT,
+    issued_at TIMESTAMPTZ NOT NULL,
+    renewed_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE CASCADE,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_secret_leases_item_active
+    ON secret_leases(item_id, expires_at)
+    WHERE revoked_at IS NULL;
+CREATE INDEX idx_secret_leases_expires ON secret_leases(expires_at);
@@ -0,0 +1,20 @@
+CREATE TABLE transit_keys (
+    id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    latest_version INTEGER NOT NULL,
+    min_decryption_version INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE,
+    UNIQUE (vault_id, name)
+);
+
+CREATE TABLE transit_key_versions (
+    key_id UUID NOT NULL,
+    version INTEGER NOT NULL,
+    key_enc BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (key_id, version),
+    FOREIGN KEY (key_id) REFERENCES transit_keys(id) ON DELETE CASCADE
+);
@@ -0,0 +1,25 @@
+CREATE TABLE ssh_cas (
+    id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL UNIQUE,
+    public_key TEXT NOT NULL,
+    private_key_enc BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE
+);
+
+CREATE TABLE ssh_signing_roles (
+    id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    cert_type TEXT NOT NULL,
+    allowed_principals JSONB NOT NULL DEFAULT '[]'::jsonb,
+    default_principals JSONB NOT NULL DEFAULT '[]'::jsonb,
+    allowed_extensions JSONB NOT NULL DEFAULT '[]'::jsonb,
+    default_extensions JSONB NOT NULL DEFAULT '[]'::jsonb,
+    default_ttl_seconds BIGINT NOT NULL,
+    max_ttl_seconds BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE,
+    UNIQUE (vault_id, name)
+);
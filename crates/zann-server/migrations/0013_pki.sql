@@ -0,0 +1,53 @@
+CREATE TABLE pki_cas (
+    id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL,
+    kind TEXT NOT NULL,
+    common_name TEXT NOT NULL,
+    certificate_pem TEXT NOT NULL,
+    private_key_enc BYTEA NOT NULL,
+    serial TEXT NOT NULL,
+    not_after TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE,
+    UNIQUE (vault_id, kind)
+);
+
+CREATE TABLE pki_roles (
+    id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    allowed_domains JSONB NOT NULL DEFAULT '[]'::jsonb,
+    allow_subdomains BOOLEAN NOT NULL DEFAULT FALSE,
+    allow_ip_sans BOOLEAN NOT NULL DEFAULT FALSE,
+    key_type TEXT NOT NULL,
+    server_auth BOOLEAN NOT NULL DEFAULT TRUE,
+    client_auth BOOLEAN NOT NULL DEFAULT FALSE,
+    default_ttl_seconds BIGINT NOT NULL,
+    max_ttl_seconds BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE,
+    UNIQUE (vault_id, name)
+);
+
+CREATE TABLE pki_certificates (
+    id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL,
+    issuer_id UUID NOT NULL,
+    serial TEXT NOT NULL,
+    role_name TEXT NOT NULL,
+    common_name TEXT NOT NULL,
+    sans JSONB NOT NULL DEFAULT '[]'::jsonb,
+    certificate_pem TEXT NOT NULL,
+    not_before TIMESTAMPTZ NOT NULL,
+    not_after TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE,
+    FOREIGN KEY (issuer_id) REFERENCES pki_cas(id) ON DELETE CASCADE,
+    UNIQUE (vault_id, serial)
+);
+
+CREATE INDEX idx_pki_certificates_revoked
+    ON pki_certificates(issuer_id)
+    WHERE revoked_at IS NOT NULL;
@@ -0,0 +1,7 @@
+ALTER TABLE items
+    ADD COLUMN expires_at TIMESTAMPTZ,
+    ADD COLUMN expiry_notified_at TIMESTAMPTZ;
+
+CREATE INDEX idx_items_expires_at
+    ON items(expires_at)
+    WHERE expires_at IS NOT NULL AND sync_status = 1;
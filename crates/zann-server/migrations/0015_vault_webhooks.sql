@@ -0,0 +1,36 @@
+CREATE TABLE vault_webhooks (
+    id UUID PRIMARY KEY NOT NULL,
+    vault_id UUID NOT NULL,
+    url TEXT NOT NULL,
+    path_prefix TEXT,
+    secret_enc BYTEA NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    last_seq BIGINT NOT NULL,
+    created_by_user_id UUID,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (vault_id) REFERENCES vaults(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_vault_webhooks_vault ON vault_webhooks(vault_id);
+
+CREATE TABLE webhook_deliveries (
+    id UUID PRIMARY KEY NOT NULL,
+    webhook_id UUID NOT NULL,
+    seq BIGINT NOT NULL,
+    payload JSONB NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ,
+    response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (webhook_id) REFERENCES vault_webhooks(id) ON DELETE CASCADE,
+    UNIQUE (webhook_id, seq)
+);
+
+CREATE INDEX idx_webhook_deliveries_due
+    ON webhook_deliveries(next_attempt_at)
+    WHERE status = 'pending';
+CREATE INDEX idx_webhook_deliveries_created ON webhook_deliveries(created_at);
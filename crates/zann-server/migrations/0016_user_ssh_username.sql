@@ -0,0 +1,6 @@
+ALTER TABLE users
+    ADD COLUMN ssh_username TEXT;
+
+CREATE UNIQUE INDEX idx_users_ssh_username
+    ON users(ssh_username)
+    WHERE ssh_username IS NOT NULL;
@@ -6,13 +6,13 @@ use tokio::sync::Semaphore;
 use crate::config::ServerConfig;
 use crate::domains::access_control::policy_store::PolicyStore;
 use crate::domains::auth::core::oidc::OidcJwksCache;
-use crate::domains::secrets::policies::PasswordPolicy;
+use crate::domains::secrets::policies::SecretPolicy;
+use crate::domains::system::seal::MasterKeyStore;
 use crate::infra::usage::UsageTracker;
 use crate::settings::DbTxIsolation;
 use ed25519_dalek::SigningKey;
 use std::sync::Arc;
 use zann_core::SecurityProfileRegistry;
-use zann_crypto::crypto::SecretKey;
 use zann_db::PgPool;
 
 #[derive(Clone)]
@@ -22,7 +22,7 @@ pub struct AppState {
     pub started_at: Instant,
     pub password_pepper: String,
     pub token_pepper: String,
-    pub server_master_key: Option<Arc<SecretKey>>,
+    pub server_master_key: MasterKeyStore,
     pub identity_key: Arc<SigningKey>,
     pub access_token_ttl_seconds: i64,
     pub refresh_token_ttl_seconds: i64,
@@ -32,7 +32,7 @@ pub struct AppState {
     pub policy_store: PolicyStore,
     pub usage_tracker: std::sync::Arc<UsageTracker>,
     pub security_profiles: SecurityProfileRegistry,
-    pub secret_policies: HashMap<String, PasswordPolicy>,
+    pub secret_policies: HashMap<String, SecretPolicy>,
     pub secret_default_policy: String,
 }
 
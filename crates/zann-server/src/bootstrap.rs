@@ -14,11 +14,18 @@ use tower_http::trace::TraceLayer;
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 use crate::app::{self, AppState};
-use crate::config::MetricsConfig;
+use crate::config::{MasterKeyMode, MetricsConfig};
 use crate::domains::access_control::policy_store;
 use crate::domains::auth::core::oidc;
+use crate::domains::dynamic;
+use crate::domains::items::expiry;
+use crate::domains::secrets::leases;
+use crate::domains::system::seal::MasterKeyStore;
+use crate::domains::vaults::rotation;
+use crate::domains::webhooks;
+use crate::infra::notifier::Notifier;
 use crate::infra::security_profiles;
-use crate::infra::{history, metrics, usage};
+use crate::infra::{audit_chain, audit_sinks, history, metrics, usage};
 use crate::runtime;
 use crate::settings;
 use zann_db::{connect_postgres_with_max, PgPool};
@@ -151,9 +158,19 @@ pub fn build_state(settings: &settings::Settings, db: PgPool) -> AppState {
         started_at: Instant::now(),
         password_pepper: settings.password_pepper.clone(),
         token_pepper: settings.token_pepper.clone(),
-        server_master_key: settings.server_master_key.as_ref().map(|key| {
-            std::sync::Arc::new(zann_crypto::crypto::SecretKey::from_bytes(*key.as_bytes()))
-        }),
+        server_master_key: if matches!(
+            settings.config.server.master_key_mode,
+            MasterKeyMode::ManualUnseal
+        ) {
+            MasterKeyStore::sealed()
+        } else {
+            MasterKeyStore::new(
+                settings
+                    .server_master_key
+                    .as_ref()
+                    .map(|key| zann_crypto::crypto::SecretKey::from_bytes(*key.as_bytes())),
+            )
+        },
         identity_key: settings.identity_key.clone(),
         access_token_ttl_seconds: settings.access_token_ttl_seconds,
         refresh_token_ttl_seconds: settings.refresh_token_ttl_seconds,
@@ -169,6 +186,13 @@ pub fn build_state(settings: &settings::Settings, db: PgPool) -> AppState {
 }
 
 pub fn log_fingerprint(state: &AppState) {
+    if state.config.server.fingerprint.is_none() && state.server_master_key.is_sealed() {
+        tracing::warn!(
+            event = "server_sealed",
+            "Server is sealed; submit unseal shares via POST /v1/system/unseal"
+        );
+        return;
+    }
     let fingerprint = runtime::server_fingerprint(state);
     tracing::info!("SERVER FINGERPRINT: {}", fingerprint);
 }
@@ -259,6 +283,145 @@ pub fn start_background_tasks(settings: &settings::Settings, state: &AppState) {
             }
         });
     }
+    {
+        let state = state.clone();
+        let interval = settings.config.rotation.schedule_interval_seconds.max(60);
+        tokio::spawn(async move {
+            let interval = Duration::from_secs(interval);
+            loop {
+                match rotation::run_scheduled_rotations(&state).await {
+                    Ok(count) => {
+                        if count > 0 {
+                            tracing::info!(event = "scheduled_rotations_started", started = count);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(event = "scheduled_rotations_failed", error = %err);
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+    {
+        let state = state.clone();
+        let interval = settings.config.dynamic.reaper_interval_seconds.max(60);
+        tokio::spawn(async move {
+            let interval = Duration::from_secs(interval);
+            loop {
+                match dynamic::service::reap_expired_leases(&state).await {
+                    Ok(count) => {
+                        if count > 0 {
+                            tracing::info!(event = "dynamic_leases_revoked", revoked = count);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(event = "dynamic_leases_reap_failed", error = %err);
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+    {
+        let pool = state.db.clone();
+        let interval = settings.config.leases.cleanup_interval_seconds.max(60);
+        let retention = settings.config.leases.retention_seconds;
+        tokio::spawn(async move {
+            let interval = Duration::from_secs(interval);
+            loop {
+                match leases::prune_leases(&pool, retention).await {
+                    Ok(count) => {
+                        if count > 0 {
+                            tracing::info!(event = "secret_leases_pruned", deleted = count);
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(event = "secret_leases_prune_failed", error = %err);
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+    if let Some(config) = settings.config.expiry.notifier.as_ref() {
+        match Notifier::new(config) {
+            Ok(notifier) => {
+                let state = state.clone();
+                let interval = settings.config.expiry.check_interval_seconds.max(60);
+                tokio::spawn(async move {
+                    let interval = Duration::from_secs(interval);
+                    loop {
+                        match expiry::run_expiry_notifications(&state, &notifier).await {
+                            Ok(count) => {
+                                if count > 0 {
+                                    tracing::info!(event = "expiry_notices_sent", sent = count);
+                                }
+                            }
+                            Err(err) => {
+                                tracing::error!(event = "expiry_notices_failed", error = %err);
+                            }
+                        }
+                        tokio::time::sleep(interval).await;
+                    }
+                });
+            }
+            Err(err) => {
+                tracing::error!(event = "expiry_notifier_init_failed", error = %err);
+            }
+        }
+    }
+    {
+        let state = state.clone();
+        let interval = settings.config.webhooks.poll_interval_seconds.max(1);
+        tokio::spawn(async move {
+            let interval = Duration::from_secs(interval);
+            loop {
+                match webhooks::delivery::run_webhook_deliveries(&state).await {
+                    Ok(pass) => {
+                        if pass.delivered > 0 || pass.failed > 0 {
+                            tracing::info!(
+                                event = "webhook_deliveries_sent",
+                                delivered = pass.delivered,
+                                failed = pass.failed
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        tracing::error!(event = "webhook_deliveries_failed", error = %err);
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+    if let Err(err) = audit_sinks::init(&settings.config.audit) {
+        tracing::error!(event = "audit_sinks_init_failed", error = %err);
+    }
+    {
+        let pool = state.db.clone();
+        let identity_key = state.identity_key.clone();
+        let interval = settings.config.audit.checkpoint_interval_seconds.max(60);
+        tokio::spawn(async move {
+            let interval = Duration::from_secs(interval);
+            loop {
+                tokio::time::sleep(interval).await;
+                match audit_chain::write_checkpoint(&pool, &identity_key).await {
+                    Ok(Some(checkpoint)) => {
+                        tracing::info!(
+                            event = "audit_checkpoint_written",
+                            seq = checkpoint.seq,
+                            hash = %checkpoint.hash
+                        );
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        tracing::error!(event = "audit_checkpoint_failed", error = %err);
+                    }
+                }
+            }
+        });
+    }
     if settings.config.metrics.enabled {
         metrics::start_db_pool_metrics(state.db.clone(), settings.db_pool_max);
     }
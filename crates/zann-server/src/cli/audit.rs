@@ -0,0 +1,84 @@
+use base64::Engine;
+use clap::{Args, Subcommand};
+use ed25519_dalek::VerifyingKey;
+use zann_db::PgPool;
+
+use crate::infra::audit_chain::{self, VerifyError};
+use crate::settings;
+
+#[derive(Debug, Clone, Args)]
+pub struct AuditArgs {
+    #[command(subcommand)]
+    pub command: AuditCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum AuditCommand {
+    /// Verify the audit hash chain and signed checkpoints
+    Verify(AuditVerifyArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct AuditVerifyArgs {
+    #[arg(
+        long,
+        value_name = "base64",
+        help = "Additional identity public key to accept for checkpoints (e.g. before a key rotation); repeatable"
+    )]
+    pub trusted_key: Vec<String>,
+}
+
+pub(crate) async fn run(
+    settings: &settings::Settings,
+    db: &PgPool,
+    args: &AuditArgs,
+) -> Result<(), String> {
+    match &args.command {
+        AuditCommand::Verify(command) => verify(settings, db, command).await,
+    }
+}
+
+async fn verify(
+    settings: &settings::Settings,
+    db: &PgPool,
+    args: &AuditVerifyArgs,
+) -> Result<(), String> {
+    let mut trusted_keys = vec![settings.identity_key.verifying_key()];
+    for value in &args.trusted_key {
+        trusted_keys.push(parse_verifying_key(value)?);
+    }
+
+    match audit_chain::verify(db, &trusted_keys).await {
+        Ok(report) => {
+            println!(
+                "audit chain ok: {} records verified, {} checkpoints verified",
+                report.verified, report.checkpoints
+            );
+            if let Some(seq) = report.head_seq {
+                println!("head seq: {seq}");
+            }
+            if report.unchained > 0 {
+                println!(
+                    "note: {} records predate chaining and were not verified",
+                    report.unchained
+                );
+            }
+            Ok(())
+        }
+        Err(VerifyError::Db(err)) => {
+            tracing::error!(event = "audit_verify_failed", error = %err);
+            Err("audit_verify_failed".to_string())
+        }
+        Err(err @ VerifyError::Broken(_)) => Err(err.to_string()),
+    }
+}
+
+fn parse_verifying_key(value: &str) -> Result<VerifyingKey, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(value.trim())
+        .map_err(|_| "invalid_trusted_key".to_string())?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| "invalid_trusted_key_length".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| "invalid_trusted_key".to_string())
+}
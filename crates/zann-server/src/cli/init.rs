@@ -9,11 +9,15 @@ use zann_core::{CachePolicy, UserStatus, VaultEncryptionType, VaultKind, VaultMe
 use zann_core::{Group, GroupMember, User, Vault, VaultMember};
 use zann_crypto::crypto::SecretKey;
 use zann_crypto::vault_crypto;
+use zann_db::repo::{SealConfig, SealConfigRepo};
 
-use crate::config::AuthMode;
+use crate::config::{AuthMode, MasterKeyMode};
 use crate::domains::auth::core::passwords::{
     derive_auth_hash, hash_password, random_kdf_salt, KdfParams,
 };
+use crate::domains::system::seal::{
+    key_check, split_master_key, DEFAULT_KEY_SHARES, DEFAULT_KEY_THRESHOLD,
+};
 use crate::infra::db::apply_tx_isolation;
 use crate::settings::Settings;
 
@@ -27,6 +31,12 @@ pub struct InitArgs {
     pub vault_name: String,
     #[arg(long, value_name = "slug")]
     pub vault_slug: String,
+    /// Number of unseal key shares to generate (manual_unseal mode)
+    #[arg(long, value_name = "n", default_value_t = DEFAULT_KEY_SHARES)]
+    pub key_shares: u8,
+    /// Shares required to unseal (manual_unseal mode)
+    #[arg(long, value_name = "n", default_value_t = DEFAULT_KEY_THRESHOLD)]
+    pub key_threshold: u8,
 }
 
 #[derive(Debug, Serialize)]
@@ -36,6 +46,10 @@ struct InitOutput {
     vault_id: String,
     vault_slug: String,
     vault_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unseal_threshold: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unseal_shares: Option<Vec<String>>,
 }
 
 pub async fn run(settings: &Settings, db: &zann_db::PgPool, args: &InitArgs) -> Result<(), String> {
@@ -43,8 +57,23 @@ pub async fn run(settings: &Settings, db: &zann_db::PgPool, args: &InitArgs) ->
     {
         return Err("internal_auth_disabled".to_string());
     }
-    let Some(server_master_key) = settings.server_master_key.as_ref() else {
-        return Err("server_master_key_missing".to_string());
+    // In manual_unseal mode the master key is generated here and only leaves
+    // this process as Shamir shares.
+    let manual_unseal = matches!(
+        settings.config.server.master_key_mode,
+        MasterKeyMode::ManualUnseal
+    );
+    let generated_key;
+    let (server_master_key, unseal_shares) = if manual_unseal {
+        generated_key = SecretKey::generate();
+        let shares = split_master_key(&generated_key, args.key_threshold, args.key_shares)
+            .map_err(|_| "invalid_key_shares".to_string())?;
+        (&generated_key, Some(shares))
+    } else {
+        let Some(server_master_key) = settings.server_master_key.as_ref() else {
+            return Err("server_master_key_missing".to_string());
+        };
+        (server_master_key, None)
     };
 
     let email = args.email.trim();
@@ -371,6 +400,28 @@ pub async fn run(settings: &Settings, db: &zann_db::PgPool, args: &InitArgs) ->
         "vault_member_create_failed".to_string()
     })?;
 
+    if manual_unseal {
+        let seal_config = SealConfig {
+            threshold: i16::from(args.key_threshold),
+            shares: i16::from(args.key_shares),
+            key_check: key_check(server_master_key),
+            created_at: now,
+        };
+        let inserted = SealConfigRepo::create_in(&mut tx, &seal_config)
+            .await
+            .map_err(|err| {
+                tracing::error!(event = "init_failed", error = %err, "Seal config create failed");
+                "seal_config_create_failed".to_string()
+            })?;
+        if inserted == 0 {
+            if let Err(err) = tx.rollback().await {
+                tracing::error!(event = "init_failed", error = %err, "DB rollback failed");
+                return Err("db_error".to_string());
+            }
+            return Err("already_initialized".to_string());
+        }
+    }
+
     tx.commit().await.map_err(|err| {
         tracing::error!(event = "init_failed", error = %err, "DB commit failed");
         "db_error".to_string()
@@ -382,6 +433,8 @@ pub async fn run(settings: &Settings, db: &zann_db::PgPool, args: &InitArgs) ->
         vault_id: vault.id.to_string(),
         vault_slug: vault.slug,
         vault_name: vault.name,
+        unseal_threshold: unseal_shares.as_ref().map(|_| args.key_threshold),
+        unseal_shares,
     };
     println!(
         "{}",
@@ -1,8 +1,10 @@
 use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
+pub mod audit;
 pub mod export;
 pub mod init;
+pub mod operator;
 pub mod provision;
 pub mod tokens;
 
@@ -28,6 +30,10 @@ enum Command {
     Provision(provision::ProvisionArgs),
     /// Manage service account tokens
     Token(tokens::TokenArgs),
+    /// Audit log maintenance
+    Audit(audit::AuditArgs),
+    /// Unseal a running server (manual_unseal mode)
+    Operator(operator::OperatorArgs),
 }
 
 #[derive(Args)]
@@ -45,6 +51,8 @@ pub enum RunMode {
     Init(init::InitArgs),
     Provision(provision::ProvisionArgs),
     Token(tokens::TokenArgs),
+    Audit(audit::AuditArgs),
+    Operator(operator::OperatorArgs),
 }
 
 pub fn parse_args() -> RunMode {
@@ -57,12 +65,15 @@ pub fn parse_args() -> RunMode {
         Some(Command::Init(args)) => RunMode::Init(args),
         Some(Command::Provision(args)) => RunMode::Provision(args),
         Some(Command::Token(args)) => RunMode::Token(args),
+        Some(Command::Audit(args)) => RunMode::Audit(args),
+        Some(Command::Operator(args)) => RunMode::Operator(args),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::audit::AuditCommand;
     use crate::cli::tokens::TokenCommand;
     use std::path::PathBuf;
 
@@ -169,6 +180,24 @@ mod tests {
         assert_eq!(args.issued_by_email.as_deref(), Some("admin@example.com"));
     }
 
+    #[test]
+    fn parse_audit_verify_with_trusted_keys() {
+        let cli = Cli::parse_from([
+            "zann-server",
+            "audit",
+            "verify",
+            "--trusted-key",
+            "a2V5LTE=",
+            "--trusted-key",
+            "a2V5LTI=",
+        ]);
+        let Some(Command::Audit(audit_args)) = cli.command else {
+            panic!("expected audit command");
+        };
+        let AuditCommand::Verify(args) = audit_args.command;
+        assert_eq!(args.trusted_key, vec!["a2V5LTE=", "a2V5LTI="]);
+    }
+
     #[test]
     fn parse_init_command() {
         let cli = Cli::parse_from([
@@ -190,6 +219,52 @@ mod tests {
         assert_eq!(args.vault_slug, "prod");
     }
 
+    #[test]
+    fn parse_init_key_share_flags() {
+        let cli = Cli::parse_from([
+            "zann-server",
+            "init",
+            "--email",
+            "admin@example.com",
+            "--password",
+            "secret",
+            "--vault-name",
+            "Production",
+            "--vault-slug",
+            "prod",
+            "--key-shares",
+            "7",
+            "--key-threshold",
+            "4",
+        ]);
+        let Some(Command::Init(args)) = cli.command else {
+            panic!("expected init command");
+        };
+        assert_eq!(args.key_shares, 7);
+        assert_eq!(args.key_threshold, 4);
+    }
+
+    #[test]
+    fn parse_operator_unseal_command() {
+        let cli = Cli::parse_from([
+            "zann-server",
+            "operator",
+            "unseal",
+            "AQID",
+            "--addr",
+            "https://zann.example.com",
+        ]);
+        let Some(Command::Operator(args)) = cli.command else {
+            panic!("expected operator command");
+        };
+        assert_eq!(args.addr.as_deref(), Some("https://zann.example.com"));
+        let operator::OperatorCommand::Unseal(command) = args.command else {
+            panic!("expected unseal command");
+        };
+        assert_eq!(command.share.as_deref(), Some("AQID"));
+        assert!(!command.reset);
+    }
+
     #[test]
     fn parse_provision_ensure_vault_command() {
         let cli = Cli::parse_from([
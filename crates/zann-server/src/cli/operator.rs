@@ -0,0 +1,123 @@
+use std::io::{BufRead, IsTerminal};
+
+use clap::{Args, Subcommand};
+use serde_json::Value;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+#[derive(Debug, Clone, Args)]
+pub struct OperatorArgs {
+    /// Server address or URL (defaults to ZANN_ADDR, then 127.0.0.1:8080)
+    #[arg(long, global = true, value_name = "url")]
+    pub addr: Option<String>,
+    #[command(subcommand)]
+    pub command: OperatorCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum OperatorCommand {
+    /// Submit an unseal key share (read from stdin when omitted)
+    Unseal(UnsealArgs),
+    /// Show seal status and unseal progress
+    SealStatus,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct UnsealArgs {
+    /// Base64 key share printed by `zann-server init`
+    pub share: Option<String>,
+    /// Discard shares submitted so far
+    #[arg(long, conflicts_with = "share")]
+    pub reset: bool,
+}
+
+pub async fn run(args: &OperatorArgs) -> Result<(), String> {
+    let base = base_url(args.addr.as_deref());
+    let client = reqwest::Client::new();
+    let response = match &args.command {
+        OperatorCommand::Unseal(command) => {
+            let body = if command.reset {
+                serde_json::json!({ "reset": true })
+            } else {
+                let share = match command.share.clone() {
+                    Some(share) => share,
+                    None => read_share()?,
+                };
+                serde_json::json!({ "share": share.trim() })
+            };
+            client
+                .post(format!("{base}/v1/system/unseal"))
+                .json(&body)
+                .send()
+                .await
+        }
+        OperatorCommand::SealStatus => {
+            client
+                .get(format!("{base}/v1/system/seal-status"))
+                .send()
+                .await
+        }
+    }
+    .map_err(|err| format!("request failed: {err}"))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|err| format!("invalid response ({status}): {err}"))?;
+    if !status.is_success() {
+        let error = body
+            .get("error")
+            .and_then(Value::as_str)
+            .unwrap_or("request_failed");
+        return Err(format!("{error} ({status})"));
+    }
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&body).map_err(|err| err.to_string())?
+    );
+    Ok(())
+}
+
+fn base_url(addr: Option<&str>) -> String {
+    let addr = addr
+        .map(ToString::to_string)
+        .or_else(|| std::env::var("ZANN_ADDR").ok())
+        .unwrap_or_else(|| DEFAULT_ADDR.to_string());
+    let addr = addr.trim_end_matches('/');
+    if addr.starts_with("http://") || addr.starts_with("https://") {
+        addr.to_string()
+    } else {
+        format!("http://{addr}")
+    }
+}
+
+fn read_share() -> Result<String, String> {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        eprint!("Unseal key share: ");
+    }
+    let mut line = String::new();
+    stdin
+        .lock()
+        .read_line(&mut line)
+        .map_err(|err| format!("failed to read share: {err}"))?;
+    if line.trim().is_empty() {
+        return Err("share_required".to_string());
+    }
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_url_adds_scheme_and_strips_slash() {
+        assert_eq!(base_url(Some("10.0.0.5:8080")), "http://10.0.0.5:8080");
+        assert_eq!(
+            base_url(Some("https://zann.example.com/")),
+            "https://zann.example.com"
+        );
+    }
+}
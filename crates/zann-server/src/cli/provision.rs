@@ -23,10 +23,13 @@ use zann_db::repo::{
 };
 use zann_db::PgPool;
 
-use crate::cli::tokens::{SERVICE_ACCOUNT_PREFIX, SERVICE_ACCOUNT_PREFIX_LEN, SYSTEM_OWNER_EMAIL};
+use crate::cli::tokens::SYSTEM_OWNER_EMAIL;
 use crate::domains::auth::core::passwords;
 use crate::domains::auth::helpers::build_device;
 use crate::domains::items::service::{basename_from_path, ITEM_HISTORY_LIMIT};
+use crate::domains::service_accounts::tokens::{
+    SERVICE_ACCOUNT_PREFIX, SERVICE_ACCOUNT_PREFIX_LEN,
+};
 use crate::settings;
 
 const PROVISION_DEVICE_NAME: &str = "provision";
@@ -90,7 +93,7 @@ pub struct EnsureTokenArgs {
     #[arg(
         value_name = "ops",
         default_value = "read",
-        help = "Comma-separated ops (read, write, read_history, read_previous)"
+        help = "Comma-separated ops (read, write, read_history, read_previous, transit_encrypt, transit_decrypt, ssh_sign, pki_issue)"
     )]
     pub ops: String,
     #[arg(long)]
@@ -362,6 +365,7 @@ async fn set_field_command(
             deleted_at: None,
             deleted_by_user_id: None,
             deleted_by_device_id: None,
+            expires_at: None,
             created_at: now,
             updated_at: now,
         };
@@ -853,6 +857,10 @@ fn parse_ops(value: &str) -> Result<Vec<&'static str>, String> {
             "read_history" => "read_history",
             "read_previous" => "read_previous",
             "history_read" => "read_history",
+            "transit_encrypt" => "transit_encrypt",
+            "transit_decrypt" => "transit_decrypt",
+            "ssh_sign" => "ssh_sign",
+            "pki_issue" => "pki_issue",
             _ => return Err(format!("invalid_ops:{token}")),
         };
         if !ops.contains(&op) {
@@ -1,18 +1,14 @@
 use chrono::Utc;
-use rand::distributions::Alphanumeric;
-use rand::{thread_rng, Rng};
 use zann_core::ServiceAccount;
 use zann_db::repo::ServiceAccountRepo;
 
-use crate::domains::auth::core::passwords;
+use crate::domains::service_accounts::tokens::{build_scopes, issue_token, prefixes_display};
 use crate::settings;
 
 use super::models::{
-    normalize_prefix, parse_ops, parse_ttl, resolve_owner, resolve_shared_vault, TokenDescription,
-};
-use super::{
-    TokenCreateArgs, SERVICE_ACCOUNT_PREFIX, SERVICE_ACCOUNT_PREFIX_LEN, SYSTEM_OWNER_EMAIL,
+    parse_ops, parse_prefixes, parse_ttl, resolve_owner, resolve_shared_vault, TokenDescription,
 };
+use super::{TokenCreateArgs, SYSTEM_OWNER_EMAIL};
 
 pub(super) async fn tokens_create(
     settings: &settings::Settings,
@@ -62,73 +58,16 @@ pub(super) async fn tokens_create(
         .map(|duration| Utc::now() + duration);
 
     let vault = resolve_shared_vault(db, vault_selector).await?;
-    let mut normalized_prefixes = Vec::new();
-    let mut full_vault = false;
-    for prefix in prefixes_raw.split(',') {
-        let prefix = prefix.trim();
-        if prefix.is_empty() {
-            return Err("invalid prefix".to_string());
-        }
-        if prefix == "/" {
-            full_vault = true;
-            continue;
-        }
-        normalized_prefixes.push(normalize_prefix(prefix)?);
-    }
-    if full_vault && !normalized_prefixes.is_empty() {
-        return Err("prefix '/' cannot be combined with other prefixes".to_string());
-    }
-    if !full_vault && normalized_prefixes.is_empty() {
-        return Err("missing --prefix".to_string());
-    }
-
-    let scopes: Vec<String> = if full_vault {
-        permissions
-            .iter()
-            .map(|permission| format!("{}:{permission}", vault.id))
-            .collect()
-    } else {
-        normalized_prefixes
-            .iter()
-            .flat_map(|normalized_prefix| {
-                permissions.iter().map(|permission| {
-                    format!(
-                        "{}/prefix:{}:{}",
-                        vault.id, normalized_prefix.scope, permission
-                    )
-                })
-            })
-            .collect()
-    };
+    let prefixes = parse_prefixes(prefixes_raw.split(','))?;
+    let scopes = build_scopes(vault.id, prefixes.as_deref(), &permissions);
 
-    let token_suffix: String = thread_rng()
-        .sample_iter(&Alphanumeric)
-        .take(32)
-        .map(char::from)
-        .collect();
-    let token = format!("{SERVICE_ACCOUNT_PREFIX}{token_suffix}");
-    let token_prefix: String = token.chars().take(SERVICE_ACCOUNT_PREFIX_LEN).collect();
-    let params = passwords::KdfParams {
-        algorithm: settings.config.auth.kdf.algorithm.clone(),
-        iterations: settings.config.auth.kdf.iterations,
-        memory_kb: settings.config.auth.kdf.memory_kb,
-        parallelism: settings.config.auth.kdf.parallelism,
-    };
-    let token_hash = passwords::hash_service_token(&token, &settings.token_pepper, &params)
-        .map_err(|err| {
-            tracing::error!(event = "token_hash_failed", error = %err);
-            "token_hash_failed".to_string()
-        })?;
+    let issued = issue_token(&settings.config, &settings.token_pepper).map_err(|err| {
+        tracing::error!(event = "token_hash_failed", error = %err);
+        "token_hash_failed".to_string()
+    })?;
 
     let now = Utc::now();
-    let prefixes_display: Vec<String> = if full_vault {
-        vec!["/".to_string()]
-    } else {
-        normalized_prefixes
-            .iter()
-            .map(|value| value.canonical.clone())
-            .collect()
-    };
+    let prefixes_display = prefixes_display(prefixes.as_deref());
     let token_description = TokenDescription {
         issued_by: issued_by_email.to_string(),
         vault_id: vault.id.to_string(),
@@ -149,8 +88,8 @@ pub(super) async fn tokens_create(
         owner_user_id: owner,
         name: name.to_string(),
         description,
-        token_hash,
-        token_prefix,
+        token_hash: issued.token_hash,
+        token_prefix: issued.token_prefix,
         scopes: sqlx_core::types::Json(scopes),
         allowed_ips: None,
         expires_at,
@@ -169,7 +108,7 @@ pub(super) async fn tokens_create(
     })?;
 
     let output = serde_json::json!({
-        "token": token,
+        "token": issued.token,
         "service_account": {
             "id": account.id,
             "owner_user_id": account.owner_user_id,
@@ -7,8 +7,6 @@ mod list;
 mod models;
 mod revoke;
 
-pub(super) const SERVICE_ACCOUNT_PREFIX: &str = "zann_sa_";
-pub(super) const SERVICE_ACCOUNT_PREFIX_LEN: usize = 12;
 pub(super) const SYSTEM_OWNER_EMAIL: &str = "system@zann.internal";
 
 #[derive(Debug, Clone, Args)]
@@ -1,8 +1,11 @@
-use chrono::Duration as ChronoDuration;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use zann_db::repo::{UserRepo, VaultRepo};
 use zann_db::PgPool;
 
+pub(super) use crate::domains::service_accounts::tokens::{
+    parse_description, parse_ops, parse_prefixes, parse_ttl, TokenDescription,
+};
+
 pub(super) async fn resolve_owner(
     db: &PgPool,
     owner_email: Option<&str>,
@@ -50,96 +53,6 @@ pub(super) async fn resolve_shared_vault(
         .ok_or_else(|| "vault not found".to_string())
 }
 
-pub(super) fn normalize_prefix(prefix: &str) -> Result<NormalizedPrefix, String> {
-    let trimmed = prefix.trim();
-    if trimmed.is_empty() {
-        return Err("prefix cannot be empty".to_string());
-    }
-    let canonical = trimmed.trim_matches('/').to_string();
-    if canonical.is_empty() {
-        return Err("prefix cannot be root".to_string());
-    }
-    Ok(NormalizedPrefix {
-        canonical: format!("/{canonical}"),
-        scope: canonical.replace('/', "::"),
-    })
-}
-
-pub(super) fn parse_ops(value: &str) -> Result<Vec<&'static str>, String> {
-    let mut ops = Vec::new();
-    for token in value.split(',') {
-        let token = token.trim();
-        if token.is_empty() {
-            continue;
-        }
-        let normalized = token.to_ascii_lowercase();
-        let op = match normalized.as_str() {
-            "read" => "read",
-            "write" => "write",
-            "read_history" => "read_history",
-            "read_previous" => "read_previous",
-            "history_read" => "read_history",
-            _ => return Err(format!("invalid ops: {token}")),
-        };
-        if !ops.contains(&op) {
-            ops.push(op);
-        }
-    }
-    if ops.is_empty() {
-        return Err("invalid ops".to_string());
-    }
-    Ok(ops)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::parse_ops;
-
-    #[test]
-    fn parse_ops_accepts_write() {
-        let ops = parse_ops("read,write").expect("ops");
-        assert_eq!(ops, vec!["read", "write"]);
-    }
-
-    #[test]
-    fn parse_ops_rejects_unknown_values() {
-        let err = parse_ops("read,rotate").expect_err("invalid ops");
-        assert_eq!(err, "invalid ops: rotate");
-    }
-}
-
-pub(super) fn parse_ttl(value: &str) -> Result<ChronoDuration, String> {
-    let trimmed = value.trim().to_ascii_lowercase();
-    if trimmed.is_empty() {
-        return Err("invalid ttl".to_string());
-    }
-    let (amount, unit) = trimmed.split_at(trimmed.len().saturating_sub(1));
-    let amount = amount
-        .parse::<i64>()
-        .map_err(|_| "invalid ttl".to_string())?;
-    match unit {
-        "s" => Ok(ChronoDuration::seconds(amount)),
-        "m" => Ok(ChronoDuration::minutes(amount)),
-        "h" => Ok(ChronoDuration::hours(amount)),
-        "d" => Ok(ChronoDuration::days(amount)),
-        _ => Err("invalid ttl".to_string()),
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub(super) struct TokenDescription {
-    pub(super) issued_by: String,
-    pub(super) vault_id: String,
-    pub(super) vault_slug: String,
-    pub(super) prefix: Option<String>,
-    pub(super) prefixes: Option<Vec<String>>,
-    pub(super) ops: Vec<String>,
-}
-
-pub(super) fn parse_description(value: &str) -> Result<TokenDescription, String> {
-    serde_json::from_str(value).map_err(|_| "invalid description".to_string())
-}
-
 #[derive(Debug, Clone)]
 pub(super) struct ParsedScope {
     #[allow(dead_code)]
@@ -224,12 +137,6 @@ impl ScopeSummary {
     }
 }
 
-#[derive(Debug, Clone)]
-pub(super) struct NormalizedPrefix {
-    pub(super) canonical: String,
-    pub(super) scope: String,
-}
-
 pub(super) async fn resolve_vault_for_list(
     db: &PgPool,
     description: &TokenDescription,
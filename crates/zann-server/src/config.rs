@@ -14,6 +14,16 @@ pub struct ServerConfig {
     #[serde(default)]
     pub rotation: RotationConfig,
     #[serde(default)]
+    pub dynamic: DynamicConfig,
+    #[serde(default)]
+    pub leases: LeaseConfig,
+    #[serde(default)]
+    pub expiry: ExpiryConfig,
+    #[serde(default)]
+    pub webhooks: WebhooksConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
     pub sentry: SentryConfig,
     #[serde(default)]
     pub metrics: MetricsConfig,
@@ -164,6 +174,8 @@ impl Default for InternalAuthConfig {
 #[serde(rename_all = "snake_case")]
 pub enum InternalRegistration {
     Open,
+    /// Registration requires a valid invite token issued via `/v1/admin/invites`.
+    InviteOnly,
     #[default]
     Disabled,
 }
@@ -210,6 +222,19 @@ pub struct RotationConfig {
     pub cleanup_interval_seconds: u64,
     #[serde(default = "default_rotation_max_versions")]
     pub max_versions: i64,
+    /// How often the scheduler looks for items past their rotation schedule.
+    #[serde(default = "default_rotation_schedule_interval_seconds")]
+    pub schedule_interval_seconds: u64,
+    /// Upper bound on rotations started per scheduler pass.
+    #[serde(default = "default_rotation_schedule_batch_size")]
+    pub schedule_batch_size: i64,
+    /// How long the scheduler skips a schedule after failing to start its
+    /// rotation.
+    #[serde(default = "default_rotation_schedule_error_backoff_seconds")]
+    pub schedule_error_backoff_seconds: i64,
+    /// Time limit for each rotation hook call (apply or verify).
+    #[serde(default = "default_rotation_hook_timeout_seconds")]
+    pub hook_timeout_seconds: u64,
 }
 
 impl Default for RotationConfig {
@@ -219,10 +244,249 @@ impl Default for RotationConfig {
             stale_retention_seconds: default_rotation_stale_retention_seconds(),
             cleanup_interval_seconds: default_rotation_cleanup_interval_seconds(),
             max_versions: default_rotation_max_versions(),
+            schedule_interval_seconds: default_rotation_schedule_interval_seconds(),
+            schedule_batch_size: default_rotation_schedule_batch_size(),
+            schedule_error_backoff_seconds: default_rotation_schedule_error_backoff_seconds(),
+            hook_timeout_seconds: default_rotation_hook_timeout_seconds(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DynamicConfig {
+    /// How often expired dynamic credentials are revoked.
+    #[serde(default = "default_dynamic_reaper_interval_seconds")]
+    pub reaper_interval_seconds: u64,
+    /// Upper bound on leases revoked per reaper pass.
+    #[serde(default = "default_dynamic_reaper_batch_size")]
+    pub reaper_batch_size: i64,
+    /// Time limit for connecting to a target database and running its statements.
+    #[serde(default = "default_dynamic_statement_timeout_seconds")]
+    pub statement_timeout_seconds: u64,
+}
+
+impl Default for DynamicConfig {
+    fn default() -> Self {
+        Self {
+            reaper_interval_seconds: default_dynamic_reaper_interval_seconds(),
+            reaper_batch_size: default_dynamic_reaper_batch_size(),
+            statement_timeout_seconds: default_dynamic_statement_timeout_seconds(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaseConfig {
+    /// Longest a secret lease may live, counted from issue time across renewals.
+    #[serde(default = "default_lease_max_ttl_seconds")]
+    pub max_ttl_seconds: i64,
+    /// How long expired and revoked leases are kept before cleanup.
+    #[serde(default = "default_lease_retention_seconds")]
+    pub retention_seconds: i64,
+    #[serde(default = "default_lease_cleanup_interval_seconds")]
+    pub cleanup_interval_seconds: u64,
+}
+
+impl Default for LeaseConfig {
+    fn default() -> Self {
+        Self {
+            max_ttl_seconds: default_lease_max_ttl_seconds(),
+            retention_seconds: default_lease_retention_seconds(),
+            cleanup_interval_seconds: default_lease_cleanup_interval_seconds(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhooksConfig {
+    /// How often the change stream is scanned and due deliveries are sent.
+    #[serde(default = "default_webhooks_poll_interval_seconds")]
+    pub poll_interval_seconds: u64,
+    /// Upper bound on changes queued per webhook and deliveries sent per pass.
+    #[serde(default = "default_webhooks_batch_size")]
+    pub batch_size: i64,
+    #[serde(default = "default_webhooks_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Attempts per delivery before it is marked failed.
+    #[serde(default = "default_webhooks_max_attempts")]
+    pub max_attempts: i32,
+    #[serde(default = "default_webhooks_initial_backoff_seconds")]
+    pub initial_backoff_seconds: i64,
+    #[serde(default = "default_webhooks_max_backoff_seconds")]
+    pub max_backoff_seconds: i64,
+    /// How long delivered and failed deliveries stay in the delivery log.
+    #[serde(default = "default_webhooks_retention_seconds")]
+    pub retention_seconds: i64,
+    /// Accept plain `http://` webhook URLs; only https is allowed by default.
+    #[serde(default)]
+    pub allow_http: bool,
+    /// CIDRs webhooks may target even though they are loopback, private or
+    /// link-local; such addresses are refused otherwise.
+    #[serde(default)]
+    pub allowed_networks: Vec<String>,
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_seconds: default_webhooks_poll_interval_seconds(),
+            batch_size: default_webhooks_batch_size(),
+            timeout_seconds: default_webhooks_timeout_seconds(),
+            max_attempts: default_webhooks_max_attempts(),
+            initial_backoff_seconds: default_webhooks_initial_backoff_seconds(),
+            max_backoff_seconds: default_webhooks_max_backoff_seconds(),
+            retention_seconds: default_webhooks_retention_seconds(),
+            allow_http: false,
+            allowed_networks: Vec::new(),
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiryConfig {
+    /// How often items are checked for upcoming expiry dates.
+    #[serde(default = "default_expiry_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+    /// How far ahead an expiry date counts as "expiring soon", e.g. `30d`.
+    #[serde(default = "default_expiry_notify_within")]
+    pub notify_within: String,
+    /// Upper bound on notices sent per check.
+    #[serde(default = "default_expiry_batch_size")]
+    pub batch_size: i64,
+    /// Where notices go. Without one, no notices are sent.
+    #[serde(default)]
+    pub notifier: Option<NotifierConfig>,
+}
+
+impl Default for ExpiryConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_seconds: default_expiry_check_interval_seconds(),
+            notify_within: default_expiry_notify_within(),
+            batch_size: default_expiry_batch_size(),
+            notifier: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotifierConfig {
+    Smtp(SmtpNotifierConfig),
+    Webhook(WebhookSinkConfig),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpNotifierConfig {
+    pub host: String,
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Name sent in `EHLO`. Defaults to `localhost`.
+    #[serde(default)]
+    pub helo_name: Option<String>,
+    #[serde(default = "default_smtp_timeout_seconds")]
+    pub timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    /// Plain connection upgraded with `STARTTLS` (port 587).
+    #[default]
+    Starttls,
+    /// TLS from the first byte (port 465).
+    Tls,
+    /// No encryption; only for local relays and test servers.
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// How often the current audit chain head is signed with the server identity key.
+    #[serde(default = "default_audit_checkpoint_interval_seconds")]
+    pub checkpoint_interval_seconds: u64,
+    /// Per-sink queue size; events are dropped (and counted) when a sink falls behind.
+    #[serde(default = "default_audit_sink_queue_size")]
+    pub sink_queue_size: usize,
+    #[serde(default)]
+    pub sinks: Vec<AuditSinkConfig>,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            checkpoint_interval_seconds: default_audit_checkpoint_interval_seconds(),
+            sink_queue_size: default_audit_sink_queue_size(),
+            sinks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditSinkConfig {
+    Syslog(SyslogSinkConfig),
+    File(FileSinkConfig),
+    Webhook(WebhookSinkConfig),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyslogSinkConfig {
+    /// `host:port` of the collector.
+    pub address: String,
+    #[serde(default)]
+    pub protocol: SyslogProtocol,
+    #[serde(default = "default_syslog_facility")]
+    pub facility: String,
+    #[serde(default = "default_syslog_app_name")]
+    pub app_name: String,
+    /// Defaults to the `HOSTNAME` environment variable.
+    #[serde(default)]
+    pub hostname: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyslogProtocol {
+    #[default]
+    Udp,
+    Tcp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSinkConfig {
+    pub path: String,
+    /// Rotate once the active file reaches this size.
+    #[serde(default = "default_audit_file_max_bytes")]
+    pub max_bytes: u64,
+    /// Rotated files kept next to the active one (`<path>.1` .. `<path>.N`).
+    #[serde(default = "default_audit_file_max_files")]
+    pub max_files: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookSinkConfig {
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default = "default_webhook_timeout_seconds")]
+    pub timeout_seconds: u64,
+    #[serde(default = "default_webhook_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_webhook_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_webhook_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SentryConfig {
     #[serde(default)]
@@ -317,6 +581,134 @@ const fn default_rotation_max_versions() -> i64 {
     5
 }
 
+const fn default_rotation_schedule_interval_seconds() -> u64 {
+    5 * 60
+}
+
+const fn default_rotation_schedule_batch_size() -> i64 {
+    50
+}
+
+const fn default_rotation_schedule_error_backoff_seconds() -> i64 {
+    60 * 60
+}
+
+const fn default_rotation_hook_timeout_seconds() -> u64 {
+    30
+}
+
+const fn default_dynamic_reaper_interval_seconds() -> u64 {
+    60
+}
+
+const fn default_dynamic_reaper_batch_size() -> i64 {
+    100
+}
+
+const fn default_dynamic_statement_timeout_seconds() -> u64 {
+    30
+}
+
+const fn default_lease_max_ttl_seconds() -> i64 {
+    24 * 60 * 60
+}
+
+const fn default_lease_retention_seconds() -> i64 {
+    7 * 24 * 60 * 60
+}
+
+const fn default_lease_cleanup_interval_seconds() -> u64 {
+    3600
+}
+
+const fn default_audit_checkpoint_interval_seconds() -> u64 {
+    60 * 60
+}
+
+const fn default_audit_sink_queue_size() -> usize {
+    1024
+}
+
+fn default_syslog_facility() -> String {
+    "authpriv".to_string()
+}
+
+fn default_syslog_app_name() -> String {
+    "zann-server".to_string()
+}
+
+const fn default_audit_file_max_bytes() -> u64 {
+    100 * 1024 * 1024
+}
+
+const fn default_audit_file_max_files() -> u32 {
+    5
+}
+
+const fn default_webhooks_poll_interval_seconds() -> u64 {
+    5
+}
+
+const fn default_webhooks_batch_size() -> i64 {
+    100
+}
+
+const fn default_webhooks_timeout_seconds() -> u64 {
+    10
+}
+
+const fn default_webhooks_max_attempts() -> i32 {
+    8
+}
+
+const fn default_webhooks_initial_backoff_seconds() -> i64 {
+    10
+}
+
+const fn default_webhooks_max_backoff_seconds() -> i64 {
+    60 * 60
+}
+
+const fn default_webhooks_retention_seconds() -> i64 {
+    30 * 24 * 60 * 60
+}
+
+const fn default_expiry_check_interval_seconds() -> u64 {
+    86400
+}
+
+fn default_expiry_notify_within() -> String {
+    "30d".to_string()
+}
+
+const fn default_expiry_batch_size() -> i64 {
+    500
+}
+
+const fn default_smtp_port() -> u16 {
+    587
+}
+
+const fn default_smtp_timeout_seconds() -> u64 {
+    10
+}
+
+const fn default_webhook_timeout_seconds() -> u64 {
+    5
+}
+
+const fn default_webhook_max_retries() -> u32 {
+    5
+}
+
+const fn default_webhook_initial_backoff_ms() -> u64 {
+    500
+}
+
+const fn default_webhook_max_backoff_ms() -> u64 {
+    30_000
+}
+
 const fn default_kdf_iterations() -> u32 {
     3
 }
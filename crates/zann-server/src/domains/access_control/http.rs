@@ -25,11 +25,12 @@ pub async fn vault_role_allows(
         };
         return service_account_allows(state, service_account_id, &vault, action, scope).await;
     }
+    // Direct membership and group grants are merged; the strongest role wins.
     let repo = VaultMemberRepo::new(&state.db);
-    let Some(member) = repo.get(vault_id, identity.user_id).await? else {
+    let Some(role) = repo.effective_role(vault_id, identity.user_id).await? else {
         return Ok(false);
     };
-    Ok(role_permits(member.role, action, scope))
+    Ok(role_permits(role, action, scope))
 }
 
 async fn service_account_allows(
@@ -39,10 +40,11 @@ async fn service_account_allows(
     action: &str,
     scope: VaultScope,
 ) -> Result<bool, sqlx_core::Error> {
-    if !matches!(action, "read" | "list") {
+    let read_only = matches!(action, "read" | "list");
+    if matches!(scope, VaultScope::Members) {
         return Ok(false);
     }
-    if matches!(scope, VaultScope::Members) {
+    if !read_only && !matches!(scope, VaultScope::Items) {
         return Ok(false);
     }
     if vault.kind != VaultKind::Shared || vault.encryption_type != VaultEncryptionType::Server {
@@ -53,10 +55,10 @@ async fn service_account_allows(
     let Some(account) = repo.get_by_id(service_account_id).await? else {
         return Ok(false);
     };
-    if scopes_allow_vault(&account.scopes.0, vault) {
-        return Ok(true);
+    if read_only {
+        return Ok(scopes_allow_vault(&account.scopes.0, vault));
     }
-    Ok(false)
+    Ok(scopes_allow_vault_action(&account.scopes.0, vault, action))
 }
 
 pub struct ScopeRule {
@@ -80,6 +82,32 @@ pub fn scopes_allow_vault(scopes: &[String], vault: &Vault) -> bool {
     false
 }
 
+/// Vault-wide check for non-read actions. Only scopes without a prefix count here, because
+/// the caller has no item path; prefixed write scopes are enforced per path by the secrets and
+/// shared-items handlers.
+pub fn scopes_allow_vault_action(scopes: &[String], vault: &Vault, action: &str) -> bool {
+    scopes.iter().any(|scope| {
+        parse_scope(scope).is_some_and(|rule| {
+            rule.prefix.is_none()
+                && scope_allows_action(&rule.permission, action)
+                && vault_matches_scope(vault, &rule.target)
+        })
+    })
+}
+
+/// Maps a request action onto the scope permission that grants it.
+pub(crate) fn scope_allows_action(permission: &str, action: &str) -> bool {
+    match action {
+        "read" | "list" => permission == "read",
+        "read_history" => matches!(
+            permission,
+            "history_read" | "read_history" | "read_previous"
+        ),
+        "read_previous" => permission == "read_previous",
+        _ => permission == action,
+    }
+}
+
 pub fn parse_scope(scope: &str) -> Option<ScopeRule> {
     let mut parts = scope.rsplitn(2, ':');
     let permission = parts.next()?.trim();
@@ -104,7 +132,10 @@ fn split_prefix(selector: &str) -> Option<(&str, Option<String>)> {
 }
 
 fn normalize_prefix(prefix: &str) -> Option<String> {
-    let trimmed = prefix.trim().trim_matches('/');
+    // `zann-server token create` encodes nested prefixes as `apps::billing` so that the
+    // scope stays splittable on `:`.
+    let decoded = prefix.replace("::", "/");
+    let trimmed = decoded.trim().trim_matches('/');
     if trimmed.is_empty() {
         return None;
     }
@@ -208,13 +239,29 @@ fn role_permits(role: VaultMemberRole, action: &str, scope: VaultScope) -> bool
                     | "rotate_abort"
                     | "read_candidate"
                     | "recover"
+                    | "read_leases"
+                    | "transit_encrypt"
+                    | "transit_decrypt"
+                    | "ssh_sign"
+                    | "pki_issue"
+                    | "pki_revoke"
             ),
             VaultScope::Members => matches!(action, "read" | "list"),
         },
         VaultMemberRole::Member => match scope {
             VaultScope::Vault => matches!(action, "read" | "list"),
             VaultScope::Items | VaultScope::Sync => {
-                matches!(action, "read" | "list" | "write" | "read_history")
+                matches!(
+                    action,
+                    "read"
+                        | "list"
+                        | "write"
+                        | "read_history"
+                        | "transit_encrypt"
+                        | "transit_decrypt"
+                        | "ssh_sign"
+                        | "pki_issue"
+                )
             }
             VaultScope::Members => matches!(action, "read" | "list"),
         },
@@ -0,0 +1,232 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zann_core::{AuditEvent, Identity};
+use zann_db::repo::{AuditEventFilter, AuditEventRepo, VaultRepo};
+
+use crate::app::AppState;
+use crate::domains::access_control::http::find_vault;
+use crate::infra::metrics;
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    error: &'static str,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct AuditListQuery {
+    /// User id or email of the actor.
+    #[serde(default)]
+    pub(crate) actor: Option<String>,
+    /// Vault id or slug.
+    #[serde(default)]
+    pub(crate) vault: Option<String>,
+    /// Exact item/secret path.
+    #[serde(default)]
+    pub(crate) path: Option<String>,
+    #[serde(default)]
+    pub(crate) action: Option<String>,
+    #[serde(default)]
+    pub(crate) result: Option<String>,
+    /// RFC 3339 lower bound (inclusive).
+    #[serde(default)]
+    pub(crate) since: Option<String>,
+    /// RFC 3339 upper bound (exclusive).
+    #[serde(default)]
+    pub(crate) until: Option<String>,
+    #[serde(default)]
+    pub(crate) limit: Option<i64>,
+    #[serde(default)]
+    pub(crate) cursor: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct AuditEventResponse {
+    pub(crate) id: String,
+    pub(crate) seq: i64,
+    pub(crate) category: String,
+    pub(crate) action: String,
+    pub(crate) result: String,
+    pub(crate) actor_user_id: Option<String>,
+    pub(crate) actor_email: Option<String>,
+    pub(crate) actor_device_id: Option<String>,
+    pub(crate) actor_service_account_id: Option<String>,
+    pub(crate) auth_source: Option<String>,
+    pub(crate) vault_id: Option<String>,
+    pub(crate) path: Option<String>,
+    pub(crate) target: Option<String>,
+    pub(crate) detail: Option<String>,
+    pub(crate) client_ip: Option<String>,
+    pub(crate) prev_hash: Option<String>,
+    pub(crate) hash: Option<String>,
+    pub(crate) created_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct AuditListResponse {
+    pub(crate) events: Vec<AuditEventResponse>,
+    pub(crate) next_cursor: Option<String>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/v1/audit", get(list_audit_events))
+}
+
+#[tracing::instrument(skip(state, identity, query))]
+async fn list_audit_events(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<AuditListQuery>,
+) -> impl IntoResponse {
+    let resource = "audit";
+    let policies = state.policy_store.get();
+    if !policies.is_allowed(&identity, "read", resource) {
+        metrics::forbidden_access(resource);
+        tracing::warn!(
+            event = "forbidden",
+            action = "read",
+            resource = resource,
+            "Access denied"
+        );
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let mut filter = AuditEventFilter {
+        item_path: non_empty(query.path),
+        action: non_empty(query.action),
+        result: non_empty(query.result),
+        ..AuditEventFilter::default()
+    };
+
+    if let Some(actor) = non_empty(query.actor) {
+        match Uuid::parse_str(&actor) {
+            Ok(user_id) => filter.actor_user_id = Some(user_id),
+            Err(_) => filter.actor_email = Some(actor),
+        }
+    }
+
+    if let Some(vault_ref) = non_empty(query.vault) {
+        let vault_repo = VaultRepo::new(&state.db);
+        filter.vault_id = match Uuid::parse_str(&vault_ref) {
+            Ok(vault_id) => Some(vault_id),
+            Err(_) => match find_vault(&vault_repo, &vault_ref).await {
+                Ok(Some(vault)) => Some(vault.id),
+                Ok(None) => {
+                    return (
+                        StatusCode::OK,
+                        Json(AuditListResponse {
+                            events: Vec::new(),
+                            next_cursor: None,
+                        }),
+                    )
+                        .into_response();
+                }
+                Err(_) => {
+                    tracing::error!(event = "audit_list_failed", "DB error");
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ErrorResponse { error: "db_error" }),
+                    )
+                        .into_response();
+                }
+            },
+        };
+    }
+
+    let Ok(since) = parse_time(query.since.as_deref()) else {
+        return bad_request("invalid_since");
+    };
+    let Ok(until) = parse_time(query.until.as_deref()) else {
+        return bad_request("invalid_until");
+    };
+    filter.since = since;
+    filter.until = until;
+
+    if let Some(cursor) = non_empty(query.cursor) {
+        match cursor.parse::<i64>() {
+            Ok(seq) if seq > 0 => filter.before_seq = Some(seq),
+            _ => return bad_request("invalid_cursor"),
+        }
+    }
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let repo = AuditEventRepo::new(&state.db);
+    let mut events = match repo.list(&filter, limit + 1).await {
+        Ok(events) => events,
+        Err(err) => {
+            tracing::error!(event = "audit_list_failed", error = %err, "DB error");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: "db_error" }),
+            )
+                .into_response();
+        }
+    };
+
+    let has_more = events.len() as i64 > limit;
+    events.truncate(limit as usize);
+    let next_cursor = if has_more {
+        events.last().map(|event| event.seq.to_string())
+    } else {
+        None
+    };
+
+    let events = events.into_iter().map(audit_event_response).collect();
+    (
+        StatusCode::OK,
+        Json(AuditListResponse {
+            events,
+            next_cursor,
+        }),
+    )
+        .into_response()
+}
+
+fn audit_event_response(event: AuditEvent) -> AuditEventResponse {
+    AuditEventResponse {
+        id: event.id.to_string(),
+        seq: event.seq,
+        category: event.category,
+        action: event.action,
+        result: event.result,
+        actor_user_id: event.actor_user_id.map(|id| id.to_string()),
+        actor_email: event.actor_email,
+        actor_device_id: event.actor_device_id.map(|id| id.to_string()),
+        actor_service_account_id: event.actor_service_account_id.map(|id| id.to_string()),
+        auth_source: event.auth_source,
+        vault_id: event.vault_id.map(|id| id.to_string()),
+        path: event.item_path,
+        target: event.target,
+        detail: event.detail,
+        client_ip: event.client_ip,
+        prev_hash: event.prev_hash,
+        hash: event.hash,
+        created_at: event.created_at.to_rfc3339(),
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_time(value: Option<&str>) -> Result<Option<DateTime<Utc>>, chrono::ParseError> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| DateTime::parse_from_rfc3339(value).map(|dt| dt.with_timezone(&Utc)))
+        .transpose()
+}
+
+fn bad_request(error: &'static str) -> axum::response::Response {
+    (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response()
+}
@@ -0,0 +1,180 @@
+use ipnet::IpNet;
+use std::net::{IpAddr, SocketAddr};
+
+/// Why a service account token was refused by its `allowed_ips` list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpDenial {
+    /// No client address could be resolved for the request.
+    ClientIpUnknown,
+    /// The resolved client address could not be parsed as an IP.
+    ClientIpInvalid,
+    /// The client address is outside every allowed entry.
+    NotAllowed,
+}
+
+impl IpDenial {
+    pub const fn reason(self) -> &'static str {
+        match self {
+            Self::ClientIpUnknown => "client_ip_unknown",
+            Self::ClientIpInvalid => "client_ip_invalid",
+            Self::NotAllowed => "ip_not_allowed",
+        }
+    }
+}
+
+/// Parses an `allowed_ips` entry: a bare IPv4/IPv6 address or a CIDR range.
+/// Ranges with host bits set (`10.20.1.0/16`) are rejected as ambiguous.
+pub fn parse_allowed_ip(value: &str) -> Result<IpNet, String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err("allowed ip cannot be empty".to_string());
+    }
+    if trimmed.contains('/') {
+        let net = trimmed
+            .parse::<IpNet>()
+            .map_err(|_| format!("invalid allowed ip: {trimmed}"))?;
+        if net.trunc() != net {
+            return Err(format!(
+                "invalid allowed ip: {trimmed} has host bits set (use {})",
+                net.trunc()
+            ));
+        }
+        return Ok(net);
+    }
+    trimmed
+        .parse::<IpAddr>()
+        .map(|ip| IpNet::from(ip.to_canonical()))
+        .map_err(|_| format!("invalid allowed ip: {trimmed}"))
+}
+
+/// Canonical form stored on the account: single hosts without a prefix
+/// length, ranges in network/prefix notation.
+pub fn normalize_allowed_ip(value: &str) -> Result<String, String> {
+    let net = parse_allowed_ip(value)?;
+    if net.prefix_len() == net.max_prefix_len() {
+        Ok(net.addr().to_string())
+    } else {
+        Ok(net.to_string())
+    }
+}
+
+/// Parses a resolved client address. Forwarded headers may carry a port,
+/// brackets or quotes, and IPv4-mapped IPv6 addresses are folded to IPv4.
+pub fn parse_client_ip(value: &str) -> Option<IpAddr> {
+    let trimmed = value.trim().trim_matches('"');
+    let ip = trimmed
+        .parse::<IpAddr>()
+        .ok()
+        .or_else(|| trimmed.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .or_else(|| {
+            trimmed
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|inner| inner.parse::<IpAddr>().ok())
+        })?;
+    Some(ip.to_canonical())
+}
+
+/// Checks `client_ip` against an account's `allowed_ips`. Stored entries
+/// that no longer parse never match.
+pub fn check_allowed_ips(allowed: &[String], client_ip: Option<&str>) -> Result<(), IpDenial> {
+    let client_ip = client_ip.ok_or(IpDenial::ClientIpUnknown)?;
+    let ip = parse_client_ip(client_ip).ok_or(IpDenial::ClientIpInvalid)?;
+    let allowed = allowed
+        .iter()
+        .filter_map(|entry| parse_allowed_ip(entry).ok())
+        .any(|net| net.contains(&ip));
+    if allowed {
+        Ok(())
+    } else {
+        Err(IpDenial::NotAllowed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_allowed_ips, normalize_allowed_ip, parse_client_ip, IpDenial};
+
+    fn allowed(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|entry| (*entry).to_string()).collect()
+    }
+
+    #[test]
+    fn normalize_accepts_addresses_and_ranges() {
+        assert_eq!(
+            normalize_allowed_ip(" 10.0.0.5 "),
+            Ok("10.0.0.5".to_string())
+        );
+        assert_eq!(
+            normalize_allowed_ip("10.0.0.5/32"),
+            Ok("10.0.0.5".to_string())
+        );
+        assert_eq!(
+            normalize_allowed_ip("10.20.0.0/16"),
+            Ok("10.20.0.0/16".to_string())
+        );
+        assert_eq!(
+            normalize_allowed_ip("2001:DB8::/48"),
+            Ok("2001:db8::/48".to_string())
+        );
+        assert_eq!(
+            normalize_allowed_ip("::ffff:10.0.0.5"),
+            Ok("10.0.0.5".to_string())
+        );
+    }
+
+    #[test]
+    fn normalize_rejects_invalid_entries() {
+        assert!(normalize_allowed_ip("").is_err());
+        assert!(normalize_allowed_ip("10.0.0").is_err());
+        assert!(normalize_allowed_ip("10.0.0.0/33").is_err());
+        assert!(normalize_allowed_ip("10.20.1.0/16").is_err());
+        assert!(normalize_allowed_ip("example.com").is_err());
+    }
+
+    #[test]
+    fn parse_client_ip_strips_ports_and_brackets() {
+        assert_eq!(parse_client_ip("10.0.0.5:443"), "10.0.0.5".parse().ok());
+        assert_eq!(
+            parse_client_ip("\"[2001:db8::1]:8443\""),
+            "2001:db8::1".parse().ok()
+        );
+        assert_eq!(parse_client_ip("[2001:db8::1]"), "2001:db8::1".parse().ok());
+        assert_eq!(parse_client_ip("::ffff:10.0.0.5"), "10.0.0.5".parse().ok());
+        assert_eq!(parse_client_ip("unknown"), None);
+    }
+
+    #[test]
+    fn check_matches_ranges() {
+        let entries = allowed(&["192.0.2.7", "10.20.0.0/16", "2001:db8::/48"]);
+        assert!(check_allowed_ips(&entries, Some("192.0.2.7")).is_ok());
+        assert!(check_allowed_ips(&entries, Some("10.20.33.4")).is_ok());
+        assert!(check_allowed_ips(&entries, Some("2001:db8:0:ff::9")).is_ok());
+        assert!(check_allowed_ips(&entries, Some("::ffff:10.20.1.1")).is_ok());
+        assert_eq!(
+            check_allowed_ips(&entries, Some("10.21.0.1")),
+            Err(IpDenial::NotAllowed)
+        );
+        assert_eq!(
+            check_allowed_ips(&entries, Some("2001:db9::1")),
+            Err(IpDenial::NotAllowed)
+        );
+    }
+
+    #[test]
+    fn check_reports_missing_or_invalid_client_ip() {
+        let entries = allowed(&["10.0.0.0/8"]);
+        assert_eq!(
+            check_allowed_ips(&entries, None),
+            Err(IpDenial::ClientIpUnknown)
+        );
+        assert_eq!(
+            check_allowed_ips(&entries, Some("not-an-ip")),
+            Err(IpDenial::ClientIpInvalid)
+        );
+        assert_eq!(
+            check_allowed_ips(&allowed(&["garbage"]), Some("10.0.0.1")),
+            Err(IpDenial::NotAllowed)
+        );
+    }
+}
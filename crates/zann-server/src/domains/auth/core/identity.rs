@@ -6,7 +6,10 @@ use zann_db::repo::{
 };
 
 use crate::app::AppState;
+use crate::domains::auth::core::allowed_ips::check_allowed_ips;
 use crate::domains::auth::core::passwords::{hash_service_token, random_kdf_salt, KdfParams};
+use crate::infra::audit::{self, AuditEntry};
+use crate::infra::metrics;
 use crate::infra::user_display::{avatar_initials_for_user, display_name_for_user};
 
 const SERVICE_ACCOUNT_PREFIX: &str = "zann_sa_";
@@ -257,11 +260,24 @@ pub async fn identity_from_service_account_token(
         return Err("token_expired");
     }
     if let Some(allowed_ips) = account.allowed_ips.as_ref() {
-        let Some(client_ip) = client_ip else {
-            return Err("ip_not_allowed");
-        };
-        if !allowed_ips.0.iter().any(|ip| ip == client_ip) {
-            return Err("ip_not_allowed");
+        if let Err(denial) = check_allowed_ips(&allowed_ips.0, client_ip) {
+            let reason = denial.reason();
+            metrics::service_account_ip_denied(reason, "token");
+            tracing::warn!(
+                event = "auth_sa_ip_denied",
+                reason,
+                service_account_id = %account.id,
+                ip = %client_ip.unwrap_or("unknown"),
+                "Service account token used from a disallowed address"
+            );
+            let detail = format!("forbidden: {reason}");
+            let entry = AuditEntry::new("auth", "service_account_token", "forbidden")
+                .actor(Some(account.owner_user_id), None)
+                .service_account(account.id)
+                .detail(Some(&detail))
+                .client_ip(client_ip);
+            audit::record(state, None, entry).await;
+            return Err(reason);
         }
     }
 
@@ -80,7 +80,9 @@ pub async fn auth_middleware(
             .await
         {
             Ok(identity) => identity,
-            Err("ip_not_allowed") => return Err(StatusCode::FORBIDDEN),
+            Err("ip_not_allowed" | "client_ip_unknown" | "client_ip_invalid") => {
+                return Err(StatusCode::FORBIDDEN)
+            }
             Err("db_error") => return Err(StatusCode::INTERNAL_SERVER_ERROR),
             Err(_) => return Err(StatusCode::UNAUTHORIZED),
         }
@@ -1,3 +1,4 @@
+pub mod allowed_ips;
 pub mod identity;
 pub mod middleware;
 pub mod oidc;
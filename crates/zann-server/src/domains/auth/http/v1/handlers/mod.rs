@@ -7,7 +7,9 @@ use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
 
+use crate::app::AppState;
 use crate::domains::auth::service::AuthError;
+use crate::infra::audit::{self, AuditEntry};
 
 use super::types::ErrorResponse;
 
@@ -16,6 +18,21 @@ pub(crate) use prelogin_register::{prelogin, register};
 pub(crate) use service_account::login_service_account;
 pub(crate) use session::{login, logout, refresh};
 
+async fn audit_auth_failure(
+    state: &AppState,
+    action: &str,
+    error: &AuthError,
+    email: Option<&str>,
+    client_ip: Option<&str>,
+) {
+    let detail = error.to_string();
+    let entry = AuditEntry::new("auth", action, error.label())
+        .actor(None, email)
+        .detail(Some(&detail))
+        .client_ip(client_ip);
+    audit::record(state, None, entry).await;
+}
+
 pub(super) fn map_auth_error(error: AuthError) -> axum::response::Response {
     match error {
         AuthError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
@@ -26,7 +26,11 @@ pub(crate) async fn login_oidc(
     };
     match service::login_oidc(&state, &payload, &ctx).await {
         Ok(body) => (StatusCode::OK, Json(body)).into_response(),
-        Err(err) => super::map_auth_error(err),
+        Err(err) => {
+            super::audit_auth_failure(&state, "login_oidc", &err, None, ctx.client_ip.as_deref())
+                .await;
+            super::map_auth_error(err)
+        }
     }
 }
 
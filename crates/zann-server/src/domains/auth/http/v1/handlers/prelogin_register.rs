@@ -45,6 +45,16 @@ pub(crate) async fn register(
     };
     match service::register(&state, &payload, &ctx).await {
         Ok(body) => (StatusCode::CREATED, Json(body)).into_response(),
-        Err(err) => super::map_auth_error(err),
+        Err(err) => {
+            super::audit_auth_failure(
+                &state,
+                "register",
+                &err,
+                Some(&payload.email),
+                ctx.client_ip.as_deref(),
+            )
+            .await;
+            super::map_auth_error(err)
+        }
     }
 }
@@ -1,6 +1,7 @@
 use super::super::types::ServiceAccountLoginRequest;
 use crate::app::AppState;
 use crate::domains::auth::service::{self, AuthRequestContext};
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::request_context::{client_ip, request_id, user_agent};
 use axum::{
     extract::{ConnectInfo, State},
@@ -22,7 +23,28 @@ pub(crate) async fn login_service_account(
         user_agent: user_agent(&headers),
     };
     match service::login_service_account(&state, &payload, &ctx).await {
-        Ok(body) => (StatusCode::OK, Json(body)).into_response(),
-        Err(err) => super::map_auth_error(err),
+        Ok(body) => {
+            let mut entry = AuditEntry::new("auth", "login_service_account", "ok")
+                .client_ip(ctx.client_ip.as_deref());
+            if let Ok(service_account_id) = body.service_account_id.parse() {
+                entry = entry.service_account(service_account_id);
+            }
+            if let Ok(owner_user_id) = body.owner_user_id.parse() {
+                entry = entry.actor(Some(owner_user_id), None);
+            }
+            audit::record(&state, None, entry).await;
+            (StatusCode::OK, Json(body)).into_response()
+        }
+        Err(err) => {
+            super::audit_auth_failure(
+                &state,
+                "login_service_account",
+                &err,
+                None,
+                ctx.client_ip.as_deref(),
+            )
+            .await;
+            super::map_auth_error(err)
+        }
     }
 }
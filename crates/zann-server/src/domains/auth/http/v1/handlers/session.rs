@@ -26,7 +26,17 @@ pub(crate) async fn login(
     };
     match service::login_internal(&state, &payload, &ctx).await {
         Ok(body) => (StatusCode::OK, Json(body)).into_response(),
-        Err(err) => super::map_auth_error(err),
+        Err(err) => {
+            super::audit_auth_failure(
+                &state,
+                "login",
+                &err,
+                Some(&payload.email),
+                ctx.client_ip.as_deref(),
+            )
+            .await;
+            super::map_auth_error(err)
+        }
     }
 }
 
@@ -17,6 +17,7 @@ use zann_db::repo::{
 use crate::app::AppState;
 use crate::config::{AuthMode, InternalRegistration};
 use crate::domains::access_control::http::scopes_allow_vault;
+use crate::domains::auth::core::allowed_ips::check_allowed_ips;
 use crate::domains::auth::core::identity::identity_from_oidc;
 use crate::domains::auth::core::oidc::validate_oidc_jwt;
 use crate::domains::auth::core::passwords::{
@@ -29,6 +30,8 @@ use crate::domains::auth::helpers::{
     ensure_personal_vault_tx, ttl_seconds_u64,
 };
 use crate::domains::errors::ServiceError;
+use crate::domains::invites::service::{accept_invite_tx, find_for_registration};
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::db::apply_tx_isolation;
 use crate::infra::metrics;
 
@@ -108,6 +111,15 @@ pub async fn prelogin(
     })
 }
 
+/// Metric result and log reason for an invite that failed to validate or apply.
+fn invite_failure(err: &AuthError) -> (&'static str, &'static str) {
+    match err {
+        AuthError::Forbidden(code) => ("rejected", code),
+        AuthError::DbError => ("db_error", "db_error"),
+        other => ("rejected", other.label()),
+    }
+}
+
 pub async fn register(
     state: &AppState,
     payload: &RegisterRequest,
@@ -126,6 +138,11 @@ pub async fn register(
         return Err(AuthError::Forbidden("internal_disabled"));
     }
 
+    let invite_token = payload
+        .invite_token
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
     match state.config.auth.internal.registration {
         InternalRegistration::Disabled => {
             metrics::auth_register("rejected");
@@ -139,9 +156,41 @@ pub async fn register(
             );
             return Err(AuthError::Forbidden("registration_disabled"));
         }
-        InternalRegistration::Open => {}
+        InternalRegistration::InviteOnly if invite_token.is_none() => {
+            metrics::auth_register("rejected");
+            tracing::warn!(
+                event = "auth_register_rejected",
+                reason = "invite_required",
+                email = "redacted",
+                ip = %ctx.client_ip.as_deref().unwrap_or("unknown"),
+                request_id = %ctx.request_id.as_deref().unwrap_or("unknown"),
+                "Registration rejected"
+            );
+            return Err(AuthError::Forbidden("invite_required"));
+        }
+        InternalRegistration::InviteOnly | InternalRegistration::Open => {}
     }
 
+    let invite = match invite_token {
+        Some(token) => match find_for_registration(state, token, &payload.email).await {
+            Ok(invite) => Some(invite),
+            Err(err) => {
+                let (result, reason) = invite_failure(&err);
+                metrics::auth_register(result);
+                tracing::warn!(
+                    event = "auth_register_rejected",
+                    reason,
+                    email = "redacted",
+                    ip = %ctx.client_ip.as_deref().unwrap_or("unknown"),
+                    request_id = %ctx.request_id.as_deref().unwrap_or("unknown"),
+                    "Registration rejected"
+                );
+                return Err(err);
+            }
+        },
+        None => None,
+    };
+
     let now = Utc::now();
     let params = KdfParams {
         algorithm: state.config.auth.kdf.algorithm.clone(),
@@ -498,6 +547,63 @@ pub async fn register(
         return Err(AuthError::Internal("personal_vault_create_failed"));
     }
 
+    if let Some(invite) = invite.as_ref() {
+        if let Err(err) = accept_invite_tx(&mut tx, invite, user.id, now).await {
+            if let Err(rollback_err) = tx.rollback().await {
+                tracing::error!(
+                    event = "auth_register_failed",
+                    error = %rollback_err,
+                    "DB rollback failed"
+                );
+                metrics::auth_register("db_error");
+                return Err(AuthError::DbError);
+            }
+            let (result, reason) = invite_failure(&err);
+            metrics::auth_register(result);
+            tracing::warn!(
+                event = "auth_register_rejected",
+                reason,
+                email = "redacted",
+                ip = %ctx.client_ip.as_deref().unwrap_or("unknown"),
+                request_id = %ctx.request_id.as_deref().unwrap_or("unknown"),
+                "Registration rejected"
+            );
+            return Err(err);
+        }
+    }
+
+    let invite_detail = invite
+        .as_ref()
+        .map(|invite| format!("invite={}", invite.id));
+    let entry = AuditEntry::new("auth", "register", "ok")
+        .actor(Some(user.id), Some(&user.email))
+        .target(user.id)
+        .detail(invite_detail.as_deref())
+        .client_ip(ctx.client_ip.as_deref());
+    let audited = match audit::record_tx(&mut tx, None, entry).await {
+        Ok(audited) => audited,
+        Err(err) => {
+            if let Err(rollback_err) = tx.rollback().await {
+                tracing::error!(
+                    event = "auth_register_failed",
+                    error = %rollback_err,
+                    "DB rollback failed"
+                );
+            }
+            metrics::auth_register("db_error");
+            tracing::error!(
+                event = "auth_register_failed",
+                reason = "audit_write_failed",
+                error = %err,
+                email = "redacted",
+                ip = %ctx.client_ip.as_deref().unwrap_or("unknown"),
+                request_id = %ctx.request_id.as_deref().unwrap_or("unknown"),
+                "Registration failed"
+            );
+            return Err(AuthError::DbError);
+        }
+    };
+
     if let Err(err) = tx.commit().await {
         metrics::auth_register("db_error");
         tracing::error!(
@@ -511,6 +617,7 @@ pub async fn register(
         );
         return Err(AuthError::DbError);
     }
+    audited.dispatch();
 
     metrics::auth_register("ok");
     metrics::auth_tokens_issued("access");
@@ -713,6 +820,11 @@ pub async fn login_internal(
         );
     }
     metrics::auth_login("ok", "internal");
+    let entry = AuditEntry::new("auth", "login", "ok")
+        .actor(Some(user.id), Some(&user.email))
+        .detail(Some("internal"))
+        .client_ip(ctx.client_ip.as_deref());
+    audit::record(state, None, entry).await;
     metrics::auth_tokens_issued("access");
     metrics::auth_tokens_issued("refresh");
     tracing::info!(
@@ -869,6 +981,10 @@ pub async fn login_oidc(
     }
 
     metrics::auth_login("ok", "oidc");
+    let entry = AuditEntry::new("auth", "login", "ok")
+        .detail(Some("oidc"))
+        .client_ip(ctx.client_ip.as_deref());
+    audit::record(state, Some(&identity), entry).await;
     metrics::auth_tokens_issued("access");
     metrics::auth_tokens_issued("refresh");
     tracing::info!(
@@ -1124,13 +1240,20 @@ pub(crate) async fn login_service_account(
     }
 
     if let Some(allowed_ips) = account.allowed_ips.as_ref() {
-        let Some(client_ip) = ctx.client_ip.as_deref() else {
-            metrics::auth_login("ip_denied", "service_account");
-            return Err(AuthError::Forbidden("ip_not_allowed"));
-        };
-        if !allowed_ips.0.iter().any(|ip| ip == client_ip) {
+        if let Err(denial) = check_allowed_ips(&allowed_ips.0, ctx.client_ip.as_deref()) {
+            let reason = denial.reason();
             metrics::auth_login("ip_denied", "service_account");
-            return Err(AuthError::Forbidden("ip_not_allowed"));
+            metrics::service_account_ip_denied(reason, "login");
+            tracing::warn!(
+                event = "auth_login_failed",
+                reason,
+                method = "service_account",
+                service_account_id = %account.id,
+                ip = %ctx.client_ip.as_deref().unwrap_or("unknown"),
+                request_id = %ctx.request_id.as_deref().unwrap_or("unknown"),
+                "Login failed"
+            );
+            return Err(AuthError::Forbidden(reason));
         }
     }
 
@@ -1222,7 +1345,7 @@ async fn service_account_vault_keys(
     state: &AppState,
     account: &zann_core::ServiceAccount,
 ) -> Result<Vec<ServiceAccountVaultKey>, &'static str> {
-    let Some(smk) = state.server_master_key.as_ref() else {
+    let Some(smk) = state.server_master_key.get() else {
         return Err("smk_missing");
     };
     let vault_repo = VaultRepo::new(&state.db);
@@ -1244,7 +1367,7 @@ async fn service_account_vault_keys(
             continue;
         }
 
-        let key = core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc)
+        let key = core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc)
             .map_err(|err| err.as_code())?;
         let key_b64 = base64::engine::general_purpose::STANDARD.encode(key.as_bytes());
         keys.push(ServiceAccountVaultKey {
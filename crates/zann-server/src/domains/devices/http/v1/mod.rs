@@ -13,6 +13,7 @@ use zann_core::{Device, Identity};
 use zann_db::repo::DeviceRepo;
 
 use crate::app::AppState;
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::metrics;
 
 #[derive(Serialize, JsonSchema)]
@@ -147,6 +148,8 @@ async fn revoke_device(
             resource = %resource,
             "Access denied"
         );
+        let entry = AuditEntry::new("devices", "revoke", "forbidden").target(device_id);
+        audit::record(&state, Some(&identity), entry).await;
         return StatusCode::FORBIDDEN.into_response();
     }
 
@@ -168,7 +171,18 @@ async fn revoke_device(
         return StatusCode::NOT_FOUND.into_response();
     }
 
-    let Ok(affected) = repo.revoke(device_id, Utc::now()).await else {
+    let revoke = async {
+        let mut tx = state.db.begin().await?;
+        let affected = DeviceRepo::revoke_in(&mut tx, device_id, Utc::now()).await?;
+        if affected > 0 {
+            let entry = AuditEntry::new("devices", "revoke", "ok").target(device_id);
+            let audited = audit::record_tx(&mut tx, Some(&identity), entry).await?;
+            tx.commit().await?;
+            audited.dispatch();
+        }
+        Ok::<_, sqlx_core::Error>(affected)
+    };
+    let Ok(affected) = revoke.await else {
         tracing::error!(event = "device_revoke_failed", "DB error");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
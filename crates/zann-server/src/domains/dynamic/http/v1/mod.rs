@@ -0,0 +1,278 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zann_core::{DynamicSecret, Identity};
+
+use crate::app::AppState;
+use crate::domains::dynamic::service::{
+    self, DynamicError, DynamicSecretConfig, IssuedCredential, SetDynamicSecretCommand,
+};
+use crate::infra::audit;
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    pub(crate) error: &'static str,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct SetDynamicSecretRequest {
+    #[serde(flatten)]
+    pub(crate) config: DynamicSecretConfig,
+    #[serde(default)]
+    pub(crate) default_ttl: Option<String>,
+    #[serde(default)]
+    pub(crate) max_ttl: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema, Default)]
+pub(crate) struct IssueDynamicCredentialRequest {
+    #[serde(default)]
+    pub(crate) ttl: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct DynamicSecretResponse {
+    pub(crate) id: String,
+    pub(crate) vault_id: String,
+    pub(crate) path: String,
+    pub(crate) kind: String,
+    pub(crate) target: String,
+    pub(crate) default_ttl_seconds: i64,
+    pub(crate) max_ttl_seconds: i64,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct DynamicCredentialResponse {
+    pub(crate) lease_id: String,
+    pub(crate) username: String,
+    pub(crate) password: String,
+    pub(crate) issued_at: String,
+    pub(crate) expires_at: String,
+    pub(crate) ttl_seconds: i64,
+    pub(crate) host: Option<String>,
+    pub(crate) port: Option<u16>,
+    pub(crate) database: Option<String>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/v1/vaults/:vault_id/dynamic/*path",
+            get(get_dynamic_secret)
+                .put(set_dynamic_secret)
+                .delete(delete_dynamic_secret)
+                .post(issue_credential),
+        )
+        .route(
+            "/v1/vaults/:vault_id/dynamic-leases/:lease_id",
+            delete(revoke_lease),
+        )
+}
+
+fn map_dynamic_error(error: DynamicError) -> axum::response::Response {
+    match error {
+        DynamicError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        DynamicError::Forbidden(code) => {
+            (StatusCode::FORBIDDEN, Json(ErrorResponse { error: code })).into_response()
+        }
+        DynamicError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        DynamicError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        DynamicError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        DynamicError::DbError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response(),
+        DynamicError::Internal(code @ ("dynamic_issue_failed" | "dynamic_revoke_failed")) => {
+            (StatusCode::BAD_GATEWAY, Json(ErrorResponse { error: code })).into_response()
+        }
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+fn secret_response(secret: DynamicSecret, config: &DynamicSecretConfig) -> DynamicSecretResponse {
+    DynamicSecretResponse {
+        id: secret.id.to_string(),
+        vault_id: secret.vault_id.to_string(),
+        path: secret.path,
+        kind: secret.kind,
+        target: config.describe(),
+        default_ttl_seconds: secret.default_ttl_seconds,
+        max_ttl_seconds: secret.max_ttl_seconds,
+        created_at: secret.created_at.to_rfc3339(),
+        updated_at: secret.updated_at.to_rfc3339(),
+    }
+}
+
+fn credential_response(issued: IssuedCredential) -> DynamicCredentialResponse {
+    let lease = issued.lease;
+    let connection = issued.connection;
+    DynamicCredentialResponse {
+        lease_id: lease.id.to_string(),
+        username: lease.username,
+        password: issued.password,
+        issued_at: lease.issued_at.to_rfc3339(),
+        expires_at: lease.expires_at.to_rfc3339(),
+        ttl_seconds: (lease.expires_at - lease.issued_at).num_seconds(),
+        host: connection.as_ref().map(|info| info.host.clone()),
+        port: connection.as_ref().map(|info| info.port),
+        database: connection.and_then(|info| info.database),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_dynamic_secret(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, path)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::get_dynamic_secret(&state, &identity, &vault_id, &path).await {
+        Ok((secret, config)) => {
+            (StatusCode::OK, Json(secret_response(secret, &config))).into_response()
+        }
+        Err(error) => map_dynamic_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn set_dynamic_secret(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, path)): Path<(String, String)>,
+    Json(payload): Json<SetDynamicSecretRequest>,
+) -> impl IntoResponse {
+    let command = SetDynamicSecretCommand {
+        config: payload.config.clone(),
+        default_ttl: payload.default_ttl,
+        max_ttl: payload.max_ttl,
+    };
+    match service::set_dynamic_secret(&state, &identity, &vault_id, &path, command).await {
+        Ok(secret) => {
+            audit::secrets_event(
+                &state,
+                &identity,
+                "dynamic_configure",
+                "ok",
+                &vault_id,
+                &secret.path,
+                None,
+            )
+            .await;
+            (
+                StatusCode::OK,
+                Json(secret_response(secret, &payload.config)),
+            )
+                .into_response()
+        }
+        Err(error) => map_dynamic_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn delete_dynamic_secret(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, path)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::delete_dynamic_secret(&state, &identity, &vault_id, &path).await {
+        Ok(()) => {
+            audit::secrets_event(
+                &state,
+                &identity,
+                "dynamic_delete",
+                "ok",
+                &vault_id,
+                &path,
+                None,
+            )
+            .await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => map_dynamic_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn issue_credential(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, path)): Path<(String, String)>,
+    payload: Option<Json<IssueDynamicCredentialRequest>>,
+) -> impl IntoResponse {
+    let Json(payload) = payload.unwrap_or_default();
+    let result =
+        service::issue_credential(&state, &identity, &vault_id, &path, payload.ttl.as_deref())
+            .await;
+    match result {
+        Ok(issued) => {
+            audit::secrets_event(
+                &state,
+                &identity,
+                "dynamic_issue",
+                "ok",
+                &vault_id,
+                &path,
+                Some(&issued.lease.id.to_string()),
+            )
+            .await;
+            (StatusCode::OK, Json(credential_response(issued))).into_response()
+        }
+        Err(error) => {
+            let label = error.label();
+            audit::secrets_event(
+                &state,
+                &identity,
+                "dynamic_issue",
+                label,
+                &vault_id,
+                &path,
+                Some(label),
+            )
+            .await;
+            map_dynamic_error(error)
+        }
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn revoke_lease(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, lease_id)): Path<(String, Uuid)>,
+) -> impl IntoResponse {
+    match service::revoke_lease(&state, &identity, &vault_id, lease_id).await {
+        Ok((lease, path)) => {
+            audit::secrets_event(
+                &state,
+                &identity,
+                "dynamic_revoke",
+                "ok",
+                &vault_id,
+                &path,
+                Some(&lease.id.to_string()),
+            )
+            .await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => map_dynamic_error(error),
+    }
+}
@@ -0,0 +1,239 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx_core::connection::Connection;
+use sqlx_core::executor::Executor;
+use sqlx_postgres::{PgConnectOptions, PgConnection};
+
+const MAX_ROLE_LEN: usize = 63;
+const USERNAME_PREFIX: &str = "v_";
+const USERNAME_SLUG_LEN: usize = 24;
+const USERNAME_SUFFIX_LEN: usize = 12;
+const PASSWORD_LEN: usize = 32;
+const DEFAULT_REVOCATION: &str = "DROP ROLE IF EXISTS \"{{name}}\"";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PostgresDynamicConfig {
+    /// Connection string for a login allowed to create and drop roles.
+    pub connection_url: String,
+    /// Statements that create the credential. `{{name}}`, `{{password}}` and
+    /// `{{expiration}}` are substituted before running them in one transaction.
+    pub creation_statements: Vec<String>,
+    /// Statements that remove the credential; defaults to dropping the role.
+    #[serde(default)]
+    pub revocation_statements: Vec<String>,
+}
+
+/// Non-secret connection details handed out with a credential.
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    pub host: String,
+    pub port: u16,
+    pub database: Option<String>,
+}
+
+impl PostgresDynamicConfig {
+    pub(crate) fn validate(&self) -> Result<(), &'static str> {
+        let url = self.connection_url.trim();
+        if !(url.starts_with("postgres://") || url.starts_with("postgresql://")) {
+            return Err("invalid_connection_url");
+        }
+        PgConnectOptions::from_str(url).map_err(|_| "invalid_connection_url")?;
+        if self.creation_statements.is_empty()
+            || self
+                .creation_statements
+                .iter()
+                .any(|statement| statement.trim().is_empty())
+            || !self
+                .creation_statements
+                .iter()
+                .any(|statement| statement.contains("{{name}}"))
+        {
+            return Err("invalid_creation_statements");
+        }
+        if self
+            .revocation_statements
+            .iter()
+            .any(|statement| statement.trim().is_empty())
+        {
+            return Err("invalid_revocation_statements");
+        }
+        Ok(())
+    }
+
+    pub(crate) fn connection_info(&self) -> Option<ConnectionInfo> {
+        let options = PgConnectOptions::from_str(self.connection_url.trim()).ok()?;
+        Some(ConnectionInfo {
+            host: options.get_host().to_string(),
+            port: options.get_port(),
+            database: options.get_database().map(str::to_string),
+        })
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        match self.connection_info() {
+            Some(info) => format!(
+                "postgres://{}:{}/{}",
+                info.host,
+                info.port,
+                info.database.unwrap_or_default()
+            ),
+            None => "postgres".to_string(),
+        }
+    }
+
+    /// Runs the creation statements for `username`.
+    pub(crate) async fn create(
+        &self,
+        username: &str,
+        password: &str,
+        expires_at: DateTime<Utc>,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let expiration = expires_at.format("%Y-%m-%d %H:%M:%S+00").to_string();
+        let statements = self
+            .creation_statements
+            .iter()
+            .map(|statement| render(statement, username, password, &expiration))
+            .collect::<Vec<_>>();
+        tokio::time::timeout(timeout, self.run(&statements))
+            .await
+            .map_err(|_| "timed out".to_string())?
+    }
+
+    /// Runs the revocation statements for `username`.
+    pub(crate) async fn revoke(&self, username: &str, timeout: Duration) -> Result<(), String> {
+        let statements = if self.revocation_statements.is_empty() {
+            vec![render(DEFAULT_REVOCATION, username, "", "")]
+        } else {
+            self.revocation_statements
+                .iter()
+                .map(|statement| render(statement, username, "", ""))
+                .collect()
+        };
+        tokio::time::timeout(timeout, self.run(&statements))
+            .await
+            .map_err(|_| "timed out".to_string())?
+    }
+
+    async fn run(&self, statements: &[String]) -> Result<(), String> {
+        let options = PgConnectOptions::from_str(self.connection_url.trim())
+            .map_err(|_| "invalid_connection_url".to_string())?;
+        let mut conn = PgConnection::connect_with(&options)
+            .await
+            .map_err(|err| format!("connect failed: {err}"))?;
+        let result = run_in_transaction(&mut conn, statements).await;
+        let _ = conn.close().await;
+        result
+    }
+}
+
+async fn run_in_transaction(conn: &mut PgConnection, statements: &[String]) -> Result<(), String> {
+    conn.execute("BEGIN")
+        .await
+        .map_err(|err| format!("begin failed: {err}"))?;
+    for statement in statements {
+        if let Err(err) = conn.execute(statement.as_str()).await {
+            let _ = conn.execute("ROLLBACK").await;
+            return Err(format!("statement failed: {err}"));
+        }
+    }
+    conn.execute("COMMIT")
+        .await
+        .map(|_| ())
+        .map_err(|err| format!("commit failed: {err}"))
+}
+
+/// Substitutes template placeholders. Generated names and passwords only use
+/// `[a-z0-9_]` and `[A-Za-z0-9]`, so they are safe inside quoted identifiers and
+/// string literals.
+pub(crate) fn render(template: &str, name: &str, password: &str, expiration: &str) -> String {
+    template
+        .replace("{{name}}", name)
+        .replace("{{password}}", password)
+        .replace("{{expiration}}", expiration)
+}
+
+/// Builds a role name like `v_billing_ci_x1y2z3...` from the secret path.
+pub(crate) fn generate_username(path: &str) -> String {
+    let slug: String = path
+        .trim_matches('/')
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() {
+                ch.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .take(USERNAME_SLUG_LEN)
+        .collect();
+    let suffix: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(USERNAME_SUFFIX_LEN)
+        .map(|ch| char::from(ch).to_ascii_lowercase())
+        .collect();
+    let mut name = format!("{USERNAME_PREFIX}{slug}_{suffix}");
+    name.truncate(MAX_ROLE_LEN);
+    name
+}
+
+pub(crate) fn generate_password() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(PASSWORD_LEN)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_substitutes_placeholders() {
+        let statement = render(
+            "CREATE ROLE \"{{name}}\" LOGIN PASSWORD '{{password}}' VALID UNTIL '{{expiration}}'",
+            "v_app_abc",
+            "pw",
+            "2030-01-01 00:00:00+00",
+        );
+        assert_eq!(
+            statement,
+            "CREATE ROLE \"v_app_abc\" LOGIN PASSWORD 'pw' VALID UNTIL '2030-01-01 00:00:00+00'"
+        );
+    }
+
+    #[test]
+    fn generated_usernames_are_safe_identifiers() {
+        let name = generate_username("/Billing/CI-Readonly");
+        assert!(name.starts_with("v_billing_ci_readonly_"));
+        assert!(name.len() <= MAX_ROLE_LEN);
+        assert!(name
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_'));
+        assert_ne!(name, generate_username("/Billing/CI-Readonly"));
+        assert_eq!(generate_password().len(), PASSWORD_LEN);
+    }
+
+    #[test]
+    fn validate_requires_name_placeholder() {
+        let mut config = PostgresDynamicConfig {
+            connection_url: "postgres://admin:pw@db.internal:5432/app".to_string(),
+            creation_statements: vec!["CREATE ROLE \"{{name}}\" LOGIN".to_string()],
+            revocation_statements: Vec::new(),
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.describe(), "postgres://db.internal:5432/app");
+        config.creation_statements = vec!["SELECT 1".to_string()];
+        assert_eq!(config.validate(), Err("invalid_creation_statements"));
+        config.creation_statements = vec!["CREATE ROLE \"{{name}}\"".to_string()];
+        config.connection_url = "mysql://db".to_string();
+        assert_eq!(config.validate(), Err("invalid_connection_url"));
+    }
+}
@@ -0,0 +1,457 @@
+//! Dynamic secrets issue short-lived database credentials on demand. A secret
+//! lives at a vault path and holds the connection settings; each issue creates
+//! a lease that is revoked on request or by the reaper once it expires.
+
+use std::time::Duration;
+
+use chrono::{Duration as ChronoDuration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zann_core::{DynamicLease, DynamicSecret, Identity, Vault};
+use zann_crypto::vault_crypto as core_crypto;
+use zann_db::repo::{DynamicLeaseRepo, DynamicSecretRepo, VaultRepo};
+
+use crate::app::AppState;
+use crate::domains::access_control::http::VaultScope;
+use crate::domains::dynamic::postgres::{self, ConnectionInfo, PostgresDynamicConfig};
+use crate::domains::errors::ServiceError;
+use crate::domains::secrets::service::{
+    authorize_vault_access, ensure_server_encryption, normalize_secret_path, server_vault_key,
+};
+use crate::domains::service_accounts::tokens::parse_ttl;
+use crate::domains::vaults::service::get_vault;
+use crate::infra::audit::{self, AuditEntry};
+use crate::infra::metrics;
+
+pub type DynamicError = ServiceError;
+
+const DEFAULT_TTL_SECONDS: i64 = 60 * 60;
+const DEFAULT_MAX_TTL_SECONDS: i64 = 24 * 60 * 60;
+const MIN_TTL_SECONDS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DynamicSecretConfig {
+    Postgres(PostgresDynamicConfig),
+}
+
+impl DynamicSecretConfig {
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Postgres(_) => "postgres",
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            Self::Postgres(config) => config.validate(),
+        }
+    }
+
+    /// Target description without credentials, for API responses.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Postgres(config) => config.describe(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetDynamicSecretCommand {
+    pub config: DynamicSecretConfig,
+    pub default_ttl: Option<String>,
+    pub max_ttl: Option<String>,
+}
+
+/// A freshly issued credential. The password is only ever returned here.
+#[derive(Debug, Clone)]
+pub struct IssuedCredential {
+    pub lease: DynamicLease,
+    pub password: String,
+    pub connection: Option<ConnectionInfo>,
+}
+
+fn resource(vault_id: &str, path: &str) -> String {
+    format!("vaults/{vault_id}/dynamic{path}")
+}
+
+pub async fn get_dynamic_secret(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    path: &str,
+) -> Result<(DynamicSecret, DynamicSecretConfig), DynamicError> {
+    let path = normalize_secret_path(path)?;
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "read",
+        &resource(vault_id, &path),
+        &path,
+        VaultScope::Items,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    let secret = load_secret(state, &vault, &path).await?;
+    let config = decrypt_config(state, &vault, &secret)?;
+    Ok((secret, config))
+}
+
+pub async fn set_dynamic_secret(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    path: &str,
+    cmd: SetDynamicSecretCommand,
+) -> Result<DynamicSecret, DynamicError> {
+    if identity.service_account_id.is_some() {
+        return Err(DynamicError::ForbiddenNoBody);
+    }
+    let path = normalize_secret_path(path)?;
+    // The config carries credentials for the target database, so only vault admins manage it.
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "dynamic_configure",
+        &resource(vault_id, &path),
+        &path,
+        VaultScope::Items,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    cmd.config.validate().map_err(DynamicError::BadRequest)?;
+    let default_ttl_seconds = match cmd.default_ttl.as_deref() {
+        Some(value) => parse_ttl_seconds(value)?,
+        None => DEFAULT_TTL_SECONDS,
+    };
+    let max_ttl_seconds = match cmd.max_ttl.as_deref() {
+        Some(value) => parse_ttl_seconds(value)?,
+        None => DEFAULT_MAX_TTL_SECONDS.max(default_ttl_seconds),
+    };
+    if default_ttl_seconds > max_ttl_seconds {
+        return Err(DynamicError::BadRequest("default_ttl_exceeds_max"));
+    }
+
+    let repo = DynamicSecretRepo::new(&state.db);
+    // Keep the id of an existing secret: it is bound into the config ciphertext.
+    let existing = repo.get_by_path(vault.id, &path).await.map_err(|err| {
+        tracing::error!(event = "dynamic_secret_set_failed", error = %err, "DB error");
+        DynamicError::DbError
+    })?;
+    let now = Utc::now();
+    let id = existing
+        .as_ref()
+        .map_or_else(Uuid::now_v7, |secret| secret.id);
+    let vault_key = server_vault_key(state, &vault)?;
+    let config_bytes =
+        serde_json::to_vec(&cmd.config).map_err(|_| DynamicError::Internal("encrypt_failed"))?;
+    let config_enc = core_crypto::encrypt_dynamic_secret(&vault_key, vault.id, id, &config_bytes)
+        .map_err(|_| DynamicError::Internal("encrypt_failed"))?;
+    let secret = DynamicSecret {
+        id,
+        vault_id: vault.id,
+        path,
+        kind: cmd.config.kind().to_string(),
+        config_enc,
+        default_ttl_seconds,
+        max_ttl_seconds,
+        created_at: existing.map_or(now, |secret| secret.created_at),
+        updated_at: now,
+    };
+    repo.upsert(&secret).await.map_err(|err| {
+        tracing::error!(event = "dynamic_secret_set_failed", error = %err, "DB error");
+        DynamicError::DbError
+    })
+}
+
+pub async fn delete_dynamic_secret(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    path: &str,
+) -> Result<(), DynamicError> {
+    if identity.service_account_id.is_some() {
+        return Err(DynamicError::ForbiddenNoBody);
+    }
+    let path = normalize_secret_path(path)?;
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "dynamic_configure",
+        &resource(vault_id, &path),
+        &path,
+        VaultScope::Items,
+    )
+    .await?;
+    let secret = load_secret(state, &vault, &path).await?;
+    // Deleting the secret would lose the connection needed to revoke live credentials.
+    let active = DynamicLeaseRepo::new(&state.db)
+        .count_active_by_secret(secret.id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "dynamic_secret_delete_failed", error = %err, "DB error");
+            DynamicError::DbError
+        })?;
+    if active > 0 {
+        return Err(DynamicError::Conflict("leases_active"));
+    }
+    DynamicSecretRepo::new(&state.db)
+        .delete(secret.id)
+        .await
+        .map(|_| ())
+        .map_err(|err| {
+            tracing::error!(event = "dynamic_secret_delete_failed", error = %err, "DB error");
+            DynamicError::DbError
+        })
+}
+
+pub async fn issue_credential(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    path: &str,
+    ttl: Option<&str>,
+) -> Result<IssuedCredential, DynamicError> {
+    let path = normalize_secret_path(path)?;
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "read",
+        &resource(vault_id, &path),
+        &path,
+        VaultScope::Items,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    let secret = load_secret(state, &vault, &path).await?;
+    let config = decrypt_config(state, &vault, &secret)?;
+    let ttl_seconds = match ttl {
+        Some(value) => parse_ttl_seconds(value)?.min(secret.max_ttl_seconds),
+        None => secret.default_ttl_seconds,
+    };
+
+    let now = Utc::now();
+    let lease = DynamicLease {
+        id: Uuid::now_v7(),
+        secret_id: secret.id,
+        vault_id: vault.id,
+        username: postgres::generate_username(&secret.path),
+        issued_to_user_id: Some(identity.user_id),
+        issued_to_service_account_id: identity.service_account_id,
+        issued_at: now,
+        expires_at: now + ChronoDuration::seconds(ttl_seconds),
+        revoked_at: None,
+        last_error: None,
+    };
+    let password = postgres::generate_password();
+    // Record the lease first so the reaper can clean up a partially created role.
+    let leases = DynamicLeaseRepo::new(&state.db);
+    leases.create(&lease).await.map_err(|err| {
+        tracing::error!(event = "dynamic_issue_failed", error = %err, "DB error");
+        DynamicError::DbError
+    })?;
+
+    let timeout = statement_timeout(state);
+    let DynamicSecretConfig::Postgres(postgres_config) = &config;
+    if let Err(err) = postgres_config
+        .create(&lease.username, &password, lease.expires_at, timeout)
+        .await
+    {
+        metrics::dynamic_credentials("issue", "error");
+        tracing::warn!(
+            event = "dynamic_issue_failed",
+            secret_id = %secret.id,
+            error = %err,
+            "Creating dynamic credential failed"
+        );
+        let _ = leases.mark_error(lease.id, &err).await;
+        return Err(DynamicError::Internal("dynamic_issue_failed"));
+    }
+    metrics::dynamic_credentials("issue", "ok");
+    tracing::info!(
+        event = "dynamic_credential_issued",
+        lease_id = %lease.id,
+        secret_id = %secret.id,
+        ttl_seconds
+    );
+    Ok(IssuedCredential {
+        connection: postgres_config.connection_info(),
+        lease,
+        password,
+    })
+}
+
+/// Holders may revoke their own leases while they can still read the secret;
+/// anyone else needs `manage_leases`. Access to the vault is checked before
+/// the lease is looked up, so lease ids cannot be probed from outside it.
+pub async fn revoke_lease(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    lease_id: Uuid,
+) -> Result<(DynamicLease, String), DynamicError> {
+    let vault = get_vault(state, identity, vault_id).await?;
+    let lease = match DynamicLeaseRepo::new(&state.db).get_by_id(lease_id).await {
+        Ok(Some(lease)) if lease.vault_id == vault.id => lease,
+        Ok(_) => return Err(DynamicError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "dynamic_revoke_failed", error = %err, "DB error");
+            return Err(DynamicError::DbError);
+        }
+    };
+    let secret = match DynamicSecretRepo::new(&state.db)
+        .get_by_id(lease.secret_id)
+        .await
+    {
+        Ok(Some(secret)) => secret,
+        Ok(None) => return Err(DynamicError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "dynamic_revoke_failed", error = %err, "DB error");
+            return Err(DynamicError::DbError);
+        }
+    };
+    let is_holder = lease.issued_to_user_id == Some(identity.user_id)
+        && lease.issued_to_service_account_id == identity.service_account_id;
+    let action = if is_holder { "read" } else { "manage_leases" };
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        action,
+        &resource(vault_id, &secret.path),
+        &secret.path,
+        VaultScope::Items,
+    )
+    .await?;
+    if lease.revoked_at.is_some() {
+        return Ok((lease, secret.path));
+    }
+    ensure_server_encryption(state, &vault)?;
+    let config = decrypt_config(state, &vault, &secret)?;
+    revoke(state, &config, &lease)
+        .await
+        .map_err(|_| DynamicError::Internal("dynamic_revoke_failed"))?;
+    Ok((lease, secret.path))
+}
+
+/// One reaper pass: revokes expired leases. Failures are recorded on the lease
+/// and retried on the next pass. Does nothing while the master key is unavailable.
+pub async fn reap_expired_leases(state: &AppState) -> Result<usize, sqlx_core::Error> {
+    if state.server_master_key.get().is_none() {
+        return Ok(0);
+    }
+    let leases = DynamicLeaseRepo::new(&state.db)
+        .list_expired(Utc::now(), state.config.dynamic.reaper_batch_size.max(1))
+        .await?;
+    let mut revoked = 0;
+    for lease in leases {
+        let Some(secret) = DynamicSecretRepo::new(&state.db)
+            .get_by_id(lease.secret_id)
+            .await?
+        else {
+            continue;
+        };
+        let Some(vault) = VaultRepo::new(&state.db).get_by_id(secret.vault_id).await? else {
+            continue;
+        };
+        let config = match decrypt_config(state, &vault, &secret) {
+            Ok(config) => config,
+            Err(err) => {
+                DynamicLeaseRepo::new(&state.db)
+                    .mark_error(lease.id, err.label())
+                    .await?;
+                continue;
+            }
+        };
+        if revoke(state, &config, &lease).await.is_ok() {
+            revoked += 1;
+            let entry = AuditEntry::new("secrets", "dynamic_revoke", "ok")
+                .vault(vault.id)
+                .path(&secret.path)
+                .target(lease.id)
+                .detail(Some("expired"));
+            audit::record(state, None, entry).await;
+        }
+    }
+    Ok(revoked)
+}
+
+async fn revoke(
+    state: &AppState,
+    config: &DynamicSecretConfig,
+    lease: &DynamicLease,
+) -> Result<(), String> {
+    let leases = DynamicLeaseRepo::new(&state.db);
+    let DynamicSecretConfig::Postgres(postgres_config) = config;
+    if let Err(err) = postgres_config
+        .revoke(&lease.username, statement_timeout(state))
+        .await
+    {
+        metrics::dynamic_credentials("revoke", "error");
+        tracing::warn!(
+            event = "dynamic_revoke_failed",
+            lease_id = %lease.id,
+            error = %err,
+            "Revoking dynamic credential failed"
+        );
+        let _ = leases.mark_error(lease.id, &err).await;
+        return Err(err);
+    }
+    leases
+        .mark_revoked(lease.id, Utc::now())
+        .await
+        .map_err(|err| err.to_string())?;
+    metrics::dynamic_credentials("revoke", "ok");
+    Ok(())
+}
+
+async fn load_secret(
+    state: &AppState,
+    vault: &Vault,
+    path: &str,
+) -> Result<DynamicSecret, DynamicError> {
+    match DynamicSecretRepo::new(&state.db)
+        .get_by_path(vault.id, path)
+        .await
+    {
+        Ok(Some(secret)) => Ok(secret),
+        Ok(None) => Err(DynamicError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "dynamic_secret_load_failed", error = %err, "DB error");
+            Err(DynamicError::DbError)
+        }
+    }
+}
+
+fn parse_ttl_seconds(value: &str) -> Result<i64, DynamicError> {
+    let seconds = parse_ttl(value)
+        .map_err(|_| DynamicError::BadRequest("invalid_ttl"))?
+        .num_seconds();
+    if seconds < MIN_TTL_SECONDS {
+        return Err(DynamicError::BadRequest("ttl_too_short"));
+    }
+    Ok(seconds)
+}
+
+fn statement_timeout(state: &AppState) -> Duration {
+    Duration::from_secs(state.config.dynamic.statement_timeout_seconds.max(1))
+}
+
+fn decrypt_config(
+    state: &AppState,
+    vault: &Vault,
+    secret: &DynamicSecret,
+) -> Result<DynamicSecretConfig, DynamicError> {
+    let vault_key = server_vault_key(state, vault)?;
+    let bytes =
+        core_crypto::decrypt_dynamic_secret(&vault_key, vault.id, secret.id, &secret.config_enc)
+            .map_err(|_| DynamicError::Internal("decrypt_failed"))?;
+    serde_json::from_slice(&bytes).map_err(|_| DynamicError::Internal("decrypt_failed"))
+}
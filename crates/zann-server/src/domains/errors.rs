@@ -33,3 +33,25 @@ pub enum ServiceError {
     #[error("policy_mismatch")]
     PolicyMismatch { existing: String, requested: String },
 }
+
+impl ServiceError {
+    /// Stable, low-cardinality label used for metrics and audit results.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ForbiddenNoBody | Self::Forbidden(_) => "forbidden",
+            Self::NotFound => "not_found",
+            Self::BadRequest(_) => "bad_request",
+            Self::Conflict(_) => "conflict",
+            Self::Unauthorized(_) => "unauthorized",
+            Self::PolicyMismatch { .. } => "policy_mismatch",
+            Self::PayloadTooLarge(_) => "payload_too_large",
+            Self::DbError => "db_error",
+            Self::Internal(_) => "internal",
+            Self::NoChanges => "no_changes",
+            Self::InvalidPassword => "invalid_password",
+            Self::InvalidCredentials => "invalid_credentials",
+            Self::Kdf => "kdf_error",
+            Self::DeviceRequired => "device_required",
+        }
+    }
+}
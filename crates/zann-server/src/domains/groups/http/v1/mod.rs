@@ -8,11 +8,13 @@ use axum::{
 use chrono::Utc;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sqlx_postgres::PgConnection;
 use uuid::Uuid;
 use zann_core::{Group, GroupMember, Identity};
 use zann_db::repo::{GroupMemberRepo, GroupRepo, UserRepo};
 
 use crate::app::AppState;
+use crate::infra::audit::{self, AuditEntry, PendingAudit};
 use crate::infra::metrics;
 
 #[derive(Serialize, JsonSchema)]
@@ -138,6 +140,7 @@ async fn create_group(
             resource = resource,
             "Access denied"
         );
+        audit_group(&state, &identity, "create", "forbidden", &payload.slug).await;
         return StatusCode::FORBIDDEN.into_response();
     }
 
@@ -196,7 +199,16 @@ async fn create_group(
         name: name.to_string(),
         created_at: Utc::now(),
     };
-    if let Err(err) = repo.create(&group).await {
+    let write = async {
+        let mut tx = state.db.begin().await?;
+        GroupRepo::create_in(&mut tx, &group).await?;
+        let audited = audit_group_tx(&mut tx, &identity, "create", &group.slug).await?;
+        tx.commit().await?;
+        audited.dispatch();
+        Ok::<_, sqlx_core::Error>(())
+    }
+    .await;
+    if let Err(err) = write {
         tracing::error!(event = "group_create_failed", error = %err, "DB error");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -268,6 +280,7 @@ async fn update_group(
             resource = %resource,
             "Access denied"
         );
+        audit_group(&state, &identity, "update", "forbidden", &slug).await;
         return StatusCode::FORBIDDEN.into_response();
     }
 
@@ -349,7 +362,18 @@ async fn update_group(
             .into_response();
     }
 
-    let Ok(affected) = repo.update(group.id, &group.slug, &group.name).await else {
+    let write = async {
+        let mut tx = state.db.begin().await?;
+        let affected = GroupRepo::update_in(&mut tx, group.id, &group.slug, &group.name).await?;
+        if affected > 0 {
+            let audited = audit_group_tx(&mut tx, &identity, "update", &group.slug).await?;
+            tx.commit().await?;
+            audited.dispatch();
+        }
+        Ok::<_, sqlx_core::Error>(affected)
+    }
+    .await;
+    let Ok(affected) = write else {
         tracing::error!(event = "group_update_failed", "DB error");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -385,6 +409,7 @@ async fn delete_group(
             resource = %resource,
             "Access denied"
         );
+        audit_group(&state, &identity, "delete", "forbidden", &slug).await;
         return StatusCode::FORBIDDEN.into_response();
     }
 
@@ -402,7 +427,18 @@ async fn delete_group(
         }
     };
 
-    let Ok(affected) = repo.delete_by_id(group.id).await else {
+    let write = async {
+        let mut tx = state.db.begin().await?;
+        let affected = GroupRepo::delete_by_id_in(&mut tx, group.id).await?;
+        if affected > 0 {
+            let audited = audit_group_tx(&mut tx, &identity, "delete", &slug).await?;
+            tx.commit().await?;
+            audited.dispatch();
+        }
+        Ok::<_, sqlx_core::Error>(affected)
+    }
+    .await;
+    let Ok(affected) = write else {
         tracing::error!(event = "group_delete_failed", "DB error");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -439,6 +475,7 @@ async fn add_member(
             resource = %resource,
             "Access denied"
         );
+        audit_group(&state, &identity, "add_member", "forbidden", &slug).await;
         return StatusCode::FORBIDDEN.into_response();
     }
 
@@ -497,7 +534,17 @@ async fn add_member(
         user_id: payload.user_id,
         created_at: Utc::now(),
     };
-    if let Err(err) = member_repo.create(&member).await {
+    let target = format!("{slug}/{}", payload.user_id);
+    let write = async {
+        let mut tx = state.db.begin().await?;
+        GroupMemberRepo::create_in(&mut tx, &member).await?;
+        let audited = audit_group_tx(&mut tx, &identity, "add_member", &target).await?;
+        tx.commit().await?;
+        audited.dispatch();
+        Ok::<_, sqlx_core::Error>(())
+    }
+    .await;
+    if let Err(err) = write {
         tracing::error!(event = "group_member_add_failed", error = %err, "DB error");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -531,6 +578,7 @@ async fn remove_member(
             resource = %resource,
             "Access denied"
         );
+        audit_group(&state, &identity, "remove_member", "forbidden", &slug).await;
         return StatusCode::FORBIDDEN.into_response();
     }
 
@@ -548,8 +596,19 @@ async fn remove_member(
         }
     };
 
-    let member_repo = GroupMemberRepo::new(&state.db);
-    let Ok(affected) = member_repo.delete(group.id, user_id).await else {
+    let target = format!("{slug}/{user_id}");
+    let write = async {
+        let mut tx = state.db.begin().await?;
+        let affected = GroupMemberRepo::delete_in(&mut tx, group.id, user_id).await?;
+        if affected > 0 {
+            let audited = audit_group_tx(&mut tx, &identity, "remove_member", &target).await?;
+            tx.commit().await?;
+            audited.dispatch();
+        }
+        Ok::<_, sqlx_core::Error>(affected)
+    }
+    .await;
+    let Ok(affected) = write else {
         tracing::error!(event = "group_member_remove_failed", "DB error");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -586,3 +645,25 @@ fn group_member_response(member: GroupMember) -> GroupMemberResponse {
         created_at: member.created_at.to_rfc3339(),
     }
 }
+
+async fn audit_group(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    result: &str,
+    target: &str,
+) {
+    let entry = AuditEntry::new("groups", action, result).target(target);
+    audit::record(state, Some(identity), entry).await;
+}
+
+/// Records a successful group change inside the change's transaction.
+async fn audit_group_tx(
+    conn: &mut PgConnection,
+    identity: &Identity,
+    action: &str,
+    target: &str,
+) -> Result<PendingAudit, sqlx_core::Error> {
+    let entry = AuditEntry::new("groups", action, "ok").target(target);
+    audit::record_tx(conn, Some(identity), entry).await
+}
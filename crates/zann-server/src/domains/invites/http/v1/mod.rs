@@ -0,0 +1,238 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get},
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zann_core::{Identity, Invite};
+
+use crate::app::AppState;
+use crate::domains::errors::ServiceError;
+use crate::domains::invites::service::{
+    self, CreateInviteCommand, InviteServiceError, InviteStatus, InviteVaultCommand, IssuedInvite,
+    ListInvitesCommand,
+};
+use crate::infra::audit::{self, AuditEntry};
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    error: &'static str,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct ListInvitesQuery {
+    #[serde(default)]
+    sort: Option<String>,
+    #[serde(default)]
+    limit: Option<i64>,
+    #[serde(default)]
+    offset: Option<i64>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct InviteVaultRoleRequest {
+    /// Vault id or slug.
+    vault: String,
+    /// One of `admin`, `operator`, `member`, `readonly`.
+    role: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct CreateInviteRequest {
+    /// The invitee must register with this email.
+    email: String,
+    /// Lifetime such as `48h` or `7d` (the default); at most `30d`.
+    #[serde(default)]
+    ttl: Option<String>,
+    /// Group slugs the user joins on registration.
+    #[serde(default)]
+    groups: Vec<String>,
+    /// Vault roles the user receives on registration.
+    #[serde(default)]
+    vaults: Vec<InviteVaultRoleRequest>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct InviteVaultRoleResponse {
+    pub(crate) vault_id: String,
+    pub(crate) role: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct InviteResponse {
+    pub(crate) id: String,
+    pub(crate) email: String,
+    /// One of `pending`, `accepted`, `revoked`, `expired`.
+    pub(crate) status: &'static str,
+    pub(crate) group_ids: Vec<String>,
+    pub(crate) vaults: Vec<InviteVaultRoleResponse>,
+    pub(crate) created_by_user_id: Option<String>,
+    pub(crate) expires_at: String,
+    pub(crate) created_at: String,
+    pub(crate) accepted_at: Option<String>,
+    pub(crate) accepted_user_id: Option<String>,
+    pub(crate) revoked_at: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct InviteListResponse {
+    pub(crate) invites: Vec<InviteResponse>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct InviteTokenResponse {
+    /// Plaintext token for `invite_token` on registration. It is shown once.
+    pub(crate) token: String,
+    pub(crate) invite: InviteResponse,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/v1/admin/invites", get(list_invites).post(create_invite))
+        .route("/v1/admin/invites/:id", delete(revoke_invite))
+}
+
+fn map_invite_error(error: InviteServiceError) -> axum::response::Response {
+    match error {
+        InviteServiceError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        InviteServiceError::Forbidden(code) => {
+            (StatusCode::FORBIDDEN, Json(ErrorResponse { error: code })).into_response()
+        }
+        InviteServiceError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        InviteServiceError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        InviteServiceError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        InviteServiceError::DbError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response(),
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, query))]
+async fn list_invites(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<ListInvitesQuery>,
+) -> impl IntoResponse {
+    let command = ListInvitesCommand {
+        sort: query.sort,
+        limit: query.limit,
+        offset: query.offset,
+    };
+    match service::list_invites(&state, &identity, command).await {
+        Ok(invites) => {
+            let invites = invites.into_iter().map(invite_response).collect();
+            (StatusCode::OK, Json(InviteListResponse { invites })).into_response()
+        }
+        Err(err) => map_invite_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn create_invite(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Json(payload): Json<CreateInviteRequest>,
+) -> impl IntoResponse {
+    let command = CreateInviteCommand {
+        email: payload.email,
+        ttl: payload.ttl,
+        groups: payload.groups,
+        vaults: payload
+            .vaults
+            .into_iter()
+            .map(|entry| InviteVaultCommand {
+                vault: entry.vault,
+                role: entry.role,
+            })
+            .collect(),
+    };
+    let email = command.email.trim().to_string();
+    let result = service::create_invite(&state, &identity, command).await;
+    let target = result
+        .as_ref()
+        .map_or(String::new(), |issued| issued.invite.id.to_string());
+    audit_invite(&state, &identity, "create", &result, &target, Some(&email)).await;
+    match result {
+        Ok(issued) => (StatusCode::CREATED, Json(token_response(issued))).into_response(),
+        Err(err) => map_invite_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn revoke_invite(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    let result = service::revoke_invite(&state, &identity, &id).await;
+    let email = result.as_ref().ok().map(|invite| invite.email.clone());
+    audit_invite(&state, &identity, "revoke", &result, &id, email.as_deref()).await;
+    match result {
+        Ok(invite) => (StatusCode::OK, Json(invite_response(invite))).into_response(),
+        Err(err) => map_invite_error(err),
+    }
+}
+
+pub(crate) fn invite_response(invite: Invite) -> InviteResponse {
+    InviteResponse {
+        id: invite.id.to_string(),
+        status: InviteStatus::of(&invite, Utc::now()).as_str(),
+        email: invite.email,
+        group_ids: invite.group_ids.0.iter().map(ToString::to_string).collect(),
+        vaults: invite
+            .vault_roles
+            .0
+            .iter()
+            .map(|grant| InviteVaultRoleResponse {
+                vault_id: grant.vault_id.to_string(),
+                role: grant.role.as_str().to_string(),
+            })
+            .collect(),
+        created_by_user_id: invite.created_by_user_id.map(|id| id.to_string()),
+        expires_at: invite.expires_at.to_rfc3339(),
+        created_at: invite.created_at.to_rfc3339(),
+        accepted_at: invite.accepted_at.map(|value| value.to_rfc3339()),
+        accepted_user_id: invite.accepted_user_id.map(|id| id.to_string()),
+        revoked_at: invite.revoked_at.map(|value| value.to_rfc3339()),
+    }
+}
+
+fn token_response(issued: IssuedInvite) -> InviteTokenResponse {
+    InviteTokenResponse {
+        token: issued.token,
+        invite: invite_response(issued.invite),
+    }
+}
+
+async fn audit_invite<T>(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    result: &Result<T, ServiceError>,
+    target: &str,
+    email: Option<&str>,
+) {
+    let label = result.as_ref().err().map_or("ok", ServiceError::label);
+    let detail = email.map(|email| format!("email={email}"));
+    let entry = AuditEntry::new("invites", action, label)
+        .target(target)
+        .detail(detail.as_deref());
+    audit::record(state, Some(identity), entry).await;
+}
@@ -0,0 +1,369 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use sqlx_core::query::query;
+use sqlx_core::types::Json;
+use sqlx_postgres::{PgConnection, Postgres};
+use uuid::Uuid;
+use zann_core::{Identity, Invite, InviteVaultRole};
+use zann_db::repo::{GroupRepo, InviteRepo, UserRepo, VaultRepo};
+
+use crate::app::AppState;
+use crate::domains::access_control::http::find_vault;
+use crate::domains::auth::core::tokens::hash_token;
+use crate::domains::errors::ServiceError;
+use crate::domains::members::service::{ensure_can_manage_members, parse_role};
+use crate::domains::service_accounts::tokens::parse_ttl;
+use crate::infra::metrics;
+
+pub const INVITE_TOKEN_PREFIX: &str = "zann_inv_";
+const DEFAULT_INVITE_TTL: &str = "7d";
+const MAX_INVITE_TTL_DAYS: i64 = 30;
+
+pub type InviteServiceError = ServiceError;
+
+pub struct CreateInviteCommand {
+    pub email: String,
+    pub ttl: Option<String>,
+    pub groups: Vec<String>,
+    pub vaults: Vec<InviteVaultCommand>,
+}
+
+pub struct InviteVaultCommand {
+    pub vault: String,
+    pub role: String,
+}
+
+pub struct ListInvitesCommand {
+    pub sort: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// A new invite together with the plaintext token, which is only returned
+/// once, on create.
+pub struct IssuedInvite {
+    pub invite: Invite,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InviteStatus {
+    Pending,
+    Accepted,
+    Revoked,
+    Expired,
+}
+
+impl InviteStatus {
+    pub fn of(invite: &Invite, now: DateTime<Utc>) -> Self {
+        if invite.accepted_at.is_some() {
+            Self::Accepted
+        } else if invite.revoked_at.is_some() {
+            Self::Revoked
+        } else if invite.expires_at <= now {
+            Self::Expired
+        } else {
+            Self::Pending
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Accepted => "accepted",
+            Self::Revoked => "revoked",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+fn forbidden(resource: &str, action: &str, reason: &str) -> InviteServiceError {
+    metrics::forbidden_access(resource);
+    tracing::warn!(
+        event = "forbidden",
+        action = action,
+        resource = %resource,
+        reason = reason,
+        "Access denied"
+    );
+    InviteServiceError::ForbiddenNoBody
+}
+
+fn ensure_policy(
+    state: &AppState,
+    identity: &Identity,
+    resource: &str,
+    action: &str,
+) -> Result<(), InviteServiceError> {
+    if identity.service_account_id.is_some() {
+        return Err(forbidden(resource, action, "service_account"));
+    }
+    let policies = state.policy_store.get();
+    if !policies.is_allowed(identity, action, resource) {
+        return Err(forbidden(resource, action, "policy"));
+    }
+    Ok(())
+}
+
+fn db_error(event: &'static str) -> impl Fn(sqlx_core::Error) -> InviteServiceError {
+    move |err| {
+        tracing::error!(event = event, error = %err, "DB error");
+        InviteServiceError::DbError
+    }
+}
+
+fn normalize_email(value: &str) -> Result<String, InviteServiceError> {
+    let email = value.trim();
+    match email.split_once('@') {
+        Some((local, domain)) if !local.is_empty() && !domain.is_empty() => Ok(email.to_string()),
+        _ => Err(InviteServiceError::BadRequest("invalid_email")),
+    }
+}
+
+fn invite_ttl(value: Option<&str>) -> Result<ChronoDuration, InviteServiceError> {
+    let ttl = parse_ttl(value.unwrap_or(DEFAULT_INVITE_TTL))
+        .map_err(|_| InviteServiceError::BadRequest("invalid_ttl"))?;
+    if ttl <= ChronoDuration::zero() || ttl > ChronoDuration::days(MAX_INVITE_TTL_DAYS) {
+        return Err(InviteServiceError::BadRequest("invalid_ttl"));
+    }
+    Ok(ttl)
+}
+
+fn issue_token() -> String {
+    let suffix: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+    format!("{INVITE_TOKEN_PREFIX}{suffix}")
+}
+
+/// Resolves pre-assigned groups by slug. Handing out group membership needs
+/// the same grant as adding a member to the group directly.
+async fn resolve_groups(
+    state: &AppState,
+    identity: &Identity,
+    slugs: &[String],
+) -> Result<Vec<Uuid>, InviteServiceError> {
+    let policies = state.policy_store.get();
+    let repo = GroupRepo::new(&state.db);
+    let mut group_ids = Vec::with_capacity(slugs.len());
+    for slug in slugs {
+        let slug = slug.trim();
+        let resource = format!("groups/{slug}/members");
+        if !policies.is_allowed(identity, "write", &resource) {
+            return Err(forbidden(&resource, "write", "policy"));
+        }
+        let group = repo
+            .get_by_slug(slug)
+            .await
+            .map_err(db_error("invite_group_lookup_failed"))?
+            .ok_or(InviteServiceError::BadRequest("unknown_group"))?;
+        if !group_ids.contains(&group.id) {
+            group_ids.push(group.id);
+        }
+    }
+    Ok(group_ids)
+}
+
+/// Resolves pre-assigned vault roles. The inviter must be able to manage the
+/// members of every vault listed.
+async fn resolve_vault_roles(
+    state: &AppState,
+    identity: &Identity,
+    vaults: &[InviteVaultCommand],
+) -> Result<Vec<InviteVaultRole>, InviteServiceError> {
+    let repo = VaultRepo::new(&state.db);
+    let mut roles: Vec<InviteVaultRole> = Vec::with_capacity(vaults.len());
+    for entry in vaults {
+        let role = parse_role(&entry.role)?;
+        let vault = find_vault(&repo, entry.vault.trim())
+            .await
+            .map_err(db_error("invite_vault_lookup_failed"))?
+            .ok_or(InviteServiceError::BadRequest("unknown_vault"))?;
+        ensure_can_manage_members(state, identity, &vault).await?;
+        if roles.iter().any(|existing| existing.vault_id == vault.id) {
+            return Err(InviteServiceError::BadRequest("duplicate_vault"));
+        }
+        roles.push(InviteVaultRole {
+            vault_id: vault.id,
+            role,
+        });
+    }
+    Ok(roles)
+}
+
+pub async fn create_invite(
+    state: &AppState,
+    identity: &Identity,
+    cmd: CreateInviteCommand,
+) -> Result<IssuedInvite, InviteServiceError> {
+    ensure_policy(state, identity, "admin/invites", "write")?;
+    let email = normalize_email(&cmd.email)?;
+    let ttl = invite_ttl(cmd.ttl.as_deref())?;
+
+    let existing = UserRepo::new(&state.db)
+        .get_by_email(&email)
+        .await
+        .map_err(db_error("invite_user_lookup_failed"))?;
+    if existing.is_some() {
+        return Err(InviteServiceError::Conflict("email_taken"));
+    }
+    let group_ids = resolve_groups(state, identity, &cmd.groups).await?;
+    let vault_roles = resolve_vault_roles(state, identity, &cmd.vaults).await?;
+
+    let now = Utc::now();
+    let token = issue_token();
+    let invite = Invite {
+        id: Uuid::now_v7(),
+        email,
+        token_hash: hash_token(&token, &state.token_pepper),
+        group_ids: Json(group_ids),
+        vault_roles: Json(vault_roles),
+        created_by_user_id: Some(identity.user_id),
+        expires_at: now + ttl,
+        created_at: now,
+        accepted_at: None,
+        accepted_user_id: None,
+        revoked_at: None,
+    };
+    InviteRepo::new(&state.db)
+        .create(&invite)
+        .await
+        .map_err(db_error("invite_create_failed"))?;
+
+    tracing::info!(
+        event = "invite_created",
+        invite_id = %invite.id,
+        expires_at = %invite.expires_at,
+        "Invite created"
+    );
+    Ok(IssuedInvite { invite, token })
+}
+
+pub async fn list_invites(
+    state: &AppState,
+    identity: &Identity,
+    cmd: ListInvitesCommand,
+) -> Result<Vec<Invite>, InviteServiceError> {
+    ensure_policy(state, identity, "admin/invites", "read")?;
+    let limit = cmd.limit.unwrap_or(50).clamp(1, 200);
+    let offset = cmd.offset.unwrap_or(0).max(0);
+    let sort = cmd.sort.as_deref().unwrap_or("desc");
+    InviteRepo::new(&state.db)
+        .list(limit, offset, sort)
+        .await
+        .map_err(db_error("invite_list_failed"))
+}
+
+pub async fn revoke_invite(
+    state: &AppState,
+    identity: &Identity,
+    id: &str,
+) -> Result<Invite, InviteServiceError> {
+    let resource = format!("admin/invites/{id}");
+    ensure_policy(state, identity, &resource, "write")?;
+    let id = Uuid::parse_str(id).map_err(|_| InviteServiceError::BadRequest("invalid_id"))?;
+
+    let repo = InviteRepo::new(&state.db);
+    let mut invite = repo
+        .get_by_id(id)
+        .await
+        .map_err(db_error("invite_revoke_failed"))?
+        .ok_or(InviteServiceError::NotFound)?;
+    let now = Utc::now();
+    match InviteStatus::of(&invite, now) {
+        InviteStatus::Accepted => return Err(InviteServiceError::Conflict("invite_accepted")),
+        InviteStatus::Revoked => return Ok(invite),
+        InviteStatus::Pending | InviteStatus::Expired => {}
+    }
+    let updated = repo
+        .revoke(id, now)
+        .await
+        .map_err(db_error("invite_revoke_failed"))?;
+    if updated == 0 {
+        // Accepted between the read and the update.
+        return Err(InviteServiceError::Conflict("invite_accepted"));
+    }
+    invite.revoked_at = Some(now);
+    tracing::info!(event = "invite_revoked", invite_id = %invite.id, "Invite revoked");
+    Ok(invite)
+}
+
+/// Looks up the invite a registration presents. Unknown, used and revoked
+/// tokens are indistinguishable to the caller; an expired one is reported as
+/// such so the invitee knows to ask for a new one.
+pub async fn find_for_registration(
+    state: &AppState,
+    token: &str,
+    email: &str,
+) -> Result<Invite, InviteServiceError> {
+    let token_hash = hash_token(token.trim(), &state.token_pepper);
+    let invite = InviteRepo::new(&state.db)
+        .get_by_token_hash(&token_hash)
+        .await
+        .map_err(db_error("invite_lookup_failed"))?
+        .ok_or(InviteServiceError::Forbidden("invite_invalid"))?;
+    match InviteStatus::of(&invite, Utc::now()) {
+        InviteStatus::Pending => {}
+        InviteStatus::Expired => return Err(InviteServiceError::Forbidden("invite_expired")),
+        InviteStatus::Accepted | InviteStatus::Revoked => {
+            return Err(InviteServiceError::Forbidden("invite_invalid"));
+        }
+    }
+    if !invite.email.eq_ignore_ascii_case(email.trim()) {
+        return Err(InviteServiceError::Forbidden("invite_email_mismatch"));
+    }
+    Ok(invite)
+}
+
+/// Consumes the invite for the newly created user and applies its groups and
+/// vault roles inside the registration transaction. Groups or vaults deleted
+/// since the invite was issued are skipped.
+pub(crate) async fn accept_invite_tx(
+    conn: &mut PgConnection,
+    invite: &Invite,
+    user_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<(), InviteServiceError> {
+    let db_error = db_error("invite_accept_failed");
+    let accepted = InviteRepo::accept_in(conn, invite.id, user_id, now)
+        .await
+        .map_err(&db_error)?;
+    if accepted == 0 {
+        return Err(InviteServiceError::Forbidden("invite_invalid"));
+    }
+
+    query::<Postgres>(
+        r#"
+        INSERT INTO group_members (group_id, user_id, created_at)
+        SELECT id, $2, $3 FROM groups WHERE id = ANY($1)
+        ON CONFLICT DO NOTHING
+        "#,
+    )
+    .bind(&invite.group_ids.0)
+    .bind(user_id)
+    .bind(now)
+    .execute(&mut *conn)
+    .await
+    .map_err(&db_error)?;
+
+    for grant in &invite.vault_roles.0 {
+        query::<Postgres>(
+            r#"
+            INSERT INTO vault_members (vault_id, user_id, role, created_at)
+            SELECT id, $2, $3, $4 FROM vaults WHERE id = $1 AND deleted_at IS NULL
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(grant.vault_id)
+        .bind(user_id)
+        .bind(grant.role.as_i32())
+        .bind(now)
+        .execute(&mut *conn)
+        .await
+        .map_err(&db_error)?;
+    }
+    Ok(())
+}
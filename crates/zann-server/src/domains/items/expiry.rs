@@ -0,0 +1,258 @@
+//! Expiry dates on items, e.g. when a vendor API key stops working. The date is
+//! informational: the item stays readable after it passes. A periodic check
+//! announces items whose date is near through the configured notifier, once
+//! per date.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer};
+use uuid::Uuid;
+use zann_core::Item;
+use zann_db::repo::{ItemRepo, VaultRepo};
+
+use crate::app::AppState;
+use crate::config::ExpiryConfig;
+use crate::domains::service_accounts::tokens::parse_ttl;
+use crate::infra::metrics;
+use crate::infra::notifier::{self, Notification, Notifier};
+
+pub const EXPIRING_EVENT: &str = "item.expiring";
+pub const EXPIRED_EVENT: &str = "item.expired";
+
+/// Parses `2027-01-01` (midnight UTC) or an RFC 3339 timestamp.
+pub fn parse_expires_at(value: &str) -> Result<DateTime<Utc>, &'static str> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return date
+            .and_hms_opt(0, 0, 0)
+            .map(|dt| dt.and_utc())
+            .ok_or("invalid_expires_at");
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| "invalid_expires_at")
+}
+
+/// Parses an optional request value; an empty string means no expiry.
+pub fn parse_optional_expires_at(
+    value: Option<&str>,
+) -> Result<Option<DateTime<Utc>>, &'static str> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => parse_expires_at(value).map(Some),
+    }
+}
+
+/// Turns an `expiring_within` filter such as `30d` into a cutoff: items
+/// expiring at or before it match, including ones already expired.
+pub fn expiring_cutoff(
+    within: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, &'static str> {
+    let Some(within) = within.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    let within = parse_ttl(within).map_err(|_| "invalid_expiring_within")?;
+    if within <= ChronoDuration::zero() {
+        return Err("invalid_expiring_within");
+    }
+    Ok(Some(now + within))
+}
+
+pub fn expires_by(item: &Item, cutoff: Option<DateTime<Utc>>) -> bool {
+    match cutoff {
+        Some(cutoff) => item
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= cutoff),
+        None => true,
+    }
+}
+
+/// For update requests: an absent field deserializes to `None` (keep the
+/// current date), an explicit `null` to `Some(None)` (clear it).
+pub(crate) fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// The expiry date after an update request; `None` leaves `current` as is.
+pub fn updated_expires_at(
+    value: Option<Option<&str>>,
+    current: Option<DateTime<Utc>>,
+) -> Result<Option<DateTime<Utc>>, &'static str> {
+    match value {
+        None => Ok(current),
+        Some(value) => parse_optional_expires_at(value),
+    }
+}
+
+/// Checks the `expiry` config section at startup.
+pub fn validate(config: &ExpiryConfig) -> Option<String> {
+    match parse_ttl(&config.notify_within) {
+        Ok(within) if within > ChronoDuration::zero() => {}
+        _ => {
+            return Some(format!(
+                "expiry.notify_within: invalid duration '{}'",
+                config.notify_within
+            ));
+        }
+    }
+    config
+        .notifier
+        .as_ref()
+        .and_then(|notifier| notifier::validate(notifier).err())
+}
+
+/// One check: sends a notice for every live item that expires within the
+/// configured window and has not been announced for its current date. Failed
+/// deliveries are retried on the next check.
+pub async fn run_expiry_notifications(
+    state: &AppState,
+    notifier: &Notifier,
+) -> Result<usize, sqlx_core::Error> {
+    let now = Utc::now();
+    let within =
+        parse_ttl(&state.config.expiry.notify_within).unwrap_or_else(|_| ChronoDuration::days(30));
+    let item_repo = ItemRepo::new(&state.db);
+    let due = item_repo
+        .list_expiry_unnotified(now + within, state.config.expiry.batch_size.max(1))
+        .await?;
+
+    let vault_repo = VaultRepo::new(&state.db);
+    let mut vault_names: HashMap<Uuid, String> = HashMap::new();
+    let mut sent = 0;
+    for item in due {
+        let Some(expires_at) = item.expires_at else {
+            continue;
+        };
+        let vault_name = match vault_names.get(&item.vault_id) {
+            Some(name) => name.clone(),
+            None => {
+                let name = vault_repo
+                    .get_by_id(item.vault_id)
+                    .await?
+                    .map_or_else(|| item.vault_id.to_string(), |vault| vault.name);
+                vault_names.insert(item.vault_id, name.clone());
+                name
+            }
+        };
+        let notification = expiry_notification(&item, &vault_name, expires_at, now);
+        match notifier.send(&notification).await {
+            Ok(()) => {
+                sent += 1;
+                metrics::expiry_notification(notifier.kind(), "ok");
+                item_repo
+                    .mark_expiry_notified(item.id, expires_at, Utc::now())
+                    .await?;
+            }
+            Err(err) => {
+                metrics::expiry_notification(notifier.kind(), "error");
+                tracing::warn!(
+                    event = "expiry_notification_failed",
+                    notifier = notifier.kind(),
+                    item_id = %item.id,
+                    error = %err
+                );
+            }
+        }
+    }
+    Ok(sent)
+}
+
+fn expiry_notification(
+    item: &Item,
+    vault_name: &str,
+    expires_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Notification {
+    let expired = expires_at <= now;
+    let date = expires_at.format("%Y-%m-%d %H:%M UTC");
+    let subject = if expired {
+        format!("[zann] {vault_name}/{} expired on {date}", item.path)
+    } else {
+        format!("[zann] {vault_name}/{} expires on {date}", item.path)
+    };
+    let text = format!(
+        "{subject}\n\nVault: {vault_name} ({})\nItem: {} ({})\nType: {}\nExpires: {}\n\n\
+         Renew the secret upstream and update the item, or change its expiry date.\n",
+        item.vault_id,
+        item.path,
+        item.id,
+        item.type_id,
+        expires_at.to_rfc3339(),
+    );
+    Notification {
+        event: if expired {
+            EXPIRED_EVENT
+        } else {
+            EXPIRING_EVENT
+        },
+        subject,
+        text,
+        data: serde_json::json!({
+            "item_id": item.id,
+            "vault_id": item.vault_id,
+            "vault_name": vault_name,
+            "path": item.path,
+            "type_id": item.type_id,
+            "expires_at": expires_at.to_rfc3339(),
+            "expired": expired,
+        }),
+        sent_at: now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_expires_at_accepts_dates_and_timestamps() {
+        assert_eq!(
+            parse_expires_at("2027-01-01").expect("date").to_rfc3339(),
+            "2027-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_expires_at(" 2027-01-01T12:30:00+02:00 ")
+                .expect("timestamp")
+                .to_rfc3339(),
+            "2027-01-01T10:30:00+00:00"
+        );
+        assert_eq!(parse_expires_at("next year"), Err("invalid_expires_at"));
+        assert_eq!(parse_expires_at("2027-02-30"), Err("invalid_expires_at"));
+    }
+
+    #[test]
+    fn updated_expires_at_distinguishes_absent_and_cleared() {
+        let current = parse_expires_at("2027-01-01").ok();
+        assert_eq!(updated_expires_at(None, current), Ok(current));
+        assert_eq!(updated_expires_at(Some(None), current), Ok(None));
+        assert_eq!(updated_expires_at(Some(Some("")), current), Ok(None));
+        assert_eq!(
+            updated_expires_at(Some(Some("2028-06-01")), current),
+            Ok(parse_expires_at("2028-06-01").ok())
+        );
+    }
+
+    #[test]
+    fn expiring_cutoff_requires_a_positive_window() {
+        let now = Utc::now();
+        assert_eq!(expiring_cutoff(None, now), Ok(None));
+        assert_eq!(
+            expiring_cutoff(Some("30d"), now),
+            Ok(Some(now + ChronoDuration::days(30)))
+        );
+        assert_eq!(
+            expiring_cutoff(Some("0d"), now),
+            Err("invalid_expiring_within")
+        );
+        assert_eq!(
+            expiring_cutoff(Some("soon"), now),
+            Err("invalid_expiring_within")
+        );
+    }
+}
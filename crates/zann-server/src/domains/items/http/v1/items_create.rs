@@ -4,7 +4,7 @@ use zann_core::Identity;
 use crate::app::AppState;
 use crate::domains::items::service::{self, CreateItemCommand};
 
-use super::items_helpers::item_response;
+use super::items_helpers::{audit_item, item_response};
 use super::items_models::CreateItemRequest;
 use super::map_items_error;
 
@@ -24,12 +24,26 @@ pub(super) async fn create_item(
         checksum: payload.checksum,
         version: payload.version,
         fields_changed: payload.fields_changed,
+        expires_at: payload.expires_at,
     };
+    let path = command.path.clone();
     match service::create_item(&state, &identity, &vault_id, command).await {
         Ok(response) => match item_response(&state, &response.vault, response.item) {
             Ok(item) => (StatusCode::CREATED, Json(item)).into_response(),
             Err(error) => map_items_error(error),
         },
-        Err(error) => map_items_error(error),
+        Err(error) => {
+            audit_item(
+                &state,
+                &identity,
+                "create",
+                error.label(),
+                &vault_id,
+                None,
+                Some(&path),
+            )
+            .await;
+            map_items_error(error)
+        }
     }
 }
@@ -12,6 +12,7 @@ use zann_core::Identity;
 use crate::app::AppState;
 use crate::domains::items::service::{self, FileRepresentation};
 
+use super::items_helpers::audit_item;
 use super::items_models::FileUploadResponse;
 use super::map_items_error;
 
@@ -76,9 +77,20 @@ pub(super) async fn upload_item_file(
     .await
     {
         Ok(result) => result,
-        Err(error) => return map_items_error(error),
+        Err(error) => {
+            audit_item(
+                &state,
+                &identity,
+                "file_upload",
+                error.label(),
+                &vault_id,
+                Some(item_id),
+                None,
+            )
+            .await;
+            return map_items_error(error);
+        }
     };
-
     Json(FileUploadResponse {
         file_id: result.file_id.to_string(),
         upload_state: "ready".to_string(),
@@ -106,8 +118,30 @@ pub(super) async fn download_item_file(
             .await
         {
             Ok(result) => result,
-            Err(error) => return map_items_error(error),
+            Err(error) => {
+                audit_item(
+                    &state,
+                    &identity,
+                    "file_download",
+                    error.label(),
+                    &vault_id,
+                    Some(item_id),
+                    None,
+                )
+                .await;
+                return map_items_error(error);
+            }
         };
+    audit_item(
+        &state,
+        &identity,
+        "file_download",
+        "ok",
+        &vault_id,
+        Some(item_id),
+        None,
+    )
+    .await;
 
     (
         [(axum::http::header::CONTENT_TYPE, "application/octet-stream")],
@@ -1,7 +1,9 @@
-use zann_core::{Item, Vault, VaultEncryptionType};
+use uuid::Uuid;
+use zann_core::{Identity, Item, Vault, VaultEncryptionType};
 
 use crate::app::AppState;
 use crate::domains::items::service::{self, ItemsError};
+use crate::infra::audit::{self, AuditEntry};
 
 use super::items_models::{ItemResponse, ItemSummary};
 
@@ -17,6 +19,7 @@ pub(super) fn item_summary(item: Item) -> ItemSummary {
         version: item.version,
         deleted_at: item.deleted_at.map(|dt| dt.to_rfc3339()),
         updated_at: item.updated_at.to_rfc3339(),
+        expires_at: item.expires_at.map(|dt| dt.to_rfc3339()),
     }
 }
 
@@ -45,5 +48,29 @@ pub(super) fn item_response(
         version: item.version,
         deleted_at: item.deleted_at.map(|dt| dt.to_rfc3339()),
         updated_at: item.updated_at.to_rfc3339(),
+        expires_at: item.expires_at.map(|dt| dt.to_rfc3339()),
     })
 }
+
+pub(super) async fn audit_item(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    result: &str,
+    vault_ref: &str,
+    item_id: Option<Uuid>,
+    path: Option<&str>,
+) {
+    let mut entry = AuditEntry::new("items", action, result);
+    match audit::resolve_vault_id(state, vault_ref).await {
+        Some(vault_id) => entry = entry.vault(vault_id),
+        None => entry = entry.detail(Some(vault_ref)),
+    }
+    if let Some(item_id) = item_id {
+        entry = entry.target(item_id);
+    }
+    if let Some(path) = path {
+        entry = entry.path(path);
+    }
+    audit::record(state, Some(identity), entry).await;
+}
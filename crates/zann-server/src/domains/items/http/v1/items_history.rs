@@ -11,7 +11,7 @@ use crate::domains::items::service;
 
 use zann_core::VaultEncryptionType;
 
-use super::items_helpers::item_response;
+use super::items_helpers::{audit_item, item_response};
 use super::items_models::{
     HistoryListQuery, ItemHistoryDetailResponse, ItemHistoryListResponse, ItemHistorySummary,
 };
@@ -91,6 +91,18 @@ pub(super) async fn restore_item_version(
             Ok(item) => Json(item).into_response(),
             Err(error) => map_items_error(error),
         },
-        Err(error) => map_items_error(error),
+        Err(error) => {
+            audit_item(
+                &state,
+                &identity,
+                "restore",
+                error.label(),
+                &vault_id,
+                Some(item_id),
+                None,
+            )
+            .await;
+            map_items_error(error)
+        }
     }
 }
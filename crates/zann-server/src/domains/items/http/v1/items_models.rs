@@ -26,6 +26,9 @@ pub(crate) struct CreateItemRequest {
     pub(crate) version: Option<i64>,
     #[serde(default)]
     pub(crate) fields_changed: Option<FieldsChanged>,
+    /// `YYYY-MM-DD` or RFC 3339.
+    #[serde(default)]
+    pub(crate) expires_at: Option<String>,
 }
 
 #[derive(Deserialize, JsonSchema)]
@@ -52,6 +55,12 @@ pub(crate) struct UpdateItemRequest {
     pub(crate) base_version: Option<i64>,
     #[serde(default)]
     pub(crate) fields_changed: Option<FieldsChanged>,
+    /// Omit to keep the current date, `null` to clear it.
+    #[serde(
+        default,
+        deserialize_with = "crate::domains::items::expiry::deserialize_some"
+    )]
+    pub(crate) expires_at: Option<Option<String>>,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -66,6 +75,7 @@ pub(crate) struct ItemSummary {
     pub(crate) version: i64,
     pub(crate) deleted_at: Option<String>,
     pub(crate) updated_at: String,
+    pub(crate) expires_at: Option<String>,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -77,6 +87,9 @@ pub(crate) struct ItemsResponse {
 pub(crate) struct ItemsListQuery {
     #[serde(default)]
     pub(crate) prefix: Option<String>,
+    /// Only items expiring within this window (e.g. `30d`), expired ones included.
+    #[serde(default)]
+    pub(crate) expiring_within: Option<String>,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -96,6 +109,7 @@ pub(crate) struct ItemResponse {
     pub(crate) version: i64,
     pub(crate) deleted_at: Option<String>,
     pub(crate) updated_at: String,
+    pub(crate) expires_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
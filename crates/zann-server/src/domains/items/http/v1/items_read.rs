@@ -5,7 +5,7 @@ use zann_core::Identity;
 use crate::app::AppState;
 use crate::domains::items::service;
 
-use super::items_helpers::{item_response, item_summary};
+use super::items_helpers::{audit_item, item_response, item_summary};
 use super::items_models::{ItemsListQuery, ItemsResponse};
 use super::map_items_error;
 
@@ -15,11 +15,18 @@ pub(super) async fn list_items(
     axum::extract::Path(vault_id): axum::extract::Path<String>,
     Query(query): Query<ItemsListQuery>,
 ) -> impl IntoResponse {
-    let items =
-        match service::list_items(&state, &identity, &vault_id, query.prefix.as_deref()).await {
-            Ok(items) => items,
-            Err(error) => return map_items_error(error),
-        };
+    let items = match service::list_items(
+        &state,
+        &identity,
+        &vault_id,
+        query.prefix.as_deref(),
+        query.expiring_within.as_deref(),
+    )
+    .await
+    {
+        Ok(items) => items,
+        Err(error) => return map_items_error(error),
+    };
 
     let items = items.into_iter().map(item_summary).collect::<Vec<_>>();
     tracing::info!(
@@ -38,8 +45,30 @@ pub(super) async fn get_item(
 ) -> impl IntoResponse {
     let response = match service::get_item(&state, &identity, &vault_id, item_id).await {
         Ok(response) => response,
-        Err(error) => return map_items_error(error),
+        Err(error) => {
+            audit_item(
+                &state,
+                &identity,
+                "read",
+                error.label(),
+                &vault_id,
+                Some(item_id),
+                None,
+            )
+            .await;
+            return map_items_error(error);
+        }
     };
+    audit_item(
+        &state,
+        &identity,
+        "read",
+        "ok",
+        &vault_id,
+        Some(item_id),
+        Some(&response.item.path),
+    )
+    .await;
 
     let item = match item_response(&state, &response.vault, response.item) {
         Ok(item) => item,
@@ -5,7 +5,7 @@ use zann_core::Identity;
 use crate::app::AppState;
 use crate::domains::items::service::{self, UpdateItemCommand};
 
-use super::items_helpers::item_response;
+use super::items_helpers::{audit_item, item_response};
 use super::items_models::UpdateItemRequest;
 use super::map_items_error;
 
@@ -27,13 +27,26 @@ pub(super) async fn update_item(
         version: payload.version,
         base_version: payload.base_version,
         fields_changed: payload.fields_changed,
+        expires_at: payload.expires_at,
     };
     match service::update_item(&state, &identity, &vault_id, item_id, command).await {
         Ok(response) => match item_response(&state, &response.vault, response.item) {
             Ok(item) => Json(item).into_response(),
             Err(error) => map_items_error(error),
         },
-        Err(error) => map_items_error(error),
+        Err(error) => {
+            audit_item(
+                &state,
+                &identity,
+                "update",
+                error.label(),
+                &vault_id,
+                Some(item_id),
+                None,
+            )
+            .await;
+            map_items_error(error)
+        }
     }
 }
 
@@ -45,6 +58,18 @@ pub(super) async fn delete_item(
 ) -> impl IntoResponse {
     match service::delete_item(&state, &identity, &vault_id, item_id).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
-        Err(error) => map_items_error(error),
+        Err(error) => {
+            audit_item(
+                &state,
+                &identity,
+                "delete",
+                error.label(),
+                &vault_id,
+                Some(item_id),
+                None,
+            )
+            .await;
+            map_items_error(error)
+        }
     }
 }
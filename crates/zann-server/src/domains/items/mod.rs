@@ -1,2 +1,3 @@
+pub mod expiry;
 pub mod http;
 pub mod service;
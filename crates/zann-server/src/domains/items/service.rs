@@ -1,6 +1,7 @@
 use chrono::Utc;
 use serde_json::Value as JsonValue;
 use sqlx_core::types::Json as SqlxJson;
+use sqlx_postgres::PgConnection;
 use uuid::Uuid;
 use zann_core::{
     Attachment, Change, ChangeOp, ChangeType, FieldsChanged, Identity, Item, ItemHistory,
@@ -15,10 +16,12 @@ use zann_db::repo::{
 
 use crate::app::AppState;
 use crate::domains::access_control::http::{
-    find_vault, parse_scope, vault_role_allows, ScopeRule, VaultScope,
+    find_vault, parse_scope, scope_allows_action, vault_role_allows, ScopeRule, VaultScope,
 };
 use crate::domains::access_control::policies::PolicyDecision;
 use crate::domains::errors::ServiceError;
+use crate::domains::items::expiry;
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::metrics;
 
 pub const ITEM_HISTORY_LIMIT: i64 = 5;
@@ -47,6 +50,7 @@ pub struct CreateItemCommand {
     pub checksum: Option<String>,
     pub version: Option<i64>,
     pub fields_changed: Option<FieldsChanged>,
+    pub expires_at: Option<String>,
 }
 
 pub struct UpdateItemCommand {
@@ -61,6 +65,7 @@ pub struct UpdateItemCommand {
     pub version: Option<i64>,
     pub base_version: Option<i64>,
     pub fields_changed: Option<FieldsChanged>,
+    pub expires_at: Option<Option<String>>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -98,8 +103,11 @@ pub async fn list_items(
     identity: &Identity,
     vault_id: &str,
     prefix: Option<&str>,
+    expiring_within: Option<&str>,
 ) -> Result<Vec<Item>, ItemsError> {
     let resource = format!("vaults/{vault_id}/items");
+    let expiring_cutoff =
+        expiry::expiring_cutoff(expiring_within, Utc::now()).map_err(ItemsError::BadRequest)?;
     let vault = authorize_vault_access(
         state,
         identity,
@@ -135,6 +143,7 @@ pub async fn list_items(
     if let Some(prefix) = prefix {
         items.retain(|item| prefix_match(Some(&prefix), &item.path));
     }
+    items.retain(|item| expiry::expires_by(item, expiring_cutoff));
 
     tracing::info!(
         event = "items_listed",
@@ -239,6 +248,8 @@ pub async fn upload_item_file(
             if existing.item_id != item_id {
                 return Err(ItemsError::Conflict("file_id_conflict"));
             }
+            let entry = item_audit_entry("file_upload", &vault, &item);
+            audit::record(state, Some(identity), entry).await;
             return Ok(FileUploadResult {
                 file_id: existing.id,
             });
@@ -281,12 +292,12 @@ pub async fn upload_item_file(
     let (content_enc, checksum, enc_mode) = if vault.encryption_type == VaultEncryptionType::Server
     {
         if representation == FileRepresentation::Plain {
-            let Some(smk) = state.server_master_key.as_ref() else {
+            let Some(smk) = state.server_master_key.get() else {
                 tracing::error!(event = "file_upload_failed", "SMK not configured");
                 return Err(ItemsError::Internal("smk_missing"));
             };
             let vault_key =
-                match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+                match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
                     Ok(key) => key,
                     Err(err) => {
                         tracing::error!(
@@ -330,10 +341,20 @@ pub async fn upload_item_file(
         deleted_at: None,
     };
 
-    if let Err(err) = attachment_repo.create(&attachment).await {
+    let db_error = |err: sqlx_core::Error| {
         tracing::error!(event = "attachment_create_failed", error = %err, "DB error");
-        return Err(ItemsError::DbError);
-    }
+        ItemsError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    AttachmentRepo::create_in(&mut tx, &attachment)
+        .await
+        .map_err(db_error)?;
+    let entry = item_audit_entry("file_upload", &vault, &item);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     if vault.encryption_type == VaultEncryptionType::Server {
         if let Err(err) = update_file_upload_state(
@@ -451,11 +472,11 @@ pub async fn download_item_file(
     }
 
     if vault.encryption_type == VaultEncryptionType::Server && attachment.enc_mode == "plain" {
-        let Some(smk) = state.server_master_key.as_ref() else {
+        let Some(smk) = state.server_master_key.get() else {
             tracing::error!(event = "file_download_failed", "SMK not configured");
             return Err(ItemsError::Internal("smk_missing"));
         };
-        let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+        let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
             Ok(key) => key,
             Err(err) => {
                 tracing::error!(event = "file_download_failed", error = %err, "Key decrypt failed");
@@ -503,6 +524,8 @@ pub async fn create_item(
     let path = validate_item_path(&command.path)?;
     let name = basename_from_path(path);
     let type_id = type_id.to_string();
+    let expires_at = expiry::parse_optional_expires_at(command.expires_at.as_deref())
+        .map_err(ItemsError::BadRequest)?;
 
     let tags = command.tags.map(|tags| {
         tags.into_iter()
@@ -527,11 +550,11 @@ pub async fn create_item(
             Ok(bytes) => bytes,
             Err(_) => return Err(ItemsError::BadRequest("invalid_payload")),
         };
-        let Some(smk) = state.server_master_key.as_ref() else {
+        let Some(smk) = state.server_master_key.get() else {
             tracing::error!(event = "item_create_failed", "SMK not configured");
             return Err(ItemsError::Internal("smk_missing"));
         };
-        let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+        let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
             Ok(key) => key,
             Err(err) => {
                 tracing::error!(event = "item_create_failed", error = %err, "Key decrypt failed");
@@ -582,17 +605,11 @@ pub async fn create_item(
         deleted_at: None,
         deleted_by_user_id: None,
         deleted_by_device_id: None,
+        expires_at,
         created_at: now,
         updated_at: now,
     };
 
-    let item_repo = ItemRepo::new(&state.db);
-    if let Err(err) = item_repo.create(&item).await {
-        tracing::error!(event = "item_create_failed", error = %err, "DB error");
-        return Err(ItemsError::DbError);
-    }
-
-    let history_repo = ItemHistoryRepo::new(&state.db);
     let actor = actor_snapshot(state, identity, Some(device_id)).await;
     let history = ItemHistory {
         id: Uuid::now_v7(),
@@ -609,27 +626,6 @@ pub async fn create_item(
         changed_by_device_name: actor.device_name,
         created_at: now,
     };
-    if let Err(err) = history_repo.create(&history).await {
-        tracing::error!(
-            event = "item_history_create_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to create item history"
-        );
-    }
-    if let Err(err) = history_repo
-        .prune_by_item(item.id, ITEM_HISTORY_LIMIT)
-        .await
-    {
-        tracing::error!(
-            event = "item_history_prune_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to prune item history"
-        );
-    }
-
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -639,14 +635,25 @@ pub async fn create_item(
         device_id,
         created_at: now,
     };
-    if let Err(err) = change_repo.create(&change).await {
-        tracing::error!(
-            event = "item_change_create_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to create change entry"
-        );
-    }
+
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "item_create_failed", error = %err, "DB error");
+        ItemsError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    ItemRepo::create_in(&mut tx, &item)
+        .await
+        .map_err(db_error)?;
+    write_history(&mut tx, &history).await.map_err(db_error)?;
+    ChangeRepo::create_in(&mut tx, &change)
+        .await
+        .map_err(db_error)?;
+    let entry = item_audit_entry("create", &vault, &item);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     tracing::info!(event = "item_created", item_id = %item.id, "Item created");
     Ok(ItemWithVault { vault, item })
@@ -658,6 +665,19 @@ pub async fn update_item(
     vault_id: &str,
     item_id: Uuid,
     command: UpdateItemCommand,
+) -> Result<ItemWithVault, ItemsError> {
+    apply_item_update(state, identity, vault_id, item_id, command, Some("update")).await
+}
+
+/// Applies `command` to the item, recording `audit_action` in the same
+/// transaction when given.
+async fn apply_item_update(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    item_id: Uuid,
+    command: UpdateItemCommand,
+    audit_action: Option<&str>,
 ) -> Result<ItemWithVault, ItemsError> {
     let resource = format!("vaults/{vault_id}/items/{item_id}");
 
@@ -753,6 +773,15 @@ pub async fn update_item(
             updated = true;
         }
     }
+    let expires_at = expiry::updated_expires_at(
+        command.expires_at.as_ref().map(Option::as_deref),
+        item.expires_at,
+    )
+    .map_err(ItemsError::BadRequest)?;
+    if expires_at != item.expires_at {
+        item.expires_at = expires_at;
+        updated = true;
+    }
     if let Some(plaintext_payload) = command.payload {
         if vault.encryption_type != VaultEncryptionType::Server {
             return Err(ItemsError::BadRequest("plaintext_not_allowed"));
@@ -766,11 +795,11 @@ pub async fn update_item(
             Ok(bytes) => bytes,
             Err(_) => return Err(ItemsError::BadRequest("invalid_payload")),
         };
-        let Some(smk) = state.server_master_key.as_ref() else {
+        let Some(smk) = state.server_master_key.get() else {
             tracing::error!(event = "item_update_failed", "SMK not configured");
             return Err(ItemsError::Internal("smk_missing"));
         };
-        let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+        let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
             Ok(key) => key,
             Err(err) => {
                 tracing::error!(event = "item_update_failed", error = %err, "Key decrypt failed");
@@ -811,10 +840,9 @@ pub async fn update_item(
         return Err(ItemsError::BadRequest("no_changes"));
     }
 
-    if payload_changed {
-        let history_repo = ItemHistoryRepo::new(&state.db);
+    let history = if payload_changed {
         let actor = actor_snapshot(state, identity, Some(device_id)).await;
-        let history = ItemHistory {
+        Some(ItemHistory {
             id: Uuid::now_v7(),
             item_id: item.id,
             payload_enc: previous_payload,
@@ -828,41 +856,14 @@ pub async fn update_item(
             changed_by_device_id: Some(device_id),
             changed_by_device_name: actor.device_name,
             created_at: Utc::now(),
-        };
-        if let Err(err) = history_repo.create(&history).await {
-            tracing::error!(
-                event = "item_history_create_failed",
-                error = %err,
-                item_id = %item.id,
-                "Failed to create item history"
-            );
-        }
-        if let Err(err) = history_repo
-            .prune_by_item(item.id, ITEM_HISTORY_LIMIT)
-            .await
-        {
-            tracing::error!(
-                event = "item_history_prune_failed",
-                error = %err,
-                item_id = %item.id,
-                "Failed to prune item history"
-            );
-        }
-    }
+        })
+    } else {
+        None
+    };
 
     item.version = command.version.unwrap_or(item.version + 1);
     item.device_id = device_id;
     item.updated_at = Utc::now();
-
-    let Ok(affected) = item_repo.update(&item).await else {
-        tracing::error!(event = "item_update_failed", "DB error");
-        return Err(ItemsError::DbError);
-    };
-    if affected == 0 {
-        return Err(ItemsError::Conflict("row_version_conflict"));
-    }
-
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -872,13 +873,37 @@ pub async fn update_item(
         device_id,
         created_at: item.updated_at,
     };
-    if let Err(err) = change_repo.create(&change).await {
-        tracing::error!(
-            event = "item_change_create_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to create change entry"
-        );
+
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "item_update_failed", error = %err, "DB error");
+        ItemsError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    if let Some(history) = &history {
+        write_history(&mut tx, history).await.map_err(db_error)?;
+    }
+    let affected = ItemRepo::update_in(&mut tx, &item)
+        .await
+        .map_err(db_error)?;
+    if affected == 0 {
+        return Err(ItemsError::Conflict("row_version_conflict"));
+    }
+    ChangeRepo::create_in(&mut tx, &change)
+        .await
+        .map_err(db_error)?;
+    let audited = match audit_action {
+        Some(action) => {
+            let entry = item_audit_entry(action, &vault, &item);
+            let audited = audit::record_tx(&mut tx, Some(identity), entry)
+                .await
+                .map_err(db_error)?;
+            Some(audited)
+        }
+        None => None,
+    };
+    tx.commit().await.map_err(db_error)?;
+    if let Some(audited) = audited {
+        audited.dispatch();
     }
 
     tracing::info!(event = "item_updated", item_id = %item_id, "Item updated");
@@ -923,7 +948,6 @@ pub async fn delete_item(
     }
 
     let now = Utc::now();
-    let history_repo = ItemHistoryRepo::new(&state.db);
     let actor = actor_snapshot(state, identity, Some(device_id)).await;
     let history = ItemHistory {
         id: Uuid::now_v7(),
@@ -940,25 +964,6 @@ pub async fn delete_item(
         changed_by_device_name: actor.device_name,
         created_at: now,
     };
-    if let Err(err) = history_repo.create(&history).await {
-        tracing::error!(
-            event = "item_history_create_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to create item history"
-        );
-    }
-    if let Err(err) = history_repo
-        .prune_by_item(item.id, ITEM_HISTORY_LIMIT)
-        .await
-    {
-        tracing::error!(
-            event = "item_history_prune_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to prune item history"
-        );
-    }
 
     item.deleted_at = Some(now);
     item.deleted_by_user_id = Some(identity.user_id);
@@ -967,36 +972,6 @@ pub async fn delete_item(
     item.version += 1;
     item.device_id = device_id;
     item.updated_at = now;
-
-    let attachment_repo = AttachmentRepo::new(&state.db);
-    if let Err(err) = attachment_repo.mark_deleted_by_item(item.id, now).await {
-        tracing::error!(
-            event = "item_attachment_mark_deleted_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to mark attachments deleted"
-        );
-    }
-    let grace_days = state.config.server.attachments_gc_grace_days.max(0);
-    let cutoff = now - chrono::Duration::days(grace_days);
-    if let Err(err) = attachment_repo.purge_deleted_before(cutoff).await {
-        tracing::error!(
-            event = "item_attachment_purge_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to purge deleted attachments"
-        );
-    }
-
-    let Ok(affected) = item_repo.update(&item).await else {
-        tracing::error!(event = "item_delete_failed", "DB error");
-        return Err(ItemsError::DbError);
-    };
-    if affected == 0 {
-        return Err(ItemsError::Conflict("row_version_conflict"));
-    }
-
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -1006,12 +981,43 @@ pub async fn delete_item(
         device_id,
         created_at: now,
     };
-    if let Err(err) = change_repo.create(&change).await {
+
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "item_delete_failed", error = %err, "DB error");
+        ItemsError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    write_history(&mut tx, &history).await.map_err(db_error)?;
+    AttachmentRepo::mark_deleted_by_item_in(&mut tx, item.id, now)
+        .await
+        .map_err(db_error)?;
+    let affected = ItemRepo::update_in(&mut tx, &item)
+        .await
+        .map_err(db_error)?;
+    if affected == 0 {
+        return Err(ItemsError::Conflict("row_version_conflict"));
+    }
+    ChangeRepo::create_in(&mut tx, &change)
+        .await
+        .map_err(db_error)?;
+    let entry = item_audit_entry("delete", &vault, &item);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
+
+    let grace_days = state.config.server.attachments_gc_grace_days.max(0);
+    let cutoff = now - chrono::Duration::days(grace_days);
+    if let Err(err) = AttachmentRepo::new(&state.db)
+        .purge_deleted_before(cutoff)
+        .await
+    {
         tracing::error!(
-            event = "item_change_create_failed",
+            event = "item_attachment_purge_failed",
             error = %err,
             item_id = %item.id,
-            "Failed to create change entry"
+            "Failed to purge deleted attachments"
         );
     }
 
@@ -1212,25 +1218,6 @@ pub async fn restore_item_version(
         changed_by_device_name: actor.device_name,
         created_at: now,
     };
-    if let Err(err) = history_repo.create(&history_snapshot).await {
-        tracing::error!(
-            event = "item_history_create_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to create item history"
-        );
-    }
-    if let Err(err) = history_repo
-        .prune_by_item(item.id, ITEM_HISTORY_LIMIT)
-        .await
-    {
-        tracing::error!(
-            event = "item_history_prune_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to prune item history"
-        );
-    }
 
     item.payload_enc = history.payload_enc;
     item.checksum = history.checksum;
@@ -1241,28 +1228,6 @@ pub async fn restore_item_version(
     item.deleted_by_user_id = None;
     item.deleted_by_device_id = None;
     item.updated_at = now;
-
-    if item.type_id == "file_secret" {
-        let attachment_repo = AttachmentRepo::new(&state.db);
-        if let Err(err) = attachment_repo.clear_deleted_by_item(item.id).await {
-            tracing::error!(
-                event = "item_attachment_clear_deleted_failed",
-                error = %err,
-                item_id = %item.id,
-                "Failed to clear deleted attachments"
-            );
-        }
-    }
-
-    let Ok(affected) = item_repo.update(&item).await else {
-        tracing::error!(event = "item_restore_failed", "DB error");
-        return Err(ItemsError::DbError);
-    };
-    if affected == 0 {
-        return Err(ItemsError::Conflict("row_version_conflict"));
-    }
-
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -1272,14 +1237,35 @@ pub async fn restore_item_version(
         device_id,
         created_at: item.updated_at,
     };
-    if let Err(err) = change_repo.create(&change).await {
-        tracing::error!(
-            event = "item_change_create_failed",
-            error = %err,
-            item_id = %item.id,
-            "Failed to create change entry"
-        );
+
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "item_restore_failed", error = %err, "DB error");
+        ItemsError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    write_history(&mut tx, &history_snapshot)
+        .await
+        .map_err(db_error)?;
+    if item.type_id == "file_secret" {
+        AttachmentRepo::clear_deleted_by_item_in(&mut tx, item.id)
+            .await
+            .map_err(db_error)?;
+    }
+    let affected = ItemRepo::update_in(&mut tx, &item)
+        .await
+        .map_err(db_error)?;
+    if affected == 0 {
+        return Err(ItemsError::Conflict("row_version_conflict"));
     }
+    ChangeRepo::create_in(&mut tx, &change)
+        .await
+        .map_err(db_error)?;
+    let entry = item_audit_entry("restore", &vault, &item);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     tracing::info!(
         event = "item.restore_previous",
@@ -1480,18 +1466,30 @@ async fn service_account_allows_prefix(
         .any(|rule| scope_matches_prefix(rule, vault, prefix))
 }
 
-fn scope_allows_action(permission: &str, action: &str) -> bool {
-    match action {
-        "read_history" => {
-            matches!(
-                permission,
-                "history_read" | "read_history" | "read_previous"
-            )
-        }
-        "read_previous" => permission == "read_previous",
-        "read" | "list" => permission == "read",
-        _ => permission == action,
-    }
+/// Read rules of a service account that cover `vault`, for results that span
+/// the whole vault such as sync pulls. Empty when the account is gone.
+pub(crate) async fn service_account_read_rules(
+    state: &AppState,
+    service_account_id: Uuid,
+    vault: &Vault,
+) -> Vec<ScopeRule> {
+    let Some(scopes) = service_account_scopes(state, service_account_id).await else {
+        return Vec::new();
+    };
+    scopes
+        .iter()
+        .filter_map(|scope| parse_scope(scope))
+        .filter(|rule| {
+            scope_allows_action(&rule.permission, "read")
+                && vault_matches_scope(vault, &rule.target)
+        })
+        .collect()
+}
+
+pub(crate) fn rules_allow_path(rules: &[ScopeRule], vault: &Vault, path: &str) -> bool {
+    rules
+        .iter()
+        .any(|rule| scope_matches_path(rule, vault, path))
 }
 
 fn file_aad(
@@ -1513,11 +1511,11 @@ pub(crate) fn decrypt_payload_bytes(
     item_id: Uuid,
     payload_enc: &[u8],
 ) -> Result<Vec<u8>, ItemsError> {
-    let Some(smk) = state.server_master_key.as_ref() else {
+    let Some(smk) = state.server_master_key.get() else {
         tracing::error!(event = "item_payload_decrypt_failed", "SMK not configured");
         return Err(ItemsError::Internal("smk_missing"));
     };
-    let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+    let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
         Ok(key) => key,
         Err(err) => {
             tracing::error!(
@@ -1660,11 +1658,57 @@ async fn update_file_upload_state(
         version: None,
         base_version: None,
         fields_changed: None,
+        expires_at: None,
     };
-    update_item(state, identity, vault_id, item_id, command).await?;
+    apply_item_update(state, identity, vault_id, item_id, command, None).await?;
+    Ok(())
+}
+
+/// Stores an item write with its history snapshot and change entry, and records
+/// `entry` in the audit log, in one transaction. Returns `false` without
+/// writing anything when the item's row version moved on in the meantime.
+pub(crate) async fn write_item_change(
+    state: &AppState,
+    identity: &Identity,
+    item: &Item,
+    history: Option<&ItemHistory>,
+    change: &Change,
+    entry: AuditEntry,
+) -> Result<bool, sqlx_core::Error> {
+    let mut tx = state.db.begin().await?;
+    if change.op == ChangeOp::Create {
+        ItemRepo::create_in(&mut tx, item).await?;
+    } else if ItemRepo::update_in(&mut tx, item).await? == 0 {
+        return Ok(false);
+    }
+    if let Some(history) = history {
+        write_history(&mut tx, history).await?;
+    }
+    ChangeRepo::create_in(&mut tx, change).await?;
+    let audited = audit::record_tx(&mut tx, Some(identity), entry).await?;
+    tx.commit().await?;
+    audited.dispatch();
+    Ok(true)
+}
+
+/// Stores a history snapshot and drops snapshots beyond [`ITEM_HISTORY_LIMIT`].
+pub(crate) async fn write_history(
+    conn: &mut PgConnection,
+    history: &ItemHistory,
+) -> Result<(), sqlx_core::Error> {
+    ItemHistoryRepo::create_in(conn, history).await?;
+    ItemHistoryRepo::prune_by_item_in(conn, history.item_id, ITEM_HISTORY_LIMIT).await?;
     Ok(())
 }
 
+/// Audit event for a successful item write, recorded in the write's transaction.
+pub(crate) fn item_audit_entry(action: &str, vault: &Vault, item: &Item) -> AuditEntry {
+    AuditEntry::new("items", action, "ok")
+        .vault(vault.id)
+        .target(item.id)
+        .path(&item.path)
+}
+
 async fn actor_snapshot(
     state: &AppState,
     identity: &Identity,
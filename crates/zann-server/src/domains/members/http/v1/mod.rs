@@ -1,23 +1,129 @@
 use axum::{
-    extract::State, http::StatusCode, response::IntoResponse, routing::get, Extension, Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, put},
+    Extension, Json, Router,
 };
 use schemars::JsonSchema;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use zann_core::Identity;
 
 use crate::app::AppState;
-use crate::domains::access_control::http::{find_vault, vault_role_allows, VaultScope};
-use crate::domains::access_control::policies::PolicyDecision;
-use crate::infra::metrics;
-use zann_db::repo::VaultRepo;
+use crate::domains::errors::ServiceError;
+use crate::domains::members::service::{
+    self, AddMemberCommand, GroupGrantView, MemberServiceError, MemberView,
+};
+use crate::infra::audit::{self, AuditEntry};
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    error: &'static str,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct AddVaultMemberRequest {
+    /// Identify the user either by id or by email.
+    #[serde(default)]
+    user_id: Option<Uuid>,
+    #[serde(default)]
+    email: Option<String>,
+    /// One of `admin`, `operator`, `member`, `readonly`.
+    role: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct UpdateVaultMemberRequest {
+    /// One of `admin`, `operator`, `member`, `readonly`.
+    role: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct GrantVaultGroupRequest {
+    /// Slug of the group to grant access to.
+    group: String,
+    /// One of `admin`, `operator`, `member`, `readonly`.
+    role: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct VaultMemberResponse {
+    pub(crate) user_id: String,
+    pub(crate) email: String,
+    pub(crate) role: String,
+    /// `direct` for explicit members, `group` when inherited from a group grant.
+    pub(crate) source: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) group: Option<String>,
+    pub(crate) created_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct VaultGroupGrantResponse {
+    pub(crate) group_id: String,
+    pub(crate) slug: String,
+    pub(crate) name: String,
+    pub(crate) role: String,
+    pub(crate) created_at: String,
+}
 
 #[derive(Serialize, JsonSchema)]
 pub(crate) struct MembersResponse {
-    pub(crate) members: Vec<serde_json::Value>,
+    pub(crate) members: Vec<VaultMemberResponse>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct VaultGroupGrantsResponse {
+    pub(crate) groups: Vec<VaultGroupGrantResponse>,
 }
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/v1/vaults/:vault_id/members", get(list_members))
+    Router::new()
+        .route(
+            "/v1/vaults/:vault_id/members",
+            get(list_members).post(add_member),
+        )
+        .route(
+            "/v1/vaults/:vault_id/members/:user_id",
+            put(update_member).delete(remove_member),
+        )
+        .route(
+            "/v1/vaults/:vault_id/groups",
+            get(list_group_grants).post(grant_group),
+        )
+        .route(
+            "/v1/vaults/:vault_id/groups/:slug",
+            put(update_group_grant).delete(revoke_group),
+        )
+}
+
+fn map_member_error(error: MemberServiceError) -> axum::response::Response {
+    match error {
+        MemberServiceError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        MemberServiceError::Forbidden(code) => {
+            (StatusCode::FORBIDDEN, Json(ErrorResponse { error: code })).into_response()
+        }
+        MemberServiceError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        MemberServiceError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        MemberServiceError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        MemberServiceError::DbError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response(),
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
 }
 
 #[tracing::instrument(skip(state, identity), fields(vault_id = %vault_id))]
@@ -26,62 +132,216 @@ async fn list_members(
     Extension(identity): Extension<Identity>,
     axum::extract::Path(vault_id): axum::extract::Path<String>,
 ) -> impl IntoResponse {
-    let policies = state.policy_store.get();
-    let resource = format!("vaults/{vault_id}/members");
-
-    let vault_repo = VaultRepo::new(&state.db);
-    let vault = match find_vault(&vault_repo, &vault_id).await {
-        Ok(Some(vault)) => vault,
-        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
-        Err(_) => {
-            tracing::error!(event = "members_list_failed", "DB error");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": "db_error" })),
-            )
-                .into_response();
+    match service::list_members(&state, &identity, &vault_id).await {
+        Ok(members) => {
+            tracing::info!(event = "members_listed", "Member list returned");
+            let members = members.into_iter().map(member_response).collect();
+            (StatusCode::OK, Json(MembersResponse { members })).into_response()
         }
+        Err(err) => map_member_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload), fields(vault_id = %vault_id))]
+async fn add_member(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path(vault_id): axum::extract::Path<String>,
+    Json(payload): Json<AddVaultMemberRequest>,
+) -> impl IntoResponse {
+    let requested = payload
+        .user_id
+        .map(|id| id.to_string())
+        .or_else(|| payload.email.clone())
+        .unwrap_or_default();
+    let command = AddMemberCommand {
+        user_id: payload.user_id,
+        email: payload.email,
+        role: payload.role,
     };
+    match service::add_member(&state, &identity, &vault_id, command).await {
+        Ok(view) => (StatusCode::CREATED, Json(member_response(view))).into_response(),
+        Err(err) => {
+            audit_member_failure(&state, &identity, "add", &err, &vault_id, &requested, None).await;
+            map_member_error(err)
+        }
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload), fields(vault_id = %vault_id, user_id = %user_id))]
+async fn update_member(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path((vault_id, user_id)): axum::extract::Path<(String, String)>,
+    Json(payload): Json<UpdateVaultMemberRequest>,
+) -> impl IntoResponse {
+    match service::update_member_role(&state, &identity, &vault_id, &user_id, &payload.role).await {
+        Ok(view) => (StatusCode::OK, Json(member_response(view))).into_response(),
+        Err(err) => {
+            let detail = format!("role={}", payload.role.trim());
+            audit_member_failure(
+                &state,
+                &identity,
+                "update_role",
+                &err,
+                &vault_id,
+                &user_id,
+                Some(detail),
+            )
+            .await;
+            map_member_error(err)
+        }
+    }
+}
 
-    match policies.evaluate(&identity, "list", &resource) {
-        PolicyDecision::Allow => {}
-        PolicyDecision::Deny => {
-            metrics::forbidden_access(&resource);
-            tracing::warn!(
-                event = "forbidden",
-                action = "list",
-                resource = %resource,
-                "Access denied"
-            );
-            return StatusCode::FORBIDDEN.into_response();
+#[tracing::instrument(skip(state, identity), fields(vault_id = %vault_id, user_id = %user_id))]
+async fn remove_member(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path((vault_id, user_id)): axum::extract::Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::remove_member(&state, &identity, &vault_id, &user_id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => {
+            audit_member_failure(&state, &identity, "remove", &err, &vault_id, &user_id, None)
+                .await;
+            map_member_error(err)
+        }
+    }
+}
+
+#[tracing::instrument(skip(state, identity), fields(vault_id = %vault_id))]
+async fn list_group_grants(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path(vault_id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    match service::list_group_grants(&state, &identity, &vault_id).await {
+        Ok(grants) => {
+            let groups = grants.into_iter().map(group_grant_response).collect();
+            (StatusCode::OK, Json(VaultGroupGrantsResponse { groups })).into_response()
+        }
+        Err(err) => map_member_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload), fields(vault_id = %vault_id))]
+async fn grant_group(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path(vault_id): axum::extract::Path<String>,
+    Json(payload): Json<GrantVaultGroupRequest>,
+) -> impl IntoResponse {
+    match service::grant_group(&state, &identity, &vault_id, &payload.group, &payload.role).await {
+        Ok(view) => (StatusCode::CREATED, Json(group_grant_response(view))).into_response(),
+        Err(err) => {
+            let target = format!("group:{}", payload.group.trim());
+            audit_member_failure(
+                &state,
+                &identity,
+                "grant_group",
+                &err,
+                &vault_id,
+                &target,
+                None,
+            )
+            .await;
+            map_member_error(err)
+        }
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload), fields(vault_id = %vault_id, slug = %slug))]
+async fn update_group_grant(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path((vault_id, slug)): axum::extract::Path<(String, String)>,
+    Json(payload): Json<UpdateVaultMemberRequest>,
+) -> impl IntoResponse {
+    match service::update_group_grant(&state, &identity, &vault_id, &slug, &payload.role).await {
+        Ok(view) => (StatusCode::OK, Json(group_grant_response(view))).into_response(),
+        Err(err) => {
+            let detail = format!("role={}", payload.role.trim());
+            audit_member_failure(
+                &state,
+                &identity,
+                "update_group",
+                &err,
+                &vault_id,
+                &format!("group:{slug}"),
+                Some(detail),
+            )
+            .await;
+            map_member_error(err)
         }
-        PolicyDecision::NoMatch => {
-            match vault_role_allows(&state, &identity, vault.id, "list", VaultScope::Members).await
-            {
-                Ok(true) => {}
-                Ok(false) => {
-                    metrics::forbidden_access(&resource);
-                    tracing::warn!(
-                        event = "forbidden",
-                        action = "list",
-                        resource = %resource,
-                        "Access denied"
-                    );
-                    return StatusCode::FORBIDDEN.into_response();
-                }
-                Err(_) => {
-                    tracing::error!(event = "members_list_failed", "DB error");
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({ "error": "db_error" })),
-                    )
-                        .into_response();
-                }
-            }
+    }
+}
+
+#[tracing::instrument(skip(state, identity), fields(vault_id = %vault_id, slug = %slug))]
+async fn revoke_group(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path((vault_id, slug)): axum::extract::Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::revoke_group(&state, &identity, &vault_id, &slug).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => {
+            audit_member_failure(
+                &state,
+                &identity,
+                "revoke_group",
+                &err,
+                &vault_id,
+                &format!("group:{slug}"),
+                None,
+            )
+            .await;
+            map_member_error(err)
         }
     }
+}
 
-    tracing::info!(event = "members_listed", "Member list returned");
-    let body = MembersResponse { members: vec![] };
-    (StatusCode::OK, Json(body)).into_response()
+fn member_response(view: MemberView) -> VaultMemberResponse {
+    VaultMemberResponse {
+        user_id: view.member.user_id.to_string(),
+        email: view.email,
+        role: view.member.role.as_str().to_string(),
+        source: if view.group.is_some() {
+            "group"
+        } else {
+            "direct"
+        },
+        group: view.group,
+        created_at: view.member.created_at.to_rfc3339(),
+    }
+}
+
+fn group_grant_response(view: GroupGrantView) -> VaultGroupGrantResponse {
+    VaultGroupGrantResponse {
+        group_id: view.grant.group_id.to_string(),
+        slug: view.slug,
+        name: view.name,
+        role: view.grant.role.as_str().to_string(),
+        created_at: view.grant.created_at.to_rfc3339(),
+    }
+}
+
+/// Successful changes are audited by the service in their own transaction;
+/// this records the refused and failed attempts.
+async fn audit_member_failure(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    err: &ServiceError,
+    vault_ref: &str,
+    target: &str,
+    detail: Option<String>,
+) {
+    let mut entry = AuditEntry::new("members", action, err.label())
+        .target(target)
+        .detail(detail.as_deref());
+    if let Some(vault_id) = audit::resolve_vault_id(state, vault_ref).await {
+        entry = entry.vault(vault_id);
+    }
+    audit::record(state, Some(identity), entry).await;
 }
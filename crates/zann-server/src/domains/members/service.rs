@@ -0,0 +1,686 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use zann_core::{
+    Group, Identity, User, UserStatus, Vault, VaultGroupGrant, VaultKind, VaultMember,
+    VaultMemberRole,
+};
+use zann_db::repo::{
+    GroupMemberRepo, GroupRepo, UserRepo, VaultGroupGrantRepo, VaultMemberRepo, VaultRepo,
+};
+
+use crate::app::AppState;
+use crate::domains::access_control::http::{find_vault, vault_role_allows, VaultScope};
+use crate::domains::access_control::policies::PolicyDecision;
+use crate::domains::errors::ServiceError;
+use crate::infra::audit::{self, AuditEntry};
+use crate::infra::db::apply_tx_isolation;
+use crate::infra::metrics;
+
+pub type MemberServiceError = ServiceError;
+
+pub struct AddMemberCommand {
+    pub user_id: Option<Uuid>,
+    pub email: Option<String>,
+    pub role: String,
+}
+
+/// A vault membership joined with the member's email for display. `group`
+/// is the slug of the granting group when access is inherited.
+pub struct MemberView {
+    pub member: VaultMember,
+    pub email: String,
+    pub group: Option<String>,
+}
+
+/// A group grant joined with the group's slug and name for display.
+pub struct GroupGrantView {
+    pub grant: VaultGroupGrant,
+    pub slug: String,
+    pub name: String,
+}
+
+/// Who a vault role is granted to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Grantee {
+    User(Uuid),
+    Group(Uuid),
+}
+
+/// The audit row of a successful membership change, written in the same
+/// transaction as the change itself.
+fn member_audit_entry(action: &str, vault_id: Uuid, target: &str, detail: &str) -> AuditEntry {
+    AuditEntry::new("members", action, "ok")
+        .vault(vault_id)
+        .target(target)
+        .detail(Some(detail))
+}
+
+fn forbidden(resource: &str, action: &str, reason: &str) -> MemberServiceError {
+    metrics::forbidden_access(resource);
+    tracing::warn!(
+        event = "forbidden",
+        action = action,
+        resource = %resource,
+        reason = reason,
+        "Access denied"
+    );
+    MemberServiceError::ForbiddenNoBody
+}
+
+async fn load_vault(state: &AppState, selector: &str) -> Result<Vault, MemberServiceError> {
+    let repo = VaultRepo::new(&state.db);
+    match find_vault(&repo, selector).await {
+        Ok(Some(vault)) => Ok(vault),
+        Ok(None) => Err(MemberServiceError::NotFound),
+        Err(_) => {
+            tracing::error!(event = "members_vault_lookup_failed", "DB error");
+            Err(MemberServiceError::DbError)
+        }
+    }
+}
+
+/// Policy first, then the vault role. `vault_role_allows` already refuses
+/// the members scope to service accounts.
+async fn ensure_access(
+    state: &AppState,
+    identity: &Identity,
+    vault: &Vault,
+    resource: &str,
+    action: &str,
+) -> Result<(), MemberServiceError> {
+    let policies = state.policy_store.get();
+    match policies.evaluate(identity, action, resource) {
+        PolicyDecision::Allow => Ok(()),
+        PolicyDecision::Deny => Err(forbidden(resource, action, "policy")),
+        PolicyDecision::NoMatch => {
+            match vault_role_allows(state, identity, vault.id, action, VaultScope::Members).await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(forbidden(resource, action, "vault_role")),
+                Err(_) => {
+                    tracing::error!(event = "members_access_failed", "DB error");
+                    Err(MemberServiceError::DbError)
+                }
+            }
+        }
+    }
+}
+
+/// The default policy grants `list` on `vaults/*/members` to everyone, so a
+/// policy allow alone does not expose the roster: callers must also hold a
+/// vault role, or be allowed to manage members.
+async fn ensure_list_access(
+    state: &AppState,
+    identity: &Identity,
+    vault: &Vault,
+    resource: &str,
+) -> Result<(), MemberServiceError> {
+    let policies = state.policy_store.get();
+    if policies.evaluate(identity, "list", resource) == PolicyDecision::Deny {
+        return Err(forbidden(resource, "list", "policy"));
+    }
+    match vault_role_allows(state, identity, vault.id, "list", VaultScope::Members).await {
+        Ok(true) => Ok(()),
+        Ok(false)
+            if identity.service_account_id.is_none()
+                && policies.evaluate(identity, "write", resource) == PolicyDecision::Allow =>
+        {
+            Ok(())
+        }
+        Ok(false) => Err(forbidden(resource, "list", "vault_role")),
+        Err(_) => {
+            tracing::error!(event = "members_access_failed", "DB error");
+            Err(MemberServiceError::DbError)
+        }
+    }
+}
+
+/// Membership changes need `write`, which only the vault admin role grants.
+/// Service accounts are refused even when a policy would match them.
+async fn ensure_manager(
+    state: &AppState,
+    identity: &Identity,
+    vault: &Vault,
+    resource: &str,
+) -> Result<(), MemberServiceError> {
+    if identity.service_account_id.is_some() {
+        return Err(forbidden(resource, "write", "service_account"));
+    }
+    ensure_access(state, identity, vault, resource, "write").await?;
+    if vault.kind == VaultKind::Personal {
+        return Err(MemberServiceError::BadRequest("personal_vault"));
+    }
+    Ok(())
+}
+
+/// Checks that `identity` may change the membership of `vault`, for callers
+/// outside this module that hand out vault roles (e.g. invites).
+pub async fn ensure_can_manage_members(
+    state: &AppState,
+    identity: &Identity,
+    vault: &Vault,
+) -> Result<(), MemberServiceError> {
+    let resource = format!("vaults/{}/members", vault.id);
+    ensure_manager(state, identity, vault, &resource).await
+}
+
+pub(crate) fn parse_role(value: &str) -> Result<VaultMemberRole, MemberServiceError> {
+    value
+        .trim()
+        .to_ascii_lowercase()
+        .parse()
+        .map_err(|_| MemberServiceError::BadRequest("invalid_role"))
+}
+
+fn parse_user_id(value: &str) -> Result<Uuid, MemberServiceError> {
+    Uuid::parse_str(value).map_err(|_| MemberServiceError::BadRequest("invalid_user_id"))
+}
+
+async fn load_user(state: &AppState, cmd: &AddMemberCommand) -> Result<User, MemberServiceError> {
+    let repo = UserRepo::new(&state.db);
+    let email = cmd
+        .email
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let result = match (cmd.user_id, email) {
+        (Some(user_id), None) => repo.get_by_id(user_id).await,
+        (None, Some(email)) => repo.get_by_email(email).await,
+        _ => return Err(MemberServiceError::BadRequest("invalid_user")),
+    };
+    match result {
+        Ok(Some(user)) if user.status == UserStatus::Active && user.deleted_at.is_none() => {
+            Ok(user)
+        }
+        Ok(Some(_)) => Err(MemberServiceError::BadRequest("user_inactive")),
+        Ok(None) => Err(MemberServiceError::NotFound),
+        Err(_) => {
+            tracing::error!(event = "member_user_lookup_failed", "DB error");
+            Err(MemberServiceError::DbError)
+        }
+    }
+}
+
+async fn member_email(state: &AppState, user_id: Uuid) -> Result<String, MemberServiceError> {
+    match UserRepo::new(&state.db).get_by_id(user_id).await {
+        Ok(user) => Ok(user.map(|user| user.email).unwrap_or_default()),
+        Err(_) => {
+            tracing::error!(event = "member_user_lookup_failed", "DB error");
+            Err(MemberServiceError::DbError)
+        }
+    }
+}
+
+pub async fn list_members(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<Vec<MemberView>, MemberServiceError> {
+    let vault = load_vault(state, vault_id).await?;
+    let resource = format!("vaults/{vault_id}/members");
+    ensure_list_access(state, identity, &vault, &resource).await?;
+
+    let listings = match VaultMemberRepo::new(&state.db)
+        .list_with_emails(vault.id)
+        .await
+    {
+        Ok(listings) => listings,
+        Err(_) => {
+            tracing::error!(event = "members_list_failed", "DB error");
+            return Err(MemberServiceError::DbError);
+        }
+    };
+    let mut views: Vec<MemberView> = listings
+        .into_iter()
+        .map(|listing| MemberView {
+            member: listing.member,
+            email: listing.email,
+            group: listing.group_slug,
+        })
+        .collect();
+    views.sort_by(|a, b| a.email.cmp(&b.email).then_with(|| a.group.cmp(&b.group)));
+    Ok(views)
+}
+
+async fn load_group_grants(
+    state: &AppState,
+    vault: &Vault,
+) -> Result<Vec<GroupGrantView>, MemberServiceError> {
+    match VaultGroupGrantRepo::new(&state.db)
+        .list_by_vault(vault.id)
+        .await
+    {
+        Ok(listings) => Ok(listings
+            .into_iter()
+            .map(|listing| GroupGrantView {
+                grant: listing.grant,
+                slug: listing.slug,
+                name: listing.name,
+            })
+            .collect()),
+        Err(_) => {
+            tracing::error!(event = "group_grants_list_failed", "DB error");
+            Err(MemberServiceError::DbError)
+        }
+    }
+}
+
+pub async fn add_member(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    cmd: AddMemberCommand,
+) -> Result<MemberView, MemberServiceError> {
+    let vault = load_vault(state, vault_id).await?;
+    let resource = format!("vaults/{vault_id}/members");
+    ensure_manager(state, identity, &vault, &resource).await?;
+    let role = parse_role(&cmd.role)?;
+    let user = load_user(state, &cmd).await?;
+
+    let repo = VaultMemberRepo::new(&state.db);
+    match repo.get(vault.id, user.id).await {
+        Ok(Some(_)) => return Err(MemberServiceError::Conflict("already_member")),
+        Ok(None) => {}
+        Err(_) => {
+            tracing::error!(event = "member_add_failed", "DB error");
+            return Err(MemberServiceError::DbError);
+        }
+    }
+    let member = VaultMember {
+        vault_id: vault.id,
+        user_id: user.id,
+        role,
+        created_at: Utc::now(),
+    };
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "member_add_failed", error = %err, "DB error");
+        MemberServiceError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    if let Err(err) = VaultMemberRepo::create_in(&mut tx, &member).await {
+        if err
+            .as_database_error()
+            .is_some_and(|db_err| db_err.is_unique_violation())
+        {
+            return Err(MemberServiceError::Conflict("already_member"));
+        }
+        return Err(db_error(err));
+    }
+    let entry = member_audit_entry(
+        "add",
+        vault.id,
+        &user.id.to_string(),
+        &format!("role={}", role.as_str()),
+    );
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
+
+    tracing::info!(
+        event = "member_added",
+        vault_id = %vault.id,
+        user_id = %user.id,
+        role = role.as_str(),
+        "Vault member added"
+    );
+    Ok(MemberView {
+        member,
+        email: user.email,
+        group: None,
+    })
+}
+
+/// Applies a role change or removal to a direct membership or a group grant
+/// while holding locks on both, and refuses it when it would leave the vault
+/// without an admin. Admin group grants count towards keeping one only while
+/// the group has an active member. Returns
+/// the grant's role and creation time as they were before the change.
+///
+/// The change is audited as `action` on `target` inside the same transaction.
+async fn change_membership(
+    state: &AppState,
+    identity: &Identity,
+    vault: &Vault,
+    grantee: Grantee,
+    new_role: Option<VaultMemberRole>,
+    action: &str,
+    target: &str,
+) -> Result<(VaultMemberRole, DateTime<Utc>), MemberServiceError> {
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "member_change_failed", error = %err, "DB error");
+        MemberServiceError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    apply_tx_isolation(&mut tx, state.db_tx_isolation)
+        .await
+        .map_err(db_error)?;
+
+    let members = VaultMemberRepo::lock_by_vault(&mut tx, vault.id)
+        .await
+        .map_err(db_error)?;
+    let grants = VaultGroupGrantRepo::lock_by_vault(&mut tx, vault.id)
+        .await
+        .map_err(db_error)?;
+    let admin_groups: Vec<Uuid> = grants
+        .iter()
+        .filter(|grant| grant.role == VaultMemberRole::Admin)
+        .map(|grant| grant.group_id)
+        .collect();
+    let staffed_groups = GroupMemberRepo::with_active_members_in(&mut tx, &admin_groups)
+        .await
+        .map_err(db_error)?;
+    let roles = members
+        .iter()
+        .map(|member| {
+            (
+                Grantee::User(member.user_id),
+                member.role,
+                member.created_at,
+            )
+        })
+        .chain(
+            grants
+                .iter()
+                .map(|grant| (Grantee::Group(grant.group_id), grant.role, grant.created_at)),
+        );
+    let mut current = None;
+    let mut other_admins = 0;
+    for (holder, role, created_at) in roles {
+        if holder == grantee {
+            current = Some((role, created_at));
+        } else if role == VaultMemberRole::Admin
+            && match holder {
+                Grantee::User(_) => true,
+                Grantee::Group(group_id) => staffed_groups.contains(&group_id),
+            }
+        {
+            other_admins += 1;
+        }
+    }
+    let Some((role, created_at)) = current else {
+        return Err(MemberServiceError::NotFound);
+    };
+    let demotes_admin = role == VaultMemberRole::Admin
+        && new_role.is_none_or(|role| role != VaultMemberRole::Admin);
+    if demotes_admin && other_admins == 0 {
+        return Err(MemberServiceError::Conflict("last_admin"));
+    }
+
+    match (grantee, new_role) {
+        (Grantee::User(user_id), Some(role)) => {
+            VaultMemberRepo::update_role_in(&mut tx, vault.id, user_id, role).await
+        }
+        (Grantee::User(user_id), None) => {
+            VaultMemberRepo::delete_in(&mut tx, vault.id, user_id).await
+        }
+        (Grantee::Group(group_id), Some(role)) => {
+            VaultGroupGrantRepo::update_role_in(&mut tx, vault.id, group_id, role).await
+        }
+        (Grantee::Group(group_id), None) => {
+            VaultGroupGrantRepo::delete_in(&mut tx, vault.id, group_id).await
+        }
+    }
+    .map_err(db_error)?;
+    let detail = match new_role {
+        Some(new_role) => format!("role={}->{}", role.as_str(), new_role.as_str()),
+        None => format!("role={}", role.as_str()),
+    };
+    let entry = member_audit_entry(action, vault.id, target, &detail);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
+    Ok((role, created_at))
+}
+
+pub async fn update_member_role(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    user_id: &str,
+    role: &str,
+) -> Result<MemberView, MemberServiceError> {
+    let vault = load_vault(state, vault_id).await?;
+    let resource = format!("vaults/{vault_id}/members/{user_id}");
+    ensure_manager(state, identity, &vault, &resource).await?;
+    let user_id = parse_user_id(user_id)?;
+    let role = parse_role(role)?;
+
+    let (previous, created_at) = change_membership(
+        state,
+        identity,
+        &vault,
+        Grantee::User(user_id),
+        Some(role),
+        "update_role",
+        &user_id.to_string(),
+    )
+    .await?;
+    let member = VaultMember {
+        vault_id: vault.id,
+        user_id,
+        role,
+        created_at,
+    };
+    tracing::info!(
+        event = "member_role_updated",
+        vault_id = %vault.id,
+        user_id = %user_id,
+        from = previous.as_str(),
+        to = role.as_str(),
+        "Vault member role updated"
+    );
+    let email = member_email(state, user_id).await?;
+    Ok(MemberView {
+        member,
+        email,
+        group: None,
+    })
+}
+
+pub async fn remove_member(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    user_id: &str,
+) -> Result<VaultMember, MemberServiceError> {
+    let vault = load_vault(state, vault_id).await?;
+    let resource = format!("vaults/{vault_id}/members/{user_id}");
+    ensure_manager(state, identity, &vault, &resource).await?;
+    let user_id = parse_user_id(user_id)?;
+
+    let (role, created_at) = change_membership(
+        state,
+        identity,
+        &vault,
+        Grantee::User(user_id),
+        None,
+        "remove",
+        &user_id.to_string(),
+    )
+    .await?;
+    tracing::info!(
+        event = "member_removed",
+        vault_id = %vault.id,
+        user_id = %user_id,
+        "Vault member removed"
+    );
+    Ok(VaultMember {
+        vault_id: vault.id,
+        user_id,
+        role,
+        created_at,
+    })
+}
+
+async fn load_group(state: &AppState, slug: &str) -> Result<Group, MemberServiceError> {
+    match GroupRepo::new(&state.db).get_by_slug(slug.trim()).await {
+        Ok(Some(group)) => Ok(group),
+        Ok(None) => Err(MemberServiceError::NotFound),
+        Err(_) => {
+            tracing::error!(event = "group_grant_group_lookup_failed", "DB error");
+            Err(MemberServiceError::DbError)
+        }
+    }
+}
+
+pub async fn list_group_grants(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<Vec<GroupGrantView>, MemberServiceError> {
+    let vault = load_vault(state, vault_id).await?;
+    let resource = format!("vaults/{vault_id}/members");
+    ensure_list_access(state, identity, &vault, &resource).await?;
+    load_group_grants(state, &vault).await
+}
+
+pub async fn grant_group(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    group: &str,
+    role: &str,
+) -> Result<GroupGrantView, MemberServiceError> {
+    let vault = load_vault(state, vault_id).await?;
+    let resource = format!("vaults/{vault_id}/members");
+    ensure_manager(state, identity, &vault, &resource).await?;
+    let role = parse_role(role)?;
+    let group = load_group(state, group).await?;
+
+    let repo = VaultGroupGrantRepo::new(&state.db);
+    match repo.get(vault.id, group.id).await {
+        Ok(Some(_)) => return Err(MemberServiceError::Conflict("already_granted")),
+        Ok(None) => {}
+        Err(_) => {
+            tracing::error!(event = "group_grant_failed", "DB error");
+            return Err(MemberServiceError::DbError);
+        }
+    }
+    let grant = VaultGroupGrant {
+        vault_id: vault.id,
+        group_id: group.id,
+        role,
+        created_at: Utc::now(),
+    };
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "group_grant_failed", error = %err, "DB error");
+        MemberServiceError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    if let Err(err) = VaultGroupGrantRepo::create_in(&mut tx, &grant).await {
+        if err
+            .as_database_error()
+            .is_some_and(|db_err| db_err.is_unique_violation())
+        {
+            return Err(MemberServiceError::Conflict("already_granted"));
+        }
+        return Err(db_error(err));
+    }
+    let entry = member_audit_entry(
+        "grant_group",
+        vault.id,
+        &format!("group:{}", group.slug),
+        &format!("role={}", role.as_str()),
+    );
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
+
+    tracing::info!(
+        event = "group_granted",
+        vault_id = %vault.id,
+        group_id = %group.id,
+        role = role.as_str(),
+        "Vault access granted to group"
+    );
+    Ok(GroupGrantView {
+        grant,
+        slug: group.slug,
+        name: group.name,
+    })
+}
+
+pub async fn update_group_grant(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    group: &str,
+    role: &str,
+) -> Result<GroupGrantView, MemberServiceError> {
+    let vault = load_vault(state, vault_id).await?;
+    let resource = format!("vaults/{vault_id}/members");
+    ensure_manager(state, identity, &vault, &resource).await?;
+    let role = parse_role(role)?;
+    let group = load_group(state, group).await?;
+
+    let target = format!("group:{}", group.slug);
+    let (previous, created_at) = change_membership(
+        state,
+        identity,
+        &vault,
+        Grantee::Group(group.id),
+        Some(role),
+        "update_group",
+        &target,
+    )
+    .await?;
+    tracing::info!(
+        event = "group_grant_updated",
+        vault_id = %vault.id,
+        group_id = %group.id,
+        from = previous.as_str(),
+        to = role.as_str(),
+        "Vault group grant updated"
+    );
+    Ok(GroupGrantView {
+        grant: VaultGroupGrant {
+            vault_id: vault.id,
+            group_id: group.id,
+            role,
+            created_at,
+        },
+        slug: group.slug,
+        name: group.name,
+    })
+}
+
+pub async fn revoke_group(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    group: &str,
+) -> Result<VaultGroupGrant, MemberServiceError> {
+    let vault = load_vault(state, vault_id).await?;
+    let resource = format!("vaults/{vault_id}/members");
+    ensure_manager(state, identity, &vault, &resource).await?;
+    let group = load_group(state, group).await?;
+
+    let target = format!("group:{}", group.slug);
+    let (role, created_at) = change_membership(
+        state,
+        identity,
+        &vault,
+        Grantee::Group(group.id),
+        None,
+        "revoke_group",
+        &target,
+    )
+    .await?;
+    tracing::info!(
+        event = "group_revoked",
+        vault_id = %vault.id,
+        group_id = %group.id,
+        "Vault group grant revoked"
+    );
+    Ok(VaultGroupGrant {
+        vault_id: vault.id,
+        group_id: group.id,
+        role,
+        created_at,
+    })
+}
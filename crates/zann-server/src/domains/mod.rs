@@ -1,12 +1,20 @@
 pub mod access_control;
+pub mod audit;
 pub mod auth;
 pub mod devices;
+pub mod dynamic;
 pub mod errors;
 pub mod groups;
+pub mod invites;
 pub mod items;
 pub mod members;
+pub mod pki;
 pub mod secrets;
+pub mod service_accounts;
+pub mod ssh;
 pub mod sync;
 pub mod system;
+pub mod transit;
 pub mod users;
 pub mod vaults;
+pub mod webhooks;
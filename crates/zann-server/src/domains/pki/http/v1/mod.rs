@@ -0,0 +1,636 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zann_core::{Identity, PkiCa, PkiCertificate, PkiRole};
+
+use crate::app::AppState;
+use crate::domains::pki::service::{
+    self, CreateCaCommand, IssueCommand, IssuedCertificate, PkiCaChain, PkiCaKind, PkiError,
+    PkiKeyType, SetPkiRoleCommand,
+};
+use crate::infra::audit::{self, AuditEntry};
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    pub(crate) error: &'static str,
+}
+
+#[derive(Deserialize, JsonSchema, Default)]
+pub(crate) struct CreatePkiCaRequest {
+    /// Subject common name of a generated CA.
+    #[serde(default)]
+    pub(crate) common_name: Option<String>,
+    #[serde(default)]
+    pub(crate) key_type: PkiKeyType,
+    /// Validity of a generated CA, e.g. `8760h`.
+    #[serde(default)]
+    pub(crate) ttl: Option<String>,
+    /// PEM certificate to import; requires `private_key`.
+    #[serde(default)]
+    pub(crate) certificate: Option<String>,
+    /// Unencrypted PKCS#8 PEM private key matching `certificate`.
+    #[serde(default)]
+    pub(crate) private_key: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct SetPkiRoleRequest {
+    /// Domains leaf names must match; `*` allows any name.
+    pub(crate) allowed_domains: Vec<String>,
+    /// Also allow names below each allowed domain, including wildcards.
+    #[serde(default)]
+    pub(crate) allow_subdomains: bool,
+    #[serde(default)]
+    pub(crate) allow_ip_sans: bool,
+    #[serde(default)]
+    pub(crate) key_type: PkiKeyType,
+    #[serde(default = "default_true")]
+    pub(crate) server_auth: bool,
+    #[serde(default)]
+    pub(crate) client_auth: bool,
+    #[serde(default)]
+    pub(crate) default_ttl: Option<String>,
+    #[serde(default)]
+    pub(crate) max_ttl: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct PkiIssueRequest {
+    pub(crate) role: String,
+    pub(crate) common_name: String,
+    #[serde(default)]
+    pub(crate) alt_names: Vec<String>,
+    #[serde(default)]
+    pub(crate) ip_sans: Vec<String>,
+    #[serde(default)]
+    pub(crate) ttl: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct ListPkiCertificatesQuery {
+    #[serde(default)]
+    pub(crate) limit: Option<i64>,
+    #[serde(default)]
+    pub(crate) offset: Option<i64>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PkiCaResponse {
+    pub(crate) id: String,
+    pub(crate) kind: PkiCaKind,
+    pub(crate) common_name: String,
+    pub(crate) serial: String,
+    pub(crate) certificate: String,
+    pub(crate) not_after: String,
+    pub(crate) created_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PkiChainResponse {
+    pub(crate) root: Option<PkiCaResponse>,
+    pub(crate) intermediate: Option<PkiCaResponse>,
+    /// PEM certificates from the issuing CA up to the root.
+    pub(crate) ca_chain: Vec<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PkiRoleResponse {
+    pub(crate) name: String,
+    pub(crate) allowed_domains: Vec<String>,
+    pub(crate) allow_subdomains: bool,
+    pub(crate) allow_ip_sans: bool,
+    pub(crate) key_type: String,
+    pub(crate) server_auth: bool,
+    pub(crate) client_auth: bool,
+    pub(crate) default_ttl_seconds: i64,
+    pub(crate) max_ttl_seconds: i64,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PkiRoleListResponse {
+    pub(crate) roles: Vec<PkiRoleResponse>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PkiCertificateResponse {
+    pub(crate) serial: String,
+    pub(crate) role: String,
+    pub(crate) common_name: String,
+    pub(crate) sans: Vec<String>,
+    pub(crate) certificate: String,
+    pub(crate) not_before: String,
+    pub(crate) not_after: String,
+    pub(crate) revoked_at: Option<String>,
+    pub(crate) created_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PkiCertificateListResponse {
+    pub(crate) certificates: Vec<PkiCertificateResponse>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PkiIssueResponse {
+    pub(crate) serial: String,
+    pub(crate) common_name: String,
+    pub(crate) sans: Vec<String>,
+    pub(crate) certificate: String,
+    /// PKCS#8 PEM private key. The server does not keep a copy.
+    pub(crate) private_key: String,
+    pub(crate) issuing_ca: String,
+    pub(crate) ca_chain: Vec<String>,
+    pub(crate) not_before: String,
+    pub(crate) not_after: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct PkiCrlResponse {
+    /// PEM-encoded CRL signed by the issuing CA.
+    pub(crate) crl: String,
+    pub(crate) issuer_serial: String,
+    pub(crate) revoked: usize,
+    pub(crate) this_update: String,
+    pub(crate) next_update: String,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/v1/vaults/:vault_id/pki/ca",
+            get(get_chain).delete(delete_cas),
+        )
+        .route("/v1/vaults/:vault_id/pki/root", post(create_root))
+        .route(
+            "/v1/vaults/:vault_id/pki/intermediate",
+            post(create_intermediate),
+        )
+        .route("/v1/vaults/:vault_id/pki/roles", get(list_roles))
+        .route(
+            "/v1/vaults/:vault_id/pki/roles/:role",
+            get(get_role).put(set_role).delete(delete_role),
+        )
+        .route("/v1/vaults/:vault_id/pki/issue", post(issue))
+        .route("/v1/vaults/:vault_id/pki/certs", get(list_certificates))
+        .route(
+            "/v1/vaults/:vault_id/pki/certs/:serial",
+            get(get_certificate),
+        )
+        .route(
+            "/v1/vaults/:vault_id/pki/certs/:serial/revoke",
+            post(revoke),
+        )
+        .route("/v1/vaults/:vault_id/pki/crl", get(crl))
+}
+
+fn map_pki_error(error: PkiError) -> axum::response::Response {
+    match error {
+        PkiError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        PkiError::Forbidden(code) => {
+            (StatusCode::FORBIDDEN, Json(ErrorResponse { error: code })).into_response()
+        }
+        PkiError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        PkiError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        PkiError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        PkiError::DbError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response(),
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+fn ca_response(ca: PkiCa) -> PkiCaResponse {
+    PkiCaResponse {
+        id: ca.id.to_string(),
+        kind: if ca.kind == PkiCaKind::Root.as_str() {
+            PkiCaKind::Root
+        } else {
+            PkiCaKind::Intermediate
+        },
+        common_name: ca.common_name,
+        serial: ca.serial,
+        certificate: ca.certificate_pem,
+        not_after: ca.not_after.to_rfc3339(),
+        created_at: ca.created_at.to_rfc3339(),
+    }
+}
+
+fn chain_response(chain: PkiCaChain) -> PkiChainResponse {
+    let ca_chain = chain.pem_chain();
+    PkiChainResponse {
+        root: chain.root.map(ca_response),
+        intermediate: chain.intermediate.map(ca_response),
+        ca_chain,
+    }
+}
+
+fn role_response(role: PkiRole) -> PkiRoleResponse {
+    PkiRoleResponse {
+        name: role.name,
+        allowed_domains: role.allowed_domains.0,
+        allow_subdomains: role.allow_subdomains,
+        allow_ip_sans: role.allow_ip_sans,
+        key_type: role.key_type,
+        server_auth: role.server_auth,
+        client_auth: role.client_auth,
+        default_ttl_seconds: role.default_ttl_seconds,
+        max_ttl_seconds: role.max_ttl_seconds,
+        created_at: role.created_at.to_rfc3339(),
+        updated_at: role.updated_at.to_rfc3339(),
+    }
+}
+
+fn certificate_response(cert: PkiCertificate) -> PkiCertificateResponse {
+    PkiCertificateResponse {
+        serial: cert.serial,
+        role: cert.role_name,
+        common_name: cert.common_name,
+        sans: cert.sans.0,
+        certificate: cert.certificate_pem,
+        not_before: cert.not_before.to_rfc3339(),
+        not_after: cert.not_after.to_rfc3339(),
+        revoked_at: cert.revoked_at.map(|value| value.to_rfc3339()),
+        created_at: cert.created_at.to_rfc3339(),
+    }
+}
+
+fn issue_response(issued: IssuedCertificate) -> PkiIssueResponse {
+    let cert = issued.certificate;
+    PkiIssueResponse {
+        serial: cert.serial,
+        common_name: cert.common_name,
+        sans: cert.sans.0,
+        certificate: cert.certificate_pem,
+        private_key: issued.private_key,
+        issuing_ca: issued.ca_chain.first().cloned().unwrap_or_default(),
+        ca_chain: issued.ca_chain,
+        not_before: cert.not_before.to_rfc3339(),
+        not_after: cert.not_after.to_rfc3339(),
+    }
+}
+
+fn create_command(payload: CreatePkiCaRequest) -> CreateCaCommand {
+    CreateCaCommand {
+        common_name: payload.common_name,
+        key_type: payload.key_type,
+        ttl: payload.ttl,
+        certificate: payload.certificate,
+        private_key: payload.private_key,
+    }
+}
+
+async fn record(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    result: &str,
+    vault_id: &str,
+    target: &str,
+    detail: Option<&str>,
+) {
+    let mut entry = AuditEntry::new("pki", action, result)
+        .target(target)
+        .detail(detail);
+    if let Some(vault_id) = audit::resolve_vault_id(state, vault_id).await {
+        entry = entry.vault(vault_id);
+    }
+    audit::record(state, Some(identity), entry).await;
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_chain(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+) -> impl IntoResponse {
+    match service::get_chain(&state, &identity, &vault_id).await {
+        Ok(chain) => (StatusCode::OK, Json(chain_response(chain))).into_response(),
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn create_root(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    payload: Option<Json<CreatePkiCaRequest>>,
+) -> impl IntoResponse {
+    let Json(payload) = payload.unwrap_or_default();
+    create_ca(state, identity, vault_id, PkiCaKind::Root, payload).await
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn create_intermediate(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    payload: Option<Json<CreatePkiCaRequest>>,
+) -> impl IntoResponse {
+    let Json(payload) = payload.unwrap_or_default();
+    create_ca(state, identity, vault_id, PkiCaKind::Intermediate, payload).await
+}
+
+async fn create_ca(
+    state: AppState,
+    identity: Identity,
+    vault_id: String,
+    kind: PkiCaKind,
+    payload: CreatePkiCaRequest,
+) -> axum::response::Response {
+    let detail = if payload.certificate.is_some() {
+        "imported"
+    } else {
+        "generated"
+    };
+    let command = create_command(payload);
+    let result = match kind {
+        PkiCaKind::Root => service::create_root(&state, &identity, &vault_id, command).await,
+        PkiCaKind::Intermediate => {
+            service::create_intermediate(&state, &identity, &vault_id, command).await
+        }
+    };
+    match result {
+        Ok(ca) => {
+            record(
+                &state,
+                &identity,
+                &format!("pki_{}_create", kind.as_str()),
+                "ok",
+                &vault_id,
+                &ca.common_name,
+                Some(detail),
+            )
+            .await;
+            (StatusCode::CREATED, Json(ca_response(ca))).into_response()
+        }
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn delete_cas(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+) -> impl IntoResponse {
+    match service::delete_cas(&state, &identity, &vault_id).await {
+        Ok(()) => {
+            record(
+                &state,
+                &identity,
+                "pki_ca_delete",
+                "ok",
+                &vault_id,
+                "ca",
+                None,
+            )
+            .await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn list_roles(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+) -> impl IntoResponse {
+    match service::list_roles(&state, &identity, &vault_id).await {
+        Ok(roles) => (
+            StatusCode::OK,
+            Json(PkiRoleListResponse {
+                roles: roles.into_iter().map(role_response).collect(),
+            }),
+        )
+            .into_response(),
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_role(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::get_role(&state, &identity, &vault_id, &name).await {
+        Ok(role) => (StatusCode::OK, Json(role_response(role))).into_response(),
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn set_role(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+    Json(payload): Json<SetPkiRoleRequest>,
+) -> impl IntoResponse {
+    let command = SetPkiRoleCommand {
+        allowed_domains: payload.allowed_domains,
+        allow_subdomains: payload.allow_subdomains,
+        allow_ip_sans: payload.allow_ip_sans,
+        key_type: payload.key_type,
+        server_auth: payload.server_auth,
+        client_auth: payload.client_auth,
+        default_ttl: payload.default_ttl,
+        max_ttl: payload.max_ttl,
+    };
+    match service::set_role(&state, &identity, &vault_id, &name, command).await {
+        Ok(role) => {
+            let detail = role.allowed_domains.join(",");
+            record(
+                &state,
+                &identity,
+                "pki_role_set",
+                "ok",
+                &vault_id,
+                &role.name,
+                Some(&detail),
+            )
+            .await;
+            (StatusCode::OK, Json(role_response(role))).into_response()
+        }
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn delete_role(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::delete_role(&state, &identity, &vault_id, &name).await {
+        Ok(()) => {
+            record(
+                &state,
+                &identity,
+                "pki_role_delete",
+                "ok",
+                &vault_id,
+                &name,
+                None,
+            )
+            .await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn issue(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    Json(payload): Json<PkiIssueRequest>,
+) -> impl IntoResponse {
+    let role_name = payload.role.clone();
+    let command = IssueCommand {
+        role: payload.role,
+        common_name: payload.common_name,
+        alt_names: payload.alt_names,
+        ip_sans: payload.ip_sans,
+        ttl: payload.ttl,
+    };
+    match service::issue(&state, &identity, &vault_id, command).await {
+        Ok(issued) => {
+            let detail = format!(
+                "serial={} names={}",
+                issued.certificate.serial,
+                issued.certificate.sans.join(",")
+            );
+            record(
+                &state,
+                &identity,
+                "pki_issue",
+                "ok",
+                &vault_id,
+                &role_name,
+                Some(&detail),
+            )
+            .await;
+            (StatusCode::OK, Json(issue_response(issued))).into_response()
+        }
+        Err(error) => {
+            let label = error.label();
+            record(
+                &state,
+                &identity,
+                "pki_issue",
+                label,
+                &vault_id,
+                &role_name,
+                Some(label),
+            )
+            .await;
+            map_pki_error(error)
+        }
+    }
+}
+
+#[tracing::instrument(skip(state, identity, query))]
+async fn list_certificates(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    Query(query): Query<ListPkiCertificatesQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let offset = query.offset.unwrap_or(0).max(0);
+    match service::list_certificates(&state, &identity, &vault_id, limit, offset).await {
+        Ok(certificates) => (
+            StatusCode::OK,
+            Json(PkiCertificateListResponse {
+                certificates: certificates.into_iter().map(certificate_response).collect(),
+            }),
+        )
+            .into_response(),
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_certificate(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, serial)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::get_certificate(&state, &identity, &vault_id, &serial).await {
+        Ok(cert) => (StatusCode::OK, Json(certificate_response(cert))).into_response(),
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn revoke(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, serial)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::revoke(&state, &identity, &vault_id, &serial).await {
+        Ok(cert) => {
+            record(
+                &state,
+                &identity,
+                "pki_revoke",
+                "ok",
+                &vault_id,
+                &cert.serial,
+                Some(&cert.common_name),
+            )
+            .await;
+            (StatusCode::OK, Json(certificate_response(cert))).into_response()
+        }
+        Err(error) => map_pki_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn crl(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+) -> impl IntoResponse {
+    match service::crl(&state, &identity, &vault_id).await {
+        Ok(signed) => (
+            StatusCode::OK,
+            Json(PkiCrlResponse {
+                crl: signed.crl,
+                issuer_serial: signed.issuer.serial,
+                revoked: signed.revoked,
+                this_update: signed.this_update.to_rfc3339(),
+                next_update: signed.next_update.to_rfc3339(),
+            }),
+        )
+            .into_response(),
+        Err(error) => map_pki_error(error),
+    }
+}
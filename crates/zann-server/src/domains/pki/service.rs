@@ -0,0 +1,1160 @@
+//! X.509 PKI: each shared vault can hold a root CA and an intermediate CA.
+//! The intermediate, or the root when there is none, issues leaf certificates
+//! under roles that bound names, key type and validity, and signs the CRL.
+
+use std::net::IpAddr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use rcgen::{
+    BasicConstraints, CertificateParams, CertificateRevocationListParams, DistinguishedName,
+    DnType, ExtendedKeyUsagePurpose, IsCa, Issuer, KeyIdMethod, KeyPair, KeyUsagePurpose,
+    RevokedCertParams, SanType, SerialNumber, SignatureAlgorithm,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx_core::types::Json;
+use time::OffsetDateTime;
+use uuid::Uuid;
+use x509_parser::certificate::X509Certificate;
+use zann_core::{Identity, PkiCa, PkiCertificate, PkiRole, Vault};
+use zann_crypto::vault_crypto as core_crypto;
+use zann_db::repo::{PkiCaRepo, PkiCertificateRepo, PkiRoleRepo};
+
+use crate::app::AppState;
+use crate::domains::access_control::http::VaultScope;
+use crate::domains::errors::ServiceError;
+use crate::domains::secrets::service::{
+    authorize_vault_access, ensure_server_encryption, normalize_name, result_label,
+    server_vault_key,
+};
+use crate::domains::service_accounts::tokens::parse_ttl;
+use crate::infra::metrics;
+
+pub type PkiError = ServiceError;
+
+const ROOT_TTL_SECONDS: i64 = 10 * 365 * 24 * 60 * 60;
+const INTERMEDIATE_TTL_SECONDS: i64 = 5 * 365 * 24 * 60 * 60;
+const DEFAULT_TTL_SECONDS: i64 = 24 * 60 * 60;
+const DEFAULT_MAX_TTL_SECONDS: i64 = 30 * 24 * 60 * 60;
+const MIN_TTL_SECONDS: i64 = 60;
+/// Certificates are backdated so peers with a slightly slow clock accept them.
+const CLOCK_SKEW_SECONDS: i64 = 60;
+const CRL_LIFETIME_SECONDS: i64 = 24 * 60 * 60;
+const SERIAL_LEN: usize = 16;
+const MAX_NAME_LEN: usize = 253;
+const ANY: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PkiCaKind {
+    Root,
+    Intermediate,
+}
+
+impl PkiCaKind {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Root => "root",
+            Self::Intermediate => "intermediate",
+        }
+    }
+
+    fn default_ttl_seconds(self) -> i64 {
+        match self {
+            Self::Root => ROOT_TTL_SECONDS,
+            Self::Intermediate => INTERMEDIATE_TTL_SECONDS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum PkiKeyType {
+    #[default]
+    #[serde(rename = "ec-p256")]
+    EcP256,
+    #[serde(rename = "ec-p384")]
+    EcP384,
+    #[serde(rename = "ed25519")]
+    Ed25519,
+}
+
+impl PkiKeyType {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::EcP256 => "ec-p256",
+            Self::EcP384 => "ec-p384",
+            Self::Ed25519 => "ed25519",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ec-p256" => Some(Self::EcP256),
+            "ec-p384" => Some(Self::EcP384),
+            "ed25519" => Some(Self::Ed25519),
+            _ => None,
+        }
+    }
+
+    fn algorithm(self) -> &'static SignatureAlgorithm {
+        match self {
+            Self::EcP256 => &rcgen::PKCS_ECDSA_P256_SHA256,
+            Self::EcP384 => &rcgen::PKCS_ECDSA_P384_SHA384,
+            Self::Ed25519 => &rcgen::PKCS_ED25519,
+        }
+    }
+}
+
+/// Generates a CA, or imports one when both `certificate` and `private_key`
+/// are set.
+#[derive(Debug, Clone, Default)]
+pub struct CreateCaCommand {
+    pub common_name: Option<String>,
+    pub key_type: PkiKeyType,
+    pub ttl: Option<String>,
+    pub certificate: Option<String>,
+    pub private_key: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SetPkiRoleCommand {
+    pub allowed_domains: Vec<String>,
+    pub allow_subdomains: bool,
+    pub allow_ip_sans: bool,
+    pub key_type: PkiKeyType,
+    pub server_auth: bool,
+    pub client_auth: bool,
+    pub default_ttl: Option<String>,
+    pub max_ttl: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IssueCommand {
+    pub role: String,
+    pub common_name: String,
+    pub alt_names: Vec<String>,
+    pub ip_sans: Vec<String>,
+    pub ttl: Option<String>,
+}
+
+/// The vault's CAs. Leaf certificates come from `intermediate` when set.
+#[derive(Debug, Clone)]
+pub struct PkiCaChain {
+    pub root: Option<PkiCa>,
+    pub intermediate: Option<PkiCa>,
+}
+
+impl PkiCaChain {
+    #[must_use]
+    pub fn issuer(&self) -> Option<&PkiCa> {
+        self.intermediate.as_ref().or(self.root.as_ref())
+    }
+
+    /// PEM certificates from the issuing CA up to the root.
+    #[must_use]
+    pub fn pem_chain(&self) -> Vec<String> {
+        self.intermediate
+            .iter()
+            .chain(self.root.iter())
+            .map(|ca| ca.certificate_pem.clone())
+            .collect()
+    }
+}
+
+/// A leaf certificate with its private key, which the server does not keep.
+#[derive(Debug, Clone)]
+pub struct IssuedCertificate {
+    pub certificate: PkiCertificate,
+    pub private_key: String,
+    pub ca_chain: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignedCrl {
+    pub crl: String,
+    pub issuer: PkiCa,
+    pub revoked: usize,
+    pub this_update: DateTime<Utc>,
+    pub next_update: DateTime<Utc>,
+}
+
+fn ca_resource(vault_id: &str) -> String {
+    format!("vaults/{vault_id}/pki/ca")
+}
+
+fn role_resource(vault_id: &str, name: &str) -> String {
+    format!("vaults/{vault_id}/pki/roles/{name}")
+}
+
+fn role_scope_path(name: &str) -> String {
+    format!("pki/roles/{name}")
+}
+
+/// Lowercases and strips `:` separators so `AB:CD` and `abcd` name the same
+/// certificate.
+#[must_use]
+pub fn normalize_serial(serial: &str) -> String {
+    serial
+        .trim()
+        .chars()
+        .filter(|c| *c != ':')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+pub async fn get_chain(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<PkiCaChain, PkiError> {
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "read",
+        &ca_resource(vault_id),
+        "pki/ca",
+        VaultScope::Items,
+    )
+    .await?;
+    load_chain(state, &vault).await
+}
+
+pub async fn create_root(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    cmd: CreateCaCommand,
+) -> Result<PkiCa, PkiError> {
+    let vault =
+        authorize_configure(state, identity, vault_id, &ca_resource(vault_id), "pki/ca").await?;
+    let ca = match imported(&cmd)? {
+        Some((certificate, private_key)) => {
+            import_ca(&vault, PkiCaKind::Root, certificate, private_key, None)?
+        }
+        None => generate_ca(&vault, PkiCaKind::Root, &cmd, None)?,
+    };
+    store_ca(state, &vault, ca).await
+}
+
+/// Creates the intermediate. A generated one is signed by the vault root; an
+/// imported one must be issued by the vault root when there is one.
+pub async fn create_intermediate(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    cmd: CreateCaCommand,
+) -> Result<PkiCa, PkiError> {
+    let vault =
+        authorize_configure(state, identity, vault_id, &ca_resource(vault_id), "pki/ca").await?;
+    let chain = load_chain(state, &vault).await?;
+    let root = match &chain.root {
+        Some(root) => Some((root, ca_key_pair(state, &vault, root)?)),
+        None => None,
+    };
+    let ca = match imported(&cmd)? {
+        Some((certificate, private_key)) => import_ca(
+            &vault,
+            PkiCaKind::Intermediate,
+            certificate,
+            private_key,
+            root.as_ref().map(|(root, _)| *root),
+        )?,
+        None => {
+            let Some((root, root_key)) = &root else {
+                return Err(PkiError::BadRequest("root_ca_required"));
+            };
+            generate_ca(
+                &vault,
+                PkiCaKind::Intermediate,
+                &cmd,
+                Some((*root, root_key)),
+            )?
+        }
+    };
+    store_ca(state, &vault, ca).await
+}
+
+/// Removes both CAs and the record of every certificate they issued. Issued
+/// certificates stay valid until they expire, so peers must stop trusting the
+/// chain as well.
+pub async fn delete_cas(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<(), PkiError> {
+    let vault =
+        authorize_configure(state, identity, vault_id, &ca_resource(vault_id), "pki/ca").await?;
+    let deleted = PkiCaRepo::new(&state.db)
+        .delete_by_vault(vault.id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "pki_ca_delete_failed", error = %err, "DB error");
+            PkiError::DbError
+        })?;
+    if deleted == 0 {
+        return Err(PkiError::NotFound);
+    }
+    Ok(())
+}
+
+pub async fn set_role(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+    cmd: SetPkiRoleCommand,
+) -> Result<PkiRole, PkiError> {
+    let name = normalize_name(name, "invalid_role_name")?;
+    let vault = authorize_configure(
+        state,
+        identity,
+        vault_id,
+        &role_resource(vault_id, &name),
+        &role_scope_path(&name),
+    )
+    .await?;
+    let mut allowed_domains: Vec<String> = Vec::with_capacity(cmd.allowed_domains.len());
+    for domain in cmd.allowed_domains {
+        let domain = domain.trim().to_ascii_lowercase();
+        if domain != ANY && !is_dns_name(&domain) {
+            return Err(PkiError::BadRequest("invalid_domain"));
+        }
+        if !allowed_domains.contains(&domain) {
+            allowed_domains.push(domain);
+        }
+    }
+    if allowed_domains.is_empty() {
+        return Err(PkiError::BadRequest("allowed_domains_required"));
+    }
+    if !cmd.server_auth && !cmd.client_auth {
+        return Err(PkiError::BadRequest("key_usage_required"));
+    }
+    let default_ttl_seconds = match cmd.default_ttl.as_deref() {
+        Some(value) => parse_ttl_seconds(value)?,
+        None => DEFAULT_TTL_SECONDS,
+    };
+    let max_ttl_seconds = match cmd.max_ttl.as_deref() {
+        Some(value) => parse_ttl_seconds(value)?,
+        None => DEFAULT_MAX_TTL_SECONDS.max(default_ttl_seconds),
+    };
+    if default_ttl_seconds > max_ttl_seconds {
+        return Err(PkiError::BadRequest("default_ttl_exceeds_max"));
+    }
+
+    let now = Utc::now();
+    let role = PkiRole {
+        id: Uuid::now_v7(),
+        vault_id: vault.id,
+        name,
+        allowed_domains: Json(allowed_domains),
+        allow_subdomains: cmd.allow_subdomains,
+        allow_ip_sans: cmd.allow_ip_sans,
+        key_type: cmd.key_type.as_str().to_string(),
+        server_auth: cmd.server_auth,
+        client_auth: cmd.client_auth,
+        default_ttl_seconds,
+        max_ttl_seconds,
+        created_at: now,
+        updated_at: now,
+    };
+    PkiRoleRepo::new(&state.db)
+        .upsert(&role)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "pki_role_set_failed", error = %err, "DB error");
+            PkiError::DbError
+        })
+}
+
+pub async fn get_role(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+) -> Result<PkiRole, PkiError> {
+    let name = normalize_name(name, "invalid_role_name")?;
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "read",
+        &role_resource(vault_id, &name),
+        &role_scope_path(&name),
+        VaultScope::Items,
+    )
+    .await?;
+    load_role(state, &vault, &name).await
+}
+
+pub async fn list_roles(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<Vec<PkiRole>, PkiError> {
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "list",
+        &format!("vaults/{vault_id}/pki/roles"),
+        "pki/roles",
+        VaultScope::Items,
+    )
+    .await?;
+    PkiRoleRepo::new(&state.db)
+        .list_by_vault(vault.id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "pki_role_list_failed", error = %err, "DB error");
+            PkiError::DbError
+        })
+}
+
+pub async fn delete_role(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+) -> Result<(), PkiError> {
+    let name = normalize_name(name, "invalid_role_name")?;
+    let vault = authorize_configure(
+        state,
+        identity,
+        vault_id,
+        &role_resource(vault_id, &name),
+        &role_scope_path(&name),
+    )
+    .await?;
+    let deleted = PkiRoleRepo::new(&state.db)
+        .delete(vault.id, &name)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "pki_role_delete_failed", error = %err, "DB error");
+            PkiError::DbError
+        })?;
+    if deleted == 0 {
+        return Err(PkiError::NotFound);
+    }
+    Ok(())
+}
+
+/// Issues a leaf certificate for a fresh key within the limits of `cmd.role`.
+pub async fn issue(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    cmd: IssueCommand,
+) -> Result<IssuedCertificate, PkiError> {
+    let name = normalize_name(&cmd.role, "invalid_role_name")?;
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "pki_issue",
+        &role_resource(vault_id, &name),
+        &role_scope_path(&name),
+        VaultScope::Items,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    let role = load_role(state, &vault, &name).await?;
+    let chain = load_chain(state, &vault).await?;
+    let Some(issuer) = chain.issuer() else {
+        return Err(PkiError::NotFound);
+    };
+    let result = issue_leaf(state, &vault, issuer, &role, &cmd);
+    metrics::pki_certificates(&role.key_type, result_label(&result));
+    let (certificate, private_key) = result?;
+    PkiCertificateRepo::new(&state.db)
+        .create(&certificate)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "pki_certificate_store_failed", error = %err, "DB error");
+            PkiError::DbError
+        })?;
+    Ok(IssuedCertificate {
+        certificate,
+        private_key,
+        ca_chain: chain.pem_chain(),
+    })
+}
+
+fn issue_leaf(
+    state: &AppState,
+    vault: &Vault,
+    issuer: &PkiCa,
+    role: &PkiRole,
+    cmd: &IssueCommand,
+) -> Result<(PkiCertificate, String), PkiError> {
+    let key_type =
+        PkiKeyType::parse(&role.key_type).ok_or(PkiError::Internal("pki_role_invalid"))?;
+    let names = resolve_names(role, &cmd.common_name, &cmd.alt_names)?;
+    let ips = resolve_ips(role, &cmd.ip_sans)?;
+    let ttl_seconds = match cmd.ttl.as_deref() {
+        Some(value) => parse_ttl_seconds(value)?,
+        None => role.default_ttl_seconds,
+    }
+    .min(role.max_ttl_seconds);
+
+    let now = Utc::now();
+    let not_before = now - ChronoDuration::seconds(CLOCK_SKEW_SECONDS);
+    let not_after = (now + ChronoDuration::seconds(ttl_seconds)).min(issuer.not_after);
+    if not_after <= now {
+        return Err(PkiError::Conflict("issuer_expired"));
+    }
+    let (serial, serial_hex) = random_serial();
+
+    let mut params = CertificateParams::default();
+    params.distinguished_name = distinguished_name(&names[0]);
+    params.subject_alt_names = names
+        .iter()
+        .map(|name| {
+            name.as_str()
+                .try_into()
+                .map(SanType::DnsName)
+                .map_err(|_| PkiError::BadRequest("invalid_name"))
+        })
+        .chain(ips.iter().map(|ip| Ok(SanType::IpAddress(*ip))))
+        .collect::<Result<_, _>>()?;
+    params.is_ca = IsCa::ExplicitNoCa;
+    params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+    if role.server_auth {
+        params
+            .extended_key_usages
+            .push(ExtendedKeyUsagePurpose::ServerAuth);
+    }
+    if role.client_auth {
+        params
+            .extended_key_usages
+            .push(ExtendedKeyUsagePurpose::ClientAuth);
+    }
+    params.not_before = offset_date_time(not_before)?;
+    params.not_after = offset_date_time(not_after)?;
+    params.serial_number = Some(serial);
+    params.use_authority_key_identifier_extension = true;
+
+    let key = KeyPair::generate_for(key_type.algorithm()).map_err(|err| {
+        tracing::error!(event = "pki_key_generate_failed", error = %err, "Key generation failed");
+        PkiError::Internal("pki_key_generate_failed")
+    })?;
+    let issuer_key = ca_key_pair(state, vault, issuer)?;
+    let signer = Issuer::from_ca_cert_pem(&issuer.certificate_pem, issuer_key)
+        .map_err(|_| PkiError::Internal("pki_issuer_invalid"))?;
+    let certificate = params.signed_by(&key, &signer).map_err(|err| {
+        tracing::error!(event = "pki_issue_failed", error = %err, "Certificate signing failed");
+        PkiError::Internal("pki_issue_failed")
+    })?;
+
+    let sans = names
+        .iter()
+        .cloned()
+        .chain(ips.iter().map(ToString::to_string))
+        .collect();
+    let record = PkiCertificate {
+        id: Uuid::now_v7(),
+        vault_id: vault.id,
+        issuer_id: issuer.id,
+        serial: serial_hex,
+        role_name: role.name.clone(),
+        common_name: names[0].clone(),
+        sans: Json(sans),
+        certificate_pem: certificate.pem(),
+        not_before,
+        not_after,
+        revoked_at: None,
+        created_at: now,
+    };
+    Ok((record, key.serialize_pem()))
+}
+
+pub async fn list_certificates(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<PkiCertificate>, PkiError> {
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "list",
+        &format!("vaults/{vault_id}/pki/certs"),
+        "pki/certs",
+        VaultScope::Items,
+    )
+    .await?;
+    PkiCertificateRepo::new(&state.db)
+        .list_by_vault(vault.id, limit, offset)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "pki_certificate_list_failed", error = %err, "DB error");
+            PkiError::DbError
+        })
+}
+
+pub async fn get_certificate(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    serial: &str,
+) -> Result<PkiCertificate, PkiError> {
+    let serial = normalize_serial(serial);
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "read",
+        &format!("vaults/{vault_id}/pki/certs/{serial}"),
+        &format!("pki/certs/{serial}"),
+        VaultScope::Items,
+    )
+    .await?;
+    load_certificate(state, &vault, &serial).await
+}
+
+/// Revokes a certificate so it appears on the next CRL.
+pub async fn revoke(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    serial: &str,
+) -> Result<PkiCertificate, PkiError> {
+    let serial = normalize_serial(serial);
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "pki_revoke",
+        &format!("vaults/{vault_id}/pki/certs/{serial}"),
+        &format!("pki/certs/{serial}"),
+        VaultScope::Items,
+    )
+    .await?;
+    let revoked = PkiCertificateRepo::new(&state.db)
+        .revoke(vault.id, &serial, Utc::now())
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "pki_certificate_revoke_failed", error = %err, "DB error");
+            PkiError::DbError
+        })?;
+    let certificate = load_certificate(state, &vault, &serial).await?;
+    if !revoked {
+        return Err(PkiError::Conflict("already_revoked"));
+    }
+    Ok(certificate)
+}
+
+/// Signs a fresh CRL with the issuing CA listing every certificate it issued
+/// that has been revoked.
+pub async fn crl(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<SignedCrl, PkiError> {
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "read",
+        &format!("vaults/{vault_id}/pki/crl"),
+        "pki/crl",
+        VaultScope::Items,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    let chain = load_chain(state, &vault).await?;
+    let Some(issuer) = chain.issuer() else {
+        return Err(PkiError::NotFound);
+    };
+    let revoked = PkiCertificateRepo::new(&state.db)
+        .list_revoked_by_issuer(issuer.id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "pki_crl_load_failed", error = %err, "DB error");
+            PkiError::DbError
+        })?;
+
+    let this_update = Utc::now();
+    let next_update = this_update + ChronoDuration::seconds(CRL_LIFETIME_SECONDS);
+    let revoked_certs = revoked
+        .iter()
+        .map(|cert| {
+            Ok(RevokedCertParams {
+                serial_number: SerialNumber::from(
+                    hex::decode(&cert.serial)
+                        .map_err(|_| PkiError::Internal("pki_serial_invalid"))?,
+                ),
+                revocation_time: offset_date_time(cert.revoked_at.unwrap_or(this_update))?,
+                reason_code: None,
+                invalidity_date: None,
+            })
+        })
+        .collect::<Result<Vec<_>, PkiError>>()?;
+    let params = CertificateRevocationListParams {
+        this_update: offset_date_time(this_update)?,
+        next_update: offset_date_time(next_update)?,
+        // Seconds since the epoch keep CRL numbers increasing without a counter.
+        crl_number: SerialNumber::from(this_update.timestamp().to_be_bytes().to_vec()),
+        issuing_distribution_point: None,
+        revoked_certs,
+        key_identifier_method: KeyIdMethod::Sha256,
+    };
+    let issuer_key = ca_key_pair(state, &vault, issuer)?;
+    let signer = Issuer::from_ca_cert_pem(&issuer.certificate_pem, issuer_key)
+        .map_err(|_| PkiError::Internal("pki_issuer_invalid"))?;
+    let crl = params
+        .signed_by(&signer)
+        .and_then(|crl| crl.pem())
+        .map_err(|err| match err {
+            rcgen::Error::IssuerNotCrlSigner => PkiError::Conflict("issuer_not_crl_signer"),
+            err => {
+                tracing::error!(event = "pki_crl_sign_failed", error = %err, "CRL signing failed");
+                PkiError::Internal("pki_crl_sign_failed")
+            }
+        })?;
+    Ok(SignedCrl {
+        crl,
+        issuer: issuer.clone(),
+        revoked: revoked.len(),
+        this_update,
+        next_update,
+    })
+}
+
+async fn authorize_configure(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    resource: &str,
+    scope_path: &str,
+) -> Result<Vault, PkiError> {
+    // Service accounts issue certificates but never manage the CAs or roles.
+    if identity.service_account_id.is_some() {
+        return Err(PkiError::ForbiddenNoBody);
+    }
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "pki_configure",
+        resource,
+        scope_path,
+        VaultScope::Items,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    Ok(vault)
+}
+
+fn imported(cmd: &CreateCaCommand) -> Result<Option<(&str, &str)>, PkiError> {
+    match (cmd.certificate.as_deref(), cmd.private_key.as_deref()) {
+        (Some(certificate), Some(private_key)) => Ok(Some((certificate, private_key))),
+        (None, None) => Ok(None),
+        _ => Err(PkiError::BadRequest("certificate_and_key_required")),
+    }
+}
+
+fn generate_ca(
+    vault: &Vault,
+    kind: PkiCaKind,
+    cmd: &CreateCaCommand,
+    parent: Option<(&PkiCa, &KeyPair)>,
+) -> Result<(PkiCa, String), PkiError> {
+    let common_name = cmd
+        .common_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .ok_or(PkiError::BadRequest("common_name_required"))?;
+    let ttl_seconds = match cmd.ttl.as_deref() {
+        Some(value) => parse_ttl_seconds(value)?,
+        None => kind.default_ttl_seconds(),
+    };
+    let now = Utc::now();
+    let mut not_after = now + ChronoDuration::seconds(ttl_seconds);
+    if let Some((parent, _)) = parent {
+        not_after = not_after.min(parent.not_after);
+    }
+    let (serial, serial_hex) = random_serial();
+
+    let mut params = CertificateParams::default();
+    params.distinguished_name = distinguished_name(common_name);
+    params.is_ca = IsCa::Ca(match kind {
+        PkiCaKind::Root => BasicConstraints::Unconstrained,
+        PkiCaKind::Intermediate => BasicConstraints::Constrained(0),
+    });
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params.not_before = offset_date_time(now - ChronoDuration::seconds(CLOCK_SKEW_SECONDS))?;
+    params.not_after = offset_date_time(not_after)?;
+    params.serial_number = Some(serial);
+    params.use_authority_key_identifier_extension = parent.is_some();
+
+    let key = KeyPair::generate_for(cmd.key_type.algorithm())
+        .map_err(|_| PkiError::Internal("pki_key_generate_failed"))?;
+    let certificate = match parent {
+        Some((parent, parent_key)) => {
+            let signer = Issuer::from_ca_cert_pem(&parent.certificate_pem, parent_key)
+                .map_err(|_| PkiError::Internal("pki_issuer_invalid"))?;
+            params.signed_by(&key, &signer)
+        }
+        None => params.self_signed(&key),
+    }
+    .map_err(|err| {
+        tracing::error!(event = "pki_ca_generate_failed", error = %err, "CA generation failed");
+        PkiError::Internal("pki_ca_generate_failed")
+    })?;
+    let ca = PkiCa {
+        id: Uuid::now_v7(),
+        vault_id: vault.id,
+        kind: kind.as_str().to_string(),
+        common_name: common_name.to_string(),
+        certificate_pem: certificate.pem(),
+        private_key_enc: Vec::new(),
+        serial: serial_hex,
+        not_after,
+        created_at: now,
+    };
+    Ok((ca, key.serialize_pem()))
+}
+
+fn import_ca(
+    vault: &Vault,
+    kind: PkiCaKind,
+    certificate: &str,
+    private_key: &str,
+    root: Option<&PkiCa>,
+) -> Result<(PkiCa, String), PkiError> {
+    let key = KeyPair::from_pem(private_key.trim())
+        .map_err(|_| PkiError::BadRequest("invalid_private_key"))?;
+    let (_, pem) = x509_parser::pem::parse_x509_pem(certificate.trim().as_bytes())
+        .map_err(|_| PkiError::BadRequest("invalid_certificate"))?;
+    let cert = pem
+        .parse_x509()
+        .map_err(|_| PkiError::BadRequest("invalid_certificate"))?;
+    if !has_ca_constraint(&cert) {
+        return Err(PkiError::BadRequest("certificate_not_ca"));
+    }
+    if cert.public_key().subject_public_key.data.as_ref() != key.public_key_raw() {
+        return Err(PkiError::BadRequest("private_key_mismatch"));
+    }
+    if kind == PkiCaKind::Root
+        && (cert.issuer().as_raw() != cert.subject().as_raw()
+            || cert.verify_signature(None).is_err())
+    {
+        return Err(PkiError::BadRequest("root_not_self_signed"));
+    }
+    if let Some(root) = root {
+        let (_, root_pem) = x509_parser::pem::parse_x509_pem(root.certificate_pem.as_bytes())
+            .map_err(|_| PkiError::Internal("pki_issuer_invalid"))?;
+        let root_cert = root_pem
+            .parse_x509()
+            .map_err(|_| PkiError::Internal("pki_issuer_invalid"))?;
+        if cert.issuer().as_raw() != root_cert.subject().as_raw()
+            || cert.verify_signature(Some(root_cert.public_key())).is_err()
+        {
+            return Err(PkiError::BadRequest("intermediate_not_issued_by_root"));
+        }
+        if cert.validity().not_after > root_cert.validity().not_after {
+            return Err(PkiError::BadRequest("intermediate_outlives_root"));
+        }
+    }
+    let not_after = Utc
+        .timestamp_opt(cert.validity().not_after.timestamp(), 0)
+        .single()
+        .ok_or(PkiError::BadRequest("invalid_certificate"))?;
+    if not_after <= Utc::now() {
+        return Err(PkiError::BadRequest("certificate_expired"));
+    }
+    let ca = PkiCa {
+        id: Uuid::now_v7(),
+        vault_id: vault.id,
+        kind: kind.as_str().to_string(),
+        common_name: common_name(&cert),
+        certificate_pem: pem_encode(&pem.contents),
+        private_key_enc: Vec::new(),
+        serial: hex::encode(cert.raw_serial()),
+        not_after,
+        created_at: Utc::now(),
+    };
+    Ok((ca, key.serialize_pem()))
+}
+
+async fn store_ca(
+    state: &AppState,
+    vault: &Vault,
+    (mut ca, private_key): (PkiCa, String),
+) -> Result<PkiCa, PkiError> {
+    let vault_key = server_vault_key(state, vault)?;
+    ca.private_key_enc =
+        core_crypto::encrypt_pki_ca_key(&vault_key, vault.id, ca.id, private_key.as_bytes())
+            .map_err(|_| PkiError::Internal("encrypt_failed"))?;
+    let created = PkiCaRepo::new(&state.db).create(&ca).await.map_err(|err| {
+        tracing::error!(event = "pki_ca_create_failed", error = %err, "DB error");
+        PkiError::DbError
+    })?;
+    if !created {
+        return Err(PkiError::Conflict("pki_ca_exists"));
+    }
+    Ok(ca)
+}
+
+/// Whether the certificate has a basicConstraints extension with `CA:true`.
+/// A duplicated or malformed extension counts as missing.
+fn has_ca_constraint(cert: &X509Certificate<'_>) -> bool {
+    matches!(cert.basic_constraints(), Ok(Some(constraints)) if constraints.value.ca)
+}
+
+fn common_name(cert: &X509Certificate<'_>) -> String {
+    cert.subject()
+        .iter_common_name()
+        .next()
+        .and_then(|cn| cn.as_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Re-encodes the parsed certificate so text around the PEM block is dropped.
+fn pem_encode(der: &[u8]) -> String {
+    let encoded = STANDARD.encode(der);
+    let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str("-----END CERTIFICATE-----\n");
+    pem
+}
+
+fn distinguished_name(common_name: &str) -> DistinguishedName {
+    let mut name = DistinguishedName::new();
+    name.push(DnType::CommonName, common_name);
+    name
+}
+
+/// Random positive serial with a non-zero leading byte, so its DER encoding and
+/// hex form stay exactly `SERIAL_LEN` bytes.
+fn random_serial() -> (SerialNumber, String) {
+    let mut bytes = [0u8; SERIAL_LEN];
+    OsRng.fill_bytes(&mut bytes);
+    bytes[0] = (bytes[0] & 0x7f).max(1);
+    (SerialNumber::from_slice(&bytes), hex::encode(bytes))
+}
+
+/// Validates the common name and alt names against the role. The common name
+/// comes first and is always included as a DNS SAN.
+fn resolve_names(
+    role: &PkiRole,
+    common_name: &str,
+    alt_names: &[String],
+) -> Result<Vec<String>, PkiError> {
+    let mut names: Vec<String> = Vec::with_capacity(alt_names.len() + 1);
+    for name in std::iter::once(common_name).chain(alt_names.iter().map(String::as_str)) {
+        let name = name.trim().trim_end_matches('.').to_ascii_lowercase();
+        if !is_dns_name(&name) {
+            return Err(PkiError::BadRequest("invalid_name"));
+        }
+        if !name_allowed(role, &name) {
+            return Err(PkiError::Forbidden("name_not_allowed"));
+        }
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+fn resolve_ips(role: &PkiRole, ip_sans: &[String]) -> Result<Vec<IpAddr>, PkiError> {
+    let mut ips: Vec<IpAddr> = Vec::with_capacity(ip_sans.len());
+    for value in ip_sans {
+        let ip: IpAddr = value
+            .trim()
+            .parse()
+            .map_err(|_| PkiError::BadRequest("invalid_ip_san"))?;
+        if !role.allow_ip_sans {
+            return Err(PkiError::Forbidden("ip_sans_not_allowed"));
+        }
+        if !ips.contains(&ip) {
+            ips.push(ip);
+        }
+    }
+    Ok(ips)
+}
+
+/// A name matches an allowed domain exactly or, with `allow_subdomains`, as
+/// any name below it, wildcards included.
+fn name_allowed(role: &PkiRole, name: &str) -> bool {
+    role.allowed_domains.iter().any(|domain| {
+        if domain == ANY {
+            return true;
+        }
+        if name == domain {
+            return true;
+        }
+        role.allow_subdomains
+            && name
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.'))
+    })
+}
+
+/// Hostname syntax, allowing a single leading `*` label.
+fn is_dns_name(name: &str) -> bool {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return false;
+    }
+    let labels = name.strip_prefix("*.").unwrap_or(name);
+    labels.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+fn offset_date_time(value: DateTime<Utc>) -> Result<OffsetDateTime, PkiError> {
+    OffsetDateTime::from_unix_timestamp(value.timestamp())
+        .map_err(|_| PkiError::BadRequest("invalid_ttl"))
+}
+
+fn parse_ttl_seconds(value: &str) -> Result<i64, PkiError> {
+    let seconds = parse_ttl(value)
+        .map_err(|_| PkiError::BadRequest("invalid_ttl"))?
+        .num_seconds();
+    if seconds < MIN_TTL_SECONDS {
+        return Err(PkiError::BadRequest("ttl_too_short"));
+    }
+    Ok(seconds)
+}
+
+async fn load_chain(state: &AppState, vault: &Vault) -> Result<PkiCaChain, PkiError> {
+    let repo = PkiCaRepo::new(&state.db);
+    let load = |kind: PkiCaKind| repo.get_by_kind(vault.id, kind.as_str());
+    let map_err = |err: sqlx_core::Error| {
+        tracing::error!(event = "pki_ca_load_failed", error = %err, "DB error");
+        PkiError::DbError
+    };
+    Ok(PkiCaChain {
+        root: load(PkiCaKind::Root).await.map_err(map_err)?,
+        intermediate: load(PkiCaKind::Intermediate).await.map_err(map_err)?,
+    })
+}
+
+async fn load_role(state: &AppState, vault: &Vault, name: &str) -> Result<PkiRole, PkiError> {
+    match PkiRoleRepo::new(&state.db)
+        .get_by_name(vault.id, name)
+        .await
+    {
+        Ok(Some(role)) => Ok(role),
+        Ok(None) => Err(PkiError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "pki_role_load_failed", error = %err, "DB error");
+            Err(PkiError::DbError)
+        }
+    }
+}
+
+async fn load_certificate(
+    state: &AppState,
+    vault: &Vault,
+    serial: &str,
+) -> Result<PkiCertificate, PkiError> {
+    match PkiCertificateRepo::new(&state.db)
+        .get_by_serial(vault.id, serial)
+        .await
+    {
+        Ok(Some(cert)) => Ok(cert),
+        Ok(None) => Err(PkiError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "pki_certificate_load_failed", error = %err, "DB error");
+            Err(PkiError::DbError)
+        }
+    }
+}
+
+fn ca_key_pair(state: &AppState, vault: &Vault, ca: &PkiCa) -> Result<KeyPair, PkiError> {
+    let vault_key = server_vault_key(state, vault)?;
+    let bytes = core_crypto::decrypt_pki_ca_key(&vault_key, vault.id, ca.id, &ca.private_key_enc)
+        .map_err(|_| PkiError::Internal("decrypt_failed"))?;
+    let encoded = String::from_utf8(bytes).map_err(|_| PkiError::Internal("decrypt_failed"))?;
+    KeyPair::from_pem(&encoded).map_err(|_| PkiError::Internal("decrypt_failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(domains: &[&str], allow_subdomains: bool, allow_ip_sans: bool) -> PkiRole {
+        PkiRole {
+            id: Uuid::now_v7(),
+            vault_id: Uuid::now_v7(),
+            name: "web".to_string(),
+            allowed_domains: Json(domains.iter().map(|d| (*d).to_string()).collect()),
+            allow_subdomains,
+            allow_ip_sans,
+            key_type: PkiKeyType::EcP256.as_str().to_string(),
+            server_auth: true,
+            client_auth: false,
+            default_ttl_seconds: DEFAULT_TTL_SECONDS,
+            max_ttl_seconds: DEFAULT_MAX_TTL_SECONDS,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn names_limited_to_allowed_domains() {
+        let bare = role(&["example.com"], false, false);
+        assert!(resolve_names(&bare, "example.com", &[]).is_ok());
+        let err = resolve_names(&bare, "api.example.com", &[]).expect_err("subdomain");
+        assert!(matches!(err, PkiError::Forbidden("name_not_allowed")));
+
+        let subdomains = role(&["example.com"], true, false);
+        let names = resolve_names(
+            &subdomains,
+            "API.example.com.",
+            &["*.example.com".to_string(), "api.example.com".to_string()],
+        )
+        .expect("subdomains");
+        assert_eq!(names, vec!["api.example.com", "*.example.com"]);
+        let err = resolve_names(&subdomains, "badexample.com", &[]).expect_err("suffix");
+        assert!(matches!(err, PkiError::Forbidden("name_not_allowed")));
+        let err = resolve_names(&subdomains, "a b.example.com", &[]).expect_err("syntax");
+        assert!(matches!(err, PkiError::BadRequest("invalid_name")));
+    }
+
+    #[test]
+    fn ip_sans_require_role_flag() {
+        let ips = vec!["10.0.0.1".to_string()];
+        let err = resolve_ips(&role(&["*"], false, false), &ips).expect_err("disabled");
+        assert!(matches!(err, PkiError::Forbidden("ip_sans_not_allowed")));
+        let resolved = resolve_ips(&role(&["*"], false, true), &ips).expect("enabled");
+        assert_eq!(resolved, vec!["10.0.0.1".parse::<IpAddr>().expect("ip")]);
+        let err =
+            resolve_ips(&role(&["*"], false, true), &["host".to_string()]).expect_err("invalid");
+        assert!(matches!(err, PkiError::BadRequest("invalid_ip_san")));
+    }
+
+    #[test]
+    fn serial_is_positive_and_normalized() {
+        let (_, serial) = random_serial();
+        assert_eq!(serial.len(), SERIAL_LEN * 2);
+        let first = u8::from_str_radix(&serial[..2], 16).expect("hex");
+        assert!((1..0x80).contains(&first));
+        assert_eq!(normalize_serial(" AB:cd:01 "), "abcd01");
+    }
+}
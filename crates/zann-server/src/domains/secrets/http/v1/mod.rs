@@ -1,17 +1,19 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
-    routing::{get, post},
+    routing::{delete, get, post},
     Extension, Json, Router,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Instant;
-use zann_core::Identity;
+use uuid::Uuid;
+use zann_core::{Identity, SecretLease};
 
 use crate::app::AppState;
+use crate::domains::secrets::leases;
 use crate::domains::secrets::service::{self, SecretError, SecretRecord};
 use crate::infra::{audit, metrics};
 
@@ -35,6 +37,10 @@ pub(crate) struct SecretRequest {
     policy: Option<String>,
     #[serde(default)]
     meta: Option<HashMap<String, String>>,
+    /// Rotate only: wait up to this many seconds for outstanding leases to
+    /// end, and refuse to rotate if any remain.
+    #[serde(default)]
+    wait_for_leases_seconds: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -44,6 +50,30 @@ pub(crate) struct SecretSetRequest {
     policy: Option<String>,
     #[serde(default)]
     meta: Option<HashMap<String, String>>,
+    /// `YYYY-MM-DD` or RFC 3339; omit to keep the current date, `null` to clear it.
+    #[serde(
+        default,
+        deserialize_with = "crate::domains::items::expiry::deserialize_some"
+    )]
+    expires_at: Option<Option<String>>,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(crate) struct GetSecretQuery {
+    /// Issue a lease for this read, e.g. `15m`.
+    #[serde(default)]
+    pub(crate) lease: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct SecretLeasesQuery {
+    pub(crate) path: String,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(crate) struct RenewLeaseRequest {
+    #[serde(default)]
+    pub(crate) ttl: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -51,6 +81,21 @@ pub(crate) struct BatchEnsureRequest {
     secrets: Vec<SecretRequest>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct BatchSetItem {
+    path: String,
+    value: String,
+    #[serde(default)]
+    policy: Option<String>,
+    #[serde(default)]
+    meta: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct BatchSetRequest {
+    secrets: Vec<BatchSetItem>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct BatchGetRequest {
     paths: Vec<String>,
@@ -66,9 +111,51 @@ pub(crate) struct SecretResponse {
     pub(crate) meta: Option<HashMap<String, String>>,
     pub(crate) version: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) expires_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) previous_version: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) created: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) lease: Option<SecretLeaseResponse>,
+    /// Leases still held on the previous value (rotate only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) outstanding_leases: Option<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct SecretLeaseResponse {
+    pub(crate) lease_id: String,
+    pub(crate) item_version: i64,
+    pub(crate) ttl_seconds: i64,
+    pub(crate) issued_at: String,
+    pub(crate) renewed_at: Option<String>,
+    pub(crate) expires_at: String,
+    pub(crate) revoked_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct SecretHolderResponse {
+    pub(crate) lease_id: String,
+    pub(crate) user_id: String,
+    pub(crate) service_account_id: Option<String>,
+    pub(crate) device_id: Option<String>,
+    pub(crate) email: String,
+    pub(crate) name: Option<String>,
+    pub(crate) item_version: i64,
+    /// The holder's copy predates the current version.
+    pub(crate) stale: bool,
+    pub(crate) issued_at: String,
+    pub(crate) renewed_at: Option<String>,
+    pub(crate) expires_at: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct SecretHoldersResponse {
+    pub(crate) vault_id: String,
+    pub(crate) path: String,
+    pub(crate) version: i64,
+    pub(crate) holders: Vec<SecretHolderResponse>,
 }
 
 #[derive(Debug, Serialize, JsonSchema)]
@@ -93,32 +180,80 @@ pub fn router() -> Router<AppState> {
             "/v1/vaults/:vault_id/secrets/batch/ensure",
             post(batch_ensure),
         )
+        .route("/v1/vaults/:vault_id/secrets/batch/set", post(batch_set))
         .route("/v1/vaults/:vault_id/secrets/batch/get", post(batch_get))
+        .route(
+            "/v1/vaults/:vault_id/secret-leases",
+            get(list_lease_holders),
+        )
+        .route(
+            "/v1/vaults/:vault_id/secret-leases/:lease_id",
+            delete(revoke_lease),
+        )
+        .route(
+            "/v1/vaults/:vault_id/secret-leases/:lease_id/renew",
+            post(renew_lease),
+        )
 }
 
 async fn get_secret(
     State(state): State<AppState>,
     Extension(identity): Extension<Identity>,
     Path((vault_id, path)): Path<(String, String)>,
+    Query(query): Query<GetSecretQuery>,
 ) -> impl IntoResponse {
     let start = Instant::now();
-    let result = service::get_secret(&state, &identity, &vault_id, &path).await;
+    let result = match query.lease.as_deref() {
+        Some(ttl) => match leases::parse_lease_ttl(&state, ttl) {
+            Ok(ttl_seconds) => read_leased(&state, &identity, &vault_id, &path, ttl_seconds).await,
+            Err(err) => Err(err),
+        },
+        None => service::get_secret(&state, &identity, &vault_id, &path)
+            .await
+            .map(|record| (record, None)),
+    };
     let elapsed = start.elapsed().as_secs_f64();
     match result {
-        Ok(record) => {
+        Ok((record, lease)) => {
             metrics::secrets_operation("get", "ok", elapsed);
-            audit::secrets_event(&identity, "get", "ok", &vault_id, &path, None);
-            (StatusCode::OK, Json(secret_response(record, None, None))).into_response()
+            // Leased reads are audited with the lease, in its transaction.
+            if lease.is_none() {
+                audit::secrets_event(&state, &identity, "get", "ok", &vault_id, &path, None).await;
+            }
+            let mut response = secret_response(record, None, None);
+            response.lease = lease.map(lease_response);
+            (StatusCode::OK, Json(response)).into_response()
         }
         Err(err) => {
-            let label = error_label(&err);
+            let label = err.label();
             metrics::secrets_operation("get", label, elapsed);
-            audit::secrets_event(&identity, "get", label, &vault_id, &path, Some(label));
+            audit::secrets_event(
+                &state,
+                &identity,
+                "get",
+                label,
+                &vault_id,
+                &path,
+                Some(label),
+            )
+            .await;
             map_secret_error(err)
         }
     }
 }
 
+async fn read_leased(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    path: &str,
+    ttl_seconds: i64,
+) -> Result<(SecretRecord, Option<SecretLease>), SecretError> {
+    let record = service::get_secret(state, identity, vault_id, path).await?;
+    let lease = leases::issue_lease(state, identity, path, &record, ttl_seconds).await?;
+    Ok((record, Some(lease)))
+}
+
 async fn ensure_secret(
     State(state): State<AppState>,
     Extension(identity): Extension<Identity>,
@@ -140,14 +275,6 @@ async fn ensure_secret(
         Ok((record, created)) => {
             let result_label = if created { "created" } else { "existing" };
             metrics::secrets_operation("ensure", result_label, elapsed);
-            audit::secrets_event(
-                &identity,
-                "ensure",
-                result_label,
-                &vault_id,
-                &payload.path,
-                None,
-            );
             (
                 StatusCode::OK,
                 Json(secret_response(record, None, Some(created))),
@@ -155,16 +282,18 @@ async fn ensure_secret(
                 .into_response()
         }
         Err(err) => {
-            let label = error_label(&err);
+            let label = err.label();
             metrics::secrets_operation("ensure", label, elapsed);
             audit::secrets_event(
+                &state,
                 &identity,
                 "ensure",
                 label,
                 &vault_id,
                 &payload.path,
                 Some(label),
-            );
+            )
+            .await;
             map_secret_error(err)
         }
     }
@@ -185,6 +314,7 @@ async fn set_secret(
         &payload.value,
         payload.policy.as_deref(),
         payload.meta.clone(),
+        payload.expires_at.as_ref().map(Option::as_deref),
     )
     .await;
     let elapsed = start.elapsed().as_secs_f64();
@@ -192,7 +322,6 @@ async fn set_secret(
         Ok((record, created)) => {
             let result_label = if created { "created" } else { "updated" };
             metrics::secrets_operation("set", result_label, elapsed);
-            audit::secrets_event(&identity, "set", result_label, &vault_id, &path, None);
             (
                 StatusCode::OK,
                 Json(secret_response(record, None, Some(created))),
@@ -200,9 +329,18 @@ async fn set_secret(
                 .into_response()
         }
         Err(err) => {
-            let label = error_label(&err);
+            let label = err.label();
             metrics::secrets_operation("set", label, elapsed);
-            audit::secrets_event(&identity, "set", label, &vault_id, &path, Some(label));
+            audit::secrets_event(
+                &state,
+                &identity,
+                "set",
+                label,
+                &vault_id,
+                &path,
+                Some(label),
+            )
+            .await;
             map_secret_error(err)
         }
     }
@@ -222,30 +360,37 @@ async fn rotate_secret(
         &payload.path,
         payload.policy.as_deref(),
         payload.meta.clone(),
+        payload.wait_for_leases_seconds,
     )
     .await;
     let elapsed = start.elapsed().as_secs_f64();
     match result {
         Ok((record, previous_version)) => {
             metrics::secrets_operation("rotate", "ok", elapsed);
-            audit::secrets_event(&identity, "rotate", "ok", &vault_id, &payload.path, None);
-            (
-                StatusCode::OK,
-                Json(secret_response(record, Some(previous_version), None)),
-            )
-                .into_response()
+            let outstanding = match leases::count_outstanding(&state, record.item_id).await {
+                Ok(count) => Some(count),
+                Err(err) => {
+                    tracing::warn!(event = "secret_leases_count_failed", error = %err);
+                    None
+                }
+            };
+            let mut response = secret_response(record, Some(previous_version), None);
+            response.outstanding_leases = outstanding;
+            (StatusCode::OK, Json(response)).into_response()
         }
         Err(err) => {
-            let label = error_label(&err);
+            let label = err.label();
             metrics::secrets_operation("rotate", label, elapsed);
             audit::secrets_event(
+                &state,
                 &identity,
                 "rotate",
                 label,
                 &vault_id,
                 &payload.path,
                 Some(label),
-            );
+            )
+            .await;
             map_secret_error(err)
         }
     }
@@ -276,32 +421,86 @@ async fn batch_ensure(
             Ok((record, created)) => {
                 let result_label = if created { "created" } else { "existing" };
                 metrics::secrets_operation("ensure", result_label, elapsed);
+                BatchResult {
+                    path,
+                    status: if created { "created" } else { "existing" }.to_string(),
+                    secret: Some(secret_response(record, None, Some(created))),
+                    error: None,
+                }
+            }
+            Err(err) => {
+                let label = err.label();
+                metrics::secrets_operation("ensure", label, elapsed);
                 audit::secrets_event(
+                    &state,
                     &identity,
                     "ensure",
-                    result_label,
+                    label,
                     &vault_id,
                     &audit_path,
-                    None,
-                );
+                    Some(label),
+                )
+                .await;
                 BatchResult {
                     path,
-                    status: if created { "created" } else { "existing" }.to_string(),
+                    status: "error".to_string(),
+                    secret: None,
+                    error: Some(map_secret_error_body(err)),
+                }
+            }
+        };
+        results.push(result);
+    }
+    (StatusCode::OK, Json(results)).into_response()
+}
+
+async fn batch_set(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    Json(payload): Json<BatchSetRequest>,
+) -> impl IntoResponse {
+    let mut results = Vec::with_capacity(payload.secrets.len());
+    for secret in payload.secrets {
+        let path = secret.path;
+        let audit_path = path.clone();
+        let start = Instant::now();
+        let outcome = service::set_secret(
+            &state,
+            &identity,
+            &vault_id,
+            &path,
+            &secret.value,
+            secret.policy.as_deref(),
+            secret.meta,
+            None,
+        )
+        .await;
+        let elapsed = start.elapsed().as_secs_f64();
+        let result = match outcome {
+            Ok((record, created)) => {
+                let result_label = if created { "created" } else { "updated" };
+                metrics::secrets_operation("set", result_label, elapsed);
+                BatchResult {
+                    path,
+                    status: result_label.to_string(),
                     secret: Some(secret_response(record, None, Some(created))),
                     error: None,
                 }
             }
             Err(err) => {
-                let label = error_label(&err);
-                metrics::secrets_operation("ensure", label, elapsed);
+                let label = err.label();
+                metrics::secrets_operation("set", label, elapsed);
                 audit::secrets_event(
+                    &state,
                     &identity,
-                    "ensure",
+                    "set",
                     label,
                     &vault_id,
                     &audit_path,
                     Some(label),
-                );
+                )
+                .await;
                 BatchResult {
                     path,
                     status: "error".to_string(),
@@ -330,7 +529,8 @@ async fn batch_get(
         let result = match outcome {
             Ok(record) => {
                 metrics::secrets_operation("get", "ok", elapsed);
-                audit::secrets_event(&identity, "get", "ok", &vault_id, &audit_path, None);
+                audit::secrets_event(&state, &identity, "get", "ok", &vault_id, &audit_path, None)
+                    .await;
                 BatchResult {
                     path,
                     status: "ok".to_string(),
@@ -339,9 +539,18 @@ async fn batch_get(
                 }
             }
             Err(err) => {
-                let label = error_label(&err);
+                let label = err.label();
                 metrics::secrets_operation("get", label, elapsed);
-                audit::secrets_event(&identity, "get", label, &vault_id, &audit_path, Some(label));
+                audit::secrets_event(
+                    &state,
+                    &identity,
+                    "get",
+                    label,
+                    &vault_id,
+                    &audit_path,
+                    Some(label),
+                )
+                .await;
                 BatchResult {
                     path,
                     status: "error".to_string(),
@@ -367,8 +576,95 @@ fn secret_response(
         policy: record.policy,
         meta: record.meta,
         version: record.version,
+        expires_at: record.expires_at.map(|dt| dt.to_rfc3339()),
         previous_version,
         created,
+        lease: None,
+        outstanding_leases: None,
+    }
+}
+
+fn lease_response(lease: SecretLease) -> SecretLeaseResponse {
+    let started = lease.renewed_at.unwrap_or(lease.issued_at);
+    SecretLeaseResponse {
+        lease_id: lease.id.to_string(),
+        item_version: lease.item_version,
+        ttl_seconds: (lease.expires_at - started).num_seconds(),
+        issued_at: lease.issued_at.to_rfc3339(),
+        renewed_at: lease.renewed_at.map(|value| value.to_rfc3339()),
+        expires_at: lease.expires_at.to_rfc3339(),
+        revoked_at: lease.revoked_at.map(|value| value.to_rfc3339()),
+    }
+}
+
+fn holder_response(lease: SecretLease, current_version: i64) -> SecretHolderResponse {
+    SecretHolderResponse {
+        lease_id: lease.id.to_string(),
+        user_id: lease.holder_user_id.to_string(),
+        service_account_id: lease.holder_service_account_id.map(|id| id.to_string()),
+        device_id: lease.holder_device_id.map(|id| id.to_string()),
+        email: lease.holder_email,
+        name: lease.holder_name,
+        item_version: lease.item_version,
+        stale: lease.item_version < current_version,
+        issued_at: lease.issued_at.to_rfc3339(),
+        renewed_at: lease.renewed_at.map(|value| value.to_rfc3339()),
+        expires_at: lease.expires_at.to_rfc3339(),
+    }
+}
+
+async fn list_lease_holders(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    Query(query): Query<SecretLeasesQuery>,
+) -> impl IntoResponse {
+    match leases::list_holders(&state, &identity, &vault_id, &query.path).await {
+        Ok((item, active)) => {
+            let response = SecretHoldersResponse {
+                vault_id: item.vault_id.to_string(),
+                path: item.path,
+                version: item.version,
+                holders: active
+                    .into_iter()
+                    .map(|lease| holder_response(lease, item.version))
+                    .collect(),
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(err) => map_secret_error(err),
+    }
+}
+
+async fn renew_lease(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, lease_id)): Path<(String, Uuid)>,
+    payload: Option<Json<RenewLeaseRequest>>,
+) -> impl IntoResponse {
+    let Json(payload) = payload.unwrap_or_default();
+    match leases::renew_lease(
+        &state,
+        &identity,
+        &vault_id,
+        lease_id,
+        payload.ttl.as_deref(),
+    )
+    .await
+    {
+        Ok(lease) => (StatusCode::OK, Json(lease_response(lease))).into_response(),
+        Err(err) => map_secret_error(err),
+    }
+}
+
+async fn revoke_lease(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, lease_id)): Path<(String, Uuid)>,
+) -> impl IntoResponse {
+    match leases::revoke_lease(&state, &identity, &vault_id, lease_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) => map_secret_error(err),
     }
 }
 
@@ -559,22 +855,3 @@ fn map_secret_error_body(error: SecretError) -> ErrorResponse {
         },
     }
 }
-
-fn error_label(error: &SecretError) -> &'static str {
-    match error {
-        SecretError::ForbiddenNoBody | SecretError::Forbidden(_) => "forbidden",
-        SecretError::NotFound => "not_found",
-        SecretError::BadRequest(_) => "bad_request",
-        SecretError::Conflict(_) => "conflict",
-        SecretError::Unauthorized(_) => "unauthorized",
-        SecretError::PolicyMismatch { .. } => "policy_mismatch",
-        SecretError::PayloadTooLarge(_) => "payload_too_large",
-        SecretError::DbError => "db_error",
-        SecretError::Internal(_) => "internal",
-        SecretError::NoChanges => "no_changes",
-        SecretError::InvalidPassword => "invalid_password",
-        SecretError::InvalidCredentials => "invalid_credentials",
-        SecretError::Kdf => "kdf_error",
-        SecretError::DeviceRequired => "device_required",
-    }
-}
@@ -0,0 +1,289 @@
+use std::time::{Duration, Instant};
+
+use chrono::{Duration as ChronoDuration, Utc};
+use uuid::Uuid;
+use zann_core::{Identity, Item, SecretLease};
+use zann_db::repo::{ItemRepo, SecretLeaseRepo};
+use zann_db::PgPool;
+
+use crate::app::AppState;
+use crate::domains::access_control::http::VaultScope;
+use crate::domains::errors::ServiceError;
+use crate::domains::secrets::service::{
+    authorize_vault_access, normalize_secret_path, SecretRecord,
+};
+use crate::domains::service_accounts::tokens::parse_ttl;
+use crate::infra::audit::{self, AuditEntry};
+use crate::infra::metrics;
+
+pub type LeaseError = ServiceError;
+
+const MIN_LEASE_TTL_SECONDS: i64 = 10;
+const MAX_WAIT_SECONDS: u64 = 300;
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Parses a requested lease TTL and clamps it to `leases.max_ttl_seconds`.
+pub fn parse_lease_ttl(state: &AppState, value: &str) -> Result<i64, LeaseError> {
+    let seconds = parse_ttl(value)
+        .map_err(|_| LeaseError::BadRequest("invalid_lease_ttl"))?
+        .num_seconds();
+    if seconds < MIN_LEASE_TTL_SECONDS {
+        return Err(LeaseError::BadRequest("lease_ttl_too_short"));
+    }
+    Ok(seconds.min(max_ttl_seconds(state)))
+}
+
+/// Records a lease for a secret that was just read by `identity`, together with
+/// the read's audit event for `path`.
+pub async fn issue_lease(
+    state: &AppState,
+    identity: &Identity,
+    path: &str,
+    record: &SecretRecord,
+    ttl_seconds: i64,
+) -> Result<SecretLease, LeaseError> {
+    let vault_id =
+        Uuid::parse_str(&record.vault_id).map_err(|_| LeaseError::Internal("invalid_vault_id"))?;
+    let now = Utc::now();
+    let lease = SecretLease {
+        id: Uuid::now_v7(),
+        item_id: record.item_id,
+        vault_id,
+        item_version: record.version,
+        holder_user_id: identity.user_id,
+        holder_service_account_id: identity.service_account_id,
+        holder_device_id: identity.device_id,
+        holder_email: identity.email.clone(),
+        holder_name: Some(identity.display_name.clone()).filter(|name| !name.is_empty()),
+        issued_at: now,
+        renewed_at: None,
+        expires_at: now + ChronoDuration::seconds(ttl_seconds),
+        revoked_at: None,
+    };
+    let issue = async {
+        let mut tx = state.db.begin().await?;
+        SecretLeaseRepo::create_in(&mut tx, &lease).await?;
+        let entry = lease_audit_entry("get", &lease, path);
+        let audited = audit::record_tx(&mut tx, Some(identity), entry).await?;
+        tx.commit().await?;
+        audited.dispatch();
+        Ok::<_, sqlx_core::Error>(())
+    };
+    if let Err(err) = issue.await {
+        metrics::secret_leases("issue", "error");
+        tracing::error!(event = "secret_lease_issue_failed", error = %err, "DB error");
+        return Err(LeaseError::DbError);
+    }
+    metrics::secret_leases("issue", "ok");
+    Ok(lease)
+}
+
+/// Extends an active lease by `ttl` (or by its original length), never past
+/// `issued_at + leases.max_ttl_seconds`. Holders may renew their own leases
+/// while they can still read the secret; anyone else needs `manage_leases`.
+pub async fn renew_lease(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    lease_id: Uuid,
+    ttl: Option<&str>,
+) -> Result<SecretLease, LeaseError> {
+    let requested = ttl.map(|value| parse_lease_ttl(state, value)).transpose()?;
+    let (lease, item) = authorize_lease(state, identity, vault_id, lease_id).await?;
+    let now = Utc::now();
+    if lease.revoked_at.is_some() {
+        return Err(LeaseError::Conflict("lease_revoked"));
+    }
+    if lease.expires_at <= now {
+        return Err(LeaseError::Conflict("lease_expired"));
+    }
+    let ttl_seconds = requested.unwrap_or_else(|| {
+        (lease.expires_at - lease.renewed_at.unwrap_or(lease.issued_at)).num_seconds()
+    });
+    let limit = lease.issued_at + ChronoDuration::seconds(max_ttl_seconds(state));
+    let expires_at = (now + ChronoDuration::seconds(ttl_seconds)).min(limit);
+    let renew = async {
+        let mut tx = state.db.begin().await?;
+        let renewed = SecretLeaseRepo::renew_in(&mut tx, lease.id, expires_at, now).await?;
+        if renewed {
+            let entry = lease_audit_entry("lease_renew", &lease, &item.path);
+            let audited = audit::record_tx(&mut tx, Some(identity), entry).await?;
+            tx.commit().await?;
+            audited.dispatch();
+        }
+        Ok::<_, sqlx_core::Error>(renewed)
+    };
+    let renewed = renew.await.map_err(|err| {
+        tracing::error!(event = "secret_lease_renew_failed", error = %err, "DB error");
+        LeaseError::DbError
+    })?;
+    if !renewed {
+        return Err(LeaseError::Conflict("lease_expired"));
+    }
+    metrics::secret_leases("renew", "ok");
+    Ok(SecretLease {
+        renewed_at: Some(now),
+        expires_at,
+        ..lease
+    })
+}
+
+/// Revokes a lease. Revoking an already revoked or expired lease succeeds.
+pub async fn revoke_lease(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    lease_id: Uuid,
+) -> Result<(), LeaseError> {
+    let (lease, item) = authorize_lease(state, identity, vault_id, lease_id).await?;
+    let now = Utc::now();
+    let revoke = async {
+        let mut tx = state.db.begin().await?;
+        if lease.revoked_at.is_none() {
+            SecretLeaseRepo::revoke_in(&mut tx, lease.id, now).await?;
+        }
+        let entry = lease_audit_entry("lease_revoke", &lease, &item.path);
+        let audited = audit::record_tx(&mut tx, Some(identity), entry).await?;
+        tx.commit().await?;
+        audited.dispatch();
+        Ok::<_, sqlx_core::Error>(())
+    };
+    revoke.await.map_err(|err| {
+        tracing::error!(event = "secret_lease_revoke_failed", error = %err, "DB error");
+        LeaseError::DbError
+    })?;
+    if lease.revoked_at.is_none() {
+        metrics::secret_leases("revoke", "ok");
+    }
+    Ok(())
+}
+
+/// Audit event for a lease change on the secret at `path`.
+fn lease_audit_entry(action: &str, lease: &SecretLease, path: &str) -> AuditEntry {
+    AuditEntry::new("secrets", action, "ok")
+        .vault(lease.vault_id)
+        .path(path)
+        .detail(Some(&lease.id.to_string()))
+}
+
+/// Active leases on the secret at `path`, i.e. who currently holds it.
+pub async fn list_holders(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    path: &str,
+) -> Result<(Item, Vec<SecretLease>), LeaseError> {
+    let normalized_path = normalize_secret_path(path)?;
+    let resource = format!("vaults/{vault_id}/secrets/{normalized_path}");
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "read_leases",
+        &resource,
+        &normalized_path,
+        VaultScope::Items,
+    )
+    .await?;
+    let item = match ItemRepo::new(&state.db)
+        .get_by_vault_path(vault.id, &normalized_path)
+        .await
+    {
+        Ok(Some(item)) => item,
+        Ok(None) => return Err(LeaseError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "secret_leases_list_failed", error = %err, "DB error");
+            return Err(LeaseError::DbError);
+        }
+    };
+    let leases = SecretLeaseRepo::new(&state.db)
+        .list_active_by_item(item.id, Utc::now())
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "secret_leases_list_failed", error = %err, "DB error");
+            LeaseError::DbError
+        })?;
+    Ok((item, leases))
+}
+
+/// Number of unexpired, unrevoked leases on an item.
+pub async fn count_outstanding(state: &AppState, item_id: Uuid) -> Result<i64, sqlx_core::Error> {
+    SecretLeaseRepo::new(&state.db)
+        .count_active_by_item(item_id, Utc::now())
+        .await
+}
+
+/// Polls until the item has no outstanding leases or `wait_seconds` (capped at
+/// five minutes) pass. Returns the number still outstanding.
+pub async fn wait_for_release(
+    state: &AppState,
+    item_id: Uuid,
+    wait_seconds: u64,
+) -> Result<i64, sqlx_core::Error> {
+    let deadline = Instant::now() + Duration::from_secs(wait_seconds.min(MAX_WAIT_SECONDS));
+    loop {
+        let outstanding = count_outstanding(state, item_id).await?;
+        if outstanding == 0 || Instant::now() >= deadline {
+            return Ok(outstanding);
+        }
+        tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+    }
+}
+
+/// Drops leases that ended more than `retention_seconds` ago.
+pub async fn prune_leases(pool: &PgPool, retention_seconds: i64) -> Result<u64, sqlx_core::Error> {
+    let cutoff = Utc::now() - ChronoDuration::seconds(retention_seconds.max(0));
+    SecretLeaseRepo::new(pool)
+        .delete_inactive_before(cutoff)
+        .await
+}
+
+async fn authorize_lease(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    lease_id: Uuid,
+) -> Result<(SecretLease, Item), LeaseError> {
+    let lease = match SecretLeaseRepo::new(&state.db).get_by_id(lease_id).await {
+        Ok(Some(lease)) => lease,
+        Ok(None) => return Err(LeaseError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "secret_lease_load_failed", error = %err, "DB error");
+            return Err(LeaseError::DbError);
+        }
+    };
+    let item = match ItemRepo::new(&state.db).get_by_id(lease.item_id).await {
+        Ok(Some(item)) => item,
+        Ok(None) => return Err(LeaseError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "secret_lease_load_failed", error = %err, "DB error");
+            return Err(LeaseError::DbError);
+        }
+    };
+    let is_holder = lease.holder_user_id == identity.user_id
+        && lease.holder_service_account_id == identity.service_account_id;
+    let action = if is_holder { "read" } else { "manage_leases" };
+    let resource = format!("vaults/{vault_id}/secrets/{}", item.path);
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        action,
+        &resource,
+        &item.path,
+        VaultScope::Items,
+    )
+    .await?;
+    if vault.id != lease.vault_id {
+        return Err(LeaseError::NotFound);
+    }
+    Ok((lease, item))
+}
+
+fn max_ttl_seconds(state: &AppState) -> i64 {
+    state
+        .config
+        .leases
+        .max_ttl_seconds
+        .max(MIN_LEASE_TTL_SECONDS)
+}
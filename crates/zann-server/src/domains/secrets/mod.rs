@@ -1,3 +1,4 @@
 pub mod http;
+pub mod leases;
 pub mod policies;
 pub mod service;
@@ -1,28 +1,33 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 use zann_core::{Change, ChangeOp, ChangeType, Identity, Item, ItemHistory, SyncStatus, Vault};
+use zann_crypto::crypto::SecretKey;
 use zann_crypto::vault_crypto as core_crypto;
-use zann_db::repo::{
-    ChangeRepo, DeviceRepo, ItemHistoryRepo, ItemRepo, ServiceAccountRepo, UserRepo, VaultRepo,
-};
+use zann_db::repo::{DeviceRepo, ItemRepo, ServiceAccountRepo, UserRepo, VaultRepo};
 
 use crate::app::AppState;
 use crate::domains::access_control::http::{
-    find_vault, parse_scope, vault_role_allows, ScopeRule, ScopeTarget, VaultScope,
+    find_vault, parse_scope, scope_allows_action, vault_role_allows, ScopeRule, ScopeTarget,
+    VaultScope,
 };
 use crate::domains::access_control::policies::PolicyDecision;
 use crate::domains::auth::helpers::build_device;
 use crate::domains::errors::ServiceError;
-use crate::domains::items::service::basename_from_path;
-use crate::domains::secrets::policies::{generate_secret, PasswordPolicy};
+use crate::domains::items::expiry;
+use crate::domains::items::service::{basename_from_path, write_item_change};
+use crate::domains::secrets::leases;
+use crate::domains::secrets::policies::{generate_secret, GeneratedSecret, SecretPolicy};
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::metrics;
 
 pub type SecretError = ServiceError;
 
 const SERVICE_ACCOUNT_DEVICE_NAME: &str = "Service Account";
 const SERVICE_ACCOUNT_DEVICE_FINGERPRINT: &str = "service-account";
+const PUBLIC_KEY_META: &str = "public_key";
+const MAX_NAME_LEN: usize = 128;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecretPayload {
@@ -34,12 +39,14 @@ pub struct SecretPayload {
 
 #[derive(Debug, Clone)]
 pub struct SecretRecord {
+    pub item_id: Uuid,
     pub path: String,
     pub vault_id: String,
     pub value: String,
     pub policy: String,
     pub meta: Option<HashMap<String, String>>,
     pub version: i64,
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 struct ActorSnapshot {
@@ -96,12 +103,14 @@ pub async fn get_secret(
         usage_tracker.record_read(item_id, user_id, device_id).await;
     });
     Ok(SecretRecord {
+        item_id: item.id,
         path: item.path,
         vault_id: vault.id.to_string(),
         value: payload.value,
         policy: payload.policy,
         meta: payload.meta,
         version: item.version,
+        expires_at: item.expires_at,
     })
 }
 
@@ -146,19 +155,23 @@ pub async fn ensure_secret(
             });
         }
         let record = SecretRecord {
+            item_id: item.id,
             path: item.path,
             vault_id: vault.id.to_string(),
             value: payload.value,
             policy: payload.policy,
             meta: payload.meta,
             version: item.version,
+            expires_at: item.expires_at,
         };
+        let entry = secret_audit_entry("ensure", "existing", &vault, path);
+        audit::record(state, Some(identity), entry).await;
         return Ok((record, false));
     }
 
     let (policy_name, policy) = resolve_policy(state, policy_name)?;
-    let value = generate_secret(&policy).map_err(SecretError::Internal)?;
-    let normalized_meta = normalize_meta(meta);
+    let generated = generate_with_policy(policy).await?;
+    let (value, normalized_meta) = with_public_key(generated, normalize_meta(meta));
     let payload = SecretPayload {
         value: value.clone(),
         policy: policy_name.clone(),
@@ -186,45 +199,11 @@ pub async fn ensure_secret(
         deleted_at: None,
         deleted_by_user_id: None,
         deleted_by_device_id: None,
+        expires_at: None,
         created_at: now,
         updated_at: now,
     };
 
-    let created = match item_repo.create(&item).await {
-        Ok(()) => true,
-        Err(err) => {
-            tracing::warn!(event = "secret_create_conflict", error = %err);
-            let existing = item_repo
-                .get_by_vault_path(vault.id, &normalized_path)
-                .await
-                .map_err(|_| SecretError::DbError)?;
-            if let Some(existing) = existing {
-                if existing.type_id != "secret" || existing.sync_status != SyncStatus::Active {
-                    return Err(SecretError::Conflict("path_in_use"));
-                }
-                let payload = decrypt_secret_payload(state, &vault, &existing)?;
-                let requested_policy = resolve_policy_name(state, Some(policy_name.as_str()));
-                if payload.policy != requested_policy {
-                    return Err(SecretError::PolicyMismatch {
-                        existing: payload.policy,
-                        requested: requested_policy,
-                    });
-                }
-                let record = SecretRecord {
-                    path: existing.path,
-                    vault_id: vault.id.to_string(),
-                    value: payload.value,
-                    policy: payload.policy,
-                    meta: payload.meta,
-                    version: existing.version,
-                };
-                return Ok((record, false));
-            }
-            return Err(SecretError::DbError);
-        }
-    };
-
-    let history_repo = ItemHistoryRepo::new(&state.db);
     let actor = actor_snapshot(state, identity, identity.device_id).await;
     let history = ItemHistory {
         id: Uuid::now_v7(),
@@ -241,11 +220,6 @@ pub async fn ensure_secret(
         changed_by_device_name: actor.device_name,
         created_at: now,
     };
-    if let Err(err) = history_repo.create(&history).await {
-        tracing::warn!(event = "secret_history_create_failed", error = %err);
-    }
-
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -255,19 +229,55 @@ pub async fn ensure_secret(
         device_id,
         created_at: now,
     };
-    if let Err(err) = change_repo.create(&change).await {
-        tracing::warn!(event = "secret_change_create_failed", error = %err);
+    let entry = secret_audit_entry("ensure", "created", &vault, path);
+    if let Err(err) =
+        write_item_change(state, identity, &item, Some(&history), &change, entry).await
+    {
+        tracing::warn!(event = "secret_create_conflict", error = %err);
+        let existing = item_repo
+            .get_by_vault_path(vault.id, &normalized_path)
+            .await
+            .map_err(|_| SecretError::DbError)?;
+        if let Some(existing) = existing {
+            if existing.type_id != "secret" || existing.sync_status != SyncStatus::Active {
+                return Err(SecretError::Conflict("path_in_use"));
+            }
+            let payload = decrypt_secret_payload(state, &vault, &existing)?;
+            let requested_policy = resolve_policy_name(state, Some(policy_name.as_str()));
+            if payload.policy != requested_policy {
+                return Err(SecretError::PolicyMismatch {
+                    existing: payload.policy,
+                    requested: requested_policy,
+                });
+            }
+            let record = SecretRecord {
+                item_id: existing.id,
+                path: existing.path,
+                vault_id: vault.id.to_string(),
+                value: payload.value,
+                policy: payload.policy,
+                meta: payload.meta,
+                version: existing.version,
+                expires_at: existing.expires_at,
+            };
+            let entry = secret_audit_entry("ensure", "existing", &vault, path);
+            audit::record(state, Some(identity), entry).await;
+            return Ok((record, false));
+        }
+        return Err(SecretError::DbError);
     }
 
     let record = SecretRecord {
+        item_id: item.id,
         path: item.path,
         vault_id: vault.id.to_string(),
         value,
         policy: policy_name,
         meta: normalized_meta,
         version: item.version,
+        expires_at: item.expires_at,
     };
-    Ok((record, created))
+    Ok((record, true))
 }
 
 pub async fn set_secret(
@@ -278,9 +288,14 @@ pub async fn set_secret(
     value: &str,
     policy_name: Option<&str>,
     meta: Option<HashMap<String, String>>,
+    expires_at: Option<Option<&str>>,
 ) -> Result<(SecretRecord, bool), SecretError> {
     let device_id = effective_device_id(state, identity).await?;
     let normalized_path = normalize_secret_path(path)?;
+    let expires_at = expires_at
+        .map(expiry::parse_optional_expires_at)
+        .transpose()
+        .map_err(SecretError::BadRequest)?;
     let resource = format!("vaults/{vault_id}/secrets/{normalized_path}");
     let vault = authorize_vault_access(
         state,
@@ -321,58 +336,55 @@ pub async fn set_secret(
             meta: normalized_meta.clone(),
         };
 
-        if payload.value == existing_payload.value
+        let payload_unchanged = payload.value == existing_payload.value
             && payload.policy == existing_payload.policy
-            && payload.meta == existing_payload.meta
-        {
+            && payload.meta == existing_payload.meta;
+        let expires_at = expires_at.unwrap_or(item.expires_at);
+        if payload_unchanged && expires_at == item.expires_at {
             let record = SecretRecord {
+                item_id: item.id,
                 path: item.path,
                 vault_id: vault.id.to_string(),
                 value: payload.value,
                 policy: payload.policy,
                 meta: payload.meta,
                 version: item.version,
+                expires_at: item.expires_at,
             };
+            let entry = secret_audit_entry("set", "updated", &vault, path);
+            audit::record(state, Some(identity), entry).await;
             return Ok((record, false));
         }
 
-        let history_repo = ItemHistoryRepo::new(&state.db);
-        let actor = actor_snapshot(state, identity, identity.device_id).await;
-        let history = ItemHistory {
-            id: Uuid::now_v7(),
-            item_id: item.id,
-            payload_enc: item.payload_enc.clone(),
-            checksum: item.checksum.clone(),
-            version: item.version,
-            change_type: ChangeType::Update,
-            fields_changed: None,
-            changed_by_user_id: identity.user_id,
-            changed_by_email: actor.email,
-            changed_by_name: actor.name,
-            changed_by_device_id: identity.device_id,
-            changed_by_device_name: actor.device_name,
-            created_at: Utc::now(),
-        };
-        if let Err(err) = history_repo.create(&history).await {
-            tracing::warn!(event = "secret_history_create_failed", error = %err);
-        }
+        // An expiry-only change keeps the payload and skips the history entry.
+        let mut history = None;
+        if !payload_unchanged {
+            let actor = actor_snapshot(state, identity, identity.device_id).await;
+            history = Some(ItemHistory {
+                id: Uuid::now_v7(),
+                item_id: item.id,
+                payload_enc: item.payload_enc.clone(),
+                checksum: item.checksum.clone(),
+                version: item.version,
+                change_type: ChangeType::Update,
+                fields_changed: None,
+                changed_by_user_id: identity.user_id,
+                changed_by_email: actor.email,
+                changed_by_name: actor.name,
+                changed_by_device_id: identity.device_id,
+                changed_by_device_name: actor.device_name,
+                created_at: Utc::now(),
+            });
 
-        let (payload_enc, checksum) = encrypt_secret_payload(state, &vault, item.id, &payload)?;
-        item.payload_enc = payload_enc;
-        item.checksum = checksum;
+            let (payload_enc, checksum) = encrypt_secret_payload(state, &vault, item.id, &payload)?;
+            item.payload_enc = payload_enc;
+            item.checksum = checksum;
+        }
+        item.expires_at = expires_at;
         item.version = item.version.saturating_add(1);
         item.device_id = device_id;
         item.updated_at = Utc::now();
 
-        let Ok(affected) = item_repo.update(&item).await else {
-            tracing::error!(event = "secret_set_failed", "DB error");
-            return Err(SecretError::DbError);
-        };
-        if affected == 0 {
-            return Err(SecretError::Conflict("row_version_conflict"));
-        }
-
-        let change_repo = ChangeRepo::new(&state.db);
         let change = Change {
             seq: 0,
             vault_id: vault.id,
@@ -382,17 +394,25 @@ pub async fn set_secret(
             device_id,
             created_at: item.updated_at,
         };
-        if let Err(err) = change_repo.create(&change).await {
-            tracing::warn!(event = "secret_change_create_failed", error = %err);
+        let entry = secret_audit_entry("set", "updated", &vault, path);
+        match write_item_change(state, identity, &item, history.as_ref(), &change, entry).await {
+            Ok(true) => {}
+            Ok(false) => return Err(SecretError::Conflict("row_version_conflict")),
+            Err(err) => {
+                tracing::error!(event = "secret_set_failed", error = %err, "DB error");
+                return Err(SecretError::DbError);
+            }
         }
 
         let record = SecretRecord {
+            item_id: item.id,
             path: item.path,
             vault_id: vault.id.to_string(),
             value: payload.value,
             policy: payload.policy,
             meta: payload.meta,
             version: item.version,
+            expires_at: item.expires_at,
         };
         return Ok((record, false));
     }
@@ -426,38 +446,11 @@ pub async fn set_secret(
         deleted_at: None,
         deleted_by_user_id: None,
         deleted_by_device_id: None,
+        expires_at: expires_at.flatten(),
         created_at: now,
         updated_at: now,
     };
 
-    match item_repo.create(&item).await {
-        Ok(()) => {}
-        Err(err) => {
-            tracing::warn!(event = "secret_set_conflict", error = %err);
-            let existing = item_repo
-                .get_by_vault_path(vault.id, &normalized_path)
-                .await
-                .map_err(|_| SecretError::DbError)?;
-            if let Some(existing) = existing {
-                if existing.type_id != "secret" || existing.sync_status != SyncStatus::Active {
-                    return Err(SecretError::Conflict("path_in_use"));
-                }
-                let payload = decrypt_secret_payload(state, &vault, &existing)?;
-                let record = SecretRecord {
-                    path: existing.path,
-                    vault_id: vault.id.to_string(),
-                    value: payload.value,
-                    policy: payload.policy,
-                    meta: payload.meta,
-                    version: existing.version,
-                };
-                return Ok((record, false));
-            }
-            return Err(SecretError::DbError);
-        }
-    }
-
-    let history_repo = ItemHistoryRepo::new(&state.db);
     let actor = actor_snapshot(state, identity, identity.device_id).await;
     let history = ItemHistory {
         id: Uuid::now_v7(),
@@ -474,11 +467,6 @@ pub async fn set_secret(
         changed_by_device_name: actor.device_name,
         created_at: now,
     };
-    if let Err(err) = history_repo.create(&history).await {
-        tracing::warn!(event = "secret_history_create_failed", error = %err);
-    }
-
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -488,17 +476,46 @@ pub async fn set_secret(
         device_id,
         created_at: now,
     };
-    if let Err(err) = change_repo.create(&change).await {
-        tracing::warn!(event = "secret_change_create_failed", error = %err);
+    let entry = secret_audit_entry("set", "created", &vault, path);
+    if let Err(err) =
+        write_item_change(state, identity, &item, Some(&history), &change, entry).await
+    {
+        tracing::warn!(event = "secret_set_conflict", error = %err);
+        let existing = item_repo
+            .get_by_vault_path(vault.id, &normalized_path)
+            .await
+            .map_err(|_| SecretError::DbError)?;
+        if let Some(existing) = existing {
+            if existing.type_id != "secret" || existing.sync_status != SyncStatus::Active {
+                return Err(SecretError::Conflict("path_in_use"));
+            }
+            let payload = decrypt_secret_payload(state, &vault, &existing)?;
+            let record = SecretRecord {
+                item_id: existing.id,
+                path: existing.path,
+                vault_id: vault.id.to_string(),
+                value: payload.value,
+                policy: payload.policy,
+                meta: payload.meta,
+                version: existing.version,
+                expires_at: existing.expires_at,
+            };
+            let entry = secret_audit_entry("set", "updated", &vault, path);
+            audit::record(state, Some(identity), entry).await;
+            return Ok((record, false));
+        }
+        return Err(SecretError::DbError);
     }
 
     let record = SecretRecord {
+        item_id: item.id,
         path: item.path,
         vault_id: vault.id.to_string(),
         value: value.to_string(),
         policy,
         meta: normalized_meta,
         version: item.version,
+        expires_at: item.expires_at,
     };
     Ok((record, true))
 }
@@ -510,6 +527,7 @@ pub async fn rotate_secret(
     path: &str,
     policy_name: Option<&str>,
     meta: Option<HashMap<String, String>>,
+    wait_for_leases_seconds: Option<u64>,
 ) -> Result<(SecretRecord, i64), SecretError> {
     let device_id = effective_device_id(state, identity).await?;
     let normalized_path = normalize_secret_path(path)?;
@@ -544,9 +562,21 @@ pub async fn rotate_secret(
         return Err(SecretError::NotFound);
     }
 
+    if let Some(wait_seconds) = wait_for_leases_seconds {
+        let outstanding = leases::wait_for_release(state, item.id, wait_seconds)
+            .await
+            .map_err(|_| {
+                tracing::error!(event = "secret_rotate_failed", "DB error");
+                SecretError::DbError
+            })?;
+        if outstanding > 0 {
+            return Err(SecretError::Conflict("leases_outstanding"));
+        }
+    }
+
     let (policy_name, policy) = resolve_policy(state, policy_name)?;
-    let value = generate_secret(&policy).map_err(SecretError::Internal)?;
-    let normalized_meta = normalize_meta(meta);
+    let generated = generate_with_policy(policy).await?;
+    let (value, normalized_meta) = with_public_key(generated, normalize_meta(meta));
     let payload = SecretPayload {
         value: value.clone(),
         policy: policy_name.clone(),
@@ -556,7 +586,6 @@ pub async fn rotate_secret(
     let (payload_enc, checksum) = encrypt_secret_payload(state, &vault, item.id, &payload)?;
     let previous_version = item.version;
 
-    let history_repo = ItemHistoryRepo::new(&state.db);
     let actor = actor_snapshot(state, identity, identity.device_id).await;
     let history = ItemHistory {
         id: Uuid::now_v7(),
@@ -573,9 +602,6 @@ pub async fn rotate_secret(
         changed_by_device_name: actor.device_name,
         created_at: Utc::now(),
     };
-    if let Err(err) = history_repo.create(&history).await {
-        tracing::warn!(event = "secret_history_create_failed", error = %err);
-    }
 
     item.payload_enc = payload_enc;
     item.checksum = checksum;
@@ -583,15 +609,6 @@ pub async fn rotate_secret(
     item.device_id = device_id;
     item.updated_at = Utc::now();
 
-    let Ok(affected) = item_repo.update(&item).await else {
-        tracing::error!(event = "secret_rotate_failed", "DB error");
-        return Err(SecretError::DbError);
-    };
-    if affected == 0 {
-        return Err(SecretError::Conflict("row_version_conflict"));
-    }
-
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -601,22 +618,37 @@ pub async fn rotate_secret(
         device_id,
         created_at: item.updated_at,
     };
-    if let Err(err) = change_repo.create(&change).await {
-        tracing::warn!(event = "secret_change_create_failed", error = %err);
+    let entry = secret_audit_entry("rotate", "ok", &vault, path);
+    match write_item_change(state, identity, &item, Some(&history), &change, entry).await {
+        Ok(true) => {}
+        Ok(false) => return Err(SecretError::Conflict("row_version_conflict")),
+        Err(err) => {
+            tracing::error!(event = "secret_rotate_failed", error = %err, "DB error");
+            return Err(SecretError::DbError);
+        }
     }
 
     let record = SecretRecord {
+        item_id: item.id,
         path: item.path,
         vault_id: vault.id.to_string(),
         value,
         policy: policy_name,
         meta: normalized_meta,
         version: item.version,
+        expires_at: item.expires_at,
     };
     Ok((record, previous_version))
 }
 
-fn normalize_secret_path(path: &str) -> Result<String, SecretError> {
+/// Audit event for a successful secret operation on the requested `path`.
+fn secret_audit_entry(action: &str, result: &str, vault: &Vault, path: &str) -> AuditEntry {
+    AuditEntry::new("secrets", action, result)
+        .vault(vault.id)
+        .path(path)
+}
+
+pub(crate) fn normalize_secret_path(path: &str) -> Result<String, SecretError> {
     let trimmed = path.trim().trim_matches('/');
     if trimmed.is_empty() {
         return Err(SecretError::BadRequest("invalid_path"));
@@ -624,6 +656,20 @@ fn normalize_secret_path(path: &str) -> Result<String, SecretError> {
     Ok(format!("/{trimmed}"))
 }
 
+/// Stores the public half of a generated key pair in `meta.public_key` so it
+/// can be read back without the private key.
+fn with_public_key(
+    generated: GeneratedSecret,
+    meta: Option<HashMap<String, String>>,
+) -> (String, Option<HashMap<String, String>>) {
+    let Some(public_key) = generated.public_key else {
+        return (generated.value, meta);
+    };
+    let mut meta = meta.unwrap_or_default();
+    meta.insert(PUBLIC_KEY_META.to_string(), public_key);
+    (generated.value, Some(meta))
+}
+
 fn normalize_meta(meta: Option<HashMap<String, String>>) -> Option<HashMap<String, String>> {
     meta.and_then(|map| {
         let filtered: HashMap<String, String> = map
@@ -726,10 +772,29 @@ fn resolve_policy_name(state: &AppState, policy_name: Option<&str>) -> String {
         .unwrap_or_else(|| state.secret_default_policy.clone())
 }
 
+/// Key pairs (RSA up to 8192 bits) can take seconds to generate, so they are
+/// built on the blocking pool instead of an async worker.
+async fn generate_with_policy(policy: SecretPolicy) -> Result<GeneratedSecret, SecretError> {
+    if !policy.is_key_pair() {
+        return generate_secret(&policy).map_err(SecretError::Internal);
+    }
+    tokio::task::spawn_blocking(move || generate_secret(&policy))
+        .await
+        .map_err(|err| {
+            tracing::error!(
+                event = "secret_generate_failed",
+                error = %err,
+                "Key generation task failed"
+            );
+            SecretError::Internal("secret_generate_failed")
+        })?
+        .map_err(SecretError::Internal)
+}
+
 fn resolve_policy(
     state: &AppState,
     policy_name: Option<&str>,
-) -> Result<(String, PasswordPolicy), SecretError> {
+) -> Result<(String, SecretPolicy), SecretError> {
     let name = resolve_policy_name(state, policy_name);
     let Some(policy) = state.secret_policies.get(&name).cloned() else {
         return Err(SecretError::BadRequest("unknown_policy"));
@@ -738,7 +803,7 @@ fn resolve_policy(
     Ok((name, policy))
 }
 
-fn ensure_server_encryption(state: &AppState, vault: &Vault) -> Result<(), SecretError> {
+pub(crate) fn ensure_server_encryption(state: &AppState, vault: &Vault) -> Result<(), SecretError> {
     if vault.encryption_type != zann_core::VaultEncryptionType::Server {
         return Err(SecretError::BadRequest("vault_not_server_encrypted"));
     }
@@ -748,16 +813,48 @@ fn ensure_server_encryption(state: &AppState, vault: &Vault) -> Result<(), Secre
     Ok(())
 }
 
+/// Decrypts the vault key with the server master key, for the engines that
+/// keep their own material (transit keys, CA keys, configs) under it.
+pub(crate) fn server_vault_key(state: &AppState, vault: &Vault) -> Result<SecretKey, ServiceError> {
+    let Some(smk) = state.server_master_key.get() else {
+        return Err(ServiceError::Internal("smk_missing"));
+    };
+    core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc)
+        .map_err(|_| ServiceError::Internal("decrypt_failed"))
+}
+
+pub(crate) fn result_label<T>(result: &Result<T, ServiceError>) -> &'static str {
+    match result {
+        Ok(_) => "ok",
+        Err(_) => "error",
+    }
+}
+
+/// Trims a transit key or engine role name and checks it is made of ASCII
+/// alphanumerics, `-`, `_` and `.`; rejects it with `error` otherwise.
+pub(crate) fn normalize_name(name: &str, error: &'static str) -> Result<String, ServiceError> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(ServiceError::BadRequest(error));
+    }
+    Ok(name.to_string())
+}
+
 fn decrypt_secret_payload(
     state: &AppState,
     vault: &Vault,
     item: &Item,
 ) -> Result<SecretPayload, SecretError> {
-    let Some(smk) = state.server_master_key.as_ref() else {
+    let Some(smk) = state.server_master_key.get() else {
         return Err(SecretError::Internal("smk_missing"));
     };
     let vault_key =
-        core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc).map_err(|err| {
+        core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc).map_err(|err| {
             tracing::error!(event = "secret_decrypt_failed", error = %err);
             SecretError::Internal("vault_key_decrypt_failed")
         })?;
@@ -786,11 +883,11 @@ fn encrypt_secret_payload(
     item_id: Uuid,
     payload: &SecretPayload,
 ) -> Result<(Vec<u8>, String), SecretError> {
-    let Some(smk) = state.server_master_key.as_ref() else {
+    let Some(smk) = state.server_master_key.get() else {
         return Err(SecretError::Internal("smk_missing"));
     };
     let vault_key =
-        core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc).map_err(|err| {
+        core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc).map_err(|err| {
             tracing::error!(event = "secret_encrypt_failed", error = %err);
             SecretError::Internal("vault_key_decrypt_failed")
         })?;
@@ -809,7 +906,7 @@ fn encrypt_secret_payload(
     Ok((payload_enc, checksum))
 }
 
-async fn authorize_vault_access(
+pub(crate) async fn authorize_vault_access(
     state: &AppState,
     identity: &Identity,
     vault_id: &str,
@@ -908,13 +1005,6 @@ async fn service_account_allows_path(
     })
 }
 
-fn scope_allows_action(permission: &str, action: &str) -> bool {
-    match action {
-        "read" | "list" => permission == "read",
-        _ => permission == action,
-    }
-}
-
 fn scope_matches_path(rule: &ScopeRule, vault: &Vault, path: &str) -> bool {
     if !vault_matches_scope(vault, &rule.target) {
         return false;
@@ -0,0 +1,294 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zann_core::{Identity, ServiceAccount};
+
+use crate::app::AppState;
+use crate::domains::errors::ServiceError;
+use crate::domains::service_accounts::service::{
+    self, account_description, account_vault_id, CreateServiceAccountCommand, IssuedServiceAccount,
+    ListServiceAccountsCommand, ServiceAccountError,
+};
+use crate::infra::audit::{self, AuditEntry};
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    error: &'static str,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct ListServiceAccountsQuery {
+    /// Vault id or slug. Lists every account scoped to the vault instead of
+    /// only the caller's own accounts; requires the vault admin role.
+    #[serde(default)]
+    vault: Option<String>,
+    #[serde(default)]
+    sort: Option<String>,
+    #[serde(default)]
+    limit: Option<i64>,
+    #[serde(default)]
+    offset: Option<i64>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct CreateServiceAccountRequest {
+    name: String,
+    /// Vault id or slug.
+    vault: String,
+    /// Path prefixes the token is limited to; `["/"]` grants the whole vault.
+    prefixes: Vec<String>,
+    /// Any of `read`, `write`, `read_history`, `read_previous`.
+    #[serde(default = "default_ops")]
+    ops: Vec<String>,
+    /// Lifetime such as `12h` or `30d`; omitted means no expiry.
+    #[serde(default)]
+    ttl: Option<String>,
+    #[serde(default)]
+    allowed_ips: Option<Vec<String>>,
+}
+
+fn default_ops() -> Vec<String> {
+    vec!["read".to_string()]
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ServiceAccountResponse {
+    pub(crate) id: String,
+    pub(crate) owner_user_id: String,
+    pub(crate) name: String,
+    pub(crate) token_prefix: String,
+    pub(crate) scopes: Vec<String>,
+    pub(crate) allowed_ips: Option<Vec<String>>,
+    pub(crate) issued_by: Option<String>,
+    pub(crate) vault_id: Option<String>,
+    pub(crate) vault_slug: Option<String>,
+    pub(crate) prefixes: Option<Vec<String>>,
+    pub(crate) ops: Option<Vec<String>>,
+    pub(crate) expires_at: Option<String>,
+    pub(crate) last_used_at: Option<String>,
+    pub(crate) last_used_ip: Option<String>,
+    pub(crate) use_count: i64,
+    pub(crate) created_at: String,
+    pub(crate) revoked_at: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ServiceAccountListResponse {
+    pub(crate) service_accounts: Vec<ServiceAccountResponse>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ServiceAccountTokenResponse {
+    /// Plaintext token. It is shown once and cannot be retrieved later.
+    pub(crate) token: String,
+    pub(crate) service_account: ServiceAccountResponse,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/v1/service-accounts",
+            get(list_service_accounts).post(create_service_account),
+        )
+        .route(
+            "/v1/service-accounts/:id",
+            get(get_service_account).delete(revoke_service_account),
+        )
+        .route(
+            "/v1/service-accounts/:id/rotate",
+            post(rotate_service_account),
+        )
+}
+
+fn map_service_account_error(error: ServiceAccountError) -> axum::response::Response {
+    match error {
+        ServiceAccountError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        ServiceAccountError::Forbidden(code) => {
+            (StatusCode::FORBIDDEN, Json(ErrorResponse { error: code })).into_response()
+        }
+        ServiceAccountError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        ServiceAccountError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        ServiceAccountError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        ServiceAccountError::DbError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response(),
+        ServiceAccountError::Kdf => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "kdf_error" }),
+        )
+            .into_response(),
+        ServiceAccountError::Internal(code) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: code }),
+        )
+            .into_response(),
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, query))]
+async fn list_service_accounts(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Query(query): Query<ListServiceAccountsQuery>,
+) -> impl IntoResponse {
+    let command = ListServiceAccountsCommand {
+        vault: query.vault,
+        sort: query.sort,
+        limit: query.limit,
+        offset: query.offset,
+    };
+    match service::list_service_accounts(&state, &identity, command).await {
+        Ok(accounts) => {
+            let service_accounts = accounts.into_iter().map(service_account_response).collect();
+            (
+                StatusCode::OK,
+                Json(ServiceAccountListResponse { service_accounts }),
+            )
+                .into_response()
+        }
+        Err(err) => map_service_account_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn create_service_account(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Json(payload): Json<CreateServiceAccountRequest>,
+) -> impl IntoResponse {
+    let command = CreateServiceAccountCommand {
+        name: payload.name,
+        vault: payload.vault,
+        prefixes: payload.prefixes,
+        ops: payload.ops,
+        ttl: payload.ttl,
+        allowed_ips: payload.allowed_ips,
+    };
+    let name = command.name.clone();
+    let result = service::create_service_account(&state, &identity, command).await;
+    let target = result
+        .as_ref()
+        .map_or(name, |issued| issued.account.id.to_string());
+    let vault_id = result
+        .as_ref()
+        .ok()
+        .and_then(|issued| account_vault_id(&issued.account));
+    audit_service_account(&state, &identity, "create", &result, &target, vault_id).await;
+    match result {
+        Ok(issued) => (StatusCode::CREATED, Json(token_response(issued))).into_response(),
+        Err(err) => map_service_account_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_service_account(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    match service::get_service_account(&state, &identity, &id).await {
+        Ok(account) => (StatusCode::OK, Json(service_account_response(account))).into_response(),
+        Err(err) => map_service_account_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn rotate_service_account(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    let result = service::rotate_service_account(&state, &identity, &id).await;
+    let vault_id = result
+        .as_ref()
+        .ok()
+        .and_then(|issued| account_vault_id(&issued.account));
+    audit_service_account(&state, &identity, "rotate", &result, &id, vault_id).await;
+    match result {
+        Ok(issued) => (StatusCode::OK, Json(token_response(issued))).into_response(),
+        Err(err) => map_service_account_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn revoke_service_account(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl IntoResponse {
+    let result = service::revoke_service_account(&state, &identity, &id).await;
+    let vault_id = result.as_ref().ok().and_then(account_vault_id);
+    audit_service_account(&state, &identity, "revoke", &result, &id, vault_id).await;
+    match result {
+        Ok(account) => (StatusCode::OK, Json(service_account_response(account))).into_response(),
+        Err(err) => map_service_account_error(err),
+    }
+}
+
+pub(crate) fn service_account_response(account: ServiceAccount) -> ServiceAccountResponse {
+    let description = account_description(&account);
+    ServiceAccountResponse {
+        id: account.id.to_string(),
+        owner_user_id: account.owner_user_id.to_string(),
+        name: account.name,
+        token_prefix: account.token_prefix,
+        scopes: account.scopes.0,
+        allowed_ips: account.allowed_ips.map(|ips| ips.0),
+        issued_by: description.as_ref().map(|value| value.issued_by.clone()),
+        vault_id: description.as_ref().map(|value| value.vault_id.clone()),
+        vault_slug: description.as_ref().map(|value| value.vault_slug.clone()),
+        prefixes: description
+            .as_ref()
+            .and_then(|value| value.prefixes.clone()),
+        ops: description.map(|value| value.ops),
+        expires_at: account.expires_at.map(|value| value.to_rfc3339()),
+        last_used_at: account.last_used_at.map(|value| value.to_rfc3339()),
+        last_used_ip: account.last_used_ip,
+        use_count: account.use_count,
+        created_at: account.created_at.to_rfc3339(),
+        revoked_at: account.revoked_at.map(|value| value.to_rfc3339()),
+    }
+}
+
+fn token_response(issued: IssuedServiceAccount) -> ServiceAccountTokenResponse {
+    ServiceAccountTokenResponse {
+        token: issued.token,
+        service_account: service_account_response(issued.account),
+    }
+}
+
+async fn audit_service_account<T>(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    result: &Result<T, ServiceError>,
+    target: &str,
+    vault_id: Option<Uuid>,
+) {
+    let label = result.as_ref().err().map_or("ok", ServiceError::label);
+    let mut entry = AuditEntry::new("service_accounts", action, label).target(target);
+    if let Some(vault_id) = vault_id {
+        entry = entry.vault(vault_id);
+    }
+    audit::record(state, Some(identity), entry).await;
+}
@@ -0,0 +1,403 @@
+use chrono::Utc;
+use uuid::Uuid;
+use zann_core::{Identity, ServiceAccount, Vault, VaultEncryptionType, VaultKind};
+use zann_db::repo::{ServiceAccountRepo, ServiceAccountSessionRepo, VaultRepo};
+
+use crate::app::AppState;
+use crate::domains::access_control::http::{find_vault, vault_role_allows, VaultScope};
+use crate::domains::access_control::policies::PolicyDecision;
+use crate::domains::auth::core::allowed_ips::normalize_allowed_ip;
+use crate::domains::errors::ServiceError;
+use crate::domains::service_accounts::tokens::{
+    build_scopes, issue_token, parse_description, parse_ops, parse_prefixes, parse_ttl,
+    prefixes_display, IssuedToken, TokenDescription,
+};
+use crate::infra::metrics;
+
+pub struct CreateServiceAccountCommand {
+    pub name: String,
+    pub vault: String,
+    pub prefixes: Vec<String>,
+    pub ops: Vec<String>,
+    pub ttl: Option<String>,
+    pub allowed_ips: Option<Vec<String>>,
+}
+
+pub struct ListServiceAccountsCommand {
+    pub vault: Option<String>,
+    pub sort: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub type ServiceAccountError = ServiceError;
+
+/// A service account together with the plaintext token, which is only ever
+/// returned on create and rotate.
+pub struct IssuedServiceAccount {
+    pub account: ServiceAccount,
+    pub token: String,
+}
+
+fn forbidden(resource: &str, action: &str, reason: &str) -> ServiceAccountError {
+    metrics::forbidden_access(resource);
+    tracing::warn!(
+        event = "forbidden",
+        action = action,
+        resource = %resource,
+        reason = reason,
+        "Access denied"
+    );
+    ServiceAccountError::ForbiddenNoBody
+}
+
+fn ensure_policy(
+    state: &AppState,
+    identity: &Identity,
+    resource: &str,
+    action: &str,
+) -> Result<(), ServiceAccountError> {
+    // Tokens must not be able to mint or rotate other tokens.
+    if identity.service_account_id.is_some() {
+        return Err(forbidden(resource, action, "service_account"));
+    }
+    let policies = state.policy_store.get();
+    if !policies.is_allowed(identity, action, resource) {
+        return Err(forbidden(resource, action, "policy"));
+    }
+    Ok(())
+}
+
+/// Managing tokens for a vault requires a `write` grant on
+/// `vaults/<id>/service-accounts` or the vault admin role. Listing and reading
+/// use the same check because they expose every token scoped to the vault;
+/// the broad `list` grant on `vaults/*` must not open them up.
+async fn ensure_vault_manager(
+    state: &AppState,
+    identity: &Identity,
+    vault: &Vault,
+    action: &str,
+) -> Result<(), ServiceAccountError> {
+    let resource = format!("vaults/{}/service-accounts", vault.id);
+    let policies = state.policy_store.get();
+    match policies.evaluate(identity, "write", &resource) {
+        PolicyDecision::Allow => Ok(()),
+        PolicyDecision::Deny => Err(forbidden(&resource, action, "policy")),
+        PolicyDecision::NoMatch => {
+            match vault_role_allows(state, identity, vault.id, "write", VaultScope::Vault).await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(forbidden(&resource, action, "vault_role")),
+                Err(_) => {
+                    tracing::error!(event = "service_account_access_failed", "DB error");
+                    Err(ServiceAccountError::DbError)
+                }
+            }
+        }
+    }
+}
+
+async fn load_vault(state: &AppState, selector: &str) -> Result<Vault, ServiceAccountError> {
+    let repo = VaultRepo::new(&state.db);
+    match find_vault(&repo, selector).await {
+        Ok(Some(vault)) => Ok(vault),
+        Ok(None) => Err(ServiceAccountError::NotFound),
+        Err(_) => {
+            tracing::error!(event = "service_account_vault_lookup_failed", "DB error");
+            Err(ServiceAccountError::DbError)
+        }
+    }
+}
+
+/// Owners may always manage their own accounts; anyone else needs to manage
+/// the vault the account is scoped to.
+async fn load_account(
+    state: &AppState,
+    identity: &Identity,
+    id: &str,
+    action: &str,
+) -> Result<ServiceAccount, ServiceAccountError> {
+    let resource = format!("service-accounts/{id}");
+    ensure_policy(state, identity, &resource, action)?;
+    let account_id =
+        Uuid::parse_str(id).map_err(|_| ServiceAccountError::BadRequest("invalid_id"))?;
+
+    let repo = ServiceAccountRepo::new(&state.db);
+    let account = match repo.get_by_id(account_id).await {
+        Ok(Some(account)) => account,
+        Ok(None) => return Err(ServiceAccountError::NotFound),
+        Err(_) => {
+            tracing::error!(event = "service_account_get_failed", "DB error");
+            return Err(ServiceAccountError::DbError);
+        }
+    };
+    if account.owner_user_id == identity.user_id {
+        return Ok(account);
+    }
+
+    let Some(vault_id) = account_vault_id(&account) else {
+        return Err(forbidden(&resource, action, "not_owner"));
+    };
+    let vault = match VaultRepo::new(&state.db).get_by_id(vault_id).await {
+        Ok(Some(vault)) => vault,
+        Ok(None) => return Err(forbidden(&resource, action, "not_owner")),
+        Err(_) => {
+            tracing::error!(event = "service_account_get_failed", "DB error");
+            return Err(ServiceAccountError::DbError);
+        }
+    };
+    ensure_vault_manager(state, identity, &vault, action).await?;
+    Ok(account)
+}
+
+#[must_use]
+pub fn account_description(account: &ServiceAccount) -> Option<TokenDescription> {
+    account
+        .description
+        .as_deref()
+        .and_then(|value| parse_description(value).ok())
+}
+
+#[must_use]
+pub fn account_vault_id(account: &ServiceAccount) -> Option<Uuid> {
+    account_description(account).and_then(|description| description.vault_id.parse().ok())
+}
+
+fn parse_allowed_ips(values: Vec<String>) -> Result<Vec<String>, ServiceAccountError> {
+    values
+        .iter()
+        .map(|value| {
+            normalize_allowed_ip(value)
+                .map_err(|_| ServiceAccountError::BadRequest("invalid_allowed_ips"))
+        })
+        .collect()
+}
+
+async fn issue(state: &AppState, operation: &str) -> Result<IssuedToken, ServiceAccountError> {
+    let _permit = match metrics::acquire_kdf_permit(&state.argon2_semaphore, operation).await {
+        Ok(permit) => permit,
+        Err(_) => {
+            tracing::error!(
+                event = "service_account_token_failed",
+                "Argon2 limiter closed"
+            );
+            return Err(ServiceAccountError::Kdf);
+        }
+    };
+    issue_token(&state.config, &state.token_pepper).map_err(|err| {
+        tracing::error!(event = "service_account_token_failed", error = %err);
+        ServiceAccountError::Kdf
+    })
+}
+
+pub async fn create_service_account(
+    state: &AppState,
+    identity: &Identity,
+    cmd: CreateServiceAccountCommand,
+) -> Result<IssuedServiceAccount, ServiceAccountError> {
+    ensure_policy(state, identity, "service-accounts", "write")?;
+
+    let name = cmd.name.trim();
+    if name.is_empty() {
+        return Err(ServiceAccountError::BadRequest("invalid_name"));
+    }
+    let ops = parse_ops(&cmd.ops.join(","))
+        .map_err(|_| ServiceAccountError::BadRequest("invalid_ops"))?;
+    let prefixes = parse_prefixes(cmd.prefixes.iter().map(String::as_str))
+        .map_err(|_| ServiceAccountError::BadRequest("invalid_prefixes"))?;
+    let expires_at = match cmd.ttl.as_deref() {
+        Some(ttl) => {
+            let duration =
+                parse_ttl(ttl).map_err(|_| ServiceAccountError::BadRequest("invalid_ttl"))?;
+            if duration <= chrono::Duration::zero() {
+                return Err(ServiceAccountError::BadRequest("invalid_ttl"));
+            }
+            Some(Utc::now() + duration)
+        }
+        None => None,
+    };
+    let allowed_ips = cmd.allowed_ips.map(parse_allowed_ips).transpose()?;
+
+    let vault = load_vault(state, cmd.vault.trim()).await?;
+    ensure_vault_manager(state, identity, &vault, "write").await?;
+    if vault.kind != VaultKind::Shared || vault.encryption_type != VaultEncryptionType::Server {
+        return Err(ServiceAccountError::BadRequest("unsupported_vault"));
+    }
+
+    let repo = ServiceAccountRepo::new(&state.db);
+    match repo
+        .get_active_by_owner_and_name(identity.user_id, name)
+        .await
+    {
+        Ok(Some(_)) => return Err(ServiceAccountError::Conflict("name_taken")),
+        Ok(None) => {}
+        Err(_) => {
+            tracing::error!(event = "service_account_create_failed", "DB error");
+            return Err(ServiceAccountError::DbError);
+        }
+    }
+
+    let issued = issue(state, "service_accounts_create").await?;
+    let prefixes_display = prefixes_display(prefixes.as_deref());
+    let description = TokenDescription {
+        issued_by: identity.email.clone(),
+        vault_id: vault.id.to_string(),
+        vault_slug: vault.slug.clone(),
+        prefix: prefixes_display.first().cloned(),
+        prefixes: Some(prefixes_display),
+        ops: ops.iter().map(|op| (*op).to_string()).collect(),
+    };
+    let description = serde_json::to_string(&description)
+        .map_err(|_| ServiceAccountError::Internal("description_encode_failed"))?;
+
+    let account = ServiceAccount {
+        id: Uuid::now_v7(),
+        owner_user_id: identity.user_id,
+        name: name.to_string(),
+        description: Some(description),
+        token_hash: issued.token_hash,
+        token_prefix: issued.token_prefix,
+        scopes: sqlx_core::types::Json(build_scopes(vault.id, prefixes.as_deref(), &ops)),
+        allowed_ips: allowed_ips.map(sqlx_core::types::Json),
+        expires_at,
+        last_used_at: None,
+        last_used_ip: None,
+        last_used_user_agent: None,
+        use_count: 0,
+        created_at: Utc::now(),
+        revoked_at: None,
+    };
+    if let Err(err) = repo.create(&account).await {
+        tracing::error!(event = "service_account_create_failed", error = %err, "DB error");
+        return Err(ServiceAccountError::DbError);
+    }
+
+    tracing::info!(
+        event = "service_account_created",
+        service_account_id = %account.id,
+        vault_id = %vault.id,
+        "Service account created"
+    );
+    Ok(IssuedServiceAccount {
+        account,
+        token: issued.token,
+    })
+}
+
+pub async fn list_service_accounts(
+    state: &AppState,
+    identity: &Identity,
+    cmd: ListServiceAccountsCommand,
+) -> Result<Vec<ServiceAccount>, ServiceAccountError> {
+    ensure_policy(state, identity, "service-accounts", "list")?;
+
+    let sort = match cmd.sort.as_deref() {
+        Some("asc") => "asc",
+        Some("desc") | None => "desc",
+        Some(_) => return Err(ServiceAccountError::BadRequest("invalid_query")),
+    };
+    let limit = cmd.limit.unwrap_or(100).clamp(1, 500);
+    let offset = cmd.offset.unwrap_or(0).max(0);
+
+    let repo = ServiceAccountRepo::new(&state.db);
+    let accounts = if let Some(selector) = cmd.vault.as_deref() {
+        let vault = load_vault(state, selector.trim()).await?;
+        ensure_vault_manager(state, identity, &vault, "list").await?;
+        repo.list_by_vault(vault.id, limit, offset, sort).await
+    } else {
+        repo.list_by_owner(identity.user_id, limit, offset, sort)
+            .await
+    };
+    let Ok(accounts) = accounts else {
+        tracing::error!(event = "service_accounts_list_failed", "DB error");
+        return Err(ServiceAccountError::DbError);
+    };
+
+    tracing::info!(
+        event = "service_accounts_listed",
+        count = accounts.len(),
+        "Service accounts listed"
+    );
+    Ok(accounts)
+}
+
+pub async fn get_service_account(
+    state: &AppState,
+    identity: &Identity,
+    id: &str,
+) -> Result<ServiceAccount, ServiceAccountError> {
+    load_account(state, identity, id, "read").await
+}
+
+/// Replaces the token secret in place. The account ID, scopes and expiry are
+/// kept; sessions minted from the old token are dropped.
+pub async fn rotate_service_account(
+    state: &AppState,
+    identity: &Identity,
+    id: &str,
+) -> Result<IssuedServiceAccount, ServiceAccountError> {
+    let mut account = load_account(state, identity, id, "write").await?;
+    if account.revoked_at.is_some() {
+        return Err(ServiceAccountError::Conflict("service_account_revoked"));
+    }
+
+    let issued = issue(state, "service_accounts_rotate").await?;
+    let repo = ServiceAccountRepo::new(&state.db);
+    match repo
+        .update_token(account.id, &issued.token_hash, &issued.token_prefix)
+        .await
+    {
+        Ok(0) => return Err(ServiceAccountError::NotFound),
+        Ok(_) => {}
+        Err(_) => {
+            tracing::error!(event = "service_account_rotate_failed", "DB error");
+            return Err(ServiceAccountError::DbError);
+        }
+    }
+    revoke_sessions(state, account.id).await?;
+    account.token_hash = issued.token_hash;
+    account.token_prefix = issued.token_prefix;
+
+    tracing::info!(
+        event = "service_account_rotated",
+        service_account_id = %account.id,
+        "Service account token rotated"
+    );
+    Ok(IssuedServiceAccount {
+        account,
+        token: issued.token,
+    })
+}
+
+pub async fn revoke_service_account(
+    state: &AppState,
+    identity: &Identity,
+    id: &str,
+) -> Result<ServiceAccount, ServiceAccountError> {
+    let mut account = load_account(state, identity, id, "write").await?;
+    if account.revoked_at.is_none() {
+        let revoked_at = Utc::now();
+        let repo = ServiceAccountRepo::new(&state.db);
+        if repo.revoke(account.id, revoked_at).await.is_err() {
+            tracing::error!(event = "service_account_revoke_failed", "DB error");
+            return Err(ServiceAccountError::DbError);
+        }
+        account.revoked_at = Some(revoked_at);
+    }
+    revoke_sessions(state, account.id).await?;
+
+    tracing::info!(
+        event = "service_account_revoked",
+        service_account_id = %account.id,
+        "Service account revoked"
+    );
+    Ok(account)
+}
+
+async fn revoke_sessions(state: &AppState, id: Uuid) -> Result<(), ServiceAccountError> {
+    let repo = ServiceAccountSessionRepo::new(&state.db);
+    if repo.revoke_by_service_account(id).await.is_err() {
+        tracing::error!(event = "service_account_sessions_revoke_failed", "DB error");
+        return Err(ServiceAccountError::DbError);
+    }
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use chrono::Duration as ChronoDuration;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::config::ServerConfig;
+use crate::domains::auth::core::passwords;
+
+pub const SERVICE_ACCOUNT_PREFIX: &str = "zann_sa_";
+pub const SERVICE_ACCOUNT_PREFIX_LEN: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenDescription {
+    pub issued_by: String,
+    pub vault_id: String,
+    pub vault_slug: String,
+    pub prefix: Option<String>,
+    pub prefixes: Option<Vec<String>>,
+    pub ops: Vec<String>,
+}
+
+pub fn parse_description(value: &str) -> Result<TokenDescription, String> {
+    serde_json::from_str(value).map_err(|_| "invalid description".to_string())
+}
+
+#[derive(Debug, Clone)]
+pub struct NormalizedPrefix {
+    pub canonical: String,
+    pub scope: String,
+}
+
+pub fn normalize_prefix(prefix: &str) -> Result<NormalizedPrefix, String> {
+    let trimmed = prefix.trim();
+    if trimmed.is_empty() {
+        return Err("prefix cannot be empty".to_string());
+    }
+    let canonical = trimmed.trim_matches('/').to_string();
+    if canonical.is_empty() {
+        return Err("prefix cannot be root".to_string());
+    }
+    Ok(NormalizedPrefix {
+        canonical: format!("/{canonical}"),
+        scope: canonical.replace('/', "::"),
+    })
+}
+
+pub fn parse_ops(value: &str) -> Result<Vec<&'static str>, String> {
+    let mut ops = Vec::new();
+    for token in value.split(',') {
+        let token = token.trim();
+        if token.is_empty() {
+            continue;
+        }
+        let normalized = token.to_ascii_lowercase();
+        let op = match normalized.as_str() {
+            "read" => "read",
+            "write" => "write",
+            "read_history" => "read_history",
+            "read_previous" => "read_previous",
+            "history_read" => "read_history",
+            "transit_encrypt" => "transit_encrypt",
+            "transit_decrypt" => "transit_decrypt",
+            "ssh_sign" => "ssh_sign",
+            "pki_issue" => "pki_issue",
+            _ => return Err(format!("invalid ops: {token}")),
+        };
+        if !ops.contains(&op) {
+            ops.push(op);
+        }
+    }
+    if ops.is_empty() {
+        return Err("invalid ops".to_string());
+    }
+    Ok(ops)
+}
+
+pub fn parse_ttl(value: &str) -> Result<ChronoDuration, String> {
+    let trimmed = value.trim().to_ascii_lowercase();
+    if trimmed.is_empty() {
+        return Err("invalid ttl".to_string());
+    }
+    let (amount, unit) = trimmed.split_at(trimmed.len().saturating_sub(1));
+    let amount = amount
+        .parse::<i64>()
+        .map_err(|_| "invalid ttl".to_string())?;
+    match unit {
+        "s" => Ok(ChronoDuration::seconds(amount)),
+        "m" => Ok(ChronoDuration::minutes(amount)),
+        "h" => Ok(ChronoDuration::hours(amount)),
+        "d" => Ok(ChronoDuration::days(amount)),
+        _ => Err("invalid ttl".to_string()),
+    }
+}
+
+/// Prefixes a token is limited to. `None` means the whole vault (`/`).
+pub fn parse_prefixes<'a, I>(values: I) -> Result<Option<Vec<NormalizedPrefix>>, String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut normalized_prefixes = Vec::new();
+    let mut full_vault = false;
+    for prefix in values {
+        let prefix = prefix.trim();
+        if prefix.is_empty() {
+            return Err("invalid prefix".to_string());
+        }
+        if prefix == "/" {
+            full_vault = true;
+            continue;
+        }
+        normalized_prefixes.push(normalize_prefix(prefix)?);
+    }
+    if full_vault && !normalized_prefixes.is_empty() {
+        return Err("prefix '/' cannot be combined with other prefixes".to_string());
+    }
+    if !full_vault && normalized_prefixes.is_empty() {
+        return Err("missing prefix".to_string());
+    }
+    Ok((!full_vault).then_some(normalized_prefixes))
+}
+
+pub fn build_scopes(
+    vault_id: uuid::Uuid,
+    prefixes: Option<&[NormalizedPrefix]>,
+    ops: &[&str],
+) -> Vec<String> {
+    match prefixes {
+        None => ops.iter().map(|op| format!("{vault_id}:{op}")).collect(),
+        Some(prefixes) => prefixes
+            .iter()
+            .flat_map(|prefix| {
+                ops.iter()
+                    .map(move |op| format!("{vault_id}/prefix:{}:{op}", prefix.scope))
+            })
+            .collect(),
+    }
+}
+
+pub fn prefixes_display(prefixes: Option<&[NormalizedPrefix]>) -> Vec<String> {
+    match prefixes {
+        None => vec!["/".to_string()],
+        Some(prefixes) => prefixes
+            .iter()
+            .map(|value| value.canonical.clone())
+            .collect(),
+    }
+}
+
+pub struct IssuedToken {
+    pub token: String,
+    pub token_prefix: String,
+    pub token_hash: String,
+}
+
+/// Generates a fresh `zann_sa_` token and its peppered hash. Callers on the
+/// request path must hold a KDF permit.
+pub fn issue_token(config: &ServerConfig, pepper: &str) -> Result<IssuedToken, &'static str> {
+    let token_suffix: String = thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect();
+    let token = format!("{SERVICE_ACCOUNT_PREFIX}{token_suffix}");
+    let token_prefix: String = token.chars().take(SERVICE_ACCOUNT_PREFIX_LEN).collect();
+    let params = passwords::KdfParams {
+        algorithm: config.auth.kdf.algorithm.clone(),
+        iterations: config.auth.kdf.iterations,
+        memory_kb: config.auth.kdf.memory_kb,
+        parallelism: config.auth.kdf.parallelism,
+    };
+    let token_hash = passwords::hash_service_token(&token, pepper, &params)?;
+    Ok(IssuedToken {
+        token,
+        token_prefix,
+        token_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_ops;
+
+    #[test]
+    fn parse_ops_accepts_write() {
+        let ops = parse_ops("read,write").expect("ops");
+        assert_eq!(ops, vec!["read", "write"]);
+    }
+
+    #[test]
+    fn parse_ops_rejects_unknown_values() {
+        let err = parse_ops("read,rotate").expect_err("invalid ops");
+        assert_eq!(err, "invalid ops: rotate");
+    }
+}
@@ -0,0 +1,404 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zann_core::{Identity, SshCa, SshSigningRole};
+
+use crate::app::AppState;
+use crate::domains::ssh::service::{
+    self, SetSigningRoleCommand, SignCommand, SignedCertificate, SshCertType, SshError,
+};
+use crate::infra::audit::{self, AuditEntry};
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    pub(crate) error: &'static str,
+}
+
+#[derive(Deserialize, JsonSchema, Default)]
+pub(crate) struct CreateSshCaRequest {
+    /// Unencrypted OpenSSH private key to import; a new Ed25519 key is
+    /// generated when omitted.
+    #[serde(default)]
+    pub(crate) private_key: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct SetSshRoleRequest {
+    #[serde(default)]
+    pub(crate) cert_type: SshCertType,
+    /// Principals callers may request; `{{username}}` is the caller's email
+    /// local part and `*` allows any principal.
+    pub(crate) allowed_principals: Vec<String>,
+    #[serde(default)]
+    pub(crate) default_principals: Vec<String>,
+    #[serde(default)]
+    pub(crate) allowed_extensions: Vec<String>,
+    #[serde(default)]
+    pub(crate) default_extensions: Vec<String>,
+    #[serde(default)]
+    pub(crate) default_ttl: Option<String>,
+    #[serde(default)]
+    pub(crate) max_ttl: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct SshSignRequest {
+    /// OpenSSH public key, e.g. the contents of `~/.ssh/id_ed25519.pub`.
+    pub(crate) public_key: String,
+    pub(crate) role: String,
+    #[serde(default)]
+    pub(crate) principals: Vec<String>,
+    #[serde(default)]
+    pub(crate) extensions: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) ttl: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SshCaResponse {
+    pub(crate) id: String,
+    pub(crate) vault_id: String,
+    pub(crate) public_key: String,
+    pub(crate) created_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SshRoleResponse {
+    pub(crate) name: String,
+    pub(crate) cert_type: String,
+    pub(crate) allowed_principals: Vec<String>,
+    pub(crate) default_principals: Vec<String>,
+    pub(crate) allowed_extensions: Vec<String>,
+    pub(crate) default_extensions: Vec<String>,
+    pub(crate) default_ttl_seconds: i64,
+    pub(crate) max_ttl_seconds: i64,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SshRoleListResponse {
+    pub(crate) roles: Vec<SshRoleResponse>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SshSignResponse {
+    /// OpenSSH certificate, to be saved as `<key>-cert.pub`.
+    pub(crate) certificate: String,
+    pub(crate) serial: String,
+    pub(crate) key_id: String,
+    pub(crate) cert_type: SshCertType,
+    pub(crate) principals: Vec<String>,
+    pub(crate) extensions: Vec<String>,
+    pub(crate) valid_after: String,
+    pub(crate) valid_before: String,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/v1/vaults/:vault_id/ssh/ca",
+            get(get_ca).post(create_ca).delete(delete_ca),
+        )
+        .route("/v1/vaults/:vault_id/ssh/roles", get(list_roles))
+        .route(
+            "/v1/vaults/:vault_id/ssh/roles/:role",
+            get(get_role).put(set_role).delete(delete_role),
+        )
+        .route("/v1/vaults/:vault_id/ssh/sign", post(sign))
+}
+
+fn map_ssh_error(error: SshError) -> axum::response::Response {
+    match error {
+        SshError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        SshError::Forbidden(code) => {
+            (StatusCode::FORBIDDEN, Json(ErrorResponse { error: code })).into_response()
+        }
+        SshError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        SshError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        SshError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        SshError::DbError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response(),
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+fn ca_response(ca: SshCa) -> SshCaResponse {
+    SshCaResponse {
+        id: ca.id.to_string(),
+        vault_id: ca.vault_id.to_string(),
+        public_key: ca.public_key,
+        created_at: ca.created_at.to_rfc3339(),
+    }
+}
+
+fn role_response(role: SshSigningRole) -> SshRoleResponse {
+    SshRoleResponse {
+        name: role.name,
+        cert_type: role.cert_type,
+        allowed_principals: role.allowed_principals.0,
+        default_principals: role.default_principals.0,
+        allowed_extensions: role.allowed_extensions.0,
+        default_extensions: role.default_extensions.0,
+        default_ttl_seconds: role.default_ttl_seconds,
+        max_ttl_seconds: role.max_ttl_seconds,
+        created_at: role.created_at.to_rfc3339(),
+        updated_at: role.updated_at.to_rfc3339(),
+    }
+}
+
+fn sign_response(signed: SignedCertificate) -> SshSignResponse {
+    SshSignResponse {
+        certificate: signed.certificate,
+        serial: signed.serial.to_string(),
+        key_id: signed.key_id,
+        cert_type: signed.cert_type,
+        principals: signed.principals,
+        extensions: signed.extensions,
+        valid_after: signed.valid_after.to_rfc3339(),
+        valid_before: signed.valid_before.to_rfc3339(),
+    }
+}
+
+async fn record(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    result: &str,
+    vault_id: &str,
+    target: &str,
+    detail: Option<&str>,
+) {
+    let mut entry = AuditEntry::new("ssh", action, result)
+        .target(target)
+        .detail(detail);
+    if let Some(vault_id) = audit::resolve_vault_id(state, vault_id).await {
+        entry = entry.vault(vault_id);
+    }
+    audit::record(state, Some(identity), entry).await;
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_ca(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+) -> impl IntoResponse {
+    match service::get_ca(&state, &identity, &vault_id).await {
+        Ok(ca) => (StatusCode::OK, Json(ca_response(ca))).into_response(),
+        Err(error) => map_ssh_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn create_ca(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    payload: Option<Json<CreateSshCaRequest>>,
+) -> impl IntoResponse {
+    let Json(payload) = payload.unwrap_or_default();
+    match service::create_ca(&state, &identity, &vault_id, payload.private_key.as_deref()).await {
+        Ok(ca) => {
+            let detail = if payload.private_key.is_some() {
+                "imported"
+            } else {
+                "generated"
+            };
+            record(
+                &state,
+                &identity,
+                "ssh_ca_create",
+                "ok",
+                &vault_id,
+                "ca",
+                Some(detail),
+            )
+            .await;
+            (StatusCode::CREATED, Json(ca_response(ca))).into_response()
+        }
+        Err(error) => map_ssh_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn delete_ca(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+) -> impl IntoResponse {
+    match service::delete_ca(&state, &identity, &vault_id).await {
+        Ok(()) => {
+            record(
+                &state,
+                &identity,
+                "ssh_ca_delete",
+                "ok",
+                &vault_id,
+                "ca",
+                None,
+            )
+            .await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => map_ssh_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn list_roles(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+) -> impl IntoResponse {
+    match service::list_roles(&state, &identity, &vault_id).await {
+        Ok(roles) => (
+            StatusCode::OK,
+            Json(SshRoleListResponse {
+                roles: roles.into_iter().map(role_response).collect(),
+            }),
+        )
+            .into_response(),
+        Err(error) => map_ssh_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_role(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::get_role(&state, &identity, &vault_id, &name).await {
+        Ok(role) => (StatusCode::OK, Json(role_response(role))).into_response(),
+        Err(error) => map_ssh_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn set_role(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+    Json(payload): Json<SetSshRoleRequest>,
+) -> impl IntoResponse {
+    let command = SetSigningRoleCommand {
+        cert_type: payload.cert_type,
+        allowed_principals: payload.allowed_principals,
+        default_principals: payload.default_principals,
+        allowed_extensions: payload.allowed_extensions,
+        default_extensions: payload.default_extensions,
+        default_ttl: payload.default_ttl,
+        max_ttl: payload.max_ttl,
+    };
+    match service::set_role(&state, &identity, &vault_id, &name, command).await {
+        Ok(role) => {
+            record(
+                &state,
+                &identity,
+                "ssh_role_set",
+                "ok",
+                &vault_id,
+                &role.name,
+                Some(&role.cert_type),
+            )
+            .await;
+            (StatusCode::OK, Json(role_response(role))).into_response()
+        }
+        Err(error) => map_ssh_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn delete_role(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::delete_role(&state, &identity, &vault_id, &name).await {
+        Ok(()) => {
+            record(
+                &state,
+                &identity,
+                "ssh_role_delete",
+                "ok",
+                &vault_id,
+                &name,
+                None,
+            )
+            .await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => map_ssh_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn sign(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    Json(payload): Json<SshSignRequest>,
+) -> impl IntoResponse {
+    let role_name = payload.role.clone();
+    let command = SignCommand {
+        public_key: payload.public_key,
+        role: payload.role,
+        principals: payload.principals,
+        extensions: payload.extensions,
+        ttl: payload.ttl,
+    };
+    match service::sign(&state, &identity, &vault_id, command).await {
+        Ok((role, signed)) => {
+            let detail = format!(
+                "serial={} principals={}",
+                signed.serial,
+                signed.principals.join(",")
+            );
+            record(
+                &state,
+                &identity,
+                "ssh_sign",
+                "ok",
+                &vault_id,
+                &role.name,
+                Some(&detail),
+            )
+            .await;
+            (StatusCode::OK, Json(sign_response(signed))).into_response()
+        }
+        Err(error) => {
+            let label = error.label();
+            record(
+                &state,
+                &identity,
+                "ssh_sign",
+                label,
+                &vault_id,
+                &role_name,
+                Some(label),
+            )
+            .await;
+            map_ssh_error(error)
+        }
+    }
+}
@@ -0,0 +1,702 @@
+//! SSH certificate authority: each shared vault can hold one CA key and a set
+//! of signing roles. A role bounds the certificate type, principals,
+//! extensions and validity a caller may request when signing a public key.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use rand::rngs::OsRng;
+use rand::Rng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx_core::types::Json;
+use ssh_key::certificate::{Builder, CertType};
+use ssh_key::private::{Ed25519Keypair, KeypairData};
+use ssh_key::{LineEnding, PrivateKey, PublicKey};
+use uuid::Uuid;
+use zann_core::{Identity, SshCa, SshSigningRole, Vault};
+use zann_crypto::vault_crypto as core_crypto;
+use zann_db::repo::{SshCaRepo, SshSigningRoleRepo, UserRepo};
+
+use crate::app::AppState;
+use crate::domains::access_control::http::VaultScope;
+use crate::domains::errors::ServiceError;
+use crate::domains::secrets::service::{
+    authorize_vault_access, ensure_server_encryption, normalize_name, result_label,
+    server_vault_key,
+};
+use crate::domains::service_accounts::tokens::parse_ttl;
+use crate::infra::metrics;
+
+pub type SshError = ServiceError;
+
+const DEFAULT_TTL_SECONDS: i64 = 60 * 60;
+const DEFAULT_MAX_TTL_SECONDS: i64 = 24 * 60 * 60;
+const MIN_TTL_SECONDS: i64 = 60;
+/// Certificates are backdated so hosts with a slightly slow clock accept them.
+const CLOCK_SKEW_SECONDS: i64 = 60;
+/// Expands to the SSH username an admin assigned to the caller.
+const USERNAME_TEMPLATE: &str = "{{username}}";
+const ANY: &str = "*";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SshCertType {
+    #[default]
+    User,
+    Host,
+}
+
+impl SshCertType {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Host => "host",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user" => Some(Self::User),
+            "host" => Some(Self::Host),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SetSigningRoleCommand {
+    pub cert_type: SshCertType,
+    pub allowed_principals: Vec<String>,
+    pub default_principals: Vec<String>,
+    pub allowed_extensions: Vec<String>,
+    pub default_extensions: Vec<String>,
+    pub default_ttl: Option<String>,
+    pub max_ttl: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignCommand {
+    pub public_key: String,
+    pub role: String,
+    /// Empty means the role's default principals.
+    pub principals: Vec<String>,
+    /// `None` means the role's default extensions.
+    pub extensions: Option<Vec<String>>,
+    pub ttl: Option<String>,
+}
+
+/// A certificate issued by the vault CA.
+#[derive(Debug, Clone)]
+pub struct SignedCertificate {
+    pub certificate: String,
+    pub serial: u64,
+    pub key_id: String,
+    pub cert_type: SshCertType,
+    pub principals: Vec<String>,
+    pub extensions: Vec<String>,
+    pub valid_after: DateTime<Utc>,
+    pub valid_before: DateTime<Utc>,
+}
+
+fn ca_resource(vault_id: &str) -> String {
+    format!("vaults/{vault_id}/ssh/ca")
+}
+
+fn role_resource(vault_id: &str, name: &str) -> String {
+    format!("vaults/{vault_id}/ssh/roles/{name}")
+}
+
+fn role_scope_path(name: &str) -> String {
+    format!("ssh/roles/{name}")
+}
+
+/// Creates the vault CA, generating an Ed25519 key unless an unencrypted
+/// OpenSSH private key is supplied.
+pub async fn create_ca(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    private_key: Option<&str>,
+) -> Result<SshCa, SshError> {
+    let vault =
+        authorize_configure(state, identity, vault_id, &ca_resource(vault_id), "ssh/ca").await?;
+    let private_key = match private_key {
+        Some(value) => {
+            let key = PrivateKey::from_openssh(value.trim())
+                .map_err(|_| SshError::BadRequest("invalid_private_key"))?;
+            if key.is_encrypted() {
+                return Err(SshError::BadRequest("private_key_encrypted"));
+            }
+            key
+        }
+        None => PrivateKey::new(
+            KeypairData::from(Ed25519Keypair::random(&mut OsRng)),
+            "zann-ssh-ca",
+        )
+        .map_err(|_| SshError::Internal("ssh_ca_generate_failed"))?,
+    };
+    let public_key = private_key
+        .public_key()
+        .to_openssh()
+        .map_err(|_| SshError::Internal("ssh_ca_encode_failed"))?;
+    let encoded = private_key
+        .to_openssh(LineEnding::LF)
+        .map_err(|_| SshError::Internal("ssh_ca_encode_failed"))?;
+    let id = Uuid::now_v7();
+    let vault_key = server_vault_key(state, &vault)?;
+    let private_key_enc =
+        core_crypto::encrypt_ssh_ca_key(&vault_key, vault.id, id, encoded.as_bytes())
+            .map_err(|_| SshError::Internal("encrypt_failed"))?;
+    let ca = SshCa {
+        id,
+        vault_id: vault.id,
+        public_key,
+        private_key_enc,
+        created_at: Utc::now(),
+    };
+    let created = SshCaRepo::new(&state.db).create(&ca).await.map_err(|err| {
+        tracing::error!(event = "ssh_ca_create_failed", error = %err, "DB error");
+        SshError::DbError
+    })?;
+    if !created {
+        return Err(SshError::Conflict("ssh_ca_exists"));
+    }
+    Ok(ca)
+}
+
+pub async fn get_ca(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<SshCa, SshError> {
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "read",
+        &ca_resource(vault_id),
+        "ssh/ca",
+        VaultScope::Items,
+    )
+    .await?;
+    load_ca(state, &vault).await
+}
+
+/// Removes the CA. Certificates it issued stay valid until they expire, so
+/// hosts must stop trusting its public key as well.
+pub async fn delete_ca(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<(), SshError> {
+    let vault =
+        authorize_configure(state, identity, vault_id, &ca_resource(vault_id), "ssh/ca").await?;
+    let deleted = SshCaRepo::new(&state.db)
+        .delete_by_vault(vault.id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "ssh_ca_delete_failed", error = %err, "DB error");
+            SshError::DbError
+        })?;
+    if deleted == 0 {
+        return Err(SshError::NotFound);
+    }
+    Ok(())
+}
+
+pub async fn set_role(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+    cmd: SetSigningRoleCommand,
+) -> Result<SshSigningRole, SshError> {
+    let name = normalize_name(name, "invalid_role_name")?;
+    let vault = authorize_configure(
+        state,
+        identity,
+        vault_id,
+        &role_resource(vault_id, &name),
+        &role_scope_path(&name),
+    )
+    .await?;
+    let allowed_principals = normalize_list(cmd.allowed_principals, "invalid_principal")?;
+    let default_principals = normalize_list(cmd.default_principals, "invalid_principal")?;
+    let allowed_extensions = normalize_list(cmd.allowed_extensions, "invalid_extension")?;
+    let default_extensions = normalize_list(cmd.default_extensions, "invalid_extension")?;
+    if allowed_principals.is_empty() {
+        return Err(SshError::BadRequest("allowed_principals_required"));
+    }
+    if default_principals
+        .iter()
+        .any(|principal| !permits(&allowed_principals, principal))
+    {
+        return Err(SshError::BadRequest("default_principal_not_allowed"));
+    }
+    if default_extensions
+        .iter()
+        .any(|extension| !permits(&allowed_extensions, extension))
+    {
+        return Err(SshError::BadRequest("default_extension_not_allowed"));
+    }
+    if cmd.cert_type == SshCertType::Host && !allowed_extensions.is_empty() {
+        return Err(SshError::BadRequest("host_extensions_unsupported"));
+    }
+    let default_ttl_seconds = match cmd.default_ttl.as_deref() {
+        Some(value) => parse_ttl_seconds(value)?,
+        None => DEFAULT_TTL_SECONDS,
+    };
+    let max_ttl_seconds = match cmd.max_ttl.as_deref() {
+        Some(value) => parse_ttl_seconds(value)?,
+        None => DEFAULT_MAX_TTL_SECONDS.max(default_ttl_seconds),
+    };
+    if default_ttl_seconds > max_ttl_seconds {
+        return Err(SshError::BadRequest("default_ttl_exceeds_max"));
+    }
+
+    let now = Utc::now();
+    let role = SshSigningRole {
+        id: Uuid::now_v7(),
+        vault_id: vault.id,
+        name,
+        cert_type: cmd.cert_type.as_str().to_string(),
+        allowed_principals: Json(allowed_principals),
+        default_principals: Json(default_principals),
+        allowed_extensions: Json(allowed_extensions),
+        default_extensions: Json(default_extensions),
+        default_ttl_seconds,
+        max_ttl_seconds,
+        created_at: now,
+        updated_at: now,
+    };
+    SshSigningRoleRepo::new(&state.db)
+        .upsert(&role)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "ssh_role_set_failed", error = %err, "DB error");
+            SshError::DbError
+        })
+}
+
+pub async fn get_role(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+) -> Result<SshSigningRole, SshError> {
+    let name = normalize_name(name, "invalid_role_name")?;
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "read",
+        &role_resource(vault_id, &name),
+        &role_scope_path(&name),
+        VaultScope::Items,
+    )
+    .await?;
+    load_role(state, &vault, &name).await
+}
+
+pub async fn list_roles(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<Vec<SshSigningRole>, SshError> {
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "list",
+        &format!("vaults/{vault_id}/ssh/roles"),
+        "ssh/roles",
+        VaultScope::Items,
+    )
+    .await?;
+    SshSigningRoleRepo::new(&state.db)
+        .list_by_vault(vault.id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "ssh_role_list_failed", error = %err, "DB error");
+            SshError::DbError
+        })
+}
+
+pub async fn delete_role(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+) -> Result<(), SshError> {
+    let name = normalize_name(name, "invalid_role_name")?;
+    let vault = authorize_configure(
+        state,
+        identity,
+        vault_id,
+        &role_resource(vault_id, &name),
+        &role_scope_path(&name),
+    )
+    .await?;
+    let deleted = SshSigningRoleRepo::new(&state.db)
+        .delete(vault.id, &name)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "ssh_role_delete_failed", error = %err, "DB error");
+            SshError::DbError
+        })?;
+    if deleted == 0 {
+        return Err(SshError::NotFound);
+    }
+    Ok(())
+}
+
+/// Signs `cmd.public_key` with the vault CA within the limits of `cmd.role`.
+pub async fn sign(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    cmd: SignCommand,
+) -> Result<(SshSigningRole, SignedCertificate), SshError> {
+    let name = normalize_name(&cmd.role, "invalid_role_name")?;
+    let public_key = PublicKey::from_openssh(cmd.public_key.trim())
+        .map_err(|_| SshError::BadRequest("invalid_public_key"))?;
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "ssh_sign",
+        &role_resource(vault_id, &name),
+        &role_scope_path(&name),
+        VaultScope::Items,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    let role = load_role(state, &vault, &name).await?;
+    let ca = load_ca(state, &vault).await?;
+    let username = ssh_username(state, identity).await?;
+    let signer = Signer {
+        key_id: key_id(identity),
+        username: username.as_deref(),
+    };
+    let result = issue(state, &signer, &vault, &ca, &role, &public_key, &cmd);
+    metrics::ssh_certificates(&role.cert_type, result_label(&result));
+    Ok((role, result?))
+}
+
+/// Who a certificate is issued to.
+struct Signer<'a> {
+    key_id: String,
+    username: Option<&'a str>,
+}
+
+fn issue(
+    state: &AppState,
+    signer: &Signer<'_>,
+    vault: &Vault,
+    ca: &SshCa,
+    role: &SshSigningRole,
+    public_key: &PublicKey,
+    cmd: &SignCommand,
+) -> Result<SignedCertificate, SshError> {
+    let cert_type =
+        SshCertType::parse(&role.cert_type).ok_or(SshError::Internal("ssh_role_invalid"))?;
+    let principals = resolve_principals(role, signer.username, &cmd.principals)?;
+    let extensions = resolve_extensions(role, cmd.extensions.as_deref())?;
+    let ttl_seconds = match cmd.ttl.as_deref() {
+        Some(value) => parse_ttl_seconds(value)?,
+        None => role.default_ttl_seconds,
+    }
+    .min(role.max_ttl_seconds);
+
+    let now = Utc::now();
+    let valid_after = now - ChronoDuration::seconds(CLOCK_SKEW_SECONDS);
+    let valid_before = now + ChronoDuration::seconds(ttl_seconds);
+    let serial: u64 = OsRng.gen();
+    let key_id = signer.key_id.clone();
+    let signing_key = ca_private_key(state, vault, ca)?;
+
+    let build = || -> Result<String, ssh_key::Error> {
+        let mut builder = Builder::new_with_random_nonce(
+            &mut OsRng,
+            public_key.key_data().clone(),
+            unix_seconds(valid_after),
+            unix_seconds(valid_before),
+        )?;
+        builder.serial(serial)?;
+        builder.key_id(key_id.as_str())?;
+        builder.cert_type(match cert_type {
+            SshCertType::User => CertType::User,
+            SshCertType::Host => CertType::Host,
+        })?;
+        for principal in &principals {
+            builder.valid_principal(principal.as_str())?;
+        }
+        for extension in &extensions {
+            builder.extension(extension.as_str(), "")?;
+        }
+        builder.comment(public_key.comment())?;
+        builder.sign(&signing_key)?.to_openssh()
+    };
+    let certificate = build().map_err(|err| {
+        tracing::error!(event = "ssh_sign_failed", error = %err, "SSH signing failed");
+        SshError::Internal("ssh_sign_failed")
+    })?;
+    Ok(SignedCertificate {
+        certificate,
+        serial,
+        key_id,
+        cert_type,
+        principals,
+        extensions,
+        valid_after,
+        valid_before,
+    })
+}
+
+async fn authorize_configure(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    resource: &str,
+    scope_path: &str,
+) -> Result<Vault, SshError> {
+    // Service accounts sign certificates but never manage the CA or its roles.
+    if identity.service_account_id.is_some() {
+        return Err(SshError::ForbiddenNoBody);
+    }
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "ssh_configure",
+        resource,
+        scope_path,
+        VaultScope::Items,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    Ok(vault)
+}
+
+fn key_id(identity: &Identity) -> String {
+    match identity.service_account_id {
+        Some(id) => format!("service-account:{id}"),
+        None => identity.email.clone(),
+    }
+}
+
+/// The principal `{{username}}` stands for. Only an admin-assigned SSH
+/// username is used; service accounts never have one.
+async fn ssh_username(state: &AppState, identity: &Identity) -> Result<Option<String>, SshError> {
+    if identity.service_account_id.is_some() {
+        return Ok(None);
+    }
+    UserRepo::new(&state.db)
+        .get_ssh_username(identity.user_id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "ssh_username_lookup_failed", error = %err, "DB error");
+            SshError::DbError
+        })
+}
+
+/// Entries using `{{username}}` are dropped when the caller has no SSH username.
+fn expand(values: &[String], username: Option<&str>) -> Vec<String> {
+    values
+        .iter()
+        .filter_map(|value| {
+            if value.contains(USERNAME_TEMPLATE) {
+                username.map(|name| value.replace(USERNAME_TEMPLATE, name))
+            } else {
+                Some(value.clone())
+            }
+        })
+        .collect()
+}
+
+fn permits(allowed: &[String], value: &str) -> bool {
+    allowed.iter().any(|entry| entry == ANY || entry == value)
+}
+
+fn resolve_principals(
+    role: &SshSigningRole,
+    username: Option<&str>,
+    requested: &[String],
+) -> Result<Vec<String>, SshError> {
+    let principals = if requested.is_empty() {
+        let templated = role
+            .default_principals
+            .iter()
+            .any(|value| value.contains(USERNAME_TEMPLATE));
+        if templated && username.is_none() {
+            return Err(SshError::Forbidden("ssh_username_missing"));
+        }
+        expand(&role.default_principals, username)
+    } else {
+        normalize_list(requested.to_vec(), "invalid_principal")?
+    };
+    if principals.is_empty() {
+        return Err(SshError::BadRequest("principals_required"));
+    }
+    let allowed = expand(&role.allowed_principals, username);
+    if principals
+        .iter()
+        .any(|principal| principal == ANY || !permits(&allowed, principal))
+    {
+        return Err(SshError::Forbidden("principal_not_allowed"));
+    }
+    Ok(principals)
+}
+
+fn resolve_extensions(
+    role: &SshSigningRole,
+    requested: Option<&[String]>,
+) -> Result<Vec<String>, SshError> {
+    let extensions = match requested {
+        Some(values) => normalize_list(values.to_vec(), "invalid_extension")?,
+        None => role.default_extensions.0.clone(),
+    };
+    if extensions
+        .iter()
+        .any(|extension| extension == ANY || !permits(&role.allowed_extensions, extension))
+    {
+        return Err(SshError::Forbidden("extension_not_allowed"));
+    }
+    Ok(extensions)
+}
+
+/// Trims, drops duplicates and rejects blank or whitespace-containing entries.
+fn normalize_list(values: Vec<String>, error: &'static str) -> Result<Vec<String>, SshError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(values.len());
+    for value in values {
+        let value = value.trim();
+        if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == ',') {
+            return Err(SshError::BadRequest(error));
+        }
+        if !normalized.iter().any(|existing| existing == value) {
+            normalized.push(value.to_string());
+        }
+    }
+    Ok(normalized)
+}
+
+fn unix_seconds(value: DateTime<Utc>) -> u64 {
+    u64::try_from(value.timestamp()).unwrap_or(0)
+}
+
+fn parse_ttl_seconds(value: &str) -> Result<i64, SshError> {
+    let seconds = parse_ttl(value)
+        .map_err(|_| SshError::BadRequest("invalid_ttl"))?
+        .num_seconds();
+    if seconds < MIN_TTL_SECONDS {
+        return Err(SshError::BadRequest("ttl_too_short"));
+    }
+    Ok(seconds)
+}
+
+async fn load_ca(state: &AppState, vault: &Vault) -> Result<SshCa, SshError> {
+    match SshCaRepo::new(&state.db).get_by_vault(vault.id).await {
+        Ok(Some(ca)) => Ok(ca),
+        Ok(None) => Err(SshError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "ssh_ca_load_failed", error = %err, "DB error");
+            Err(SshError::DbError)
+        }
+    }
+}
+
+async fn load_role(
+    state: &AppState,
+    vault: &Vault,
+    name: &str,
+) -> Result<SshSigningRole, SshError> {
+    match SshSigningRoleRepo::new(&state.db)
+        .get_by_name(vault.id, name)
+        .await
+    {
+        Ok(Some(role)) => Ok(role),
+        Ok(None) => Err(SshError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "ssh_role_load_failed", error = %err, "DB error");
+            Err(SshError::DbError)
+        }
+    }
+}
+
+fn ca_private_key(state: &AppState, vault: &Vault, ca: &SshCa) -> Result<PrivateKey, SshError> {
+    let vault_key = server_vault_key(state, vault)?;
+    let bytes = core_crypto::decrypt_ssh_ca_key(&vault_key, vault.id, ca.id, &ca.private_key_enc)
+        .map_err(|_| SshError::Internal("decrypt_failed"))?;
+    let encoded = String::from_utf8(bytes).map_err(|_| SshError::Internal("decrypt_failed"))?;
+    PrivateKey::from_openssh(&encoded).map_err(|_| SshError::Internal("decrypt_failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(allowed: &[&str], defaults: &[&str], extensions: &[&str]) -> SshSigningRole {
+        let list = |values: &[&str]| Json(values.iter().map(|v| (*v).to_string()).collect());
+        SshSigningRole {
+            id: Uuid::now_v7(),
+            vault_id: Uuid::now_v7(),
+            name: "ops".to_string(),
+            cert_type: "user".to_string(),
+            allowed_principals: list(allowed),
+            default_principals: list(defaults),
+            allowed_extensions: list(extensions),
+            default_extensions: list(extensions),
+            default_ttl_seconds: DEFAULT_TTL_SECONDS,
+            max_ttl_seconds: DEFAULT_MAX_TTL_SECONDS,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn principals_expand_username_template() {
+        let role = role(&["{{username}}", "deploy"], &["{{username}}"], &[]);
+        let alice = Some("alice");
+        let defaults = resolve_principals(&role, alice, &[]).expect("defaults");
+        assert_eq!(defaults, vec!["alice".to_string()]);
+        let requested = vec!["deploy".to_string(), "alice".to_string()];
+        assert!(resolve_principals(&role, alice, &requested).is_ok());
+        let err = resolve_principals(&role, alice, &["bob".to_string()]).expect_err("bob");
+        assert!(matches!(err, SshError::Forbidden("principal_not_allowed")));
+    }
+
+    #[test]
+    fn username_template_needs_an_assigned_username() {
+        let role = role(&["{{username}}", "deploy"], &["{{username}}"], &[]);
+        let err = resolve_principals(&role, None, &[]).expect_err("no username");
+        assert!(matches!(err, SshError::Forbidden("ssh_username_missing")));
+        assert!(resolve_principals(&role, None, &["deploy".to_string()]).is_ok());
+        let err = resolve_principals(&role, None, &["{{username}}".to_string()])
+            .expect_err("literal template");
+        assert!(matches!(err, SshError::Forbidden("principal_not_allowed")));
+    }
+
+    #[test]
+    fn wildcard_is_not_a_requestable_principal() {
+        let role = role(&["*"], &[], &[]);
+        let alice = Some("alice");
+        assert!(resolve_principals(&role, alice, &["anyone".to_string()]).is_ok());
+        let err = resolve_principals(&role, alice, &["*".to_string()]).expect_err("wildcard");
+        assert!(matches!(err, SshError::Forbidden("principal_not_allowed")));
+        let err = resolve_principals(&role, alice, &[]).expect_err("no defaults");
+        assert!(matches!(err, SshError::BadRequest("principals_required")));
+    }
+
+    #[test]
+    fn extensions_limited_to_role() {
+        let role = role(&["*"], &[], &["permit-pty"]);
+        let defaults = resolve_extensions(&role, None).expect("defaults");
+        assert_eq!(defaults, vec!["permit-pty".to_string()]);
+        assert!(resolve_extensions(&role, Some(&[]))
+            .expect("none")
+            .is_empty());
+        let err = resolve_extensions(&role, Some(&["permit-port-forwarding".to_string()]))
+            .expect_err("forwarding");
+        assert!(matches!(err, SshError::Forbidden("extension_not_allowed")));
+    }
+}
@@ -163,6 +163,7 @@ pub(crate) async fn apply_change(
             deleted_at as "deleted_at",
             deleted_by_user_id as "deleted_by_user_id",
             deleted_by_device_id as "deleted_by_device_id",
+            expires_at as "expires_at",
             created_at as "created_at",
             updated_at as "updated_at"
         FROM items
@@ -255,6 +256,7 @@ pub(crate) async fn apply_change(
                 deleted_at: None,
                 deleted_by_user_id: None,
                 deleted_by_device_id: None,
+                expires_at: change.expires_at.flatten(),
                 created_at: now,
                 updated_at: now,
             };
@@ -265,9 +267,12 @@ pub(crate) async fn apply_change(
                 INSERT INTO items (
                     id, vault_id, path, name, type_id, tags, favorite, payload_enc, checksum,
                     version, row_version, device_id, sync_status, deleted_at, deleted_by_user_id,
-                    deleted_by_device_id, created_at, updated_at
+                    deleted_by_device_id, created_at, updated_at, expires_at
+                )
+                VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18,
+                    $19
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
                 ",
                 item.id,
                 item.vault_id,
@@ -286,7 +291,8 @@ pub(crate) async fn apply_change(
                 item.deleted_by_user_id,
                 item.deleted_by_device_id,
                 item.created_at,
-                item.updated_at
+                item.updated_at,
+                item.expires_at
             )
             .execute(&mut *conn)
             .await
@@ -385,6 +391,9 @@ pub(crate) async fn apply_change(
                     item.checksum = checksum.trim().to_string();
                 }
             }
+            if let Some(expires_at) = change.expires_at {
+                item.expires_at = expires_at;
+            }
 
             item.version = item.version.saturating_add(1);
             item.row_version = item.row_version.saturating_add(1);
@@ -403,7 +412,12 @@ pub(crate) async fn apply_change(
                     version = $7,
                     row_version = $8,
                     device_id = $9,
-                    updated_at = $10
+                    updated_at = $10,
+                    expiry_notified_at = CASE
+                        WHEN expires_at IS DISTINCT FROM $11 THEN NULL
+                        ELSE expiry_notified_at
+                    END,
+                    expires_at = $11
                 WHERE id = $1
                 ",
                 item.id,
@@ -415,7 +429,8 @@ pub(crate) async fn apply_change(
                 item.version,
                 item.row_version,
                 item.device_id,
-                item.updated_at
+                item.updated_at,
+                item.expires_at
             )
             .execute(&mut *conn)
             .await
@@ -487,6 +502,9 @@ pub(crate) async fn apply_change(
                     item.checksum = checksum.trim().to_string();
                 }
             }
+            if let Some(expires_at) = change.expires_at {
+                item.expires_at = expires_at;
+            }
 
             item.version = item.version.saturating_add(1);
             item.row_version = item.row_version.saturating_add(1);
@@ -513,7 +531,12 @@ pub(crate) async fn apply_change(
                     deleted_at = $11,
                     deleted_by_user_id = $12,
                     deleted_by_device_id = $13,
-                    updated_at = $14
+                    updated_at = $14,
+                    expiry_notified_at = CASE
+                        WHEN expires_at IS DISTINCT FROM $15 THEN NULL
+                        ELSE expiry_notified_at
+                    END,
+                    expires_at = $15
                 WHERE id = $1
                 ",
                 item.id,
@@ -529,7 +552,8 @@ pub(crate) async fn apply_change(
                 item.deleted_at,
                 item.deleted_by_user_id,
                 item.deleted_by_device_id,
-                item.updated_at
+                item.updated_at,
+                item.expires_at
             )
             .execute(&mut *conn)
             .await
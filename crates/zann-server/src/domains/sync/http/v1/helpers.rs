@@ -55,6 +55,7 @@ impl FromRow<'_, PgRow> for SyncPullRow {
             payload_enc: row.try_get("payload_enc")?,
             checksum: row.try_get("checksum")?,
             updated_at: row.try_get("updated_at")?,
+            expires_at: row.try_get("expires_at")?,
         })
     }
 }
@@ -48,6 +48,7 @@ pub(crate) struct SyncPullChange {
     pub(crate) operation: ChangeType,
     pub(crate) seq: i64,
     pub(crate) updated_at: DateTime<Utc>,
+    pub(crate) expires_at: Option<DateTime<Utc>>,
     pub(crate) checksum: String,
     pub(crate) payload_enc: Option<Vec<u8>>,
     pub(crate) path: String,
@@ -63,6 +64,7 @@ pub(crate) struct SyncSharedPullChange {
     pub(crate) operation: ChangeType,
     pub(crate) seq: i64,
     pub(crate) updated_at: String,
+    pub(crate) expires_at: Option<String>,
     pub(crate) payload: Option<JsonValue>,
     pub(crate) checksum: String,
     pub(crate) path: String,
@@ -117,6 +119,12 @@ pub(crate) struct SyncPushChange {
     pub(crate) type_id: Option<String>,
     #[serde(default)]
     pub(crate) base_seq: Option<i64>,
+    /// Omit to keep the item's expiry date, `null` to clear it.
+    #[serde(
+        default,
+        deserialize_with = "crate::domains::items::expiry::deserialize_some"
+    )]
+    pub(crate) expires_at: Option<Option<DateTime<Utc>>>,
 }
 
 #[derive(Deserialize, JsonSchema)]
@@ -133,6 +141,12 @@ pub(crate) struct SyncSharedPushChange {
     pub(crate) type_id: Option<String>,
     #[serde(default)]
     pub(crate) base_seq: Option<i64>,
+    /// Omit to keep the item's expiry date, `null` to clear it.
+    #[serde(
+        default,
+        deserialize_with = "crate::domains::items::expiry::deserialize_some"
+    )]
+    pub(crate) expires_at: Option<Option<DateTime<Utc>>>,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -169,6 +183,7 @@ pub(crate) struct SyncPullRow {
     pub(crate) payload_enc: Vec<u8>,
     pub(crate) checksum: String,
     pub(crate) updated_at: DateTime<Utc>,
+    pub(crate) expires_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema)]
@@ -8,7 +8,9 @@ use crate::app::AppState;
 use crate::domains::access_control::http::{vault_role_allows, VaultScope};
 use crate::domains::access_control::policies::PolicyDecision;
 use crate::domains::errors::ServiceError;
-use crate::domains::items::service::ITEM_HISTORY_LIMIT;
+use crate::domains::items::service::{
+    rules_allow_path, service_account_read_rules, ITEM_HISTORY_LIMIT,
+};
 use crate::domains::sync::http::v1::handlers::push_apply::{apply_change, ApplyChangeResult};
 use crate::domains::sync::http::v1::helpers::{
     can_push, decode_cursor, encode_cursor, parse_plaintext_payload,
@@ -17,6 +19,7 @@ use crate::domains::sync::http::v1::types::{
     SyncAppliedChange, SyncHistoryEntry, SyncPullChange, SyncPullRow, SyncPushChange,
     SyncPushConflict, SyncSharedHistoryEntry, SyncSharedPullChange, SyncSharedPushChange,
 };
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::db::apply_tx_isolation;
 use crate::infra::metrics;
 
@@ -137,7 +140,8 @@ pub(crate) async fn sync_pull(
             i.type_id as "type_id",
             i.payload_enc as "payload_enc",
             i.checksum as "checksum",
-            i.updated_at as "updated_at"
+            i.updated_at as "updated_at",
+            i.expires_at as "expires_at"
         FROM changes c
         JOIN items i ON i.id = c.item_id
         WHERE c.vault_id = $1 AND c.seq > $2
@@ -219,6 +223,7 @@ pub(crate) async fn sync_pull(
             operation,
             seq,
             updated_at: row.updated_at,
+            expires_at: row.expires_at,
             checksum: row.checksum,
             payload_enc,
             path: row.path,
@@ -249,7 +254,10 @@ pub(crate) async fn sync_shared_pull(
     let resource = "sync/shared/pull";
     let policies = state.policy_store.get();
 
-    let _device_id = identity.device_id.ok_or(SyncError::DeviceRequired)?;
+    // Service accounts pull read-only (e.g. `zann agent`) and have no device.
+    if identity.service_account_id.is_none() {
+        identity.device_id.ok_or(SyncError::DeviceRequired)?;
+    }
 
     let vault_repo = VaultRepo::new(&state.db);
     let vault = match vault_repo.get_by_id(vault_id).await {
@@ -265,7 +273,7 @@ pub(crate) async fn sync_shared_pull(
         return Err(SyncError::BadRequest("vault_not_shared"));
     }
 
-    let Some(smk) = state.server_master_key.as_ref() else {
+    let Some(smk) = state.server_master_key.get() else {
         return Err(SyncError::Internal("smk_missing"));
     };
 
@@ -302,7 +310,23 @@ pub(crate) async fn sync_shared_pull(
         }
     }
 
-    let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+    // Service accounts only see items under the prefixes they may read, and
+    // only which paths changed: values go through the secrets API, where the
+    // read scopes, leases and audit apply.
+    let metadata_only = identity.service_account_id.is_some();
+    let path_rules = match identity.service_account_id {
+        Some(service_account_id) => {
+            Some(service_account_read_rules(state, service_account_id, &vault).await)
+        }
+        None => None,
+    };
+    let path_allowed = |path: &str| {
+        path_rules
+            .as_ref()
+            .is_none_or(|rules| rules_allow_path(rules, &vault, path))
+    };
+
+    let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
         Ok(key) => key,
         Err(err) => {
             tracing::error!(
@@ -330,7 +354,8 @@ pub(crate) async fn sync_shared_pull(
             i.type_id as "type_id",
             i.payload_enc as "payload_enc",
             i.checksum as "checksum",
-            i.updated_at as "updated_at"
+            i.updated_at as "updated_at",
+            i.expires_at as "expires_at"
         FROM changes c
         JOIN items i ON i.id = c.item_id
         WHERE c.vault_id = $1
@@ -374,7 +399,10 @@ pub(crate) async fn sync_shared_pull(
         let history_repo = ItemHistoryRepo::new(&state.db);
         let mut changes = Vec::with_capacity(items.len());
         for item in items {
-            let payload = if item.deleted_at.is_some() {
+            if !path_allowed(&item.path) {
+                continue;
+            }
+            let payload = if metadata_only || item.deleted_at.is_some() {
                 None
             } else {
                 match core_crypto::decrypt_payload_bytes(
@@ -403,62 +431,66 @@ pub(crate) async fn sync_shared_pull(
                     }
                 }
             };
-            let history = match history_repo
-                .list_by_item_limit(item.id, ITEM_HISTORY_LIMIT)
-                .await
-            {
-                Ok(entries) => {
-                    let mut mapped = Vec::with_capacity(entries.len());
-                    for entry in entries {
-                        let payload = match core_crypto::decrypt_payload_bytes(
-                            &vault_key,
-                            vault.id,
-                            item.id,
-                            &entry.payload_enc,
-                        ) {
-                            Ok(bytes) => match serde_json::from_slice(&bytes) {
-                                Ok(payload) => payload,
-                                Err(_) => {
+            let history = if metadata_only {
+                Vec::new()
+            } else {
+                match history_repo
+                    .list_by_item_limit(item.id, ITEM_HISTORY_LIMIT)
+                    .await
+                {
+                    Ok(entries) => {
+                        let mut mapped = Vec::with_capacity(entries.len());
+                        for entry in entries {
+                            let payload = match core_crypto::decrypt_payload_bytes(
+                                &vault_key,
+                                vault.id,
+                                item.id,
+                                &entry.payload_enc,
+                            ) {
+                                Ok(bytes) => match serde_json::from_slice(&bytes) {
+                                    Ok(payload) => payload,
+                                    Err(_) => {
+                                        tracing::error!(
+                                            event = "sync_shared_pull_failed",
+                                            "History payload decode failed"
+                                        );
+                                        return Err(SyncError::Internal("payload_decrypt_failed"));
+                                    }
+                                },
+                                Err(err) => {
                                     tracing::error!(
                                         event = "sync_shared_pull_failed",
-                                        "History payload decode failed"
+                                        error = %err,
+                                        "History payload decrypt failed"
                                     );
                                     return Err(SyncError::Internal("payload_decrypt_failed"));
                                 }
-                            },
-                            Err(err) => {
-                                tracing::error!(
-                                    event = "sync_shared_pull_failed",
-                                    error = %err,
-                                    "History payload decrypt failed"
-                                );
-                                return Err(SyncError::Internal("payload_decrypt_failed"));
-                            }
-                        };
-                        mapped.push(SyncSharedHistoryEntry {
-                            version: entry.version,
-                            checksum: entry.checksum,
-                            change_type: entry.change_type,
-                            changed_by_name: entry.changed_by_name,
-                            changed_by_email: entry.changed_by_email,
-                            created_at: entry.created_at.to_rfc3339(),
-                            payload,
-                        });
+                            };
+                            mapped.push(SyncSharedHistoryEntry {
+                                version: entry.version,
+                                checksum: entry.checksum,
+                                change_type: entry.change_type,
+                                changed_by_name: entry.changed_by_name,
+                                changed_by_email: entry.changed_by_email,
+                                created_at: entry.created_at.to_rfc3339(),
+                                payload,
+                            });
+                        }
+                        tracing::info!(
+                            event = "sync_shared_pull_history",
+                            item_id = %item.id,
+                            count = mapped.len()
+                        );
+                        mapped
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            event = "sync_shared_pull_history_failed",
+                            item_id = %item.id,
+                            error = %err
+                        );
+                        Vec::new()
                     }
-                    tracing::info!(
-                        event = "sync_shared_pull_history",
-                        item_id = %item.id,
-                        count = mapped.len()
-                    );
-                    mapped
-                }
-                Err(err) => {
-                    tracing::warn!(
-                        event = "sync_shared_pull_history_failed",
-                        item_id = %item.id,
-                        error = %err
-                    );
-                    Vec::new()
                 }
             };
             changes.push(SyncSharedPullChange {
@@ -470,6 +502,7 @@ pub(crate) async fn sync_shared_pull(
                 },
                 seq: last_seq,
                 updated_at: item.updated_at.to_rfc3339(),
+                expires_at: item.expires_at.map(|dt| dt.to_rfc3339()),
                 checksum: item.checksum,
                 payload,
                 path: item.path,
@@ -492,9 +525,13 @@ pub(crate) async fn sync_shared_pull(
     let history_repo = ItemHistoryRepo::new(&state.db);
     for row in rows {
         let seq = row.seq;
+        if !path_allowed(&row.path) {
+            last_seq = seq;
+            continue;
+        }
         let op =
             ChangeOp::try_from(row.op).map_err(|_| SyncError::BadRequest("invalid_operation"))?;
-        let payload = if op == ChangeOp::Delete {
+        let payload = if metadata_only || op == ChangeOp::Delete {
             None
         } else {
             match core_crypto::decrypt_payload_bytes(
@@ -520,62 +557,66 @@ pub(crate) async fn sync_shared_pull(
                 }
             }
         };
-        let history = match history_repo
-            .list_by_item_limit(row.item_id, ITEM_HISTORY_LIMIT)
-            .await
-        {
-            Ok(entries) => {
-                let mut mapped = Vec::with_capacity(entries.len());
-                for entry in entries {
-                    let payload = match core_crypto::decrypt_payload_bytes(
-                        &vault_key,
-                        vault.id,
-                        row.item_id,
-                        &entry.payload_enc,
-                    ) {
-                        Ok(bytes) => match serde_json::from_slice(&bytes) {
-                            Ok(payload) => payload,
-                            Err(_) => {
+        let history = if metadata_only {
+            Vec::new()
+        } else {
+            match history_repo
+                .list_by_item_limit(row.item_id, ITEM_HISTORY_LIMIT)
+                .await
+            {
+                Ok(entries) => {
+                    let mut mapped = Vec::with_capacity(entries.len());
+                    for entry in entries {
+                        let payload = match core_crypto::decrypt_payload_bytes(
+                            &vault_key,
+                            vault.id,
+                            row.item_id,
+                            &entry.payload_enc,
+                        ) {
+                            Ok(bytes) => match serde_json::from_slice(&bytes) {
+                                Ok(payload) => payload,
+                                Err(_) => {
+                                    tracing::error!(
+                                        event = "sync_shared_pull_failed",
+                                        "History payload decode failed"
+                                    );
+                                    return Err(SyncError::Internal("payload_decrypt_failed"));
+                                }
+                            },
+                            Err(err) => {
                                 tracing::error!(
                                     event = "sync_shared_pull_failed",
-                                    "History payload decode failed"
+                                    error = %err,
+                                    "History payload decrypt failed"
                                 );
                                 return Err(SyncError::Internal("payload_decrypt_failed"));
                             }
-                        },
-                        Err(err) => {
-                            tracing::error!(
-                                event = "sync_shared_pull_failed",
-                                error = %err,
-                                "History payload decrypt failed"
-                            );
-                            return Err(SyncError::Internal("payload_decrypt_failed"));
-                        }
-                    };
-                    mapped.push(SyncSharedHistoryEntry {
-                        version: entry.version,
-                        checksum: entry.checksum,
-                        change_type: entry.change_type,
-                        changed_by_name: entry.changed_by_name,
-                        changed_by_email: entry.changed_by_email,
-                        created_at: entry.created_at.to_rfc3339(),
-                        payload,
-                    });
+                        };
+                        mapped.push(SyncSharedHistoryEntry {
+                            version: entry.version,
+                            checksum: entry.checksum,
+                            change_type: entry.change_type,
+                            changed_by_name: entry.changed_by_name,
+                            changed_by_email: entry.changed_by_email,
+                            created_at: entry.created_at.to_rfc3339(),
+                            payload,
+                        });
+                    }
+                    tracing::info!(
+                        event = "sync_shared_pull_history",
+                        item_id = %row.item_id,
+                        count = mapped.len()
+                    );
+                    mapped
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        event = "sync_shared_pull_history_failed",
+                        item_id = %row.item_id,
+                        error = %err
+                    );
+                    Vec::new()
                 }
-                tracing::info!(
-                    event = "sync_shared_pull_history",
-                    item_id = %row.item_id,
-                    count = mapped.len()
-                );
-                mapped
-            }
-            Err(err) => {
-                tracing::warn!(
-                    event = "sync_shared_pull_history_failed",
-                    item_id = %row.item_id,
-                    error = %err
-                );
-                Vec::new()
             }
         };
         last_seq = seq;
@@ -589,6 +630,7 @@ pub(crate) async fn sync_shared_pull(
             operation,
             seq,
             updated_at: row.updated_at.to_rfc3339(),
+            expires_at: row.expires_at.map(|dt| dt.to_rfc3339()),
             checksum: row.checksum,
             payload,
             path: row.path,
@@ -623,6 +665,7 @@ pub(crate) async fn sync_push(
     let mut applied = Vec::new();
     let mut applied_changes = Vec::new();
     let mut conflicts = Vec::new();
+    let mut audited = Vec::new();
 
     let mut tx = match state.db.begin().await {
         Ok(tx) => tx,
@@ -637,11 +680,14 @@ pub(crate) async fn sync_push(
     }
 
     for change in changes {
+        let operation = change.operation;
+        let path = change.path.clone();
         match apply_change(&mut tx, identity, device_id, vault.id, change).await {
             Ok(ApplyChangeResult::Applied {
                 item_id,
                 applied_change,
             }) => {
+                audited.push((item_id, operation, path));
                 applied.push(item_id.to_string());
                 applied_changes.push(applied_change);
             }
@@ -684,10 +730,43 @@ pub(crate) async fn sync_push(
         });
     }
 
+    let mut recorded = Vec::with_capacity(audited.len());
+    for (item_id, operation, path) in audited {
+        let action = match operation {
+            ChangeType::Create => "sync_create",
+            ChangeType::Update => "sync_update",
+            ChangeType::Delete => "sync_delete",
+            ChangeType::Restore => "sync_restore",
+        };
+        let mut entry = AuditEntry::new("items", action, "ok")
+            .vault(vault.id)
+            .target(item_id);
+        if let Some(path) = path.as_deref() {
+            entry = entry.path(path);
+        }
+        match audit::record_tx(&mut tx, Some(identity), entry).await {
+            Ok(pending) => recorded.push(pending),
+            Err(err) => {
+                tracing::error!(event = "sync_push_failed", error = %err, "Audit write failed");
+                if let Err(rollback_err) = tx.rollback().await {
+                    tracing::error!(
+                        event = "sync_push_failed",
+                        error = %rollback_err,
+                        "DB rollback failed"
+                    );
+                }
+                return Err(SyncError::DbError);
+            }
+        }
+    }
+
     if let Err(err) = tx.commit().await {
         tracing::error!(event = "sync_push_failed", error = %err, "DB commit failed");
         return Err(SyncError::DbError);
     }
+    for pending in recorded {
+        pending.dispatch();
+    }
 
     let change_repo = ChangeRepo::new(&state.db);
     let new_seq = change_repo.last_seq_for_vault(vault.id).await.unwrap_or(0);
@@ -726,7 +805,7 @@ pub(crate) async fn sync_shared_push(
         return Err(SyncError::BadRequest("vault_not_shared"));
     }
 
-    let Some(smk) = state.server_master_key.as_ref() else {
+    let Some(smk) = state.server_master_key.get() else {
         return Err(SyncError::Internal("smk_missing"));
     };
 
@@ -775,6 +854,7 @@ pub(crate) async fn sync_shared_push(
                 name: change.name,
                 type_id: change.type_id,
                 base_seq: change.base_seq,
+                expires_at: None,
             });
             continue;
         }
@@ -793,7 +873,7 @@ pub(crate) async fn sync_shared_push(
             Ok(payload) => payload,
             Err(error) => return Err(SyncError::BadRequest(error.error)),
         };
-        let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+        let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
             Ok(key) => key,
             Err(err) => {
                 tracing::error!(
@@ -831,6 +911,7 @@ pub(crate) async fn sync_shared_push(
             name: change.name,
             type_id: Some(type_id.to_string()),
             base_seq: change.base_seq,
+            expires_at: change.expires_at,
         });
     }
 
@@ -1,19 +1,31 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
 use base64::Engine;
 use data_encoding::BASE32_NOPAD;
 use ed25519_dalek::Signer;
 use schemars::JsonSchema;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx_core::query::query;
 use sqlx_postgres::Postgres;
 use std::collections::HashMap;
-use zann_core::{AuthMethod, SecurityProfile, UserStatus};
+use zann_core::{AuthMethod, Identity, SecurityProfile, UserStatus};
 
 use crate::app::AppState;
 use crate::config::AuthMode;
+use crate::domains::system::seal::{self, SealServiceError, SealStatus};
 use crate::runtime;
 
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    error: &'static str,
+}
+
 #[derive(Serialize, JsonSchema)]
 pub(crate) struct SystemInfoResponse {
     pub(crate) version: &'static str,
@@ -35,15 +47,112 @@ pub(crate) struct SystemIdentity {
     pub(crate) signature: String,
 }
 
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SealStatusResponse {
+    /// `false` unless the server runs in `manual_unseal` mode.
+    pub(crate) manual_unseal: bool,
+    pub(crate) sealed: bool,
+    /// Whether `zann-server init` has recorded unseal shares.
+    pub(crate) initialized: bool,
+    pub(crate) threshold: Option<i16>,
+    pub(crate) shares: Option<i16>,
+    /// Shares submitted towards the current unseal attempt.
+    pub(crate) progress: usize,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct UnsealRequest {
+    /// Base64 key share printed by `zann-server init`.
+    #[serde(default)]
+    share: Option<String>,
+    /// Discard shares submitted so far.
+    #[serde(default)]
+    reset: bool,
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/v1/system/info", get(info))
         .route("/v1/system/security-profiles", get(security_profiles))
+        .route("/v1/system/seal-status", get(seal_status))
+        .route("/v1/system/unseal", post(unseal))
+}
+
+/// Routes that need an authenticated identity.
+pub fn protected_router() -> Router<AppState> {
+    Router::new().route("/v1/system/seal", post(seal))
+}
+
+fn map_seal_error(error: SealServiceError) -> axum::response::Response {
+    match error {
+        SealServiceError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        SealServiceError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        SealServiceError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+fn seal_status_response(status: SealStatus) -> SealStatusResponse {
+    SealStatusResponse {
+        manual_unseal: status.manual_unseal,
+        sealed: status.sealed,
+        initialized: status.initialized,
+        threshold: status.threshold,
+        shares: status.shares,
+        progress: status.progress,
+    }
+}
+
+async fn seal_status(State(state): State<AppState>) -> impl IntoResponse {
+    match seal::seal_status(&state).await {
+        Ok(status) => (StatusCode::OK, Json(seal_status_response(status))).into_response(),
+        Err(err) => map_seal_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, payload))]
+async fn unseal(
+    State(state): State<AppState>,
+    Json(payload): Json<UnsealRequest>,
+) -> impl IntoResponse {
+    match seal::unseal(&state, payload.share.as_deref(), payload.reset).await {
+        Ok(status) => (StatusCode::OK, Json(seal_status_response(status))).into_response(),
+        Err(err) => map_seal_error(err),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn seal(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+) -> impl IntoResponse {
+    match seal::seal(&state, &identity).await {
+        Ok(status) => (StatusCode::OK, Json(seal_status_response(status))).into_response(),
+        Err(err) => map_seal_error(err),
+    }
 }
 
 async fn info(State(state): State<AppState>) -> impl IntoResponse {
     let version = env!("CARGO_PKG_VERSION");
     let build_commit = option_env!("GIT_COMMIT");
+    // The derived fingerprint needs the master key; a pinned one does not.
+    if state.config.server.fingerprint.is_none() && state.server_master_key.is_sealed() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse { error: "sealed" }),
+        )
+            .into_response();
+    }
     let fingerprint = runtime::server_fingerprint(&state);
     let verifying_key = state.identity_key.verifying_key();
     let public_key_bytes = verifying_key.to_bytes();
@@ -108,6 +217,7 @@ async fn info(State(state): State<AppState>) -> impl IntoResponse {
             internal_users_present,
         }),
     )
+        .into_response()
 }
 
 #[derive(Serialize, JsonSchema)]
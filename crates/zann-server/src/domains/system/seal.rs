@@ -0,0 +1,464 @@
+//! Manual unseal. In `manual_unseal` mode the server master key never touches
+//! disk or config: the server boots sealed and rebuilds the key in memory once
+//! a threshold of Shamir shares (split by `zann-server init`) is submitted.
+
+use std::sync::{Arc, RwLock};
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::Engine;
+use subtle::ConstantTimeEq;
+use zann_core::Identity;
+use zann_crypto::crypto::SecretKey;
+use zann_crypto::shamir::{self, ShamirError};
+use zann_db::repo::{SealConfig, SealConfigRepo};
+use zeroize::Zeroize;
+
+use crate::app::AppState;
+use crate::domains::errors::ServiceError;
+use crate::infra::audit::{self, AuditEntry};
+use crate::infra::metrics;
+
+pub type SealServiceError = ServiceError;
+
+const KEY_CHECK_CONTEXT: &[u8] = b"zann-seal-check:v1";
+const SHARE_LEN: usize = 33;
+pub const DEFAULT_KEY_SHARES: u8 = 5;
+pub const DEFAULT_KEY_THRESHOLD: u8 = 3;
+
+/// The server master key as seen by request handlers. Keys loaded from config
+/// are fixed for the process lifetime; in manual-unseal mode the key comes
+/// and goes with unseal and seal.
+#[derive(Clone)]
+pub struct MasterKeyStore {
+    inner: Arc<RwLock<MasterKeyState>>,
+}
+
+struct MasterKeyState {
+    key: Option<Arc<SecretKey>>,
+    manual_unseal: bool,
+    pending: Vec<Vec<u8>>,
+}
+
+impl MasterKeyState {
+    fn clear_pending(&mut self) {
+        for share in &mut self.pending {
+            share.zeroize();
+        }
+        self.pending.clear();
+    }
+}
+
+impl Drop for MasterKeyState {
+    fn drop(&mut self) {
+        self.clear_pending();
+    }
+}
+
+/// Result of submitting one share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsealProgress {
+    /// Shares collected so far, still below the threshold.
+    Pending(usize),
+    Unsealed,
+}
+
+impl MasterKeyStore {
+    /// Store for `auto_generate` and `external` modes.
+    #[must_use]
+    pub fn new(key: Option<SecretKey>) -> Self {
+        Self::with_state(key.map(Arc::new), false)
+    }
+
+    /// Sealed store for `manual_unseal` mode.
+    #[must_use]
+    pub fn sealed() -> Self {
+        Self::with_state(None, true)
+    }
+
+    fn with_state(key: Option<Arc<SecretKey>>, manual_unseal: bool) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(MasterKeyState {
+                key,
+                manual_unseal,
+                pending: Vec::new(),
+            })),
+        }
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, MasterKeyState> {
+        self.inner.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, MasterKeyState> {
+        self.inner.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    #[must_use]
+    pub fn get(&self) -> Option<Arc<SecretKey>> {
+        self.read().key.clone()
+    }
+
+    #[must_use]
+    pub fn is_some(&self) -> bool {
+        self.read().key.is_some()
+    }
+
+    #[must_use]
+    pub fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
+    #[must_use]
+    pub fn is_manual_unseal(&self) -> bool {
+        self.read().manual_unseal
+    }
+
+    #[must_use]
+    pub fn is_sealed(&self) -> bool {
+        let state = self.read();
+        state.manual_unseal && state.key.is_none()
+    }
+
+    #[must_use]
+    pub fn progress(&self) -> usize {
+        self.read().pending.len()
+    }
+
+    /// Adds a decoded share and reconstructs the key once `config.threshold`
+    /// distinct shares are in. A reconstruction that fails the key check
+    /// discards every collected share.
+    pub fn submit_share(
+        &self,
+        share: Vec<u8>,
+        config: &SealConfig,
+    ) -> Result<UnsealProgress, SealServiceError> {
+        if share.len() != SHARE_LEN || share[0] == 0 {
+            return Err(SealServiceError::BadRequest("invalid_share"));
+        }
+        let mut state = self.write();
+        if state.key.is_some() {
+            return Ok(UnsealProgress::Unsealed);
+        }
+        if state.pending.iter().any(|pending| pending[0] == share[0]) {
+            return Err(SealServiceError::BadRequest("duplicate_share"));
+        }
+        state.pending.push(share);
+        let threshold = usize::try_from(config.threshold).unwrap_or(usize::MAX);
+        if state.pending.len() < threshold {
+            return Ok(UnsealProgress::Pending(state.pending.len()));
+        }
+
+        let combined = shamir::combine_shares(&state.pending);
+        state.clear_pending();
+        let mut bytes = match combined {
+            Ok(bytes) => bytes,
+            Err(ShamirError::DuplicateShare | ShamirError::InvalidShare) => {
+                return Err(SealServiceError::BadRequest("invalid_share"));
+            }
+            Err(_) => return Err(SealServiceError::BadRequest("unseal_failed")),
+        };
+        let key = <[u8; 32]>::try_from(bytes.as_slice()).map(SecretKey::from_bytes);
+        bytes.zeroize();
+        let Ok(key) = key else {
+            return Err(SealServiceError::BadRequest("unseal_failed"));
+        };
+        let matches: bool = key_check(&key)
+            .as_bytes()
+            .ct_eq(config.key_check.as_bytes())
+            .into();
+        if !matches {
+            return Err(SealServiceError::BadRequest("unseal_failed"));
+        }
+        state.key = Some(Arc::new(key));
+        Ok(UnsealProgress::Unsealed)
+    }
+
+    /// Drops collected shares without unsealing.
+    pub fn reset_progress(&self) {
+        self.write().clear_pending();
+    }
+
+    /// Forgets the key. Returns false when the store is not in manual-unseal
+    /// mode, since nothing could bring the key back.
+    pub fn seal(&self) -> bool {
+        let mut state = self.write();
+        if !state.manual_unseal {
+            return false;
+        }
+        state.key = None;
+        state.clear_pending();
+        true
+    }
+}
+
+/// Value stored at init to recognise the reconstructed master key without
+/// revealing it.
+#[must_use]
+pub fn key_check(key: &SecretKey) -> String {
+    let hash = blake3::keyed_hash(key.as_bytes(), KEY_CHECK_CONTEXT);
+    hex::encode(hash.as_bytes())
+}
+
+/// Splits the master key into base64 shares for operators.
+pub fn split_master_key(
+    key: &SecretKey,
+    threshold: u8,
+    shares: u8,
+) -> Result<Vec<String>, ShamirError> {
+    let split = shamir::split_secret(key.as_bytes(), threshold, shares)?;
+    Ok(split
+        .into_iter()
+        .map(|mut share| {
+            let encoded = base64::engine::general_purpose::STANDARD.encode(&share);
+            share.zeroize();
+            encoded
+        })
+        .collect())
+}
+
+fn decode_share(value: &str) -> Option<Vec<u8>> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(value.trim())
+        .ok()?;
+    (bytes.len() == SHARE_LEN && bytes[0] != 0).then_some(bytes)
+}
+
+#[derive(Debug, Clone)]
+pub struct SealStatus {
+    pub manual_unseal: bool,
+    pub sealed: bool,
+    pub initialized: bool,
+    pub threshold: Option<i16>,
+    pub shares: Option<i16>,
+    pub progress: usize,
+}
+
+async fn load_config(state: &AppState) -> Result<Option<SealConfig>, SealServiceError> {
+    SealConfigRepo::new(&state.db).get().await.map_err(|err| {
+        tracing::error!(event = "seal_config_load_failed", error = %err, "DB error");
+        SealServiceError::DbError
+    })
+}
+
+pub async fn seal_status(state: &AppState) -> Result<SealStatus, SealServiceError> {
+    let store = &state.server_master_key;
+    if !store.is_manual_unseal() {
+        return Ok(SealStatus {
+            manual_unseal: false,
+            sealed: false,
+            initialized: true,
+            threshold: None,
+            shares: None,
+            progress: 0,
+        });
+    }
+    let config = load_config(state).await?;
+    Ok(SealStatus {
+        manual_unseal: true,
+        sealed: store.is_sealed(),
+        initialized: config.is_some(),
+        threshold: config.as_ref().map(|config| config.threshold),
+        shares: config.as_ref().map(|config| config.shares),
+        progress: store.progress(),
+    })
+}
+
+pub async fn unseal(
+    state: &AppState,
+    share: Option<&str>,
+    reset: bool,
+) -> Result<SealStatus, SealServiceError> {
+    let store = &state.server_master_key;
+    if !store.is_manual_unseal() {
+        return Err(SealServiceError::BadRequest("unseal_not_supported"));
+    }
+    if reset {
+        store.reset_progress();
+        return seal_status(state).await;
+    }
+    let Some(share) = share else {
+        return Err(SealServiceError::BadRequest("share_required"));
+    };
+    if !store.is_sealed() {
+        return seal_status(state).await;
+    }
+    let Some(config) = load_config(state).await? else {
+        return Err(SealServiceError::Conflict("not_initialized"));
+    };
+    let Some(share) = decode_share(share) else {
+        return Err(SealServiceError::BadRequest("invalid_share"));
+    };
+
+    match store.submit_share(share, &config) {
+        Ok(UnsealProgress::Pending(progress)) => {
+            tracing::info!(
+                event = "unseal_share_accepted",
+                progress,
+                threshold = config.threshold,
+                "Unseal share accepted"
+            );
+        }
+        Ok(UnsealProgress::Unsealed) => {
+            tracing::info!(event = "server_unsealed", "Server unsealed");
+            audit::record(state, None, AuditEntry::new("system", "unseal", "ok")).await;
+        }
+        Err(err) => {
+            tracing::warn!(event = "unseal_rejected", reason = ?err, "Unseal share rejected");
+            if matches!(err, SealServiceError::BadRequest("unseal_failed")) {
+                let entry = AuditEntry::new("system", "unseal", err.label())
+                    .detail(Some("key_check_mismatch"));
+                audit::record(state, None, entry).await;
+            }
+            return Err(err);
+        }
+    }
+    seal_status(state).await
+}
+
+pub async fn seal(state: &AppState, identity: &Identity) -> Result<SealStatus, SealServiceError> {
+    let resource = "system/seal";
+    let policies = state.policy_store.get();
+    if identity.service_account_id.is_some() || !policies.is_allowed(identity, "write", resource) {
+        metrics::forbidden_access(resource);
+        tracing::warn!(
+            event = "forbidden",
+            action = "write",
+            resource = resource,
+            "Access denied"
+        );
+        return Err(SealServiceError::ForbiddenNoBody);
+    }
+    if !state.server_master_key.seal() {
+        return Err(SealServiceError::BadRequest("seal_not_supported"));
+    }
+    tracing::warn!(event = "server_sealed", "Server sealed");
+    audit::record(
+        state,
+        Some(identity),
+        AuditEntry::new("system", "seal", "ok"),
+    )
+    .await;
+    seal_status(state).await
+}
+
+/// Rejects API calls with `503 sealed` while the master key is missing in
+/// manual-unseal mode. Health and `/v1/system/*` stay reachable so operators
+/// can check status and unseal.
+pub async fn sealed_middleware(request: Request<Body>, next: Next) -> Response {
+    let sealed = request
+        .extensions()
+        .get::<AppState>()
+        .is_some_and(|state| state.server_master_key.is_sealed());
+    if sealed {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "sealed" })),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn config_for(key: &SecretKey, threshold: i16, shares: i16) -> SealConfig {
+        SealConfig {
+            threshold,
+            shares,
+            key_check: key_check(key),
+            created_at: Utc::now(),
+        }
+    }
+
+    fn decoded(shares: &[String]) -> Vec<Vec<u8>> {
+        shares
+            .iter()
+            .map(|share| decode_share(share).expect("decode share"))
+            .collect()
+    }
+
+    #[test]
+    fn threshold_shares_unseal_and_seal_forgets_key() {
+        let key = SecretKey::generate();
+        let shares = decoded(&split_master_key(&key, 3, 5).expect("split"));
+        let config = config_for(&key, 3, 5);
+        let store = MasterKeyStore::sealed();
+        assert!(store.is_sealed());
+
+        assert!(matches!(
+            store.submit_share(shares[4].clone(), &config),
+            Ok(UnsealProgress::Pending(1))
+        ));
+        assert!(matches!(
+            store.submit_share(shares[4].clone(), &config),
+            Err(SealServiceError::BadRequest("duplicate_share"))
+        ));
+        assert!(matches!(
+            store.submit_share(shares[1].clone(), &config),
+            Ok(UnsealProgress::Pending(2))
+        ));
+        assert!(matches!(
+            store.submit_share(shares[2].clone(), &config),
+            Ok(UnsealProgress::Unsealed)
+        ));
+        assert!(!store.is_sealed());
+        assert_eq!(
+            store.get().expect("key").as_bytes(),
+            key.as_bytes(),
+            "reconstructed key"
+        );
+        assert_eq!(store.progress(), 0);
+
+        assert!(store.seal());
+        assert!(store.is_sealed());
+        assert!(store.get().is_none());
+    }
+
+    #[test]
+    fn shares_from_another_split_fail_the_key_check() {
+        let key = SecretKey::generate();
+        let other = SecretKey::generate();
+        let shares = decoded(&split_master_key(&other, 2, 3).expect("split"));
+        let config = config_for(&key, 2, 3);
+        let store = MasterKeyStore::sealed();
+
+        store
+            .submit_share(shares[0].clone(), &config)
+            .expect("first share");
+        assert!(matches!(
+            store.submit_share(shares[1].clone(), &config),
+            Err(SealServiceError::BadRequest("unseal_failed"))
+        ));
+        assert!(store.is_sealed());
+        assert_eq!(store.progress(), 0);
+    }
+
+    #[test]
+    fn config_keys_cannot_be_sealed() {
+        let store = MasterKeyStore::new(Some(SecretKey::generate()));
+        assert!(!store.is_sealed());
+        assert!(!store.seal());
+        assert!(store.get().is_some());
+    }
+
+    #[test]
+    fn decode_share_rejects_malformed_input() {
+        assert!(decode_share("not base64!").is_none());
+        assert!(
+            decode_share(&base64::engine::general_purpose::STANDARD.encode([1u8; 8])).is_none()
+        );
+        assert!(
+            decode_share(&base64::engine::general_purpose::STANDARD.encode([0u8; SHARE_LEN]))
+                .is_none()
+        );
+    }
+}
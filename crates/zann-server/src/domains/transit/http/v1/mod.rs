@@ -0,0 +1,341 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zann_core::{Identity, TransitKey};
+
+use crate::app::AppState;
+use crate::domains::transit::service::{self, TransitCiphertext, TransitError};
+use crate::infra::audit::{self, AuditEntry};
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    pub(crate) error: &'static str,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct UpdateTransitKeyRequest {
+    pub(crate) min_decryption_version: i32,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct TransitEncryptRequest {
+    /// Base64-encoded data to encrypt.
+    pub(crate) plaintext: String,
+    /// Optional base64 associated data; the same value is required to decrypt.
+    #[serde(default)]
+    pub(crate) context: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct TransitCiphertextRequest {
+    pub(crate) ciphertext: String,
+    #[serde(default)]
+    pub(crate) context: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct TransitKeyResponse {
+    pub(crate) id: String,
+    pub(crate) vault_id: String,
+    pub(crate) name: String,
+    pub(crate) latest_version: i32,
+    pub(crate) min_decryption_version: i32,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct TransitCiphertextResponse {
+    pub(crate) ciphertext: String,
+    pub(crate) key_version: i32,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct TransitPlaintextResponse {
+    /// Base64-encoded decrypted data.
+    pub(crate) plaintext: String,
+    pub(crate) key_version: i32,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/v1/vaults/:vault_id/transit/:key",
+            get(get_key).post(create_key).patch(update_key),
+        )
+        .route("/v1/vaults/:vault_id/transit/:key/encrypt", post(encrypt))
+        .route("/v1/vaults/:vault_id/transit/:key/decrypt", post(decrypt))
+        .route("/v1/vaults/:vault_id/transit/:key/rewrap", post(rewrap))
+        .route("/v1/vaults/:vault_id/transit/:key/rotate", post(rotate_key))
+}
+
+fn map_transit_error(error: TransitError) -> axum::response::Response {
+    match error {
+        TransitError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        TransitError::Forbidden(code) => {
+            (StatusCode::FORBIDDEN, Json(ErrorResponse { error: code })).into_response()
+        }
+        TransitError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        TransitError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        TransitError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        TransitError::DbError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response(),
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+fn key_response(key: TransitKey) -> TransitKeyResponse {
+    TransitKeyResponse {
+        id: key.id.to_string(),
+        vault_id: key.vault_id.to_string(),
+        name: key.name,
+        latest_version: key.latest_version,
+        min_decryption_version: key.min_decryption_version,
+        created_at: key.created_at.to_rfc3339(),
+        updated_at: key.updated_at.to_rfc3339(),
+    }
+}
+
+fn ciphertext_response(result: TransitCiphertext) -> TransitCiphertextResponse {
+    TransitCiphertextResponse {
+        ciphertext: result.ciphertext,
+        key_version: result.key_version,
+    }
+}
+
+async fn record_ok(state: &AppState, identity: &Identity, action: &str, key: &TransitKey) {
+    let version = key.latest_version.to_string();
+    let entry = AuditEntry::new("transit", action, "ok")
+        .vault(key.vault_id)
+        .target(&key.name)
+        .detail(Some(&version));
+    audit::record(state, Some(identity), entry).await;
+}
+
+async fn record_error(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    vault_id: &str,
+    name: &str,
+    error: &TransitError,
+) {
+    let label = error.label();
+    let mut entry = AuditEntry::new("transit", action, label)
+        .target(name)
+        .detail(Some(label));
+    if let Some(vault_id) = audit::resolve_vault_id(state, vault_id).await {
+        entry = entry.vault(vault_id);
+    }
+    audit::record(state, Some(identity), entry).await;
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_key(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::get_key(&state, &identity, &vault_id, &name).await {
+        Ok(key) => (StatusCode::OK, Json(key_response(key))).into_response(),
+        Err(error) => map_transit_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn create_key(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::create_key(&state, &identity, &vault_id, &name).await {
+        Ok(key) => {
+            record_ok(&state, &identity, "transit_key_create", &key).await;
+            (StatusCode::CREATED, Json(key_response(key))).into_response()
+        }
+        Err(error) => map_transit_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn update_key(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+    Json(payload): Json<UpdateTransitKeyRequest>,
+) -> impl IntoResponse {
+    let result = service::set_min_decryption_version(
+        &state,
+        &identity,
+        &vault_id,
+        &name,
+        payload.min_decryption_version,
+    )
+    .await;
+    match result {
+        Ok(key) => {
+            let detail = key.min_decryption_version.to_string();
+            let entry = AuditEntry::new("transit", "transit_key_update", "ok")
+                .vault(key.vault_id)
+                .target(&key.name)
+                .detail(Some(&detail));
+            audit::record(&state, Some(&identity), entry).await;
+            (StatusCode::OK, Json(key_response(key))).into_response()
+        }
+        Err(error) => map_transit_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn rotate_key(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match service::rotate_key(&state, &identity, &vault_id, &name).await {
+        Ok(key) => {
+            record_ok(&state, &identity, "transit_key_rotate", &key).await;
+            (StatusCode::OK, Json(key_response(key))).into_response()
+        }
+        Err(error) => map_transit_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn encrypt(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+    Json(payload): Json<TransitEncryptRequest>,
+) -> impl IntoResponse {
+    let result = service::encrypt(
+        &state,
+        &identity,
+        &vault_id,
+        &name,
+        &payload.plaintext,
+        payload.context.as_deref(),
+    )
+    .await;
+    match result {
+        Ok((key, ciphertext)) => {
+            record_ok(&state, &identity, "transit_encrypt", &key).await;
+            (StatusCode::OK, Json(ciphertext_response(ciphertext))).into_response()
+        }
+        Err(error) => {
+            record_error(
+                &state,
+                &identity,
+                "transit_encrypt",
+                &vault_id,
+                &name,
+                &error,
+            )
+            .await;
+            map_transit_error(error)
+        }
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn decrypt(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+    Json(payload): Json<TransitCiphertextRequest>,
+) -> impl IntoResponse {
+    let result = service::decrypt(
+        &state,
+        &identity,
+        &vault_id,
+        &name,
+        &payload.ciphertext,
+        payload.context.as_deref(),
+    )
+    .await;
+    match result {
+        Ok((key, plaintext)) => {
+            let version = plaintext.key_version.to_string();
+            let entry = AuditEntry::new("transit", "transit_decrypt", "ok")
+                .vault(key.vault_id)
+                .target(&key.name)
+                .detail(Some(&version));
+            audit::record(&state, Some(&identity), entry).await;
+            (
+                StatusCode::OK,
+                Json(TransitPlaintextResponse {
+                    plaintext: plaintext.plaintext,
+                    key_version: plaintext.key_version,
+                }),
+            )
+                .into_response()
+        }
+        Err(error) => {
+            record_error(
+                &state,
+                &identity,
+                "transit_decrypt",
+                &vault_id,
+                &name,
+                &error,
+            )
+            .await;
+            map_transit_error(error)
+        }
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn rewrap(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, name)): Path<(String, String)>,
+    Json(payload): Json<TransitCiphertextRequest>,
+) -> impl IntoResponse {
+    let result = service::rewrap(
+        &state,
+        &identity,
+        &vault_id,
+        &name,
+        &payload.ciphertext,
+        payload.context.as_deref(),
+    )
+    .await;
+    match result {
+        Ok((key, ciphertext)) => {
+            record_ok(&state, &identity, "transit_rewrap", &key).await;
+            (StatusCode::OK, Json(ciphertext_response(ciphertext))).into_response()
+        }
+        Err(error) => {
+            record_error(
+                &state,
+                &identity,
+                "transit_rewrap",
+                &vault_id,
+                &name,
+                &error,
+            )
+            .await;
+            map_transit_error(error)
+        }
+    }
+}
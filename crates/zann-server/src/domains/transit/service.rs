@@ -0,0 +1,371 @@
+//! Transit encryption: named keys kept in server-encrypted vaults that encrypt
+//! and decrypt caller data without the key material ever leaving the server.
+//! Ciphertexts carry the key version (`zann:v<N>:<base64>`) so data encrypted
+//! before a rotation stays readable and can be rewrapped to the latest version.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::Utc;
+use uuid::Uuid;
+use zann_core::{Identity, TransitKey, TransitKeyVersion, Vault};
+use zann_crypto::crypto::{decrypt_blob, encrypt_blob, EncryptedBlob, SecretKey};
+use zann_crypto::vault_crypto as core_crypto;
+use zann_db::repo::TransitKeyRepo;
+
+use crate::app::AppState;
+use crate::domains::access_control::http::VaultScope;
+use crate::domains::errors::ServiceError;
+use crate::domains::secrets::service::{
+    authorize_vault_access, ensure_server_encryption, normalize_name, result_label,
+    server_vault_key,
+};
+use crate::infra::metrics;
+
+pub type TransitError = ServiceError;
+
+const CIPHERTEXT_PREFIX: &str = "zann:v";
+const MAX_PLAINTEXT_BYTES: usize = 1024 * 1024;
+
+/// Data encrypted under a transit key.
+#[derive(Debug, Clone)]
+pub struct TransitCiphertext {
+    pub ciphertext: String,
+    pub key_version: i32,
+}
+
+/// Decrypted data and the key version that had encrypted it.
+#[derive(Debug, Clone)]
+pub struct TransitPlaintext {
+    pub plaintext: String,
+    pub key_version: i32,
+}
+
+fn resource(vault_id: &str, name: &str) -> String {
+    format!("vaults/{vault_id}/transit/{name}")
+}
+
+fn scope_path(name: &str) -> String {
+    format!("transit/{name}")
+}
+
+pub async fn create_key(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+) -> Result<TransitKey, TransitError> {
+    let (vault, name) = authorize(state, identity, vault_id, name, "transit_manage").await?;
+    let vault_key = server_vault_key(state, &vault)?;
+    let now = Utc::now();
+    let key = TransitKey {
+        id: Uuid::now_v7(),
+        vault_id: vault.id,
+        name,
+        latest_version: 1,
+        min_decryption_version: 1,
+        created_at: now,
+        updated_at: now,
+    };
+    let version = new_version(&vault_key, &key, 1)?;
+    let created = TransitKeyRepo::new(&state.db)
+        .create(&key, &version)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "transit_key_create_failed", error = %err, "DB error");
+            TransitError::DbError
+        })?;
+    if !created {
+        return Err(TransitError::Conflict("transit_key_exists"));
+    }
+    metrics::transit_operations("create", "ok");
+    Ok(key)
+}
+
+pub async fn get_key(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+) -> Result<TransitKey, TransitError> {
+    let (vault, name) = authorize(state, identity, vault_id, name, "read").await?;
+    load_key(state, &vault, &name).await
+}
+
+/// Adds a new key version. New encryptions use it; older versions keep
+/// decrypting until `min_decryption_version` moves past them.
+pub async fn rotate_key(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+) -> Result<TransitKey, TransitError> {
+    let (vault, name) = authorize(state, identity, vault_id, name, "transit_manage").await?;
+    let mut key = load_key(state, &vault, &name).await?;
+    let vault_key = server_vault_key(state, &vault)?;
+    let next = key
+        .latest_version
+        .checked_add(1)
+        .ok_or(TransitError::Conflict("transit_key_version_limit"))?;
+    let version = new_version(&vault_key, &key, next)?;
+    let rotated = TransitKeyRepo::new(&state.db)
+        .add_version(&version, version.created_at)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "transit_key_rotate_failed", error = %err, "DB error");
+            TransitError::DbError
+        })?;
+    if !rotated {
+        return Err(TransitError::Conflict("transit_key_rotating"));
+    }
+    metrics::transit_operations("rotate", "ok");
+    key.latest_version = next;
+    key.updated_at = version.created_at;
+    Ok(key)
+}
+
+/// Retires versions below `min_decryption_version`: ciphertexts under them can
+/// no longer be decrypted or rewrapped.
+pub async fn set_min_decryption_version(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+    min_decryption_version: i32,
+) -> Result<TransitKey, TransitError> {
+    let (vault, name) = authorize(state, identity, vault_id, name, "transit_manage").await?;
+    let mut key = load_key(state, &vault, &name).await?;
+    if min_decryption_version < 1 || min_decryption_version > key.latest_version {
+        return Err(TransitError::BadRequest("invalid_min_decryption_version"));
+    }
+    let now = Utc::now();
+    let updated = TransitKeyRepo::new(&state.db)
+        .set_min_decryption_version(key.id, min_decryption_version, now)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "transit_key_update_failed", error = %err, "DB error");
+            TransitError::DbError
+        })?;
+    if !updated {
+        return Err(TransitError::NotFound);
+    }
+    key.min_decryption_version = min_decryption_version;
+    key.updated_at = now;
+    Ok(key)
+}
+
+/// Encrypts base64 `plaintext` with the latest key version. `context` is
+/// optional base64 bound into the ciphertext as associated data.
+pub async fn encrypt(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+    plaintext: &str,
+    context: Option<&str>,
+) -> Result<(TransitKey, TransitCiphertext), TransitError> {
+    let plaintext = decode_base64(plaintext, "invalid_plaintext")?;
+    if plaintext.len() > MAX_PLAINTEXT_BYTES {
+        return Err(TransitError::BadRequest("plaintext_too_large"));
+    }
+    let context = decode_context(context)?;
+    let (vault, name) = authorize(state, identity, vault_id, name, "transit_encrypt").await?;
+    let key = load_key(state, &vault, &name).await?;
+    let result = seal(state, &vault, &key, &plaintext, &context).await;
+    metrics::transit_operations("encrypt", result_label(&result));
+    Ok((key, result?))
+}
+
+pub async fn decrypt(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+    ciphertext: &str,
+    context: Option<&str>,
+) -> Result<(TransitKey, TransitPlaintext), TransitError> {
+    let context = decode_context(context)?;
+    let (vault, name) = authorize(state, identity, vault_id, name, "transit_decrypt").await?;
+    let key = load_key(state, &vault, &name).await?;
+    let result = open(state, &vault, &key, ciphertext, &context).await;
+    metrics::transit_operations("decrypt", result_label(&result));
+    let (plaintext, key_version) = result?;
+    Ok((
+        key,
+        TransitPlaintext {
+            plaintext: STANDARD.encode(plaintext),
+            key_version,
+        },
+    ))
+}
+
+/// Re-encrypts a ciphertext under the latest key version without returning
+/// the plaintext. Needs the same permission as `encrypt`.
+pub async fn rewrap(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+    ciphertext: &str,
+    context: Option<&str>,
+) -> Result<(TransitKey, TransitCiphertext), TransitError> {
+    let context = decode_context(context)?;
+    let (vault, name) = authorize(state, identity, vault_id, name, "transit_encrypt").await?;
+    let key = load_key(state, &vault, &name).await?;
+    let result = async {
+        let (plaintext, _) = open(state, &vault, &key, ciphertext, &context).await?;
+        seal(state, &vault, &key, &plaintext, &context).await
+    }
+    .await;
+    metrics::transit_operations("rewrap", result_label(&result));
+    Ok((key, result?))
+}
+
+async fn authorize(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    name: &str,
+    action: &str,
+) -> Result<(Vault, String), TransitError> {
+    let name = normalize_name(name, "invalid_key_name")?;
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        action,
+        &resource(vault_id, &name),
+        &scope_path(&name),
+        VaultScope::Items,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    Ok((vault, name))
+}
+
+async fn seal(
+    state: &AppState,
+    vault: &Vault,
+    key: &TransitKey,
+    plaintext: &[u8],
+    context: &[u8],
+) -> Result<TransitCiphertext, TransitError> {
+    let version_key = version_key(state, vault, key, key.latest_version).await?;
+    let aad = core_crypto::transit_data_aad(key.id, context);
+    let blob = encrypt_blob(&version_key, plaintext, &aad)
+        .map_err(|_| TransitError::Internal("encrypt_failed"))?;
+    Ok(TransitCiphertext {
+        ciphertext: format!(
+            "{CIPHERTEXT_PREFIX}{}:{}",
+            key.latest_version,
+            STANDARD.encode(blob.to_bytes())
+        ),
+        key_version: key.latest_version,
+    })
+}
+
+async fn open(
+    state: &AppState,
+    vault: &Vault,
+    key: &TransitKey,
+    ciphertext: &str,
+    context: &[u8],
+) -> Result<(Vec<u8>, i32), TransitError> {
+    let (version, blob) = parse_ciphertext(ciphertext)?;
+    if version > key.latest_version {
+        return Err(TransitError::BadRequest("invalid_ciphertext"));
+    }
+    if version < key.min_decryption_version {
+        return Err(TransitError::BadRequest("key_version_retired"));
+    }
+    let version_key = version_key(state, vault, key, version).await?;
+    let aad = core_crypto::transit_data_aad(key.id, context);
+    let plaintext = decrypt_blob(&version_key, &blob, &aad)
+        .map_err(|_| TransitError::BadRequest("decrypt_failed"))?;
+    Ok((plaintext, version))
+}
+
+fn parse_ciphertext(value: &str) -> Result<(i32, EncryptedBlob), TransitError> {
+    let invalid = || TransitError::BadRequest("invalid_ciphertext");
+    let rest = value
+        .trim()
+        .strip_prefix(CIPHERTEXT_PREFIX)
+        .ok_or_else(invalid)?;
+    let (version, encoded) = rest.split_once(':').ok_or_else(invalid)?;
+    let version: i32 = version.parse().map_err(|_| invalid())?;
+    if version < 1 {
+        return Err(invalid());
+    }
+    let bytes = STANDARD.decode(encoded).map_err(|_| invalid())?;
+    let blob = EncryptedBlob::from_bytes(&bytes).map_err(|_| invalid())?;
+    Ok((version, blob))
+}
+
+fn new_version(
+    vault_key: &SecretKey,
+    key: &TransitKey,
+    version: i32,
+) -> Result<TransitKeyVersion, TransitError> {
+    let key_enc = core_crypto::encrypt_transit_key(
+        vault_key,
+        key.vault_id,
+        key.id,
+        version,
+        &SecretKey::generate(),
+    )
+    .map_err(|_| TransitError::Internal("encrypt_failed"))?;
+    Ok(TransitKeyVersion {
+        key_id: key.id,
+        version,
+        key_enc,
+        created_at: Utc::now(),
+    })
+}
+
+async fn version_key(
+    state: &AppState,
+    vault: &Vault,
+    key: &TransitKey,
+    version: i32,
+) -> Result<SecretKey, TransitError> {
+    let stored = match TransitKeyRepo::new(&state.db)
+        .get_version(key.id, version)
+        .await
+    {
+        Ok(Some(stored)) => stored,
+        Ok(None) => return Err(TransitError::Internal("transit_key_version_missing")),
+        Err(err) => {
+            tracing::error!(event = "transit_key_load_failed", error = %err, "DB error");
+            return Err(TransitError::DbError);
+        }
+    };
+    let vault_key = server_vault_key(state, vault)?;
+    core_crypto::decrypt_transit_key(&vault_key, vault.id, key.id, version, &stored.key_enc)
+        .map_err(|_| TransitError::Internal("decrypt_failed"))
+}
+
+async fn load_key(state: &AppState, vault: &Vault, name: &str) -> Result<TransitKey, TransitError> {
+    match TransitKeyRepo::new(&state.db)
+        .get_by_name(vault.id, name)
+        .await
+    {
+        Ok(Some(key)) => Ok(key),
+        Ok(None) => Err(TransitError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "transit_key_load_failed", error = %err, "DB error");
+            Err(TransitError::DbError)
+        }
+    }
+}
+
+fn decode_base64(value: &str, error: &'static str) -> Result<Vec<u8>, TransitError> {
+    STANDARD
+        .decode(value.trim())
+        .map_err(|_| TransitError::BadRequest(error))
+}
+
+fn decode_context(context: Option<&str>) -> Result<Vec<u8>, TransitError> {
+    context.map_or_else(
+        || Ok(Vec::new()),
+        |value| decode_base64(value, "invalid_context"),
+    )
+}
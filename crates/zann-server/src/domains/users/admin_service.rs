@@ -10,6 +10,7 @@ use crate::domains::auth::core::passwords::{
     derive_auth_hash, hash_password, random_kdf_salt, KdfParams,
 };
 use crate::domains::errors::ServiceError;
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::metrics;
 
 pub struct ListUsersCommand {
@@ -197,10 +198,19 @@ pub async fn create_user(
         return Err(AdminUserError::BadRequest("email_exists"));
     }
 
-    let Ok(()) = repo.create(&user).await else {
-        tracing::error!(event = "users_create_failed", "DB error");
-        return Err(AdminUserError::DbError);
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "users_create_failed", error = %err, "DB error");
+        AdminUserError::DbError
     };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    UserRepo::create_in(&mut tx, &user)
+        .await
+        .map_err(db_error)?;
+    let audited = audit::record_tx(&mut tx, Some(identity), user_audit_entry("create", user.id))
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
     tracing::info!(event = "users_create", user_id = "redacted", "User created");
     Ok(user)
 }
@@ -257,22 +267,29 @@ pub async fn delete_user(
     };
 
     let deleted_at = chrono::Utc::now();
-    let Ok(affected) = repo
-        .delete_by_id(
-            user.id,
-            user.row_version,
-            deleted_at,
-            identity.user_id,
-            device_id,
-        )
-        .await
-    else {
-        tracing::error!(event = "users_delete_failed", "DB error");
-        return Err(AdminUserError::DbError);
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "users_delete_failed", error = %err, "DB error");
+        AdminUserError::DbError
     };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    let affected = UserRepo::delete_by_id_in(
+        &mut tx,
+        user.id,
+        user.row_version,
+        deleted_at,
+        identity.user_id,
+        device_id,
+    )
+    .await
+    .map_err(db_error)?;
     if affected == 0 {
         return Err(AdminUserError::NotFound);
     }
+    let audited = audit::record_tx(&mut tx, Some(identity), user_audit_entry("delete", user.id))
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     tracing::info!(event = "users_delete", user_id = "redacted", "User deleted");
     Ok(())
@@ -300,16 +317,7 @@ pub async fn block_user(
             return Err(AdminUserError::DbError);
         }
     };
-    let Ok(affected) = repo
-        .update_status(user.id, user.row_version, UserStatus::Disabled)
-        .await
-    else {
-        tracing::error!(event = "users_block_failed", "DB error");
-        return Err(AdminUserError::DbError);
-    };
-    if affected == 0 {
-        return Err(AdminUserError::NotFound);
-    }
+    set_status(state, identity, &user, UserStatus::Disabled, "block").await?;
 
     tracing::info!(event = "users_block", user_id = "redacted", "User blocked");
     Ok(user)
@@ -337,16 +345,7 @@ pub async fn unblock_user(
             return Err(AdminUserError::DbError);
         }
     };
-    let Ok(affected) = repo
-        .update_status(user.id, user.row_version, UserStatus::Active)
-        .await
-    else {
-        tracing::error!(event = "users_unblock_failed", "DB error");
-        return Err(AdminUserError::DbError);
-    };
-    if affected == 0 {
-        return Err(AdminUserError::NotFound);
-    }
+    set_status(state, identity, &user, UserStatus::Active, "unblock").await?;
 
     tracing::info!(
         event = "users_unblock",
@@ -424,16 +423,24 @@ pub async fn reset_password(
             tracing::error!(event = "users_reset_password_failed", "KDF error");
             return Err(AdminUserError::Kdf);
         };
-    let Ok(affected) = repo
-        .update_password_hash(user.id, user.row_version, Some(&password_hash))
-        .await
-    else {
-        tracing::error!(event = "users_reset_password_failed", "DB error");
-        return Err(AdminUserError::DbError);
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "users_reset_password_failed", error = %err, "DB error");
+        AdminUserError::DbError
     };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    let affected =
+        UserRepo::update_password_hash_in(&mut tx, user.id, user.row_version, Some(&password_hash))
+            .await
+            .map_err(db_error)?;
     if affected == 0 {
         return Err(AdminUserError::NotFound);
     }
+    let entry = user_audit_entry("reset_password", user.id);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     tracing::info!(
         event = "users_reset_password",
@@ -442,3 +449,104 @@ pub async fn reset_password(
     );
     Ok(ResetPasswordResult { password, user })
 }
+
+/// Assigns the SSH principal that `{{username}}` expands to in SSH signing
+/// roles. `None` (or a blank value) clears it.
+pub async fn set_ssh_username(
+    state: &AppState,
+    identity: &Identity,
+    user_id: &str,
+    ssh_username: Option<&str>,
+) -> Result<Option<String>, AdminUserError> {
+    let resource = "users";
+    ensure_internal(identity, resource, "write")?;
+    ensure_policy(state, identity, resource, "write")?;
+
+    let Ok(id) = Uuid::parse_str(user_id) else {
+        return Err(AdminUserError::BadRequest("invalid_user_id"));
+    };
+    let ssh_username = normalize_ssh_username(ssh_username)?;
+
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "users_ssh_username_failed", error = %err, "DB error");
+        AdminUserError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    let affected =
+        match UserRepo::update_ssh_username_in(&mut tx, id, ssh_username.as_deref()).await {
+            Ok(affected) => affected,
+            Err(err)
+                if err
+                    .as_database_error()
+                    .is_some_and(|db_err| db_err.is_unique_violation()) =>
+            {
+                return Err(AdminUserError::Conflict("ssh_username_taken"));
+            }
+            Err(err) => return Err(db_error(err)),
+        };
+    if affected == 0 {
+        return Err(AdminUserError::NotFound);
+    }
+    let entry = user_audit_entry("set_ssh_username", id).detail(ssh_username.as_deref());
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
+
+    tracing::info!(
+        event = "users_ssh_username",
+        user_id = "redacted",
+        "SSH username updated"
+    );
+    Ok(ssh_username)
+}
+
+/// Accepts POSIX-style login names: a lowercase letter or `_` followed by up
+/// to 31 lowercase letters, digits, `_`, `.` or `-`.
+fn normalize_ssh_username(value: Option<&str>) -> Result<Option<String>, AdminUserError> {
+    let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Ok(None);
+    };
+    let mut chars = value.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_lowercase() || first == '_');
+    let valid_rest = chars
+        .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || matches!(ch, '_' | '.' | '-'));
+    if !valid_start || !valid_rest || value.len() > 32 {
+        return Err(AdminUserError::BadRequest("invalid_ssh_username"));
+    }
+    Ok(Some(value.to_string()))
+}
+
+/// Updates the user's status and records `action` in the same transaction.
+async fn set_status(
+    state: &AppState,
+    identity: &Identity,
+    user: &User,
+    status: UserStatus,
+    action: &str,
+) -> Result<(), AdminUserError> {
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "users_status_update_failed", error = %err, action, "DB error");
+        AdminUserError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    let affected = UserRepo::update_status_in(&mut tx, user.id, user.row_version, status)
+        .await
+        .map_err(db_error)?;
+    if affected == 0 {
+        return Err(AdminUserError::NotFound);
+    }
+    let audited = audit::record_tx(&mut tx, Some(identity), user_audit_entry(action, user.id))
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
+    Ok(())
+}
+
+pub(crate) fn user_audit_entry(action: &str, user_id: Uuid) -> AuditEntry {
+    AuditEntry::new("users", action, "ok").target(user_id)
+}
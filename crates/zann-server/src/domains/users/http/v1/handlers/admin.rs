@@ -13,9 +13,9 @@ use crate::domains::users::admin_service::{
 
 use super::super::types::{
     CreateUserRequest, ErrorResponse, ListUsersQuery, ResetPasswordRequest, ResetPasswordResponse,
-    UserListResponse,
+    SetSshUsernameRequest, SshUsernameResponse, UserListResponse,
 };
-use super::helpers::user_response;
+use super::helpers::{audit_user_failure, user_response};
 
 fn map_admin_error(error: AdminUserError) -> axum::response::Response {
     match error {
@@ -125,9 +125,13 @@ pub(crate) async fn create_user(
         password: payload.password,
         full_name: payload.full_name,
     };
+    let email = command.email.clone();
     match admin_service::create_user(&state, &identity, command).await {
         Ok(user) => (StatusCode::OK, Json(user_response(user))).into_response(),
-        Err(err) => map_admin_error(err),
+        Err(err) => {
+            audit_user_failure(&state, &identity, "create", &err, &email).await;
+            map_admin_error(err)
+        }
     }
 }
 
@@ -151,7 +155,10 @@ pub(crate) async fn delete_user(
 ) -> impl IntoResponse {
     match admin_service::delete_user(&state, &identity, &id, identity.device_id).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
-        Err(err) => map_admin_error(err),
+        Err(err) => {
+            audit_user_failure(&state, &identity, "delete", &err, &id).await;
+            map_admin_error(err)
+        }
     }
 }
 
@@ -163,7 +170,10 @@ pub(crate) async fn block_user(
 ) -> impl IntoResponse {
     match admin_service::block_user(&state, &identity, &id).await {
         Ok(user) => (StatusCode::OK, Json(user_response(user))).into_response(),
-        Err(err) => map_admin_error(err),
+        Err(err) => {
+            audit_user_failure(&state, &identity, "block", &err, &id).await;
+            map_admin_error(err)
+        }
     }
 }
 
@@ -175,7 +185,10 @@ pub(crate) async fn unblock_user(
 ) -> impl IntoResponse {
     match admin_service::unblock_user(&state, &identity, &id).await {
         Ok(user) => (StatusCode::OK, Json(user_response(user))).into_response(),
-        Err(err) => map_admin_error(err),
+        Err(err) => {
+            audit_user_failure(&state, &identity, "unblock", &err, &id).await;
+            map_admin_error(err)
+        }
     }
 }
 
@@ -187,7 +200,7 @@ pub(crate) async fn reset_password(
     Json(payload): Json<ResetPasswordRequest>,
 ) -> impl IntoResponse {
     let command = ResetPasswordCommand {
-        user_id: id,
+        user_id: id.clone(),
         password: payload.password,
     };
     match admin_service::reset_password(&state, &identity, command).await {
@@ -198,6 +211,29 @@ pub(crate) async fn reset_password(
             }),
         )
             .into_response(),
-        Err(err) => map_admin_error(err),
+        Err(err) => {
+            audit_user_failure(&state, &identity, "reset_password", &err, &id).await;
+            map_admin_error(err)
+        }
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+pub(crate) async fn set_ssh_username(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+    Json(payload): Json<SetSshUsernameRequest>,
+) -> impl IntoResponse {
+    match admin_service::set_ssh_username(&state, &identity, &id, payload.ssh_username.as_deref())
+        .await
+    {
+        Ok(ssh_username) => {
+            (StatusCode::OK, Json(SshUsernameResponse { ssh_username })).into_response()
+        }
+        Err(err) => {
+            audit_user_failure(&state, &identity, "set_ssh_username", &err, &id).await;
+            map_admin_error(err)
+        }
     }
 }
@@ -1,5 +1,8 @@
-use zann_core::User;
+use zann_core::{Identity, User};
 
+use crate::app::AppState;
+use crate::domains::errors::ServiceError;
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::user_display::{avatar_initials_for_user, display_name_for_user};
 
 use super::super::types::UserResponse;
@@ -21,3 +24,16 @@ pub(crate) fn user_response(user: User) -> UserResponse {
         last_login_at: user.last_login_at.map(|value| value.to_rfc3339()),
     }
 }
+
+/// Records a rejected user change; successful ones are audited by the service,
+/// in the change's transaction.
+pub(crate) async fn audit_user_failure(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    err: &ServiceError,
+    target: &str,
+) {
+    let entry = AuditEntry::new("users", action, err.label()).target(target);
+    audit::record(state, Some(identity), entry).await;
+}
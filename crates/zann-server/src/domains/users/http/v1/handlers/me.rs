@@ -10,7 +10,7 @@ use crate::domains::users::service::{
 use super::super::types::{
     ChangePasswordRequest, ErrorResponse, RecoveryKitResponse, UpdateMeRequest,
 };
-use super::helpers::user_response;
+use super::helpers::{audit_user_failure, user_response};
 
 fn map_me_error(error: MeError) -> axum::response::Response {
     match error {
@@ -126,7 +126,11 @@ pub(crate) async fn change_password(
     };
     match change_password_service(&state, &identity, command).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
-        Err(err) => map_me_error(err),
+        Err(err) => {
+            let target = identity.user_id.to_string();
+            audit_user_failure(&state, &identity, "change_password", &err, &target).await;
+            map_me_error(err)
+        }
     }
 }
 
@@ -143,6 +147,10 @@ pub(crate) async fn create_recovery_kit(
             }),
         )
             .into_response(),
-        Err(err) => map_me_error(err),
+        Err(err) => {
+            let target = identity.user_id.to_string();
+            audit_user_failure(&state, &identity, "create_recovery_kit", &err, &target).await;
+            map_me_error(err)
+        }
     }
 }
@@ -3,6 +3,7 @@ mod helpers;
 mod me;
 
 pub(crate) use admin::{
-    block_user, create_user, delete_user, get_user, list_users, reset_password, unblock_user,
+    block_user, create_user, delete_user, get_user, list_users, reset_password, set_ssh_username,
+    unblock_user,
 };
 pub(crate) use me::{change_password, create_recovery_kit, me, update_me};
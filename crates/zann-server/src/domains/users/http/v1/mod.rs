@@ -1,5 +1,5 @@
 use axum::{
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 
@@ -30,4 +30,8 @@ pub fn router() -> Router<AppState> {
             "/v1/users/:id/reset-password",
             post(handlers::reset_password),
         )
+        .route(
+            "/v1/users/:id/ssh-username",
+            put(handlers::set_ssh_username),
+        )
 }
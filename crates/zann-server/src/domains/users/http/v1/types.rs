@@ -32,6 +32,18 @@ pub(crate) struct ResetPasswordRequest {
     pub(crate) password: Option<String>,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct SetSshUsernameRequest {
+    /// Principal for `{{username}}` in SSH signing roles; `null` clears it.
+    #[serde(default)]
+    pub(crate) ssh_username: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct SshUsernameResponse {
+    pub(crate) ssh_username: Option<String>,
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub(crate) struct UpdateMeRequest {
     #[serde(default)]
@@ -9,6 +9,8 @@ use crate::domains::auth::core::passwords::{
     derive_auth_hash, hash_password, kdf_params_from_user, verify_password,
 };
 use crate::domains::errors::ServiceError;
+use crate::domains::users::admin_service::user_audit_entry;
+use crate::infra::audit;
 use crate::infra::metrics;
 
 pub struct UpdateMeCommand {
@@ -211,16 +213,28 @@ pub async fn change_password(
             tracing::error!(event = "users_me_password_failed", "KDF error");
             return Err(MeError::Kdf);
         };
-    let Ok(affected) = user_repo
-        .update_password_hash(identity.user_id, user.row_version, Some(&password_hash))
-        .await
-    else {
-        tracing::error!(event = "users_me_password_failed", "DB error");
-        return Err(MeError::DbError);
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "users_me_password_failed", error = %err, "DB error");
+        MeError::DbError
     };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    let affected = UserRepo::update_password_hash_in(
+        &mut tx,
+        identity.user_id,
+        user.row_version,
+        Some(&password_hash),
+    )
+    .await
+    .map_err(db_error)?;
     if affected == 0 {
         return Err(MeError::NotFound);
     }
+    let entry = user_audit_entry("change_password", identity.user_id);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     tracing::info!(event = "users_me_password_changed", "Password changed");
     Ok(())
@@ -300,16 +314,28 @@ pub async fn create_recovery_kit(
             return Err(MeError::Kdf);
         };
 
-    let Ok(affected) = user_repo
-        .update_recovery_key_hash(identity.user_id, user.row_version, &recovery_key_hash)
-        .await
-    else {
-        tracing::error!(event = "users_me_recovery_failed", "DB error");
-        return Err(MeError::DbError);
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "users_me_recovery_failed", error = %err, "DB error");
+        MeError::DbError
     };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    let affected = UserRepo::update_recovery_key_hash_in(
+        &mut tx,
+        identity.user_id,
+        user.row_version,
+        &recovery_key_hash,
+    )
+    .await
+    .map_err(db_error)?;
     if affected == 0 {
         return Err(MeError::NotFound);
     }
+    let entry = user_audit_entry("create_recovery_kit", identity.user_id);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     tracing::info!(event = "users_me_recovery_created", "Recovery kit created");
     Ok(RecoveryKitResult { recovery_key })
@@ -12,10 +12,12 @@ use crate::app::AppState;
 use crate::domains::vaults::service::{
     self, CreateVaultCommand, ListVaultsCommand, UpdateVaultKeyCommand, VaultServiceError,
 };
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::metrics;
 
 mod vaults_service_account;
 use vaults_service_account::list_service_account_vaults;
+pub(crate) mod rotation;
 pub(crate) mod shared;
 
 #[derive(Serialize, JsonSchema)]
@@ -72,6 +74,7 @@ pub fn router() -> Router<AppState> {
         .route("/v1/vaults/:vault_id", get(get_vault).delete(delete_vault))
         .route("/v1/vaults/:vault_id/key", put(update_vault_key))
         .merge(shared::router())
+        .merge(rotation::router())
 }
 
 #[tracing::instrument(skip(state, identity, query))]
@@ -201,9 +204,14 @@ async fn create_vault(
         vault_key_enc: payload.vault_key_enc,
         tags: payload.tags,
     };
+    let slug = command.slug.clone();
     match service::create_vault(&state, &identity, command).await {
         Ok(vault) => (StatusCode::CREATED, Json(vault_response(vault))).into_response(),
-        Err(err) => map_vault_error(err),
+        Err(err) => {
+            let entry = AuditEntry::new("vaults", "create", err.label()).target(slug);
+            audit::record(&state, Some(&identity), entry).await;
+            map_vault_error(err)
+        }
     }
 }
 
@@ -227,12 +235,15 @@ async fn update_vault_key(
     Json(payload): Json<UpdateVaultKeyRequest>,
 ) -> impl IntoResponse {
     let command = UpdateVaultKeyCommand {
-        vault_id,
+        vault_id: vault_id.clone(),
         vault_key_enc: payload.vault_key_enc,
     };
     match service::update_vault_key(&state, &identity, command).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
-        Err(err) => map_vault_error(err),
+        Err(err) => {
+            audit_vault_failure(&state, &identity, "update_key", &err, &vault_id).await;
+            map_vault_error(err)
+        }
     }
 }
 
@@ -244,8 +255,26 @@ async fn delete_vault(
 ) -> impl IntoResponse {
     match service::delete_vault(&state, &identity, &vault_id).await {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
-        Err(err) => map_vault_error(err),
+        Err(err) => {
+            audit_vault_failure(&state, &identity, "delete", &err, &vault_id).await;
+            map_vault_error(err)
+        }
+    }
+}
+
+/// Successful vault writes are audited by the service, in their transaction.
+async fn audit_vault_failure(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    err: &VaultServiceError,
+    vault_ref: &str,
+) {
+    let mut entry = AuditEntry::new("vaults", action, err.label()).target(vault_ref);
+    if let Some(vault_id) = audit::resolve_vault_id(state, vault_ref).await {
+        entry = entry.vault(vault_id);
     }
+    audit::record(state, Some(identity), entry).await;
 }
 
 fn vault_response(vault: Vault) -> VaultResponse {
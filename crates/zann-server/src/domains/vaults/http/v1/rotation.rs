@@ -0,0 +1,285 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Extension, Json, Router,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zann_core::{Identity, ItemRotationHook, ItemRotationSchedule};
+
+use crate::app::AppState;
+use crate::domains::vaults::rotation::{
+    self, OverdueItem, RotationServiceError, SetScheduleCommand,
+};
+use crate::domains::vaults::rotation_hooks::{self, RotationHookConfig, SetHookCommand};
+
+use super::ErrorResponse;
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct SetRotationScheduleRequest {
+    pub(crate) schedule: String,
+    #[serde(default)]
+    pub(crate) password_policy: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct RotationScheduleResponse {
+    pub(crate) item_id: String,
+    pub(crate) vault_id: String,
+    pub(crate) schedule: String,
+    pub(crate) max_age_seconds: i64,
+    pub(crate) password_policy: Option<String>,
+    pub(crate) last_rotated_at: String,
+    pub(crate) due_at: String,
+    pub(crate) last_started_at: Option<String>,
+    pub(crate) last_error: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct OverdueQuery {
+    #[serde(default)]
+    pub(crate) max_age: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct OverdueItemResponse {
+    pub(crate) item_id: String,
+    pub(crate) path: String,
+    pub(crate) name: String,
+    pub(crate) schedule: Option<String>,
+    pub(crate) max_age_seconds: i64,
+    pub(crate) last_rotated_at: String,
+    pub(crate) due_at: String,
+    pub(crate) overdue_seconds: i64,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct OverdueResponse {
+    pub(crate) vault_id: String,
+    pub(crate) items: Vec<OverdueItemResponse>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct SetRotationHookRequest {
+    #[serde(flatten)]
+    pub(crate) config: RotationHookConfig,
+    #[serde(default)]
+    pub(crate) verify: Option<bool>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct RotationHookResponse {
+    pub(crate) item_id: String,
+    pub(crate) kind: String,
+    pub(crate) verify: bool,
+    pub(crate) target: String,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/v1/shared/items/:item_id/rotation/schedule",
+            get(get_schedule).put(set_schedule).delete(delete_schedule),
+        )
+        .route(
+            "/v1/shared/items/:item_id/rotation/hook",
+            get(get_hook).put(set_hook).delete(delete_hook),
+        )
+        .route("/v1/vaults/:vault_id/rotation/overdue", get(list_overdue))
+}
+
+fn map_rotation_error(error: RotationServiceError) -> axum::response::Response {
+    match error {
+        RotationServiceError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        RotationServiceError::Forbidden(code) => {
+            (StatusCode::FORBIDDEN, Json(ErrorResponse { error: code })).into_response()
+        }
+        RotationServiceError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        RotationServiceError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        RotationServiceError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        RotationServiceError::DbError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response(),
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+fn schedule_response(schedule: ItemRotationSchedule) -> RotationScheduleResponse {
+    RotationScheduleResponse {
+        item_id: schedule.item_id.to_string(),
+        vault_id: schedule.vault_id.to_string(),
+        due_at: schedule.due_at().to_rfc3339(),
+        schedule: schedule.schedule,
+        max_age_seconds: schedule.max_age_seconds,
+        password_policy: schedule.password_policy,
+        last_rotated_at: schedule.last_rotated_at.to_rfc3339(),
+        last_started_at: schedule.last_started_at.map(|value| value.to_rfc3339()),
+        last_error: schedule.last_error,
+    }
+}
+
+fn hook_response(hook: ItemRotationHook, config: &RotationHookConfig) -> RotationHookResponse {
+    RotationHookResponse {
+        item_id: hook.item_id.to_string(),
+        kind: hook.kind,
+        verify: hook.verify,
+        target: config.describe(),
+        created_at: hook.created_at.to_rfc3339(),
+        updated_at: hook.updated_at.to_rfc3339(),
+    }
+}
+
+fn overdue_response(entry: OverdueItem, now: chrono::DateTime<chrono::Utc>) -> OverdueItemResponse {
+    OverdueItemResponse {
+        item_id: entry.item.id.to_string(),
+        path: entry.item.path,
+        name: entry.item.name,
+        schedule: entry.schedule.map(|schedule| schedule.schedule),
+        max_age_seconds: entry.max_age_seconds,
+        last_rotated_at: entry.last_rotated_at.to_rfc3339(),
+        due_at: entry.due_at.to_rfc3339(),
+        overdue_seconds: (now - entry.due_at).num_seconds(),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_schedule(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(item_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match rotation::get_schedule(&state, &identity, item_id).await {
+        Ok(schedule) => (StatusCode::OK, Json(schedule_response(schedule))).into_response(),
+        Err(error) => map_rotation_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn set_schedule(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<SetRotationScheduleRequest>,
+) -> impl IntoResponse {
+    let command = SetScheduleCommand {
+        schedule: payload.schedule,
+        password_policy: payload.password_policy,
+    };
+    match rotation::set_schedule(&state, &identity, item_id, command).await {
+        Ok(schedule) => {
+            tracing::info!(
+                event = "rotation_schedule_set",
+                item_id = %schedule.item_id,
+                max_age_seconds = schedule.max_age_seconds
+            );
+            (StatusCode::OK, Json(schedule_response(schedule))).into_response()
+        }
+        Err(error) => map_rotation_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn delete_schedule(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(item_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match rotation::delete_schedule(&state, &identity, item_id).await {
+        Ok(()) => {
+            tracing::info!(event = "rotation_schedule_deleted", item_id = %item_id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => map_rotation_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_hook(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(item_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match rotation_hooks::get_hook(&state, &identity, item_id).await {
+        Ok((hook, config)) => (StatusCode::OK, Json(hook_response(hook, &config))).into_response(),
+        Err(error) => map_rotation_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn set_hook(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(item_id): Path<Uuid>,
+    Json(payload): Json<SetRotationHookRequest>,
+) -> impl IntoResponse {
+    let command = SetHookCommand {
+        config: payload.config.clone(),
+        verify: payload.verify.unwrap_or(true),
+    };
+    match rotation_hooks::set_hook(&state, &identity, item_id, command).await {
+        Ok(hook) => {
+            tracing::info!(
+                event = "rotation_hook_set",
+                item_id = %hook.item_id,
+                kind = %hook.kind
+            );
+            (StatusCode::OK, Json(hook_response(hook, &payload.config))).into_response()
+        }
+        Err(error) => map_rotation_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn delete_hook(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(item_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match rotation_hooks::delete_hook(&state, &identity, item_id).await {
+        Ok(()) => {
+            tracing::info!(event = "rotation_hook_deleted", item_id = %item_id);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => map_rotation_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, query))]
+async fn list_overdue(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    Query(query): Query<OverdueQuery>,
+) -> impl IntoResponse {
+    match rotation::overdue_report(&state, &identity, &vault_id, query.max_age.as_deref()).await {
+        Ok((vault, items)) => {
+            let now = chrono::Utc::now();
+            let body = OverdueResponse {
+                vault_id: vault.id.to_string(),
+                items: items
+                    .into_iter()
+                    .map(|entry| overdue_response(entry, now))
+                    .collect(),
+            };
+            (StatusCode::OK, Json(body)).into_response()
+        }
+        Err(error) => map_rotation_error(error),
+    }
+}
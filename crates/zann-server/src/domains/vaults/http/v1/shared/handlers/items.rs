@@ -9,16 +9,17 @@ use sqlx_core::types::Json as SqlxJson;
 use uuid::Uuid;
 use zann_core::{Change, ChangeOp, ChangeType, Identity, Item, ItemHistory, SyncStatus};
 use zann_crypto::vault_crypto as core_crypto;
-use zann_db::repo::{ChangeRepo, ItemHistoryRepo, ItemRepo, VaultRepo};
+use zann_db::repo::{ItemHistoryRepo, ItemRepo, VaultRepo};
 
 use crate::app::AppState;
 use crate::domains::access_control::http::{find_vault, vault_role_allows, VaultScope};
-use crate::domains::items::service::{basename_from_path, ITEM_HISTORY_LIMIT};
+use crate::domains::items::expiry;
+use crate::domains::items::service::{basename_from_path, item_audit_entry, write_item_change};
 use crate::infra::metrics;
 
 use super::super::helpers::{
-    actor_snapshot, cursor_allows, effective_device_id, encode_cursor, evaluate_history_policy,
-    is_shared_server_vault, normalize_path, parse_cursor, prefix_match,
+    actor_snapshot, audit_shared_item, cursor_allows, effective_device_id, encode_cursor,
+    evaluate_history_policy, is_shared_server_vault, normalize_path, parse_cursor, prefix_match,
     service_account_allows_path, service_account_allows_prefix,
 };
 use super::super::types::{
@@ -115,10 +116,18 @@ pub(crate) async fn list_shared_items(
 
     let limit = query.limit.unwrap_or(100).clamp(1, 500) as usize;
     let cursor = query.cursor.as_deref().and_then(parse_cursor);
+    let expiring_cutoff =
+        match expiry::expiring_cutoff(query.expiring_within.as_deref(), Utc::now()) {
+            Ok(cutoff) => cutoff,
+            Err(error) => {
+                return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+            }
+        };
 
     let mut filtered = items
         .into_iter()
         .filter(|item| prefix_match(prefix.as_deref(), &item.path))
+        .filter(|item| expiry::expires_by(item, expiring_cutoff))
         .collect::<Vec<_>>();
     filtered.sort_by(|a, b| {
         b.updated_at
@@ -138,8 +147,8 @@ pub(crate) async fn list_shared_items(
         page.push(item);
     }
 
-    let smk = match state.server_master_key.as_ref() {
-        Some(value) => value.as_ref(),
+    let smk = match state.server_master_key.get() {
+        Some(value) => value,
         None => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -150,7 +159,7 @@ pub(crate) async fn list_shared_items(
                 .into_response();
         }
     };
-    let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+    let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
         Ok(key) => key,
         Err(_) => {
             return (
@@ -207,6 +216,7 @@ pub(crate) async fn list_shared_items(
             version: item.version,
             deleted_at: item.deleted_at.map(|dt| dt.to_rfc3339()),
             updated_at: item.updated_at.to_rfc3339(),
+            expires_at: item.expires_at.map(|dt| dt.to_rfc3339()),
         });
     }
 
@@ -292,8 +302,8 @@ pub(crate) async fn get_shared_item(
         }
     }
 
-    let smk = match state.server_master_key.as_ref() {
-        Some(value) => value.as_ref(),
+    let smk = match state.server_master_key.get() {
+        Some(value) => value,
         None => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -304,7 +314,7 @@ pub(crate) async fn get_shared_item(
                 .into_response();
         }
     };
-    let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+    let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
         Ok(key) => key,
         Err(_) => {
             return (
@@ -346,6 +356,15 @@ pub(crate) async fn get_shared_item(
         }
     };
 
+    audit_shared_item(
+        &state,
+        &identity,
+        "read",
+        item.vault_id,
+        item.id,
+        &item.path,
+    )
+    .await;
     (
         StatusCode::OK,
         Json(SharedItemResponse {
@@ -361,6 +380,7 @@ pub(crate) async fn get_shared_item(
             version: item.version,
             deleted_at: item.deleted_at.map(|dt| dt.to_rfc3339()),
             updated_at: item.updated_at.to_rfc3339(),
+            expires_at: item.expires_at.map(|dt| dt.to_rfc3339()),
         }),
     )
         .into_response()
@@ -577,8 +597,8 @@ pub(crate) async fn get_shared_version(
         }
     };
 
-    let smk = match state.server_master_key.as_ref() {
-        Some(value) => value.as_ref(),
+    let smk = match state.server_master_key.get() {
+        Some(value) => value,
         None => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -589,7 +609,7 @@ pub(crate) async fn get_shared_version(
                 .into_response();
         }
     };
-    let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+    let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
         Ok(key) => key,
         Err(_) => {
             return (
@@ -686,6 +706,12 @@ pub(crate) async fn create_shared_item(
         )
             .into_response();
     }
+    let expires_at = match expiry::parse_optional_expires_at(req.expires_at.as_deref()) {
+        Ok(expires_at) => expires_at,
+        Err(error) => {
+            return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+        }
+    };
 
     // Authorization
     if let Some(service_account_id) = identity.service_account_id {
@@ -747,8 +773,8 @@ pub(crate) async fn create_shared_item(
                 .into_response();
         }
     };
-    let smk = match state.server_master_key.as_ref() {
-        Some(value) => value.as_ref(),
+    let smk = match state.server_master_key.get() {
+        Some(value) => value,
         None => {
             tracing::error!(event = "shared_item_create_failed", "SMK not configured");
             return (
@@ -760,7 +786,7 @@ pub(crate) async fn create_shared_item(
                 .into_response();
         }
     };
-    let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+    let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
         Ok(key) => key,
         Err(err) => {
             tracing::error!(event = "shared_item_create_failed", error = %err, "Key decrypt failed");
@@ -843,22 +869,12 @@ pub(crate) async fn create_shared_item(
         deleted_at: None,
         deleted_by_user_id: None,
         deleted_by_device_id: None,
+        expires_at,
         created_at: now,
         updated_at: now,
     };
 
-    let item_repo = ItemRepo::new(&state.db);
-    if let Err(err) = item_repo.create(&item).await {
-        tracing::error!(event = "shared_item_create_failed", error = %err, "DB error");
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: "db_error" }),
-        )
-            .into_response();
-    }
-
     // History entry
-    let history_repo = ItemHistoryRepo::new(&state.db);
     let actor = actor_snapshot(&state, &identity, identity.device_id).await;
     let history = ItemHistory {
         id: Uuid::now_v7(),
@@ -875,12 +891,8 @@ pub(crate) async fn create_shared_item(
         changed_by_device_name: actor.device_name,
         created_at: now,
     };
-    if let Err(err) = history_repo.create(&history).await {
-        tracing::error!(event = "item_history_create_failed", error = %err, item_id = %item_id);
-    }
 
     // Change entry
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -890,8 +902,15 @@ pub(crate) async fn create_shared_item(
         device_id,
         created_at: now,
     };
-    if let Err(err) = change_repo.create(&change).await {
-        tracing::error!(event = "item_change_create_failed", error = %err, item_id = %item_id);
+    let entry = item_audit_entry("create", &vault, &item);
+    let write = write_item_change(&state, &identity, &item, Some(&history), &change, entry).await;
+    if let Err(err) = write {
+        tracing::error!(event = "shared_item_create_failed", error = %err, "DB error");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response();
     }
 
     tracing::info!(event = "shared_item_created", item_id = %item_id, path = %path);
@@ -910,6 +929,7 @@ pub(crate) async fn create_shared_item(
             version: 1,
             deleted_at: None,
             updated_at: now.to_rfc3339(),
+            expires_at: expires_at.map(|dt| dt.to_rfc3339()),
         }),
     )
         .into_response()
@@ -1055,6 +1075,17 @@ pub(crate) async fn update_shared_item(
         item.favorite = favorite;
     }
 
+    // Update expiry
+    match expiry::updated_expires_at(
+        req.expires_at.as_ref().map(Option::as_deref),
+        item.expires_at,
+    ) {
+        Ok(expires_at) => item.expires_at = expires_at,
+        Err(error) => {
+            return (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })).into_response();
+        }
+    }
+
     // Encrypt new payload
     let payload_bytes = match serde_json::to_vec(&req.payload) {
         Ok(bytes) => bytes,
@@ -1068,8 +1099,8 @@ pub(crate) async fn update_shared_item(
                 .into_response();
         }
     };
-    let smk = match state.server_master_key.as_ref() {
-        Some(value) => value.as_ref(),
+    let smk = match state.server_master_key.get() {
+        Some(value) => value,
         None => {
             tracing::error!(event = "shared_item_update_failed", "SMK not configured");
             return (
@@ -1081,7 +1112,7 @@ pub(crate) async fn update_shared_item(
                 .into_response();
         }
     };
-    let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+    let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
         Ok(key) => key,
         Err(err) => {
             tracing::error!(event = "shared_item_update_failed", error = %err, "Key decrypt failed");
@@ -1118,10 +1149,9 @@ pub(crate) async fn update_shared_item(
     let payload_changed = item.checksum != previous_checksum;
 
     // History entry for previous version
-    if payload_changed {
-        let history_repo = ItemHistoryRepo::new(&state.db);
+    let history = if payload_changed {
         let actor = actor_snapshot(&state, &identity, identity.device_id).await;
-        let history = ItemHistory {
+        Some(ItemHistory {
             id: Uuid::now_v7(),
             item_id: item.id,
             payload_enc: previous_payload_enc,
@@ -1135,17 +1165,10 @@ pub(crate) async fn update_shared_item(
             changed_by_device_id: identity.device_id,
             changed_by_device_name: actor.device_name,
             created_at: Utc::now(),
-        };
-        if let Err(err) = history_repo.create(&history).await {
-            tracing::error!(event = "item_history_create_failed", error = %err, item_id = %item.id);
-        }
-        if let Err(err) = history_repo
-            .prune_by_item(item.id, ITEM_HISTORY_LIMIT)
-            .await
-        {
-            tracing::error!(event = "item_history_prune_failed", error = %err, item_id = %item.id);
-        }
-    }
+        })
+    } else {
+        None
+    };
 
     let device_id = match effective_device_id(&state, &identity).await {
         Ok(device_id) => device_id,
@@ -1171,26 +1194,7 @@ pub(crate) async fn update_shared_item(
     item.device_id = device_id;
     item.updated_at = Utc::now();
 
-    let Ok(affected) = item_repo.update(&item).await else {
-        tracing::error!(event = "shared_item_update_failed", "DB error");
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: "db_error" }),
-        )
-            .into_response();
-    };
-    if affected == 0 {
-        return (
-            StatusCode::CONFLICT,
-            Json(ErrorResponse {
-                error: "version_conflict",
-            }),
-        )
-            .into_response();
-    }
-
     // Change entry
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -1200,8 +1204,27 @@ pub(crate) async fn update_shared_item(
         device_id,
         created_at: item.updated_at,
     };
-    if let Err(err) = change_repo.create(&change).await {
-        tracing::error!(event = "item_change_create_failed", error = %err, item_id = %item.id);
+    let entry = item_audit_entry("update", &vault, &item);
+    let write = write_item_change(&state, &identity, &item, history.as_ref(), &change, entry).await;
+    match write {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse {
+                    error: "version_conflict",
+                }),
+            )
+                .into_response();
+        }
+        Err(err) => {
+            tracing::error!(event = "shared_item_update_failed", error = %err, "DB error");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: "db_error" }),
+            )
+                .into_response();
+        }
     }
 
     tracing::info!(event = "shared_item_updated", item_id = %item.id, path = %item.path);
@@ -1252,6 +1275,7 @@ pub(crate) async fn update_shared_item(
             version: item.version,
             deleted_at: item.deleted_at.map(|dt| dt.to_rfc3339()),
             updated_at: item.updated_at.to_rfc3339(),
+            expires_at: item.expires_at.map(|dt| dt.to_rfc3339()),
         }),
     )
         .into_response()
@@ -1347,7 +1371,6 @@ pub(crate) async fn delete_shared_item(
     let now = Utc::now();
 
     // History entry
-    let history_repo = ItemHistoryRepo::new(&state.db);
     let actor = actor_snapshot(&state, &identity, identity.device_id).await;
     let history = ItemHistory {
         id: Uuid::now_v7(),
@@ -1364,9 +1387,6 @@ pub(crate) async fn delete_shared_item(
         changed_by_device_name: actor.device_name,
         created_at: now,
     };
-    if let Err(err) = history_repo.create(&history).await {
-        tracing::error!(event = "item_history_create_failed", error = %err, item_id = %item.id);
-    }
 
     // Soft delete
     item.sync_status = SyncStatus::Tombstone;
@@ -1377,26 +1397,7 @@ pub(crate) async fn delete_shared_item(
     item.device_id = device_id;
     item.updated_at = now;
 
-    let Ok(affected) = item_repo.update(&item).await else {
-        tracing::error!(event = "shared_item_delete_failed", "DB error");
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse { error: "db_error" }),
-        )
-            .into_response();
-    };
-    if affected == 0 {
-        return (
-            StatusCode::CONFLICT,
-            Json(ErrorResponse {
-                error: "version_conflict",
-            }),
-        )
-            .into_response();
-    }
-
     // Change entry
-    let change_repo = ChangeRepo::new(&state.db);
     let change = Change {
         seq: 0,
         vault_id: vault.id,
@@ -1406,8 +1407,27 @@ pub(crate) async fn delete_shared_item(
         device_id,
         created_at: now,
     };
-    if let Err(err) = change_repo.create(&change).await {
-        tracing::error!(event = "item_change_create_failed", error = %err, item_id = %item.id);
+    let entry = item_audit_entry("delete", &vault, &item);
+    let write = write_item_change(&state, &identity, &item, Some(&history), &change, entry).await;
+    match write {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse {
+                    error: "version_conflict",
+                }),
+            )
+                .into_response();
+        }
+        Err(err) => {
+            tracing::error!(event = "shared_item_delete_failed", error = %err, "DB error");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: "db_error" }),
+            )
+                .into_response();
+        }
     }
 
     tracing::info!(event = "shared_item_deleted", item_id = %item.id, path = %item.path);
@@ -6,14 +6,20 @@ use sqlx_postgres::Postgres;
 use uuid::Uuid;
 use zann_core::{FieldKind, Identity};
 use zann_crypto::vault_crypto as core_crypto;
-use zann_db::repo::{ItemHistoryRepo, ItemRepo, VaultRepo};
+use zann_db::repo::{ItemHistoryRepo, ItemRepo, ItemRotationScheduleRepo, VaultRepo};
 
 use crate::app::AppState;
 use crate::domains::access_control::http::{vault_role_allows, VaultScope};
+use crate::domains::secrets::leases;
+use crate::domains::vaults::rotation_hooks::{self, RotationTarget};
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::metrics;
 
-use super::super::helpers::{actor_snapshot, decrypt_rotation_candidate, is_shared_server_vault};
-use super::super::types::{ErrorResponse, RotationCommitResponse};
+use super::super::helpers::{
+    abort_rotation, actor_snapshot, audit_shared_item_tx, decrypt_rotation_candidate,
+    is_shared_server_vault, reopen_unverified_rotation,
+};
+use super::super::types::{ErrorResponse, RotateCommitRequest, RotationCommitResponse};
 use super::super::{ROTATION_STATE_ROTATING, ROTATION_STATE_STALE};
 
 async fn rollback(conn: &mut PoolConnection<Postgres>) {
@@ -33,7 +39,9 @@ pub(crate) async fn rotate_commit(
     State(state): State<AppState>,
     Extension(identity): Extension<Identity>,
     axum::extract::Path(item_id): axum::extract::Path<Uuid>,
+    payload: Option<Json<RotateCommitRequest>>,
 ) -> impl IntoResponse {
+    let Json(payload) = payload.unwrap_or_default();
     let resource = "shared/items/rotate/commit";
     let policies = state.policy_store.get();
 
@@ -97,8 +105,31 @@ pub(crate) async fn rotate_commit(
         }
     }
 
-    let smk = match state.server_master_key.as_ref() {
-        Some(value) => value.as_ref(),
+    if let Some(wait_seconds) = payload.wait_for_leases_seconds {
+        match leases::wait_for_release(&state, item.id, wait_seconds).await {
+            Ok(0) => {}
+            Ok(_) => {
+                return (
+                    StatusCode::CONFLICT,
+                    Json(ErrorResponse {
+                        error: "leases_outstanding",
+                    }),
+                )
+                    .into_response();
+            }
+            Err(err) => {
+                tracing::error!(event = "rotation_commit_failed", error = %err, "DB error");
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse { error: "db_error" }),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let smk = match state.server_master_key.get() {
+        Some(value) => value,
         None => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -214,7 +245,7 @@ pub(crate) async fn rotate_commit(
             .into_response();
     };
 
-    let candidate = match decrypt_rotation_candidate(smk, &vault, item.id, &candidate_enc) {
+    let candidate = match decrypt_rotation_candidate(&smk, &vault, item.id, &candidate_enc) {
         Ok(value) => value,
         Err(_) => {
             rollback(&mut conn).await;
@@ -288,7 +319,7 @@ pub(crate) async fn rotate_commit(
                 .into_response();
         }
     };
-    let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+    let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
         Ok(key) => key,
         Err(_) => {
             rollback(&mut conn).await;
@@ -335,6 +366,55 @@ pub(crate) async fn rotate_commit(
             .into_response();
     }
 
+    let hook = match rotation_hooks::load_hook(&state, &vault_key, item.id).await {
+        Ok(hook) => hook,
+        Err(error) => {
+            rollback(&mut conn).await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error }),
+            )
+                .into_response();
+        }
+    };
+    let target = RotationTarget {
+        vault_id: vault.id,
+        item_id: item.id,
+        path: item.path.clone(),
+    };
+    if let Some(hook) = hook.as_ref() {
+        let kind = hook.executor.kind();
+        if let Err(err) = hook.executor.apply(&target, &candidate).await {
+            rollback(&mut conn).await;
+            metrics::rotation_hook(kind, "apply", "error");
+            tracing::warn!(
+                event = "rotation_hook_apply_failed",
+                item_id = %item.id,
+                kind,
+                error = %err,
+                "Rotation hook failed; aborting rotation"
+            );
+            let reason = format!("hook_apply_failed: {kind}");
+            if let Err(err) = abort_rotation(&state, item.id, Some(&reason)).await {
+                tracing::error!(event = "rotation_commit_failed", error = %err, "DB error");
+            }
+            let entry = AuditEntry::new("items", "rotate_abort", "error")
+                .vault(item.vault_id)
+                .path(&item.path)
+                .target(item.id)
+                .detail(Some(&reason));
+            audit::record(&state, Some(&identity), entry).await;
+            return (
+                StatusCode::BAD_GATEWAY,
+                Json(ErrorResponse {
+                    error: "rotation_hook_failed",
+                }),
+            )
+                .into_response();
+        }
+        metrics::rotation_hook(kind, "apply", "ok");
+    }
+
     let new_payload_enc =
         match core_crypto::encrypt_payload(&vault_key, vault.id, item.id, &payload) {
             Ok(value) => value,
@@ -443,6 +523,38 @@ pub(crate) async fn rotate_commit(
             .into_response();
     }
 
+    if let Err(err) = ItemRotationScheduleRepo::record_rotated_in(&mut conn, item.id, now).await {
+        rollback(&mut conn).await;
+        tracing::error!(event = "rotation_commit_failed", error = %err, "DB error");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response();
+    }
+
+    let audited = match audit_shared_item_tx(
+        &mut conn,
+        &identity,
+        "rotate_commit",
+        item.vault_id,
+        item.id,
+        &item.path,
+    )
+    .await
+    {
+        Ok(audited) => audited,
+        Err(err) => {
+            rollback(&mut conn).await;
+            tracing::error!(event = "rotation_commit_failed", error = %err, "DB error");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: "db_error" }),
+            )
+                .into_response();
+        }
+    };
+
     if let Err(err) = sqlx_core::query::query("COMMIT").execute(&mut *conn).await {
         tracing::error!(event = "rotation_commit_failed", error = %err, "DB error");
         return (
@@ -451,6 +563,7 @@ pub(crate) async fn rotate_commit(
         )
             .into_response();
     }
+    audited.dispatch();
 
     let history_repo = ItemHistoryRepo::new(&state.db);
     if let Err(err) = history_repo
@@ -464,9 +577,47 @@ pub(crate) async fn rotate_commit(
         );
     }
 
+    let mut status = "committed";
+    if let Some(hook) = hook.as_ref().filter(|hook| hook.verify) {
+        let kind = hook.executor.kind();
+        match hook.executor.verify(&target, &candidate).await {
+            Ok(()) => metrics::rotation_hook(kind, "verify", "ok"),
+            Err(err) => {
+                metrics::rotation_hook(kind, "verify", "error");
+                tracing::warn!(
+                    event = "rotation_hook_verify_failed",
+                    item_id = %item.id,
+                    kind,
+                    error = %err,
+                    "Rotation hook could not verify the new credential"
+                );
+                let reason = format!("hook_verify_failed: {kind}");
+                if let Err(err) = reopen_unverified_rotation(
+                    &state,
+                    item.id,
+                    &candidate_enc,
+                    identity.user_id,
+                    &reason,
+                )
+                .await
+                {
+                    tracing::error!(event = "rotation_commit_failed", error = %err, "DB error");
+                }
+                status = "verification_failed";
+            }
+        }
+    }
+
+    let outstanding_leases = leases::count_outstanding(&state, item.id)
+        .await
+        .unwrap_or_else(|err| {
+            tracing::warn!(event = "rotation_leases_count_failed", error = %err);
+            0
+        });
     let response = RotationCommitResponse {
-        status: "committed",
+        status,
         version: new_version,
+        outstanding_leases,
     };
     (StatusCode::OK, Json(response)).into_response()
 }
@@ -10,7 +10,8 @@ use crate::domains::access_control::http::{vault_role_allows, VaultScope};
 use crate::infra::metrics;
 
 use super::super::helpers::{
-    encrypt_rotation_candidate, fetch_rotation_row, generate_password, is_shared_server_vault,
+    audit_shared_item, encrypt_rotation_candidate, fetch_rotation_row, generate_password,
+    is_shared_server_vault,
 };
 use super::super::types::{ErrorResponse, RotateStartRequest, RotationCandidateResponse};
 use super::super::ROTATION_STATE_ROTATING;
@@ -84,8 +85,8 @@ pub(crate) async fn rotate_start(
         }
     }
 
-    let smk = match state.server_master_key.as_ref() {
-        Some(value) => value.as_ref(),
+    let smk = match state.server_master_key.get() {
+        Some(value) => value,
         None => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -127,7 +128,7 @@ pub(crate) async fn rotate_start(
             .into_response();
     }
 
-    let vault_key = match core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc) {
+    let vault_key = match core_crypto::decrypt_vault_key(&smk, vault.id, &vault.vault_key_enc) {
         Ok(key) => key,
         Err(_) => {
             return (
@@ -178,7 +179,7 @@ pub(crate) async fn rotate_start(
                 .into_response();
         }
     };
-    let candidate_enc = match encrypt_rotation_candidate(smk, &vault, item.id, &candidate) {
+    let candidate_enc = match encrypt_rotation_candidate(&smk, &vault, item.id, &candidate) {
         Ok(value) => value,
         Err(_) => {
             return (
@@ -245,5 +246,14 @@ pub(crate) async fn rotate_start(
         expires_at: Some(expires_at.to_rfc3339()),
         recover_until: Some(recover_until.to_rfc3339()),
     };
+    audit_shared_item(
+        &state,
+        &identity,
+        "rotate_start",
+        item.vault_id,
+        item.id,
+        &item.path,
+    )
+    .await;
     (StatusCode::OK, Json(response)).into_response()
 }
@@ -6,11 +6,12 @@ use zann_db::repo::{ItemRepo, VaultRepo};
 
 use crate::app::AppState;
 use crate::domains::access_control::http::{vault_role_allows, VaultScope};
+use crate::domains::secrets::leases;
 use crate::infra::metrics;
 
 use super::super::helpers::{
-    decrypt_rotation_candidate, fetch_rotation_row, is_shared_server_vault,
-    normalize_rotation_state, rotation_state_label,
+    abort_rotation, audit_shared_item, audit_shared_item_tx, decrypt_rotation_candidate,
+    fetch_rotation_row, is_shared_server_vault, normalize_rotation_state, rotation_state_label,
 };
 use super::super::types::{
     ErrorResponse, RotateAbortRequest, RotationCandidateResponse, RotationStatusResponse,
@@ -109,6 +110,18 @@ pub(crate) async fn rotate_status(
         }
     };
 
+    let active_leases = match leases::count_outstanding(&state, item.id).await {
+        Ok(count) => count,
+        Err(_) => {
+            tracing::error!(event = "rotation_status_failed", "DB error");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: "db_error" }),
+            )
+                .into_response();
+        }
+    };
+
     let response = RotationStatusResponse {
         state: rotation_state_label(&row.state),
         started_at: row.started_at.map(|value| value.to_rfc3339()),
@@ -116,6 +129,7 @@ pub(crate) async fn rotate_status(
         expires_at: row.expires_at.map(|value| value.to_rfc3339()),
         recover_until: row.recover_until.map(|value| value.to_rfc3339()),
         aborted_reason: row.aborted_reason,
+        active_leases: Some(active_leases),
     };
     (StatusCode::OK, Json(response)).into_response()
 }
@@ -243,8 +257,8 @@ pub(crate) async fn rotate_candidate(
         }
     };
 
-    let smk = match state.server_master_key.as_ref() {
-        Some(value) => value.as_ref(),
+    let smk = match state.server_master_key.get() {
+        Some(value) => value,
         None => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -255,7 +269,7 @@ pub(crate) async fn rotate_candidate(
                 .into_response();
         }
     };
-    let candidate = match decrypt_rotation_candidate(smk, &vault, item.id, &candidate_enc) {
+    let candidate = match decrypt_rotation_candidate(&smk, &vault, item.id, &candidate_enc) {
         Ok(value) => value,
         Err(_) => {
             return (
@@ -274,6 +288,15 @@ pub(crate) async fn rotate_candidate(
         expires_at: row.expires_at.map(|value| value.to_rfc3339()),
         recover_until: row.recover_until.map(|value| value.to_rfc3339()),
     };
+    audit_shared_item(
+        &state,
+        &identity,
+        "rotate_candidate",
+        item.vault_id,
+        item.id,
+        &item.path,
+    )
+    .await;
     (StatusCode::OK, Json(response)).into_response()
 }
 
@@ -411,8 +434,8 @@ pub(crate) async fn rotate_recover(
                 .into_response();
         }
     };
-    let smk = match state.server_master_key.as_ref() {
-        Some(value) => value.as_ref(),
+    let smk = match state.server_master_key.get() {
+        Some(value) => value,
         None => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -423,7 +446,7 @@ pub(crate) async fn rotate_recover(
                 .into_response();
         }
     };
-    let candidate = match decrypt_rotation_candidate(smk, &vault, item.id, &candidate_enc) {
+    let candidate = match decrypt_rotation_candidate(&smk, &vault, item.id, &candidate_enc) {
         Ok(value) => value,
         Err(_) => {
             return (
@@ -442,6 +465,33 @@ pub(crate) async fn rotate_recover(
         expires_at: row.expires_at.map(|value| value.to_rfc3339()),
         recover_until: row.recover_until.map(|value| value.to_rfc3339()),
     };
+    // The stale candidate is only handed out once its audit row is stored.
+    let audited = async {
+        let mut tx = state.db.begin().await?;
+        let audited = audit_shared_item_tx(
+            &mut tx,
+            &identity,
+            "rotate_recover",
+            item.vault_id,
+            item.id,
+            &item.path,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok::<_, sqlx_core::Error>(audited)
+    }
+    .await;
+    match audited {
+        Ok(audited) => audited.dispatch(),
+        Err(err) => {
+            tracing::error!(event = "rotation_recover_failed", error = %err, "DB error");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse { error: "db_error" }),
+            )
+                .into_response();
+        }
+    }
     (StatusCode::OK, Json(response)).into_response()
 }
 
@@ -545,24 +595,7 @@ pub(crate) async fn rotate_abort(
     }
 
     let reason = payload.reason.clone();
-    let result = sqlx_core::query::query(
-        r#"
-        UPDATE items
-        SET rotation_state = NULL,
-            rotation_candidate_enc = NULL,
-            rotation_started_at = NULL,
-            rotation_started_by = NULL,
-            rotation_expires_at = NULL,
-            rotation_recover_until = NULL,
-            rotation_aborted_reason = $2
-        WHERE id = $1
-        "#,
-    )
-    .bind(item.id)
-    .bind(reason.clone())
-    .execute(&state.db)
-    .await;
-    if let Err(err) = result {
+    if let Err(err) = abort_rotation(&state, item.id, reason.as_deref()).await {
         tracing::error!(event = "rotation_abort_failed", error = %err, "DB error");
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -578,6 +611,16 @@ pub(crate) async fn rotate_abort(
         expires_at: None,
         recover_until: None,
         aborted_reason: reason,
+        active_leases: None,
     };
+    audit_shared_item(
+        &state,
+        &identity,
+        "rotate_abort",
+        item.vault_id,
+        item.id,
+        &item.path,
+    )
+    .await;
     (StatusCode::OK, Json(response)).into_response()
 }
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use rand::seq::SliceRandom;
 use sqlx_core::row::Row;
+use sqlx_postgres::PgConnection;
 use uuid::Uuid;
 use zann_core::{Identity, Vault, VaultEncryptionType, VaultKind};
 use zann_crypto::crypto::SecretKey;
@@ -9,8 +10,11 @@ use zann_db::repo::{DeviceRepo, ServiceAccountRepo, UserRepo};
 
 use super::{ROTATION_STATE_ROTATING, ROTATION_STATE_STALE};
 use crate::app::AppState;
-use crate::domains::access_control::http::{parse_scope, ScopeRule, ScopeTarget};
+use crate::domains::access_control::http::{
+    parse_scope, scope_allows_action, ScopeRule, ScopeTarget,
+};
 use crate::domains::auth::helpers::build_device;
+use crate::infra::audit::{self, AuditEntry, PendingAudit};
 
 const SERVICE_ACCOUNT_DEVICE_NAME: &str = "Service Account";
 const SERVICE_ACCOUNT_DEVICE_FINGERPRINT: &str = "service-account";
@@ -126,6 +130,71 @@ pub(super) fn is_shared_server_vault(vault: &Vault) -> bool {
     vault.kind == VaultKind::Shared && vault.encryption_type == VaultEncryptionType::Server
 }
 
+/// Ends an in-flight rotation without committing it, keeping `reason` for `rotate/status`.
+pub(super) async fn abort_rotation(
+    state: &AppState,
+    item_id: Uuid,
+    reason: Option<&str>,
+) -> Result<(), sqlx_core::Error> {
+    sqlx_core::query::query(
+        r#"
+        UPDATE items
+        SET rotation_state = NULL,
+            rotation_candidate_enc = NULL,
+            rotation_started_at = NULL,
+            rotation_started_by = NULL,
+            rotation_expires_at = NULL,
+            rotation_recover_until = NULL,
+            rotation_aborted_reason = $2
+        WHERE id = $1
+        "#,
+    )
+    .bind(item_id)
+    .bind(reason)
+    .execute(&state.db)
+    .await
+    .map(|_| ())
+}
+
+/// Reopens a committed rotation as stale after its hook failed to verify the new
+/// credential: the candidate stays readable through `rotate/recover` until the recover
+/// window closes, and committing again re-runs the hook.
+pub(super) async fn reopen_unverified_rotation(
+    state: &AppState,
+    item_id: Uuid,
+    candidate_enc: &[u8],
+    started_by: Uuid,
+    reason: &str,
+) -> Result<(), sqlx_core::Error> {
+    let now = Utc::now();
+    let recover_until =
+        now + chrono::Duration::seconds(state.config.rotation.stale_retention_seconds);
+    sqlx_core::query::query(
+        r#"
+        UPDATE items
+        SET rotation_state = $2,
+            rotation_candidate_enc = $3,
+            rotation_started_at = $4,
+            rotation_started_by = $5,
+            rotation_expires_at = $4,
+            rotation_recover_until = $6,
+            rotation_aborted_reason = $7
+        WHERE id = $1
+          AND rotation_state IS NULL
+        "#,
+    )
+    .bind(item_id)
+    .bind(ROTATION_STATE_STALE)
+    .bind(candidate_enc)
+    .bind(now)
+    .bind(started_by)
+    .bind(recover_until)
+    .bind(reason)
+    .execute(&state.db)
+    .await
+    .map(|_| ())
+}
+
 pub(super) fn encrypt_rotation_candidate(
     smk: &SecretKey,
     vault: &Vault,
@@ -293,20 +362,6 @@ async fn ensure_service_account_device(
     Ok(device.id)
 }
 
-pub(super) fn scope_allows_action(permission: &str, action: &str) -> bool {
-    match action {
-        "read" | "list" => permission == "read",
-        "read_history" => {
-            matches!(
-                permission,
-                "history_read" | "read_history" | "read_previous"
-            )
-        }
-        "read_previous" => permission == "read_previous",
-        _ => permission == action,
-    }
-}
-
 pub(super) fn evaluate_history_policy(
     policies: &crate::domains::access_control::policies::PolicySet,
     identity: &Identity,
@@ -431,3 +486,36 @@ pub(super) async fn service_account_allows_prefix(
         .iter()
         .any(|rule| scope_matches_prefix(rule, vault, prefix))
 }
+
+fn shared_item_audit_entry(action: &str, vault_id: Uuid, item_id: Uuid, path: &str) -> AuditEntry {
+    AuditEntry::new("items", action, "ok")
+        .vault(vault_id)
+        .path(path)
+        .target(item_id)
+}
+
+pub(super) async fn audit_shared_item(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    vault_id: Uuid,
+    item_id: Uuid,
+    path: &str,
+) {
+    let entry = shared_item_audit_entry(action, vault_id, item_id, path);
+    audit::record(state, Some(identity), entry).await;
+}
+
+/// Like [`audit_shared_item`], but written in the caller's transaction; the
+/// returned event is dispatched once that transaction commits.
+pub(super) async fn audit_shared_item_tx(
+    conn: &mut PgConnection,
+    identity: &Identity,
+    action: &str,
+    vault_id: Uuid,
+    item_id: Uuid,
+    path: &str,
+) -> Result<PendingAudit, sqlx_core::Error> {
+    let entry = shared_item_audit_entry(action, vault_id, item_id, path);
+    audit::record_tx(conn, Some(identity), entry).await
+}
@@ -4,13 +4,13 @@ use axum::{
 };
 
 use crate::app::AppState;
+use crate::domains::vaults::rotation::ROTATION_STATE_ROTATING;
 
 mod handlers;
 mod helpers;
 pub(crate) mod types;
 
 const HISTORY_LIMIT: i64 = 5;
-const ROTATION_STATE_ROTATING: &str = "rotating";
 const ROTATION_STATE_STALE: &str = "stale";
 
 pub fn router() -> Router<AppState> {
@@ -28,6 +28,7 @@ pub(crate) struct SharedItemResponse {
     pub(crate) version: i64,
     pub(crate) deleted_at: Option<String>,
     pub(crate) updated_at: String,
+    pub(crate) expires_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -39,6 +40,9 @@ pub(crate) struct SharedItemsQuery {
     pub(crate) limit: Option<i64>,
     #[serde(default)]
     pub(crate) cursor: Option<String>,
+    /// Only items expiring within this window (e.g. `30d`), expired ones included.
+    #[serde(default)]
+    pub(crate) expiring_within: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -80,6 +84,9 @@ pub(crate) struct CreateSharedItemRequest {
     #[serde(default)]
     pub(crate) favorite: Option<bool>,
     pub(crate) payload: JsonValue,
+    /// `YYYY-MM-DD` or RFC 3339.
+    #[serde(default)]
+    pub(crate) expires_at: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -93,6 +100,12 @@ pub(crate) struct UpdateSharedItemRequest {
     #[serde(default)]
     pub(crate) favorite: Option<bool>,
     pub(crate) payload: JsonValue,
+    /// Omit to keep the current date, `null` to clear it.
+    #[serde(
+        default,
+        deserialize_with = "crate::domains::items::expiry::deserialize_some"
+    )]
+    pub(crate) expires_at: Option<Option<String>>,
 }
 
 #[derive(Deserialize, JsonSchema)]
@@ -101,6 +114,14 @@ pub(crate) struct RotateStartRequest {
     pub(crate) policy: Option<String>,
 }
 
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub(crate) struct RotateCommitRequest {
+    /// Wait up to this many seconds for outstanding leases to end before
+    /// committing; the commit is refused if any remain.
+    #[serde(default)]
+    pub(crate) wait_for_leases_seconds: Option<u64>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct RotateAbortRequest {
     #[serde(default)]
@@ -117,6 +138,9 @@ pub(crate) struct RotationStatusResponse {
     pub(crate) expires_at: Option<String>,
     pub(crate) recover_until: Option<String>,
     pub(crate) aborted_reason: Option<String>,
+    /// Unexpired secret leases on the item.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) active_leases: Option<i64>,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -131,4 +155,6 @@ pub(crate) struct RotationCandidateResponse {
 pub(crate) struct RotationCommitResponse {
     pub(crate) status: &'static str,
     pub(crate) version: i64,
+    /// Leases still held on the replaced value.
+    pub(crate) outstanding_leases: i64,
 }
@@ -1,2 +1,4 @@
 pub mod http;
+pub mod rotation;
+pub mod rotation_hooks;
 pub mod service;
@@ -0,0 +1,473 @@
+//! Rotation schedules for shared items. A schedule gives an item a maximum
+//! secret age; the scheduler starts a rotation (the same state the manual
+//! `rotate/start` flow produces) once that age is exceeded.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use uuid::Uuid;
+use zann_core::{FieldKind, Identity, Item, ItemRotationSchedule, Vault, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_crypto::vault_crypto as core_crypto;
+use zann_db::repo::{ItemRepo, ItemRotationScheduleRepo, VaultRepo};
+
+use crate::app::AppState;
+use crate::domains::access_control::http::{find_vault, vault_role_allows, VaultScope};
+use crate::domains::access_control::policies::PolicyDecision;
+use crate::domains::errors::ServiceError;
+use crate::domains::secrets::policies::generate_secret;
+use crate::domains::service_accounts::tokens::parse_ttl;
+use crate::infra::audit::{self, AuditEntry};
+use crate::infra::metrics;
+
+pub type RotationServiceError = ServiceError;
+
+pub(crate) const ROTATION_STATE_ROTATING: &str = "rotating";
+
+const MIN_MAX_AGE_SECONDS: i64 = 60 * 60;
+
+/// Parses a schedule into a maximum age in seconds. Accepts durations such as
+/// `90d` or `12h` and the aliases `@hourly`, `@daily`, `@weekly`, `@monthly`
+/// (30 days), `@quarterly` (90 days) and `@yearly` (365 days).
+pub fn parse_schedule(value: &str) -> Result<i64, &'static str> {
+    let value = value.trim().to_ascii_lowercase();
+    let seconds = match value.as_str() {
+        "@hourly" => 60 * 60,
+        "@daily" => 24 * 60 * 60,
+        "@weekly" => 7 * 24 * 60 * 60,
+        "@monthly" => 30 * 24 * 60 * 60,
+        "@quarterly" => 90 * 24 * 60 * 60,
+        "@yearly" | "@annually" => 365 * 24 * 60 * 60,
+        other => parse_ttl(other)
+            .map_err(|_| "invalid_schedule")?
+            .num_seconds(),
+    };
+    if seconds < MIN_MAX_AGE_SECONDS {
+        return Err("schedule_too_short");
+    }
+    Ok(seconds)
+}
+
+#[derive(Debug, Clone)]
+pub struct SetScheduleCommand {
+    pub schedule: String,
+    pub password_policy: Option<String>,
+}
+
+/// An item whose secret is older than its allowed age.
+#[derive(Debug, Clone)]
+pub struct OverdueItem {
+    pub item: Item,
+    pub schedule: Option<ItemRotationSchedule>,
+    pub max_age_seconds: i64,
+    pub last_rotated_at: DateTime<Utc>,
+    pub due_at: DateTime<Utc>,
+}
+
+pub async fn get_schedule(
+    state: &AppState,
+    identity: &Identity,
+    item_id: Uuid,
+) -> Result<ItemRotationSchedule, RotationServiceError> {
+    let (_, vault) = load_shared_item(state, item_id).await?;
+    authorize(
+        state,
+        identity,
+        &vault,
+        "read",
+        "shared/items/rotation/schedule",
+    )
+    .await?;
+    match ItemRotationScheduleRepo::new(&state.db)
+        .get_by_item(item_id)
+        .await
+    {
+        Ok(Some(schedule)) => Ok(schedule),
+        Ok(None) => Err(RotationServiceError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "rotation_schedule_get_failed", error = %err, "DB error");
+            Err(RotationServiceError::DbError)
+        }
+    }
+}
+
+pub async fn set_schedule(
+    state: &AppState,
+    identity: &Identity,
+    item_id: Uuid,
+    cmd: SetScheduleCommand,
+) -> Result<ItemRotationSchedule, RotationServiceError> {
+    if identity.service_account_id.is_some() {
+        return Err(RotationServiceError::ForbiddenNoBody);
+    }
+    let (item, vault) = load_shared_item(state, item_id).await?;
+    authorize(
+        state,
+        identity,
+        &vault,
+        "rotate_start",
+        "shared/items/rotation/schedule",
+    )
+    .await?;
+
+    let max_age_seconds =
+        parse_schedule(&cmd.schedule).map_err(RotationServiceError::BadRequest)?;
+    let password_policy = cmd
+        .password_policy
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+    if let Some(name) = password_policy.as_deref() {
+        let Some(policy) = state.secret_policies.get(name) else {
+            return Err(RotationServiceError::BadRequest("unknown_policy"));
+        };
+        policy
+            .validate()
+            .map_err(RotationServiceError::BadRequest)?;
+        // A password field holds a single value; the public half of a key
+        // pair would be lost.
+        if policy.is_key_pair() {
+            return Err(RotationServiceError::BadRequest("policy_kind_unsupported"));
+        }
+    }
+
+    let now = Utc::now();
+    let schedule = ItemRotationSchedule {
+        item_id: item.id,
+        vault_id: vault.id,
+        schedule: cmd.schedule.trim().to_string(),
+        max_age_seconds,
+        password_policy,
+        // A new schedule counts from the last change to the item.
+        last_rotated_at: item.updated_at,
+        last_started_at: None,
+        last_error: None,
+        created_at: now,
+        updated_at: now,
+    };
+    ItemRotationScheduleRepo::new(&state.db)
+        .upsert(&schedule)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "rotation_schedule_set_failed", error = %err, "DB error");
+            RotationServiceError::DbError
+        })
+}
+
+pub async fn delete_schedule(
+    state: &AppState,
+    identity: &Identity,
+    item_id: Uuid,
+) -> Result<(), RotationServiceError> {
+    if identity.service_account_id.is_some() {
+        return Err(RotationServiceError::ForbiddenNoBody);
+    }
+    let (_, vault) = load_shared_item(state, item_id).await?;
+    authorize(
+        state,
+        identity,
+        &vault,
+        "rotate_start",
+        "shared/items/rotation/schedule",
+    )
+    .await?;
+    match ItemRotationScheduleRepo::new(&state.db)
+        .delete_by_item(item_id)
+        .await
+    {
+        Ok(0) => Err(RotationServiceError::NotFound),
+        Ok(_) => Ok(()),
+        Err(err) => {
+            tracing::error!(event = "rotation_schedule_delete_failed", error = %err, "DB error");
+            Err(RotationServiceError::DbError)
+        }
+    }
+}
+
+/// Items in a shared vault whose secret is older than allowed. Scheduled items
+/// use their own max age; with `max_age` set, every live item is also checked
+/// against it (using its last update as the secret age when unscheduled).
+pub async fn overdue_report(
+    state: &AppState,
+    identity: &Identity,
+    vault_ref: &str,
+    max_age: Option<&str>,
+) -> Result<(Vault, Vec<OverdueItem>), RotationServiceError> {
+    let report_max_age = max_age
+        .map(|value| {
+            parse_ttl(value).map_err(|_| RotationServiceError::BadRequest("invalid_max_age"))
+        })
+        .transpose()?
+        .map(|value| value.num_seconds());
+    if report_max_age.is_some_and(|value| value <= 0) {
+        return Err(RotationServiceError::BadRequest("invalid_max_age"));
+    }
+
+    let vault_repo = VaultRepo::new(&state.db);
+    let vault = match find_vault(&vault_repo, vault_ref).await {
+        Ok(Some(vault)) if vault.kind == VaultKind::Shared => vault,
+        Ok(_) => return Err(RotationServiceError::NotFound),
+        Err(_) => {
+            tracing::error!(event = "rotation_overdue_failed", "DB error");
+            return Err(RotationServiceError::DbError);
+        }
+    };
+    authorize(
+        state,
+        identity,
+        &vault,
+        "list",
+        "shared/items/rotation/overdue",
+    )
+    .await?;
+
+    let items = ItemRepo::new(&state.db)
+        .list_by_vault(vault.id, false)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "rotation_overdue_failed", error = %err, "DB error");
+            RotationServiceError::DbError
+        })?;
+    let mut schedules = ItemRotationScheduleRepo::new(&state.db)
+        .list_by_vault(vault.id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "rotation_overdue_failed", error = %err, "DB error");
+            RotationServiceError::DbError
+        })?
+        .into_iter()
+        .map(|schedule| (schedule.item_id, schedule))
+        .collect::<std::collections::HashMap<_, _>>();
+
+    let now = Utc::now();
+    let mut overdue = Vec::new();
+    for item in items {
+        let schedule = schedules.remove(&item.id);
+        let last_rotated_at = schedule
+            .as_ref()
+            .map_or(item.updated_at, |schedule| schedule.last_rotated_at);
+        let max_age_seconds = match (report_max_age, schedule.as_ref()) {
+            (Some(report), Some(schedule)) => report.min(schedule.max_age_seconds),
+            (Some(report), None) => report,
+            (None, Some(schedule)) => schedule.max_age_seconds,
+            (None, None) => continue,
+        };
+        let due_at = last_rotated_at + ChronoDuration::seconds(max_age_seconds);
+        if due_at <= now {
+            overdue.push(OverdueItem {
+                item,
+                schedule,
+                max_age_seconds,
+                last_rotated_at,
+                due_at,
+            });
+        }
+    }
+    overdue.sort_by_key(|entry| entry.due_at);
+    Ok((vault, overdue))
+}
+
+pub(crate) async fn load_shared_item(
+    state: &AppState,
+    item_id: Uuid,
+) -> Result<(Item, Vault), RotationServiceError> {
+    let item = match ItemRepo::new(&state.db).get_by_id(item_id).await {
+        Ok(Some(item)) if item.deleted_at.is_none() => item,
+        Ok(_) => return Err(RotationServiceError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "rotation_item_lookup_failed", error = %err, "DB error");
+            return Err(RotationServiceError::DbError);
+        }
+    };
+    let vault = match VaultRepo::new(&state.db).get_by_id(item.vault_id).await {
+        Ok(Some(vault)) => vault,
+        Ok(None) => return Err(RotationServiceError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "rotation_item_lookup_failed", error = %err, "DB error");
+            return Err(RotationServiceError::DbError);
+        }
+    };
+    if vault.kind != VaultKind::Shared
+        || vault.encryption_type != zann_core::VaultEncryptionType::Server
+    {
+        return Err(RotationServiceError::NotFound);
+    }
+    Ok((item, vault))
+}
+
+pub(crate) async fn authorize(
+    state: &AppState,
+    identity: &Identity,
+    vault: &Vault,
+    action: &str,
+    resource: &str,
+) -> Result<(), RotationServiceError> {
+    let policies = state.policy_store.get();
+    let allowed = match policies.evaluate(identity, action, resource) {
+        PolicyDecision::Allow => true,
+        PolicyDecision::Deny => false,
+        PolicyDecision::NoMatch => {
+            vault_role_allows(state, identity, vault.id, action, VaultScope::Items)
+                .await
+                .map_err(|_| {
+                    tracing::error!(event = "rotation_access_failed", "DB error");
+                    RotationServiceError::DbError
+                })?
+        }
+    };
+    if !allowed {
+        metrics::forbidden_access(resource);
+        tracing::warn!(
+            event = "forbidden",
+            action = action,
+            resource = resource,
+            "Access denied"
+        );
+        return Err(RotationServiceError::ForbiddenNoBody);
+    }
+    Ok(())
+}
+
+/// One scheduler pass: refreshes the overdue gauge and starts rotations for
+/// due items. Does nothing while the master key is unavailable.
+pub async fn run_scheduled_rotations(state: &AppState) -> Result<usize, sqlx_core::Error> {
+    let repo = ItemRotationScheduleRepo::new(&state.db);
+    let now = Utc::now();
+    metrics::rotation_overdue(repo.count_overdue(now).await?);
+
+    let Some(smk) = state.server_master_key.get() else {
+        return Ok(0);
+    };
+    let retry_errors_before =
+        now - ChronoDuration::seconds(state.config.rotation.schedule_error_backoff_seconds);
+    let due = repo
+        .list_due(
+            now,
+            retry_errors_before,
+            state.config.rotation.schedule_batch_size.max(1),
+        )
+        .await?;
+    let mut started = 0;
+    for schedule in due {
+        match start_scheduled_rotation(state, &smk, &schedule).await {
+            Ok(()) => {
+                started += 1;
+                metrics::rotation_scheduled("ok");
+                repo.mark_started(schedule.item_id, Utc::now()).await?;
+            }
+            Err(code) => {
+                metrics::rotation_scheduled(code);
+                tracing::warn!(
+                    event = "scheduled_rotation_failed",
+                    item_id = %schedule.item_id,
+                    error = code
+                );
+                repo.mark_error(schedule.item_id, code, Utc::now()).await?;
+            }
+        }
+    }
+    Ok(started)
+}
+
+async fn start_scheduled_rotation(
+    state: &AppState,
+    smk: &SecretKey,
+    schedule: &ItemRotationSchedule,
+) -> Result<(), &'static str> {
+    let (item, vault) = load_shared_item(state, schedule.item_id)
+        .await
+        .map_err(|_| "item_missing")?;
+    let vault_key = core_crypto::decrypt_vault_key(smk, vault.id, &vault.vault_key_enc)
+        .map_err(|_| "decrypt_failed")?;
+    let payload = core_crypto::decrypt_payload(&vault_key, vault.id, item.id, &item.payload_enc)
+        .map_err(|_| "decrypt_failed")?;
+    if !payload
+        .fields
+        .values()
+        .any(|field| field.kind == FieldKind::Password)
+    {
+        return Err("password_field_missing");
+    }
+
+    let policy_name = schedule
+        .password_policy
+        .clone()
+        .unwrap_or_else(|| state.secret_default_policy.clone());
+    let policy = state
+        .secret_policies
+        .get(&policy_name)
+        .ok_or("unknown_policy")?;
+    if policy.is_key_pair() {
+        return Err("policy_kind_unsupported");
+    }
+    let candidate = generate_secret(policy)?.value;
+    let candidate_enc = core_crypto::encrypt_rotation_candidate(
+        &vault_key,
+        vault.id,
+        item.id,
+        candidate.as_bytes(),
+    )
+    .map_err(|_| "encrypt_failed")?;
+
+    let now = Utc::now();
+    let expires_at = now + ChronoDuration::seconds(state.config.rotation.lock_ttl_seconds);
+    let recover_until =
+        expires_at + ChronoDuration::seconds(state.config.rotation.stale_retention_seconds);
+    let result = sqlx_core::query::query(
+        r#"
+        UPDATE items
+        SET rotation_state = $1,
+            rotation_candidate_enc = $2,
+            rotation_started_at = $3,
+            rotation_started_by = NULL,
+            rotation_expires_at = $4,
+            rotation_recover_until = $5,
+            rotation_aborted_reason = NULL
+        WHERE id = $6
+          AND rotation_state IS NULL
+        "#,
+    )
+    .bind(ROTATION_STATE_ROTATING)
+    .bind(candidate_enc)
+    .bind(now)
+    .bind(expires_at)
+    .bind(recover_until)
+    .bind(item.id)
+    .execute(&state.db)
+    .await
+    .map_err(|_| "db_error")?;
+    if result.rows_affected() == 0 {
+        return Err("rotation_in_progress");
+    }
+
+    tracing::info!(
+        event = "scheduled_rotation_started",
+        item_id = %item.id,
+        vault_id = %vault.id
+    );
+    let entry = AuditEntry::new("items", "rotate_start", "ok")
+        .vault(vault.id)
+        .path(&item.path)
+        .target(item.id)
+        .detail(Some("scheduled"));
+    audit::record(state, None, entry).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_schedule_accepts_durations_and_aliases() {
+        assert_eq!(parse_schedule("90d"), Ok(90 * 24 * 60 * 60));
+        assert_eq!(parse_schedule(" 12H "), Ok(12 * 60 * 60));
+        assert_eq!(parse_schedule("@weekly"), Ok(7 * 24 * 60 * 60));
+        assert_eq!(parse_schedule("@Quarterly"), Ok(90 * 24 * 60 * 60));
+    }
+
+    #[test]
+    fn parse_schedule_rejects_invalid_and_short_values() {
+        assert_eq!(parse_schedule("soon"), Err("invalid_schedule"));
+        assert_eq!(parse_schedule("@fortnightly"), Err("invalid_schedule"));
+        assert_eq!(parse_schedule("30m"), Err("schedule_too_short"));
+        assert_eq!(parse_schedule("-5d"), Err("schedule_too_short"));
+    }
+}
@@ -0,0 +1,257 @@
+//! Rotation hooks apply a rotated credential to the system that uses it. The hook runs
+//! between candidate generation and commit; a failed apply aborts the rotation and a
+//! failed post-commit verification leaves the candidate in the recover window.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zann_core::{Identity, ItemRotationHook};
+use zann_crypto::crypto::SecretKey;
+use zann_crypto::vault_crypto as core_crypto;
+use zann_db::repo::ItemRotationHookRepo;
+
+use crate::app::AppState;
+use crate::domains::errors::ServiceError;
+use crate::domains::secrets::service::server_vault_key;
+use crate::domains::vaults::rotation::{authorize, load_shared_item};
+
+mod postgres;
+pub mod webhook;
+
+pub use postgres::{PostgresExecutor, PostgresHookConfig};
+pub use webhook::{WebhookExecutor, WebhookHookConfig};
+
+pub type RotationHookServiceError = ServiceError;
+
+pub type ExecutorFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// Item being rotated, as seen by an executor.
+#[derive(Debug, Clone)]
+pub struct RotationTarget {
+    pub vault_id: Uuid,
+    pub item_id: Uuid,
+    pub path: String,
+}
+
+/// Applies a new credential to an external system.
+pub trait RotationExecutor: Send + Sync {
+    /// Stable label used in logs, metrics and abort reasons.
+    fn kind(&self) -> &'static str;
+
+    /// Makes `password` the live credential on the target.
+    fn apply<'a>(&'a self, target: &'a RotationTarget, password: &'a str) -> ExecutorFuture<'a>;
+
+    /// Confirms the target accepts `password`.
+    fn verify<'a>(&'a self, target: &'a RotationTarget, password: &'a str) -> ExecutorFuture<'a>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RotationHookConfig {
+    Postgres(PostgresHookConfig),
+    Webhook(WebhookHookConfig),
+}
+
+impl RotationHookConfig {
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Postgres(_) => "postgres",
+            Self::Webhook(_) => "webhook",
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            Self::Postgres(config) => config.validate(),
+            Self::Webhook(config) => config.validate(),
+        }
+    }
+
+    /// Target description without credentials, for API responses.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Postgres(config) => config.describe(),
+            Self::Webhook(config) => config.url.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn executor(&self, timeout: Duration) -> Box<dyn RotationExecutor> {
+        match self {
+            Self::Postgres(config) => Box::new(PostgresExecutor::new(config.clone(), timeout)),
+            Self::Webhook(config) => Box::new(WebhookExecutor::new(config.clone(), timeout)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetHookCommand {
+    pub config: RotationHookConfig,
+    pub verify: bool,
+}
+
+pub struct LoadedHook {
+    pub executor: Box<dyn RotationExecutor>,
+    pub verify: bool,
+}
+
+pub async fn get_hook(
+    state: &AppState,
+    identity: &Identity,
+    item_id: Uuid,
+) -> Result<(ItemRotationHook, RotationHookConfig), RotationHookServiceError> {
+    let (_, vault) = load_shared_item(state, item_id).await?;
+    authorize(
+        state,
+        identity,
+        &vault,
+        "rotate_start",
+        "shared/items/rotation/hook",
+    )
+    .await?;
+    let hook = match ItemRotationHookRepo::new(&state.db)
+        .get_by_item(item_id)
+        .await
+    {
+        Ok(Some(hook)) => hook,
+        Ok(None) => return Err(RotationHookServiceError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "rotation_hook_get_failed", error = %err, "DB error");
+            return Err(RotationHookServiceError::DbError);
+        }
+    };
+    let vault_key = server_vault_key(state, &vault)?;
+    let config = decrypt_config(&vault_key, &hook)
+        .map_err(|_| RotationHookServiceError::Internal("decrypt_failed"))?;
+    Ok((hook, config))
+}
+
+pub async fn set_hook(
+    state: &AppState,
+    identity: &Identity,
+    item_id: Uuid,
+    cmd: SetHookCommand,
+) -> Result<ItemRotationHook, RotationHookServiceError> {
+    if identity.service_account_id.is_some() {
+        return Err(RotationHookServiceError::ForbiddenNoBody);
+    }
+    let (item, vault) = load_shared_item(state, item_id).await?;
+    // Hooks carry credentials for the target system, so only vault admins manage them.
+    authorize(
+        state,
+        identity,
+        &vault,
+        "rotate_configure",
+        "shared/items/rotation/hook",
+    )
+    .await?;
+    cmd.config
+        .validate()
+        .map_err(RotationHookServiceError::BadRequest)?;
+
+    let vault_key = server_vault_key(state, &vault)?;
+    let config_bytes = serde_json::to_vec(&cmd.config)
+        .map_err(|_| RotationHookServiceError::Internal("encrypt_failed"))?;
+    let config_enc =
+        core_crypto::encrypt_rotation_hook(&vault_key, vault.id, item.id, &config_bytes)
+            .map_err(|_| RotationHookServiceError::Internal("encrypt_failed"))?;
+
+    let now = Utc::now();
+    let hook = ItemRotationHook {
+        item_id: item.id,
+        vault_id: vault.id,
+        kind: cmd.config.kind().to_string(),
+        config_enc,
+        verify: cmd.verify,
+        created_at: now,
+        updated_at: now,
+    };
+    let repo = ItemRotationHookRepo::new(&state.db);
+    if let Err(err) = repo.upsert(&hook).await {
+        tracing::error!(event = "rotation_hook_set_failed", error = %err, "DB error");
+        return Err(RotationHookServiceError::DbError);
+    }
+    match repo.get_by_item(item.id).await {
+        Ok(Some(hook)) => Ok(hook),
+        Ok(None) => Err(RotationHookServiceError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "rotation_hook_set_failed", error = %err, "DB error");
+            Err(RotationHookServiceError::DbError)
+        }
+    }
+}
+
+pub async fn delete_hook(
+    state: &AppState,
+    identity: &Identity,
+    item_id: Uuid,
+) -> Result<(), RotationHookServiceError> {
+    if identity.service_account_id.is_some() {
+        return Err(RotationHookServiceError::ForbiddenNoBody);
+    }
+    let (_, vault) = load_shared_item(state, item_id).await?;
+    authorize(
+        state,
+        identity,
+        &vault,
+        "rotate_configure",
+        "shared/items/rotation/hook",
+    )
+    .await?;
+    match ItemRotationHookRepo::new(&state.db)
+        .delete_by_item(item_id)
+        .await
+    {
+        Ok(0) => Err(RotationHookServiceError::NotFound),
+        Ok(_) => Ok(()),
+        Err(err) => {
+            tracing::error!(event = "rotation_hook_delete_failed", error = %err, "DB error");
+            Err(RotationHookServiceError::DbError)
+        }
+    }
+}
+
+/// Loads the executor configured for an item, if any.
+pub async fn load_hook(
+    state: &AppState,
+    vault_key: &SecretKey,
+    item_id: Uuid,
+) -> Result<Option<LoadedHook>, &'static str> {
+    let hook = ItemRotationHookRepo::new(&state.db)
+        .get_by_item(item_id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "rotation_hook_load_failed", error = %err, "DB error");
+            "db_error"
+        })?;
+    let Some(hook) = hook else {
+        return Ok(None);
+    };
+    let config = decrypt_config(vault_key, &hook)?;
+    let timeout = Duration::from_secs(state.config.rotation.hook_timeout_seconds.max(1));
+    Ok(Some(LoadedHook {
+        executor: config.executor(timeout),
+        verify: hook.verify,
+    }))
+}
+
+fn decrypt_config(
+    vault_key: &SecretKey,
+    hook: &ItemRotationHook,
+) -> Result<RotationHookConfig, &'static str> {
+    let bytes = core_crypto::decrypt_rotation_hook(
+        vault_key,
+        hook.vault_id,
+        hook.item_id,
+        &hook.config_enc,
+    )
+    .map_err(|_| "decrypt_failed")?;
+    serde_json::from_slice(&bytes).map_err(|_| "decrypt_failed")
+}
@@ -0,0 +1,164 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx_core::connection::Connection;
+use sqlx_core::executor::Executor;
+use sqlx_postgres::{PgConnectOptions, PgConnection};
+
+use super::{ExecutorFuture, RotationExecutor, RotationTarget};
+
+const MAX_ROLE_LEN: usize = 63;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PostgresHookConfig {
+    /// Connection string for a login allowed to `ALTER ROLE` the target role.
+    pub connection_url: String,
+    /// Role whose password is rotated.
+    pub role: String,
+}
+
+impl PostgresHookConfig {
+    pub(super) fn validate(&self) -> Result<(), &'static str> {
+        let url = self.connection_url.trim();
+        if !(url.starts_with("postgres://") || url.starts_with("postgresql://")) {
+            return Err("invalid_connection_url");
+        }
+        PgConnectOptions::from_str(url).map_err(|_| "invalid_connection_url")?;
+        let role = self.role.trim();
+        if role.is_empty() || role.len() > MAX_ROLE_LEN || role.contains('\0') {
+            return Err("invalid_role");
+        }
+        Ok(())
+    }
+
+    pub(super) fn describe(&self) -> String {
+        match PgConnectOptions::from_str(&self.connection_url) {
+            Ok(options) => format!(
+                "postgres://{}:{}/{} role={}",
+                options.get_host(),
+                options.get_port(),
+                options.get_database().unwrap_or_default(),
+                self.role
+            ),
+            Err(_) => format!("postgres role={}", self.role),
+        }
+    }
+}
+
+/// Sets the role password with `ALTER ROLE` and verifies by logging in as the role.
+pub struct PostgresExecutor {
+    config: PostgresHookConfig,
+    timeout: Duration,
+}
+
+impl PostgresExecutor {
+    #[must_use]
+    pub fn new(config: PostgresHookConfig, timeout: Duration) -> Self {
+        Self { config, timeout }
+    }
+
+    async fn alter_role(&self, password: &str) -> Result<(), String> {
+        let options = PgConnectOptions::from_str(&self.config.connection_url)
+            .map_err(|_| "invalid_connection_url".to_string())?;
+        let statement = alter_role_statement(self.config.role.trim(), password)?;
+        let mut conn = PgConnection::connect_with(&options)
+            .await
+            .map_err(|err| format!("connect failed: {err}"))?;
+        let result = conn
+            .execute(statement.as_str())
+            .await
+            .map(|_| ())
+            .map_err(|err| format!("alter role failed: {err}"));
+        let _ = conn.close().await;
+        result
+    }
+
+    async fn login(&self, password: &str) -> Result<(), String> {
+        let options = PgConnectOptions::from_str(&self.config.connection_url)
+            .map_err(|_| "invalid_connection_url".to_string())?
+            .username(self.config.role.trim())
+            .password(password);
+        let mut conn = PgConnection::connect_with(&options)
+            .await
+            .map_err(|err| format!("login failed: {err}"))?;
+        let result = conn
+            .execute("SELECT 1")
+            .await
+            .map(|_| ())
+            .map_err(|err| format!("login check failed: {err}"));
+        let _ = conn.close().await;
+        result
+    }
+}
+
+impl RotationExecutor for PostgresExecutor {
+    fn kind(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn apply<'a>(&'a self, _target: &'a RotationTarget, password: &'a str) -> ExecutorFuture<'a> {
+        Box::pin(async move {
+            tokio::time::timeout(self.timeout, self.alter_role(password))
+                .await
+                .map_err(|_| "timed out".to_string())?
+        })
+    }
+
+    fn verify<'a>(&'a self, _target: &'a RotationTarget, password: &'a str) -> ExecutorFuture<'a> {
+        Box::pin(async move {
+            tokio::time::timeout(self.timeout, self.login(password))
+                .await
+                .map_err(|_| "timed out".to_string())?
+        })
+    }
+}
+
+/// `ALTER ROLE` takes no bind parameters, so the role and password are quoted inline. The
+/// password uses an escape string literal so the result does not depend on
+/// `standard_conforming_strings`.
+fn alter_role_statement(role: &str, password: &str) -> Result<String, String> {
+    if role.contains('\0') || password.contains('\0') {
+        return Err("invalid characters in role or password".to_string());
+    }
+    let role = role.replace('"', "\"\"");
+    let password = password.replace('\\', "\\\\").replace('\'', "''");
+    Ok(format!("ALTER ROLE \"{role}\" WITH PASSWORD E'{password}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alter_role_statement_quotes_role_and_password() {
+        assert_eq!(
+            alter_role_statement("app", "s3cret").expect("statement"),
+            "ALTER ROLE \"app\" WITH PASSWORD E's3cret'"
+        );
+        assert_eq!(
+            alter_role_statement("we\"ird", "it's\\x").expect("statement"),
+            "ALTER ROLE \"we\"\"ird\" WITH PASSWORD E'it''s\\\\x'"
+        );
+        assert!(alter_role_statement("app", "nul\0").is_err());
+    }
+
+    #[test]
+    fn validate_rejects_bad_url_and_role() {
+        let mut config = PostgresHookConfig {
+            connection_url: "postgres://admin:pw@db.internal:5432/app".to_string(),
+            role: "app".to_string(),
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.describe(),
+            "postgres://db.internal:5432/app role=app"
+        );
+        config.role = " ".to_string();
+        assert_eq!(config.validate(), Err("invalid_role"));
+        config.role = "app".to_string();
+        config.connection_url = "mysql://nope".to_string();
+        assert_eq!(config.validate(), Err("invalid_connection_url"));
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use super::{ExecutorFuture, RotationExecutor, RotationTarget};
+
+const MIN_SECRET_LEN: usize = 16;
+pub const SIGNATURE_HEADER: &str = "x-zann-signature";
+pub const TIMESTAMP_HEADER: &str = "x-zann-timestamp";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebhookHookConfig {
+    pub url: String,
+    /// Shared secret used to sign requests (HMAC-SHA256).
+    pub secret: String,
+}
+
+impl WebhookHookConfig {
+    pub(super) fn validate(&self) -> Result<(), &'static str> {
+        let url = self.url.trim();
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err("invalid_url");
+        }
+        reqwest::Url::parse(url).map_err(|_| "invalid_url")?;
+        if self.secret.len() < MIN_SECRET_LEN {
+            return Err("invalid_secret");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct HookRequest<'a> {
+    event: &'a str,
+    vault_id: String,
+    item_id: String,
+    path: &'a str,
+    password: &'a str,
+}
+
+/// POSTs `rotation.apply` and `rotation.verify` events carrying the new password. Each
+/// request is signed as `sha256=<hex HMAC of "<timestamp>.<body>">`; any 2xx response
+/// counts as success. Requests are not retried.
+pub struct WebhookExecutor {
+    config: WebhookHookConfig,
+    timeout: Duration,
+}
+
+impl WebhookExecutor {
+    #[must_use]
+    pub fn new(config: WebhookHookConfig, timeout: Duration) -> Self {
+        Self { config, timeout }
+    }
+
+    async fn post(
+        &self,
+        event: &str,
+        target: &RotationTarget,
+        password: &str,
+    ) -> Result<(), String> {
+        let body = serde_json::to_vec(&HookRequest {
+            event,
+            vault_id: target.vault_id.to_string(),
+            item_id: target.item_id.to_string(),
+            path: &target.path,
+            password,
+        })
+        .map_err(|err| err.to_string())?;
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = sign(&self.config.secret, &timestamp, &body);
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|err| format!("webhook client: {err}"))?;
+        let response = client
+            .post(self.config.url.trim())
+            .header("content-type", "application/json")
+            .header(TIMESTAMP_HEADER, timestamp)
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| format!("request failed: {err}"))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(format!("status {status}"))
+        }
+    }
+}
+
+impl RotationExecutor for WebhookExecutor {
+    fn kind(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn apply<'a>(&'a self, target: &'a RotationTarget, password: &'a str) -> ExecutorFuture<'a> {
+        Box::pin(self.post("rotation.apply", target, password))
+    }
+
+    fn verify<'a>(&'a self, target: &'a RotationTarget, password: &'a str) -> ExecutorFuture<'a> {
+        Box::pin(self.post("rotation.verify", target, password))
+    }
+}
+
+/// Signature for a hook request, as sent in the `x-zann-signature` header.
+#[must_use]
+pub fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_matches_reference_hmac() {
+        let signature = sign("key", "1700000000", b"{}");
+        assert_eq!(
+            signature,
+            "sha256=9d713ed406bb7076d4123f0dc2c39d2df5c654ed4b0cd56b52c8b4c940bd63ae"
+        );
+        assert_ne!(signature, sign("key", "1700000001", b"{}"));
+    }
+
+    #[test]
+    fn validate_requires_http_url_and_secret() {
+        let mut config = WebhookHookConfig {
+            url: "https://hooks.example.com/rotate".to_string(),
+            secret: "0123456789abcdef".to_string(),
+        };
+        assert!(config.validate().is_ok());
+        config.secret = "short".to_string();
+        assert_eq!(config.validate(), Err("invalid_secret"));
+        config.secret = "0123456789abcdef".to_string();
+        config.url = "ftp://hooks.example.com".to_string();
+        assert_eq!(config.validate(), Err("invalid_url"));
+    }
+}
@@ -11,6 +11,7 @@ use crate::app::AppState;
 use crate::domains::access_control::http::{find_vault, vault_role_allows, VaultScope};
 use crate::domains::access_control::policies::PolicyDecision;
 use crate::domains::errors::ServiceError;
+use crate::infra::audit::{self, AuditEntry};
 use crate::infra::metrics;
 
 pub type VaultServiceError = ServiceError;
@@ -160,11 +161,11 @@ pub async fn create_vault(
 
     let (encryption_type, vault_key_enc) = match kind {
         VaultKind::Shared => {
-            let Some(smk) = state.server_master_key.as_ref() else {
+            let Some(smk) = state.server_master_key.get() else {
                 return Err(VaultServiceError::Internal("smk_missing"));
             };
             let vault_key = SecretKey::generate();
-            let vault_key_enc = core_crypto::encrypt_vault_key(smk, vault_id, &vault_key)
+            let vault_key_enc = core_crypto::encrypt_vault_key(&smk, vault_id, &vault_key)
                 .map_err(|_| VaultServiceError::Internal("vault_key_encrypt_failed"))?;
             (VaultEncryptionType::Server, vault_key_enc)
         }
@@ -192,22 +193,31 @@ pub async fn create_vault(
         created_at: now,
     };
 
-    if let Err(err) = repo.create(&vault).await {
-        tracing::error!(event = "vault_create_failed", error = %err, "DB error");
-        return Err(VaultServiceError::DbError);
-    }
-
-    let member_repo = VaultMemberRepo::new(&state.db);
     let member = zann_core::VaultMember {
         vault_id: vault.id,
         user_id: identity.user_id,
         role: zann_core::VaultMemberRole::Admin,
         created_at: now,
     };
-    if let Err(err) = member_repo.create(&member).await {
-        tracing::error!(event = "vault_member_create_failed", error = %err, "DB error");
-        return Err(VaultServiceError::DbError);
-    }
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "vault_create_failed", error = %err, "DB error");
+        VaultServiceError::DbError
+    };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    VaultRepo::create_in(&mut tx, &vault)
+        .await
+        .map_err(db_error)?;
+    VaultMemberRepo::create_in(&mut tx, &member)
+        .await
+        .map_err(db_error)?;
+    let entry = AuditEntry::new("vaults", "create", "ok")
+        .vault(vault.id)
+        .target(&vault.slug);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     tracing::info!(event = "vault_created", vault_id = %vault.id, "Vault created");
     Ok(vault)
@@ -329,13 +339,25 @@ pub async fn update_vault_key(
     }
 
     let key_checksum = blake3::hash(&cmd.vault_key_enc).to_hex().to_string();
-    let Ok(affected) = repo.update_key_by_id(vault.id, &cmd.vault_key_enc).await else {
-        tracing::error!(event = "vault_key_update_failed", "DB error");
-        return Err(VaultServiceError::DbError);
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "vault_key_update_failed", error = %err, "DB error");
+        VaultServiceError::DbError
     };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    let affected = VaultRepo::update_key_by_id_in(&mut tx, vault.id, &cmd.vault_key_enc)
+        .await
+        .map_err(db_error)?;
     if affected == 0 {
         return Err(VaultServiceError::NotFound);
     }
+    let entry = AuditEntry::new("vaults", "update_key", "ok")
+        .target(&cmd.vault_id)
+        .vault(vault.id);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     tracing::info!(
         event = "vault_key_updated",
@@ -400,22 +422,32 @@ pub async fn delete_vault(
         }
     }
 
-    let Ok(affected) = repo
-        .delete_by_id(
-            vault.id,
-            vault.row_version,
-            Utc::now(),
-            identity.user_id,
-            identity.device_id,
-        )
-        .await
-    else {
-        tracing::error!(event = "vault_delete_failed", "DB error");
-        return Err(VaultServiceError::DbError);
+    let db_error = |err: sqlx_core::Error| {
+        tracing::error!(event = "vault_delete_failed", error = %err, "DB error");
+        VaultServiceError::DbError
     };
+    let mut tx = state.db.begin().await.map_err(db_error)?;
+    let affected = VaultRepo::delete_by_id_in(
+        &mut tx,
+        vault.id,
+        vault.row_version,
+        Utc::now(),
+        identity.user_id,
+        identity.device_id,
+    )
+    .await
+    .map_err(db_error)?;
     if affected == 0 {
         return Err(VaultServiceError::NotFound);
     }
+    let entry = AuditEntry::new("vaults", "delete", "ok")
+        .target(vault_id)
+        .vault(vault.id);
+    let audited = audit::record_tx(&mut tx, Some(identity), entry)
+        .await
+        .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+    audited.dispatch();
 
     tracing::info!(event = "vault_deleted", vault_id = %vault.id, "Vault deleted");
     Ok(())
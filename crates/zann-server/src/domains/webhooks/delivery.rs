@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{Duration as ChronoDuration, Utc};
+use serde_json::json;
+use sqlx_core::types::Json as SqlxJson;
+use uuid::Uuid;
+use zann_core::{ChangeOp, VaultWebhook, WebhookDelivery};
+use zann_db::repo::{ChangeNotice, VaultRepo, VaultWebhookRepo, WebhookDeliveryRepo};
+
+use crate::app::AppState;
+use crate::config::WebhooksConfig;
+use crate::domains::vaults::rotation_hooks::webhook::{sign, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use crate::domains::webhooks::service::{decrypt_secret, prefix_matches};
+use crate::domains::webhooks::target;
+use crate::infra::metrics;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_DELIVERED: &str = "delivered";
+pub const STATUS_FAILED: &str = "failed";
+pub const DELIVERY_HEADER: &str = "x-zann-delivery";
+pub const EVENT_HEADER: &str = "x-zann-event";
+
+const MAX_ERROR_LEN: usize = 512;
+
+/// What one worker pass did.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DeliveryPass {
+    pub queued: usize,
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+fn event_name(op: i32) -> &'static str {
+    match op {
+        ChangeOp::CREATE => "item.created",
+        ChangeOp::DELETE => "item.deleted",
+        _ => "item.updated",
+    }
+}
+
+fn op_name(op: i32) -> &'static str {
+    match op {
+        ChangeOp::CREATE => "create",
+        ChangeOp::DELETE => "delete",
+        _ => "update",
+    }
+}
+
+/// The notice body for one change. Identifies the item and who changed it, never its value.
+fn notice_payload(
+    webhook: &VaultWebhook,
+    delivery_id: Uuid,
+    change: &ChangeNotice,
+) -> serde_json::Value {
+    json!({
+        "id": delivery_id,
+        "event": event_name(change.op),
+        "webhook_id": webhook.id,
+        "vault_id": change.vault_id,
+        "seq": change.seq,
+        "item_id": change.item_id,
+        "path": change.path,
+        "type_id": change.type_id,
+        "op": op_name(change.op),
+        "version": change.version,
+        "actor": {
+            "user_id": change.user_id,
+            "email": change.user_email,
+            "device_id": change.device_id,
+        },
+        "changed_at": change.created_at.to_rfc3339(),
+    })
+}
+
+/// Delay before retry number `attempts` (1-based): doubles from the initial
+/// backoff and stops growing at the maximum.
+pub fn backoff(config: &WebhooksConfig, attempts: i32) -> ChronoDuration {
+    let initial = config.initial_backoff_seconds.max(1);
+    let max = config.max_backoff_seconds.max(initial);
+    let exponent = u32::try_from(attempts.saturating_sub(1).clamp(0, 30)).unwrap_or(30);
+    let seconds = initial
+        .saturating_mul(2i64.saturating_pow(exponent))
+        .min(max);
+    ChronoDuration::seconds(seconds)
+}
+
+/// One worker pass: queues new changes for every enabled webhook, sends the
+/// deliveries that are due and prunes the delivery log. Does nothing while the
+/// master key is unavailable.
+pub async fn run_webhook_deliveries(state: &AppState) -> Result<DeliveryPass, sqlx_core::Error> {
+    let mut pass = DeliveryPass::default();
+    if state.server_master_key.get().is_none() {
+        return Ok(pass);
+    }
+    let config = &state.config.webhooks;
+    let batch_size = config.batch_size.max(1);
+    let webhooks = VaultWebhookRepo::new(&state.db).list_enabled().await?;
+    for webhook in &webhooks {
+        pass.queued += enqueue_changes(state, webhook, batch_size).await?;
+    }
+
+    let deliveries = WebhookDeliveryRepo::new(&state.db);
+    let due = deliveries.list_due(Utc::now(), batch_size).await?;
+    let by_id: HashMap<Uuid, &VaultWebhook> = webhooks.iter().map(|hook| (hook.id, hook)).collect();
+    let mut secrets: HashMap<Uuid, Option<String>> = HashMap::new();
+    for mut record in due {
+        let Some(webhook) = by_id.get(&record.webhook_id).copied() else {
+            continue;
+        };
+        let secret = match secrets.get(&webhook.id) {
+            Some(secret) => secret.clone(),
+            None => {
+                let secret = match VaultRepo::new(&state.db)
+                    .get_by_id(webhook.vault_id)
+                    .await?
+                {
+                    Some(vault) => decrypt_secret(state, &vault, webhook).ok(),
+                    None => None,
+                };
+                if secret.is_none() {
+                    tracing::warn!(
+                        event = "webhook_secret_unavailable",
+                        webhook_id = %webhook.id
+                    );
+                }
+                secrets.insert(webhook.id, secret.clone());
+                secret
+            }
+        };
+        let Some(secret) = secret else {
+            continue;
+        };
+        attempt(state, webhook, &secret, &mut record).await?;
+        match record.status.as_str() {
+            STATUS_DELIVERED => pass.delivered += 1,
+            STATUS_FAILED => pass.failed += 1,
+            _ => {}
+        }
+    }
+
+    let cutoff = Utc::now() - ChronoDuration::seconds(config.retention_seconds.max(0));
+    deliveries.delete_finished_before(cutoff).await?;
+    Ok(pass)
+}
+
+/// Queues deliveries for changes after the webhook's position and moves it
+/// past them, including changes outside the path prefix.
+async fn enqueue_changes(
+    state: &AppState,
+    webhook: &VaultWebhook,
+    batch_size: i64,
+) -> Result<usize, sqlx_core::Error> {
+    let webhooks = VaultWebhookRepo::new(&state.db);
+    let changes = webhooks
+        .list_changes_since(webhook.vault_id, webhook.last_seq, batch_size)
+        .await?;
+    let Some(last) = changes.last().map(|change| change.seq) else {
+        return Ok(0);
+    };
+    let deliveries = WebhookDeliveryRepo::new(&state.db);
+    let mut queued = 0;
+    for change in changes
+        .iter()
+        .filter(|change| prefix_matches(webhook.path_prefix.as_deref(), &change.path))
+    {
+        let now = Utc::now();
+        let id = Uuid::now_v7();
+        let delivery = WebhookDelivery {
+            id,
+            webhook_id: webhook.id,
+            seq: change.seq,
+            payload: SqlxJson(notice_payload(webhook, id, change)),
+            status: STATUS_PENDING.to_string(),
+            attempts: 0,
+            next_attempt_at: Some(now),
+            response_status: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        if deliveries.create(&delivery).await? {
+            queued += 1;
+        }
+    }
+    webhooks.advance(webhook.id, webhook.last_seq, last).await?;
+    Ok(queued)
+}
+
+/// Sends `record` once and stores the outcome: delivered on a 2xx response,
+/// otherwise rescheduled with backoff until `max_attempts` is reached.
+pub(crate) async fn attempt(
+    state: &AppState,
+    webhook: &VaultWebhook,
+    secret: &str,
+    record: &mut WebhookDelivery,
+) -> Result<(), sqlx_core::Error> {
+    let config = &state.config.webhooks;
+    let result = post(
+        config,
+        webhook,
+        secret,
+        record,
+        Duration::from_secs(config.timeout_seconds.max(1)),
+    )
+    .await;
+    let now = Utc::now();
+    record.attempts = record.attempts.saturating_add(1);
+    record.updated_at = now;
+    match result {
+        Ok(status) => {
+            record.status = STATUS_DELIVERED.to_string();
+            record.next_attempt_at = None;
+            record.response_status = Some(status);
+            record.last_error = None;
+            metrics::webhook_delivery("ok");
+        }
+        Err((status, error)) => {
+            record.response_status = status;
+            record.last_error = Some(error.chars().take(MAX_ERROR_LEN).collect());
+            if record.attempts >= config.max_attempts.max(1) {
+                record.status = STATUS_FAILED.to_string();
+                record.next_attempt_at = None;
+                metrics::webhook_delivery("failed");
+            } else {
+                record.status = STATUS_PENDING.to_string();
+                record.next_attempt_at = Some(now + backoff(config, record.attempts));
+                metrics::webhook_delivery("retry");
+            }
+            tracing::warn!(
+                event = "webhook_delivery_failed",
+                webhook_id = %webhook.id,
+                delivery_id = %record.id,
+                attempts = record.attempts,
+                error = %error
+            );
+        }
+    }
+    WebhookDeliveryRepo::new(&state.db)
+        .update_state(record)
+        .await
+}
+
+/// POSTs the stored payload signed as `sha256=<hex HMAC of "<timestamp>.<body>">`,
+/// like rotation hook requests. Returns the response status.
+///
+/// The target is checked again on every attempt and the request is pinned to
+/// the checked addresses, so a host that later resolves to an internal address
+/// is refused. Redirects are not followed.
+async fn post(
+    config: &WebhooksConfig,
+    webhook: &VaultWebhook,
+    secret: &str,
+    record: &WebhookDelivery,
+    timeout: Duration,
+) -> Result<i32, (Option<i32>, String)> {
+    let body = serde_json::to_vec(&record.payload.0).map_err(|err| (None, err.to_string()))?;
+    let event = record
+        .payload
+        .0
+        .get("event")
+        .and_then(|event| event.as_str())
+        .unwrap_or("item.updated")
+        .to_string();
+    let timestamp = Utc::now().timestamp().to_string();
+    let signature = sign(secret, &timestamp, &body);
+    let target = target::resolve(config, &webhook.url)
+        .await
+        .map_err(|reason| (None, format!("target refused: {reason}")))?;
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .resolve_to_addrs(&target.host, &target.addrs)
+        .build()
+        .map_err(|err| (None, format!("webhook client: {err}")))?;
+    let response = client
+        .post(target.url)
+        .header("content-type", "application/json")
+        .header(TIMESTAMP_HEADER, timestamp)
+        .header(SIGNATURE_HEADER, signature)
+        .header(DELIVERY_HEADER, record.id.to_string())
+        .header(EVENT_HEADER, event)
+        .body(body)
+        .send()
+        .await
+        .map_err(|err| (None, format!("request failed: {err}")))?;
+    let status = response.status();
+    let code = i32::from(status.as_u16());
+    if status.is_success() {
+        Ok(code)
+    } else {
+        Err((Some(code), format!("status {status}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let config = WebhooksConfig {
+            initial_backoff_seconds: 10,
+            max_backoff_seconds: 60,
+            ..WebhooksConfig::default()
+        };
+        assert_eq!(backoff(&config, 1), ChronoDuration::seconds(10));
+        assert_eq!(backoff(&config, 2), ChronoDuration::seconds(20));
+        assert_eq!(backoff(&config, 3), ChronoDuration::seconds(40));
+        assert_eq!(backoff(&config, 4), ChronoDuration::seconds(60));
+        assert_eq!(backoff(&config, 100), ChronoDuration::seconds(60));
+    }
+
+    #[test]
+    fn events_follow_change_ops() {
+        assert_eq!(event_name(ChangeOp::CREATE), "item.created");
+        assert_eq!(event_name(ChangeOp::UPDATE), "item.updated");
+        assert_eq!(event_name(ChangeOp::DELETE), "item.deleted");
+        assert_eq!(op_name(ChangeOp::DELETE), "delete");
+    }
+}
@@ -0,0 +1,361 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zann_core::{Identity, VaultWebhook, WebhookDelivery};
+
+use crate::app::AppState;
+use crate::domains::webhooks::service::{
+    self, CreateWebhookCommand, UpdateWebhookCommand, WebhookError,
+};
+use crate::infra::audit::{self, AuditEntry};
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(crate) struct ErrorResponse {
+    pub(crate) error: &'static str,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct CreateWebhookRequest {
+    pub(crate) url: String,
+    /// Shared secret for the `x-zann-signature` HMAC; at least 16 characters.
+    pub(crate) secret: String,
+    /// Only changes under this path are delivered, e.g. `prod` or `prod/*`.
+    #[serde(default)]
+    pub(crate) path_prefix: Option<String>,
+    #[serde(default)]
+    pub(crate) enabled: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct UpdateWebhookRequest {
+    #[serde(default)]
+    pub(crate) url: Option<String>,
+    #[serde(default)]
+    pub(crate) secret: Option<String>,
+    /// Omit to keep the current prefix, `null` to follow the whole vault.
+    #[serde(
+        default,
+        deserialize_with = "crate::domains::items::expiry::deserialize_some"
+    )]
+    pub(crate) path_prefix: Option<Option<String>>,
+    #[serde(default)]
+    pub(crate) enabled: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub(crate) struct ListDeliveriesQuery {
+    /// `pending`, `delivered` or `failed`.
+    #[serde(default)]
+    pub(crate) status: Option<String>,
+    #[serde(default)]
+    pub(crate) limit: Option<i64>,
+    #[serde(default)]
+    pub(crate) offset: Option<i64>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WebhookResponse {
+    pub(crate) id: String,
+    pub(crate) vault_id: String,
+    pub(crate) url: String,
+    pub(crate) path_prefix: Option<String>,
+    pub(crate) enabled: bool,
+    /// Last change seq that was queued for delivery.
+    pub(crate) last_seq: i64,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WebhookListResponse {
+    pub(crate) webhooks: Vec<WebhookResponse>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WebhookDeliveryResponse {
+    pub(crate) id: String,
+    pub(crate) webhook_id: String,
+    pub(crate) seq: i64,
+    pub(crate) payload: serde_json::Value,
+    pub(crate) status: String,
+    pub(crate) attempts: i32,
+    pub(crate) next_attempt_at: Option<String>,
+    pub(crate) response_status: Option<i32>,
+    pub(crate) last_error: Option<String>,
+    pub(crate) created_at: String,
+    pub(crate) updated_at: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub(crate) struct WebhookDeliveryListResponse {
+    pub(crate) deliveries: Vec<WebhookDeliveryResponse>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/v1/vaults/:vault_id/webhooks",
+            get(list_webhooks).post(create_webhook),
+        )
+        .route(
+            "/v1/vaults/:vault_id/webhooks/:webhook_id",
+            get(get_webhook)
+                .patch(update_webhook)
+                .delete(delete_webhook),
+        )
+        .route(
+            "/v1/vaults/:vault_id/webhooks/:webhook_id/deliveries",
+            get(list_deliveries),
+        )
+        .route(
+            "/v1/vaults/:vault_id/webhooks/:webhook_id/deliveries/:delivery_id/redeliver",
+            post(redeliver),
+        )
+}
+
+fn map_webhook_error(error: WebhookError) -> axum::response::Response {
+    match error {
+        WebhookError::ForbiddenNoBody => StatusCode::FORBIDDEN.into_response(),
+        WebhookError::Forbidden(code) => {
+            (StatusCode::FORBIDDEN, Json(ErrorResponse { error: code })).into_response()
+        }
+        WebhookError::BadRequest(code) => {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: code })).into_response()
+        }
+        WebhookError::Conflict(code) => {
+            (StatusCode::CONFLICT, Json(ErrorResponse { error: code })).into_response()
+        }
+        WebhookError::NotFound => StatusCode::NOT_FOUND.into_response(),
+        WebhookError::DbError => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse { error: "db_error" }),
+        )
+            .into_response(),
+        other => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: other.label(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+fn webhook_response(webhook: VaultWebhook) -> WebhookResponse {
+    WebhookResponse {
+        id: webhook.id.to_string(),
+        vault_id: webhook.vault_id.to_string(),
+        url: webhook.url,
+        path_prefix: webhook.path_prefix,
+        enabled: webhook.enabled,
+        last_seq: webhook.last_seq,
+        created_at: webhook.created_at.to_rfc3339(),
+        updated_at: webhook.updated_at.to_rfc3339(),
+    }
+}
+
+fn delivery_response(delivery: WebhookDelivery) -> WebhookDeliveryResponse {
+    WebhookDeliveryResponse {
+        id: delivery.id.to_string(),
+        webhook_id: delivery.webhook_id.to_string(),
+        seq: delivery.seq,
+        payload: delivery.payload.0,
+        status: delivery.status,
+        attempts: delivery.attempts,
+        next_attempt_at: delivery.next_attempt_at.map(|value| value.to_rfc3339()),
+        response_status: delivery.response_status,
+        last_error: delivery.last_error,
+        created_at: delivery.created_at.to_rfc3339(),
+        updated_at: delivery.updated_at.to_rfc3339(),
+    }
+}
+
+async fn record_ok(
+    state: &AppState,
+    identity: &Identity,
+    action: &str,
+    vault_id: Uuid,
+    target: &str,
+    detail: Option<&str>,
+) {
+    let entry = AuditEntry::new("webhooks", action, "ok")
+        .vault(vault_id)
+        .target(target)
+        .detail(detail);
+    audit::record(state, Some(identity), entry).await;
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn list_webhooks(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+) -> impl IntoResponse {
+    match service::list_webhooks(&state, &identity, &vault_id).await {
+        Ok(webhooks) => (
+            StatusCode::OK,
+            Json(WebhookListResponse {
+                webhooks: webhooks.into_iter().map(webhook_response).collect(),
+            }),
+        )
+            .into_response(),
+        Err(error) => map_webhook_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path(vault_id): Path<String>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> impl IntoResponse {
+    let cmd = CreateWebhookCommand {
+        url: payload.url,
+        secret: payload.secret,
+        path_prefix: payload.path_prefix,
+        enabled: payload.enabled.unwrap_or(true),
+    };
+    match service::create_webhook(&state, &identity, &vault_id, cmd).await {
+        Ok(webhook) => {
+            record_ok(
+                &state,
+                &identity,
+                "webhook_create",
+                webhook.vault_id,
+                &webhook.id.to_string(),
+                Some(&webhook.url),
+            )
+            .await;
+            (StatusCode::CREATED, Json(webhook_response(webhook))).into_response()
+        }
+        Err(error) => map_webhook_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn get_webhook(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, webhook_id)): Path<(String, Uuid)>,
+) -> impl IntoResponse {
+    match service::get_webhook(&state, &identity, &vault_id, webhook_id).await {
+        Ok(webhook) => (StatusCode::OK, Json(webhook_response(webhook))).into_response(),
+        Err(error) => map_webhook_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, payload))]
+async fn update_webhook(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, webhook_id)): Path<(String, Uuid)>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> impl IntoResponse {
+    let cmd = UpdateWebhookCommand {
+        url: payload.url,
+        secret: payload.secret,
+        path_prefix: payload.path_prefix,
+        enabled: payload.enabled,
+    };
+    match service::update_webhook(&state, &identity, &vault_id, webhook_id, cmd).await {
+        Ok(webhook) => {
+            record_ok(
+                &state,
+                &identity,
+                "webhook_update",
+                webhook.vault_id,
+                &webhook.id.to_string(),
+                Some(&webhook.url),
+            )
+            .await;
+            (StatusCode::OK, Json(webhook_response(webhook))).into_response()
+        }
+        Err(error) => map_webhook_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn delete_webhook(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, webhook_id)): Path<(String, Uuid)>,
+) -> impl IntoResponse {
+    match service::delete_webhook(&state, &identity, &vault_id, webhook_id).await {
+        Ok(vault) => {
+            record_ok(
+                &state,
+                &identity,
+                "webhook_delete",
+                vault.id,
+                &webhook_id.to_string(),
+                None,
+            )
+            .await;
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(error) => map_webhook_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity, query))]
+async fn list_deliveries(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, webhook_id)): Path<(String, Uuid)>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> impl IntoResponse {
+    let result = service::list_deliveries(
+        &state,
+        &identity,
+        &vault_id,
+        webhook_id,
+        query.status.as_deref(),
+        query.limit,
+        query.offset,
+    )
+    .await;
+    match result {
+        Ok(deliveries) => (
+            StatusCode::OK,
+            Json(WebhookDeliveryListResponse {
+                deliveries: deliveries.into_iter().map(delivery_response).collect(),
+            }),
+        )
+            .into_response(),
+        Err(error) => map_webhook_error(error),
+    }
+}
+
+#[tracing::instrument(skip(state, identity))]
+async fn redeliver(
+    State(state): State<AppState>,
+    Extension(identity): Extension<Identity>,
+    Path((vault_id, webhook_id, delivery_id)): Path<(String, Uuid, Uuid)>,
+) -> impl IntoResponse {
+    match service::redeliver(&state, &identity, &vault_id, webhook_id, delivery_id).await {
+        Ok(delivery) => {
+            if let Some(vault_id) = audit::resolve_vault_id(&state, &vault_id).await {
+                let detail = format!("{} {}", delivery.seq, delivery.status);
+                record_ok(
+                    &state,
+                    &identity,
+                    "webhook_redeliver",
+                    vault_id,
+                    &delivery.id.to_string(),
+                    Some(&detail),
+                )
+                .await;
+            }
+            (StatusCode::OK, Json(delivery_response(delivery))).into_response()
+        }
+        Err(error) => map_webhook_error(error),
+    }
+}
@@ -0,0 +1,4 @@
+pub mod delivery;
+pub mod http;
+pub mod service;
+pub mod target;
@@ -0,0 +1,360 @@
+//! Webhook subscriptions push vault change notices to external systems. Each
+//! subscription follows the `changes` seq stream of one vault, optionally
+//! limited to a path prefix, and receives HMAC-signed JSON with the item path,
+//! op, version and actor. Item values are never sent.
+
+use chrono::Utc;
+use uuid::Uuid;
+use zann_core::{Identity, Vault, VaultWebhook, WebhookDelivery};
+use zann_crypto::vault_crypto as core_crypto;
+use zann_db::repo::{ChangeRepo, VaultWebhookRepo, WebhookDeliveryRepo};
+
+use crate::app::AppState;
+use crate::domains::access_control::http::VaultScope;
+use crate::domains::errors::ServiceError;
+use crate::domains::secrets::service::{
+    authorize_vault_access, ensure_server_encryption, server_vault_key,
+};
+use crate::domains::webhooks::{delivery, target};
+
+pub type WebhookError = ServiceError;
+
+const MIN_SECRET_LEN: usize = 16;
+const MAX_DELIVERY_PAGE: i64 = 500;
+
+#[derive(Debug, Clone)]
+pub struct CreateWebhookCommand {
+    pub url: String,
+    pub secret: String,
+    pub path_prefix: Option<String>,
+    pub enabled: bool,
+}
+
+/// `None` fields keep their current value; `path_prefix: Some(None)` removes the prefix.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateWebhookCommand {
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub path_prefix: Option<Option<String>>,
+    pub enabled: Option<bool>,
+}
+
+fn resource(vault_id: &str) -> String {
+    format!("vaults/{vault_id}/webhooks")
+}
+
+/// Accepts `prod`, `/prod/` or `prod/*`; an empty prefix matches the whole vault.
+pub fn normalize_prefix(prefix: &str) -> Option<String> {
+    let prefix = prefix.trim();
+    let prefix = prefix.strip_suffix('*').unwrap_or(prefix);
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        None
+    } else {
+        Some(prefix.to_string())
+    }
+}
+
+/// Item paths are compared without leading or trailing slashes, so `prod`
+/// matches `prod` and `/prod/db` but not `production`.
+pub fn prefix_matches(prefix: Option<&str>, path: &str) -> bool {
+    let Some(prefix) = prefix else {
+        return true;
+    };
+    let path = path.trim_matches('/');
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Requires https unless `webhooks.allow_http` is set, and refuses hosts that
+/// resolve to internal addresses outside `webhooks.allowed_networks`.
+async fn validate_url(state: &AppState, url: &str) -> Result<String, WebhookError> {
+    target::resolve(&state.config.webhooks, url)
+        .await
+        .map_err(WebhookError::BadRequest)?;
+    Ok(url.trim().to_string())
+}
+
+fn validate_secret(secret: &str) -> Result<(), WebhookError> {
+    if secret.len() < MIN_SECRET_LEN {
+        return Err(WebhookError::BadRequest("invalid_secret"));
+    }
+    Ok(())
+}
+
+pub async fn list_webhooks(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<Vec<VaultWebhook>, WebhookError> {
+    let vault = authorize(state, identity, vault_id).await?;
+    VaultWebhookRepo::new(&state.db)
+        .list_by_vault(vault.id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "webhook_list_failed", error = %err, "DB error");
+            WebhookError::DbError
+        })
+}
+
+pub async fn get_webhook(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    webhook_id: Uuid,
+) -> Result<VaultWebhook, WebhookError> {
+    let vault = authorize(state, identity, vault_id).await?;
+    load_webhook(state, &vault, webhook_id).await
+}
+
+/// Creates a subscription that starts at the current end of the change stream;
+/// earlier changes are not replayed.
+pub async fn create_webhook(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    cmd: CreateWebhookCommand,
+) -> Result<VaultWebhook, WebhookError> {
+    let vault = authorize(state, identity, vault_id).await?;
+    let url = validate_url(state, &cmd.url).await?;
+    validate_secret(&cmd.secret)?;
+    let last_seq = ChangeRepo::new(&state.db)
+        .last_seq_for_vault(vault.id)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "webhook_create_failed", error = %err, "DB error");
+            WebhookError::DbError
+        })?;
+    let id = Uuid::now_v7();
+    let vault_key = server_vault_key(state, &vault)?;
+    let secret_enc =
+        core_crypto::encrypt_webhook_secret(&vault_key, vault.id, id, cmd.secret.as_bytes())
+            .map_err(|_| WebhookError::Internal("encrypt_failed"))?;
+    let now = Utc::now();
+    let webhook = VaultWebhook {
+        id,
+        vault_id: vault.id,
+        url,
+        path_prefix: cmd.path_prefix.as_deref().and_then(normalize_prefix),
+        secret_enc,
+        enabled: cmd.enabled,
+        last_seq,
+        created_by_user_id: Some(identity.user_id),
+        created_at: now,
+        updated_at: now,
+    };
+    VaultWebhookRepo::new(&state.db)
+        .create(&webhook)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "webhook_create_failed", error = %err, "DB error");
+            WebhookError::DbError
+        })?;
+    Ok(webhook)
+}
+
+pub async fn update_webhook(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    webhook_id: Uuid,
+    cmd: UpdateWebhookCommand,
+) -> Result<VaultWebhook, WebhookError> {
+    let vault = authorize(state, identity, vault_id).await?;
+    let mut webhook = load_webhook(state, &vault, webhook_id).await?;
+    if let Some(url) = cmd.url.as_deref() {
+        webhook.url = validate_url(state, url).await?;
+    }
+    if let Some(secret) = cmd.secret.as_deref() {
+        validate_secret(secret)?;
+        let vault_key = server_vault_key(state, &vault)?;
+        webhook.secret_enc = core_crypto::encrypt_webhook_secret(
+            &vault_key,
+            vault.id,
+            webhook.id,
+            secret.as_bytes(),
+        )
+        .map_err(|_| WebhookError::Internal("encrypt_failed"))?;
+    }
+    if let Some(prefix) = cmd.path_prefix {
+        webhook.path_prefix = prefix.as_deref().and_then(normalize_prefix);
+    }
+    if let Some(enabled) = cmd.enabled {
+        webhook.enabled = enabled;
+    }
+    webhook.updated_at = Utc::now();
+    let updated = VaultWebhookRepo::new(&state.db)
+        .update(&webhook)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "webhook_update_failed", error = %err, "DB error");
+            WebhookError::DbError
+        })?;
+    if !updated {
+        return Err(WebhookError::NotFound);
+    }
+    Ok(webhook)
+}
+
+pub async fn delete_webhook(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    webhook_id: Uuid,
+) -> Result<Vault, WebhookError> {
+    let vault = authorize(state, identity, vault_id).await?;
+    match VaultWebhookRepo::new(&state.db)
+        .delete(vault.id, webhook_id)
+        .await
+    {
+        Ok(0) => Err(WebhookError::NotFound),
+        Ok(_) => Ok(vault),
+        Err(err) => {
+            tracing::error!(event = "webhook_delete_failed", error = %err, "DB error");
+            Err(WebhookError::DbError)
+        }
+    }
+}
+
+pub async fn list_deliveries(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    webhook_id: Uuid,
+    status: Option<&str>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<WebhookDelivery>, WebhookError> {
+    let vault = authorize(state, identity, vault_id).await?;
+    let webhook = load_webhook(state, &vault, webhook_id).await?;
+    if let Some(status) = status {
+        if !matches!(
+            status,
+            delivery::STATUS_PENDING | delivery::STATUS_DELIVERED | delivery::STATUS_FAILED
+        ) {
+            return Err(WebhookError::BadRequest("invalid_status"));
+        }
+    }
+    WebhookDeliveryRepo::new(&state.db)
+        .list_by_webhook(
+            webhook.id,
+            status,
+            limit.unwrap_or(100).clamp(1, MAX_DELIVERY_PAGE),
+            offset.unwrap_or(0).max(0),
+        )
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "webhook_deliveries_list_failed", error = %err, "DB error");
+            WebhookError::DbError
+        })
+}
+
+/// Sends a stored delivery again right away, whatever its status. A failed
+/// attempt puts it back on the retry schedule with a fresh attempt budget.
+pub async fn redeliver(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+    webhook_id: Uuid,
+    delivery_id: Uuid,
+) -> Result<WebhookDelivery, WebhookError> {
+    let vault = authorize(state, identity, vault_id).await?;
+    let webhook = load_webhook(state, &vault, webhook_id).await?;
+    let mut record = match WebhookDeliveryRepo::new(&state.db)
+        .get_by_id(webhook.id, delivery_id)
+        .await
+    {
+        Ok(Some(record)) => record,
+        Ok(None) => return Err(WebhookError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "webhook_redeliver_failed", error = %err, "DB error");
+            return Err(WebhookError::DbError);
+        }
+    };
+    let secret = decrypt_secret(state, &vault, &webhook)?;
+    record.attempts = 0;
+    delivery::attempt(state, &webhook, &secret, &mut record)
+        .await
+        .map_err(|err| {
+            tracing::error!(event = "webhook_redeliver_failed", error = %err, "DB error");
+            WebhookError::DbError
+        })?;
+    Ok(record)
+}
+
+async fn authorize(
+    state: &AppState,
+    identity: &Identity,
+    vault_id: &str,
+) -> Result<Vault, WebhookError> {
+    // Subscriptions hold a signing secret and see every change in the vault.
+    if identity.service_account_id.is_some() {
+        return Err(WebhookError::ForbiddenNoBody);
+    }
+    let vault = authorize_vault_access(
+        state,
+        identity,
+        vault_id,
+        "webhooks_manage",
+        &resource(vault_id),
+        "",
+        VaultScope::Vault,
+    )
+    .await?;
+    ensure_server_encryption(state, &vault)?;
+    Ok(vault)
+}
+
+async fn load_webhook(
+    state: &AppState,
+    vault: &Vault,
+    webhook_id: Uuid,
+) -> Result<VaultWebhook, WebhookError> {
+    match VaultWebhookRepo::new(&state.db)
+        .get_by_id(vault.id, webhook_id)
+        .await
+    {
+        Ok(Some(webhook)) => Ok(webhook),
+        Ok(None) => Err(WebhookError::NotFound),
+        Err(err) => {
+            tracing::error!(event = "webhook_load_failed", error = %err, "DB error");
+            Err(WebhookError::DbError)
+        }
+    }
+}
+
+pub(crate) fn decrypt_secret(
+    state: &AppState,
+    vault: &Vault,
+    webhook: &VaultWebhook,
+) -> Result<String, WebhookError> {
+    let vault_key = server_vault_key(state, vault)?;
+    let bytes =
+        core_crypto::decrypt_webhook_secret(&vault_key, vault.id, webhook.id, &webhook.secret_enc)
+            .map_err(|_| WebhookError::Internal("decrypt_failed"))?;
+    String::from_utf8(bytes).map_err(|_| WebhookError::Internal("decrypt_failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_prefix_accepts_globs_and_slashes() {
+        assert_eq!(normalize_prefix("prod/*").as_deref(), Some("prod"));
+        assert_eq!(normalize_prefix("/prod/db/").as_deref(), Some("prod/db"));
+        assert_eq!(normalize_prefix(" * "), None);
+        assert_eq!(normalize_prefix(""), None);
+    }
+
+    #[test]
+    fn prefix_matches_whole_segments_only() {
+        assert!(prefix_matches(None, "anything"));
+        assert!(prefix_matches(Some("prod"), "prod"));
+        assert!(prefix_matches(Some("prod"), "/prod/db/password"));
+        assert!(!prefix_matches(Some("prod"), "production/db"));
+        assert!(!prefix_matches(Some("prod/db"), "prod"));
+    }
+}
@@ -0,0 +1,161 @@
+//! Webhook URLs are chosen by vault admins, so the server must not become a
+//! probe for its own network. Targets are resolved before every request and
+//! refused when any address is loopback, private, link-local or otherwise
+//! internal, unless `webhooks.allowed_networks` covers it.
+
+use std::net::{IpAddr, SocketAddr};
+
+use ipnet::IpNet;
+use reqwest::Url;
+
+use crate::config::WebhooksConfig;
+
+/// A checked webhook URL and the addresses the request must go to.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub url: Url,
+    pub host: String,
+    pub addrs: Vec<SocketAddr>,
+}
+
+/// Parses `url` and checks its scheme without resolving the host.
+pub fn parse(config: &WebhooksConfig, url: &str) -> Result<Url, &'static str> {
+    let url = Url::parse(url.trim()).map_err(|_| "invalid_url")?;
+    match url.scheme() {
+        "https" => {}
+        "http" if config.allow_http => {}
+        "http" => return Err("insecure_url"),
+        _ => return Err("invalid_url"),
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err("invalid_url");
+    }
+    Ok(url)
+}
+
+/// Parses and resolves `url`; every resolved address must be allowed.
+pub async fn resolve(config: &WebhooksConfig, url: &str) -> Result<Target, &'static str> {
+    let url = parse(config, url)?;
+    let host = url.host_str().ok_or("invalid_url")?;
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host)
+        .to_string();
+    let port = url.port_or_known_default().ok_or("invalid_url")?;
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|_| "url_unresolvable")?
+        .collect();
+    if addrs.is_empty() {
+        return Err("url_unresolvable");
+    }
+    let allowed: Vec<IpNet> = config
+        .allowed_networks
+        .iter()
+        .filter_map(|value| value.parse().ok())
+        .collect();
+    if addrs.iter().any(|addr| {
+        let ip = addr.ip().to_canonical();
+        is_internal(ip) && !allowed.iter().any(|net| net.contains(&ip))
+    }) {
+        return Err("url_not_allowed");
+    }
+    Ok(Target { url, host, addrs })
+}
+
+/// Addresses that are not reachable on the public internet.
+fn is_internal(ip: IpAddr) -> bool {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || a == 0
+                // Carrier-grade NAT, 100.64.0.0/10.
+                || (a == 100 && (b & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                // Unique local, fc00::/7.
+                || (first & 0xfe00) == 0xfc00
+                // Link-local, fe80::/10.
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_recognised() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(is_internal(ip.parse().expect("ip")), "{ip}");
+        }
+        for ip in ["93.184.216.34", "1.1.1.1", "2606:4700::1111"] {
+            assert!(!is_internal(ip.parse().expect("ip")), "{ip}");
+        }
+    }
+
+    #[test]
+    fn http_needs_opt_in() {
+        let mut config = WebhooksConfig::default();
+        assert_eq!(
+            parse(&config, "http://hooks.example.com").expect_err("refused"),
+            "insecure_url"
+        );
+        assert_eq!(
+            parse(&config, "ftp://hooks.example.com").expect_err("refused"),
+            "invalid_url"
+        );
+        assert!(parse(&config, "https://hooks.example.com").is_ok());
+        config.allow_http = true;
+        assert!(parse(&config, "http://hooks.example.com").is_ok());
+    }
+
+    #[tokio::test]
+    async fn internal_targets_need_an_allowed_network() {
+        let mut config = WebhooksConfig::default();
+        assert_eq!(
+            resolve(&config, "https://127.0.0.1:8443/hook")
+                .await
+                .expect_err("refused"),
+            "url_not_allowed"
+        );
+        assert_eq!(
+            resolve(&config, "https://169.254.169.254/latest")
+                .await
+                .expect_err("refused"),
+            "url_not_allowed"
+        );
+        config.allowed_networks = vec!["127.0.0.0/8".to_string()];
+        let target = resolve(&config, "https://127.0.0.1:8443/hook")
+            .await
+            .expect("allowed");
+        assert_eq!(target.addrs, vec!["127.0.0.1:8443".parse().expect("addr")]);
+        assert!(resolve(&config, "https://169.254.169.254/latest")
+            .await
+            .is_err());
+    }
+}
@@ -21,41 +21,83 @@ use zann_core::{
 
 use crate::app::AppState;
 use crate::domains::access_control::http_admin::ReloadResponse;
+use crate::domains::audit::http::v1::{AuditListQuery, AuditListResponse};
 use crate::domains::auth::http::v1::types::{
     PreloginQuery, ServiceAccountLoginRequest, ServiceAccountLoginResponse,
 };
 use crate::domains::devices::http::v1::{DeviceListResponse, DeviceResponse, ListDevicesQuery};
+use crate::domains::dynamic::http::v1::{
+    DynamicCredentialResponse, DynamicSecretResponse, IssueDynamicCredentialRequest,
+    SetDynamicSecretRequest,
+};
 use crate::domains::groups::http::v1::{
     AddMemberRequest, CreateGroupRequest, GroupListResponse, GroupMemberResponse, GroupResponse,
     ListGroupsQuery, UpdateGroupRequest,
 };
+use crate::domains::invites::http::v1::{
+    CreateInviteRequest, InviteListResponse, InviteResponse, InviteTokenResponse, ListInvitesQuery,
+};
 use crate::domains::items::http::v1::items_models::{
     CreateItemRequest, FileUploadResponse, HistoryListQuery, ItemHistoryDetailResponse,
     ItemHistoryListResponse, ItemResponse, ItemsResponse, UpdateItemRequest,
 };
-use crate::domains::members::http::v1::MembersResponse;
+use crate::domains::members::http::v1::{
+    AddVaultMemberRequest, GrantVaultGroupRequest, MembersResponse, UpdateVaultMemberRequest,
+    VaultGroupGrantResponse, VaultGroupGrantsResponse, VaultMemberResponse,
+};
+use crate::domains::pki::http::v1::{
+    CreatePkiCaRequest, ListPkiCertificatesQuery, PkiCaResponse, PkiCertificateListResponse,
+    PkiCertificateResponse, PkiChainResponse, PkiCrlResponse, PkiIssueRequest, PkiIssueResponse,
+    PkiRoleListResponse, PkiRoleResponse, SetPkiRoleRequest,
+};
+use crate::domains::pki::service::PkiCaKind;
 use crate::domains::secrets::http::v1::{
-    BatchEnsureRequest, BatchGetRequest, BatchResult, SecretRequest, SecretResponse,
-    SecretSetRequest,
+    BatchEnsureRequest, BatchGetRequest, BatchResult, BatchSetRequest, GetSecretQuery,
+    RenewLeaseRequest, SecretHoldersResponse, SecretLeaseResponse, SecretLeasesQuery,
+    SecretRequest, SecretResponse, SecretSetRequest,
+};
+use crate::domains::service_accounts::http::v1::{
+    CreateServiceAccountRequest, ListServiceAccountsQuery, ServiceAccountListResponse,
+    ServiceAccountResponse, ServiceAccountTokenResponse,
+};
+use crate::domains::ssh::http::v1::{
+    CreateSshCaRequest, SetSshRoleRequest, SshCaResponse, SshRoleListResponse, SshRoleResponse,
+    SshSignRequest, SshSignResponse,
 };
+use crate::domains::ssh::service::SshCertType;
 use crate::domains::sync::http::v1::types::{
     SyncPullRequest, SyncPullResponse, SyncPushRequest, SyncPushResponse, SyncSharedPullRequest,
     SyncSharedPullResponse, SyncSharedPushRequest,
 };
-use crate::domains::system::http::v1::{SecurityProfilesResponse, SystemInfoResponse};
+use crate::domains::system::http::v1::{
+    SealStatusResponse, SecurityProfilesResponse, SystemInfoResponse, UnsealRequest,
+};
+use crate::domains::transit::http::v1::{
+    TransitCiphertextRequest, TransitCiphertextResponse, TransitEncryptRequest, TransitKeyResponse,
+    TransitPlaintextResponse, UpdateTransitKeyRequest,
+};
 use crate::domains::users::http::v1::types::{
     ChangePasswordRequest, CreateUserRequest, ListUsersQuery, RecoveryKitResponse,
-    ResetPasswordRequest, ResetPasswordResponse, UpdateMeRequest, UserListResponse, UserResponse,
+    ResetPasswordRequest, ResetPasswordResponse, SetSshUsernameRequest, SshUsernameResponse,
+    UpdateMeRequest, UserListResponse, UserResponse,
+};
+use crate::domains::vaults::http::v1::rotation::{
+    OverdueQuery, OverdueResponse, RotationHookResponse, RotationScheduleResponse,
+    SetRotationHookRequest, SetRotationScheduleRequest,
 };
 use crate::domains::vaults::http::v1::shared::types::{
     ItemHistoryDetailResponse as SharedHistoryDetailResponse,
-    ItemHistoryListResponse as SharedHistoryListResponse, RotateAbortRequest, RotateStartRequest,
-    RotationCandidateResponse, RotationCommitResponse, RotationStatusResponse, SharedItemResponse,
-    SharedItemsQuery, SharedItemsResponse,
+    ItemHistoryListResponse as SharedHistoryListResponse, RotateAbortRequest, RotateCommitRequest,
+    RotateStartRequest, RotationCandidateResponse, RotationCommitResponse, RotationStatusResponse,
+    SharedItemResponse, SharedItemsQuery, SharedItemsResponse,
 };
 use crate::domains::vaults::http::v1::{
     CreateVaultRequest, ListVaultsQuery, UpdateVaultKeyRequest, VaultResponse,
 };
+use crate::domains::webhooks::http::v1::{
+    CreateWebhookRequest, ListDeliveriesQuery, UpdateWebhookRequest, WebhookDeliveryListResponse,
+    WebhookDeliveryResponse, WebhookListResponse, WebhookResponse,
+};
 use crate::http::routes::health::HealthResponse;
 
 pub fn build_openapi() -> OpenApi {
@@ -89,6 +131,9 @@ fn doc_router() -> ApiRouter<AppState> {
             "/v1/system/security-profiles",
             get(system_security_profiles),
         )
+        .api_route("/v1/system/seal-status", get(system_seal_status))
+        .api_route("/v1/system/unseal", post(system_unseal))
+        .api_route("/v1/system/seal", post(system_seal))
         .api_route("/v1/devices", get(devices_list))
         .api_route("/v1/devices/current", get(devices_current))
         .api_route("/v1/devices/:id", delete(devices_revoke))
@@ -135,6 +180,22 @@ fn doc_router() -> ApiRouter<AppState> {
             "/v1/shared/items/:item_id/rotate/abort",
             post(shared_rotate_abort),
         )
+        .api_route(
+            "/v1/shared/items/:item_id/rotation/schedule",
+            get(shared_rotation_schedule_get)
+                .put(shared_rotation_schedule_set)
+                .delete(shared_rotation_schedule_delete),
+        )
+        .api_route(
+            "/v1/shared/items/:item_id/rotation/hook",
+            get(shared_rotation_hook_get)
+                .put(shared_rotation_hook_set)
+                .delete(shared_rotation_hook_delete),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/rotation/overdue",
+            get(vaults_rotation_overdue),
+        )
         .api_route(
             "/v1/shared/items/:item_id/history",
             get(shared_history_list),
@@ -167,7 +228,22 @@ fn doc_router() -> ApiRouter<AppState> {
             "/v1/vaults/:vault_id/items/:item_id/versions/:version/restore",
             post(items_history_restore),
         )
-        .api_route("/v1/vaults/:vault_id/members", get(members_list))
+        .api_route(
+            "/v1/vaults/:vault_id/members",
+            get(members_list).post(members_add),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/members/:user_id",
+            put(members_update).delete(members_remove),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/groups",
+            get(group_grants_list).post(group_grants_add),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/groups/:slug",
+            put(group_grants_update).delete(group_grants_remove),
+        )
         .api_route(
             "/v1/vaults/:vault_id/secrets/*path",
             get(secrets_get).put(secrets_set),
@@ -178,10 +254,109 @@ fn doc_router() -> ApiRouter<AppState> {
             "/v1/vaults/:vault_id/secrets/batch/ensure",
             post(secrets_batch_ensure),
         )
+        .api_route(
+            "/v1/vaults/:vault_id/secrets/batch/set",
+            post(secrets_batch_set),
+        )
         .api_route(
             "/v1/vaults/:vault_id/secrets/batch/get",
             post(secrets_batch_get),
         )
+        .api_route(
+            "/v1/vaults/:vault_id/secret-leases",
+            get(secret_leases_list),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/secret-leases/:lease_id",
+            delete(secret_lease_revoke),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/secret-leases/:lease_id/renew",
+            post(secret_lease_renew),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/dynamic/*path",
+            get(dynamic_get)
+                .put(dynamic_set)
+                .delete(dynamic_delete)
+                .post(dynamic_issue),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/dynamic-leases/:lease_id",
+            delete(dynamic_lease_revoke),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/transit/:key",
+            get(transit_key_get)
+                .post(transit_key_create)
+                .patch(transit_key_update),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/transit/:key/encrypt",
+            post(transit_encrypt),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/transit/:key/decrypt",
+            post(transit_decrypt),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/transit/:key/rewrap",
+            post(transit_rewrap),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/transit/:key/rotate",
+            post(transit_key_rotate),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/ssh/ca",
+            get(ssh_ca_get).post(ssh_ca_create).delete(ssh_ca_delete),
+        )
+        .api_route("/v1/vaults/:vault_id/ssh/roles", get(ssh_roles_list))
+        .api_route(
+            "/v1/vaults/:vault_id/ssh/roles/:role",
+            get(ssh_role_get).put(ssh_role_set).delete(ssh_role_delete),
+        )
+        .api_route("/v1/vaults/:vault_id/ssh/sign", post(ssh_sign))
+        .api_route(
+            "/v1/vaults/:vault_id/pki/ca",
+            get(pki_ca_get).delete(pki_ca_delete),
+        )
+        .api_route("/v1/vaults/:vault_id/pki/root", post(pki_root_create))
+        .api_route(
+            "/v1/vaults/:vault_id/pki/intermediate",
+            post(pki_intermediate_create),
+        )
+        .api_route("/v1/vaults/:vault_id/pki/roles", get(pki_roles_list))
+        .api_route(
+            "/v1/vaults/:vault_id/pki/roles/:role",
+            get(pki_role_get).put(pki_role_set).delete(pki_role_delete),
+        )
+        .api_route("/v1/vaults/:vault_id/pki/issue", post(pki_issue))
+        .api_route("/v1/vaults/:vault_id/pki/certs", get(pki_certs_list))
+        .api_route("/v1/vaults/:vault_id/pki/certs/:serial", get(pki_cert_get))
+        .api_route(
+            "/v1/vaults/:vault_id/pki/certs/:serial/revoke",
+            post(pki_cert_revoke),
+        )
+        .api_route("/v1/vaults/:vault_id/pki/crl", get(pki_crl))
+        .api_route(
+            "/v1/vaults/:vault_id/webhooks",
+            get(webhooks_list).post(webhooks_create),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/webhooks/:webhook_id",
+            get(webhooks_get)
+                .patch(webhooks_update)
+                .delete(webhooks_delete),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/webhooks/:webhook_id/deliveries",
+            get(webhook_deliveries_list),
+        )
+        .api_route(
+            "/v1/vaults/:vault_id/webhooks/:webhook_id/deliveries/:delivery_id/redeliver",
+            post(webhook_redeliver),
+        )
         .api_route("/v1/sync/pull", post(sync_pull))
         .api_route("/v1/sync/push", post(sync_push))
         .api_route("/v1/sync/shared/pull", post(sync_shared_pull))
@@ -194,6 +369,22 @@ fn doc_router() -> ApiRouter<AppState> {
         .api_route("/v1/users/:id/block", post(users_block))
         .api_route("/v1/users/:id/unblock", post(users_unblock))
         .api_route("/v1/users/:id/reset-password", post(users_reset_password))
+        .api_route("/v1/users/:id/ssh-username", put(users_set_ssh_username))
+        .api_route("/v1/audit", get(audit_list))
+        .api_route(
+            "/v1/service-accounts",
+            get(service_accounts_list).post(service_accounts_create),
+        )
+        .api_route(
+            "/v1/service-accounts/:id",
+            get(service_accounts_get).delete(service_accounts_revoke),
+        )
+        .api_route(
+            "/v1/service-accounts/:id/rotate",
+            post(service_accounts_rotate),
+        )
+        .api_route("/v1/admin/invites", get(invites_list).post(invites_create))
+        .api_route("/v1/admin/invites/:id", delete(invites_revoke))
 }
 
 fn not_implemented<T>(body: T) -> (StatusCode, Json<T>) {
@@ -314,6 +505,31 @@ async fn system_security_profiles() -> (StatusCode, Json<SecurityProfilesRespons
     })
 }
 
+fn empty_seal_status() -> SealStatusResponse {
+    SealStatusResponse {
+        manual_unseal: false,
+        sealed: false,
+        initialized: false,
+        threshold: None,
+        shares: None,
+        progress: 0,
+    }
+}
+
+async fn system_seal_status() -> (StatusCode, Json<SealStatusResponse>) {
+    not_implemented(empty_seal_status())
+}
+
+async fn system_unseal(
+    Json(_payload): Json<UnsealRequest>,
+) -> (StatusCode, Json<SealStatusResponse>) {
+    not_implemented(empty_seal_status())
+}
+
+async fn system_seal() -> (StatusCode, Json<SealStatusResponse>) {
+    not_implemented(empty_seal_status())
+}
+
 async fn devices_list(
     Query(_query): Query<ListDevicesQuery>,
 ) -> (StatusCode, Json<DeviceListResponse>) {
@@ -476,6 +692,7 @@ async fn shared_items_get(Path(_item_id): Path<String>) -> (StatusCode, Json<Sha
         version: 0,
         deleted_at: None,
         updated_at: String::new(),
+        expires_at: None,
     })
 }
 
@@ -490,6 +707,7 @@ async fn shared_rotate_start(
         expires_at: None,
         recover_until: None,
         aborted_reason: None,
+        active_leases: None,
     })
 }
 
@@ -503,6 +721,7 @@ async fn shared_rotate_status(
         expires_at: None,
         recover_until: None,
         aborted_reason: None,
+        active_leases: None,
     })
 }
 
@@ -527,15 +746,18 @@ async fn shared_rotate_recover(
         expires_at: None,
         recover_until: None,
         aborted_reason: None,
+        active_leases: None,
     })
 }
 
 async fn shared_rotate_commit(
     Path(_item_id): Path<String>,
+    Json(_payload): Json<RotateCommitRequest>,
 ) -> (StatusCode, Json<RotationCommitResponse>) {
     not_implemented(RotationCommitResponse {
         status: "not_implemented",
         version: 0,
+        outstanding_leases: 0,
     })
 }
 
@@ -550,6 +772,7 @@ async fn shared_rotate_abort(
         expires_at: None,
         recover_until: None,
         aborted_reason: None,
+        active_leases: None,
     })
 }
 
@@ -595,6 +818,7 @@ async fn items_create(
         version: 0,
         deleted_at: None,
         updated_at: String::new(),
+        expires_at: None,
     })
 }
 
@@ -615,6 +839,7 @@ async fn items_get(
         version: 0,
         deleted_at: None,
         updated_at: String::new(),
+        expires_at: None,
     })
 }
 
@@ -636,6 +861,7 @@ async fn items_update(
         version: 0,
         deleted_at: None,
         updated_at: String::new(),
+        expires_at: None,
     })
 }
 
@@ -682,6 +908,7 @@ async fn items_history_restore(
         version: 0,
         deleted_at: None,
         updated_at: String::new(),
+        expires_at: None,
     })
 }
 
@@ -734,8 +961,506 @@ async fn members_list(Path(_vault_id): Path<String>) -> (StatusCode, Json<Member
     })
 }
 
+async fn members_add(
+    Path(_vault_id): Path<String>,
+    Json(_payload): Json<AddVaultMemberRequest>,
+) -> (StatusCode, Json<VaultMemberResponse>) {
+    not_implemented(empty_vault_member())
+}
+
+async fn members_update(
+    Path((_vault_id, _user_id)): Path<(String, String)>,
+    Json(_payload): Json<UpdateVaultMemberRequest>,
+) -> (StatusCode, Json<VaultMemberResponse>) {
+    not_implemented(empty_vault_member())
+}
+
+async fn members_remove(Path((_vault_id, _user_id)): Path<(String, String)>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+fn empty_vault_member() -> VaultMemberResponse {
+    VaultMemberResponse {
+        user_id: String::new(),
+        email: String::new(),
+        role: String::new(),
+        source: "direct",
+        group: None,
+        created_at: String::new(),
+    }
+}
+
+async fn group_grants_list(
+    Path(_vault_id): Path<String>,
+) -> (StatusCode, Json<VaultGroupGrantsResponse>) {
+    not_implemented(VaultGroupGrantsResponse { groups: Vec::new() })
+}
+
+async fn group_grants_add(
+    Path(_vault_id): Path<String>,
+    Json(_payload): Json<GrantVaultGroupRequest>,
+) -> (StatusCode, Json<VaultGroupGrantResponse>) {
+    not_implemented(empty_group_grant())
+}
+
+async fn group_grants_update(
+    Path((_vault_id, _slug)): Path<(String, String)>,
+    Json(_payload): Json<UpdateVaultMemberRequest>,
+) -> (StatusCode, Json<VaultGroupGrantResponse>) {
+    not_implemented(empty_group_grant())
+}
+
+async fn group_grants_remove(Path((_vault_id, _slug)): Path<(String, String)>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+fn empty_group_grant() -> VaultGroupGrantResponse {
+    VaultGroupGrantResponse {
+        group_id: String::new(),
+        slug: String::new(),
+        name: String::new(),
+        role: String::new(),
+        created_at: String::new(),
+    }
+}
+
+async fn secret_leases_list(
+    Path(_vault_id): Path<String>,
+    Query(_query): Query<SecretLeasesQuery>,
+) -> (StatusCode, Json<SecretHoldersResponse>) {
+    not_implemented(SecretHoldersResponse {
+        vault_id: String::new(),
+        path: String::new(),
+        version: 0,
+        holders: Vec::new(),
+    })
+}
+
+async fn secret_lease_renew(
+    Path((_vault_id, _lease_id)): Path<(String, Uuid)>,
+    Json(_payload): Json<RenewLeaseRequest>,
+) -> (StatusCode, Json<SecretLeaseResponse>) {
+    not_implemented(SecretLeaseResponse {
+        lease_id: String::new(),
+        item_version: 0,
+        ttl_seconds: 0,
+        issued_at: String::new(),
+        renewed_at: None,
+        expires_at: String::new(),
+        revoked_at: None,
+    })
+}
+
+async fn secret_lease_revoke(Path((_vault_id, _lease_id)): Path<(String, Uuid)>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+fn empty_dynamic_secret() -> DynamicSecretResponse {
+    DynamicSecretResponse {
+        id: String::new(),
+        vault_id: String::new(),
+        path: String::new(),
+        kind: String::new(),
+        target: String::new(),
+        default_ttl_seconds: 0,
+        max_ttl_seconds: 0,
+        created_at: String::new(),
+        updated_at: String::new(),
+    }
+}
+
+async fn dynamic_get(
+    Path((_vault_id, _path)): Path<(String, String)>,
+) -> (StatusCode, Json<DynamicSecretResponse>) {
+    not_implemented(empty_dynamic_secret())
+}
+
+async fn dynamic_set(
+    Path((_vault_id, _path)): Path<(String, String)>,
+    Json(_payload): Json<SetDynamicSecretRequest>,
+) -> (StatusCode, Json<DynamicSecretResponse>) {
+    not_implemented(empty_dynamic_secret())
+}
+
+async fn dynamic_delete(Path((_vault_id, _path)): Path<(String, String)>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+async fn dynamic_issue(
+    Path((_vault_id, _path)): Path<(String, String)>,
+    Json(_payload): Json<IssueDynamicCredentialRequest>,
+) -> (StatusCode, Json<DynamicCredentialResponse>) {
+    not_implemented(DynamicCredentialResponse {
+        lease_id: String::new(),
+        username: String::new(),
+        password: String::new(),
+        issued_at: String::new(),
+        expires_at: String::new(),
+        ttl_seconds: 0,
+        host: None,
+        port: None,
+        database: None,
+    })
+}
+
+async fn dynamic_lease_revoke(Path((_vault_id, _lease_id)): Path<(String, Uuid)>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+fn empty_transit_key() -> TransitKeyResponse {
+    TransitKeyResponse {
+        id: String::new(),
+        vault_id: String::new(),
+        name: String::new(),
+        latest_version: 0,
+        min_decryption_version: 0,
+        created_at: String::new(),
+        updated_at: String::new(),
+    }
+}
+
+fn empty_transit_ciphertext() -> TransitCiphertextResponse {
+    TransitCiphertextResponse {
+        ciphertext: String::new(),
+        key_version: 0,
+    }
+}
+
+async fn transit_key_get(
+    Path((_vault_id, _key)): Path<(String, String)>,
+) -> (StatusCode, Json<TransitKeyResponse>) {
+    not_implemented(empty_transit_key())
+}
+
+async fn transit_key_create(
+    Path((_vault_id, _key)): Path<(String, String)>,
+) -> (StatusCode, Json<TransitKeyResponse>) {
+    not_implemented(empty_transit_key())
+}
+
+async fn transit_key_update(
+    Path((_vault_id, _key)): Path<(String, String)>,
+    Json(_payload): Json<UpdateTransitKeyRequest>,
+) -> (StatusCode, Json<TransitKeyResponse>) {
+    not_implemented(empty_transit_key())
+}
+
+async fn transit_key_rotate(
+    Path((_vault_id, _key)): Path<(String, String)>,
+) -> (StatusCode, Json<TransitKeyResponse>) {
+    not_implemented(empty_transit_key())
+}
+
+async fn transit_encrypt(
+    Path((_vault_id, _key)): Path<(String, String)>,
+    Json(_payload): Json<TransitEncryptRequest>,
+) -> (StatusCode, Json<TransitCiphertextResponse>) {
+    not_implemented(empty_transit_ciphertext())
+}
+
+async fn transit_decrypt(
+    Path((_vault_id, _key)): Path<(String, String)>,
+    Json(_payload): Json<TransitCiphertextRequest>,
+) -> (StatusCode, Json<TransitPlaintextResponse>) {
+    not_implemented(TransitPlaintextResponse {
+        plaintext: String::new(),
+        key_version: 0,
+    })
+}
+
+async fn transit_rewrap(
+    Path((_vault_id, _key)): Path<(String, String)>,
+    Json(_payload): Json<TransitCiphertextRequest>,
+) -> (StatusCode, Json<TransitCiphertextResponse>) {
+    not_implemented(empty_transit_ciphertext())
+}
+
+fn empty_ssh_ca() -> SshCaResponse {
+    SshCaResponse {
+        id: String::new(),
+        vault_id: String::new(),
+        public_key: String::new(),
+        created_at: String::new(),
+    }
+}
+
+fn empty_ssh_role() -> SshRoleResponse {
+    SshRoleResponse {
+        name: String::new(),
+        cert_type: String::new(),
+        allowed_principals: Vec::new(),
+        default_principals: Vec::new(),
+        allowed_extensions: Vec::new(),
+        default_extensions: Vec::new(),
+        default_ttl_seconds: 0,
+        max_ttl_seconds: 0,
+        created_at: String::new(),
+        updated_at: String::new(),
+    }
+}
+
+async fn ssh_ca_get(Path(_vault_id): Path<String>) -> (StatusCode, Json<SshCaResponse>) {
+    not_implemented(empty_ssh_ca())
+}
+
+async fn ssh_ca_create(
+    Path(_vault_id): Path<String>,
+    Json(_payload): Json<CreateSshCaRequest>,
+) -> (StatusCode, Json<SshCaResponse>) {
+    not_implemented(empty_ssh_ca())
+}
+
+async fn ssh_ca_delete(Path(_vault_id): Path<String>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+async fn ssh_roles_list(Path(_vault_id): Path<String>) -> (StatusCode, Json<SshRoleListResponse>) {
+    not_implemented(SshRoleListResponse { roles: Vec::new() })
+}
+
+async fn ssh_role_get(
+    Path((_vault_id, _role)): Path<(String, String)>,
+) -> (StatusCode, Json<SshRoleResponse>) {
+    not_implemented(empty_ssh_role())
+}
+
+async fn ssh_role_set(
+    Path((_vault_id, _role)): Path<(String, String)>,
+    Json(_payload): Json<SetSshRoleRequest>,
+) -> (StatusCode, Json<SshRoleResponse>) {
+    not_implemented(empty_ssh_role())
+}
+
+async fn ssh_role_delete(Path((_vault_id, _role)): Path<(String, String)>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+async fn ssh_sign(
+    Path(_vault_id): Path<String>,
+    Json(_payload): Json<SshSignRequest>,
+) -> (StatusCode, Json<SshSignResponse>) {
+    not_implemented(SshSignResponse {
+        certificate: String::new(),
+        serial: String::new(),
+        key_id: String::new(),
+        cert_type: SshCertType::User,
+        principals: Vec::new(),
+        extensions: Vec::new(),
+        valid_after: String::new(),
+        valid_before: String::new(),
+    })
+}
+
+fn empty_pki_ca() -> PkiCaResponse {
+    PkiCaResponse {
+        id: String::new(),
+        kind: PkiCaKind::Root,
+        common_name: String::new(),
+        serial: String::new(),
+        certificate: String::new(),
+        not_after: String::new(),
+        created_at: String::new(),
+    }
+}
+
+fn empty_pki_role() -> PkiRoleResponse {
+    PkiRoleResponse {
+        name: String::new(),
+        allowed_domains: Vec::new(),
+        allow_subdomains: false,
+        allow_ip_sans: false,
+        key_type: String::new(),
+        server_auth: false,
+        client_auth: false,
+        default_ttl_seconds: 0,
+        max_ttl_seconds: 0,
+        created_at: String::new(),
+        updated_at: String::new(),
+    }
+}
+
+fn empty_pki_certificate() -> PkiCertificateResponse {
+    PkiCertificateResponse {
+        serial: String::new(),
+        role: String::new(),
+        common_name: String::new(),
+        sans: Vec::new(),
+        certificate: String::new(),
+        not_before: String::new(),
+        not_after: String::new(),
+        revoked_at: None,
+        created_at: String::new(),
+    }
+}
+
+async fn pki_ca_get(Path(_vault_id): Path<String>) -> (StatusCode, Json<PkiChainResponse>) {
+    not_implemented(PkiChainResponse {
+        root: None,
+        intermediate: None,
+        ca_chain: Vec::new(),
+    })
+}
+
+async fn pki_ca_delete(Path(_vault_id): Path<String>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+async fn pki_root_create(
+    Path(_vault_id): Path<String>,
+    Json(_payload): Json<CreatePkiCaRequest>,
+) -> (StatusCode, Json<PkiCaResponse>) {
+    not_implemented(empty_pki_ca())
+}
+
+async fn pki_intermediate_create(
+    Path(_vault_id): Path<String>,
+    Json(_payload): Json<CreatePkiCaRequest>,
+) -> (StatusCode, Json<PkiCaResponse>) {
+    not_implemented(empty_pki_ca())
+}
+
+async fn pki_roles_list(Path(_vault_id): Path<String>) -> (StatusCode, Json<PkiRoleListResponse>) {
+    not_implemented(PkiRoleListResponse { roles: Vec::new() })
+}
+
+async fn pki_role_get(
+    Path((_vault_id, _role)): Path<(String, String)>,
+) -> (StatusCode, Json<PkiRoleResponse>) {
+    not_implemented(empty_pki_role())
+}
+
+async fn pki_role_set(
+    Path((_vault_id, _role)): Path<(String, String)>,
+    Json(_payload): Json<SetPkiRoleRequest>,
+) -> (StatusCode, Json<PkiRoleResponse>) {
+    not_implemented(empty_pki_role())
+}
+
+async fn pki_role_delete(Path((_vault_id, _role)): Path<(String, String)>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+async fn pki_issue(
+    Path(_vault_id): Path<String>,
+    Json(_payload): Json<PkiIssueRequest>,
+) -> (StatusCode, Json<PkiIssueResponse>) {
+    not_implemented(PkiIssueResponse {
+        serial: String::new(),
+        common_name: String::new(),
+        sans: Vec::new(),
+        certificate: String::new(),
+        private_key: String::new(),
+        issuing_ca: String::new(),
+        ca_chain: Vec::new(),
+        not_before: String::new(),
+        not_after: String::new(),
+    })
+}
+
+async fn pki_certs_list(
+    Path(_vault_id): Path<String>,
+    Query(_query): Query<ListPkiCertificatesQuery>,
+) -> (StatusCode, Json<PkiCertificateListResponse>) {
+    not_implemented(PkiCertificateListResponse {
+        certificates: Vec::new(),
+    })
+}
+
+async fn pki_cert_get(
+    Path((_vault_id, _serial)): Path<(String, String)>,
+) -> (StatusCode, Json<PkiCertificateResponse>) {
+    not_implemented(empty_pki_certificate())
+}
+
+async fn pki_cert_revoke(
+    Path((_vault_id, _serial)): Path<(String, String)>,
+) -> (StatusCode, Json<PkiCertificateResponse>) {
+    not_implemented(empty_pki_certificate())
+}
+
+async fn pki_crl(Path(_vault_id): Path<String>) -> (StatusCode, Json<PkiCrlResponse>) {
+    not_implemented(PkiCrlResponse {
+        crl: String::new(),
+        issuer_serial: String::new(),
+        revoked: 0,
+        this_update: String::new(),
+        next_update: String::new(),
+    })
+}
+
+fn empty_webhook() -> WebhookResponse {
+    WebhookResponse {
+        id: String::new(),
+        vault_id: String::new(),
+        url: String::new(),
+        path_prefix: None,
+        enabled: false,
+        last_seq: 0,
+        created_at: String::new(),
+        updated_at: String::new(),
+    }
+}
+
+async fn webhooks_list(Path(_vault_id): Path<String>) -> (StatusCode, Json<WebhookListResponse>) {
+    not_implemented(WebhookListResponse {
+        webhooks: Vec::new(),
+    })
+}
+
+async fn webhooks_create(
+    Path(_vault_id): Path<String>,
+    Json(_payload): Json<CreateWebhookRequest>,
+) -> (StatusCode, Json<WebhookResponse>) {
+    not_implemented(empty_webhook())
+}
+
+async fn webhooks_get(
+    Path((_vault_id, _webhook_id)): Path<(String, Uuid)>,
+) -> (StatusCode, Json<WebhookResponse>) {
+    not_implemented(empty_webhook())
+}
+
+async fn webhooks_update(
+    Path((_vault_id, _webhook_id)): Path<(String, Uuid)>,
+    Json(_payload): Json<UpdateWebhookRequest>,
+) -> (StatusCode, Json<WebhookResponse>) {
+    not_implemented(empty_webhook())
+}
+
+async fn webhooks_delete(Path((_vault_id, _webhook_id)): Path<(String, Uuid)>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+async fn webhook_deliveries_list(
+    Path((_vault_id, _webhook_id)): Path<(String, Uuid)>,
+    Query(_query): Query<ListDeliveriesQuery>,
+) -> (StatusCode, Json<WebhookDeliveryListResponse>) {
+    not_implemented(WebhookDeliveryListResponse {
+        deliveries: Vec::new(),
+    })
+}
+
+async fn webhook_redeliver(
+    Path((_vault_id, _webhook_id, _delivery_id)): Path<(String, Uuid, Uuid)>,
+) -> (StatusCode, Json<WebhookDeliveryResponse>) {
+    not_implemented(WebhookDeliveryResponse {
+        id: String::new(),
+        webhook_id: String::new(),
+        seq: 0,
+        payload: serde_json::Value::Null,
+        status: String::new(),
+        attempts: 0,
+        next_attempt_at: None,
+        response_status: None,
+        last_error: None,
+        created_at: String::new(),
+        updated_at: String::new(),
+    })
+}
+
 async fn secrets_get(
     Path((_vault_id, _path)): Path<(String, String)>,
+    Query(_query): Query<GetSecretQuery>,
 ) -> (StatusCode, Json<SecretResponse>) {
     not_implemented(SecretResponse {
         path: String::new(),
@@ -744,8 +1469,11 @@ async fn secrets_get(
         policy: String::new(),
         meta: None,
         version: 0,
+        expires_at: None,
         previous_version: None,
         created: None,
+        lease: None,
+        outstanding_leases: None,
     })
 }
 
@@ -760,8 +1488,11 @@ async fn secrets_set(
         policy: String::new(),
         meta: None,
         version: 0,
+        expires_at: None,
         previous_version: None,
         created: None,
+        lease: None,
+        outstanding_leases: None,
     })
 }
 
@@ -776,8 +1507,11 @@ async fn secrets_ensure(
         policy: String::new(),
         meta: None,
         version: 0,
+        expires_at: None,
         previous_version: None,
         created: None,
+        lease: None,
+        outstanding_leases: None,
     })
 }
 
@@ -792,8 +1526,11 @@ async fn secrets_rotate(
         policy: String::new(),
         meta: None,
         version: 0,
+        expires_at: None,
         previous_version: None,
         created: None,
+        lease: None,
+        outstanding_leases: None,
     })
 }
 
@@ -804,6 +1541,13 @@ async fn secrets_batch_ensure(
     not_implemented(Vec::new())
 }
 
+async fn secrets_batch_set(
+    Path(_vault_id): Path<String>,
+    Json(_payload): Json<BatchSetRequest>,
+) -> (StatusCode, Json<Vec<BatchResult>>) {
+    not_implemented(Vec::new())
+}
+
 async fn secrets_batch_get(
     Path(_vault_id): Path<String>,
     Json(_payload): Json<BatchGetRequest>,
@@ -943,3 +1687,183 @@ async fn users_reset_password(
         password: String::new(),
     })
 }
+
+async fn users_set_ssh_username(
+    Path(_id): Path<String>,
+    Json(_payload): Json<SetSshUsernameRequest>,
+) -> (StatusCode, Json<SshUsernameResponse>) {
+    not_implemented(SshUsernameResponse { ssh_username: None })
+}
+
+async fn audit_list(Query(_query): Query<AuditListQuery>) -> (StatusCode, Json<AuditListResponse>) {
+    not_implemented(AuditListResponse {
+        events: Vec::new(),
+        next_cursor: None,
+    })
+}
+
+fn empty_service_account() -> ServiceAccountResponse {
+    ServiceAccountResponse {
+        id: String::new(),
+        owner_user_id: String::new(),
+        name: String::new(),
+        token_prefix: String::new(),
+        scopes: Vec::new(),
+        allowed_ips: None,
+        issued_by: None,
+        vault_id: None,
+        vault_slug: None,
+        prefixes: None,
+        ops: None,
+        expires_at: None,
+        last_used_at: None,
+        last_used_ip: None,
+        use_count: 0,
+        created_at: String::new(),
+        revoked_at: None,
+    }
+}
+
+async fn service_accounts_list(
+    Query(_query): Query<ListServiceAccountsQuery>,
+) -> (StatusCode, Json<ServiceAccountListResponse>) {
+    not_implemented(ServiceAccountListResponse {
+        service_accounts: Vec::new(),
+    })
+}
+
+async fn service_accounts_create(
+    Json(_payload): Json<CreateServiceAccountRequest>,
+) -> (StatusCode, Json<ServiceAccountTokenResponse>) {
+    not_implemented(ServiceAccountTokenResponse {
+        token: String::new(),
+        service_account: empty_service_account(),
+    })
+}
+
+async fn service_accounts_get(
+    Path(_id): Path<String>,
+) -> (StatusCode, Json<ServiceAccountResponse>) {
+    not_implemented(empty_service_account())
+}
+
+async fn service_accounts_rotate(
+    Path(_id): Path<String>,
+) -> (StatusCode, Json<ServiceAccountTokenResponse>) {
+    not_implemented(ServiceAccountTokenResponse {
+        token: String::new(),
+        service_account: empty_service_account(),
+    })
+}
+
+async fn service_accounts_revoke(
+    Path(_id): Path<String>,
+) -> (StatusCode, Json<ServiceAccountResponse>) {
+    not_implemented(empty_service_account())
+}
+
+fn empty_invite() -> InviteResponse {
+    InviteResponse {
+        id: String::new(),
+        email: String::new(),
+        status: "pending",
+        group_ids: Vec::new(),
+        vaults: Vec::new(),
+        created_by_user_id: None,
+        expires_at: String::new(),
+        created_at: String::new(),
+        accepted_at: None,
+        accepted_user_id: None,
+        revoked_at: None,
+    }
+}
+
+async fn invites_list(
+    Query(_query): Query<ListInvitesQuery>,
+) -> (StatusCode, Json<InviteListResponse>) {
+    not_implemented(InviteListResponse {
+        invites: Vec::new(),
+    })
+}
+
+async fn invites_create(
+    Json(_payload): Json<CreateInviteRequest>,
+) -> (StatusCode, Json<InviteTokenResponse>) {
+    not_implemented(InviteTokenResponse {
+        token: String::new(),
+        invite: empty_invite(),
+    })
+}
+
+async fn invites_revoke(Path(_id): Path<String>) -> (StatusCode, Json<InviteResponse>) {
+    not_implemented(empty_invite())
+}
+
+fn empty_rotation_schedule() -> RotationScheduleResponse {
+    RotationScheduleResponse {
+        item_id: String::new(),
+        vault_id: String::new(),
+        schedule: String::new(),
+        max_age_seconds: 0,
+        password_policy: None,
+        last_rotated_at: String::new(),
+        due_at: String::new(),
+        last_started_at: None,
+        last_error: None,
+    }
+}
+
+async fn shared_rotation_schedule_get(
+    Path(_item_id): Path<String>,
+) -> (StatusCode, Json<RotationScheduleResponse>) {
+    not_implemented(empty_rotation_schedule())
+}
+
+async fn shared_rotation_schedule_set(
+    Path(_item_id): Path<String>,
+    Json(_payload): Json<SetRotationScheduleRequest>,
+) -> (StatusCode, Json<RotationScheduleResponse>) {
+    not_implemented(empty_rotation_schedule())
+}
+
+async fn shared_rotation_schedule_delete(Path(_item_id): Path<String>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+fn empty_rotation_hook() -> RotationHookResponse {
+    RotationHookResponse {
+        item_id: String::new(),
+        kind: String::new(),
+        verify: true,
+        target: String::new(),
+        created_at: String::new(),
+        updated_at: String::new(),
+    }
+}
+
+async fn shared_rotation_hook_get(
+    Path(_item_id): Path<String>,
+) -> (StatusCode, Json<RotationHookResponse>) {
+    not_implemented(empty_rotation_hook())
+}
+
+async fn shared_rotation_hook_set(
+    Path(_item_id): Path<String>,
+    Json(_payload): Json<SetRotationHookRequest>,
+) -> (StatusCode, Json<RotationHookResponse>) {
+    not_implemented(empty_rotation_hook())
+}
+
+async fn shared_rotation_hook_delete(Path(_item_id): Path<String>) -> StatusCode {
+    StatusCode::NOT_IMPLEMENTED
+}
+
+async fn vaults_rotation_overdue(
+    Path(_vault_id): Path<String>,
+    Query(_query): Query<OverdueQuery>,
+) -> (StatusCode, Json<OverdueResponse>) {
+    not_implemented(OverdueResponse {
+        vault_id: String::new(),
+        items: Vec::new(),
+    })
+}
@@ -101,8 +101,25 @@ async fn health(State(state): State<AppState>) -> impl IntoResponse {
     };
     components.insert("oidc".to_string(), oidc_status);
 
+    let sealed = state.server_master_key.is_sealed();
+    components.insert(
+        "seal".to_string(),
+        HealthComponent {
+            status: if !state.server_master_key.is_manual_unseal() {
+                "disabled"
+            } else if sealed {
+                "sealed"
+            } else {
+                "ok"
+            },
+            details: None,
+        },
+    );
+
     let status = if !db_ok {
         "db_error"
+    } else if sealed {
+        "sealed"
     } else if components
         .values()
         .any(|component| component.status == "degraded")
@@ -111,7 +128,7 @@ async fn health(State(state): State<AppState>) -> impl IntoResponse {
     } else {
         "ok"
     };
-    let http_status = if db_ok {
+    let http_status = if db_ok && !sealed {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
@@ -12,13 +12,30 @@ pub fn router() -> Router<AppState> {
         .merge(crate::domains::groups::http::v1::router())
         .merge(crate::domains::users::http::v1::router())
         .merge(crate::domains::secrets::http::v1::router())
+        .merge(crate::domains::dynamic::http::v1::router())
+        .merge(crate::domains::transit::http::v1::router())
+        .merge(crate::domains::ssh::http::v1::router())
+        .merge(crate::domains::pki::http::v1::router())
+        .merge(crate::domains::webhooks::http::v1::router())
+        .merge(crate::domains::audit::http::v1::router())
+        .merge(crate::domains::service_accounts::http::v1::router())
+        .merge(crate::domains::invites::http::v1::router())
+        .merge(crate::domains::system::http::v1::protected_router())
         .layer(middleware::from_fn(
             crate::domains::auth::core::auth_middleware,
+        ))
+        .layer(middleware::from_fn(
+            crate::domains::system::seal::sealed_middleware,
         ));
 
-    // Public routes do their own auth or are unauthenticated.
+    let auth = crate::domains::auth::http::v1::router().layer(middleware::from_fn(
+        crate::domains::system::seal::sealed_middleware,
+    ));
+
+    // Public routes do their own auth or are unauthenticated. System routes
+    // stay reachable while sealed so operators can unseal.
     Router::new()
-        .merge(crate::domains::auth::http::v1::router())
+        .merge(auth)
         .merge(crate::domains::system::http::v1::router())
         .merge(protected)
 }
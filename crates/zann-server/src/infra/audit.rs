@@ -1,6 +1,147 @@
-use zann_core::Identity;
+use sqlx_postgres::PgConnection;
+use uuid::Uuid;
+use zann_core::{AuditEvent, AuthSource, Identity};
+use zann_db::repo::VaultRepo;
 
-pub fn secrets_event(
+use crate::app::AppState;
+use crate::infra::{audit_chain, audit_sinks, metrics};
+
+/// A single audit record before it is bound to an actor and persisted.
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    category: &'static str,
+    action: String,
+    result: String,
+    vault_id: Option<Uuid>,
+    item_path: Option<String>,
+    target: Option<String>,
+    detail: Option<String>,
+    client_ip: Option<String>,
+    actor_user_id: Option<Uuid>,
+    actor_email: Option<String>,
+    actor_service_account_id: Option<Uuid>,
+}
+
+impl AuditEntry {
+    pub fn new(category: &'static str, action: &str, result: &str) -> Self {
+        Self {
+            category,
+            action: action.to_string(),
+            result: result.to_string(),
+            vault_id: None,
+            item_path: None,
+            target: None,
+            detail: None,
+            client_ip: None,
+            actor_user_id: None,
+            actor_email: None,
+            actor_service_account_id: None,
+        }
+    }
+
+    pub fn vault(mut self, vault_id: Uuid) -> Self {
+        self.vault_id = Some(vault_id);
+        self
+    }
+
+    pub fn path(mut self, path: &str) -> Self {
+        self.item_path = Some(path.to_string());
+        self
+    }
+
+    pub fn target(mut self, target: impl ToString) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    pub fn detail(mut self, detail: Option<&str>) -> Self {
+        self.detail = detail.map(str::to_string);
+        self
+    }
+
+    pub fn client_ip(mut self, client_ip: Option<&str>) -> Self {
+        self.client_ip = client_ip.map(str::to_string);
+        self
+    }
+
+    /// Sets the actor for events recorded before an [`Identity`] exists (logins,
+    /// registration). Failed logins only know the claimed email.
+    pub fn actor(mut self, user_id: Option<Uuid>, email: Option<&str>) -> Self {
+        self.actor_user_id = user_id;
+        self.actor_email = email.map(str::to_string);
+        self
+    }
+
+    pub fn service_account(mut self, service_account_id: Uuid) -> Self {
+        self.actor_service_account_id = Some(service_account_id);
+        self
+    }
+}
+
+/// Persists an audit event and mirrors it to the `audit` tracing target.
+///
+/// Write failures are logged and counted but never fail the caller: the operation
+/// being audited has already happened by the time this runs.
+pub async fn record(state: &AppState, identity: Option<&Identity>, entry: AuditEntry) {
+    let mut event = build_event(identity, entry);
+    emit_trace(&event);
+    let result = match state.db.begin().await {
+        Ok(mut tx) => match audit_chain::append(&mut tx, &mut event).await {
+            Ok(seq) => tx.commit().await.map(|()| seq),
+            Err(err) => Err(err),
+        },
+        Err(err) => Err(err),
+    };
+    match result {
+        Ok(seq) => {
+            event.seq = seq;
+            metrics::audit_write(&event.category, "ok");
+            audit_sinks::dispatch(&event);
+        }
+        Err(err) => {
+            metrics::audit_write(&event.category, "error");
+            tracing::error!(
+                event = "audit_write_failed",
+                category = %event.category,
+                action = %event.action,
+                error = %err,
+                "Failed to persist audit event"
+            );
+        }
+    }
+}
+
+/// Persists an audit event inside the caller's transaction. The returned event
+/// reaches traces and sinks only through [`PendingAudit::dispatch`], which the caller
+/// runs after a successful commit, so a rolled-back change never leaves the server.
+///
+/// Call it as the last statement before commit: appending takes the chain head lock,
+/// which every other audited write waits on until this transaction ends.
+pub async fn record_tx(
+    conn: &mut PgConnection,
+    identity: Option<&Identity>,
+    entry: AuditEntry,
+) -> Result<PendingAudit, sqlx_core::Error> {
+    let mut event = build_event(identity, entry);
+    event.seq = audit_chain::append(conn, &mut event).await?;
+    Ok(PendingAudit(event))
+}
+
+/// An audit event written by [`record_tx`] whose transaction has not committed yet.
+#[must_use = "dispatch the event once its transaction has committed"]
+pub struct PendingAudit(AuditEvent);
+
+impl PendingAudit {
+    /// Mirrors the committed event to the `audit` tracing target and the sinks.
+    pub fn dispatch(self) {
+        metrics::audit_write(&self.0.category, "ok");
+        emit_trace(&self.0);
+        audit_sinks::dispatch(&self.0);
+    }
+}
+
+pub async fn secrets_event(
+    state: &AppState,
     identity: &Identity,
     action: &str,
     result: &str,
@@ -8,17 +149,82 @@ pub fn secrets_event(
     path: &str,
     detail: Option<&str>,
 ) {
+    let mut entry = AuditEntry::new("secrets", action, result)
+        .path(path)
+        .detail(detail);
+    if let Some(vault_id) = resolve_vault_id(state, vault_id).await {
+        entry = entry.vault(vault_id);
+    } else {
+        entry = entry.target(vault_id);
+    }
+    record(state, Some(identity), entry).await;
+}
+
+/// Resolves a vault reference (id or slug) for audit purposes. Unknown slugs are not
+/// an error here; the caller keeps the raw reference as the event target instead.
+pub async fn resolve_vault_id(state: &AppState, vault_ref: &str) -> Option<Uuid> {
+    if let Ok(id) = Uuid::parse_str(vault_ref) {
+        return Some(id);
+    }
+    let repo = VaultRepo::new(&state.db);
+    repo.get_by_slug(vault_ref)
+        .await
+        .ok()
+        .flatten()
+        .map(|vault| vault.id)
+}
+
+fn build_event(identity: Option<&Identity>, entry: AuditEntry) -> AuditEvent {
+    AuditEvent {
+        seq: 0,
+        id: Uuid::now_v7(),
+        actor_user_id: identity
+            .map(|identity| identity.user_id)
+            .or(entry.actor_user_id),
+        actor_email: identity
+            .map(|identity| identity.email.clone())
+            .or(entry.actor_email),
+        actor_device_id: identity.and_then(|identity| identity.device_id),
+        actor_service_account_id: identity
+            .and_then(|identity| identity.service_account_id)
+            .or(entry.actor_service_account_id),
+        auth_source: identity.map(|identity| auth_source_label(&identity.source).to_string()),
+        category: entry.category.to_string(),
+        action: entry.action,
+        result: entry.result,
+        vault_id: entry.vault_id,
+        item_path: entry.item_path,
+        target: entry.target,
+        detail: entry.detail,
+        client_ip: entry.client_ip,
+        prev_hash: None,
+        hash: None,
+        created_at: audit_chain::now(),
+    }
+}
+
+fn auth_source_label(source: &AuthSource) -> &'static str {
+    match source {
+        AuthSource::Internal => "internal",
+        AuthSource::Device => "device",
+        AuthSource::ServiceAccount => "service_account",
+        AuthSource::Oidc { .. } => "oidc",
+    }
+}
+
+fn emit_trace(event: &AuditEvent) {
     tracing::info!(
         event = "audit",
-        category = "secrets",
-        action = action,
-        result = result,
-        vault_id = vault_id,
-        path = path,
-        user_id = %identity.user_id,
-        device_id = ?identity.device_id,
-        service_account_id = ?identity.service_account_id,
-        auth_source = ?identity.source,
-        detail = ?detail,
+        category = %event.category,
+        action = %event.action,
+        result = %event.result,
+        vault_id = ?event.vault_id,
+        path = ?event.item_path,
+        target = ?event.target,
+        user_id = ?event.actor_user_id,
+        device_id = ?event.actor_device_id,
+        service_account_id = ?event.actor_service_account_id,
+        auth_source = ?event.auth_source,
+        detail = ?event.detail,
     );
 }
@@ -0,0 +1,321 @@
+use base64::Engine;
+use chrono::{DateTime, SubsecRound, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+use sqlx_postgres::PgConnection;
+use uuid::Uuid;
+use zann_core::{AuditCheckpoint, AuditEvent};
+use zann_db::repo::{AuditChainHead, AuditCheckpointRepo, AuditEventRepo};
+use zann_db::PgPool;
+
+/// `prev_hash` of the first chained record.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+const EVENT_HASH_DOMAIN: &[u8] = b"zann-audit:v1";
+const VERIFY_BATCH_SIZE: i64 = 1000;
+
+/// Links `event` to the current chain head and inserts it. Must run inside a transaction:
+/// the head row stays locked until commit, which keeps concurrent appends in `seq` order.
+///
+/// Every audited write in the cluster queues on that one row lock. This is the accepted
+/// cost of a single linear chain; callers keep the wait short by appending as the last
+/// statement before they commit.
+pub async fn append(
+    conn: &mut PgConnection,
+    event: &mut AuditEvent,
+) -> Result<i64, sqlx_core::Error> {
+    let head = AuditEventRepo::lock_chain_head(conn, GENESIS_HASH).await?;
+    let hash = event_hash(&head.hash, event);
+    event.prev_hash = Some(head.hash);
+    event.hash = Some(hash.clone());
+    let seq = AuditEventRepo::create_in(conn, event).await?;
+    AuditEventRepo::advance_chain_head(conn, &AuditChainHead { seq, hash }).await?;
+    Ok(seq)
+}
+
+/// SHA-256 over the previous hash and every persisted field except `seq`, which is only
+/// known after insert. Fields are length-prefixed so values cannot bleed into each other.
+pub fn event_hash(prev_hash: &str, event: &AuditEvent) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(EVENT_HASH_DOMAIN);
+    update_field(&mut hasher, Some(prev_hash));
+    update_field(&mut hasher, Some(&event.id.to_string()));
+    update_field(&mut hasher, uuid_field(event.actor_user_id).as_deref());
+    update_field(&mut hasher, event.actor_email.as_deref());
+    update_field(&mut hasher, uuid_field(event.actor_device_id).as_deref());
+    update_field(
+        &mut hasher,
+        uuid_field(event.actor_service_account_id).as_deref(),
+    );
+    update_field(&mut hasher, event.auth_source.as_deref());
+    update_field(&mut hasher, Some(&event.category));
+    update_field(&mut hasher, Some(&event.action));
+    update_field(&mut hasher, Some(&event.result));
+    update_field(&mut hasher, uuid_field(event.vault_id).as_deref());
+    update_field(&mut hasher, event.item_path.as_deref());
+    update_field(&mut hasher, event.target.as_deref());
+    update_field(&mut hasher, event.detail.as_deref());
+    update_field(&mut hasher, event.client_ip.as_deref());
+    hasher.update(event.created_at.timestamp_micros().to_be_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn update_field(hasher: &mut Sha256, value: Option<&str>) {
+    match value {
+        None => hasher.update([0u8]),
+        Some(value) => {
+            hasher.update([1u8]);
+            hasher.update((value.len() as u64).to_be_bytes());
+            hasher.update(value.as_bytes());
+        }
+    }
+}
+
+fn uuid_field(value: Option<Uuid>) -> Option<String> {
+    value.map(|value| value.to_string())
+}
+
+pub fn checkpoint_message(seq: i64, hash: &str, created_at: DateTime<Utc>) -> String {
+    format!(
+        "zann-audit-checkpoint:v1:{seq}:{hash}:{}",
+        created_at.timestamp_micros()
+    )
+}
+
+/// Signs the current chain head unless the latest checkpoint already covers it.
+pub async fn write_checkpoint(
+    pool: &PgPool,
+    identity_key: &SigningKey,
+) -> Result<Option<AuditCheckpoint>, sqlx_core::Error> {
+    let Some(head) = AuditEventRepo::new(pool).chain_head().await? else {
+        return Ok(None);
+    };
+    if head.seq == 0 {
+        return Ok(None);
+    }
+    let repo = AuditCheckpointRepo::new(pool);
+    if let Some(latest) = repo.latest().await? {
+        if latest.seq >= head.seq {
+            return Ok(None);
+        }
+    }
+
+    let created_at = now();
+    let message = checkpoint_message(head.seq, &head.hash, created_at);
+    let signature = identity_key.sign(message.as_bytes());
+    let engine = base64::engine::general_purpose::STANDARD;
+    let checkpoint = AuditCheckpoint {
+        id: Uuid::now_v7(),
+        seq: head.seq,
+        hash: head.hash,
+        public_key: engine.encode(identity_key.verifying_key().to_bytes()),
+        signature: engine.encode(signature.to_bytes()),
+        created_at,
+    };
+    repo.create(&checkpoint).await?;
+    Ok(Some(checkpoint))
+}
+
+/// Current time at the precision Postgres stores, so hashes computed before insert match
+/// the values read back later.
+pub fn now() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(6)
+}
+
+#[derive(Debug, Default)]
+pub struct ChainReport {
+    /// Records written before chaining was introduced.
+    pub unchained: u64,
+    pub verified: u64,
+    pub checkpoints: u64,
+    pub head_seq: Option<i64>,
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ChainBreak {
+    /// A record without a hash after the chain started.
+    #[error("seq {seq}: record has no hash")]
+    Unchained { seq: i64 },
+    /// Rows were deleted or reordered before this record.
+    #[error("seq {seq}: prev_hash does not match the preceding record")]
+    PrevHashMismatch { seq: i64 },
+    #[error("seq {seq}: record content does not match its hash")]
+    HashMismatch { seq: i64 },
+    /// The stored chain head points past the last record (tail truncated).
+    #[error("chain head at seq {head_seq} but last chained record is {last_seq:?}")]
+    HeadMismatch {
+        head_seq: i64,
+        last_seq: Option<i64>,
+    },
+    #[error("checkpoint {checkpoint_id}: record at seq {seq} is missing or altered")]
+    CheckpointMismatch { checkpoint_id: Uuid, seq: i64 },
+    #[error("checkpoint {checkpoint_id} (seq {seq}): signature not valid for any trusted key")]
+    CheckpointSignature { checkpoint_id: Uuid, seq: i64 },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VerifyError {
+    #[error("db_error: {0}")]
+    Db(#[from] sqlx_core::Error),
+    #[error("audit chain broken: {0}")]
+    Broken(ChainBreak),
+}
+
+/// Walks the whole chain in `seq` order, then checks every checkpoint against the
+/// records it covers. Returns the first broken link found.
+pub async fn verify(
+    pool: &PgPool,
+    trusted_keys: &[VerifyingKey],
+) -> Result<ChainReport, VerifyError> {
+    let repo = AuditEventRepo::new(pool);
+    let mut report = ChainReport::default();
+    let mut prev_hash: Option<String> = None;
+    let mut after_seq = 0;
+    loop {
+        let events = repo.list_after(after_seq, VERIFY_BATCH_SIZE).await?;
+        let Some(last) = events.last() else {
+            break;
+        };
+        after_seq = last.seq;
+        for event in &events {
+            let Some(hash) = event.hash.as_deref() else {
+                if prev_hash.is_some() {
+                    return Err(VerifyError::Broken(ChainBreak::Unchained {
+                        seq: event.seq,
+                    }));
+                }
+                report.unchained += 1;
+                continue;
+            };
+            let expected_prev = prev_hash.as_deref().unwrap_or(GENESIS_HASH);
+            if event.prev_hash.as_deref() != Some(expected_prev) {
+                return Err(VerifyError::Broken(ChainBreak::PrevHashMismatch {
+                    seq: event.seq,
+                }));
+            }
+            if event_hash(expected_prev, event) != hash {
+                return Err(VerifyError::Broken(ChainBreak::HashMismatch {
+                    seq: event.seq,
+                }));
+            }
+            prev_hash = Some(hash.to_string());
+            report.verified += 1;
+            report.head_seq = Some(event.seq);
+        }
+    }
+
+    if let Some(head) = repo.chain_head().await? {
+        if head.seq != report.head_seq.unwrap_or(0) {
+            return Err(VerifyError::Broken(ChainBreak::HeadMismatch {
+                head_seq: head.seq,
+                last_seq: report.head_seq,
+            }));
+        }
+    }
+
+    let engine = base64::engine::general_purpose::STANDARD;
+    for checkpoint in AuditCheckpointRepo::new(pool).list().await? {
+        let message = checkpoint_message(checkpoint.seq, &checkpoint.hash, checkpoint.created_at);
+        let signature = engine
+            .decode(&checkpoint.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok());
+        let signed = signature.is_some_and(|signature| {
+            trusted_keys
+                .iter()
+                .any(|key| key.verify(message.as_bytes(), &signature).is_ok())
+        });
+        if !signed {
+            return Err(VerifyError::Broken(ChainBreak::CheckpointSignature {
+                checkpoint_id: checkpoint.id,
+                seq: checkpoint.seq,
+            }));
+        }
+        let matches = repo
+            .get_by_seq(checkpoint.seq)
+            .await?
+            .and_then(|event| event.hash)
+            .is_some_and(|hash| hash == checkpoint.hash);
+        if !matches {
+            return Err(VerifyError::Broken(ChainBreak::CheckpointMismatch {
+                checkpoint_id: checkpoint.id,
+                seq: checkpoint.seq,
+            }));
+        }
+        report.checkpoints += 1;
+    }
+
+    Ok(report)
+}
+
+/// Audit event fixture shared by the chain and sink tests.
+#[cfg(test)]
+pub(crate) fn sample_event() -> AuditEvent {
+    AuditEvent {
+        seq: 0,
+        id: Uuid::now_v7(),
+        actor_user_id: Some(Uuid::now_v7()),
+        actor_email: Some("alice@example.com".to_string()),
+        actor_device_id: None,
+        actor_service_account_id: None,
+        auth_source: Some("internal".to_string()),
+        category: "secrets".to_string(),
+        action: "get".to_string(),
+        result: "ok".to_string(),
+        vault_id: Some(Uuid::now_v7()),
+        item_path: Some("db/password".to_string()),
+        target: None,
+        detail: None,
+        client_ip: None,
+        prev_hash: None,
+        hash: None,
+        created_at: now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_hash_is_stable_and_ignores_seq() {
+        let mut event = sample_event();
+        let first = event_hash(GENESIS_HASH, &event);
+        event.seq = 42;
+        event.hash = Some("ignored".to_string());
+        assert_eq!(first, event_hash(GENESIS_HASH, &event));
+        assert_eq!(first.len(), 64);
+    }
+
+    #[test]
+    fn event_hash_changes_with_content_and_prev() {
+        let event = sample_event();
+        let base = event_hash(GENESIS_HASH, &event);
+        assert_ne!(base, event_hash(&base, &event));
+
+        let mut altered = event.clone();
+        altered.result = "forbidden".to_string();
+        assert_ne!(base, event_hash(GENESIS_HASH, &altered));
+    }
+
+    #[test]
+    fn event_hash_distinguishes_field_boundaries() {
+        let mut left = sample_event();
+        left.item_path = Some("ab".to_string());
+        left.target = Some("c".to_string());
+        let mut right = left.clone();
+        right.item_path = Some("a".to_string());
+        right.target = Some("bc".to_string());
+        assert_ne!(
+            event_hash(GENESIS_HASH, &left),
+            event_hash(GENESIS_HASH, &right)
+        );
+
+        let mut empty = left.clone();
+        empty.detail = Some(String::new());
+        assert_ne!(
+            event_hash(GENESIS_HASH, &left),
+            event_hash(GENESIS_HASH, &empty)
+        );
+    }
+}
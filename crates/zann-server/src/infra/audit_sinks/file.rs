@@ -0,0 +1,206 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use zann_core::AuditEvent;
+
+use super::{AuditSink, DeliveryFuture};
+use crate::config::FileSinkConfig;
+
+/// Append-only JSON-lines file, rotated by size to `<path>.1` .. `<path>.<max_files>`.
+pub struct FileSink {
+    state: Arc<Mutex<FileState>>,
+}
+
+struct FileState {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: u32,
+    file: Option<File>,
+    size: u64,
+}
+
+impl FileSink {
+    pub fn new(config: &FileSinkConfig) -> Result<Self, String> {
+        validate(config)?;
+        Ok(Self {
+            state: Arc::new(Mutex::new(FileState {
+                path: PathBuf::from(&config.path),
+                max_bytes: config.max_bytes,
+                max_files: config.max_files,
+                file: None,
+                size: 0,
+            })),
+        })
+    }
+}
+
+impl AuditSink for FileSink {
+    fn kind(&self) -> &'static str {
+        "file"
+    }
+
+    fn deliver<'a>(&'a self, event: &'a AuditEvent) -> DeliveryFuture<'a> {
+        Box::pin(async move {
+            let mut line =
+                serde_json::to_vec(event).map_err(|err| format!("encode event: {err}"))?;
+            line.push(b'\n');
+            let state = self.state.clone();
+            tokio::task::spawn_blocking(move || {
+                let mut state = state
+                    .lock()
+                    .map_err(|_| "file sink lock poisoned".to_string())?;
+                state.append(&line)
+            })
+            .await
+            .map_err(|err| format!("file sink task failed: {err}"))?
+        })
+    }
+}
+
+impl FileState {
+    fn append(&mut self, line: &[u8]) -> Result<(), String> {
+        if self.file.is_some() && self.size > 0 && self.size + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        if self.file.is_none() {
+            self.open()?;
+        }
+        let Some(file) = self.file.as_mut() else {
+            return Err("file sink not open".to_string());
+        };
+        if let Err(err) = file.write_all(line) {
+            self.file = None;
+            return Err(format!("write {}: {err}", self.path.display()));
+        }
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    fn open(&mut self) -> Result<(), String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o600)
+            .open(&self.path)
+            .map_err(|err| format!("open {}: {err}", self.path.display()))?;
+        self.size = file
+            .metadata()
+            .map_err(|err| format!("stat {}: {err}", self.path.display()))?
+            .len();
+        self.file = Some(file);
+        if self.size >= self.max_bytes {
+            self.rotate()?;
+            return self.open();
+        }
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<(), String> {
+        self.file = None;
+        self.size = 0;
+        if self.max_files == 0 {
+            return fs::remove_file(&self.path)
+                .map_err(|err| format!("remove {}: {err}", self.path.display()));
+        }
+        for index in (1..self.max_files).rev() {
+            let from = rotated_path(&self.path, index);
+            if from.exists() {
+                let to = rotated_path(&self.path, index + 1);
+                fs::rename(&from, &to)
+                    .map_err(|err| format!("rotate {}: {err}", from.display()))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))
+            .map_err(|err| format!("rotate {}: {err}", self.path.display()))
+    }
+}
+
+fn rotated_path(path: &Path, index: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+pub(super) fn validate(config: &FileSinkConfig) -> Result<(), String> {
+    if config.path.trim().is_empty() {
+        return Err("audit.sinks: file path is required".to_string());
+    }
+    if config.max_bytes == 0 {
+        return Err("audit.sinks: file max_bytes must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::audit_chain::sample_event;
+    use uuid::Uuid;
+
+    fn event(seq: i64) -> AuditEvent {
+        AuditEvent {
+            seq,
+            ..sample_event()
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zann-audit-{}", Uuid::now_v7()));
+        fs::create_dir_all(&dir).expect("temp dir");
+        dir.join(name)
+    }
+
+    #[tokio::test]
+    async fn appends_json_lines() {
+        let path = temp_path("audit.jsonl");
+        let sink = FileSink::new(&FileSinkConfig {
+            path: path.display().to_string(),
+            max_bytes: 1024 * 1024,
+            max_files: 2,
+        })
+        .expect("sink");
+        sink.deliver(&event(1)).await.expect("deliver");
+        sink.deliver(&event(2)).await.expect("deliver");
+
+        let contents = fs::read_to_string(&path).expect("read");
+        let seqs: Vec<i64> = contents
+            .lines()
+            .map(|line| {
+                let value: serde_json::Value = serde_json::from_str(line).expect("json line");
+                value["seq"].as_i64().expect("seq")
+            })
+            .collect();
+        assert_eq!(seqs, vec![1, 2]);
+        let _ = fs::remove_dir_all(path.parent().expect("dir"));
+    }
+
+    #[tokio::test]
+    async fn rotates_by_size_and_keeps_max_files() {
+        let path = temp_path("audit.jsonl");
+        let line_len = serde_json::to_vec(&event(1)).expect("json").len() as u64 + 1;
+        let sink = FileSink::new(&FileSinkConfig {
+            path: path.display().to_string(),
+            max_bytes: line_len + 1,
+            max_files: 2,
+        })
+        .expect("sink");
+        for seq in 1..=4 {
+            sink.deliver(&event(seq)).await.expect("deliver");
+        }
+
+        let read_seq = |path: &Path| -> i64 {
+            let contents = fs::read_to_string(path).expect("read");
+            let value: serde_json::Value =
+                serde_json::from_str(contents.lines().next().expect("line")).expect("json");
+            value["seq"].as_i64().expect("seq")
+        };
+        assert_eq!(read_seq(&path), 4);
+        assert_eq!(read_seq(&rotated_path(&path, 1)), 3);
+        assert_eq!(read_seq(&rotated_path(&path, 2)), 2);
+        assert!(!rotated_path(&path, 3).exists());
+        let _ = fs::remove_dir_all(path.parent().expect("dir"));
+    }
+}
@@ -0,0 +1,141 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+
+use tokio::sync::mpsc;
+use zann_core::AuditEvent;
+
+use crate::config::{AuditConfig, AuditSinkConfig};
+use crate::infra::metrics;
+
+mod file;
+mod syslog;
+mod webhook;
+
+pub use file::FileSink;
+pub use syslog::SyslogSink;
+pub use webhook::WebhookSink;
+
+pub type DeliveryFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// A destination that receives every persisted audit event.
+///
+/// Each sink runs on its own worker task behind a bounded queue, so a slow or unreachable
+/// sink never delays request handling or the other sinks.
+pub trait AuditSink: Send + Sync + 'static {
+    /// Stable label used in logs and metrics.
+    fn kind(&self) -> &'static str;
+
+    fn deliver<'a>(&'a self, event: &'a AuditEvent) -> DeliveryFuture<'a>;
+}
+
+struct SinkHandle {
+    kind: &'static str,
+    tx: mpsc::Sender<Arc<AuditEvent>>,
+}
+
+pub struct AuditSinks {
+    handles: Vec<SinkHandle>,
+}
+
+static SINKS: OnceLock<AuditSinks> = OnceLock::new();
+
+impl AuditSinks {
+    /// Spawns one worker per sink. Must be called from within a Tokio runtime.
+    pub fn start(sinks: Vec<Arc<dyn AuditSink>>, queue_size: usize) -> Self {
+        let handles = sinks
+            .into_iter()
+            .map(|sink| {
+                let (tx, rx) = mpsc::channel(queue_size.max(1));
+                let kind = sink.kind();
+                tokio::spawn(run_worker(sink, rx));
+                SinkHandle { kind, tx }
+            })
+            .collect();
+        Self { handles }
+    }
+
+    pub fn dispatch(&self, event: &AuditEvent) {
+        if self.handles.is_empty() {
+            return;
+        }
+        let event = Arc::new(event.clone());
+        for handle in &self.handles {
+            if handle.tx.try_send(event.clone()).is_err() {
+                metrics::audit_sink_event(handle.kind, "dropped");
+                tracing::warn!(
+                    event = "audit_sink_dropped",
+                    sink = handle.kind,
+                    action = %event.action,
+                    "Audit sink queue full or closed"
+                );
+            }
+        }
+    }
+}
+
+async fn run_worker(sink: Arc<dyn AuditSink>, mut rx: mpsc::Receiver<Arc<AuditEvent>>) {
+    while let Some(event) = rx.recv().await {
+        match sink.deliver(&event).await {
+            Ok(()) => metrics::audit_sink_event(sink.kind(), "ok"),
+            Err(err) => {
+                metrics::audit_sink_event(sink.kind(), "error");
+                tracing::error!(
+                    event = "audit_sink_failed",
+                    sink = sink.kind(),
+                    seq = event.seq,
+                    error = %err,
+                    "Audit sink delivery failed"
+                );
+            }
+        }
+    }
+}
+
+/// Builds the configured sinks and installs them for [`dispatch`]. Later calls are ignored.
+pub fn init(config: &AuditConfig) -> Result<(), String> {
+    if config.sinks.is_empty() || SINKS.get().is_some() {
+        return Ok(());
+    }
+    let sinks = build(config)?;
+    let kinds: Vec<&'static str> = sinks.iter().map(|sink| sink.kind()).collect();
+    let _ = SINKS.set(AuditSinks::start(sinks, config.sink_queue_size));
+    tracing::info!(event = "audit_sinks_started", sinks = ?kinds);
+    Ok(())
+}
+
+pub fn build(config: &AuditConfig) -> Result<Vec<Arc<dyn AuditSink>>, String> {
+    config
+        .sinks
+        .iter()
+        .map(|sink| -> Result<Arc<dyn AuditSink>, String> {
+            Ok(match sink {
+                AuditSinkConfig::Syslog(config) => Arc::new(SyslogSink::new(config)?),
+                AuditSinkConfig::File(config) => Arc::new(FileSink::new(config)?),
+                AuditSinkConfig::Webhook(config) => Arc::new(WebhookSink::new(config)?),
+            })
+        })
+        .collect()
+}
+
+/// Checks sink settings without opening sockets or files.
+pub fn validate(config: &AuditConfig) -> Option<String> {
+    for sink in &config.sinks {
+        let result = match sink {
+            AuditSinkConfig::Syslog(config) => syslog::validate(config),
+            AuditSinkConfig::File(config) => file::validate(config),
+            AuditSinkConfig::Webhook(config) => webhook::validate(config),
+        };
+        if let Err(err) = result {
+            return Some(err);
+        }
+    }
+    None
+}
+
+/// Forwards a persisted event to the installed sinks, if any.
+pub fn dispatch(event: &AuditEvent) {
+    if let Some(sinks) = SINKS.get() {
+        sinks.dispatch(event);
+    }
+}
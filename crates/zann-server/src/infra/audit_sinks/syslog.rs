@@ -0,0 +1,318 @@
+use chrono::SecondsFormat;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::Mutex;
+use zann_core::AuditEvent;
+
+use super::{AuditSink, DeliveryFuture};
+use crate::config::{SyslogProtocol, SyslogSinkConfig};
+
+/// IANA private enterprise number reserved for documentation (RFC 5612), used as the
+/// structured-data ID suffix.
+const SD_ID: &str = "zann@32473";
+
+const SEVERITY_WARNING: u8 = 4;
+const SEVERITY_INFORMATIONAL: u8 = 6;
+
+/// RFC 5424 syslog over UDP (one message per datagram) or TCP (RFC 6587 octet counting).
+pub struct SyslogSink {
+    address: String,
+    protocol: SyslogProtocol,
+    facility: u8,
+    app_name: String,
+    hostname: String,
+    tcp: Mutex<Option<TcpStream>>,
+}
+
+impl SyslogSink {
+    pub fn new(config: &SyslogSinkConfig) -> Result<Self, String> {
+        validate(config)?;
+        let facility = parse_facility(&config.facility)
+            .ok_or_else(|| format!("unknown syslog facility: {}", config.facility))?;
+        let hostname = config
+            .hostname
+            .clone()
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .unwrap_or_default();
+        Ok(Self {
+            address: config.address.clone(),
+            protocol: config.protocol,
+            facility,
+            app_name: header_field(&config.app_name, 48),
+            hostname: header_field(&hostname, 255),
+            tcp: Mutex::new(None),
+        })
+    }
+
+    async fn send_udp(&self, message: &[u8]) -> Result<(), String> {
+        let target = tokio::net::lookup_host(&self.address)
+            .await
+            .map_err(|err| format!("resolve {}: {err}", self.address))?
+            .next()
+            .ok_or_else(|| format!("resolve {}: no addresses", self.address))?;
+        let bind = if target.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(bind)
+            .await
+            .map_err(|err| format!("bind: {err}"))?;
+        socket
+            .send_to(message, target)
+            .await
+            .map(|_| ())
+            .map_err(|err| format!("send: {err}"))
+    }
+
+    async fn send_tcp(&self, message: &[u8]) -> Result<(), String> {
+        let mut frame = format!("{} ", message.len()).into_bytes();
+        frame.extend_from_slice(message);
+
+        let mut guard = self.tcp.lock().await;
+        // One reconnect attempt covers collectors that closed an idle connection.
+        for _ in 0..2 {
+            if guard.is_none() {
+                let stream = TcpStream::connect(&self.address)
+                    .await
+                    .map_err(|err| format!("connect {}: {err}", self.address))?;
+                *guard = Some(stream);
+            }
+            if let Some(stream) = guard.as_mut() {
+                match stream.write_all(&frame).await {
+                    Ok(()) => return Ok(()),
+                    Err(err) => {
+                        tracing::debug!(event = "audit_syslog_reconnect", error = %err);
+                        *guard = None;
+                    }
+                }
+            }
+        }
+        Err(format!("write to {} failed", self.address))
+    }
+}
+
+impl AuditSink for SyslogSink {
+    fn kind(&self) -> &'static str {
+        "syslog"
+    }
+
+    fn deliver<'a>(&'a self, event: &'a AuditEvent) -> DeliveryFuture<'a> {
+        Box::pin(async move {
+            let message = format_message(self.facility, &self.hostname, &self.app_name, event)?;
+            match self.protocol {
+                SyslogProtocol::Udp => self.send_udp(message.as_bytes()).await,
+                SyslogProtocol::Tcp => self.send_tcp(message.as_bytes()).await,
+            }
+        })
+    }
+}
+
+pub(super) fn validate(config: &SyslogSinkConfig) -> Result<(), String> {
+    if parse_facility(&config.facility).is_none() {
+        return Err(format!(
+            "audit.sinks: unknown syslog facility '{}'",
+            config.facility
+        ));
+    }
+    match config.address.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(()),
+        _ => Err(format!(
+            "audit.sinks: syslog address '{}' must be host:port",
+            config.address
+        )),
+    }
+}
+
+fn parse_facility(value: &str) -> Option<u8> {
+    let facility = match value.trim().to_ascii_lowercase().as_str() {
+        "kern" => 0,
+        "user" => 1,
+        "mail" => 2,
+        "daemon" => 3,
+        "auth" => 4,
+        "syslog" => 5,
+        "lpr" => 6,
+        "news" => 7,
+        "uucp" => 8,
+        "cron" => 9,
+        "authpriv" => 10,
+        "ftp" => 11,
+        "local0" => 16,
+        "local1" => 17,
+        "local2" => 18,
+        "local3" => 19,
+        "local4" => 20,
+        "local5" => 21,
+        "local6" => 22,
+        "local7" => 23,
+        _ => return None,
+    };
+    Some(facility)
+}
+
+fn severity(result: &str) -> u8 {
+    match result {
+        "ok" | "created" | "updated" | "existing" => SEVERITY_INFORMATIONAL,
+        _ => SEVERITY_WARNING,
+    }
+}
+
+fn format_message(
+    facility: u8,
+    hostname: &str,
+    app_name: &str,
+    event: &AuditEvent,
+) -> Result<String, String> {
+    let pri = u16::from(facility) * 8 + u16::from(severity(&event.result));
+    let timestamp = event
+        .created_at
+        .to_rfc3339_opts(SecondsFormat::Micros, true);
+    let msg_id = header_field(&event.category, 32);
+    let body = serde_json::to_string(event).map_err(|err| format!("encode event: {err}"))?;
+    Ok(format!(
+        "<{pri}>1 {timestamp} {hostname} {app_name} {procid} {msg_id} {sd} {body}",
+        procid = std::process::id(),
+        sd = structured_data(event),
+    ))
+}
+
+fn structured_data(event: &AuditEvent) -> String {
+    let mut sd = format!("[{SD_ID}");
+    let mut param = |name: &str, value: Option<String>| {
+        if let Some(value) = value {
+            sd.push_str(&format!(" {name}=\"{}\"", escape_param(&value)));
+        }
+    };
+    param("seq", Some(event.seq.to_string()));
+    param("id", Some(event.id.to_string()));
+    param("action", Some(event.action.clone()));
+    param("result", Some(event.result.clone()));
+    param("actor", event.actor_email.clone());
+    param(
+        "actor_user_id",
+        event.actor_user_id.map(|id| id.to_string()),
+    );
+    param(
+        "service_account_id",
+        event.actor_service_account_id.map(|id| id.to_string()),
+    );
+    param("vault_id", event.vault_id.map(|id| id.to_string()));
+    param("path", event.item_path.clone());
+    param("client_ip", event.client_ip.clone());
+    sd.push(']');
+    sd
+}
+
+/// Escapes `"`, `\` and `]` inside an SD-PARAM value (RFC 5424 section 6.3.3).
+fn escape_param(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if matches!(ch, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Header fields are restricted to printable US-ASCII; empty values become the NILVALUE.
+fn header_field(value: &str, max_len: usize) -> String {
+    let cleaned: String = value
+        .chars()
+        .filter(|ch| ch.is_ascii_graphic())
+        .take(max_len)
+        .collect();
+    if cleaned.is_empty() {
+        "-".to_string()
+    } else {
+        cleaned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::audit_chain::sample_event;
+    use chrono::TimeZone;
+
+    fn event(result: &str) -> AuditEvent {
+        AuditEvent {
+            seq: 7,
+            actor_email: Some("ops@example.com".to_string()),
+            result: result.to_string(),
+            item_path: Some("db/\"main\"]".to_string()),
+            created_at: chrono::Utc
+                .with_ymd_and_hms(2025, 1, 2, 3, 4, 5)
+                .single()
+                .expect("timestamp"),
+            ..sample_event()
+        }
+    }
+
+    #[test]
+    fn formats_rfc5424_header_and_structured_data() {
+        let message = format_message(10, "vault-1", "zann-server", &event("ok")).expect("format");
+        let pid = std::process::id();
+        assert!(
+            message.starts_with(&format!(
+                "<86>1 2025-01-02T03:04:05.000000Z vault-1 zann-server {pid} secrets [zann@32473 seq=\"7\""
+            )),
+            "{message}"
+        );
+        assert!(message.contains(r#"path="db/\"main\"\]""#), "{message}");
+        assert!(message.contains(r#"actor="ops@example.com""#));
+        assert!(message.ends_with('}'));
+    }
+
+    #[test]
+    fn failures_use_warning_severity() {
+        let message = format_message(4, "-", "zann-server", &event("forbidden")).expect("format");
+        assert!(message.starts_with("<36>1 "), "{message}");
+    }
+
+    #[test]
+    fn header_fields_drop_non_printable_characters() {
+        assert_eq!(header_field("my host", 255), "myhost");
+        assert_eq!(header_field("", 255), "-");
+        assert_eq!(header_field("abcdef", 3), "abc");
+    }
+
+    #[test]
+    fn validate_rejects_bad_address_and_facility() {
+        let mut config = SyslogSinkConfig {
+            address: "siem.example.com:514".to_string(),
+            protocol: SyslogProtocol::Udp,
+            facility: "local3".to_string(),
+            app_name: "zann-server".to_string(),
+            hostname: None,
+        };
+        assert!(validate(&config).is_ok());
+        config.address = "siem.example.com".to_string();
+        assert!(validate(&config).is_err());
+        config.address = "[::1]:514".to_string();
+        assert!(validate(&config).is_ok());
+        config.facility = "nope".to_string();
+        assert!(validate(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn delivers_over_udp() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").await.expect("bind");
+        let config = SyslogSinkConfig {
+            address: receiver.local_addr().expect("addr").to_string(),
+            protocol: SyslogProtocol::Udp,
+            facility: "auth".to_string(),
+            app_name: "zann-server".to_string(),
+            hostname: Some("test-host".to_string()),
+        };
+        let sink = SyslogSink::new(&config).expect("sink");
+        sink.deliver(&event("ok")).await.expect("deliver");
+
+        let mut buf = vec![0u8; 4096];
+        let len = receiver.recv(&mut buf).await.expect("recv");
+        let message = String::from_utf8_lossy(&buf[..len]);
+        assert!(message.starts_with("<38>1 "), "{message}");
+        assert!(message.contains(" test-host zann-server "));
+    }
+}
@@ -0,0 +1,253 @@
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
+use serde::Serialize;
+use zann_core::AuditEvent;
+
+use super::{AuditSink, DeliveryFuture};
+use crate::config::WebhookSinkConfig;
+
+/// POSTs each event as JSON, retrying transport errors, 429 and 5xx with exponential
+/// backoff. Other 4xx responses are treated as permanent failures.
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl WebhookSink {
+    pub fn new(config: &WebhookSinkConfig) -> Result<Self, String> {
+        validate(config)?;
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| format!("invalid webhook header name: {name}"))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| format!("invalid webhook header value for {name}"))?;
+            headers.insert(name, value);
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds.max(1)))
+            .default_headers(headers)
+            .build()
+            .map_err(|err| format!("webhook client: {err}"))?;
+        Ok(Self {
+            client,
+            url: config.url.clone(),
+            max_retries: config.max_retries,
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(
+                config.max_backoff_ms.max(config.initial_backoff_ms),
+            ),
+        })
+    }
+
+    async fn post<T: Serialize + ?Sized>(&self, body: &T) -> Result<(), Attempt> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|err| Attempt::Retry(err.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            Err(Attempt::Retry(format!("status {status}")))
+        } else {
+            Err(Attempt::Fatal(format!("status {status}")))
+        }
+    }
+}
+
+enum Attempt {
+    Retry(String),
+    Fatal(String),
+}
+
+impl AuditSink for WebhookSink {
+    fn kind(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn deliver<'a>(&'a self, event: &'a AuditEvent) -> DeliveryFuture<'a> {
+        Box::pin(self.send(event))
+    }
+}
+
+impl WebhookSink {
+    /// POSTs `body` as JSON with the sink's retry policy.
+    pub async fn send<T: Serialize + ?Sized>(&self, body: &T) -> Result<(), String> {
+        let mut attempt = 0;
+        loop {
+            match self.post(body).await {
+                Ok(()) => return Ok(()),
+                Err(Attempt::Fatal(err)) => return Err(err),
+                Err(Attempt::Retry(err)) if attempt >= self.max_retries => {
+                    return Err(format!("giving up after {} attempts: {err}", attempt + 1));
+                }
+                Err(Attempt::Retry(err)) => {
+                    let delay = backoff(self.initial_backoff, self.max_backoff, attempt);
+                    tracing::debug!(
+                        event = "webhook_retry",
+                        attempt = attempt + 1,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %err
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+fn backoff(initial: Duration, max: Duration, attempt: u32) -> Duration {
+    initial
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(max)
+}
+
+pub(super) fn validate(config: &WebhookSinkConfig) -> Result<(), String> {
+    let url = config.url.trim();
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(format!(
+            "audit.sinks: webhook url '{}' must be http(s)",
+            config.url
+        ));
+    }
+    reqwest::Url::parse(url)
+        .map_err(|_| format!("audit.sinks: invalid webhook url '{}'", config.url))?;
+    for (name, value) in &config.headers {
+        if HeaderName::from_bytes(name.as_bytes()).is_err() || HeaderValue::from_str(value).is_err()
+        {
+            return Err(format!("audit.sinks: invalid webhook header '{name}'"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::audit_chain::sample_event;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let initial = Duration::from_millis(500);
+        let max = Duration::from_secs(3);
+        assert_eq!(backoff(initial, max, 0), Duration::from_millis(500));
+        assert_eq!(backoff(initial, max, 1), Duration::from_millis(1000));
+        assert_eq!(backoff(initial, max, 2), Duration::from_millis(2000));
+        assert_eq!(backoff(initial, max, 3), max);
+        assert_eq!(backoff(initial, max, 40), max);
+    }
+
+    #[test]
+    fn validate_requires_http_url() {
+        let mut config = WebhookSinkConfig {
+            url: "https://siem.example.com/ingest".to_string(),
+            headers: Default::default(),
+            timeout_seconds: 5,
+            max_retries: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1000,
+        };
+        assert!(validate(&config).is_ok());
+        config.url = "ftp://siem.example.com".to_string();
+        assert!(validate(&config).is_err());
+    }
+
+    /// Answers one request per status line, then reports how many requests it saw.
+    async fn serve(statuses: &'static [&'static str]) -> (String, tokio::task::JoinHandle<usize>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind");
+        let url = format!("http://{}/ingest", listener.local_addr().expect("addr"));
+        let handle = tokio::spawn(async move {
+            for (index, status) in statuses.iter().enumerate() {
+                let Ok(Ok((mut socket, _))) =
+                    tokio::time::timeout(Duration::from_secs(2), listener.accept()).await
+                else {
+                    return index;
+                };
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let read = socket.read(&mut buf).await.expect("read");
+                    request.extend_from_slice(&buf[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|value| value.trim().parse::<usize>().unwrap_or(0))
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                    if read == 0 {
+                        break;
+                    }
+                }
+                let response =
+                    format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+                socket.write_all(response.as_bytes()).await.expect("write");
+            }
+            statuses.len()
+        });
+        (url, handle)
+    }
+
+    fn sink_for(url: String, max_retries: u32) -> WebhookSink {
+        WebhookSink::new(&WebhookSinkConfig {
+            url,
+            headers: [("x-api-key".to_string(), "secret".to_string())]
+                .into_iter()
+                .collect(),
+            timeout_seconds: 2,
+            max_retries,
+            initial_backoff_ms: 10,
+            max_backoff_ms: 20,
+        })
+        .expect("sink")
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_success() {
+        let (url, server) =
+            serve(&["503 Service Unavailable", "429 Too Many Requests", "200 OK"]).await;
+        let sink = sink_for(url, 3);
+        sink.deliver(&sample_event()).await.expect("delivered");
+        assert_eq!(server.await.expect("server"), 3);
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let (url, server) = serve(&["400 Bad Request", "200 OK"]).await;
+        let sink = sink_for(url, 3);
+        let err = sink.deliver(&sample_event()).await.expect_err("rejected");
+        assert!(err.contains("400"), "{err}");
+        assert_eq!(server.await.expect("server"), 1);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (url, server) =
+            serve(&["500 Internal Server Error", "500 Internal Server Error"]).await;
+        let sink = sink_for(url, 1);
+        let err = sink.deliver(&sample_event()).await.expect_err("gave up");
+        assert!(err.contains("after 2 attempts"), "{err}");
+        assert_eq!(server.await.expect("server"), 2);
+    }
+}
@@ -129,6 +129,14 @@ static AUTH_LOGINS: LazyLock<IntCounterVec> = LazyLock::new(|| {
     )
 });
 
+static SERVICE_ACCOUNT_IP_DENIED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_service_account_ip_denied_total",
+        "Service account requests refused by allowed_ips",
+        &["reason", "method"],
+    )
+});
+
 static AUTH_REGISTERS: LazyLock<IntCounterVec> = LazyLock::new(|| {
     counter_vec_or_fallback(
         "zann_auth_register_total",
@@ -227,6 +235,101 @@ static SECRETS_LATENCY: LazyLock<HistogramVec> = LazyLock::new(|| {
     )
 });
 
+static AUDIT_WRITES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_audit_events_total",
+        "Audit events persisted",
+        &["category", "result"],
+    )
+});
+
+static AUDIT_SINK_EVENTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_audit_sink_events_total",
+        "Audit events forwarded to external sinks",
+        &["sink", "result"],
+    )
+});
+
+static ROTATION_SCHEDULED: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_rotation_scheduled_total",
+        "Rotations started by the rotation scheduler",
+        &["result"],
+    )
+});
+
+static ROTATION_OVERDUE: LazyLock<IntGauge> = LazyLock::new(|| {
+    gauge_or_fallback(
+        "zann_rotation_overdue_items",
+        "Items past their rotation schedule",
+    )
+});
+
+static ROTATION_HOOKS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_rotation_hook_total",
+        "Rotation hook calls by executor, stage and result",
+        &["kind", "stage", "result"],
+    )
+});
+
+static DYNAMIC_CREDENTIALS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_dynamic_credentials_total",
+        "Dynamic credential operations by operation and result",
+        &["operation", "result"],
+    )
+});
+
+static SECRET_LEASES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_secret_leases_total",
+        "Secret lease operations by operation and result",
+        &["operation", "result"],
+    )
+});
+
+static TRANSIT_OPERATIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_transit_operations_total",
+        "Transit key operations by operation and result",
+        &["operation", "result"],
+    )
+});
+
+static SSH_CERTIFICATES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_ssh_certificates_total",
+        "SSH certificates signed by certificate type and result",
+        &["cert_type", "result"],
+    )
+});
+
+static PKI_CERTIFICATES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_pki_certificates_total",
+        "X.509 certificates issued by key type and result",
+        &["key_type", "result"],
+    )
+});
+
+static EXPIRY_NOTIFICATIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_expiry_notifications_total",
+        "Item expiry notices sent by notifier and result",
+        &["notifier", "result"],
+    )
+});
+
+static WEBHOOK_DELIVERIES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter_vec_or_fallback(
+        "zann_webhook_deliveries_total",
+        "Webhook delivery attempts by result",
+        &["result"],
+    )
+});
+
 static DB_POOL_CONNECTIONS: LazyLock<IntGaugeVec> = LazyLock::new(|| {
     gauge_vec_or_fallback(
         "zann_db_pool_connections",
@@ -257,6 +360,7 @@ static HEAP_RETAINED_BYTES: LazyLock<IntGauge> =
 
 pub fn warmup() {
     let _ = &*AUTH_LOGINS;
+    let _ = &*SERVICE_ACCOUNT_IP_DENIED;
     let _ = &*AUTH_REGISTERS;
     let _ = &*OIDC_JWKS_FETCH;
     let _ = &*AUTH_TOKENS_ISSUED;
@@ -270,6 +374,18 @@ pub fn warmup() {
     let _ = &*FORBIDDEN_ACCESS;
     let _ = &*SECRETS_OPS;
     let _ = &*SECRETS_LATENCY;
+    let _ = &*AUDIT_WRITES;
+    let _ = &*AUDIT_SINK_EVENTS;
+    let _ = &*ROTATION_SCHEDULED;
+    let _ = &*ROTATION_OVERDUE;
+    let _ = &*ROTATION_HOOKS;
+    let _ = &*DYNAMIC_CREDENTIALS;
+    let _ = &*SECRET_LEASES;
+    let _ = &*TRANSIT_OPERATIONS;
+    let _ = &*SSH_CERTIFICATES;
+    let _ = &*PKI_CERTIFICATES;
+    let _ = &*EXPIRY_NOTIFICATIONS;
+    let _ = &*WEBHOOK_DELIVERIES;
     let _ = &*DB_POOL_CONNECTIONS;
     #[cfg(feature = "jemalloc")]
     {
@@ -285,6 +401,12 @@ pub fn auth_login(result: &str, method: &str) {
     AUTH_LOGINS.with_label_values(&[result, method]).inc();
 }
 
+pub fn service_account_ip_denied(reason: &str, method: &str) {
+    SERVICE_ACCOUNT_IP_DENIED
+        .with_label_values(&[reason, method])
+        .inc();
+}
+
 pub fn auth_register(result: &str) {
     AUTH_REGISTERS.with_label_values(&[result]).inc();
 }
@@ -312,6 +434,66 @@ pub fn secrets_operation(operation: &str, result: &str, duration_seconds: f64) {
         .observe(duration_seconds);
 }
 
+pub fn audit_write(category: &str, result: &str) {
+    AUDIT_WRITES.with_label_values(&[category, result]).inc();
+}
+
+pub fn audit_sink_event(sink: &str, result: &str) {
+    AUDIT_SINK_EVENTS.with_label_values(&[sink, result]).inc();
+}
+
+pub fn rotation_scheduled(result: &str) {
+    ROTATION_SCHEDULED.with_label_values(&[result]).inc();
+}
+
+pub fn rotation_overdue(count: i64) {
+    ROTATION_OVERDUE.set(count);
+}
+
+pub fn rotation_hook(kind: &str, stage: &str, result: &str) {
+    ROTATION_HOOKS
+        .with_label_values(&[kind, stage, result])
+        .inc();
+}
+
+pub fn dynamic_credentials(operation: &str, result: &str) {
+    DYNAMIC_CREDENTIALS
+        .with_label_values(&[operation, result])
+        .inc();
+}
+
+pub fn secret_leases(operation: &str, result: &str) {
+    SECRET_LEASES.with_label_values(&[operation, result]).inc();
+}
+
+pub fn transit_operations(operation: &str, result: &str) {
+    TRANSIT_OPERATIONS
+        .with_label_values(&[operation, result])
+        .inc();
+}
+
+pub fn ssh_certificates(cert_type: &str, result: &str) {
+    SSH_CERTIFICATES
+        .with_label_values(&[cert_type, result])
+        .inc();
+}
+
+pub fn pki_certificates(key_type: &str, result: &str) {
+    PKI_CERTIFICATES
+        .with_label_values(&[key_type, result])
+        .inc();
+}
+
+pub fn expiry_notification(notifier: &str, result: &str) {
+    EXPIRY_NOTIFICATIONS
+        .with_label_values(&[notifier, result])
+        .inc();
+}
+
+pub fn webhook_delivery(result: &str) {
+    WEBHOOK_DELIVERIES.with_label_values(&[result]).inc();
+}
+
 pub async fn http_metrics(req: Request<Body>, next: Next) -> Response {
     let method = req.method().as_str().to_string();
     let route = req
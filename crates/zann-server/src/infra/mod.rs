@@ -1,7 +1,10 @@
 pub mod audit;
+pub mod audit_chain;
+pub mod audit_sinks;
 pub mod db;
 pub mod history;
 pub mod metrics;
+pub mod notifier;
 pub mod request_context;
 pub mod security_profiles;
 pub mod usage;
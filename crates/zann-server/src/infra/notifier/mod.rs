@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::config::NotifierConfig;
+use crate::infra::audit_sinks::WebhookSink;
+
+mod smtp;
+
+pub use smtp::SmtpNotifier;
+
+/// A message for people responsible for a vault, as opposed to an audit event.
+/// Webhooks receive it as JSON; SMTP sends `subject` and `text` as a plain-text mail.
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub event: &'static str,
+    pub subject: String,
+    pub text: String,
+    pub data: JsonValue,
+    pub sent_at: DateTime<Utc>,
+}
+
+pub enum Notifier {
+    Smtp(SmtpNotifier),
+    Webhook(WebhookSink),
+}
+
+impl Notifier {
+    pub fn new(config: &NotifierConfig) -> Result<Self, String> {
+        validate(config)?;
+        Ok(match config {
+            NotifierConfig::Smtp(config) => Self::Smtp(SmtpNotifier::new(config)?),
+            NotifierConfig::Webhook(config) => Self::Webhook(WebhookSink::new(config)?),
+        })
+    }
+
+    /// Stable label used in logs and metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Smtp(_) => "smtp",
+            Self::Webhook(_) => "webhook",
+        }
+    }
+
+    pub async fn send(&self, notification: &Notification) -> Result<(), String> {
+        match self {
+            Self::Smtp(notifier) => notifier.send(notification).await,
+            Self::Webhook(sink) => sink.send(notification).await,
+        }
+    }
+}
+
+/// Checks notifier settings without connecting anywhere.
+pub fn validate(config: &NotifierConfig) -> Result<(), String> {
+    match config {
+        NotifierConfig::Smtp(config) => smtp::validate(config),
+        NotifierConfig::Webhook(config) => {
+            let url = config.url.trim();
+            if !(url.starts_with("https://") || url.starts_with("http://"))
+                || reqwest::Url::parse(url).is_err()
+            {
+                return Err(format!(
+                    "expiry.notifier: webhook url '{}' must be http(s)",
+                    config.url
+                ));
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::Engine;
+use chrono::Utc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+use uuid::Uuid;
+
+use super::Notification;
+use crate::config::{SmtpNotifierConfig, SmtpTls};
+
+trait Io: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Io for T {}
+
+type Connection = BufReader<Box<dyn Io>>;
+
+/// Sends each notification as a plain-text mail to the configured recipients.
+/// Speaks just enough SMTP for a relay: `EHLO`, optional `STARTTLS` and
+/// `AUTH PLAIN`, one message per connection.
+pub struct SmtpNotifier {
+    host: String,
+    port: u16,
+    tls: SmtpTls,
+    credentials: Option<(String, String)>,
+    from: String,
+    to: Vec<String>,
+    helo_name: String,
+    timeout: Duration,
+    connector: TlsConnector,
+}
+
+impl SmtpNotifier {
+    pub fn new(config: &SmtpNotifierConfig) -> Result<Self, String> {
+        validate(config)?;
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let tls_config = ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let credentials = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => Some((username.clone(), password.clone())),
+            _ => None,
+        };
+        Ok(Self {
+            host: config.host.trim().to_string(),
+            port: config.port,
+            tls: config.tls,
+            credentials,
+            from: config.from.trim().to_string(),
+            to: config.to.iter().map(|to| to.trim().to_string()).collect(),
+            helo_name: config
+                .helo_name
+                .clone()
+                .unwrap_or_else(|| "localhost".to_string()),
+            timeout: Duration::from_secs(config.timeout_seconds.max(1)),
+            connector: TlsConnector::from(Arc::new(tls_config)),
+        })
+    }
+
+    pub async fn send(&self, notification: &Notification) -> Result<(), String> {
+        let message = self.message(notification);
+        tokio::time::timeout(self.timeout, self.deliver(&message))
+            .await
+            .map_err(|_| "smtp timed out".to_string())?
+    }
+
+    async fn deliver(&self, message: &str) -> Result<(), String> {
+        let tcp = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .map_err(|err| format!("smtp connect: {err}"))?;
+        let stream: Box<dyn Io> = if self.tls == SmtpTls::Tls {
+            Box::new(self.wrap_tls(tcp).await?)
+        } else {
+            Box::new(tcp)
+        };
+        let mut conn = BufReader::new(stream);
+        expect(&mut conn, &[220]).await?;
+        command(&mut conn, &format!("EHLO {}", self.helo_name), &[250]).await?;
+
+        if self.tls == SmtpTls::Starttls {
+            command(&mut conn, "STARTTLS", &[220]).await?;
+            let tls = self.wrap_tls(conn.into_inner()).await?;
+            conn = BufReader::new(Box::new(tls));
+            command(&mut conn, &format!("EHLO {}", self.helo_name), &[250]).await?;
+        }
+
+        if let Some((username, password)) = &self.credentials {
+            let token = base64::engine::general_purpose::STANDARD
+                .encode(format!("\0{username}\0{password}"));
+            command(&mut conn, &format!("AUTH PLAIN {token}"), &[235]).await?;
+        }
+
+        command(&mut conn, &format!("MAIL FROM:<{}>", self.from), &[250]).await?;
+        for to in &self.to {
+            command(&mut conn, &format!("RCPT TO:<{to}>"), &[250, 251]).await?;
+        }
+        command(&mut conn, "DATA", &[354]).await?;
+        write_line(&mut conn, &format!("{message}\r\n.")).await?;
+        expect(&mut conn, &[250]).await?;
+        // The message is accepted at this point; a failed QUIT changes nothing.
+        let _ = command(&mut conn, "QUIT", &[221]).await;
+        Ok(())
+    }
+
+    async fn wrap_tls<S>(&self, stream: S) -> Result<tokio_rustls::client::TlsStream<S>, String>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let server_name = ServerName::try_from(self.host.clone())
+            .map_err(|_| format!("smtp: invalid host name '{}'", self.host))?;
+        self.connector
+            .connect(server_name, stream)
+            .await
+            .map_err(|err| format!("smtp tls: {err}"))
+    }
+
+    /// Headers and dot-stuffed body, without the terminating `.` line.
+    fn message(&self, notification: &Notification) -> String {
+        let now = Utc::now();
+        let domain = self
+            .from
+            .rsplit_once('@')
+            .map_or("localhost", |(_, domain)| domain);
+        let mut message = String::new();
+        for (name, value) in [
+            ("From", self.from.clone()),
+            ("To", self.to.join(", ")),
+            ("Subject", encode_header(&notification.subject)),
+            ("Date", now.to_rfc2822()),
+            ("Message-ID", format!("<{}@{domain}>", Uuid::now_v7())),
+            ("MIME-Version", "1.0".to_string()),
+            ("Content-Type", "text/plain; charset=utf-8".to_string()),
+            ("Content-Transfer-Encoding", "8bit".to_string()),
+        ] {
+            message.push_str(name);
+            message.push_str(": ");
+            message.push_str(&value);
+            message.push_str("\r\n");
+        }
+        message.push_str("\r\n");
+        let body = notification.text.replace("\r\n", "\n");
+        let lines: Vec<String> = body
+            .split('\n')
+            .map(|line| {
+                if line.starts_with('.') {
+                    format!(".{line}")
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        message.push_str(&lines.join("\r\n"));
+        message
+    }
+}
+
+async fn command(conn: &mut Connection, line: &str, accept: &[u16]) -> Result<(), String> {
+    write_line(conn, line).await?;
+    expect(conn, accept).await.map_err(|err| {
+        let verb = line.split_whitespace().next().unwrap_or(line);
+        format!("smtp {verb}: {err}")
+    })
+}
+
+async fn write_line(conn: &mut Connection, line: &str) -> Result<(), String> {
+    let stream = conn.get_mut();
+    stream
+        .write_all(format!("{line}\r\n").as_bytes())
+        .await
+        .map_err(|err| format!("smtp write: {err}"))?;
+    stream
+        .flush()
+        .await
+        .map_err(|err| format!("smtp write: {err}"))
+}
+
+/// Reads a (possibly multi-line) reply and checks its code.
+async fn expect(conn: &mut Connection, accept: &[u16]) -> Result<(), String> {
+    loop {
+        let mut line = String::new();
+        let read = conn
+            .read_line(&mut line)
+            .await
+            .map_err(|err| format!("smtp read: {err}"))?;
+        if read == 0 {
+            return Err("connection closed".to_string());
+        }
+        let line = line.trim_end();
+        let code = line
+            .get(..3)
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| format!("unexpected reply '{line}'"))?;
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue;
+        }
+        if accept.contains(&code) {
+            return Ok(());
+        }
+        return Err(format!("server replied '{line}'"));
+    }
+}
+
+/// RFC 2047 encoded-word for non-ASCII subjects.
+fn encode_header(value: &str) -> String {
+    let value: String = value.chars().filter(|c| *c != '\r' && *c != '\n').collect();
+    if value.is_ascii() {
+        value
+    } else {
+        format!(
+            "=?UTF-8?B?{}?=",
+            base64::engine::general_purpose::STANDARD.encode(value.as_bytes())
+        )
+    }
+}
+
+fn valid_address(value: &str) -> bool {
+    let value = value.trim();
+    !value.is_empty()
+        && value.contains('@')
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>' | ','))
+}
+
+pub(super) fn validate(config: &SmtpNotifierConfig) -> Result<(), String> {
+    if config.host.trim().is_empty() {
+        return Err("expiry.notifier: smtp host is required".to_string());
+    }
+    if !valid_address(&config.from) {
+        return Err(format!(
+            "expiry.notifier: invalid smtp from address '{}'",
+            config.from
+        ));
+    }
+    if config.to.is_empty() {
+        return Err("expiry.notifier: smtp needs at least one recipient".to_string());
+    }
+    if let Some(to) = config.to.iter().find(|to| !valid_address(to)) {
+        return Err(format!("expiry.notifier: invalid smtp recipient '{to}'"));
+    }
+    if config.username.is_some() != config.password.is_some() {
+        return Err("expiry.notifier: smtp username and password go together".to_string());
+    }
+    if config.username.is_some() && config.tls == SmtpTls::None {
+        return Err("expiry.notifier: smtp credentials require tls".to_string());
+    }
+    if let Some(name) = config.helo_name.as_deref() {
+        if name.is_empty() || name.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(format!("expiry.notifier: invalid smtp helo_name '{name}'"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SmtpNotifierConfig {
+        SmtpNotifierConfig {
+            host: "mail.example.com".to_string(),
+            port: 587,
+            tls: SmtpTls::Starttls,
+            username: None,
+            password: None,
+            from: "zann@example.com".to_string(),
+            to: vec!["ops@example.com".to_string()],
+            helo_name: None,
+            timeout_seconds: 10,
+        }
+    }
+
+    #[test]
+    fn validate_rejects_header_injection_and_plaintext_auth() {
+        assert!(validate(&config()).is_ok());
+
+        let mut injected = config();
+        injected.to = vec!["ops@example.com\r\nBcc: x@evil.test".to_string()];
+        assert!(validate(&injected).is_err());
+
+        let mut plaintext = config();
+        plaintext.tls = SmtpTls::None;
+        plaintext.username = Some("zann".to_string());
+        plaintext.password = Some("secret".to_string());
+        assert!(validate(&plaintext).is_err());
+    }
+
+    #[test]
+    fn message_dot_stuffs_body_and_encodes_subject() {
+        let notifier = SmtpNotifier::new(&config()).expect("notifier");
+        let notification = Notification {
+            event: "item.expiring",
+            subject: "Ablauf: schlüssel".to_string(),
+            text: "first\n.hidden\nlast".to_string(),
+            data: serde_json::Value::Null,
+            sent_at: Utc::now(),
+        };
+        let message = notifier.message(&notification);
+        assert!(message.contains("Subject: =?UTF-8?B?"));
+        assert!(message.ends_with("\r\n\r\nfirst\r\n..hidden\r\nlast"));
+    }
+}
@@ -54,6 +54,13 @@ async fn main() {
         }
         return;
     }
+    if let cli::RunMode::Operator(operator_args) = &run_mode {
+        if let Err(err) = cli::operator::run(operator_args).await {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
     let settings = if matches!(run_mode, cli::RunMode::Migrate) {
         settings::Settings::from_env_with_options(false)
     } else {
@@ -115,6 +122,13 @@ async fn main() {
         }
         return;
     }
+    if let cli::RunMode::Audit(audit_args) = run_mode {
+        if let Err(err) = cli::audit::run(&settings, &db, &audit_args).await {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
     if let cli::RunMode::Export(export_args) = run_mode {
         if let Err(err) = cli::export::run(&settings, &db, &export_args).await {
             eprintln!("{err}");
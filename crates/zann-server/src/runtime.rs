@@ -30,7 +30,7 @@ pub(crate) fn server_fingerprint(state: &app::AppState) -> String {
     compute_fingerprint(
         state.config.server.fingerprint.as_deref(),
         &state.token_pepper,
-        state.server_master_key.as_deref(),
+        state.server_master_key.get().as_deref(),
     )
 }
 
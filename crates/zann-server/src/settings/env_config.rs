@@ -14,7 +14,7 @@ use zann_crypto::crypto::SecretKey;
 use crate::config::{AuthMode, InternalRegistration, MasterKeyMode, MetricsProfile, ServerConfig};
 use crate::domains::access_control::policies::PolicySet;
 use crate::domains::secrets::policies::{
-    default_policy, default_policy_name, PasswordPolicy, SecretPoliciesFile,
+    builtin_policies, default_policy_name, SecretPoliciesFile, SecretPolicy,
 };
 
 #[cfg(unix)]
@@ -223,6 +223,7 @@ fn parse_auth_mode(value: &str) -> Option<AuthMode> {
 fn parse_internal_registration(value: &str) -> Option<InternalRegistration> {
     match normalize_enum(value).as_str() {
         "open" => Some(InternalRegistration::Open),
+        "invite_only" => Some(InternalRegistration::InviteOnly),
         "disabled" => Some(InternalRegistration::Disabled),
         _ => None,
     }
@@ -239,6 +240,10 @@ fn parse_metrics_profile(value: &str) -> Option<MetricsProfile> {
 
 pub(super) fn load_server_master_key(config: &ServerConfig) -> Option<SecretKey> {
     let mode = &config.server.master_key_mode;
+    // Sealed servers reconstruct the key from shares at runtime.
+    if matches!(mode, MasterKeyMode::ManualUnseal) {
+        return None;
+    }
     let env_key = env::var("ZANN_SMK").ok();
     if let Some(value) = env_key {
         return parse_master_key(&value).ok();
@@ -246,9 +251,6 @@ pub(super) fn load_server_master_key(config: &ServerConfig) -> Option<SecretKey>
     if let Some(value) = config.server.master_key.as_deref() {
         return parse_master_key(value).ok();
     }
-    if matches!(mode, MasterKeyMode::ManualUnseal) {
-        return None;
-    }
 
     let file_path = env::var("ZANN_SMK_FILE")
         .ok()
@@ -530,9 +532,11 @@ pub(super) fn load_policies(config: &ServerConfig) -> Result<PolicySet, String>
 
 pub(super) fn load_secret_policies(
     config: &ServerConfig,
-) -> Result<(HashMap<String, PasswordPolicy>, String), String> {
-    let mut policies = HashMap::new();
-    policies.insert(default_policy_name().to_string(), default_policy());
+) -> Result<(HashMap<String, SecretPolicy>, String), String> {
+    let mut policies: HashMap<String, SecretPolicy> = builtin_policies()
+        .into_iter()
+        .map(|(name, policy)| (name.to_string(), policy))
+        .collect();
     let mut configured_default = config.secrets.default_policy.clone();
 
     if let Some(path) = config.secrets.policies_file.as_deref() {
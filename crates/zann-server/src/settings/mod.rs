@@ -3,7 +3,7 @@ use std::net::SocketAddr;
 
 use crate::config::ServerConfig;
 use crate::domains::access_control::policies::PolicySet;
-use crate::domains::secrets::policies::PasswordPolicy;
+use crate::domains::secrets::policies::SecretPolicy;
 use ed25519_dalek::SigningKey;
 use ipnet::IpNet;
 use std::env;
@@ -32,7 +32,7 @@ pub struct Settings {
     pub item_history_ttl_interval_seconds: u64,
     pub config: ServerConfig,
     pub policies: PolicySet,
-    pub secret_policies: HashMap<String, PasswordPolicy>,
+    pub secret_policies: HashMap<String, SecretPolicy>,
     pub secret_default_policy: String,
 }
 
@@ -211,7 +211,11 @@ pub fn preflight(settings: &Settings) -> Result<(), Vec<String>> {
             missing.push(err);
         }
     }
-    if settings.server_master_key.is_none() {
+    let manual_unseal = matches!(
+        settings.config.server.master_key_mode,
+        crate::config::MasterKeyMode::ManualUnseal
+    );
+    if settings.server_master_key.is_none() && !manual_unseal {
         missing.push("ZANN_SMK or server.master_key/server.master_key_file".to_string());
     }
     if let Some(err) = validate_master_key_mode(settings) {
@@ -251,9 +255,18 @@ pub fn preflight(settings: &Settings) -> Result<(), Vec<String>> {
     if let Some(err) = validate_trusted_proxies(settings) {
         missing.push(err);
     }
+    if let Some(err) = validate_webhook_networks(settings) {
+        missing.push(err);
+    }
     if let Some(err) = validate_metrics_profile(settings) {
         missing.push(err);
     }
+    if let Some(err) = crate::infra::audit_sinks::validate(&settings.config.audit) {
+        missing.push(err);
+    }
+    if let Some(err) = crate::domains::items::expiry::validate(&settings.config.expiry) {
+        missing.push(err);
+    }
     if missing.is_empty() {
         Ok(())
     } else {
@@ -265,11 +278,18 @@ fn validate_master_key_mode(settings: &Settings) -> Option<String> {
     use crate::config::MasterKeyMode;
 
     let mode = &settings.config.server.master_key_mode;
-    if matches!(mode, MasterKeyMode::ManualUnseal)
-        && settings.config.server.master_key.is_none()
-        && env::var("ZANN_SMK").is_err()
-    {
-        return Some("manual_unseal requires ZANN_SMK or server.master_key".to_string());
+    if matches!(mode, MasterKeyMode::ManualUnseal) {
+        let has_key = settings.config.server.master_key.is_some()
+            || settings.config.server.master_key_file.is_some()
+            || env::var("ZANN_SMK").is_ok()
+            || env::var("ZANN_SMK_FILE").is_ok()
+            || env::var("ZANN_MASTER_KEY_FILE").is_ok();
+        if has_key {
+            return Some(
+                "manual_unseal does not accept ZANN_SMK or server.master_key/master_key_file"
+                    .to_string(),
+            );
+        }
     }
     if matches!(mode, MasterKeyMode::External) {
         let has_file = settings.config.server.master_key_file.as_ref().is_some()
@@ -326,6 +346,16 @@ fn allow_debug_metrics() -> bool {
         .unwrap_or(false)
 }
 
+fn validate_webhook_networks(settings: &Settings) -> Option<String> {
+    let networks = &settings.config.webhooks.allowed_networks;
+    for value in networks {
+        if value.parse::<IpNet>().is_err() {
+            return Some(format!("invalid webhook allowed network CIDR: {value}"));
+        }
+    }
+    None
+}
+
 fn validate_trusted_proxies(settings: &Settings) -> Option<String> {
     let proxies = &settings.config.server.trusted_proxies;
     for value in proxies {
@@ -82,6 +82,20 @@ fn set_config_with_auth(auth_yaml: &str) {
     env::set_var("ZANN_CONFIG_PATH", config_path);
 }
 
+fn set_config_with_audit(audit_yaml: &str) {
+    let policy_path =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config/policies.default.yaml");
+    let config_path =
+        std::env::temp_dir().join(format!("zann-test-config-{}.yaml", Uuid::new_v4()));
+    let contents = format!(
+        "policy:\n  file: {}\n{}\n",
+        policy_path.display(),
+        audit_yaml.trim_end()
+    );
+    std::fs::write(&config_path, contents).expect("write config");
+    env::set_var("ZANN_CONFIG_PATH", config_path);
+}
+
 #[test]
 fn default_auth_config_is_internal_disabled() {
     let _lock = ENV_LOCK.lock().expect("env lock");
@@ -107,13 +121,13 @@ fn invalid_auth_config_fails_fast() {
     set_config_with_auth(
         r#"auth:
   internal:
-    registration: invite_only
+    registration: sometimes
 "#,
     );
 
     let err = Settings::from_env_with_options(false).expect_err("config should fail");
     assert!(err.contains("config parse failed"));
-    assert!(err.contains("invite_only"));
+    assert!(err.contains("sometimes"));
 }
 
 #[test]
@@ -156,6 +170,21 @@ fn auth_env_overrides_apply() {
     assert!(settings.config.auth.oidc.enabled);
 }
 
+#[test]
+fn invite_only_registration_env_override_applies() {
+    let _lock = ENV_LOCK.lock().expect("env lock");
+    clear_auth_env();
+    clear_metrics_env();
+    set_policy_config_path();
+    env::set_var("ZANN_AUTH_INTERNAL_REGISTRATION", "invite-only");
+
+    let settings = Settings::from_env_with_options(false).expect("settings");
+    assert!(matches!(
+        settings.config.auth.internal.registration,
+        InternalRegistration::InviteOnly
+    ));
+}
+
 #[test]
 fn internal_auth_requires_password_pepper() {
     let _lock = ENV_LOCK.lock().expect("env lock");
@@ -319,3 +348,97 @@ fn tracing_otel_config_parses() {
     );
     assert_eq!(settings.config.tracing.otel.sampling_ratio, Some(0.25));
 }
+
+#[test]
+fn audit_sinks_config_parses() {
+    let _lock = ENV_LOCK.lock().expect("env lock");
+    clear_auth_env();
+    clear_metrics_env();
+    set_config_with_audit(
+        r#"audit:
+  sinks:
+    - type: syslog
+      address: "siem.internal:6514"
+      protocol: tcp
+      facility: local4
+    - type: file
+      path: /var/log/zann/audit.jsonl
+      max_files: 3
+    - type: webhook
+      url: "https://siem.example.com/ingest"
+      headers:
+        Authorization: "Bearer token"
+"#,
+    );
+
+    let settings = Settings::from_env_with_options(false).expect("settings");
+    let sinks = &settings.config.audit.sinks;
+    assert_eq!(sinks.len(), 3);
+    let crate::config::AuditSinkConfig::Syslog(syslog) = &sinks[0] else {
+        panic!("expected syslog sink");
+    };
+    assert_eq!(syslog.protocol, crate::config::SyslogProtocol::Tcp);
+    assert_eq!(syslog.app_name, "zann-server");
+    let crate::config::AuditSinkConfig::File(file) = &sinks[1] else {
+        panic!("expected file sink");
+    };
+    assert_eq!(file.max_files, 3);
+    assert_eq!(file.max_bytes, 100 * 1024 * 1024);
+    let crate::config::AuditSinkConfig::Webhook(webhook) = &sinks[2] else {
+        panic!("expected webhook sink");
+    };
+    assert_eq!(webhook.max_retries, 5);
+    assert_eq!(
+        webhook.headers.get("Authorization").map(String::as_str),
+        Some("Bearer token")
+    );
+}
+
+#[test]
+fn invalid_audit_sink_fails_preflight() {
+    let _lock = ENV_LOCK.lock().expect("env lock");
+    clear_auth_env();
+    clear_metrics_env();
+    env::set_var("ZANN_SMK", TEST_SMK);
+    set_config_with_audit("audit:\n  sinks:\n    - type: syslog\n      address: siem.internal\n");
+
+    let settings = Settings::from_env_with_options(false).expect("settings");
+    let missing = preflight(&settings).expect_err("preflight should fail");
+    assert!(missing
+        .iter()
+        .any(|value| value.contains("syslog address 'siem.internal' must be host:port")));
+}
+
+#[test]
+fn manual_unseal_boots_without_master_key() {
+    let _lock = ENV_LOCK.lock().expect("env lock");
+    clear_auth_env();
+    clear_pepper_env();
+    clear_metrics_env();
+    set_config_with_auth("server:\n  master_key_mode: manual_unseal\n");
+    env::set_var("ZANN_PASSWORD_PEPPER", "test-password-pepper");
+    env::set_var("ZANN_TOKEN_PEPPER", "test-token-pepper");
+
+    let settings = Settings::from_env_with_options(true).expect("settings");
+    assert!(settings.server_master_key.is_none());
+    assert!(preflight(&settings).is_ok());
+}
+
+#[test]
+fn manual_unseal_rejects_configured_master_key() {
+    let _lock = ENV_LOCK.lock().expect("env lock");
+    clear_auth_env();
+    clear_pepper_env();
+    clear_metrics_env();
+    set_config_with_auth("server:\n  master_key_mode: manual_unseal\n");
+    env::set_var("ZANN_PASSWORD_PEPPER", "test-password-pepper");
+    env::set_var("ZANN_TOKEN_PEPPER", "test-token-pepper");
+    env::set_var("ZANN_SMK", TEST_SMK);
+
+    let settings = Settings::from_env_with_options(true).expect("settings");
+    assert!(settings.server_master_key.is_none());
+    let missing = preflight(&settings).expect_err("preflight should fail");
+    assert!(missing
+        .iter()
+        .any(|value| value.contains("manual_unseal does not accept ZANN_SMK")));
+}
@@ -0,0 +1,467 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod support;
+
+use chrono::Utc;
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, Group, GroupMember, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_db::repo::{GroupMemberRepo, GroupRepo, UserRepo};
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::audit_chain::{self, ChainBreak, VerifyError};
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    pool: PgPool,
+    identity_key: std::sync::Arc<ed25519_dalek::SigningKey>,
+}
+
+impl TestApp {
+    async fn new_with_smk() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+        let config_for_state = config.clone();
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let identity_key = support::test_identity_key();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: identity_key.clone(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config: config_for_state,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        let app = build_router(state);
+        Self {
+            _guard: guard,
+            app,
+            pool,
+            identity_key,
+        }
+    }
+
+    async fn send_json(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        let request = builder
+            .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn get_json(&self, uri: &str, token: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder().method(Method::GET).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        let request = builder.body(Body::empty()).expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str, password: &str) -> serde_json::Value {
+        let payload = json!({
+            "email": email,
+            "password": password,
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let (status, json) = self
+            .send_json(Method::POST, "/v1/auth/register", None, payload)
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "register failed: {:?}", json);
+        json
+    }
+
+    async fn login(&self, email: &str, password: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": password,
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let (status, json) = self
+            .send_json(Method::POST, "/v1/auth/login", None, payload)
+            .await;
+        assert_eq!(status, StatusCode::OK, "login failed: {:?}", json);
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> serde_json::Value {
+        let payload = json!({
+            "slug": slug,
+            "name": "Shared Vault",
+            "kind": VaultKind::Shared.as_i32(),
+            "cache_policy": CachePolicy::Full.as_i32(),
+        });
+        let (status, json) = self
+            .send_json(Method::POST, "/v1/vaults", Some(token), payload)
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            json
+        );
+        json
+    }
+
+    async fn add_admin_group(&self, user_id: Uuid) {
+        let group_repo = GroupRepo::new(&self.pool);
+        let member_repo = GroupMemberRepo::new(&self.pool);
+        let now = Utc::now();
+
+        let group = Group {
+            id: Uuid::now_v7(),
+            slug: "admins".to_string(),
+            name: "Admins".to_string(),
+            created_at: now,
+        };
+        let group_id = match group_repo.get_by_slug("admins").await {
+            Ok(Some(existing)) => existing.id,
+            _ => {
+                group_repo.create(&group).await.expect("create group");
+                group.id
+            }
+        };
+
+        let member = GroupMember {
+            group_id,
+            user_id,
+            created_at: now,
+        };
+        let _ = member_repo.create(&member).await;
+    }
+
+    async fn user_id_by_email(&self, email: &str) -> Uuid {
+        let repo = UserRepo::new(&self.pool);
+        repo.get_by_email(email)
+            .await
+            .expect("user lookup")
+            .expect("user exists")
+            .id
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn audit_records_secret_access_and_filters() {
+    let app = TestApp::new_with_smk().await;
+    let email = "audit-admin@example.com";
+    let password = "password-1";
+    app.register(email, password).await;
+    let admin_id = app.user_id_by_email(email).await;
+    app.add_admin_group(admin_id).await;
+    let token = app.login(email, password).await;
+
+    let vault = app.create_shared_vault(&token, "audit-vault").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+
+    let payload = json!({ "value": "s3cret" });
+    let (status, body) = app
+        .send_json(
+            Method::PUT,
+            &format!("/v1/vaults/{}/secrets/db/password", vault_id),
+            Some(&token),
+            payload,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "set failed: {:?}", body);
+    let (status, body) = app
+        .get_json(
+            &format!("/v1/vaults/{}/secrets/db/password", vault_id),
+            Some(&token),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "get failed: {:?}", body);
+
+    let (status, body) = app
+        .get_json("/v1/audit?path=db/password", Some(&token))
+        .await;
+    assert_eq!(status, StatusCode::OK, "audit list failed: {:?}", body);
+    let events = body["events"].as_array().expect("events");
+    let actions: Vec<&str> = events
+        .iter()
+        .map(|event| event["action"].as_str().expect("action"))
+        .collect();
+    assert_eq!(actions, vec!["get", "set"]);
+    assert_eq!(events[0]["result"], "ok");
+    assert_eq!(events[1]["result"], "created");
+    for event in events {
+        assert_eq!(event["category"], "secrets");
+        assert_eq!(event["vault_id"], vault_id);
+        assert_eq!(event["actor_user_id"], admin_id.to_string());
+        assert_eq!(event["actor_email"], email);
+    }
+    assert!(body["next_cursor"].is_null());
+
+    let (status, body) = app
+        .get_json("/v1/audit?vault=audit-vault&action=set", Some(&token))
+        .await;
+    assert_eq!(status, StatusCode::OK, "audit filter failed: {:?}", body);
+    let events = body["events"].as_array().expect("events");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["path"], "db/password");
+
+    let (status, body) = app
+        .get_json(&format!("/v1/audit?actor={}", email), Some(&token))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let actions: Vec<&str> = body["events"]
+        .as_array()
+        .expect("events")
+        .iter()
+        .map(|event| event["action"].as_str().expect("action"))
+        .collect();
+    assert!(actions.contains(&"register"), "actions: {:?}", actions);
+    assert!(actions.contains(&"login"), "actions: {:?}", actions);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn audit_list_paginates_with_cursor() {
+    let app = TestApp::new_with_smk().await;
+    let email = "audit-pages@example.com";
+    let password = "password-1";
+    app.register(email, password).await;
+    let admin_id = app.user_id_by_email(email).await;
+    app.add_admin_group(admin_id).await;
+    let token = app.login(email, password).await;
+
+    let vault = app.create_shared_vault(&token, "audit-pages").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+    for _ in 0..3 {
+        let (status, _) = app
+            .get_json(
+                &format!("/v1/vaults/{}/secrets/missing", vault_id),
+                Some(&token),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    let mut seen = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut uri = "/v1/audit?path=missing&limit=2".to_string();
+        if let Some(cursor) = &cursor {
+            uri.push_str(&format!("&cursor={}", cursor));
+        }
+        let (status, body) = app.get_json(&uri, Some(&token)).await;
+        assert_eq!(status, StatusCode::OK, "audit page failed: {:?}", body);
+        for event in body["events"].as_array().expect("events") {
+            assert_eq!(event["result"], "not_found");
+            seen.push(event["seq"].as_i64().expect("seq"));
+        }
+        match body["next_cursor"].as_str() {
+            Some(next) => cursor = Some(next.to_string()),
+            None => break,
+        }
+    }
+    assert_eq!(seen.len(), 3);
+    assert!(seen.windows(2).all(|pair| pair[0] > pair[1]));
+
+    let (status, body) = app.get_json("/v1/audit?cursor=abc", Some(&token)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_cursor");
+
+    let (status, body) = app
+        .get_json("/v1/audit?since=yesterday", Some(&token))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_since");
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn audit_list_forbidden_for_non_admin() {
+    let app = TestApp::new_with_smk().await;
+    let email = "audit-user@example.com";
+    let password = "password-1";
+    app.register(email, password).await;
+    let token = app.login(email, password).await;
+
+    let (status, _) = app.get_json("/v1/audit", Some(&token)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn audit_chain_verifies_and_detects_tampering() {
+    let app = TestApp::new_with_smk().await;
+    let email = "audit-chain@example.com";
+    let password = "password-1";
+    app.register(email, password).await;
+    let token = app.login(email, password).await;
+    let vault = app.create_shared_vault(&token, "audit-chain").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+    for _ in 0..3 {
+        let (status, _) = app
+            .get_json(
+                &format!("/v1/vaults/{}/secrets/missing", vault_id),
+                Some(&token),
+            )
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    let trusted = [app.identity_key.verifying_key()];
+    let checkpoint = audit_chain::write_checkpoint(&app.pool, &app.identity_key)
+        .await
+        .expect("checkpoint")
+        .expect("new checkpoint");
+    assert!(audit_chain::write_checkpoint(&app.pool, &app.identity_key)
+        .await
+        .expect("checkpoint")
+        .is_none());
+    let report = audit_chain::verify(&app.pool, &trusted)
+        .await
+        .expect("chain verifies");
+    assert_eq!(report.checkpoints, 1);
+    assert_eq!(report.head_seq, Some(checkpoint.seq));
+    assert!(report.verified >= 5);
+
+    let other_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+    let err = audit_chain::verify(&app.pool, &[other_key.verifying_key()])
+        .await
+        .expect_err("untrusted checkpoint key");
+    assert!(matches!(
+        err,
+        VerifyError::Broken(ChainBreak::CheckpointSignature { .. })
+    ));
+
+    let victim = checkpoint.seq - 1;
+    sqlx_core::query::query::<sqlx_postgres::Postgres>("DELETE FROM audit_events WHERE seq = $1")
+        .bind(victim)
+        .execute(&app.pool)
+        .await
+        .expect("delete row");
+    let err = audit_chain::verify(&app.pool, &trusted)
+        .await
+        .expect_err("deleted row detected");
+    assert!(matches!(
+        err,
+        VerifyError::Broken(ChainBreak::PrevHashMismatch { seq }) if seq == checkpoint.seq
+    ));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn audit_chain_detects_edits_and_truncation() {
+    let app = TestApp::new_with_smk().await;
+    let email = "audit-edit@example.com";
+    let password = "password-1";
+    app.register(email, password).await;
+    let _token = app.login(email, password).await;
+    let trusted = [app.identity_key.verifying_key()];
+
+    let report = audit_chain::verify(&app.pool, &trusted)
+        .await
+        .expect("chain verifies");
+    let head = report.head_seq.expect("head");
+
+    sqlx_core::query::query::<sqlx_postgres::Postgres>(
+        "UPDATE audit_events SET result = 'forbidden' WHERE seq = $1",
+    )
+    .bind(head)
+    .execute(&app.pool)
+    .await
+    .expect("edit row");
+    let err = audit_chain::verify(&app.pool, &trusted)
+        .await
+        .expect_err("edited row detected");
+    assert!(matches!(
+        err,
+        VerifyError::Broken(ChainBreak::HashMismatch { seq }) if seq == head
+    ));
+
+    sqlx_core::query::query::<sqlx_postgres::Postgres>("DELETE FROM audit_events WHERE seq = $1")
+        .bind(head)
+        .execute(&app.pool)
+        .await
+        .expect("truncate tail");
+    let err = audit_chain::verify(&app.pool, &trusted)
+        .await
+        .expect_err("truncated tail detected");
+    assert!(matches!(
+        err,
+        VerifyError::Broken(ChainBreak::HeadMismatch { head_seq, .. }) if head_seq == head
+    ));
+}
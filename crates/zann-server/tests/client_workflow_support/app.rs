@@ -14,6 +14,7 @@ use zann_server::app::{build_router, AppState};
 use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
 use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
 use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
 use zann_server::infra::security_profiles::load_security_profiles;
 use zann_server::infra::usage::UsageTracker;
 use zann_server::oidc::OidcJwksCache;
@@ -56,7 +57,7 @@ impl TestApp {
             started_at: std::time::Instant::now(),
             password_pepper: "pepper".to_string(),
             token_pepper: "pepper".to_string(),
-            server_master_key: Some(std::sync::Arc::new(SecretKey::generate())),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
 
             identity_key: support::test_identity_key(),
             access_token_ttl_seconds: 3600,
@@ -0,0 +1,432 @@
+use std::str::FromStr;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use sqlx_core::connection::Connection;
+use sqlx_core::executor::Executor;
+use sqlx_postgres::{PgConnectOptions, PgConnection, Postgres};
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::dynamic::service::reap_expired_leases;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    pool: PgPool,
+    state: AppState,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+        config.dynamic.statement_timeout_seconds = 5;
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state.clone()),
+            pool,
+            state,
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password-1",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&payload).expect("encode")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> String {
+        let (status, vault) = self
+            .send(
+                Method::POST,
+                "/v1/vaults",
+                token,
+                Some(json!({
+                    "slug": slug,
+                    "name": "Shared Vault",
+                    "kind": VaultKind::Shared.as_i32(),
+                    "cache_policy": CachePolicy::Full.as_i32(),
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            vault
+        );
+        vault["id"].as_str().expect("vault id").to_string()
+    }
+
+    async fn configure(&self, token: &str, uri: &str) -> serde_json::Value {
+        let (status, body) = self
+            .send(Method::PUT, uri, token, Some(postgres_config()))
+            .await;
+        assert_eq!(status, StatusCode::OK, "configure failed: {:?}", body);
+        body
+    }
+
+    async fn issue(&self, token: &str, uri: &str, ttl: &str) -> serde_json::Value {
+        let (status, body) = self
+            .send(Method::POST, uri, token, Some(json!({ "ttl": ttl })))
+            .await;
+        assert_eq!(status, StatusCode::OK, "issue failed: {:?}", body);
+        body
+    }
+
+    async fn role_exists(&self, role: &str) -> bool {
+        let row: Option<(String,)> = sqlx_core::query_as::query_as::<Postgres, (String,)>(
+            "SELECT rolname::text FROM pg_roles WHERE rolname = $1",
+        )
+        .bind(role)
+        .fetch_optional(&self.pool)
+        .await
+        .expect("pg_roles");
+        row.is_some()
+    }
+}
+
+fn db_url() -> String {
+    std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL")
+}
+
+fn postgres_config() -> serde_json::Value {
+    json!({
+        "kind": "postgres",
+        "connection_url": db_url(),
+        "creation_statements": [
+            "CREATE ROLE \"{{name}}\" LOGIN PASSWORD '{{password}}' VALID UNTIL '{{expiration}}'"
+        ],
+        "default_ttl": "10m",
+        "max_ttl": "1h",
+    })
+}
+
+async fn login(username: &str, password: &str) -> Result<(), sqlx_core::Error> {
+    let options = PgConnectOptions::from_str(&db_url())
+        .expect("db url")
+        .username(username)
+        .password(password);
+    let mut conn = PgConnection::connect_with(&options).await?;
+    conn.execute("SELECT 1").await?;
+    conn.close().await
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn issued_credential_logs_in_until_revoked() {
+    let app = TestApp::new().await;
+    let token = app.register("dynamic-issue@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "dynamic-issue").await;
+    let uri = format!("/v1/vaults/{}/dynamic/db/app-ro", vault_id);
+
+    let configured = app.configure(&token, &uri).await;
+    assert_eq!(configured["kind"], "postgres");
+    assert_eq!(configured["path"], "/db/app-ro");
+    assert_eq!(configured["default_ttl_seconds"], 600);
+    assert_eq!(configured["max_ttl_seconds"], 3600);
+    assert!(!configured.to_string().contains(&db_url()));
+
+    let (status, read) = app.send(Method::GET, &uri, &token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(read["id"], configured["id"]);
+
+    // Requested TTLs above max_ttl are clamped.
+    let credential = app.issue(&token, &uri, "48h").await;
+    assert_eq!(credential["ttl_seconds"], 3600);
+    let username = credential["username"].as_str().expect("username");
+    let password = credential["password"].as_str().expect("password");
+    assert!(username.starts_with("v_db_app_ro_"));
+    assert!(credential["port"].as_u64().is_some());
+    login(username, password).await.expect("login as lease");
+
+    let (status, body) = app.send(Method::DELETE, &uri, &token, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "leases_active");
+
+    let lease_uri = format!(
+        "/v1/vaults/{}/dynamic-leases/{}",
+        vault_id,
+        credential["lease_id"].as_str().expect("lease id")
+    );
+    let (status, _) = app.send(Method::DELETE, &lease_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(!app.role_exists(username).await);
+    assert!(login(username, password).await.is_err());
+    let (status, _) = app.send(Method::DELETE, &lease_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, _) = app.send(Method::DELETE, &uri, &token, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = app.send(Method::GET, &uri, &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn reaper_revokes_expired_leases() {
+    let app = TestApp::new().await;
+    let token = app.register("dynamic-reaper@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "dynamic-reaper").await;
+    let uri = format!("/v1/vaults/{}/dynamic/db/reporting", vault_id);
+    app.configure(&token, &uri).await;
+
+    let expired = app.issue(&token, &uri, "5m").await;
+    let live = app.issue(&token, &uri, "5m").await;
+    let expired_lease =
+        Uuid::parse_str(expired["lease_id"].as_str().expect("lease id")).expect("lease uuid");
+    sqlx_core::query::query::<Postgres>(
+        "UPDATE dynamic_leases SET expires_at = NOW() - INTERVAL '1 minute' WHERE id = $1",
+    )
+    .bind(expired_lease)
+    .execute(&app.pool)
+    .await
+    .expect("backdate lease");
+
+    let revoked = reap_expired_leases(&app.state).await.expect("reap");
+    assert_eq!(revoked, 1);
+    let expired_user = expired["username"].as_str().expect("username");
+    let live_user = live["username"].as_str().expect("username");
+    assert!(!app.role_exists(expired_user).await);
+    assert!(app.role_exists(live_user).await);
+    assert_eq!(reap_expired_leases(&app.state).await.expect("reap"), 0);
+
+    let (status, _) = app
+        .send(
+            Method::DELETE,
+            &format!(
+                "/v1/vaults/{}/dynamic-leases/{}",
+                vault_id,
+                live["lease_id"].as_str().expect("lease id")
+            ),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(!app.role_exists(live_user).await);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn dynamic_secret_requires_vault_admin_and_valid_config() {
+    let app = TestApp::new().await;
+    let token = app.register("dynamic-owner@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "dynamic-guard").await;
+    let uri = format!("/v1/vaults/{}/dynamic/db/app", vault_id);
+
+    let mut invalid = postgres_config();
+    invalid["creation_statements"] = json!(["SELECT 1"]);
+    let (status, body) = app.send(Method::PUT, &uri, &token, Some(invalid)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_creation_statements");
+
+    let mut invalid = postgres_config();
+    invalid["default_ttl"] = json!("2h");
+    let (status, body) = app.send(Method::PUT, &uri, &token, Some(invalid)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "default_ttl_exceeds_max");
+
+    let outsider = app.register("dynamic-outsider@example.com").await;
+    let (status, _) = app
+        .send(Method::PUT, &uri, &outsider, Some(postgres_config()))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let operator = app.register("dynamic-operator@example.com").await;
+    let (status, added) = app
+        .send(
+            Method::POST,
+            &format!("/v1/vaults/{}/members", vault_id),
+            &token,
+            Some(json!({ "email": "dynamic-operator@example.com", "role": "operator" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{added:?}");
+    let (status, _) = app
+        .send(Method::PUT, &uri, &operator, Some(postgres_config()))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    app.configure(&token, &uri).await;
+    let (status, _) = app
+        .send(Method::POST, &uri, &outsider, Some(json!({})))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Operators can read the path, so they may request credentials.
+    let credential = app.issue(&operator, &uri, "1m").await;
+    assert_eq!(credential["ttl_seconds"], 60);
+    let (status, _) = app
+        .send(
+            Method::DELETE,
+            &format!(
+                "/v1/vaults/{}/dynamic-leases/{}",
+                vault_id,
+                credential["lease_id"].as_str().expect("lease id")
+            ),
+            &operator,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn lease_revocation_is_limited_to_holders_and_managers() {
+    let app = TestApp::new().await;
+    let token = app.register("dynamic-admin@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "dynamic-leases").await;
+    let uri = format!("/v1/vaults/{}/dynamic/db/app", vault_id);
+    app.configure(&token, &uri).await;
+
+    let holder = app.register("dynamic-holder@example.com").await;
+    let reader = app.register("dynamic-reader@example.com").await;
+    for email in ["dynamic-holder@example.com", "dynamic-reader@example.com"] {
+        let (status, added) = app
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/members", vault_id),
+                &token,
+                Some(json!({ "email": email, "role": "member" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "{added:?}");
+    }
+    let credential = app.issue(&holder, &uri, "5m").await;
+    let username = credential["username"].as_str().expect("username");
+    let lease_uri = format!(
+        "/v1/vaults/{}/dynamic-leases/{}",
+        vault_id,
+        credential["lease_id"].as_str().expect("lease id")
+    );
+
+    // Another reader of the path may not revoke someone else's lease.
+    let (status, _) = app.send(Method::DELETE, &lease_uri, &reader, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert!(app.role_exists(username).await);
+
+    // Outsiders are refused before the lease id is looked up.
+    let outsider = app.register("dynamic-prober@example.com").await;
+    let (status, _) = app.send(Method::DELETE, &lease_uri, &outsider, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let missing_uri = format!("/v1/vaults/{}/dynamic-leases/{}", vault_id, Uuid::now_v7());
+    let (status, _) = app
+        .send(Method::DELETE, &missing_uri, &outsider, None)
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app.send(Method::DELETE, &missing_uri, &reader, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // Vault admins manage every lease.
+    let (status, _) = app.send(Method::DELETE, &lease_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(!app.role_exists(username).await);
+}
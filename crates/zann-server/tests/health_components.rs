@@ -11,6 +11,7 @@ use zann_server::app::AppState;
 use zann_server::config::{MetricsConfig, MetricsProfile, ServerConfig};
 use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
 use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
 use zann_server::infra::security_profiles::load_security_profiles;
 use zann_server::infra::usage::UsageTracker;
 use zann_server::oidc::OidcJwksCache;
@@ -43,7 +44,7 @@ impl TestApp {
             started_at: std::time::Instant::now(),
             password_pepper: "pepper".to_string(),
             token_pepper: "pepper".to_string(),
-            server_master_key: None,
+            server_master_key: MasterKeyStore::new(None),
             identity_key: support::test_identity_key(),
             access_token_ttl_seconds: 3600,
             refresh_token_ttl_seconds: 3600,
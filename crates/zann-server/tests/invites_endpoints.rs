@@ -0,0 +1,444 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod support;
+
+use chrono::Utc;
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, Group, GroupMember, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_db::repo::{GroupMemberRepo, GroupRepo, UserRepo};
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    /// Router running with `invite_only` registration.
+    app: axum::Router,
+    /// Router over the same database with `open` registration, used to
+    /// bootstrap the admin account.
+    open_app: axum::Router,
+    pool: PgPool,
+}
+
+fn build_app(pool: &PgPool, registration: InternalRegistration) -> axum::Router {
+    let rules: Vec<PolicyRule> = support::load_policy_rules();
+    let mut config = ServerConfig::default();
+
+    support::tune_test_kdf(&mut config);
+    config.auth.mode = AuthMode::Internal;
+    config.auth.internal.enabled = true;
+    config.auth.internal.registration = registration;
+
+    let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+    let (secret_policies, secret_default_policy) = support::default_secret_policies();
+    let state = AppState {
+        db: pool.clone(),
+        db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+        started_at: std::time::Instant::now(),
+        password_pepper: "pepper".to_string(),
+        token_pepper: "pepper".to_string(),
+        server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+        identity_key: support::test_identity_key(),
+        access_token_ttl_seconds: 3600,
+        refresh_token_ttl_seconds: 3600,
+        argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+        oidc_jwks_cache: OidcJwksCache::new(),
+        config,
+        policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+        usage_tracker,
+        security_profiles: load_security_profiles(),
+        secret_policies,
+        secret_default_policy,
+    };
+    build_router(state)
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+
+        Self {
+            _guard: guard,
+            app: build_app(&pool, InternalRegistration::InviteOnly),
+            open_app: build_app(&pool, InternalRegistration::Open),
+            pool,
+        }
+    }
+
+    async fn send(
+        &self,
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn send_json(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        self.send(&self.app, method, uri, token, Some(body)).await
+    }
+
+    async fn get_json(&self, uri: &str, token: Option<&str>) -> (StatusCode, serde_json::Value) {
+        self.send(&self.app, Method::GET, uri, token, None).await
+    }
+
+    async fn try_register(
+        &self,
+        email: &str,
+        invite_token: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut payload = json!({
+            "email": email,
+            "password": "password",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        if let Some(token) = invite_token {
+            payload["invite_token"] = json!(token);
+        }
+        self.send_json(Method::POST, "/v1/auth/register", None, payload)
+            .await
+    }
+
+    /// Registers through the open router and joins the `admins` group.
+    async fn bootstrap_admin(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let (status, json) = self
+            .send(
+                &self.open_app,
+                Method::POST,
+                "/v1/auth/register",
+                None,
+                Some(payload),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "register failed: {:?}", json);
+        let user_id = self.user_id(email).await.expect("admin user");
+        self.add_to_group("admins", user_id).await;
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn add_to_group(&self, slug: &str, user_id: Uuid) {
+        let group_id = self.ensure_group(slug).await;
+        let _ = GroupMemberRepo::new(&self.pool)
+            .create(&GroupMember {
+                group_id,
+                user_id,
+                created_at: Utc::now(),
+            })
+            .await;
+    }
+
+    async fn ensure_group(&self, slug: &str) -> Uuid {
+        let group_repo = GroupRepo::new(&self.pool);
+        if let Ok(Some(existing)) = group_repo.get_by_slug(slug).await {
+            return existing.id;
+        }
+        let group = Group {
+            id: Uuid::now_v7(),
+            slug: slug.to_string(),
+            name: slug.to_uppercase(),
+            created_at: Utc::now(),
+        };
+        group_repo.create(&group).await.expect("create group");
+        group.id
+    }
+
+    async fn user_id(&self, email: &str) -> Option<Uuid> {
+        UserRepo::new(&self.pool)
+            .get_by_email(email)
+            .await
+            .expect("user lookup")
+            .map(|user| user.id)
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) {
+        let payload = json!({
+            "slug": slug,
+            "name": "Shared Vault",
+            "kind": VaultKind::Shared.as_i32(),
+            "cache_policy": CachePolicy::Full.as_i32(),
+        });
+        let (status, json) = self
+            .send_json(Method::POST, "/v1/vaults", Some(token), payload)
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            json
+        );
+    }
+
+    async fn create_invite(&self, token: &str, body: serde_json::Value) -> serde_json::Value {
+        let (status, json) = self
+            .send_json(Method::POST, "/v1/admin/invites", Some(token), body)
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "invite failed: {:?}", json);
+        json
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn invite_only_registration_requires_valid_invite() {
+    let app = TestApp::new().await;
+    let admin_token = app.bootstrap_admin("admin@example.com").await;
+
+    let (status, body) = app.try_register("walk-in@example.com", None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "invite_required");
+
+    let (status, body) = app
+        .try_register("walk-in@example.com", Some("zann_inv_bogus"))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "invite_invalid");
+
+    let issued = app
+        .create_invite(&admin_token, json!({ "email": "New.Hire@Example.com" }))
+        .await;
+    let token = issued["token"].as_str().expect("token");
+    assert!(token.starts_with("zann_inv_"));
+    assert_eq!(issued["invite"]["email"], "New.Hire@Example.com");
+    assert_eq!(issued["invite"]["status"], "pending");
+
+    let (status, body) = app.try_register("someone@example.com", Some(token)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "invite_email_mismatch");
+    assert!(app.user_id("someone@example.com").await.is_none());
+
+    let (status, body) = app.try_register("new.hire@example.com", Some(token)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body:?}");
+
+    // Tokens are single use.
+    let (status, body) = app.try_register("new.hire2@example.com", Some(token)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "invite_invalid");
+
+    let (status, list) = app.get_json("/v1/admin/invites", Some(&admin_token)).await;
+    assert_eq!(status, StatusCode::OK, "{list:?}");
+    let invites = list["invites"].as_array().expect("invites");
+    assert_eq!(invites.len(), 1);
+    assert_eq!(invites[0]["status"], "accepted");
+    let new_hire = app.user_id("new.hire@example.com").await.expect("user");
+    assert_eq!(invites[0]["accepted_user_id"], new_hire.to_string());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn invite_applies_groups_and_vault_roles() {
+    let app = TestApp::new().await;
+    let admin_token = app.bootstrap_admin("admin@example.com").await;
+    app.ensure_group("platform").await;
+    app.create_shared_vault(&admin_token, "infra").await;
+
+    let (status, body) = app
+        .send_json(
+            Method::POST,
+            "/v1/admin/invites",
+            Some(&admin_token),
+            json!({ "email": "ops@example.com", "groups": ["missing"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "unknown_group");
+
+    let issued = app
+        .create_invite(
+            &admin_token,
+            json!({
+                "email": "ops@example.com",
+                "ttl": "48h",
+                "groups": ["platform"],
+                "vaults": [{ "vault": "infra", "role": "operator" }],
+            }),
+        )
+        .await;
+    let token = issued["token"].as_str().expect("token");
+    assert_eq!(issued["invite"]["vaults"][0]["role"], "operator");
+
+    let (status, registered) = app.try_register("ops@example.com", Some(token)).await;
+    assert_eq!(status, StatusCode::CREATED, "{registered:?}");
+    let ops_token = registered["access_token"].as_str().expect("token");
+    let ops_id = app.user_id("ops@example.com").await.expect("user");
+
+    let platform = GroupRepo::new(&app.pool)
+        .get_by_slug("platform")
+        .await
+        .expect("group lookup")
+        .expect("group");
+    let members = GroupMemberRepo::new(&app.pool)
+        .list_by_group(platform.id)
+        .await
+        .expect("group members");
+    assert!(members.iter().any(|member| member.user_id == ops_id));
+
+    let (status, list) = app
+        .get_json("/v1/vaults/infra/members", Some(ops_token))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{list:?}");
+    let roles: Vec<(&str, &str)> = list["members"]
+        .as_array()
+        .expect("members")
+        .iter()
+        .map(|member| {
+            (
+                member["email"].as_str().unwrap_or_default(),
+                member["role"].as_str().unwrap_or_default(),
+            )
+        })
+        .collect();
+    assert!(
+        roles.contains(&("ops@example.com", "operator")),
+        "{roles:?}"
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn invites_are_admin_only_and_revocable() {
+    let app = TestApp::new().await;
+    let admin_token = app.bootstrap_admin("admin@example.com").await;
+
+    let issued = app
+        .create_invite(&admin_token, json!({ "email": "member@example.com" }))
+        .await;
+    let (status, registered) = app
+        .try_register(
+            "member@example.com",
+            Some(issued["token"].as_str().expect("token")),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{registered:?}");
+    let member_token = registered["access_token"].as_str().expect("token");
+
+    let (status, _) = app
+        .send_json(
+            Method::POST,
+            "/v1/admin/invites",
+            Some(member_token),
+            json!({ "email": "friend@example.com" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app.get_json("/v1/admin/invites", Some(member_token)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = app
+        .send_json(
+            Method::POST,
+            "/v1/admin/invites",
+            Some(&admin_token),
+            json!({ "email": "member@example.com" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "email_taken");
+    let (status, body) = app
+        .send_json(
+            Method::POST,
+            "/v1/admin/invites",
+            Some(&admin_token),
+            json!({ "email": "friend@example.com", "ttl": "90d" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_ttl");
+
+    let pending = app
+        .create_invite(&admin_token, json!({ "email": "friend@example.com" }))
+        .await;
+    let pending_id = pending["invite"]["id"].as_str().expect("id");
+    let (status, revoked) = app
+        .send(
+            &app.app,
+            Method::DELETE,
+            &format!("/v1/admin/invites/{pending_id}"),
+            Some(&admin_token),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{revoked:?}");
+    assert_eq!(revoked["status"], "revoked");
+
+    let (status, body) = app
+        .try_register(
+            "friend@example.com",
+            Some(pending["token"].as_str().expect("token")),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "invite_invalid");
+
+    let accepted_id = issued["invite"]["id"].as_str().expect("id");
+    let (status, body) = app
+        .send(
+            &app.app,
+            Method::DELETE,
+            &format!("/v1/admin/invites/{accepted_id}"),
+            Some(&admin_token),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "invite_accepted");
+}
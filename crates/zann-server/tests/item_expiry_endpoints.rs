@@ -0,0 +1,546 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use chrono::{Duration as ChronoDuration, Utc};
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{
+    AuthMode, InternalRegistration, NotifierConfig, ServerConfig, SmtpNotifierConfig, SmtpTls,
+    WebhookSinkConfig,
+};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::items::expiry::run_expiry_notifications;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::notifier::Notifier;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    state: AppState,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+        config.expiry.notify_within = "30d".to_string();
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state.clone()),
+            state,
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password-1",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&payload).expect("encode")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> String {
+        let (status, vault) = self
+            .send(
+                Method::POST,
+                "/v1/vaults",
+                token,
+                Some(json!({
+                    "slug": slug,
+                    "name": "Shared Vault",
+                    "kind": VaultKind::Shared.as_i32(),
+                    "cache_policy": CachePolicy::Full.as_i32(),
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            vault
+        );
+        vault["id"].as_str().expect("vault id").to_string()
+    }
+
+    async fn create_item(
+        &self,
+        token: &str,
+        vault_id: &str,
+        path: &str,
+        expires_at: Option<&str>,
+    ) -> serde_json::Value {
+        let (status, item) = self
+            .send(
+                Method::POST,
+                "/v1/shared/items",
+                token,
+                Some(json!({
+                    "vault_id": vault_id,
+                    "path": path,
+                    "type_id": "secret",
+                    "payload": {
+                        "v": 1,
+                        "typeId": "secret",
+                        "fields": { "token": { "kind": "text", "value": "t-1" } }
+                    },
+                    "expires_at": expires_at,
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "create failed: {item:?}");
+        item
+    }
+
+    async fn expiring_paths(&self, token: &str, vault_id: &str, within: &str) -> Vec<String> {
+        let (status, body) = self
+            .send(
+                Method::GET,
+                &format!("/v1/shared/items?vault_id={vault_id}&expiring_within={within}"),
+                token,
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "list failed: {body:?}");
+        let mut paths: Vec<String> = body["items"]
+            .as_array()
+            .expect("items")
+            .iter()
+            .map(|item| item["path"].as_str().expect("path").to_string())
+            .collect();
+        paths.sort();
+        paths
+    }
+}
+
+fn date_in(days: i64) -> String {
+    (Utc::now() + ChronoDuration::days(days))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Accepts webhook deliveries until dropped and forwards each JSON body.
+async fn webhook_stub() -> (
+    String,
+    tokio::sync::mpsc::UnboundedReceiver<serde_json::Value>,
+) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let url = format!("http://{}/expiry", listener.local_addr().expect("addr"));
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let read = socket.read(&mut buf).await.expect("read");
+                request.extend_from_slice(&buf[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|value| value.trim().parse::<usize>().unwrap_or(0))
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        let _ = tx.send(serde_json::from_str(body).expect("json body"));
+                        break;
+                    }
+                }
+                if read == 0 {
+                    break;
+                }
+            }
+            let response = "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+    (url, rx)
+}
+
+/// A minimal SMTP server that accepts one message and returns the transcript.
+async fn smtp_stub() -> (u16, tokio::task::JoinHandle<String>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let port = listener.local_addr().expect("addr").port();
+    let handle = tokio::spawn(async move {
+        let (socket, _) = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+            .await
+            .expect("accept timeout")
+            .expect("accept");
+        let (read, mut write) = socket.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let mut transcript = String::new();
+        write.write_all(b"220 stub ESMTP\r\n").await.expect("greet");
+        let mut in_data = false;
+        while let Ok(Some(line)) = lines.next_line().await {
+            transcript.push_str(&line);
+            transcript.push('\n');
+            let reply: &[u8] = if in_data {
+                if line != "." {
+                    continue;
+                }
+                in_data = false;
+                b"250 queued\r\n"
+            } else if line == "DATA" {
+                in_data = true;
+                b"354 go ahead\r\n"
+            } else if line == "QUIT" {
+                write.write_all(b"221 bye\r\n").await.expect("write");
+                break;
+            } else {
+                b"250 ok\r\n"
+            };
+            write.write_all(reply).await.expect("write");
+        }
+        transcript
+    });
+    (port, handle)
+}
+
+fn webhook_notifier(url: String) -> Notifier {
+    Notifier::new(&NotifierConfig::Webhook(WebhookSinkConfig {
+        url,
+        headers: HashMap::new(),
+        timeout_seconds: 5,
+        max_retries: 0,
+        initial_backoff_ms: 10,
+        max_backoff_ms: 10,
+    }))
+    .expect("notifier")
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn expiry_dates_are_set_filtered_and_cleared() {
+    let app = TestApp::new().await;
+    let token = app.register("expiry-owner@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "expiry-api").await;
+
+    let soon = date_in(10);
+    let item = app
+        .create_item(&token, &vault_id, "vendors/stripe", Some(&soon))
+        .await;
+    assert_eq!(
+        item["expires_at"].as_str().expect("expires_at"),
+        format!("{soon}T00:00:00+00:00")
+    );
+    app.create_item(&token, &vault_id, "vendors/github", Some(&date_in(90)))
+        .await;
+    app.create_item(&token, &vault_id, "vendors/none", None)
+        .await;
+    app.create_item(&token, &vault_id, "vendors/old", Some(&date_in(-3)))
+        .await;
+
+    assert_eq!(
+        app.expiring_paths(&token, &vault_id, "30d").await,
+        vec!["vendors/old".to_string(), "vendors/stripe".to_string()]
+    );
+    assert_eq!(app.expiring_paths(&token, &vault_id, "120d").await.len(), 3);
+
+    let (status, body) = app
+        .send(
+            Method::GET,
+            &format!("/v1/shared/items?vault_id={vault_id}&expiring_within=soon"),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body:?}");
+
+    let (status, body) = app
+        .send(
+            Method::POST,
+            "/v1/shared/items",
+            &token,
+            Some(json!({
+                "vault_id": vault_id,
+                "path": "vendors/bad",
+                "type_id": "secret",
+                "payload": { "v": 1, "typeId": "secret", "fields": {} },
+                "expires_at": "next week",
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body:?}");
+
+    // An update without the field keeps the date; null clears it.
+    let item_id = item["id"].as_str().expect("item id");
+    let (status, updated) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/shared/items/{item_id}"),
+            &token,
+            Some(json!({
+                "payload": {
+                    "v": 1,
+                    "typeId": "secret",
+                    "fields": { "token": { "kind": "text", "value": "t-2" } }
+                }
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "update failed: {updated:?}");
+    assert_eq!(updated["expires_at"], item["expires_at"]);
+
+    let (status, updated) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/shared/items/{item_id}"),
+            &token,
+            Some(json!({
+                "payload": {
+                    "v": 1,
+                    "typeId": "secret",
+                    "fields": { "token": { "kind": "text", "value": "t-1" } }
+                },
+                "expires_at": null,
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "clear failed: {updated:?}");
+    assert!(updated["expires_at"].is_null());
+    assert_eq!(
+        app.expiring_paths(&token, &vault_id, "30d").await,
+        vec!["vendors/old".to_string()]
+    );
+
+    // Secrets accept a date alongside the value and keep it on later writes.
+    let (status, secret) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/vaults/{vault_id}/secrets/vendors/aws"),
+            &token,
+            Some(json!({ "value": "key-1", "expires_at": "2027-01-01" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "secret set failed: {secret:?}");
+    assert_eq!(secret["expires_at"], "2027-01-01T00:00:00+00:00");
+
+    let (status, secret) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/vaults/{vault_id}/secrets/vendors/aws"),
+            &token,
+            Some(json!({ "value": "key-2" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "secret update failed: {secret:?}");
+    assert_eq!(secret["expires_at"], "2027-01-01T00:00:00+00:00");
+
+    let (status, secret) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{vault_id}/secrets/vendors/aws"),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "secret get failed: {secret:?}");
+    assert_eq!(secret["expires_at"], "2027-01-01T00:00:00+00:00");
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn expiry_notices_are_sent_once_per_date() {
+    let app = TestApp::new().await;
+    let token = app.register("expiry-notify@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "expiry-notify").await;
+
+    let item = app
+        .create_item(&token, &vault_id, "vendors/stripe", Some(&date_in(5)))
+        .await;
+    app.create_item(&token, &vault_id, "vendors/later", Some(&date_in(90)))
+        .await;
+    app.create_item(&token, &vault_id, "vendors/none", None)
+        .await;
+
+    let (url, mut received) = webhook_stub().await;
+    let notifier = webhook_notifier(url);
+    let sent = run_expiry_notifications(&app.state, &notifier)
+        .await
+        .expect("run");
+    assert_eq!(sent, 1);
+    let notice = received.recv().await.expect("notice");
+    assert_eq!(notice["event"], "item.expiring");
+    assert_eq!(notice["data"]["path"], "vendors/stripe");
+    assert_eq!(notice["data"]["vault_name"], "Shared Vault");
+    assert_eq!(notice["data"]["expired"], false);
+
+    let sent = run_expiry_notifications(&app.state, &notifier)
+        .await
+        .expect("rerun");
+    assert_eq!(sent, 0, "an item is announced once per date");
+
+    // Moving the date into the past announces it again, now as expired.
+    let item_id = item["id"].as_str().expect("item id");
+    let (status, updated) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/shared/items/{item_id}"),
+            &token,
+            Some(json!({
+                "payload": {
+                    "v": 1,
+                    "typeId": "secret",
+                    "fields": { "token": { "kind": "text", "value": "t-1" } }
+                },
+                "expires_at": date_in(-1),
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "update failed: {updated:?}");
+    let sent = run_expiry_notifications(&app.state, &notifier)
+        .await
+        .expect("run after change");
+    assert_eq!(sent, 1);
+    let notice = received.recv().await.expect("notice");
+    assert_eq!(notice["event"], "item.expired");
+    assert_eq!(notice["data"]["expired"], true);
+
+    // A notifier that cannot deliver leaves the item pending for the next run.
+    app.create_item(&token, &vault_id, "vendors/mail", Some(&date_in(3)))
+        .await;
+    let unreachable = webhook_notifier("http://127.0.0.1:9/expiry".to_string());
+    let sent = run_expiry_notifications(&app.state, &unreachable)
+        .await
+        .expect("failed run");
+    assert_eq!(sent, 0);
+
+    let (port, transcript) = smtp_stub().await;
+    let smtp = Notifier::new(&NotifierConfig::Smtp(SmtpNotifierConfig {
+        host: "127.0.0.1".to_string(),
+        port,
+        tls: SmtpTls::None,
+        username: None,
+        password: None,
+        from: "zann@example.com".to_string(),
+        to: vec!["ops@example.com".to_string()],
+        helo_name: None,
+        timeout_seconds: 5,
+    }))
+    .expect("smtp notifier");
+    let sent = run_expiry_notifications(&app.state, &smtp)
+        .await
+        .expect("smtp run");
+    assert_eq!(sent, 1);
+    let transcript = transcript.await.expect("transcript");
+    assert!(
+        transcript.contains("MAIL FROM:<zann@example.com>"),
+        "{transcript}"
+    );
+    assert!(
+        transcript.contains("RCPT TO:<ops@example.com>"),
+        "{transcript}"
+    );
+    assert!(
+        transcript.contains("Shared Vault/vendors/mail expires on"),
+        "{transcript}"
+    );
+}
@@ -11,6 +11,7 @@ use zann_server::domains::access_control::policy_store::PolicyStore;
 use zann_server::domains::auth::core::identity::identity_from_oidc;
 use zann_server::domains::auth::core::oidc::OidcJwksCache;
 use zann_server::domains::auth::core::passwords::random_kdf_salt;
+use zann_server::domains::system::seal::MasterKeyStore;
 use zann_server::infra::security_profiles::load_security_profiles;
 use zann_server::infra::usage::UsageTracker;
 
@@ -27,7 +28,7 @@ async fn build_state(pool: PgPool, mut config: ServerConfig) -> AppState {
         started_at: std::time::Instant::now(),
         password_pepper: "pepper".to_string(),
         token_pepper: "pepper".to_string(),
-        server_master_key: None,
+        server_master_key: MasterKeyStore::new(None),
 
         identity_key: support::test_identity_key(),
         access_token_ttl_seconds: 3600,
@@ -0,0 +1,641 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair, KeyUsagePurpose};
+use serde_json::json;
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state),
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password-1",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&payload).expect("encode")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> String {
+        let (status, vault) = self
+            .send(
+                Method::POST,
+                "/v1/vaults",
+                token,
+                Some(json!({
+                    "slug": slug,
+                    "name": "Shared Vault",
+                    "kind": VaultKind::Shared.as_i32(),
+                    "cache_policy": CachePolicy::Full.as_i32(),
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            vault
+        );
+        vault["id"].as_str().expect("vault id").to_string()
+    }
+
+    async fn add_member(&self, owner: &str, vault_id: &str, email: &str, role: &str) {
+        let (status, body) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/members", vault_id),
+                owner,
+                Some(json!({ "email": email, "role": role })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "{body:?}");
+    }
+
+    async fn service_account_token(
+        &self,
+        owner: &str,
+        slug: &str,
+        prefix: &str,
+        ops: &[&str],
+    ) -> String {
+        let (status, created) = self
+            .send(
+                Method::POST,
+                "/v1/service-accounts",
+                owner,
+                Some(json!({
+                    "name": format!("pki-{}", ops.join("-")),
+                    "vault": slug,
+                    "prefixes": [prefix],
+                    "ops": ops,
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "create failed: {:?}", created);
+        created["token"].as_str().expect("sa token").to_string()
+    }
+
+    async fn set_role(&self, token: &str, vault_id: &str, name: &str, role: serde_json::Value) {
+        let (status, body) = self
+            .send(
+                Method::PUT,
+                &format!("/v1/vaults/{}/pki/roles/{}", vault_id, name),
+                token,
+                Some(role),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "set role failed: {body:?}");
+    }
+
+    async fn issue(
+        &self,
+        token: &str,
+        vault_id: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        self.send(
+            Method::POST,
+            &format!("/v1/vaults/{}/pki/issue", vault_id),
+            token,
+            Some(body),
+        )
+        .await
+    }
+
+    /// Generates a root and an intermediate and adds a `web` role for
+    /// `example.com` and its subdomains.
+    async fn setup_pki(&self, token: &str, vault_id: &str) -> serde_json::Value {
+        let (status, root) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/pki/root", vault_id),
+                token,
+                Some(json!({ "common_name": "Example Root CA" })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "create root failed: {root:?}");
+        let (status, intermediate) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/pki/intermediate", vault_id),
+                token,
+                Some(json!({ "common_name": "Example Issuing CA", "ttl": "8760h" })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "create intermediate failed: {intermediate:?}"
+        );
+        self.set_role(
+            token,
+            vault_id,
+            "web",
+            json!({
+                "allowed_domains": ["example.com"],
+                "allow_subdomains": true,
+                "default_ttl": "24h",
+                "max_ttl": "72h"
+            }),
+        )
+        .await;
+        intermediate
+    }
+}
+
+fn parse_pem(pem: &str) -> x509_parser::pem::Pem {
+    let (_, pem) = x509_parser::pem::parse_x509_pem(pem.as_bytes()).expect("pem");
+    pem
+}
+
+fn self_signed_ca(common_name: &str) -> (String, String) {
+    let key = KeyPair::generate().expect("key");
+    let mut params = CertificateParams::default();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+    let cert = params.self_signed(&key).expect("self signed");
+    (cert.pem(), key.serialize_pem())
+}
+
+/// An intermediate CA signed by the given CA certificate and key.
+fn intermediate_ca(common_name: &str, issuer: (&str, &str)) -> (String, String) {
+    let issuer_key = KeyPair::from_pem(issuer.1).expect("issuer key");
+    let issuer = Issuer::from_ca_cert_pem(issuer.0, issuer_key).expect("issuer");
+    let key = KeyPair::generate().expect("key");
+    let mut params = CertificateParams::default();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+    params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+    let cert = params.signed_by(&key, &issuer).expect("signed");
+    (cert.pem(), key.serialize_pem())
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn pki_issues_leaf_certificates_from_intermediate() {
+    let app = TestApp::new().await;
+    let token = app.register("alice@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "pki").await;
+
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &format!("/v1/vaults/{}/pki/intermediate", vault_id),
+            &token,
+            Some(json!({ "common_name": "Orphan CA" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "root_ca_required");
+
+    app.setup_pki(&token, &vault_id).await;
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &format!("/v1/vaults/{}/pki/root", vault_id),
+            &token,
+            Some(json!({ "common_name": "Second Root" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "pki_ca_exists");
+
+    let (status, chain) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{}/pki/ca", vault_id),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(chain["ca_chain"].as_array().expect("chain").len(), 2);
+    let root_pem = parse_pem(chain["root"]["certificate"].as_str().expect("root"));
+    let root = root_pem.parse_x509().expect("root cert");
+    let intermediate_pem = parse_pem(
+        chain["intermediate"]["certificate"]
+            .as_str()
+            .expect("intermediate"),
+    );
+    let intermediate = intermediate_pem.parse_x509().expect("intermediate cert");
+    assert!(intermediate.is_ca());
+    intermediate
+        .verify_signature(Some(root.public_key()))
+        .expect("intermediate signed by root");
+
+    let (status, issued) = app
+        .issue(
+            &token,
+            &vault_id,
+            json!({
+                "role": "web",
+                "common_name": "api.example.com",
+                "alt_names": ["example.com", "*.api.example.com"],
+                "ttl": "30d"
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "issue failed: {issued:?}");
+    assert_eq!(
+        issued["sans"],
+        json!(["api.example.com", "example.com", "*.api.example.com"])
+    );
+    assert_eq!(issued["issuing_ca"], chain["intermediate"]["certificate"]);
+    let leaf_pem = parse_pem(issued["certificate"].as_str().expect("certificate"));
+    let leaf = leaf_pem.parse_x509().expect("leaf cert");
+    leaf.verify_signature(Some(intermediate.public_key()))
+        .expect("leaf signed by intermediate");
+    assert!(!leaf.is_ca());
+    assert_eq!(hex::encode(leaf.raw_serial()), issued["serial"]);
+    let key = KeyPair::from_pem(issued["private_key"].as_str().expect("key")).expect("leaf key");
+    assert_eq!(
+        leaf.public_key().subject_public_key.data.as_ref(),
+        key.public_key_raw()
+    );
+    let eku = leaf
+        .extended_key_usage()
+        .expect("eku")
+        .expect("eku present")
+        .value;
+    assert!(eku.server_auth && !eku.client_auth);
+    let lifetime = leaf.validity().not_after.timestamp() - leaf.validity().not_before.timestamp();
+    assert!(lifetime <= 72 * 3600 + 60, "lifetime {lifetime}");
+
+    let (status, body) = app
+        .issue(
+            &token,
+            &vault_id,
+            json!({ "role": "web", "common_name": "example.org" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "name_not_allowed");
+    let (status, body) = app
+        .issue(
+            &token,
+            &vault_id,
+            json!({ "role": "web", "common_name": "example.com", "ip_sans": ["10.0.0.1"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "ip_sans_not_allowed");
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn pki_revoked_serials_are_listed_on_crl() {
+    let app = TestApp::new().await;
+    let token = app.register("alice@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "pki-crl").await;
+    let intermediate = app.setup_pki(&token, &vault_id).await;
+
+    let mut serials = Vec::new();
+    for name in ["a.example.com", "b.example.com"] {
+        let (status, issued) = app
+            .issue(
+                &token,
+                &vault_id,
+                json!({ "role": "web", "common_name": name }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "issue failed: {issued:?}");
+        serials.push(issued["serial"].as_str().expect("serial").to_string());
+    }
+
+    let (status, listed) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{}/pki/certs", vault_id),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let listed = listed["certificates"].as_array().expect("certificates");
+    assert_eq!(listed.len(), 2);
+    assert!(listed.iter().all(|cert| cert["revoked_at"].is_null()));
+
+    let revoked = &serials[0];
+    let colon_serial = revoked
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| std::str::from_utf8(pair).expect("hex").to_ascii_uppercase())
+        .collect::<Vec<_>>()
+        .join(":");
+    let revoke_uri = format!("/v1/vaults/{}/pki/certs/{}/revoke", vault_id, colon_serial);
+    let (status, body) = app.send(Method::POST, &revoke_uri, &token, None).await;
+    assert_eq!(status, StatusCode::OK, "revoke failed: {body:?}");
+    assert_eq!(&body["serial"], revoked.as_str());
+    assert!(body["revoked_at"].is_string());
+    let (status, body) = app.send(Method::POST, &revoke_uri, &token, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "already_revoked");
+
+    let (status, crl) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{}/pki/crl", vault_id),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "crl failed: {crl:?}");
+    assert_eq!(crl["revoked"], 1);
+    assert_eq!(crl["issuer_serial"], intermediate["serial"]);
+    let crl_pem = parse_pem(crl["crl"].as_str().expect("crl"));
+    let (_, parsed) = x509_parser::parse_x509_crl(&crl_pem.contents).expect("parse crl");
+    let issuer_pem = parse_pem(intermediate["certificate"].as_str().expect("intermediate"));
+    let issuer = issuer_pem.parse_x509().expect("issuer cert");
+    parsed
+        .verify_signature(issuer.public_key())
+        .expect("crl signed by intermediate");
+    let listed: Vec<String> = parsed
+        .iter_revoked_certificates()
+        .map(|cert| hex::encode(cert.raw_serial()))
+        .collect();
+    assert_eq!(listed, vec![revoked.clone()]);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn pki_import_and_access_control() {
+    let app = TestApp::new().await;
+    let owner = app.register("owner@example.com").await;
+    let member = app.register("member@example.com").await;
+    let reader = app.register("reader@example.com").await;
+    let vault_id = app.create_shared_vault(&owner, "pki-import").await;
+    app.add_member(&owner, &vault_id, "member@example.com", "member")
+        .await;
+    app.add_member(&owner, &vault_id, "reader@example.com", "readonly")
+        .await;
+    let root_uri = format!("/v1/vaults/{}/pki/root", vault_id);
+
+    let (certificate, private_key) = self_signed_ca("Imported Root");
+    let (_, other_key) = self_signed_ca("Other Root");
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &root_uri,
+            &owner,
+            Some(json!({ "certificate": certificate, "private_key": other_key })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "private_key_mismatch");
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &root_uri,
+            &member,
+            Some(json!({ "certificate": certificate, "private_key": private_key })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "{body:?}");
+    let (status, root) = app
+        .send(
+            Method::POST,
+            &root_uri,
+            &owner,
+            Some(json!({ "certificate": certificate, "private_key": private_key })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "import failed: {root:?}");
+    assert_eq!(root["kind"], "root");
+    assert_eq!(root["common_name"], "Imported Root");
+
+    app.set_role(
+        &owner,
+        &vault_id,
+        "internal",
+        json!({
+            "allowed_domains": ["internal"],
+            "allow_subdomains": true,
+            "allow_ip_sans": true,
+            "key_type": "ed25519",
+            "client_auth": true
+        }),
+    )
+    .await;
+
+    let request = json!({
+        "role": "internal",
+        "common_name": "db.internal",
+        "ip_sans": ["10.0.0.5"]
+    });
+    let (status, issued) = app.issue(&member, &vault_id, request.clone()).await;
+    assert_eq!(status, StatusCode::OK, "member issue failed: {issued:?}");
+    assert_eq!(issued["sans"], json!(["db.internal", "10.0.0.5"]));
+    assert_eq!(issued["ca_chain"], json!([certificate]));
+    let (status, _) = app.issue(&reader, &vault_id, request.clone()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/vaults/{}/pki/roles/internal", vault_id),
+            &member,
+            Some(json!({ "allowed_domains": ["*"] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let sa_token = app
+        .service_account_token(&owner, "pki-import", "/pki/roles/internal", &["pki_issue"])
+        .await;
+    let (status, issued) = app.issue(&sa_token, &vault_id, request).await;
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "service account issue failed: {issued:?}"
+    );
+    let (status, _) = app
+        .send(
+            Method::DELETE,
+            &format!("/v1/vaults/{}/pki/ca", vault_id),
+            &sa_token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn pki_import_rejects_intermediate_with_forged_issuer() {
+    let app = TestApp::new().await;
+    let owner = app.register("owner@example.com").await;
+    let vault_id = app.create_shared_vault(&owner, "pki-forged").await;
+    let root_uri = format!("/v1/vaults/{}/pki/root", vault_id);
+    let (root, root_key) = self_signed_ca("Imported Root");
+
+    // A root must be self-signed: neither a CA issued by another root nor a
+    // certificate that only names itself as issuer is accepted.
+    let (issued, issued_key) = intermediate_ca("Issued Root", (&root, &root_key));
+    let (lookalike, lookalike_key) = intermediate_ca("Imported Root", (&root, &root_key));
+    for (certificate, private_key) in [(issued, issued_key), (lookalike, lookalike_key)] {
+        let (status, body) = app
+            .send(
+                Method::POST,
+                &root_uri,
+                &owner,
+                Some(json!({ "certificate": certificate, "private_key": private_key })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{body:?}");
+        assert_eq!(body["error"], "root_not_self_signed");
+    }
+
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &root_uri,
+            &owner,
+            Some(json!({ "certificate": root, "private_key": root_key })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{body:?}");
+    let intermediate_uri = format!("/v1/vaults/{}/pki/intermediate", vault_id);
+
+    // Same issuer name as the vault root, but signed by an unrelated key.
+    let (impostor, impostor_key) = self_signed_ca("Imported Root");
+    let (forged, forged_key) = intermediate_ca("Forged Intermediate", (&impostor, &impostor_key));
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &intermediate_uri,
+            &owner,
+            Some(json!({ "certificate": forged, "private_key": forged_key })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "{body:?}");
+    assert_eq!(body["error"], "intermediate_not_issued_by_root");
+
+    let (genuine, genuine_key) = intermediate_ca("Imported Intermediate", (&root, &root_key));
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &intermediate_uri,
+            &owner,
+            Some(json!({ "certificate": genuine, "private_key": genuine_key })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{body:?}");
+    assert_eq!(body["common_name"], "Imported Intermediate");
+}
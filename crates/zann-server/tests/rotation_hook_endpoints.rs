@@ -0,0 +1,540 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use sqlx_core::connection::Connection;
+use sqlx_core::executor::Executor;
+use sqlx_postgres::{PgConnectOptions, PgConnection, Postgres};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::domains::vaults::rotation_hooks::webhook::{
+    sign, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    pool: PgPool,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+        config.rotation.hook_timeout_seconds = 5;
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state),
+            pool,
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password-1",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&payload).expect("encode")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_item(&self, token: &str, slug: &str, path: &str) -> (String, String) {
+        let (status, vault) = self
+            .send(
+                Method::POST,
+                "/v1/vaults",
+                token,
+                Some(json!({
+                    "slug": slug,
+                    "name": "Shared Vault",
+                    "kind": VaultKind::Shared.as_i32(),
+                    "cache_policy": CachePolicy::Full.as_i32(),
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            vault
+        );
+        let vault_id = vault["id"].as_str().expect("vault id").to_string();
+        let (status, item) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/items", vault_id),
+                token,
+                Some(json!({
+                    "path": path,
+                    "name": path,
+                    "type_id": "login",
+                    "payload": {
+                        "v": 1,
+                        "typeId": "login",
+                        "fields": {
+                            "password": { "kind": "password", "value": "secret" }
+                        }
+                    }
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "item create failed: {:?}",
+            item
+        );
+        (vault_id, item["id"].as_str().expect("item id").to_string())
+    }
+
+    async fn start_rotation(&self, token: &str, item_id: &str) -> String {
+        let (status, body) = self
+            .send(
+                Method::POST,
+                &format!("/v1/shared/items/{}/rotate/start", item_id),
+                token,
+                Some(json!({})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "rotate start failed: {:?}", body);
+        let (status, body) = self
+            .send(
+                Method::POST,
+                &format!("/v1/shared/items/{}/rotate/candidate", item_id),
+                token,
+                Some(json!({})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "candidate failed: {:?}", body);
+        body["candidate"].as_str().expect("candidate").to_string()
+    }
+
+    async fn rotation_status(&self, token: &str, item_id: &str) -> serde_json::Value {
+        let (status, body) = self
+            .send(
+                Method::GET,
+                &format!("/v1/shared/items/{}/rotate/status", item_id),
+                token,
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        body
+    }
+}
+
+struct HookRequest {
+    headers: String,
+    body: String,
+}
+
+/// Answers one request per entry in `statuses` and returns what it received.
+async fn hook_stub(
+    statuses: Vec<&'static str>,
+) -> (String, tokio::task::JoinHandle<Vec<HookRequest>>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let url = format!("http://{}/rotate", listener.local_addr().expect("addr"));
+    let handle = tokio::spawn(async move {
+        let mut received = Vec::new();
+        for status in statuses {
+            let Ok(Ok((mut socket, _))) =
+                tokio::time::timeout(Duration::from_secs(5), listener.accept()).await
+            else {
+                break;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let read = socket.read(&mut buf).await.expect("read");
+                request.extend_from_slice(&buf[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|value| value.trim().parse::<usize>().unwrap_or(0))
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        received.push(HookRequest {
+                            headers: head.to_ascii_lowercase(),
+                            body: body.to_string(),
+                        });
+                        break;
+                    }
+                }
+                if read == 0 {
+                    break;
+                }
+            }
+            let response =
+                format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+            socket.write_all(response.as_bytes()).await.expect("write");
+        }
+        received
+    });
+    (url, handle)
+}
+
+fn header_value<'a>(headers: &'a str, name: &str) -> &'a str {
+    headers
+        .lines()
+        .find_map(|line| line.strip_prefix(&format!("{name}:")))
+        .map(str::trim)
+        .expect("header present")
+}
+
+const HOOK_SECRET: &str = "0123456789abcdef0123";
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn postgres_hook_sets_role_password_on_commit() {
+    let app = TestApp::new().await;
+    let token = app.register("hook-pg@example.com").await;
+    let (_, item_id) = app.create_shared_item(&token, "pg-hook", "db/app").await;
+
+    let role = format!("zann_hook_{}", Uuid::now_v7().simple());
+    sqlx_core::query::query::<Postgres>(&format!("CREATE ROLE \"{role}\" LOGIN PASSWORD 'secret'"))
+        .execute(&app.pool)
+        .await
+        .expect("create role");
+
+    let db_url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+    let hook_uri = format!("/v1/shared/items/{}/rotation/hook", item_id);
+    let (status, hook) = app
+        .send(
+            Method::PUT,
+            &hook_uri,
+            &token,
+            Some(json!({ "kind": "postgres", "connection_url": db_url, "role": role })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "set hook failed: {:?}", hook);
+    assert_eq!(hook["kind"], "postgres");
+    assert_eq!(hook["verify"], true);
+    let target = hook["target"].as_str().expect("target");
+    assert!(target.ends_with(&format!("role={role}")));
+    assert!(!hook.to_string().contains(&db_url));
+
+    let candidate = app.start_rotation(&token, &item_id).await;
+    let (status, commit) = app
+        .send(
+            Method::POST,
+            &format!("/v1/shared/items/{}/rotate/commit", item_id),
+            &token,
+            Some(json!({})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "commit failed: {:?}", commit);
+    assert_eq!(commit["status"], "committed");
+
+    let options = PgConnectOptions::from_str(&db_url)
+        .expect("db url")
+        .username(&role)
+        .password(&candidate);
+    let mut conn = PgConnection::connect_with(&options)
+        .await
+        .expect("login with rotated password");
+    conn.execute("SELECT 1").await.expect("query as role");
+    conn.close().await.expect("close");
+
+    let (status, _) = app.send(Method::DELETE, &hook_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = app.send(Method::GET, &hook_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    sqlx_core::query::query::<Postgres>(&format!("DROP ROLE \"{role}\""))
+        .execute(&app.pool)
+        .await
+        .expect("drop role");
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn failed_hook_apply_aborts_rotation() {
+    let app = TestApp::new().await;
+    let token = app.register("hook-apply@example.com").await;
+    let (_, item_id) = app
+        .create_shared_item(&token, "apply-hook", "api/key")
+        .await;
+    let (url, stub) = hook_stub(vec!["500 Internal Server Error"]).await;
+
+    let (status, hook) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/shared/items/{}/rotation/hook", item_id),
+            &token,
+            Some(json!({ "kind": "webhook", "url": url, "secret": HOOK_SECRET })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "set hook failed: {:?}", hook);
+    assert_eq!(hook["target"], url.as_str());
+
+    let candidate = app.start_rotation(&token, &item_id).await;
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &format!("/v1/shared/items/{}/rotate/commit", item_id),
+            &token,
+            Some(json!({})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert_eq!(body["error"], "rotation_hook_failed");
+
+    let requests = stub.await.expect("stub");
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    let payload: serde_json::Value = serde_json::from_str(&request.body).expect("hook body");
+    assert_eq!(payload["event"], "rotation.apply");
+    assert_eq!(payload["item_id"], item_id.as_str());
+    assert_eq!(payload["password"], candidate.as_str());
+    let timestamp = header_value(&request.headers, TIMESTAMP_HEADER);
+    assert_eq!(
+        header_value(&request.headers, SIGNATURE_HEADER),
+        sign(HOOK_SECRET, timestamp, request.body.as_bytes())
+    );
+
+    let rotation = app.rotation_status(&token, &item_id).await;
+    assert_eq!(rotation["state"], "active");
+    assert_eq!(rotation["aborted_reason"], "hook_apply_failed: webhook");
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn failed_hook_verify_keeps_candidate_recoverable() {
+    let app = TestApp::new().await;
+    let token = app.register("hook-verify@example.com").await;
+    let (_, item_id) = app
+        .create_shared_item(&token, "verify-hook", "api/key")
+        .await;
+    let (url, stub) = hook_stub(vec!["204 No Content", "503 Service Unavailable"]).await;
+
+    let (status, _) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/shared/items/{}/rotation/hook", item_id),
+            &token,
+            Some(json!({ "kind": "webhook", "url": url, "secret": HOOK_SECRET })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let candidate = app.start_rotation(&token, &item_id).await;
+    let (status, commit) = app
+        .send(
+            Method::POST,
+            &format!("/v1/shared/items/{}/rotate/commit", item_id),
+            &token,
+            Some(json!({})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "commit failed: {:?}", commit);
+    assert_eq!(commit["status"], "verification_failed");
+
+    let requests = stub.await.expect("stub");
+    let events: Vec<String> = requests
+        .iter()
+        .map(|request| {
+            let payload: serde_json::Value =
+                serde_json::from_str(&request.body).expect("hook body");
+            payload["event"].as_str().expect("event").to_string()
+        })
+        .collect();
+    assert_eq!(events, ["rotation.apply", "rotation.verify"]);
+
+    let rotation = app.rotation_status(&token, &item_id).await;
+    assert_eq!(rotation["state"], "stale");
+    assert_eq!(rotation["aborted_reason"], "hook_verify_failed: webhook");
+    let (status, recovered) = app
+        .send(
+            Method::POST,
+            &format!("/v1/shared/items/{}/rotate/recover", item_id),
+            &token,
+            Some(json!({})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "recover failed: {:?}", recovered);
+    assert_eq!(recovered["candidate"], candidate.as_str());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn rotation_hook_requires_vault_admin_and_valid_config() {
+    let app = TestApp::new().await;
+    let token = app.register("hook-owner@example.com").await;
+    let (vault_id, item_id) = app.create_shared_item(&token, "hook-guard", "db/app").await;
+    let hook_uri = format!("/v1/shared/items/{}/rotation/hook", item_id);
+
+    let (status, body) = app
+        .send(
+            Method::PUT,
+            &hook_uri,
+            &token,
+            Some(
+                json!({ "kind": "webhook", "url": "https://hooks.example.com", "secret": "short" }),
+            ),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_secret");
+
+    let (status, body) = app
+        .send(
+            Method::PUT,
+            &hook_uri,
+            &token,
+            Some(json!({ "kind": "postgres", "connection_url": "mysql://db", "role": "app" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_connection_url");
+
+    let hook = json!({
+        "kind": "webhook",
+        "url": "https://hooks.example.com/rotate",
+        "secret": HOOK_SECRET,
+        "verify": false,
+    });
+    let outsider = app.register("hook-outsider@example.com").await;
+    let (status, _) = app
+        .send(Method::PUT, &hook_uri, &outsider, Some(hook.clone()))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let operator = app.register("hook-operator@example.com").await;
+    let (status, added) = app
+        .send(
+            Method::POST,
+            &format!("/v1/vaults/{}/members", vault_id),
+            &token,
+            Some(json!({ "email": "hook-operator@example.com", "role": "operator" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{added:?}");
+    let (status, _) = app
+        .send(Method::PUT, &hook_uri, &operator, Some(hook.clone()))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, created) = app.send(Method::PUT, &hook_uri, &token, Some(hook)).await;
+    assert_eq!(status, StatusCode::OK, "set hook failed: {:?}", created);
+    assert_eq!(created["verify"], false);
+
+    // Operators can see that a hook exists but not change it.
+    let (status, read) = app.send(Method::GET, &hook_uri, &operator, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(read["kind"], "webhook");
+    assert!(!read.to_string().contains(HOOK_SECRET));
+    let (status, _) = app.send(Method::DELETE, &hook_uri, &operator, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
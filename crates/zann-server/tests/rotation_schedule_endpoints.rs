@@ -0,0 +1,487 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use sqlx_postgres::Postgres;
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::domains::vaults::rotation::run_scheduled_rotations;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    state: AppState,
+    pool: PgPool,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state.clone()),
+            state,
+            pool,
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password-1",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&payload).expect("encode")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_item(&self, token: &str, slug: &str, path: &str) -> (String, String) {
+        let (status, vault) = self
+            .send(
+                Method::POST,
+                "/v1/vaults",
+                token,
+                Some(json!({
+                    "slug": slug,
+                    "name": "Shared Vault",
+                    "kind": VaultKind::Shared.as_i32(),
+                    "cache_policy": CachePolicy::Full.as_i32(),
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            vault
+        );
+        let vault_id = vault["id"].as_str().expect("vault id").to_string();
+        let (status, item) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/items", vault_id),
+                token,
+                Some(json!({
+                    "path": path,
+                    "name": path,
+                    "type_id": "login",
+                    "payload": {
+                        "v": 1,
+                        "typeId": "login",
+                        "fields": {
+                            "password": { "kind": "password", "value": "secret" }
+                        }
+                    }
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "item create failed: {:?}",
+            item
+        );
+        (vault_id, item["id"].as_str().expect("item id").to_string())
+    }
+
+    async fn backdate_schedule(&self, item_id: &str, days: i32) {
+        sqlx_core::query::query::<Postgres>(
+            "UPDATE item_rotation_schedules SET last_rotated_at = now() - make_interval(days => $2) WHERE item_id = $1",
+        )
+        .bind(Uuid::parse_str(item_id).expect("item uuid"))
+        .bind(days)
+        .execute(&self.pool)
+        .await
+        .expect("backdate schedule");
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn scheduler_starts_rotation_for_overdue_items() {
+    let app = TestApp::new().await;
+    let token = app.register("schedule@example.com").await;
+    let (vault_id, item_id) = app.create_shared_item(&token, "scheduled", "db/root").await;
+
+    let schedule_uri = format!("/v1/shared/items/{}/rotation/schedule", item_id);
+    let (status, _) = app.send(Method::GET, &schedule_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, schedule) = app
+        .send(
+            Method::PUT,
+            &schedule_uri,
+            &token,
+            Some(json!({ "schedule": "@monthly" })),
+        )
+        .await;
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "set schedule failed: {:?}",
+        schedule
+    );
+    assert_eq!(schedule["max_age_seconds"], 30 * 24 * 60 * 60);
+
+    let overdue_uri = format!("/v1/vaults/{}/rotation/overdue", vault_id);
+    let (status, report) = app.send(Method::GET, &overdue_uri, &token, None).await;
+    assert_eq!(status, StatusCode::OK, "overdue failed: {:?}", report);
+    assert_eq!(report["items"].as_array().expect("items").len(), 0);
+
+    // Nothing is due yet, so a scheduler pass leaves the item alone.
+    let started = run_scheduled_rotations(&app.state).await.expect("pass");
+    assert_eq!(started, 0);
+
+    app.backdate_schedule(&item_id, 45).await;
+    let (_, report) = app.send(Method::GET, &overdue_uri, &token, None).await;
+    let items = report["items"].as_array().expect("items");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["item_id"], item_id.as_str());
+    assert_eq!(items[0]["schedule"], "@monthly");
+    assert!(items[0]["overdue_seconds"].as_i64().expect("overdue") > 0);
+
+    let started = run_scheduled_rotations(&app.state).await.expect("pass");
+    assert_eq!(started, 1);
+    let (status, rotation) = app
+        .send(
+            Method::GET,
+            &format!("/v1/shared/items/{}/rotate/status", item_id),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(rotation["state"], "rotating");
+
+    // Already rotating: a second pass must not restart it.
+    let started = run_scheduled_rotations(&app.state).await.expect("pass");
+    assert_eq!(started, 0);
+
+    let (status, commit) = app
+        .send(
+            Method::POST,
+            &format!("/v1/shared/items/{}/rotate/commit", item_id),
+            &token,
+            Some(json!({})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "commit failed: {:?}", commit);
+
+    let (_, report) = app.send(Method::GET, &overdue_uri, &token, None).await;
+    assert_eq!(report["items"].as_array().expect("items").len(), 0);
+    let (status, schedule) = app.send(Method::GET, &schedule_uri, &token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(schedule["last_started_at"].is_string());
+    assert!(schedule["last_error"].is_null());
+
+    let (status, _) = app.send(Method::DELETE, &schedule_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = app.send(Method::GET, &schedule_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn scheduler_backs_off_failing_schedules() {
+    let mut app = TestApp::new().await;
+    app.state.config.rotation.schedule_batch_size = 2;
+    let token = app.register("backoff@example.com").await;
+
+    let mut broken = Vec::new();
+    for index in 0..3 {
+        let (_, item_id) = app
+            .create_shared_item(&token, &format!("broken-{index}"), "db/root")
+            .await;
+        broken.push(item_id);
+    }
+    let (_, healthy) = app.create_shared_item(&token, "healthy", "db/root").await;
+    for item_id in broken.iter().chain([&healthy]) {
+        let (status, schedule) = app
+            .send(
+                Method::PUT,
+                &format!("/v1/shared/items/{}/rotation/schedule", item_id),
+                &token,
+                Some(json!({ "schedule": "@monthly" })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::OK,
+            "set schedule failed: {:?}",
+            schedule
+        );
+    }
+    // The broken schedules are the oldest, so they come first in every batch.
+    for item_id in &broken {
+        app.backdate_schedule(item_id, 60).await;
+        sqlx_core::query::query::<Postgres>(
+            "UPDATE item_rotation_schedules SET password_policy = 'missing' WHERE item_id = $1",
+        )
+        .bind(Uuid::parse_str(item_id).expect("item uuid"))
+        .execute(&app.pool)
+        .await
+        .expect("break schedule");
+    }
+    app.backdate_schedule(&healthy, 45).await;
+
+    let started = run_scheduled_rotations(&app.state).await.expect("pass");
+    assert_eq!(started, 0);
+    let started = run_scheduled_rotations(&app.state).await.expect("pass");
+    assert_eq!(started, 1);
+    let (status, rotation) = app
+        .send(
+            Method::GET,
+            &format!("/v1/shared/items/{}/rotate/status", healthy),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(rotation["state"], "rotating");
+
+    for item_id in &broken {
+        let (_, schedule) = app
+            .send(
+                Method::GET,
+                &format!("/v1/shared/items/{}/rotation/schedule", item_id),
+                &token,
+                None,
+            )
+            .await;
+        assert_eq!(schedule["last_error"], "unknown_policy");
+        assert!(schedule["last_started_at"].is_string());
+    }
+    // Every broken schedule failed within the backoff window.
+    let started = run_scheduled_rotations(&app.state).await.expect("pass");
+    assert_eq!(started, 0);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn overdue_report_applies_max_age_to_unscheduled_items() {
+    let app = TestApp::new().await;
+    let token = app.register("overdue@example.com").await;
+    let (vault_id, item_id) = app
+        .create_shared_item(&token, "unscheduled", "api/token")
+        .await;
+    sqlx_core::query::query::<Postgres>(
+        "UPDATE items SET updated_at = now() - interval '100 days' WHERE id = $1",
+    )
+    .bind(Uuid::parse_str(&item_id).expect("item uuid"))
+    .execute(&app.pool)
+    .await
+    .expect("age item");
+
+    let overdue_uri = format!("/v1/vaults/{}/rotation/overdue", vault_id);
+    let (_, report) = app.send(Method::GET, &overdue_uri, &token, None).await;
+    assert_eq!(report["items"].as_array().expect("items").len(), 0);
+
+    let (status, report) = app
+        .send(
+            Method::GET,
+            &format!("{overdue_uri}?max_age=90d"),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "overdue failed: {:?}", report);
+    let items = report["items"].as_array().expect("items");
+    assert_eq!(items.len(), 1);
+    assert!(items[0]["schedule"].is_null());
+    assert_eq!(items[0]["max_age_seconds"], 90 * 24 * 60 * 60);
+
+    let (status, body) = app
+        .send(
+            Method::GET,
+            &format!("{overdue_uri}?max_age=soon"),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_max_age");
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn rotation_schedule_rejects_invalid_input_and_outsiders() {
+    let app = TestApp::new().await;
+    let token = app.register("owner@example.com").await;
+    let (vault_id, item_id) = app.create_shared_item(&token, "guarded", "db/app").await;
+    let schedule_uri = format!("/v1/shared/items/{}/rotation/schedule", item_id);
+
+    let (status, body) = app
+        .send(
+            Method::PUT,
+            &schedule_uri,
+            &token,
+            Some(json!({ "schedule": "every tuesday" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_schedule");
+
+    let (status, body) = app
+        .send(
+            Method::PUT,
+            &schedule_uri,
+            &token,
+            Some(json!({ "schedule": "10m" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "schedule_too_short");
+
+    let (status, body) = app
+        .send(
+            Method::PUT,
+            &schedule_uri,
+            &token,
+            Some(json!({ "schedule": "90d", "password_policy": "missing" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "unknown_policy");
+
+    let (status, body) = app
+        .send(
+            Method::PUT,
+            &schedule_uri,
+            &token,
+            Some(json!({ "schedule": "90d", "password_policy": "ssh-ed25519" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "policy_kind_unsupported");
+
+    let (status, _) = app
+        .send(
+            Method::PUT,
+            &schedule_uri,
+            &token,
+            Some(json!({ "schedule": "90d", "password_policy": "passphrase" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let outsider = app.register("outsider@example.com").await;
+    let (status, _) = app
+        .send(
+            Method::PUT,
+            &schedule_uri,
+            &outsider,
+            Some(json!({ "schedule": "90d" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{}/rotation/overdue", vault_id),
+            &outsider,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
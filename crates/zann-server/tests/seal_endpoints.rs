@@ -0,0 +1,320 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod support;
+
+use chrono::Utc;
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, Group, GroupMember, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_db::repo::{GroupMemberRepo, GroupRepo, SealConfig, SealConfigRepo, UserRepo};
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::{key_check, split_master_key, MasterKeyStore};
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    pool: PgPool,
+}
+
+impl TestApp {
+    async fn new(server_master_key: MasterKeyStore) -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key,
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state),
+            pool,
+        }
+    }
+
+    /// Records an M-of-N split of `key` the way `zann-server init` does.
+    async fn init_seal(&self, key: &SecretKey, threshold: u8, shares: u8) -> Vec<String> {
+        let split = split_master_key(key, threshold, shares).expect("split key");
+        let mut conn = self.pool.acquire().await.expect("acquire");
+        let config = SealConfig {
+            threshold: i16::from(threshold),
+            shares: i16::from(shares),
+            key_check: key_check(key),
+            created_at: Utc::now(),
+        };
+        let inserted = SealConfigRepo::create_in(&mut conn, &config)
+            .await
+            .expect("seal config");
+        assert_eq!(inserted, 1);
+        split
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn unseal(&self, body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        self.send(Method::POST, "/v1/system/unseal", None, Some(body))
+            .await
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let (status, json) = self
+            .send(Method::POST, "/v1/auth/register", None, Some(payload))
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "register failed: {:?}", json);
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn make_admin(&self, email: &str) {
+        let user = UserRepo::new(&self.pool)
+            .get_by_email(email)
+            .await
+            .expect("user lookup")
+            .expect("user");
+        let group_repo = GroupRepo::new(&self.pool);
+        let group_id = match group_repo.get_by_slug("admins").await {
+            Ok(Some(existing)) => existing.id,
+            _ => {
+                let group = Group {
+                    id: Uuid::now_v7(),
+                    slug: "admins".to_string(),
+                    name: "Admins".to_string(),
+                    created_at: Utc::now(),
+                };
+                group_repo.create(&group).await.expect("create group");
+                group.id
+            }
+        };
+        GroupMemberRepo::new(&self.pool)
+            .create(&GroupMember {
+                group_id,
+                user_id: user.id,
+                created_at: Utc::now(),
+            })
+            .await
+            .expect("add admin");
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn sealed_server_unseals_with_threshold_shares_and_reseals() {
+    let app = TestApp::new(MasterKeyStore::sealed()).await;
+    let key = SecretKey::generate();
+    let shares = app.init_seal(&key, 2, 3).await;
+
+    let (status, body) = app.send(Method::GET, "/health", None, None).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "sealed");
+    assert_eq!(body["components"]["seal"]["status"], "sealed");
+
+    let (status, body) = app.send(Method::GET, "/v1/vaults", None, None).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["error"], "sealed");
+    let (status, body) = app
+        .send(
+            Method::POST,
+            "/v1/auth/login",
+            None,
+            Some(json!({ "email": "a@example.com", "password": "x" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["error"], "sealed");
+
+    let (status, body) = app
+        .send(Method::GET, "/v1/system/seal-status", None, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["sealed"], true);
+    assert_eq!(body["initialized"], true);
+    assert_eq!(body["threshold"], 2);
+    assert_eq!(body["progress"], 0);
+
+    let (status, body) = app.unseal(json!({ "share": "bogus" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_share");
+
+    let (status, body) = app.unseal(json!({ "share": shares[0] })).await;
+    assert_eq!(status, StatusCode::OK, "unseal failed: {:?}", body);
+    assert_eq!(body["sealed"], true);
+    assert_eq!(body["progress"], 1);
+
+    let (status, body) = app.unseal(json!({ "share": shares[0] })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "duplicate_share");
+
+    let (status, body) = app.unseal(json!({ "share": shares[2] })).await;
+    assert_eq!(status, StatusCode::OK, "unseal failed: {:?}", body);
+    assert_eq!(body["sealed"], false);
+    assert_eq!(body["progress"], 0);
+
+    let (status, body) = app.send(Method::GET, "/health", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["components"]["seal"]["status"], "ok");
+
+    let token = app.register("admin@example.com").await;
+    app.make_admin("admin@example.com").await;
+    let (status, body) = app
+        .send(
+            Method::POST,
+            "/v1/vaults",
+            Some(&token),
+            Some(json!({
+                "slug": "infra",
+                "name": "Infra",
+                "kind": VaultKind::Shared.as_i32(),
+                "cache_policy": CachePolicy::Full.as_i32(),
+            })),
+        )
+        .await;
+    assert_eq!(
+        status,
+        StatusCode::CREATED,
+        "vault create failed: {:?}",
+        body
+    );
+
+    let member = app.register("member@example.com").await;
+    let (status, _) = app
+        .send(Method::POST, "/v1/system/seal", Some(&member), None)
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = app
+        .send(Method::POST, "/v1/system/seal", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "seal failed: {:?}", body);
+    assert_eq!(body["sealed"], true);
+
+    let (status, body) = app
+        .send(Method::GET, "/v1/vaults", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["error"], "sealed");
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn shares_from_another_key_fail_and_reset_progress() {
+    let app = TestApp::new(MasterKeyStore::sealed()).await;
+    let key = SecretKey::generate();
+    let _shares = app.init_seal(&key, 2, 3).await;
+    let foreign = split_master_key(&SecretKey::generate(), 2, 3).expect("split");
+
+    let (status, _) = app.unseal(json!({ "share": foreign[0] })).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = app.unseal(json!({ "share": foreign[1] })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "unseal_failed");
+
+    let (_, body) = app
+        .send(Method::GET, "/v1/system/seal-status", None, None)
+        .await;
+    assert_eq!(body["sealed"], true);
+    assert_eq!(body["progress"], 0);
+
+    let (status, body) = app.unseal(json!({ "share": foreign[0] })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["progress"], 1);
+    let (status, body) = app.unseal(json!({ "reset": true })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["progress"], 0);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn unseal_is_rejected_outside_manual_mode() {
+    let app = TestApp::new(MasterKeyStore::new(Some(SecretKey::generate()))).await;
+
+    let (status, body) = app.send(Method::GET, "/health", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["components"]["seal"]["status"], "disabled");
+
+    let (status, body) = app.unseal(json!({ "share": "AQID" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "unseal_not_supported");
+}
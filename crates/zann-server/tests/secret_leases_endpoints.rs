@@ -0,0 +1,471 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use sqlx_postgres::Postgres;
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::secrets::leases::prune_leases;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    pool: PgPool,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+        config.leases.max_ttl_seconds = 3600;
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state),
+            pool,
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password-1",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&payload).expect("encode")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> String {
+        let (status, vault) = self
+            .send(
+                Method::POST,
+                "/v1/vaults",
+                token,
+                Some(json!({
+                    "slug": slug,
+                    "name": "Shared Vault",
+                    "kind": VaultKind::Shared.as_i32(),
+                    "cache_policy": CachePolicy::Full.as_i32(),
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            vault
+        );
+        vault["id"].as_str().expect("vault id").to_string()
+    }
+
+    async fn ensure_secret(&self, token: &str, vault_id: &str, path: &str) -> serde_json::Value {
+        let (status, body) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/secrets/ensure", vault_id),
+                token,
+                Some(json!({ "path": path })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "ensure failed: {:?}", body);
+        body
+    }
+
+    async fn add_member(&self, owner: &str, vault_id: &str, email: &str, role: &str) {
+        let (status, body) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/members", vault_id),
+                owner,
+                Some(json!({ "email": email, "role": role })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "{body:?}");
+    }
+
+    async fn holders(&self, token: &str, vault_id: &str, path: &str) -> serde_json::Value {
+        let (status, body) = self
+            .send(
+                Method::GET,
+                &format!("/v1/vaults/{}/secret-leases?path={}", vault_id, path),
+                token,
+                None,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "holders failed: {:?}", body);
+        body
+    }
+}
+
+fn lease_uri(vault_id: &str, lease: &serde_json::Value) -> String {
+    format!(
+        "/v1/vaults/{}/secret-leases/{}",
+        vault_id,
+        lease["lease_id"].as_str().expect("lease id")
+    )
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn leased_read_can_be_renewed_listed_and_revoked() {
+    let app = TestApp::new().await;
+    let token = app.register("lease-owner@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "leases").await;
+    let ensured = app.ensure_secret(&token, &vault_id, "db/password").await;
+    let secret_uri = format!("/v1/vaults/{}/secrets/db/password", vault_id);
+
+    let (status, plain) = app.send(Method::GET, &secret_uri, &token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(plain.get("lease").is_none());
+
+    let (status, body) = app
+        .send(Method::GET, &format!("{secret_uri}?lease=5s"), &token, None)
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "lease_ttl_too_short");
+
+    let (status, leased) = app
+        .send(
+            Method::GET,
+            &format!("{secret_uri}?lease=10m"),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "leased read failed: {:?}", leased);
+    assert_eq!(leased["value"], ensured["value"]);
+    let lease = &leased["lease"];
+    assert_eq!(lease["ttl_seconds"], 600);
+    assert_eq!(lease["item_version"], ensured["version"]);
+
+    let holders = app.holders(&token, &vault_id, "db/password").await;
+    let list = holders["holders"].as_array().expect("holders");
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0]["lease_id"], lease["lease_id"]);
+    assert_eq!(list[0]["email"], "lease-owner@example.com");
+    assert_eq!(list[0]["stale"], false);
+
+    // Renewals stop at issue time + leases.max_ttl_seconds.
+    let (status, renewed) = app
+        .send(
+            Method::POST,
+            &format!("{}/renew", lease_uri(&vault_id, lease)),
+            &token,
+            Some(json!({ "ttl": "48h" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "renew failed: {:?}", renewed);
+    let ttl = renewed["ttl_seconds"].as_i64().expect("ttl");
+    assert!(ttl > 600 && ttl <= 3600, "unexpected ttl {ttl}");
+    assert!(renewed["renewed_at"].is_string());
+
+    let rotate_uri = format!("/v1/vaults/{}/secrets/rotate", vault_id);
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &rotate_uri,
+            &token,
+            Some(json!({ "path": "db/password", "wait_for_leases_seconds": 0 })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "leases_outstanding");
+
+    let (status, rotated) = app
+        .send(
+            Method::POST,
+            &rotate_uri,
+            &token,
+            Some(json!({ "path": "db/password" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "rotate failed: {:?}", rotated);
+    assert_eq!(rotated["outstanding_leases"], 1);
+    let holders = app.holders(&token, &vault_id, "db/password").await;
+    assert_eq!(holders["holders"][0]["stale"], true);
+
+    let (status, _) = app
+        .send(Method::DELETE, &lease_uri(&vault_id, lease), &token, None)
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let holders = app.holders(&token, &vault_id, "db/password").await;
+    assert!(holders["holders"].as_array().expect("holders").is_empty());
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &format!("{}/renew", lease_uri(&vault_id, lease)),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "lease_revoked");
+    let (status, _) = app
+        .send(Method::DELETE, &lease_uri(&vault_id, lease), &token, None)
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, rotated) = app
+        .send(
+            Method::POST,
+            &rotate_uri,
+            &token,
+            Some(json!({ "path": "db/password", "wait_for_leases_seconds": 0 })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "rotate failed: {:?}", rotated);
+    assert_eq!(rotated["outstanding_leases"], 0);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn lease_management_follows_vault_roles() {
+    let app = TestApp::new().await;
+    let owner = app.register("lease-admin@example.com").await;
+    let vault_id = app.create_shared_vault(&owner, "lease-roles").await;
+    app.ensure_secret(&owner, &vault_id, "api/key").await;
+    let member = app.register("lease-member@example.com").await;
+    app.add_member(&owner, &vault_id, "lease-member@example.com", "member")
+        .await;
+    let operator = app.register("lease-operator@example.com").await;
+    app.add_member(&owner, &vault_id, "lease-operator@example.com", "operator")
+        .await;
+    let outsider = app.register("lease-outsider@example.com").await;
+
+    let (status, leased) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{}/secrets/api/key?lease=1h", vault_id),
+            &member,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "leased read failed: {:?}", leased);
+    let uri = lease_uri(&vault_id, &leased["lease"]);
+
+    let (status, _) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{}/secret-leases?path=api/key", vault_id),
+            &member,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let holders = app.holders(&operator, &vault_id, "api/key").await;
+    assert_eq!(holders["holders"][0]["email"], "lease-member@example.com");
+
+    let (status, _) = app.send(Method::DELETE, &uri, &outsider, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app.send(Method::DELETE, &uri, &operator, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, renewed) = app
+        .send(Method::POST, &format!("{uri}/renew"), &member, None)
+        .await;
+    assert_eq!(status, StatusCode::OK, "renew failed: {:?}", renewed);
+    let ttl = renewed["ttl_seconds"].as_i64().expect("ttl");
+    assert!(ttl > 3500 && ttl <= 3600, "unexpected ttl {ttl}");
+
+    let (status, _) = app.send(Method::DELETE, &uri, &owner, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn item_rotation_reports_and_waits_for_leases() {
+    let app = TestApp::new().await;
+    let token = app.register("lease-rotation@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "lease-rotation").await;
+    let (status, item) = app
+        .send(
+            Method::POST,
+            &format!("/v1/vaults/{}/items", vault_id),
+            &token,
+            Some(json!({
+                "path": "db/app",
+                "name": "db/app",
+                "type_id": "login",
+                "payload": {
+                    "v": 1,
+                    "typeId": "login",
+                    "fields": {
+                        "password": { "kind": "password", "value": "secret" }
+                    }
+                }
+            })),
+        )
+        .await;
+    assert_eq!(
+        status,
+        StatusCode::CREATED,
+        "item create failed: {:?}",
+        item
+    );
+    let item_id = Uuid::parse_str(item["id"].as_str().expect("item id")).expect("uuid");
+    let vault_uuid = Uuid::parse_str(&vault_id).expect("uuid");
+
+    sqlx_core::query::query::<Postgres>(
+        r#"
+        INSERT INTO secret_leases (
+            id, item_id, vault_id, item_version, holder_user_id, holder_email,
+            issued_at, expires_at
+        )
+        VALUES ($1, $2, $3, 1, $4, 'holder@example.com', NOW(), NOW() + INTERVAL '1 hour')
+        "#,
+    )
+    .bind(Uuid::now_v7())
+    .bind(item_id)
+    .bind(vault_uuid)
+    .bind(Uuid::now_v7())
+    .execute(&app.pool)
+    .await
+    .expect("insert lease");
+
+    let (status, rotation) = app
+        .send(
+            Method::GET,
+            &format!("/v1/shared/items/{}/rotate/status", item_id),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(rotation["active_leases"], 1);
+
+    let (status, started) = app
+        .send(
+            Method::POST,
+            &format!("/v1/shared/items/{}/rotate/start", item_id),
+            &token,
+            Some(json!({})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "rotate start failed: {:?}", started);
+    let commit_uri = format!("/v1/shared/items/{}/rotate/commit", item_id);
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &commit_uri,
+            &token,
+            Some(json!({ "wait_for_leases_seconds": 1 })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "leases_outstanding");
+
+    let (status, commit) = app
+        .send(Method::POST, &commit_uri, &token, Some(json!({})))
+        .await;
+    assert_eq!(status, StatusCode::OK, "commit failed: {:?}", commit);
+    assert_eq!(commit["status"], "committed");
+    assert_eq!(commit["outstanding_leases"], 1);
+
+    sqlx_core::query::query::<Postgres>(
+        "UPDATE secret_leases SET expires_at = NOW() - INTERVAL '1 day' WHERE item_id = $1",
+    )
+    .bind(item_id)
+    .execute(&app.pool)
+    .await
+    .expect("expire lease");
+    assert_eq!(prune_leases(&app.pool, 3600).await.expect("prune"), 1);
+}
@@ -0,0 +1,781 @@
+use axum::body::{to_bytes, Body};
+use axum::extract::ConnectInfo;
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod support;
+
+use chrono::Utc;
+use std::net::SocketAddr;
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind, VaultMember, VaultMemberRole};
+use zann_crypto::crypto::SecretKey;
+use zann_db::repo::{AuditEventFilter, AuditEventRepo, UserRepo, VaultMemberRepo};
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    pool: PgPool,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+        config.server.trusted_proxies = vec!["127.0.0.1/32".to_string()];
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        let app = build_router(state);
+        Self {
+            _guard: guard,
+            app,
+            pool,
+        }
+    }
+
+    async fn send_json(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        let request = builder
+            .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn get_json(&self, uri: &str, token: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder().method(Method::GET).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        let request = builder.body(Body::empty()).expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn send_from(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: serde_json::Value,
+        peer: &str,
+        forwarded_for: Option<&str>,
+    ) -> StatusCode {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        if let Some(forwarded_for) = forwarded_for {
+            builder = builder.header("x-forwarded-for", forwarded_for);
+        }
+        let mut request = builder
+            .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+            .expect("request");
+        let peer: SocketAddr = peer.parse().expect("peer addr");
+        request.extensions_mut().insert(ConnectInfo(peer));
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        response.status()
+    }
+
+    async fn register(&self, email: &str, password: &str) -> serde_json::Value {
+        let payload = json!({
+            "email": email,
+            "password": password,
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let (status, json) = self
+            .send_json(Method::POST, "/v1/auth/register", None, payload)
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "register failed: {:?}", json);
+        json
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> serde_json::Value {
+        let payload = json!({
+            "slug": slug,
+            "name": "Shared Vault",
+            "kind": VaultKind::Shared.as_i32(),
+            "cache_policy": CachePolicy::Full.as_i32(),
+        });
+        let (status, json) = self
+            .send_json(Method::POST, "/v1/vaults", Some(token), payload)
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            json
+        );
+        json
+    }
+
+    async fn add_vault_member(&self, vault_id: &str, email: &str, role: VaultMemberRole) {
+        let user = UserRepo::new(&self.pool)
+            .get_by_email(email)
+            .await
+            .expect("user lookup")
+            .expect("user");
+        VaultMemberRepo::new(&self.pool)
+            .create(&VaultMember {
+                vault_id: Uuid::parse_str(vault_id).expect("vault id"),
+                user_id: user.id,
+                role,
+                created_at: Utc::now(),
+            })
+            .await
+            .expect("member create");
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn vault_admin_manages_service_account_lifecycle() {
+    let app = TestApp::new().await;
+    let user = app.register("lead@example.com", "password").await;
+    let token = user["access_token"].as_str().expect("token");
+    let vault = app.create_shared_vault(token, "billing").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+
+    let (status, created) = app
+        .send_json(
+            Method::POST,
+            "/v1/service-accounts",
+            Some(token),
+            json!({
+                "name": "ci-billing",
+                "vault": "billing",
+                "prefixes": ["apps/billing"],
+                "ops": ["read", "write"],
+                "ttl": "1d",
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {:?}", created);
+    let sa_token = created["token"].as_str().expect("sa token").to_string();
+    assert!(sa_token.starts_with("zann_sa_"));
+    let account = &created["service_account"];
+    let account_id = account["id"].as_str().expect("id").to_string();
+    assert_eq!(account["issued_by"], "lead@example.com");
+    assert_eq!(account["vault_id"], vault_id);
+    assert_eq!(account["prefixes"], json!(["/apps/billing"]));
+    assert!(account["expires_at"].is_string());
+    let scopes = account["scopes"].as_array().expect("scopes");
+    assert!(scopes.contains(&json!(format!("{vault_id}/prefix:apps::billing:write"))));
+
+    let shared_uri = format!("/v1/shared/items?vault_id={vault_id}&prefix=apps/billing");
+    let (status, _) = app.get_json(&shared_uri, Some(&sa_token)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, list) = app.get_json("/v1/service-accounts", Some(token)).await;
+    assert_eq!(status, StatusCode::OK, "list failed: {:?}", list);
+    assert_eq!(list["service_accounts"].as_array().expect("list").len(), 1);
+
+    let (status, list) = app
+        .get_json("/v1/service-accounts?vault=billing", Some(token))
+        .await;
+    assert_eq!(status, StatusCode::OK, "vault list failed: {:?}", list);
+    assert_eq!(list["service_accounts"][0]["id"], account_id.as_str());
+
+    let account_uri = format!("/v1/service-accounts/{account_id}");
+    let (status, fetched) = app.get_json(&account_uri, Some(token)).await;
+    assert_eq!(status, StatusCode::OK, "get failed: {:?}", fetched);
+    assert!(fetched.get("token").is_none());
+
+    let (status, rotated) = app
+        .send_json(
+            Method::POST,
+            &format!("{account_uri}/rotate"),
+            Some(token),
+            json!({}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "rotate failed: {:?}", rotated);
+    let rotated_token = rotated["token"]
+        .as_str()
+        .expect("rotated token")
+        .to_string();
+    assert_ne!(rotated_token, sa_token);
+    assert_eq!(rotated["service_account"]["id"], account_id.as_str());
+
+    let (status, _) = app.get_json(&shared_uri, Some(&sa_token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = app.get_json(&shared_uri, Some(&rotated_token)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, revoked) = app
+        .send_json(Method::DELETE, &account_uri, Some(token), json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK, "revoke failed: {:?}", revoked);
+    assert!(revoked["revoked_at"].is_string());
+    let (status, _) = app.get_json(&shared_uri, Some(&rotated_token)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = app
+        .send_json(
+            Method::POST,
+            &format!("{account_uri}/rotate"),
+            Some(token),
+            json!({}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn service_accounts_require_vault_admin() {
+    let app = TestApp::new().await;
+    let lead = app.register("lead@example.com", "password").await;
+    let lead_token = lead["access_token"].as_str().expect("token");
+    let member = app.register("dev@example.com", "password").await;
+    let member_token = member["access_token"].as_str().expect("token");
+    let vault = app.create_shared_vault(lead_token, "billing").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+    app.add_vault_member(vault_id, "dev@example.com", VaultMemberRole::Member)
+        .await;
+
+    let payload = json!({
+        "name": "ci",
+        "vault": vault_id,
+        "prefixes": ["/"],
+    });
+    let (status, _) = app
+        .send_json(
+            Method::POST,
+            "/v1/service-accounts",
+            Some(member_token),
+            payload.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, created) = app
+        .send_json(
+            Method::POST,
+            "/v1/service-accounts",
+            Some(lead_token),
+            payload,
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {:?}", created);
+    let account_id = created["service_account"]["id"].as_str().expect("id");
+    let sa_token = created["token"].as_str().expect("sa token");
+    assert_eq!(
+        created["service_account"]["scopes"],
+        json!([format!("{vault_id}:read")])
+    );
+
+    let (status, _) = app
+        .get_json("/v1/service-accounts?vault=billing", Some(member_token))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app
+        .get_json(
+            &format!("/v1/service-accounts/{account_id}"),
+            Some(member_token),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app
+        .send_json(
+            Method::DELETE,
+            &format!("/v1/service-accounts/{account_id}"),
+            Some(member_token),
+            json!({}),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // Tokens must not be able to mint further tokens, even for their own vault.
+    let (status, _) = app
+        .send_json(
+            Method::POST,
+            "/v1/service-accounts",
+            Some(sa_token),
+            json!({ "name": "nested", "vault": vault_id, "prefixes": ["/"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn create_service_account_validates_payload() {
+    let app = TestApp::new().await;
+    let user = app.register("lead@example.com", "password").await;
+    let token = user["access_token"].as_str().expect("token");
+    app.create_shared_vault(token, "billing").await;
+
+    for (payload, error) in [
+        (
+            json!({ "name": "ci", "vault": "billing", "prefixes": ["/"], "ops": ["rotate"] }),
+            "invalid_ops",
+        ),
+        (
+            json!({ "name": "ci", "vault": "billing", "prefixes": ["/", "apps"] }),
+            "invalid_prefixes",
+        ),
+        (
+            json!({ "name": "ci", "vault": "billing", "prefixes": ["/"], "ttl": "soon" }),
+            "invalid_ttl",
+        ),
+        (
+            json!({
+                "name": "ci",
+                "vault": "billing",
+                "prefixes": ["/"],
+                "allowed_ips": ["not-an-ip"],
+            }),
+            "invalid_allowed_ips",
+        ),
+        (
+            json!({
+                "name": "ci",
+                "vault": "billing",
+                "prefixes": ["/"],
+                "allowed_ips": ["10.20.1.0/16"],
+            }),
+            "invalid_allowed_ips",
+        ),
+    ] {
+        let (status, json) = app
+            .send_json(Method::POST, "/v1/service-accounts", Some(token), payload)
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{error}: {json:?}");
+        assert_eq!(json["error"], error);
+    }
+
+    let (status, _) = app
+        .send_json(
+            Method::POST,
+            "/v1/service-accounts",
+            Some(token),
+            json!({ "name": "ci", "vault": "missing", "prefixes": ["/"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn service_account_allowed_ips_match_cidr_ranges() {
+    let app = TestApp::new().await;
+    let user = app.register("lead@example.com", "password").await;
+    let token = user["access_token"].as_str().expect("token");
+    app.create_shared_vault(token, "billing").await;
+
+    let (status, created) = app
+        .send_json(
+            Method::POST,
+            "/v1/service-accounts",
+            Some(token),
+            json!({
+                "name": "runners",
+                "vault": "billing",
+                "prefixes": ["/"],
+                "allowed_ips": ["10.20.0.0/16", "2001:DB8::/48", "192.0.2.7/32"],
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{created:?}");
+    assert_eq!(
+        created["service_account"]["allowed_ips"],
+        json!(["10.20.0.0/16", "2001:db8::/48", "192.0.2.7"])
+    );
+    let sa_token = created["token"].as_str().expect("sa token");
+    let login = json!({ "token": sa_token });
+
+    for (peer, forwarded_for) in [
+        ("10.20.3.4:5000", None),
+        ("[2001:db8::42]:5000", None),
+        ("127.0.0.1:5000", Some("10.20.200.1")),
+        ("127.0.0.1:5000", Some("[2001:db8:0:1::9]:443")),
+    ] {
+        let status = app
+            .send_from(
+                Method::POST,
+                "/v1/auth/service-account",
+                None,
+                login.clone(),
+                peer,
+                forwarded_for,
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "{peer} {forwarded_for:?}");
+    }
+
+    // Forwarded headers from untrusted peers are ignored.
+    let status = app
+        .send_from(
+            Method::POST,
+            "/v1/auth/service-account",
+            None,
+            login.clone(),
+            "10.21.0.1:5000",
+            Some("10.20.0.1"),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let status = app
+        .send_from(
+            Method::GET,
+            "/v1/vaults",
+            Some(sa_token),
+            serde_json::Value::Null,
+            "10.20.9.9:5000",
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let status = app
+        .send_from(
+            Method::GET,
+            "/v1/vaults",
+            Some(sa_token),
+            serde_json::Value::Null,
+            "[2001:db9::1]:5000",
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let audit = AuditEventRepo::new(&app.pool);
+    let denied = audit
+        .list(
+            &AuditEventFilter {
+                action: Some("service_account_token".to_string()),
+                ..AuditEventFilter::default()
+            },
+            10,
+        )
+        .await
+        .expect("audit list");
+    assert_eq!(denied.len(), 1);
+    assert_eq!(denied[0].result, "forbidden");
+    assert_eq!(
+        denied[0].detail.as_deref(),
+        Some("forbidden: ip_not_allowed")
+    );
+    assert_eq!(denied[0].client_ip.as_deref(), Some("2001:db9::1"));
+    assert!(denied[0].actor_service_account_id.is_some());
+
+    let login_denied = audit
+        .list(
+            &AuditEventFilter {
+                action: Some("login_service_account".to_string()),
+                result: Some("forbidden".to_string()),
+                ..AuditEventFilter::default()
+            },
+            10,
+        )
+        .await
+        .expect("audit list");
+    assert_eq!(login_denied.len(), 1);
+    assert_eq!(
+        login_denied[0].detail.as_deref(),
+        Some("forbidden: ip_not_allowed")
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn service_account_pulls_shared_changes_under_its_prefixes() {
+    let app = TestApp::new().await;
+    let user = app.register("lead@example.com", "password").await;
+    let token = user["access_token"].as_str().expect("token");
+    let vault = app.create_shared_vault(token, "billing").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+
+    let create_item = |path: &'static str| {
+        app.send_json(
+            Method::POST,
+            "/v1/shared/items",
+            Some(token),
+            json!({
+                "vault_id": vault_id,
+                "path": path,
+                "type_id": "secret",
+                "payload": {
+                    "v": 1,
+                    "typeId": "secret",
+                    "fields": { "password": { "kind": "password", "value": "p-1" } }
+                },
+            }),
+        )
+    };
+    for path in ["apps/billing/db", "apps/payroll/db"] {
+        let (status, item) = create_item(path).await;
+        assert_eq!(status, StatusCode::CREATED, "create failed: {item:?}");
+    }
+
+    let (status, created) = app
+        .send_json(
+            Method::POST,
+            "/v1/service-accounts",
+            Some(token),
+            json!({ "name": "agent", "vault": "billing", "prefixes": ["apps/billing"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {created:?}");
+    let sa_token = created["token"].as_str().expect("sa token");
+
+    let (status, pulled) = app
+        .send_json(
+            Method::POST,
+            "/v1/sync/shared/pull",
+            Some(sa_token),
+            json!({ "vault_id": vault_id, "limit": 100 }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "pull failed: {pulled:?}");
+    let paths: Vec<&str> = pulled["changes"]
+        .as_array()
+        .expect("changes")
+        .iter()
+        .map(|change| change["path"].as_str().expect("path"))
+        .collect();
+    assert_eq!(paths, vec!["apps/billing/db"]);
+    assert!(pulled["changes"][0]["payload"].is_null(), "{pulled:?}");
+    assert_eq!(pulled["push_available"], false);
+
+    let cursor = pulled["next_cursor"].as_str().expect("cursor").to_string();
+    let (status, item) = create_item("apps/billing/api").await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {item:?}");
+    let (status, _) = create_item("apps/payroll/api").await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, pulled) = app
+        .send_json(
+            Method::POST,
+            "/v1/sync/shared/pull",
+            Some(sa_token),
+            json!({ "vault_id": vault_id, "cursor": cursor, "limit": 100 }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "pull failed: {pulled:?}");
+    let changes = pulled["changes"].as_array().expect("changes");
+    assert_eq!(changes.len(), 1, "{pulled:?}");
+    assert_eq!(changes[0]["path"], "apps/billing/api");
+    assert_ne!(pulled["next_cursor"], cursor.as_str());
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn read_history_scope_grants_history_but_not_reads() {
+    let app = TestApp::new().await;
+    let user = app.register("lead@example.com", "password").await;
+    let token = user["access_token"].as_str().expect("token");
+    let vault = app.create_shared_vault(token, "billing").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+
+    let (status, item) = app
+        .send_json(
+            Method::POST,
+            "/v1/shared/items",
+            Some(token),
+            json!({
+                "vault_id": vault_id,
+                "path": "apps/billing/db",
+                "type_id": "secret",
+                "payload": {
+                    "v": 1,
+                    "typeId": "secret",
+                    "fields": { "password": { "kind": "password", "value": "p-1" } }
+                },
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {item:?}");
+    let item_id = item["id"].as_str().expect("item id");
+    let secret_uri = format!("/v1/vaults/{vault_id}/secrets/apps/billing/api");
+    let (status, body) = app
+        .send_json(
+            Method::PUT,
+            &secret_uri,
+            Some(token),
+            json!({ "value": "s3cret" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "set failed: {body:?}");
+
+    let (status, created) = app
+        .send_json(
+            Method::POST,
+            "/v1/service-accounts",
+            Some(token),
+            json!({
+                "name": "auditor",
+                "vault": "billing",
+                "prefixes": ["apps/billing"],
+                "ops": ["read_history"],
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {created:?}");
+    let sa_token = created["token"].as_str().expect("sa token");
+
+    let (status, history) = app
+        .get_json(
+            &format!("/v1/shared/items/{item_id}/history"),
+            Some(sa_token),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "history failed: {history:?}");
+
+    let (status, _) = app.get_json(&secret_uri, Some(sa_token)).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn read_only_service_account_pulls_metadata_only() {
+    let app = TestApp::new().await;
+    let user = app.register("lead@example.com", "password").await;
+    let token = user["access_token"].as_str().expect("token");
+    let vault = app.create_shared_vault(token, "billing").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+
+    let secret_uri = format!("/v1/vaults/{vault_id}/secrets/apps/billing/api");
+    for value in ["s3cret-1", "s3cret-2"] {
+        let (status, body) = app
+            .send_json(
+                Method::PUT,
+                &secret_uri,
+                Some(token),
+                json!({ "value": value }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "set failed: {body:?}");
+    }
+    let pull = json!({ "vault_id": vault_id, "limit": 100 });
+    let (status, pulled) = app
+        .send_json(
+            Method::POST,
+            "/v1/sync/shared/pull",
+            Some(token),
+            pull.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "pull failed: {pulled:?}");
+    let change = pulled["changes"]
+        .as_array()
+        .expect("changes")
+        .last()
+        .expect("change");
+    assert!(!change["payload"].is_null(), "{pulled:?}");
+    assert!(change["history"].is_array(), "{pulled:?}");
+
+    let (status, created) = app
+        .send_json(
+            Method::POST,
+            "/v1/service-accounts",
+            Some(token),
+            json!({
+                "name": "agent",
+                "vault": "billing",
+                "prefixes": ["apps/billing"],
+                "ops": ["read"],
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {created:?}");
+    let sa_token = created["token"].as_str().expect("sa token");
+
+    let (status, pulled) = app
+        .send_json(Method::POST, "/v1/sync/shared/pull", Some(sa_token), pull)
+        .await;
+    assert_eq!(status, StatusCode::OK, "pull failed: {pulled:?}");
+    let changes = pulled["changes"].as_array().expect("changes");
+    assert!(!changes.is_empty(), "{pulled:?}");
+    for change in changes {
+        assert_eq!(change["path"], "/apps/billing/api");
+        assert!(change["payload"].is_null(), "{change:?}");
+        assert!(change.get("history").is_none(), "{change:?}");
+    }
+}
@@ -16,6 +16,7 @@ use zann_server::app::{build_router, AppState};
 use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
 use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
 use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
 use zann_server::infra::security_profiles::load_security_profiles;
 use zann_server::infra::usage::UsageTracker;
 use zann_server::oidc::OidcJwksCache;
@@ -75,7 +76,7 @@ impl TestApp {
             started_at: std::time::Instant::now(),
             password_pepper: "pepper".to_string(),
             token_pepper: token_pepper.clone(),
-            server_master_key: Some(std::sync::Arc::new(SecretKey::generate())),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
 
             identity_key: support::test_identity_key(),
             access_token_ttl_seconds: 3600,
@@ -679,6 +680,107 @@ async fn service_account_token_can_set_secret_without_device_id() {
     assert_eq!(fetched["value"], "pw-1");
 }
 
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn service_account_write_scope_is_limited_to_nested_prefix() {
+    let app = TestApp::new_with_smk().await;
+    let email = "shared-secrets-ci@example.com";
+    let user = app.register(email, "password").await;
+    let token = user["access_token"].as_str().expect("token");
+
+    let vault = app.create_shared_vault(token, "shared-secrets-ci").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+    // Same encoding as `zann-server token create <vault>:apps/billing --ops read,write`.
+    let scopes = vec![
+        format!("{vault_id}/prefix:apps::billing:read"),
+        format!("{vault_id}/prefix:apps::billing:write"),
+    ];
+    let service_account = app.create_service_account(email, scopes).await;
+    let sa_token = service_account["token"].as_str().expect("sa token");
+
+    let (status, ensured) = app
+        .send_json(
+            Method::POST,
+            &format!("/v1/vaults/{}/secrets/ensure", vault_id),
+            Some(sa_token),
+            json!({ "path": "apps/billing/db_password" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "ensure failed: {:?}", ensured);
+    assert_eq!(ensured["created"], true);
+
+    let (status, rotated) = app
+        .send_json(
+            Method::POST,
+            &format!("/v1/vaults/{}/secrets/rotate", vault_id),
+            Some(sa_token),
+            json!({ "path": "apps/billing/db_password" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "rotate failed: {:?}", rotated);
+    assert_eq!(rotated["previous_version"], 1);
+    assert_ne!(rotated["value"], ensured["value"]);
+
+    let (status, created) = app
+        .send_json(
+            Method::POST,
+            "/v1/shared/items",
+            Some(sa_token),
+            json!({
+                "vault_id": vault_id,
+                "path": "apps/billing/api",
+                "type_id": "secret",
+                "payload": {
+                    "v": 1,
+                    "typeId": "secret",
+                    "fields": { "token": { "kind": "text", "value": "t-1" } }
+                }
+            }),
+        )
+        .await;
+    assert_eq!(
+        status,
+        StatusCode::CREATED,
+        "shared create failed: {:?}",
+        created
+    );
+
+    for path in ["apps/billing-old/db_password", "apps/payments/db_password"] {
+        let (status, _) = app
+            .send_json(
+                Method::POST,
+                &format!("/v1/vaults/{}/secrets/ensure", vault_id),
+                Some(sa_token),
+                json!({ "path": path }),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::FORBIDDEN,
+            "ensure outside prefix: {path}"
+        );
+    }
+
+    let (status, _) = app
+        .send_json(
+            Method::POST,
+            "/v1/shared/items",
+            Some(sa_token),
+            json!({
+                "vault_id": vault_id,
+                "path": "apps/payments/api",
+                "type_id": "secret",
+                "payload": {
+                    "v": 1,
+                    "typeId": "secret",
+                    "fields": { "token": { "kind": "text", "value": "t-1" } }
+                }
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
 #[tokio::test]
 #[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
 async fn service_account_access_token_can_set_secret_without_device_id() {
@@ -0,0 +1,585 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use sqlx_postgres::Postgres;
+use ssh_key::certificate::CertType;
+use ssh_key::private::{Ed25519Keypair, KeypairData};
+use ssh_key::{Certificate, HashAlg, PrivateKey, PublicKey};
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    pool: PgPool,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state),
+            pool,
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password-1",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&payload).expect("encode")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> String {
+        let (status, vault) = self
+            .send(
+                Method::POST,
+                "/v1/vaults",
+                token,
+                Some(json!({
+                    "slug": slug,
+                    "name": "Shared Vault",
+                    "kind": VaultKind::Shared.as_i32(),
+                    "cache_policy": CachePolicy::Full.as_i32(),
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            vault
+        );
+        vault["id"].as_str().expect("vault id").to_string()
+    }
+
+    /// Stands in for an admin assigning the user's SSH username.
+    async fn set_ssh_username(&self, email: &str, ssh_username: &str) {
+        sqlx_core::query::query::<Postgres>("UPDATE users SET ssh_username = $2 WHERE email = $1")
+            .bind(email)
+            .bind(ssh_username)
+            .execute(&self.pool)
+            .await
+            .expect("set ssh username");
+    }
+
+    async fn add_member(&self, owner: &str, vault_id: &str, email: &str, role: &str) {
+        let (status, body) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/members", vault_id),
+                owner,
+                Some(json!({ "email": email, "role": role })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "{body:?}");
+    }
+
+    async fn service_account_token(
+        &self,
+        owner: &str,
+        slug: &str,
+        prefix: &str,
+        ops: &[&str],
+    ) -> String {
+        let (status, created) = self
+            .send(
+                Method::POST,
+                "/v1/service-accounts",
+                owner,
+                Some(json!({
+                    "name": format!("ssh-{}", ops.join("-")),
+                    "vault": slug,
+                    "prefixes": [prefix],
+                    "ops": ops,
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "create failed: {:?}", created);
+        created["token"].as_str().expect("sa token").to_string()
+    }
+
+    async fn set_role(&self, token: &str, vault_id: &str, name: &str, role: serde_json::Value) {
+        let (status, body) = self
+            .send(
+                Method::PUT,
+                &format!("/v1/vaults/{}/ssh/roles/{}", vault_id, name),
+                token,
+                Some(role),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK, "set role failed: {body:?}");
+    }
+
+    async fn sign(
+        &self,
+        token: &str,
+        vault_id: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        self.send(
+            Method::POST,
+            &format!("/v1/vaults/{}/ssh/sign", vault_id),
+            token,
+            Some(body),
+        )
+        .await
+    }
+}
+
+fn user_public_key() -> String {
+    let key = PrivateKey::new(
+        KeypairData::from(Ed25519Keypair::random(&mut rand::rngs::OsRng)),
+        "alice@laptop",
+    )
+    .expect("key");
+    key.public_key().to_openssh().expect("public key")
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn ssh_ca_signs_user_keys_within_role_limits() {
+    let app = TestApp::new().await;
+    let token = app.register("alice@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "ssh-ca").await;
+    let ca_uri = format!("/v1/vaults/{}/ssh/ca", vault_id);
+
+    let (status, _) = app.send(Method::GET, &ca_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, ca) = app.send(Method::POST, &ca_uri, &token, None).await;
+    assert_eq!(status, StatusCode::CREATED, "create CA failed: {ca:?}");
+    let ca_key =
+        PublicKey::from_openssh(ca["public_key"].as_str().expect("ca key")).expect("parse ca key");
+    let (status, body) = app.send(Method::POST, &ca_uri, &token, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "ssh_ca_exists");
+
+    app.set_role(
+        &token,
+        &vault_id,
+        "ops",
+        json!({
+            "allowed_principals": ["{{username}}", "deploy"],
+            "default_principals": ["{{username}}"],
+            "allowed_extensions": ["permit-pty", "permit-port-forwarding"],
+            "default_extensions": ["permit-pty"],
+            "default_ttl": "1h",
+            "max_ttl": "8h"
+        }),
+    )
+    .await;
+
+    let public_key = user_public_key();
+    // `{{username}}` is never derived from the email address.
+    let (status, body) = app
+        .sign(
+            &token,
+            &vault_id,
+            json!({ "public_key": public_key, "role": "ops" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "ssh_username_missing");
+    let (status, body) = app
+        .sign(
+            &token,
+            &vault_id,
+            json!({ "public_key": public_key, "role": "ops", "principals": ["alice"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "principal_not_allowed");
+
+    app.set_ssh_username("alice@example.com", "alice").await;
+    let (status, signed) = app
+        .sign(
+            &token,
+            &vault_id,
+            json!({ "public_key": public_key, "role": "ops" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "sign failed: {signed:?}");
+    assert_eq!(signed["principals"], json!(["alice"]));
+    assert_eq!(signed["extensions"], json!(["permit-pty"]));
+    assert_eq!(signed["key_id"], "alice@example.com");
+
+    let cert = Certificate::from_openssh(signed["certificate"].as_str().expect("certificate"))
+        .expect("parse certificate");
+    cert.validate([&ca_key.fingerprint(HashAlg::Sha256)])
+        .expect("certificate signed by the vault CA");
+    assert_eq!(cert.cert_type(), CertType::User);
+    assert_eq!(cert.valid_principals(), ["alice".to_string()]);
+    assert_eq!(
+        cert.serial().to_string(),
+        signed["serial"].as_str().expect("serial")
+    );
+    assert_eq!(
+        cert.public_key(),
+        PublicKey::from_openssh(&public_key)
+            .expect("user key")
+            .key_data()
+    );
+    assert!(cert.extensions().contains_key("permit-pty"));
+    let lifetime = cert.valid_before() - cert.valid_after();
+    assert!(lifetime <= 3600 + 60, "lifetime {lifetime}");
+
+    let (status, signed) = app
+        .sign(
+            &token,
+            &vault_id,
+            json!({
+                "public_key": public_key,
+                "role": "ops",
+                "principals": ["deploy", "alice"],
+                "extensions": [],
+                "ttl": "30d"
+            }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "sign failed: {signed:?}");
+    let cert = Certificate::from_openssh(signed["certificate"].as_str().expect("certificate"))
+        .expect("parse certificate");
+    assert!(cert.extensions().is_empty());
+    let lifetime = cert.valid_before() - cert.valid_after();
+    assert!(
+        lifetime <= 8 * 3600 + 60,
+        "ttl clamped to max_ttl: {lifetime}"
+    );
+
+    for (body, error) in [
+        (
+            json!({ "public_key": public_key, "role": "ops", "principals": ["root"] }),
+            "principal_not_allowed",
+        ),
+        (
+            json!({ "public_key": public_key, "role": "ops", "extensions": ["permit-X11-forwarding"] }),
+            "extension_not_allowed",
+        ),
+    ] {
+        let (status, response) = app.sign(&token, &vault_id, body).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(response["error"], error);
+    }
+    let (status, response) = app
+        .sign(
+            &token,
+            &vault_id,
+            json!({ "public_key": "ssh-ed25519 nope", "role": "ops" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(response["error"], "invalid_public_key");
+    let (status, _) = app
+        .sign(
+            &token,
+            &vault_id,
+            json!({ "public_key": public_key, "role": "missing" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, roles) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{}/ssh/roles", vault_id),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(roles["roles"][0]["name"], "ops");
+    assert_eq!(roles["roles"][0]["max_ttl_seconds"], 8 * 3600);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn ssh_ca_signing_follows_roles_and_token_ops() {
+    let app = TestApp::new().await;
+    let owner = app.register("ssh-admin@example.com").await;
+    let member = app.register("bob@example.com").await;
+    let readonly = app.register("ssh-readonly@example.com").await;
+    let vault_id = app.create_shared_vault(&owner, "ssh-access").await;
+    app.add_member(&owner, &vault_id, "bob@example.com", "member")
+        .await;
+    app.set_ssh_username("bob@example.com", "bob-ops").await;
+    app.add_member(&owner, &vault_id, "ssh-readonly@example.com", "readonly")
+        .await;
+    let ca_uri = format!("/v1/vaults/{}/ssh/ca", vault_id);
+    let (status, _) = app.send(Method::POST, &ca_uri, &member, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app.send(Method::POST, &ca_uri, &owner, None).await;
+    assert_eq!(status, StatusCode::CREATED);
+    app.set_role(
+        &owner,
+        &vault_id,
+        "hosts",
+        json!({
+            "cert_type": "host",
+            "allowed_principals": ["*"],
+            "max_ttl": "30d",
+            "default_ttl": "30d"
+        }),
+    )
+    .await;
+    app.set_role(
+        &owner,
+        &vault_id,
+        "users",
+        json!({
+            "allowed_principals": ["{{username}}"],
+            "default_principals": ["{{username}}"]
+        }),
+    )
+    .await;
+    let (status, _) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/vaults/{}/ssh/roles/users", vault_id),
+            &member,
+            Some(json!({ "allowed_principals": ["*"] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let public_key = user_public_key();
+    let (status, signed) = app
+        .sign(
+            &member,
+            &vault_id,
+            json!({ "public_key": public_key, "role": "users" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "sign failed: {signed:?}");
+    assert_eq!(signed["principals"], json!(["bob-ops"]));
+    let (status, _) = app
+        .sign(
+            &readonly,
+            &vault_id,
+            json!({ "public_key": public_key, "role": "users" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, ca) = app.send(Method::GET, &ca_uri, &readonly, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(ca["public_key"]
+        .as_str()
+        .expect("ca key")
+        .starts_with("ssh-ed25519 "));
+
+    let host_signer = app
+        .service_account_token(&owner, "ssh-access", "/ssh/roles/hosts", &["ssh_sign"])
+        .await;
+    let (status, signed) = app
+        .sign(
+            &host_signer,
+            &vault_id,
+            json!({ "public_key": public_key, "role": "hosts", "principals": ["web-1.internal"] }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "sign failed: {signed:?}");
+    assert_eq!(signed["cert_type"], "host");
+    let cert = Certificate::from_openssh(signed["certificate"].as_str().expect("certificate"))
+        .expect("parse certificate");
+    assert_eq!(cert.cert_type(), CertType::Host);
+    let (status, _) = app
+        .sign(
+            &host_signer,
+            &vault_id,
+            json!({ "public_key": public_key, "role": "users" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app.send(Method::DELETE, &ca_uri, &host_signer, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = app.send(Method::DELETE, &ca_uri, &owner, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = app
+        .sign(
+            &member,
+            &vault_id,
+            json!({ "public_key": public_key, "role": "users" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn ssh_ca_imports_key_and_validates_roles() {
+    let app = TestApp::new().await;
+    let token = app.register("ssh-import@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "ssh-import").await;
+    let ca_key = PrivateKey::new(
+        KeypairData::from(Ed25519Keypair::random(&mut rand::rngs::OsRng)),
+        "legacy-ca",
+    )
+    .expect("ca key");
+    let encoded = ca_key
+        .to_openssh(ssh_key::LineEnding::LF)
+        .expect("encode ca key");
+    let (status, ca) = app
+        .send(
+            Method::POST,
+            &format!("/v1/vaults/{}/ssh/ca", vault_id),
+            &token,
+            Some(json!({ "private_key": encoded.as_str() })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "import failed: {ca:?}");
+    assert_eq!(
+        ca["public_key"],
+        ca_key.public_key().to_openssh().expect("public key")
+    );
+
+    let role_uri = format!("/v1/vaults/{}/ssh/roles/bad", vault_id);
+    for (role, error) in [
+        (
+            json!({ "allowed_principals": [] }),
+            "allowed_principals_required",
+        ),
+        (
+            json!({ "allowed_principals": ["deploy"], "default_principals": ["root"] }),
+            "default_principal_not_allowed",
+        ),
+        (
+            json!({ "allowed_principals": ["deploy"], "default_extensions": ["permit-pty"] }),
+            "default_extension_not_allowed",
+        ),
+        (
+            json!({ "cert_type": "host", "allowed_principals": ["*"], "allowed_extensions": ["permit-pty"] }),
+            "host_extensions_unsupported",
+        ),
+        (
+            json!({ "allowed_principals": ["deploy"], "default_ttl": "2d", "max_ttl": "1d" }),
+            "default_ttl_exceeds_max",
+        ),
+        (
+            json!({ "allowed_principals": ["two words"] }),
+            "invalid_principal",
+        ),
+    ] {
+        let (status, body) = app.send(Method::PUT, &role_uri, &token, Some(role)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], error);
+    }
+    let (status, body) = app
+        .send(
+            Method::PUT,
+            &format!("/v1/vaults/{}/ssh/roles/bad%20name", vault_id),
+            &token,
+            Some(json!({ "allowed_principals": ["deploy"] })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_role_name");
+
+    let (status, _) = app.send(Method::DELETE, &role_uri, &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -17,7 +17,7 @@ use zann_db::{migrate, PgPool};
 use zann_server::config::ServerConfig;
 use zann_server::domains::access_control::policies::PolicyRule;
 use zann_server::domains::secrets::policies::{
-    default_policy, default_policy_name, PasswordPolicy,
+    builtin_policies, default_policy_name, SecretPolicy,
 };
 
 struct SharedDb {
@@ -168,11 +168,12 @@ pub async fn setup_db() -> PgPool {
     pool
 }
 
-pub fn default_secret_policies() -> (HashMap<String, PasswordPolicy>, String) {
-    let mut policies = HashMap::new();
-    let default_name = default_policy_name().to_string();
-    policies.insert(default_name.clone(), default_policy());
-    (policies, default_name)
+pub fn default_secret_policies() -> (HashMap<String, SecretPolicy>, String) {
+    let policies = builtin_policies()
+        .into_iter()
+        .map(|(name, policy)| (name.to_string(), policy))
+        .collect();
+    (policies, default_policy_name().to_string())
 }
 
 pub fn test_identity_key() -> Arc<SigningKey> {
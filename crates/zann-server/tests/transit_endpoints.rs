@@ -0,0 +1,518 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::json;
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state),
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password-1",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&payload).expect("encode")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> String {
+        let (status, vault) = self
+            .send(
+                Method::POST,
+                "/v1/vaults",
+                token,
+                Some(json!({
+                    "slug": slug,
+                    "name": "Shared Vault",
+                    "kind": VaultKind::Shared.as_i32(),
+                    "cache_policy": CachePolicy::Full.as_i32(),
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            vault
+        );
+        vault["id"].as_str().expect("vault id").to_string()
+    }
+
+    async fn add_member(&self, owner: &str, vault_id: &str, email: &str, role: &str) {
+        let (status, body) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{}/members", vault_id),
+                owner,
+                Some(json!({ "email": email, "role": role })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "{body:?}");
+    }
+
+    async fn service_account_token(
+        &self,
+        owner: &str,
+        slug: &str,
+        prefix: &str,
+        ops: &[&str],
+    ) -> String {
+        let (status, created) = self
+            .send(
+                Method::POST,
+                "/v1/service-accounts",
+                owner,
+                Some(json!({
+                    "name": format!("transit-{}", ops.join("-")),
+                    "vault": slug,
+                    "prefixes": [prefix],
+                    "ops": ops,
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "create failed: {:?}", created);
+        created["token"].as_str().expect("sa token").to_string()
+    }
+
+    async fn transit(
+        &self,
+        token: &str,
+        vault_id: &str,
+        key: &str,
+        operation: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        self.send(
+            Method::POST,
+            &format!("/v1/vaults/{}/transit/{}/{}", vault_id, key, operation),
+            token,
+            Some(body),
+        )
+        .await
+    }
+}
+
+fn b64(value: &[u8]) -> String {
+    STANDARD.encode(value)
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn transit_encrypts_rotates_and_rewraps() {
+    let app = TestApp::new().await;
+    let token = app.register("transit-owner@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "transit").await;
+    let key_uri = format!("/v1/vaults/{}/transit/payments", vault_id);
+
+    let (status, key) = app.send(Method::POST, &key_uri, &token, None).await;
+    assert_eq!(status, StatusCode::CREATED, "create failed: {:?}", key);
+    assert_eq!(key["name"], "payments");
+    assert_eq!(key["latest_version"], 1);
+    let (status, body) = app.send(Method::POST, &key_uri, &token, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "transit_key_exists");
+
+    let context = b64(b"customer-42");
+    let (status, encrypted) = app
+        .transit(
+            &token,
+            &vault_id,
+            "payments",
+            "encrypt",
+            json!({ "plaintext": b64(b"4111 1111 1111 1111"), "context": context }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "encrypt failed: {:?}", encrypted);
+    assert_eq!(encrypted["key_version"], 1);
+    let v1 = encrypted["ciphertext"]
+        .as_str()
+        .expect("ciphertext")
+        .to_string();
+    assert!(v1.starts_with("zann:v1:"));
+
+    let (status, decrypted) = app
+        .transit(
+            &token,
+            &vault_id,
+            "payments",
+            "decrypt",
+            json!({ "ciphertext": v1, "context": context }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "decrypt failed: {:?}", decrypted);
+    assert_eq!(decrypted["plaintext"], b64(b"4111 1111 1111 1111"));
+
+    let (status, body) = app
+        .transit(
+            &token,
+            &vault_id,
+            "payments",
+            "decrypt",
+            json!({ "ciphertext": v1, "context": b64(b"customer-43") }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "decrypt_failed");
+
+    let (status, rotated) = app
+        .transit(&token, &vault_id, "payments", "rotate", json!({}))
+        .await;
+    assert_eq!(status, StatusCode::OK, "rotate failed: {:?}", rotated);
+    assert_eq!(rotated["latest_version"], 2);
+
+    let (status, rewrapped) = app
+        .transit(
+            &token,
+            &vault_id,
+            "payments",
+            "rewrap",
+            json!({ "ciphertext": v1, "context": context }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "rewrap failed: {:?}", rewrapped);
+    assert_eq!(rewrapped["key_version"], 2);
+    let v2 = rewrapped["ciphertext"]
+        .as_str()
+        .expect("ciphertext")
+        .to_string();
+    assert!(v2.starts_with("zann:v2:"));
+
+    let (status, updated) = app
+        .send(
+            Method::PATCH,
+            &key_uri,
+            &token,
+            Some(json!({ "min_decryption_version": 2 })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "update failed: {:?}", updated);
+    assert_eq!(updated["min_decryption_version"], 2);
+
+    let (status, body) = app
+        .transit(
+            &token,
+            &vault_id,
+            "payments",
+            "decrypt",
+            json!({ "ciphertext": v1, "context": context }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "key_version_retired");
+    let (status, decrypted) = app
+        .transit(
+            &token,
+            &vault_id,
+            "payments",
+            "decrypt",
+            json!({ "ciphertext": v2, "context": context }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(decrypted["plaintext"], b64(b"4111 1111 1111 1111"));
+    assert_eq!(decrypted["key_version"], 2);
+
+    let (status, _) = app
+        .send(
+            Method::PATCH,
+            &key_uri,
+            &token,
+            Some(json!({ "min_decryption_version": 3 })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn transit_access_follows_roles_and_token_ops() {
+    let app = TestApp::new().await;
+    let owner = app.register("transit-admin@example.com").await;
+    let member = app.register("transit-member@example.com").await;
+    let readonly = app.register("transit-readonly@example.com").await;
+    let outsider = app.register("transit-outsider@example.com").await;
+    let vault_id = app.create_shared_vault(&owner, "transit-access").await;
+    app.add_member(&owner, &vault_id, "transit-member@example.com", "member")
+        .await;
+    app.add_member(
+        &owner,
+        &vault_id,
+        "transit-readonly@example.com",
+        "readonly",
+    )
+    .await;
+    for key in ["payments", "ledger"] {
+        let uri = format!("/v1/vaults/{}/transit/{}", vault_id, key);
+        let (status, _) = app.send(Method::POST, &uri, &owner, None).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let plaintext = json!({ "plaintext": b64(b"hello") });
+    let (status, encrypted) = app
+        .transit(&member, &vault_id, "payments", "encrypt", plaintext.clone())
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let ciphertext = json!({ "ciphertext": encrypted["ciphertext"] });
+    let (status, _) = app
+        .transit(
+            &member,
+            &vault_id,
+            "payments",
+            "decrypt",
+            ciphertext.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .transit(&member, &vault_id, "payments", "rotate", json!({}))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let uri = format!("/v1/vaults/{}/transit/member-key", vault_id);
+    let (status, _) = app.send(Method::POST, &uri, &member, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = app
+        .transit(
+            &readonly,
+            &vault_id,
+            "payments",
+            "encrypt",
+            plaintext.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app
+        .transit(
+            &outsider,
+            &vault_id,
+            "payments",
+            "decrypt",
+            ciphertext.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let encrypt_only = app
+        .service_account_token(
+            &owner,
+            "transit-access",
+            "/transit/payments",
+            &["transit_encrypt"],
+        )
+        .await;
+    let (status, _) = app
+        .transit(
+            &encrypt_only,
+            &vault_id,
+            "payments",
+            "encrypt",
+            plaintext.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .transit(
+            &encrypt_only,
+            &vault_id,
+            "payments",
+            "rewrap",
+            ciphertext.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .transit(
+            &encrypt_only,
+            &vault_id,
+            "payments",
+            "decrypt",
+            ciphertext.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = app
+        .transit(
+            &encrypt_only,
+            &vault_id,
+            "ledger",
+            "encrypt",
+            plaintext.clone(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let decrypt = app
+        .service_account_token(&owner, "transit-access", "/", &["transit_decrypt"])
+        .await;
+    let (status, decrypted) = app
+        .transit(&decrypt, &vault_id, "payments", "decrypt", ciphertext)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(decrypted["plaintext"], b64(b"hello"));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn transit_rejects_invalid_input() {
+    let app = TestApp::new().await;
+    let token = app.register("transit-input@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "transit-input").await;
+    let uri = format!("/v1/vaults/{}/transit/bad%20name", vault_id);
+    let (status, body) = app.send(Method::POST, &uri, &token, None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_key_name");
+
+    let (status, _) = app
+        .transit(
+            &token,
+            &vault_id,
+            "missing",
+            "encrypt",
+            json!({ "plaintext": b64(b"x") }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let uri = format!("/v1/vaults/{}/transit/tokens", vault_id);
+    let (status, _) = app.send(Method::POST, &uri, &token, None).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, body) = app
+        .transit(
+            &token,
+            &vault_id,
+            "tokens",
+            "encrypt",
+            json!({ "plaintext": "not base64!" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_plaintext");
+    for ciphertext in ["plain", "zann:v1:@@@", "zann:v9:AAAA", "zann:vx:AAAA"] {
+        let (status, body) = app
+            .transit(
+                &token,
+                &vault_id,
+                "tokens",
+                "decrypt",
+                json!({ "ciphertext": ciphertext }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{ciphertext}");
+        assert_eq!(body["error"], "invalid_ciphertext", "{ciphertext}");
+    }
+}
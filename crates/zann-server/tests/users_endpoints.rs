@@ -16,6 +16,7 @@ use zann_server::app::{build_router, AppState};
 use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
 use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
 use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
 use zann_server::infra::security_profiles::load_security_profiles;
 use zann_server::infra::usage::UsageTracker;
 use zann_server::oidc::OidcJwksCache;
@@ -58,7 +59,7 @@ impl TestApp {
             started_at: std::time::Instant::now(),
             password_pepper: "pepper".to_string(),
             token_pepper: "pepper".to_string(),
-            server_master_key: None,
+            server_master_key: MasterKeyStore::new(None),
 
             identity_key: support::test_identity_key(),
             access_token_ttl_seconds: 3600,
@@ -333,6 +334,53 @@ async fn admin_user_lifecycle() {
     assert_eq!(status, StatusCode::OK, "reset password failed: {:?}", reset);
     assert_eq!(reset["password"], "reset-1");
 
+    let ssh_uri = format!("/v1/users/{}/ssh-username", user_id);
+    let (status, body) = app
+        .send_json(
+            Method::PUT,
+            &ssh_uri,
+            Some(&admin_token),
+            json!({ "ssh_username": " managed " }),
+        )
+        .await;
+    assert_eq!(
+        status,
+        StatusCode::OK,
+        "set ssh username failed: {:?}",
+        body
+    );
+    assert_eq!(body["ssh_username"], "managed");
+    let (status, body) = app
+        .send_json(
+            Method::PUT,
+            &format!("/v1/users/{}/ssh-username", admin_id),
+            Some(&admin_token),
+            json!({ "ssh_username": "managed" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "ssh_username_taken");
+    let (status, body) = app
+        .send_json(
+            Method::PUT,
+            &ssh_uri,
+            Some(&admin_token),
+            json!({ "ssh_username": "Root@host" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_ssh_username");
+    let (status, body) = app
+        .send_json(
+            Method::PUT,
+            &ssh_uri,
+            Some(&admin_token),
+            json!({ "ssh_username": null }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["ssh_username"].is_null());
+
     let status = app
         .send_empty(
             Method::DELETE,
@@ -354,4 +402,15 @@ async fn admin_endpoints_forbidden_for_non_admin() {
 
     let (status, _) = app.get_json("/v1/users", Some(&token)).await;
     assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let user_id = app.user_id_by_email(email).await;
+    let (status, _) = app
+        .send_json(
+            Method::PUT,
+            &format!("/v1/users/{}/ssh-username", user_id),
+            Some(&token),
+            json!({ "ssh_username": "root" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
 }
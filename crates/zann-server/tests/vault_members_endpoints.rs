@@ -0,0 +1,636 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, Group, GroupMember, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_db::repo::{AuditEventFilter, AuditEventRepo, GroupMemberRepo, GroupRepo, UserRepo};
+use zann_db::PgPool;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    pool: PgPool,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        let app = build_router(state);
+        Self {
+            _guard: guard,
+            app,
+            pool,
+        }
+    }
+
+    async fn send_json(
+        &self,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        let request = builder
+            .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn get_json(&self, uri: &str, token: Option<&str>) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder().method(Method::GET).uri(uri);
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {}", token));
+        }
+        let request = builder.body(Body::empty()).expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str, password: &str) -> serde_json::Value {
+        let payload = json!({
+            "email": email,
+            "password": password,
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let (status, json) = self
+            .send_json(Method::POST, "/v1/auth/register", None, payload)
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "register failed: {:?}", json);
+        json
+    }
+
+    async fn user_id(&self, email: &str) -> String {
+        UserRepo::new(&self.pool)
+            .get_by_email(email)
+            .await
+            .expect("user lookup")
+            .expect("user")
+            .id
+            .to_string()
+    }
+
+    /// Creates a group with the given members, as an OIDC group sync would.
+    async fn create_group(&self, slug: &str, emails: &[&str]) {
+        let now = chrono::Utc::now();
+        let group = Group {
+            id: Uuid::now_v7(),
+            slug: slug.to_string(),
+            name: slug.to_uppercase(),
+            created_at: now,
+        };
+        GroupRepo::new(&self.pool)
+            .create(&group)
+            .await
+            .expect("create group");
+        for email in emails {
+            let user_id = Uuid::parse_str(&self.user_id(email).await).expect("user id");
+            GroupMemberRepo::new(&self.pool)
+                .create(&GroupMember {
+                    group_id: group.id,
+                    user_id,
+                    created_at: now,
+                })
+                .await
+                .expect("add group member");
+        }
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> serde_json::Value {
+        let payload = json!({
+            "slug": slug,
+            "name": "Shared Vault",
+            "kind": VaultKind::Shared.as_i32(),
+            "cache_policy": CachePolicy::Full.as_i32(),
+        });
+        let (status, json) = self
+            .send_json(Method::POST, "/v1/vaults", Some(token), payload)
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            json
+        );
+        json
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn vault_admin_manages_members() {
+    let app = TestApp::new().await;
+    let lead = app.register("lead@example.com", "password").await;
+    let lead_token = lead["access_token"].as_str().expect("token");
+    let lead_id = app.user_id("lead@example.com").await;
+    let engineer = app.register("engineer@example.com", "password").await;
+    let engineer_token = engineer["access_token"].as_str().expect("token");
+    let vault = app.create_shared_vault(lead_token, "billing").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+
+    let (status, _) = app
+        .get_json("/v1/vaults/billing/members", Some(engineer_token))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, added) = app
+        .send_json(
+            Method::POST,
+            "/v1/vaults/billing/members",
+            Some(lead_token),
+            json!({ "email": "engineer@example.com", "role": "member" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "{added:?}");
+    assert_eq!(added["email"], "engineer@example.com");
+    assert_eq!(added["role"], "member");
+    let engineer_id = added["user_id"].as_str().expect("user id").to_string();
+
+    let (status, list) = app
+        .get_json("/v1/vaults/billing/members", Some(engineer_token))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{list:?}");
+    let members = list["members"].as_array().expect("members");
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0]["email"], "engineer@example.com");
+    assert_eq!(members[1]["email"], "lead@example.com");
+    assert_eq!(members[1]["role"], "admin");
+
+    // Members cannot manage membership.
+    let (status, _) = app
+        .send_json(
+            Method::PUT,
+            &format!("/v1/vaults/billing/members/{engineer_id}"),
+            Some(engineer_token),
+            json!({ "role": "admin" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // The only admin cannot step down or leave.
+    let (status, body) = app
+        .send_json(
+            Method::PUT,
+            &format!("/v1/vaults/{vault_id}/members/{lead_id}"),
+            Some(lead_token),
+            json!({ "role": "member" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "last_admin");
+    let (status, body) = app
+        .send_json(
+            Method::DELETE,
+            &format!("/v1/vaults/{vault_id}/members/{lead_id}"),
+            Some(lead_token),
+            serde_json::Value::Null,
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "last_admin");
+
+    let (status, updated) = app
+        .send_json(
+            Method::PUT,
+            &format!("/v1/vaults/billing/members/{engineer_id}"),
+            Some(lead_token),
+            json!({ "role": "Admin" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{updated:?}");
+    assert_eq!(updated["role"], "admin");
+
+    // With a second admin in place the original one may leave.
+    let (status, _) = app
+        .send_json(
+            Method::DELETE,
+            &format!("/v1/vaults/billing/members/{lead_id}"),
+            Some(engineer_token),
+            serde_json::Value::Null,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = app
+        .get_json("/v1/vaults/billing/members", Some(lead_token))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = app
+        .send_json(
+            Method::DELETE,
+            &format!("/v1/vaults/billing/members/{engineer_id}"),
+            Some(engineer_token),
+            serde_json::Value::Null,
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "last_admin");
+
+    let audit = AuditEventRepo::new(&app.pool);
+    let role_changes = audit
+        .list(
+            &AuditEventFilter {
+                vault_id: Some(Uuid::parse_str(vault_id).expect("vault id")),
+                action: Some("update_role".to_string()),
+                ..AuditEventFilter::default()
+            },
+            10,
+        )
+        .await
+        .expect("audit list");
+    let results: Vec<(&str, Option<&str>)> = role_changes
+        .iter()
+        .map(|event| (event.result.as_str(), event.detail.as_deref()))
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            ("ok", Some("role=member->admin")),
+            ("conflict", Some("role=member")),
+            ("forbidden", Some("role=admin")),
+        ]
+    );
+    assert_eq!(
+        role_changes[0].target.as_deref(),
+        Some(engineer_id.as_str())
+    );
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn add_member_validates_payload() {
+    let app = TestApp::new().await;
+    let lead = app.register("lead@example.com", "password").await;
+    let lead_token = lead["access_token"].as_str().expect("token");
+    app.register("engineer@example.com", "password").await;
+    let engineer_id = app.user_id("engineer@example.com").await;
+    app.create_shared_vault(lead_token, "billing").await;
+
+    for (payload, status, error) in [
+        (
+            json!({ "email": "engineer@example.com", "role": "owner" }),
+            StatusCode::BAD_REQUEST,
+            Some("invalid_role"),
+        ),
+        (
+            json!({ "role": "member" }),
+            StatusCode::BAD_REQUEST,
+            Some("invalid_user"),
+        ),
+        (
+            json!({ "email": "engineer@example.com", "user_id": engineer_id, "role": "member" }),
+            StatusCode::BAD_REQUEST,
+            Some("invalid_user"),
+        ),
+        (
+            json!({ "email": "nobody@example.com", "role": "member" }),
+            StatusCode::NOT_FOUND,
+            None,
+        ),
+        (
+            json!({ "user_id": engineer_id, "role": "readonly" }),
+            StatusCode::CREATED,
+            None,
+        ),
+        (
+            json!({ "email": "engineer@example.com", "role": "member" }),
+            StatusCode::CONFLICT,
+            Some("already_member"),
+        ),
+    ] {
+        let (actual, body) = app
+            .send_json(
+                Method::POST,
+                "/v1/vaults/billing/members",
+                Some(lead_token),
+                payload.clone(),
+            )
+            .await;
+        assert_eq!(actual, status, "{payload}: {body:?}");
+        if let Some(error) = error {
+            assert_eq!(body["error"], error);
+        }
+    }
+
+    let (status, body) = app
+        .send_json(
+            Method::PUT,
+            "/v1/vaults/billing/members/not-a-uuid",
+            Some(lead_token),
+            json!({ "role": "member" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_user_id");
+
+    let (status, _) = app
+        .send_json(
+            Method::DELETE,
+            &format!("/v1/vaults/billing/members/{}", Uuid::now_v7()),
+            Some(lead_token),
+            serde_json::Value::Null,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn group_grants_give_inherited_access() {
+    let app = TestApp::new().await;
+    let lead = app.register("lead@example.com", "password").await;
+    let lead_token = lead["access_token"].as_str().expect("token");
+    let lead_id = app.user_id("lead@example.com").await;
+    let engineer = app.register("engineer@example.com", "password").await;
+    let engineer_token = engineer["access_token"].as_str().expect("token");
+    let engineer_id = app.user_id("engineer@example.com").await;
+    let vault = app.create_shared_vault(lead_token, "infra").await;
+    let vault_id = vault["id"].as_str().expect("vault id");
+    app.create_group("sre", &["engineer@example.com"]).await;
+
+    let (status, _) = app
+        .get_json("/v1/vaults/infra/members", Some(engineer_token))
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    for (payload, status, error) in [
+        (
+            json!({ "group": "nobody", "role": "member" }),
+            StatusCode::NOT_FOUND,
+            None,
+        ),
+        (
+            json!({ "group": "sre", "role": "owner" }),
+            StatusCode::BAD_REQUEST,
+            Some("invalid_role"),
+        ),
+        (
+            json!({ "group": "sre", "role": "member" }),
+            StatusCode::CREATED,
+            None,
+        ),
+        (
+            json!({ "group": "sre", "role": "readonly" }),
+            StatusCode::CONFLICT,
+            Some("already_granted"),
+        ),
+    ] {
+        let (actual, body) = app
+            .send_json(
+                Method::POST,
+                "/v1/vaults/infra/groups",
+                Some(lead_token),
+                payload.clone(),
+            )
+            .await;
+        assert_eq!(actual, status, "{payload}: {body:?}");
+        if let Some(error) = error {
+            assert_eq!(body["error"], error);
+        }
+    }
+
+    // Group members see the vault and its roster without a direct membership.
+    let (status, vaults) = app.get_json("/v1/vaults", Some(engineer_token)).await;
+    assert_eq!(status, StatusCode::OK, "{vaults:?}");
+    assert!(vaults["vaults"]
+        .as_array()
+        .expect("vaults")
+        .iter()
+        .any(|vault| vault["slug"] == "infra"));
+
+    // A direct grant is listed next to the inherited one.
+    let (status, _) = app
+        .send_json(
+            Method::POST,
+            "/v1/vaults/infra/members",
+            Some(lead_token),
+            json!({ "email": "engineer@example.com", "role": "readonly" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, list) = app
+        .get_json("/v1/vaults/infra/members", Some(engineer_token))
+        .await;
+    assert_eq!(status, StatusCode::OK, "{list:?}");
+    let rows: Vec<(&str, &str, &str, Option<&str>)> = list["members"]
+        .as_array()
+        .expect("members")
+        .iter()
+        .map(|member| {
+            (
+                member["email"].as_str().expect("email"),
+                member["role"].as_str().expect("role"),
+                member["source"].as_str().expect("source"),
+                member["group"].as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("engineer@example.com", "readonly", "direct", None),
+            ("engineer@example.com", "member", "group", Some("sre")),
+            ("lead@example.com", "admin", "direct", None),
+        ]
+    );
+
+    // The strongest of the direct and group roles applies.
+    let (status, _) = app
+        .send_json(
+            Method::PUT,
+            &format!("/v1/vaults/infra/members/{lead_id}"),
+            Some(engineer_token),
+            json!({ "role": "member" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, updated) = app
+        .send_json(
+            Method::PUT,
+            "/v1/vaults/infra/groups/sre",
+            Some(lead_token),
+            json!({ "role": "admin" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "{updated:?}");
+    assert_eq!(updated["slug"], "sre");
+    assert_eq!(updated["role"], "admin");
+    let (status, _) = app
+        .send_json(
+            Method::DELETE,
+            &format!("/v1/vaults/infra/members/{lead_id}"),
+            Some(engineer_token),
+            serde_json::Value::Null,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    // The admin group grant is now the only admin left on the vault.
+    let (status, body) = app
+        .send_json(
+            Method::DELETE,
+            "/v1/vaults/infra/groups/sre",
+            Some(engineer_token),
+            serde_json::Value::Null,
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "last_admin");
+    let (status, _) = app
+        .send_json(
+            Method::PUT,
+            &format!("/v1/vaults/infra/members/{engineer_id}"),
+            Some(engineer_token),
+            json!({ "role": "admin" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .send_json(
+            Method::DELETE,
+            "/v1/vaults/infra/groups/sre",
+            Some(engineer_token),
+            serde_json::Value::Null,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, grants) = app
+        .get_json("/v1/vaults/infra/groups", Some(engineer_token))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(grants["groups"], json!([]));
+
+    let audit = AuditEventRepo::new(&app.pool);
+    let grants = audit
+        .list(
+            &AuditEventFilter {
+                vault_id: Some(Uuid::parse_str(vault_id).expect("vault id")),
+                action: Some("grant_group".to_string()),
+                ..AuditEventFilter::default()
+            },
+            10,
+        )
+        .await
+        .expect("audit list");
+    let results: Vec<&str> = grants.iter().map(|event| event.result.as_str()).collect();
+    assert_eq!(results, vec!["conflict", "ok", "bad_request", "not_found"]);
+    assert_eq!(grants[1].target.as_deref(), Some("group:sre"));
+    assert_eq!(grants[1].detail.as_deref(), Some("role=member"));
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn empty_admin_group_does_not_count_as_admin() {
+    let app = TestApp::new().await;
+    let lead = app.register("lead@example.com", "password").await;
+    let lead_token = lead["access_token"].as_str().expect("token");
+    let lead_id = app.user_id("lead@example.com").await;
+    app.create_shared_vault(lead_token, "infra").await;
+    app.create_group("ghosts", &[]).await;
+
+    let (status, _) = app
+        .send_json(
+            Method::POST,
+            "/v1/vaults/infra/groups",
+            Some(lead_token),
+            json!({ "group": "ghosts", "role": "admin" }),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // Nobody would be left to administer the vault through the empty group.
+    let (status, body) = app
+        .send_json(
+            Method::DELETE,
+            &format!("/v1/vaults/infra/members/{lead_id}"),
+            Some(lead_token),
+            serde_json::Value::Null,
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body:?}");
+    assert_eq!(body["error"], "last_admin");
+}
@@ -0,0 +1,525 @@
+use std::time::Duration;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Method, Request, StatusCode};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tower::ServiceExt;
+use tracing_subscriber::EnvFilter;
+
+mod support;
+
+use tokio::sync::Semaphore;
+use zann_core::{CachePolicy, VaultKind};
+use zann_crypto::crypto::SecretKey;
+use zann_server::app::{build_router, AppState};
+use zann_server::config::{AuthMode, InternalRegistration, ServerConfig};
+use zann_server::domains::access_control::policies::{PolicyRule, PolicySet};
+use zann_server::domains::access_control::policy_store::PolicyStore;
+use zann_server::domains::system::seal::MasterKeyStore;
+use zann_server::domains::vaults::rotation_hooks::webhook::{
+    sign, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use zann_server::domains::webhooks::delivery::{
+    run_webhook_deliveries, DELIVERY_HEADER, EVENT_HEADER,
+};
+use zann_server::infra::security_profiles::load_security_profiles;
+use zann_server::infra::usage::UsageTracker;
+use zann_server::oidc::OidcJwksCache;
+
+struct TestApp {
+    _guard: support::TestGuard,
+    app: axum::Router,
+    state: AppState,
+}
+
+impl TestApp {
+    async fn new() -> Self {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            let _ = tracing_subscriber::fmt()
+                .with_env_filter(EnvFilter::new("zann_server=debug"))
+                .with_test_writer()
+                .try_init();
+        });
+
+        let guard = support::test_guard().await;
+
+        let pool = support::setup_shared_db().await;
+        support::reset_db(&pool).await;
+        let rules: Vec<PolicyRule> = support::load_policy_rules();
+
+        let mut config = ServerConfig::default();
+
+        support::tune_test_kdf(&mut config);
+        config.auth.mode = AuthMode::Internal;
+        config.auth.internal.enabled = true;
+        config.auth.internal.registration = InternalRegistration::Open;
+        config.webhooks.timeout_seconds = 5;
+        config.webhooks.max_attempts = 2;
+        config.webhooks.allow_http = true;
+        config.webhooks.allowed_networks = vec!["127.0.0.1/32".to_string()];
+
+        let usage_tracker = std::sync::Arc::new(UsageTracker::new(pool.clone(), 100));
+        let (secret_policies, secret_default_policy) = support::default_secret_policies();
+        let state = AppState {
+            db: pool.clone(),
+            db_tx_isolation: zann_server::settings::DbTxIsolation::ReadCommitted,
+            started_at: std::time::Instant::now(),
+            password_pepper: "pepper".to_string(),
+            token_pepper: "pepper".to_string(),
+            server_master_key: MasterKeyStore::new(Some(SecretKey::generate())),
+
+            identity_key: support::test_identity_key(),
+            access_token_ttl_seconds: 3600,
+            refresh_token_ttl_seconds: 3600,
+            argon2_semaphore: std::sync::Arc::new(Semaphore::new(4)),
+            oidc_jwks_cache: OidcJwksCache::new(),
+            config,
+            policy_store: PolicyStore::new(PolicySet::from_rules(rules)),
+            usage_tracker,
+            security_profiles: load_security_profiles(),
+            secret_policies,
+            secret_default_policy,
+        };
+
+        Self {
+            _guard: guard,
+            app: build_router(state.clone()),
+            state,
+        }
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).expect("encode json")))
+                .expect("request"),
+            None => builder.body(Body::empty()).expect("request"),
+        };
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).expect("json")
+        };
+        (status, json)
+    }
+
+    async fn register(&self, email: &str) -> String {
+        let payload = json!({
+            "email": email,
+            "password": "password-1",
+            "device_name": "test",
+            "device_platform": "tests",
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/v1/auth/register")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&payload).expect("encode")))
+            .expect("request");
+        let response = self.app.clone().oneshot(request).await.expect("response");
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        json["access_token"].as_str().expect("token").to_string()
+    }
+
+    async fn create_shared_vault(&self, token: &str, slug: &str) -> String {
+        let (status, vault) = self
+            .send(
+                Method::POST,
+                "/v1/vaults",
+                token,
+                Some(json!({
+                    "slug": slug,
+                    "name": "Shared Vault",
+                    "kind": VaultKind::Shared.as_i32(),
+                    "cache_policy": CachePolicy::Full.as_i32(),
+                })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "vault create failed: {:?}",
+            vault
+        );
+        vault["id"].as_str().expect("vault id").to_string()
+    }
+
+    async fn create_item(&self, token: &str, vault_id: &str, path: &str) -> String {
+        let (status, item) = self
+            .send(
+                Method::POST,
+                "/v1/shared/items",
+                token,
+                Some(json!({
+                    "vault_id": vault_id,
+                    "path": path,
+                    "type_id": "secret",
+                    "payload": {
+                        "v": 1,
+                        "typeId": "secret",
+                        "fields": { "token": { "kind": "text", "value": "top-secret-value" } }
+                    },
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED, "create failed: {item:?}");
+        item["id"].as_str().expect("item id").to_string()
+    }
+
+    async fn create_webhook(
+        &self,
+        token: &str,
+        vault_id: &str,
+        url: &str,
+        prefix: &str,
+    ) -> serde_json::Value {
+        let (status, webhook) = self
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{vault_id}/webhooks"),
+                token,
+                Some(json!({ "url": url, "secret": HOOK_SECRET, "path_prefix": prefix })),
+            )
+            .await;
+        assert_eq!(
+            status,
+            StatusCode::CREATED,
+            "webhook create failed: {webhook:?}"
+        );
+        webhook
+    }
+
+    async fn deliveries(
+        &self,
+        token: &str,
+        vault_id: &str,
+        webhook_id: &str,
+        status: Option<&str>,
+    ) -> Vec<serde_json::Value> {
+        let mut uri = format!("/v1/vaults/{vault_id}/webhooks/{webhook_id}/deliveries");
+        if let Some(status) = status {
+            uri.push_str(&format!("?status={status}"));
+        }
+        let (code, body) = self.send(Method::GET, &uri, token, None).await;
+        assert_eq!(code, StatusCode::OK, "deliveries failed: {body:?}");
+        body["deliveries"].as_array().cloned().unwrap_or_default()
+    }
+}
+
+struct HookRequest {
+    headers: String,
+    body: String,
+}
+
+/// Answers one request per entry in `statuses` and returns what it received.
+async fn hook_stub(
+    statuses: Vec<&'static str>,
+) -> (String, tokio::task::JoinHandle<Vec<HookRequest>>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let url = format!("http://{}/events", listener.local_addr().expect("addr"));
+    let handle = tokio::spawn(async move {
+        let mut received = Vec::new();
+        for status in statuses {
+            let Ok(Ok((mut socket, _))) =
+                tokio::time::timeout(Duration::from_secs(5), listener.accept()).await
+            else {
+                break;
+            };
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let read = socket.read(&mut buf).await.expect("read");
+                request.extend_from_slice(&buf[..read]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_ascii_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|value| value.trim().parse::<usize>().unwrap_or(0))
+                        })
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        received.push(HookRequest {
+                            headers: head.to_ascii_lowercase(),
+                            body: body.to_string(),
+                        });
+                        break;
+                    }
+                }
+                if read == 0 {
+                    break;
+                }
+            }
+            let response =
+                format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n");
+            socket.write_all(response.as_bytes()).await.expect("write");
+        }
+        received
+    });
+    (url, handle)
+}
+
+fn header_value<'a>(headers: &'a str, name: &str) -> &'a str {
+    headers
+        .lines()
+        .find_map(|line| line.strip_prefix(&format!("{name}:")))
+        .map(str::trim)
+        .expect("header present")
+}
+
+const HOOK_SECRET: &str = "0123456789abcdef0123";
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn webhook_delivers_signed_notices_under_prefix() {
+    let app = TestApp::new().await;
+    let token = app.register("hooks@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "hooks").await;
+    app.create_item(&token, &vault_id, "prod/before").await;
+
+    let (url, stub) = hook_stub(vec!["204 No Content"]).await;
+    let webhook = app.create_webhook(&token, &vault_id, &url, "prod/*").await;
+    let webhook_id = webhook["id"].as_str().expect("webhook id");
+    assert_eq!(webhook["path_prefix"], "prod");
+    assert_eq!(webhook["enabled"], true);
+    assert!(!webhook.to_string().contains(HOOK_SECRET));
+
+    let item_id = app.create_item(&token, &vault_id, "prod/db").await;
+    app.create_item(&token, &vault_id, "dev/db").await;
+    app.create_item(&token, &vault_id, "production/db").await;
+
+    let pass = run_webhook_deliveries(&app.state).await.expect("pass");
+    assert_eq!(pass.queued, 1);
+    assert_eq!(pass.delivered, 1);
+
+    let requests = stub.await.expect("stub");
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert!(!request.body.contains("top-secret-value"));
+    let payload: serde_json::Value = serde_json::from_str(&request.body).expect("hook body");
+    assert_eq!(payload["event"], "item.created");
+    assert_eq!(payload["op"], "create");
+    assert_eq!(payload["path"], "prod/db");
+    assert_eq!(payload["item_id"], item_id.as_str());
+    assert_eq!(payload["vault_id"], vault_id.as_str());
+    assert_eq!(payload["actor"]["email"], "hooks@example.com");
+    assert_eq!(header_value(&request.headers, EVENT_HEADER), "item.created");
+    assert_eq!(
+        header_value(&request.headers, DELIVERY_HEADER),
+        payload["id"].as_str().expect("delivery id")
+    );
+    let timestamp = header_value(&request.headers, TIMESTAMP_HEADER);
+    assert_eq!(
+        header_value(&request.headers, SIGNATURE_HEADER),
+        sign(HOOK_SECRET, timestamp, request.body.as_bytes())
+    );
+
+    let deliveries = app
+        .deliveries(&token, &vault_id, webhook_id, Some("delivered"))
+        .await;
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0]["attempts"], 1);
+    assert_eq!(deliveries[0]["response_status"], 204);
+
+    let pass = run_webhook_deliveries(&app.state).await.expect("pass");
+    assert_eq!(pass.queued, 0);
+    assert_eq!(pass.delivered, 0);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn failed_delivery_retries_and_can_be_redelivered() {
+    let app = TestApp::new().await;
+    let token = app.register("hooks-retry@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "hooks-retry").await;
+
+    let (url, stub) = hook_stub(vec!["503 Service Unavailable", "200 OK"]).await;
+    let webhook = app.create_webhook(&token, &vault_id, &url, "").await;
+    let webhook_id = webhook["id"].as_str().expect("webhook id");
+    assert!(webhook["path_prefix"].is_null());
+    app.create_item(&token, &vault_id, "api/key").await;
+
+    let pass = run_webhook_deliveries(&app.state).await.expect("pass");
+    assert_eq!(pass.queued, 1);
+    assert_eq!(pass.delivered, 0);
+    let pending = app
+        .deliveries(&token, &vault_id, webhook_id, Some("pending"))
+        .await;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0]["attempts"], 1);
+    assert_eq!(pending[0]["response_status"], 503);
+    assert!(pending[0]["next_attempt_at"].is_string());
+
+    // The retry is not due yet, so another pass leaves it alone.
+    let pass = run_webhook_deliveries(&app.state).await.expect("pass");
+    assert_eq!(pass.delivered, 0);
+
+    let delivery_id = pending[0]["id"].as_str().expect("delivery id");
+    let (status, redelivered) = app
+        .send(
+            Method::POST,
+            &format!(
+                "/v1/vaults/{vault_id}/webhooks/{webhook_id}/deliveries/{delivery_id}/redeliver"
+            ),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "redeliver failed: {redelivered:?}");
+    assert_eq!(redelivered["status"], "delivered");
+    assert_eq!(redelivered["attempts"], 1);
+    assert_eq!(redelivered["response_status"], 200);
+
+    let requests = stub.await.expect("stub");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body, requests[1].body);
+
+    let (status, body) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{vault_id}/webhooks/{webhook_id}/deliveries?status=bogus"),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_status");
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn disabled_webhook_keeps_deliveries_queued() {
+    let app = TestApp::new().await;
+    let token = app.register("hooks-disabled@example.com").await;
+    let vault_id = app.create_shared_vault(&token, "hooks-disabled").await;
+    let webhook = app
+        .create_webhook(&token, &vault_id, "http://127.0.0.1:9/events", "")
+        .await;
+    let webhook_id = webhook["id"].as_str().expect("webhook id");
+
+    let (status, updated) = app
+        .send(
+            Method::PATCH,
+            &format!("/v1/vaults/{vault_id}/webhooks/{webhook_id}"),
+            &token,
+            Some(json!({ "enabled": false, "path_prefix": "apps/billing" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK, "update failed: {updated:?}");
+    assert_eq!(updated["enabled"], false);
+    assert_eq!(updated["path_prefix"], "apps/billing");
+
+    app.create_item(&token, &vault_id, "apps/billing/stripe")
+        .await;
+    let pass = run_webhook_deliveries(&app.state).await.expect("pass");
+    assert_eq!(pass.queued, 0);
+
+    let (status, _) = app
+        .send(
+            Method::PATCH,
+            &format!("/v1/vaults/{vault_id}/webhooks/{webhook_id}"),
+            &token,
+            Some(json!({ "secret": "short" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, list) = app
+        .send(
+            Method::GET,
+            &format!("/v1/vaults/{vault_id}/webhooks"),
+            &token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(list["webhooks"].as_array().map(Vec::len), Some(1));
+
+    let uri = format!("/v1/vaults/{vault_id}/webhooks/{webhook_id}");
+    let (status, _) = app.send(Method::DELETE, &uri, &token, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = app.send(Method::GET, &uri, &token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "postgres-tests"), ignore = "requires TEST_DATABASE_URL")]
+async fn webhooks_require_vault_admin() {
+    let app = TestApp::new().await;
+    let owner = app.register("hooks-owner@example.com").await;
+    let member = app.register("hooks-member@example.com").await;
+    let vault_id = app.create_shared_vault(&owner, "hooks-admin").await;
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &format!("/v1/vaults/{vault_id}/members"),
+            &owner,
+            Some(json!({ "email": "hooks-member@example.com", "role": "operator" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED, "add member failed: {body:?}");
+
+    let (status, _) = app
+        .send(
+            Method::POST,
+            &format!("/v1/vaults/{vault_id}/webhooks"),
+            &member,
+            Some(json!({ "url": "https://hooks.example.com/zann", "secret": HOOK_SECRET })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, body) = app
+        .send(
+            Method::POST,
+            &format!("/v1/vaults/{vault_id}/webhooks"),
+            &owner,
+            Some(json!({ "url": "ftp://hooks.example.com", "secret": HOOK_SECRET })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "invalid_url");
+    // Internal targets are refused unless `webhooks.allowed_networks` covers them.
+    for url in [
+        "https://169.254.169.254/latest/meta-data",
+        "https://10.0.0.1/hook",
+        "http://127.0.0.2/hook",
+    ] {
+        let (status, body) = app
+            .send(
+                Method::POST,
+                &format!("/v1/vaults/{vault_id}/webhooks"),
+                &owner,
+                Some(json!({ "url": url, "secret": HOOK_SECRET })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{url}");
+        assert_eq!(body["error"], "url_not_allowed", "{url}");
+    }
+}